@@ -0,0 +1,178 @@
+//! Per-instance Java agents (`-javaagent:`).
+//!
+//! Agents are stored on the instance and added to the JVM arguments at
+//! launch. Well-known agents can be downloaded into `<app_data>/agents`.
+
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+use ts_rs::TS;
+
+/// authlib-injector build metadata (third-party Yggdrasil auth servers)
+const AUTHLIB_INJECTOR_LATEST: &str = "https://authlib-injector.yushi.moe/artifact/latest.json";
+
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export, export_to = "dropout-core/instance.ts")]
+pub struct JavaAgent {
+    pub path: String,
+    #[serde(default)]
+    pub args: Option<String>,
+    #[serde(default = "default_enabled")]
+    pub enabled: bool,
+}
+
+fn default_enabled() -> bool {
+    true
+}
+
+impl JavaAgent {
+    /// `-javaagent:<path>[=<args>]`
+    pub fn to_jvm_arg(&self) -> String {
+        match self.args.as_deref().filter(|a| !a.is_empty()) {
+            Some(args) => format!("-javaagent:{}={}", self.path, args),
+            None => format!("-javaagent:{}", self.path),
+        }
+    }
+}
+
+/// JVM arguments for the enabled agents. Agents whose jar is missing are
+/// returned separately so the launch can warn instead of failing in the JVM.
+pub fn jvm_args(agents: &[JavaAgent]) -> (Vec<String>, Vec<String>) {
+    let mut args = Vec::new();
+    let mut missing = Vec::new();
+    for agent in agents.iter().filter(|a| a.enabled) {
+        if Path::new(&agent.path).is_file() {
+            args.push(agent.to_jvm_arg());
+        } else {
+            missing.push(agent.path.clone());
+        }
+    }
+    (args, missing)
+}
+
+#[derive(Debug, Deserialize)]
+struct ArtifactChecksums {
+    sha256: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct Artifact {
+    version: String,
+    download_url: String,
+    checksums: ArtifactChecksums,
+}
+
+/// Download a well-known agent (currently "authlib-injector") into
+/// `agents_dir`, reusing an already downloaded copy of the same version
+pub async fn download_known_agent(name: &str, agents_dir: &Path) -> Result<PathBuf, String> {
+    let metadata_url = match name {
+        "authlib-injector" => AUTHLIB_INJECTOR_LATEST,
+        _ => return Err(format!("Unknown agent: {}", name)),
+    };
+    let artifact: Artifact = reqwest::get(metadata_url)
+        .await
+        .map_err(|e| format!("Failed to fetch {} metadata: {}", name, e))?
+        .json()
+        .await
+        .map_err(|e| format!("Invalid {} metadata: {}", name, e))?;
+
+    let path = agents_dir.join(format!("{}-{}.jar", name, artifact.version));
+    if let Ok(existing) = std::fs::read(&path)
+        && crate::downloader::compute_sha256(&existing) == artifact.checksums.sha256
+    {
+        return Ok(path);
+    }
+
+    let data = reqwest::get(&artifact.download_url)
+        .await
+        .map_err(|e| format!("Failed to download {}: {}", name, e))?
+        .bytes()
+        .await
+        .map_err(|e| e.to_string())?;
+    if crate::downloader::compute_sha256(&data) != artifact.checksums.sha256 {
+        return Err(format!("Checksum mismatch for {}", name));
+    }
+    std::fs::create_dir_all(agents_dir).map_err(|e| e.to_string())?;
+    std::fs::write(&path, &data).map_err(|e| e.to_string())?;
+    Ok(path)
+}
+
+/// Previously downloaded copy of a well-known agent, newest version first
+fn find_downloaded(name: &str, agents_dir: &Path) -> Option<PathBuf> {
+    let prefix = format!("{}-", name);
+    let mut jars: Vec<PathBuf> = std::fs::read_dir(agents_dir)
+        .ok()?
+        .flatten()
+        .map(|entry| entry.path())
+        .filter(|path| {
+            path.file_name().is_some_and(|n| {
+                let n = n.to_string_lossy();
+                n.starts_with(&prefix) && n.ends_with(".jar")
+            })
+        })
+        .collect();
+    jars.sort_by_key(|path| std::fs::metadata(path).and_then(|m| m.modified()).ok());
+    jars.pop()
+}
+
+/// [`download_known_agent`], falling back to an already downloaded copy
+/// when the update check fails (e.g. offline)
+pub async fn ensure_known_agent(name: &str, agents_dir: &Path) -> Result<PathBuf, String> {
+    match download_known_agent(name, agents_dir).await {
+        Ok(path) => Ok(path),
+        Err(e) => find_downloaded(name, agents_dir).ok_or(e),
+    }
+}
+
+/// Whether `agents` already has `name` (e.g. added with its own arguments)
+pub fn has_known_agent(agents: &[JavaAgent], name: &str) -> bool {
+    let prefix = format!("{}-", name);
+    agents.iter().any(|a| {
+        a.enabled
+            && Path::new(&a.path)
+                .file_name()
+                .is_some_and(|n| n.to_string_lossy().starts_with(&prefix))
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_to_jvm_arg() {
+        let mut agent = JavaAgent {
+            path: "/agents/authlib-injector.jar".to_string(),
+            args: Some("https://example.com/api/yggdrasil".to_string()),
+            enabled: true,
+        };
+        assert_eq!(
+            agent.to_jvm_arg(),
+            "-javaagent:/agents/authlib-injector.jar=https://example.com/api/yggdrasil"
+        );
+        agent.args = Some(String::new());
+        assert_eq!(
+            agent.to_jvm_arg(),
+            "-javaagent:/agents/authlib-injector.jar"
+        );
+    }
+
+    #[test]
+    fn test_jvm_args_skips_disabled_and_missing() {
+        let agents = vec![
+            JavaAgent {
+                path: "/nonexistent/a.jar".to_string(),
+                args: None,
+                enabled: true,
+            },
+            JavaAgent {
+                path: "/nonexistent/b.jar".to_string(),
+                args: None,
+                enabled: false,
+            },
+        ];
+        let (args, missing) = jvm_args(&agents);
+        assert!(args.is_empty());
+        assert_eq!(missing, vec!["/nonexistent/a.jar"]);
+    }
+}
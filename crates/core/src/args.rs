@@ -0,0 +1,541 @@
+//! Launch argument placeholder resolution.
+//!
+//! Version JSONs use `${name}` placeholders in JVM and game arguments. An
+//! [`ArgContext`] holds the known values, filled in by providers for each
+//! area (auth, paths, version, resolution, quick play), and expands argument
+//! lists with their rules applied.
+
+use crate::config::FeatureFlags;
+use crate::game_version::Rule;
+use std::collections::HashMap;
+use std::path::Path;
+
+/// Placeholders understood by the launcher
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Placeholder {
+    AuthPlayerName,
+    AuthUuid,
+    AuthAccessToken,
+    AuthSession,
+    AuthXuid,
+    ClientId,
+    UserType,
+    UserProperties,
+    VersionName,
+    VersionType,
+    GameDirectory,
+    AssetsRoot,
+    GameAssets,
+    AssetsIndexName,
+    NativesDirectory,
+    LibraryDirectory,
+    Classpath,
+    ClasspathSeparator,
+    LauncherName,
+    LauncherVersion,
+    ResolutionWidth,
+    ResolutionHeight,
+    QuickPlayPath,
+    QuickPlaySingleplayer,
+    QuickPlayMultiplayer,
+    QuickPlayRealms,
+}
+
+const PLACEHOLDERS: &[(Placeholder, &str)] = &[
+    (Placeholder::AuthPlayerName, "auth_player_name"),
+    (Placeholder::AuthUuid, "auth_uuid"),
+    (Placeholder::AuthAccessToken, "auth_access_token"),
+    (Placeholder::AuthSession, "auth_session"),
+    (Placeholder::AuthXuid, "auth_xuid"),
+    (Placeholder::ClientId, "clientid"),
+    (Placeholder::UserType, "user_type"),
+    (Placeholder::UserProperties, "user_properties"),
+    (Placeholder::VersionName, "version_name"),
+    (Placeholder::VersionType, "version_type"),
+    (Placeholder::GameDirectory, "game_directory"),
+    (Placeholder::AssetsRoot, "assets_root"),
+    (Placeholder::GameAssets, "game_assets"),
+    (Placeholder::AssetsIndexName, "assets_index_name"),
+    (Placeholder::NativesDirectory, "natives_directory"),
+    (Placeholder::LibraryDirectory, "library_directory"),
+    (Placeholder::Classpath, "classpath"),
+    (Placeholder::ClasspathSeparator, "classpath_separator"),
+    (Placeholder::LauncherName, "launcher_name"),
+    (Placeholder::LauncherVersion, "launcher_version"),
+    (Placeholder::ResolutionWidth, "resolution_width"),
+    (Placeholder::ResolutionHeight, "resolution_height"),
+    (Placeholder::QuickPlayPath, "quickPlayPath"),
+    (Placeholder::QuickPlaySingleplayer, "quickPlaySingleplayer"),
+    (Placeholder::QuickPlayMultiplayer, "quickPlayMultiplayer"),
+    (Placeholder::QuickPlayRealms, "quickPlayRealms"),
+];
+
+impl Placeholder {
+    /// Name between `${` and `}`
+    pub fn key(self) -> &'static str {
+        PLACEHOLDERS
+            .iter()
+            .find(|(p, _)| *p == self)
+            .map(|(_, key)| *key)
+            .unwrap_or_default()
+    }
+
+    pub fn from_key(key: &str) -> Option<Self> {
+        PLACEHOLDERS
+            .iter()
+            .find(|(_, k)| *k == key)
+            .map(|(p, _)| *p)
+    }
+}
+
+/// What to do with an argument whose placeholders can't all be resolved
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ResolveMode {
+    /// Drop the argument (and the rest of its group)
+    Strict,
+    /// Keep the unresolved `${...}` text as is
+    Lenient,
+}
+
+fn path_str(path: &Path) -> String {
+    path.to_string_lossy().to_string()
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct ArgContext {
+    values: HashMap<Placeholder, String>,
+}
+
+impl ArgContext {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn set(&mut self, placeholder: Placeholder, value: impl Into<String>) -> &mut Self {
+        self.values.insert(placeholder, value.into());
+        self
+    }
+
+    pub fn get(&self, placeholder: Placeholder) -> Option<&str> {
+        self.values.get(&placeholder).map(String::as_str)
+    }
+
+    /// Player identity and session
+    pub fn with_auth(
+        &mut self,
+        username: &str,
+        uuid: &str,
+        access_token: &str,
+        user_type: &str,
+    ) -> &mut Self {
+        self.set(Placeholder::AuthPlayerName, username)
+            .set(Placeholder::AuthUuid, uuid)
+            .set(Placeholder::AuthAccessToken, access_token)
+            // Pre-1.6 versions take a single session string
+            .set(
+                Placeholder::AuthSession,
+                format!("token:{}:{}", access_token, uuid),
+            )
+            .set(Placeholder::UserType, user_type)
+            .set(Placeholder::UserProperties, "{}")
+            .set(Placeholder::AuthXuid, "0")
+            .set(Placeholder::ClientId, "0")
+    }
+
+    pub fn with_version(
+        &mut self,
+        version_name: &str,
+        version_type: &str,
+        assets_index_name: &str,
+    ) -> &mut Self {
+        self.set(Placeholder::VersionName, version_name)
+            .set(Placeholder::VersionType, version_type)
+            .set(Placeholder::AssetsIndexName, assets_index_name)
+    }
+
+    pub fn with_paths(
+        &mut self,
+        game_dir: &Path,
+        assets_dir: &Path,
+        natives_dir: &Path,
+        libraries_dir: &Path,
+    ) -> &mut Self {
+        self.set(Placeholder::GameDirectory, path_str(game_dir))
+            .set(Placeholder::AssetsRoot, path_str(assets_dir))
+            .set(Placeholder::GameAssets, path_str(assets_dir))
+            .set(Placeholder::NativesDirectory, path_str(natives_dir))
+            .set(Placeholder::LibraryDirectory, path_str(libraries_dir))
+    }
+
+    pub fn with_classpath(&mut self, classpath: &str, separator: &str) -> &mut Self {
+        self.set(Placeholder::Classpath, classpath)
+            .set(Placeholder::ClasspathSeparator, separator)
+    }
+
+    pub fn with_launcher(&mut self) -> &mut Self {
+        self.set(Placeholder::LauncherName, "DropOut")
+            .set(Placeholder::LauncherVersion, env!("CARGO_PKG_VERSION"))
+    }
+
+    pub fn with_resolution(&mut self, width: u32, height: u32) -> &mut Self {
+        self.set(Placeholder::ResolutionWidth, width.to_string())
+            .set(Placeholder::ResolutionHeight, height.to_string())
+    }
+
+    /// Quick Play targets; only the configured ones are set
+    pub fn with_quick_play(&mut self, features: &FeatureFlags, game_dir: &Path) -> &mut Self {
+        if !features.quick_play_enabled {
+            return self;
+        }
+        self.set(
+            Placeholder::QuickPlayPath,
+            path_str(&game_dir.join("quickPlay").join("log.json")),
+        );
+        if features.quick_play_singleplayer
+            && let Some(world) = features.quick_play_path.as_ref().filter(|p| !p.is_empty())
+        {
+            self.set(Placeholder::QuickPlaySingleplayer, world.clone());
+        }
+        if let Some(server) = features
+            .quick_play_multiplayer_server
+            .as_ref()
+            .filter(|s| !s.is_empty())
+        {
+            self.set(Placeholder::QuickPlayMultiplayer, server.clone());
+        }
+        self
+    }
+
+    /// Substitute every `${name}`. Returns the names that could not be
+    /// resolved if there are any.
+    pub fn resolve(&self, template: &str) -> Result<String, Vec<String>> {
+        let mut out = String::with_capacity(template.len());
+        let mut unresolved = Vec::new();
+        let mut rest = template;
+        while let Some(start) = rest.find("${") {
+            out.push_str(&rest[..start]);
+            let after = &rest[start + 2..];
+            let Some(end) = after.find('}') else {
+                // Malformed: no closing brace
+                unresolved.push(after.to_string());
+                out.push_str(&rest[start..]);
+                rest = "";
+                break;
+            };
+            let name = &after[..end];
+            match Placeholder::from_key(name).and_then(|p| self.get(p)) {
+                Some(value) => out.push_str(value),
+                None => {
+                    unresolved.push(name.to_string());
+                    out.push_str(&rest[start..start + 2 + end + 1]);
+                }
+            }
+            rest = &after[end + 1..];
+        }
+        out.push_str(rest);
+        if unresolved.is_empty() {
+            Ok(out)
+        } else {
+            Err(unresolved)
+        }
+    }
+
+    /// Resolve one argument according to `mode`; `None` means drop it
+    pub fn resolve_with(&self, template: &str, mode: ResolveMode) -> Option<String> {
+        match self.resolve(template) {
+            Ok(arg) => Some(arg),
+            Err(_) if mode == ResolveMode::Strict => None,
+            Err(_) => {
+                // Substitute what is known, keep the rest verbatim
+                let mut arg = template.to_string();
+                for (placeholder, value) in &self.values {
+                    arg = arg.replace(&format!("${{{}}}", placeholder.key()), value);
+                }
+                Some(arg)
+            }
+        }
+    }
+
+    /// Expand a legacy `minecraftArguments` string
+    pub fn expand_legacy(&self, arguments: &str, mode: ResolveMode) -> Vec<String> {
+        arguments
+            .split_whitespace()
+            .filter_map(|part| self.resolve_with(part, mode))
+            .collect()
+    }
+
+    /// Expand a modern `arguments.game`/`arguments.jvm` list. Entries whose
+    /// rules don't match are skipped; a multi-value entry is dropped as a
+    /// whole in strict mode so flags don't lose their values.
+    pub fn expand(
+        &self,
+        list: &serde_json::Value,
+        mode: ResolveMode,
+        features: Option<&FeatureFlags>,
+    ) -> Vec<String> {
+        let mut args = Vec::new();
+        for item in list.as_array().into_iter().flatten() {
+            if let Some(s) = item.as_str() {
+                args.extend(self.resolve_with(s, mode));
+                continue;
+            }
+            let Some(obj) = item.as_object() else {
+                continue;
+            };
+            if let Some(rules_val) = obj.get("rules") {
+                let allowed = serde_json::from_value::<Vec<Rule>>(rules_val.clone())
+                    .map(|rules| crate::rules::is_library_allowed(&Some(rules), features))
+                    .unwrap_or(false);
+                if !allowed {
+                    continue;
+                }
+            }
+            let values: Vec<&str> = match obj.get("value") {
+                Some(serde_json::Value::String(s)) => vec![s.as_str()],
+                Some(serde_json::Value::Array(values)) => {
+                    values.iter().filter_map(|v| v.as_str()).collect()
+                }
+                _ => continue,
+            };
+            let group: Option<Vec<String>> =
+                values.iter().map(|v| self.resolve_with(v, mode)).collect();
+            args.extend(group.into_iter().flatten());
+        }
+        args
+    }
+}
+
+/// Game arguments for a window size and fullscreen mode that the expanded
+/// `game_args` don't set yet (the version JSON only has them behind the
+/// `has_custom_resolution` feature, which is never enabled)
+pub fn window_args(
+    game_args: &[String],
+    width: Option<u32>,
+    height: Option<u32>,
+    fullscreen: bool,
+) -> Vec<String> {
+    let has = |flag: &str| game_args.iter().any(|a| a == flag);
+    let mut args = Vec::new();
+    if let (Some(width), Some(height)) = (width, height)
+        && !has("--width")
+        && !has("--height")
+    {
+        args.extend([
+            "--width".to_string(),
+            width.to_string(),
+            "--height".to_string(),
+            height.to_string(),
+        ]);
+    }
+    if fullscreen && !has("--fullscreen") {
+        args.push("--fullscreen".to_string());
+    }
+    args
+}
+
+/// Windows limits a command line to 32,767 UTF-16 units; keep some headroom
+pub const WINDOWS_COMMAND_LINE_LIMIT: usize = 32_000;
+
+/// Approximate length of the command line Windows builds from `program` and
+/// `args` (each argument quoted and separated by a space)
+pub fn command_line_length(program: &str, args: &[String]) -> usize {
+    std::iter::once(program)
+        .chain(args.iter().map(String::as_str))
+        .map(|arg| arg.encode_utf16().count() + 3)
+        .sum()
+}
+
+/// Contents of a Java 9+ `@argfile`: one quoted argument per line
+pub fn argfile_contents(args: &[String]) -> String {
+    args.iter()
+        .map(|arg| format!("\"{}\"\n", arg.replace('\\', "\\\\").replace('"', "\\\"")))
+        .collect()
+}
+
+/// `file:` URL of a classpath entry, as expected by a manifest `Class-Path`
+fn classpath_url(entry: &str) -> String {
+    let path = entry.replace('\\', "/");
+    let mut url = if path.starts_with('/') {
+        "file:".to_string()
+    } else {
+        "file:/".to_string()
+    };
+    for byte in path.bytes() {
+        match byte {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'/' | b':' | b'-' | b'_' | b'.' | b'~' => {
+                url.push(byte as char)
+            }
+            _ => url.push_str(&format!("%{:02X}", byte)),
+        }
+    }
+    url
+}
+
+/// Manifest of a "pathing jar" whose `Class-Path` lists the real classpath.
+/// Lines are wrapped at 72 bytes as the jar specification requires.
+pub fn pathing_manifest(entries: &[String]) -> String {
+    let class_path = entries
+        .iter()
+        .map(|e| classpath_url(e))
+        .collect::<Vec<_>>()
+        .join(" ");
+    let header = format!("Class-Path: {}", class_path);
+
+    let mut manifest = String::from("Manifest-Version: 1.0\r\n");
+    let mut rest = header.as_str();
+    let mut width = 72;
+    while !rest.is_empty() {
+        // URLs are percent-encoded ASCII, so any byte index is a char boundary
+        let (line, tail) = rest.split_at(rest.len().min(width));
+        if width == 71 {
+            manifest.push(' ');
+        }
+        manifest.push_str(line);
+        manifest.push_str("\r\n");
+        rest = tail;
+        width = 71;
+    }
+    manifest.push_str("\r\n");
+    manifest
+}
+
+/// Write a pathing jar for Java 8, where `@argfile` is not supported
+pub fn write_pathing_jar(path: &Path, entries: &[String]) -> Result<(), String> {
+    use std::io::Write;
+
+    let file = std::fs::File::create(path)
+        .map_err(|e| format!("Failed to create {}: {}", path.display(), e))?;
+    let mut jar = zip::ZipWriter::new(file);
+    jar.start_file(
+        "META-INF/MANIFEST.MF",
+        zip::write::SimpleFileOptions::default(),
+    )
+    .map_err(|e| e.to_string())?;
+    jar.write_all(pathing_manifest(entries).as_bytes())
+        .map_err(|e| e.to_string())?;
+    jar.finish().map_err(|e| e.to_string())?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn context() -> ArgContext {
+        let mut ctx = ArgContext::new();
+        ctx.with_auth("Steve", "uuid-1", "token", "msa")
+            .with_version("1.20.4", "release", "12")
+            .with_launcher();
+        ctx
+    }
+
+    #[test]
+    fn test_placeholder_keys_round_trip() {
+        for (placeholder, key) in PLACEHOLDERS {
+            assert_eq!(Placeholder::from_key(key), Some(*placeholder));
+            assert_eq!(placeholder.key(), *key);
+        }
+        assert_eq!(Placeholder::from_key("unknown"), None);
+    }
+
+    #[test]
+    fn test_resolve() {
+        let ctx = context();
+        assert_eq!(
+            ctx.resolve("--username=${auth_player_name}").unwrap(),
+            "--username=Steve"
+        );
+        assert_eq!(
+            ctx.resolve("${version_name}-${version_type}").unwrap(),
+            "1.20.4-release"
+        );
+        assert_eq!(
+            ctx.resolve("${resolution_width}x${unknown}").unwrap_err(),
+            vec!["resolution_width", "unknown"]
+        );
+        assert_eq!(ctx.resolve("${broken").unwrap_err(), vec!["broken"]);
+        assert_eq!(ctx.resolve("plain").unwrap(), "plain");
+    }
+
+    #[test]
+    fn test_resolve_modes() {
+        let ctx = context();
+        assert_eq!(
+            ctx.resolve_with("${quickPlayPath}", ResolveMode::Strict),
+            None
+        );
+        assert_eq!(
+            ctx.resolve_with("${auth_player_name}:${x}", ResolveMode::Lenient)
+                .as_deref(),
+            Some("Steve:${x}")
+        );
+    }
+
+    #[test]
+    fn test_expand_drops_whole_group() {
+        let ctx = context();
+        let list = json!([
+            "--username",
+            "${auth_player_name}",
+            {"rules": [{"action": "allow"}], "value": ["--quickPlayPath", "${quickPlayPath}"]},
+            {"rules": [{"action": "allow"}], "value": ["--version", "${version_name}"]},
+            {"rules": [{"action": "allow", "features": {"is_demo_user": true}}], "value": "--demo"}
+        ]);
+        assert_eq!(
+            ctx.expand(&list, ResolveMode::Strict, None),
+            vec!["--username", "Steve", "--version", "1.20.4"]
+        );
+    }
+
+    #[test]
+    fn test_argfile_contents() {
+        let args = vec!["-cp".to_string(), r"C:\Games\my pack\a.jar".to_string()];
+        assert_eq!(
+            argfile_contents(&args),
+            "\"-cp\"\n\"C:\\\\Games\\\\my pack\\\\a.jar\"\n"
+        );
+    }
+
+    #[test]
+    fn test_pathing_manifest() {
+        let entries: Vec<String> = (0..20)
+            .map(|i| format!(r"C:\Users\Me\.dropout\libraries\lib {}.jar", i))
+            .collect();
+        let manifest = pathing_manifest(&entries);
+        assert!(manifest.lines().all(|line| line.len() <= 72));
+        assert!(manifest.starts_with("Manifest-Version: 1.0\r\nClass-Path: file:/C:/Users/Me/"));
+        // Unwrapping continuation lines gives back the URL list
+        let unwrapped = manifest.replace("\r\n ", "");
+        assert!(unwrapped.contains("file:/C:/Users/Me/.dropout/libraries/lib%2019.jar"));
+        assert!(manifest.ends_with("\r\n\r\n"));
+    }
+
+    #[test]
+    fn test_expand_legacy() {
+        let ctx = context();
+        assert_eq!(
+            ctx.expand_legacy(
+                "--session ${auth_session} --assetsDir ${game_assets}",
+                ResolveMode::Lenient
+            ),
+            vec![
+                "--session",
+                "token:token:uuid-1",
+                "--assetsDir",
+                "${game_assets}"
+            ]
+        );
+    }
+
+    #[test]
+    fn test_window_args() {
+        assert_eq!(
+            window_args(&[], Some(1280), Some(720), true),
+            vec!["--width", "1280", "--height", "720", "--fullscreen"]
+        );
+        assert!(window_args(&[], Some(1280), None, false).is_empty());
+        let existing = vec!["--width".to_string(), "854".to_string()];
+        assert!(window_args(&existing, Some(1280), Some(720), false).is_empty());
+    }
+}
@@ -0,0 +1,347 @@
+//! Verified cache for asset index JSONs.
+//!
+//! Indexes are stored content-addressed under `indexes/sha1/<sha1>.json`, with
+//! the `indexes/<id>.json` copy the game reads kept in sync. Both are checked
+//! against the `sha1` from the version metadata; a corrupt or partially
+//! written index is re-fetched instead of breaking the asset download.
+//!
+//! Old indexes (`legacy`, `pre-1.6`) are flagged `virtual` or
+//! `map_to_resources`: those game versions read assets by name rather than
+//! from `objects/`, so the files are materialized — as hard links where the
+//! filesystem allows — into `assets/virtual/<id>` or `<game_dir>/resources`.
+
+use super::downloader::{DownloadTask, compute_sha1, get_mirrored, write_atomic};
+use super::game_version::AssetIndex;
+use serde::Deserialize;
+use std::collections::{HashMap, HashSet};
+use std::path::{Component, Path, PathBuf};
+
+const FETCH_ATTEMPTS: usize = 3;
+const RESOURCES_URL: &str = "https://resources.download.minecraft.net/";
+
+fn cache_path(indexes_dir: &Path, index: &AssetIndex) -> PathBuf {
+    indexes_dir
+        .join("sha1")
+        .join(format!("{}.json", index.sha1.to_lowercase()))
+}
+
+fn id_path(indexes_dir: &Path, index: &AssetIndex) -> PathBuf {
+    indexes_dir.join(format!("{}.json", index.id))
+}
+
+fn read_verified(path: &Path, sha1: &str) -> Option<Vec<u8>> {
+    let data = std::fs::read(path).ok()?;
+    compute_sha1(&data)
+        .eq_ignore_ascii_case(sha1)
+        .then_some(data)
+}
+
+fn store(indexes_dir: &Path, index: &AssetIndex, data: &[u8]) -> Result<(), String> {
+    write_atomic(&cache_path(indexes_dir, index), data)?;
+    write_atomic(&id_path(indexes_dir, index), data)
+}
+
+/// Load a verified index from disk, repairing whichever of the two copies is
+/// missing or corrupt. Returns `None` when neither copy matches.
+pub fn load_cached(index: &AssetIndex, indexes_dir: &Path) -> Option<String> {
+    let cached = cache_path(indexes_dir, index);
+    let by_id = id_path(indexes_dir, index);
+
+    let data = if let Some(data) = read_verified(&cached, &index.sha1) {
+        if read_verified(&by_id, &index.sha1).is_none()
+            && let Err(e) = write_atomic(&by_id, &data)
+        {
+            eprintln!("[AssetIndex] Failed to restore {}: {}", by_id.display(), e);
+        }
+        data
+    } else {
+        let data = read_verified(&by_id, &index.sha1)?;
+        if let Err(e) = write_atomic(&cached, &data) {
+            eprintln!("[AssetIndex] Failed to cache {}: {}", cached.display(), e);
+        }
+        data
+    };
+    String::from_utf8(data).ok()
+}
+
+/// Return the asset index contents, downloading and verifying it if needed
+pub async fn load_or_fetch(index: &AssetIndex, indexes_dir: &Path) -> Result<String, String> {
+    if let Some(content) = load_cached(index, indexes_dir) {
+        return Ok(content);
+    }
+
+    let mut last_error = String::new();
+    for attempt in 1..=FETCH_ATTEMPTS {
+        println!(
+            "[AssetIndex] Downloading {} (attempt {}/{})",
+            index.url, attempt, FETCH_ATTEMPTS
+        );
+        let data = match get_mirrored(&reqwest::Client::new(), &index.url).await {
+            Ok(resp) => match resp.error_for_status() {
+                Ok(resp) => resp.bytes().await.map_err(|e| e.to_string()),
+                Err(e) => Err(e.to_string()),
+            },
+            Err(e) => Err(e.to_string()),
+        };
+        match data {
+            Ok(data) if compute_sha1(&data).eq_ignore_ascii_case(&index.sha1) => {
+                store(indexes_dir, index, &data)?;
+                return String::from_utf8(data.to_vec()).map_err(|e| e.to_string());
+            }
+            Ok(data) => {
+                last_error = format!(
+                    "Checksum mismatch for asset index {}: expected {}, got {}",
+                    index.id,
+                    index.sha1,
+                    compute_sha1(&data)
+                );
+            }
+            Err(e) => last_error = format!("Failed to download asset index {}: {}", index.id, e),
+        }
+    }
+    Err(last_error)
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct AssetObject {
+    pub hash: String,
+    pub size: u64,
+}
+
+/// Contents of an asset index JSON
+#[derive(Debug, Clone, Deserialize)]
+pub struct AssetIndexContents {
+    pub objects: HashMap<String, AssetObject>,
+    #[serde(default, rename = "virtual")]
+    pub is_virtual: bool,
+    #[serde(default)]
+    pub map_to_resources: bool,
+}
+
+pub fn parse(content: &str) -> Result<AssetIndexContents, String> {
+    serde_json::from_str(content).map_err(|e| format!("Invalid asset index: {}", e))
+}
+
+pub fn object_path(objects_dir: &Path, hash: &str) -> PathBuf {
+    objects_dir.join(&hash[..2.min(hash.len())]).join(hash)
+}
+
+/// Number and total size of the distinct objects of `contents` (several
+/// names can share one object)
+pub fn download_size(contents: &AssetIndexContents) -> (usize, u64) {
+    let mut seen = HashSet::new();
+    contents
+        .objects
+        .values()
+        .filter(|object| seen.insert(object.hash.as_str()))
+        .fold((0, 0), |(count, size), object| {
+            (count + 1, size + object.size)
+        })
+}
+
+/// Download tasks for the distinct objects of `contents`, produced lazily so
+/// that downloads can start while the rest of a large index is planned
+pub fn download_tasks<'a>(
+    contents: &'a AssetIndexContents,
+    objects_dir: &'a Path,
+) -> impl Iterator<Item = DownloadTask> + 'a {
+    let mut seen = HashSet::new();
+    contents
+        .objects
+        .values()
+        .filter(move |object| seen.insert(object.hash.as_str()))
+        .map(move |object| {
+            let prefix = &object.hash[..2.min(object.hash.len())];
+            DownloadTask {
+                url: format!("{}{}/{}", RESOURCES_URL, prefix, object.hash),
+                path: object_path(objects_dir, &object.hash),
+                sha1: Some(object.hash.clone()),
+                sha256: None,
+            }
+        })
+}
+
+/// Where the assets of `index_id` have to exist under their real names, if
+/// anywhere. `mode` is the launcher setting: "auto" follows the index flags,
+/// "always" also materializes modern indexes (for mods that read asset
+/// files directly), "off" never materializes.
+pub fn materialize_dir(
+    contents: &AssetIndexContents,
+    index_id: &str,
+    mode: &str,
+    assets_dir: &Path,
+    game_dir: &Path,
+) -> Option<PathBuf> {
+    if mode == "off" {
+        return None;
+    }
+    if contents.map_to_resources {
+        Some(game_dir.join("resources"))
+    } else if contents.is_virtual || mode == "always" {
+        Some(assets_dir.join("virtual").join(index_id))
+    } else {
+        None
+    }
+}
+
+/// Asset names are relative paths; anything escaping the target is skipped
+fn is_safe_name(name: &str) -> bool {
+    !name.is_empty()
+        && Path::new(name)
+            .components()
+            .all(|c| matches!(c, Component::Normal(_)))
+}
+
+/// Link (or copy) every object of `contents` to `<target>/<name>`. Files that
+/// already have the right size are kept. Returns the number of files written.
+pub fn materialize(
+    contents: &AssetIndexContents,
+    objects_dir: &Path,
+    target: &Path,
+) -> Result<usize, String> {
+    let mut written = 0;
+    for (name, object) in &contents.objects {
+        if !is_safe_name(name) {
+            eprintln!("[AssetIndex] Skipping unsafe asset name {}", name);
+            continue;
+        }
+        let dest = target.join(name);
+        if std::fs::metadata(&dest).is_ok_and(|m| m.len() == object.size) {
+            continue;
+        }
+        let source = object_path(objects_dir, &object.hash);
+        if let Some(parent) = dest.parent() {
+            std::fs::create_dir_all(parent).map_err(|e| e.to_string())?;
+        }
+        let _ = std::fs::remove_file(&dest);
+        if std::fs::hard_link(&source, &dest).is_err() {
+            // Different volume or no hard link support
+            std::fs::copy(&source, &dest)
+                .map_err(|e| format!("Failed to materialize asset {}: {}", name, e))?;
+        }
+        written += 1;
+    }
+    Ok(written)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn index_for(content: &str) -> AssetIndex {
+        AssetIndex {
+            id: "17".to_string(),
+            sha1: compute_sha1(content.as_bytes()),
+            size: content.len() as u64,
+            url: "https://example.com/17.json".to_string(),
+            total_size: None,
+        }
+    }
+
+    #[test]
+    fn test_load_cached_repairs_corrupt_copy() {
+        let dir = std::env::temp_dir().join(format!("dropout-index-{}", uuid::Uuid::new_v4()));
+        let content = r#"{"objects": {}}"#;
+        let index = index_for(content);
+
+        assert!(load_cached(&index, &dir).is_none());
+
+        // Only the id copy exists: it gets mirrored into the content-addressed cache
+        write_atomic(&id_path(&dir, &index), content.as_bytes()).unwrap();
+        assert_eq!(load_cached(&index, &dir).as_deref(), Some(content));
+        assert!(cache_path(&dir, &index).exists());
+
+        // A truncated id copy is restored from the cache
+        std::fs::write(id_path(&dir, &index), &content[..5]).unwrap();
+        assert_eq!(load_cached(&index, &dir).as_deref(), Some(content));
+        assert_eq!(
+            std::fs::read_to_string(id_path(&dir, &index)).unwrap(),
+            content
+        );
+
+        // Both corrupt: nothing usable on disk
+        std::fs::write(id_path(&dir, &index), "x").unwrap();
+        std::fs::write(cache_path(&dir, &index), "x").unwrap();
+        assert!(load_cached(&index, &dir).is_none());
+
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn test_materialize_dir() {
+        let legacy = parse(r#"{"virtual": true, "objects": {}}"#).unwrap();
+        let pre16 = parse(r#"{"map_to_resources": true, "objects": {}}"#).unwrap();
+        let modern = parse(r#"{"objects": {}}"#).unwrap();
+        let (assets, game) = (Path::new("/a"), Path::new("/g"));
+        assert_eq!(
+            materialize_dir(&legacy, "legacy", "auto", assets, game),
+            Some(PathBuf::from("/a/virtual/legacy"))
+        );
+        assert_eq!(
+            materialize_dir(&pre16, "pre-1.6", "auto", assets, game),
+            Some(PathBuf::from("/g/resources"))
+        );
+        assert_eq!(materialize_dir(&modern, "17", "auto", assets, game), None);
+        assert_eq!(
+            materialize_dir(&modern, "17", "always", assets, game),
+            Some(PathBuf::from("/a/virtual/17"))
+        );
+        assert_eq!(
+            materialize_dir(&legacy, "legacy", "off", assets, game),
+            None
+        );
+    }
+
+    #[test]
+    fn test_materialize() {
+        let dir = std::env::temp_dir().join(format!("dropout-assets-{}", uuid::Uuid::new_v4()));
+        let objects = dir.join("objects");
+        let hash = compute_sha1(b"click");
+        let source = object_path(&objects, &hash);
+        std::fs::create_dir_all(source.parent().unwrap()).unwrap();
+        std::fs::write(&source, b"click").unwrap();
+
+        let contents = parse(&format!(
+            r#"{{"virtual": true, "objects": {{
+                "sound/random/click.ogg": {{"hash": "{hash}", "size": 5}},
+                "../escape.ogg": {{"hash": "{hash}", "size": 5}}
+            }}}}"#
+        ))
+        .unwrap();
+        let target = dir.join("virtual").join("legacy");
+        assert_eq!(materialize(&contents, &objects, &target).unwrap(), 1);
+        assert_eq!(
+            std::fs::read(target.join("sound/random/click.ogg")).unwrap(),
+            b"click"
+        );
+        assert!(!dir.join("virtual").join("escape.ogg").exists());
+        // Already in place
+        assert_eq!(materialize(&contents, &objects, &target).unwrap(), 0);
+
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn test_download_tasks_dedup_shared_objects() {
+        let contents = parse(
+            r#"{"objects": {
+                "a.ogg": {"hash": "ab12", "size": 10},
+                "b.ogg": {"hash": "ab12", "size": 10},
+                "c.png": {"hash": "cd34", "size": 5}
+            }}"#,
+        )
+        .unwrap();
+        assert_eq!(download_size(&contents), (2, 15));
+
+        let objects = Path::new("/assets/objects");
+        let mut tasks: Vec<DownloadTask> = download_tasks(&contents, objects).collect();
+        tasks.sort_by(|a, b| a.url.cmp(&b.url));
+        assert_eq!(tasks.len(), 2);
+        assert_eq!(
+            tasks[0].url,
+            "https://resources.download.minecraft.net/ab/ab12"
+        );
+        assert_eq!(tasks[0].path, objects.join("ab").join("ab12"));
+        assert_eq!(tasks[1].sha1.as_deref(), Some("cd34"));
+    }
+}
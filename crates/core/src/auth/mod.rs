@@ -0,0 +1,237 @@
+use serde::{Deserialize, Serialize};
+use std::sync::Mutex;
+use ts_rs::TS;
+use uuid::Uuid;
+
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+#[serde(tag = "type")]
+#[serde(rename_all = "camelCase")]
+#[ts(export, tag = "type", export_to = "dropout-core/auth.ts")]
+pub enum Account {
+    Offline(OfflineAccount),
+    Microsoft(MicrosoftAccount),
+    Yggdrasil(YggdrasilAccount),
+}
+
+impl Account {
+    pub fn username(&self) -> String {
+        match self {
+            Account::Offline(a) => a.username.clone(),
+            Account::Microsoft(a) => a.username.clone(),
+            Account::Yggdrasil(a) => a.username.clone(),
+        }
+    }
+
+    pub fn uuid(&self) -> String {
+        match self {
+            Account::Offline(a) => a.uuid.clone(),
+            Account::Microsoft(a) => a.uuid.clone(),
+            Account::Yggdrasil(a) => a.uuid.clone(),
+        }
+    }
+
+    pub fn access_token(&self) -> String {
+        match self {
+            Account::Offline(_) => "null".to_string(),
+            Account::Microsoft(a) => a.access_token.clone(),
+            Account::Yggdrasil(a) => a.access_token.clone(),
+        }
+    }
+
+    /// `${user_type}` of the game arguments
+    pub fn user_type(&self) -> &'static str {
+        match self {
+            Account::Offline(_) => "legacy",
+            Account::Microsoft(_) => "msa",
+            Account::Yggdrasil(_) => "mojang",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export, export_to = "dropout-core/auth.ts")]
+pub struct OfflineAccount {
+    pub username: String,
+    pub uuid: String,
+    /// Local PNG used as the player's skin (via CustomSkinLoader)
+    #[serde(default)]
+    pub skin_path: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export, export_to = "dropout-core/auth.ts")]
+pub struct MicrosoftAccount {
+    pub username: String,
+    pub uuid: String,
+    pub access_token: String,
+    pub refresh_token: Option<String>,
+    pub expires_at: i64,
+}
+
+/// Account on a third-party Yggdrasil server, used in game through
+/// authlib-injector
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export, export_to = "dropout-core/auth.ts")]
+pub struct YggdrasilAccount {
+    pub username: String,
+    pub uuid: String,
+    pub access_token: String,
+    pub client_token: String,
+    pub server_url: String, // API root, e.g. https://littleskin.cn/api/yggdrasil
+    #[serde(default)]
+    pub server_name: Option<String>,
+}
+
+pub struct AccountState {
+    pub active_account: Mutex<Option<Account>>,
+}
+
+impl AccountState {
+    pub fn new() -> Self {
+        Self {
+            active_account: Mutex::new(None),
+        }
+    }
+}
+
+impl Default for AccountState {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Check an offline username against the rules vanilla servers enforce
+pub fn validate_offline_username(username: &str) -> Result<(), String> {
+    let len = username.chars().count();
+    if !(3..=16).contains(&len) {
+        return Err("Username must be 3-16 characters long".to_string());
+    }
+    if let Some(c) = username
+        .chars()
+        .find(|c| !c.is_ascii_alphanumeric() && *c != '_')
+    {
+        return Err(format!(
+            "Username may only contain letters, digits and '_' (found '{}')",
+            c
+        ));
+    }
+    Ok(())
+}
+
+/// Generate the UUID of an offline account.
+///
+/// `namespace` is "dropout" (DropOut's historic scheme), "vanilla" (the
+/// `OfflinePlayer:<name>` UUID a vanilla server in offline mode assigns, so
+/// player data matches) or any UUID to use as a custom v3 namespace.
+pub fn generate_offline_uuid(username: &str, namespace: &str) -> Result<String, String> {
+    match namespace {
+        "" | "dropout" => Ok(Uuid::new_v3(&Uuid::NAMESPACE_OID, username.as_bytes()).to_string()),
+        "vanilla" => {
+            use md5::{Digest, Md5};
+            let hash: [u8; 16] =
+                Md5::digest(format!("OfflinePlayer:{}", username).as_bytes()).into();
+            Ok(uuid::Builder::from_md5_bytes(hash).into_uuid().to_string())
+        }
+        custom => {
+            let namespace = Uuid::parse_str(custom)
+                .map_err(|_| format!("Invalid offline UUID namespace: {}", custom))?;
+            Ok(Uuid::new_v3(&namespace, username.as_bytes()).to_string())
+        }
+    }
+}
+
+/// Validate that `data` is a Minecraft skin PNG (64x64 or legacy 64x32)
+pub fn validate_skin_png(data: &[u8]) -> Result<(), String> {
+    const PNG_SIGNATURE: &[u8] = b"\x89PNG\r\n\x1a\n";
+    if data.len() < 24 || !data.starts_with(PNG_SIGNATURE) || &data[12..16] != b"IHDR" {
+        return Err("Skin must be a PNG image".to_string());
+    }
+    let width = u32::from_be_bytes([data[16], data[17], data[18], data[19]]);
+    let height = u32::from_be_bytes([data[20], data[21], data[22], data[23]]);
+    match (width, height) {
+        (64, 64) | (64, 32) => Ok(()),
+        _ => Err(format!(
+            "Skin must be 64x64 or 64x32 pixels (got {}x{})",
+            width, height
+        )),
+    }
+}
+
+/// Place an offline account's skin where CustomSkinLoader's LocalSkin
+/// loader looks for it, or remove a previously placed one
+pub fn install_offline_skin(
+    game_dir: &std::path::Path,
+    account: &OfflineAccount,
+) -> Result<(), String> {
+    let target = game_dir
+        .join("CustomSkinLoader")
+        .join("LocalSkin")
+        .join("skins")
+        .join(format!("{}.png", account.username));
+    match &account.skin_path {
+        Some(skin) => {
+            if let Some(parent) = target.parent() {
+                std::fs::create_dir_all(parent).map_err(|e| e.to_string())?;
+            }
+            std::fs::copy(skin, &target).map_err(|e| format!("Failed to install skin: {}", e))?;
+        }
+        None if target.exists() => {
+            std::fs::remove_file(&target).map_err(|e| e.to_string())?;
+        }
+        None => {}
+    }
+    Ok(())
+}
+
+// Microsoft account sign-in (device code flow -> Xbox Live -> Minecraft)
+#[cfg(feature = "auth-msa")]
+mod msa;
+#[cfg(feature = "auth-msa")]
+pub use msa::*;
+
+// Third-party Yggdrasil servers (authlib-injector)
+#[cfg(feature = "auth-yggdrasil")]
+pub mod yggdrasil;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_validate_offline_username() {
+        assert!(validate_offline_username("Steve").is_ok());
+        assert!(validate_offline_username("a_b_123").is_ok());
+        assert!(validate_offline_username("ab").is_err());
+        assert!(validate_offline_username("a".repeat(17).as_str()).is_err());
+        assert!(validate_offline_username("bad name").is_err());
+        assert!(validate_offline_username("名字名字").is_err());
+    }
+
+    #[test]
+    fn test_generate_offline_uuid() {
+        // Same UUID a vanilla offline-mode server assigns to "Notch"
+        assert_eq!(
+            generate_offline_uuid("Notch", "vanilla").unwrap(),
+            "b50ad385-829d-3141-a216-7e7d7539ba7f"
+        );
+        assert_eq!(
+            generate_offline_uuid("Notch", "dropout").unwrap(),
+            Uuid::new_v3(&Uuid::NAMESPACE_OID, b"Notch").to_string()
+        );
+        assert!(generate_offline_uuid("Notch", "not-a-uuid").is_err());
+    }
+
+    #[test]
+    fn test_validate_skin_png() {
+        let mut png = b"\x89PNG\r\n\x1a\n\x00\x00\x00\x0dIHDR".to_vec();
+        png.extend_from_slice(&64u32.to_be_bytes());
+        png.extend_from_slice(&64u32.to_be_bytes());
+        assert!(validate_skin_png(&png).is_ok());
+        png[20..24].copy_from_slice(&128u32.to_be_bytes());
+        assert!(validate_skin_png(&png).is_err());
+        assert!(validate_skin_png(b"GIF89a").is_err());
+    }
+}
@@ -1,7 +1,9 @@
+//! Microsoft account authentication, enabled by the `auth-msa` feature.
+
 use serde::{Deserialize, Serialize};
-use std::sync::Mutex;
 use ts_rs::TS;
-use uuid::Uuid;
+
+use super::MicrosoftAccount;
 
 // Helper to create a client with a custom User-Agent
 // This is critical because Microsoft's WAF often blocks requests without a valid UA
@@ -12,80 +14,12 @@ fn get_client() -> reqwest::Client {
         .unwrap_or_else(|_| reqwest::Client::new())
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize, TS)]
-#[serde(tag = "type")]
-#[serde(rename_all = "camelCase")]
-#[ts(export, tag = "type", export_to = "auth.ts")]
-pub enum Account {
-    Offline(OfflineAccount),
-    Microsoft(MicrosoftAccount),
-}
-
-impl Account {
-    pub fn username(&self) -> String {
-        match self {
-            Account::Offline(a) => a.username.clone(),
-            Account::Microsoft(a) => a.username.clone(),
-        }
-    }
-
-    pub fn uuid(&self) -> String {
-        match self {
-            Account::Offline(a) => a.uuid.clone(),
-            Account::Microsoft(a) => a.uuid.clone(),
-        }
-    }
-
-    pub fn access_token(&self) -> String {
-        match self {
-            Account::Offline(_) => "null".to_string(),
-            Account::Microsoft(a) => a.access_token.clone(),
-        }
-    }
-}
-
-#[derive(Debug, Clone, Serialize, Deserialize, TS)]
-#[serde(rename_all = "camelCase")]
-#[ts(export, export_to = "auth.ts")]
-pub struct OfflineAccount {
-    pub username: String,
-    pub uuid: String,
-}
-
-#[derive(Debug, Clone, Serialize, Deserialize, TS)]
-#[serde(rename_all = "camelCase")]
-#[ts(export, export_to = "auth.ts")]
-pub struct MicrosoftAccount {
-    pub username: String,
-    pub uuid: String,
-    pub access_token: String,
-    pub refresh_token: Option<String>,
-    pub expires_at: i64,
-}
-
-pub struct AccountState {
-    pub active_account: Mutex<Option<Account>>,
-}
-
-impl AccountState {
-    pub fn new() -> Self {
-        Self {
-            active_account: Mutex::new(None),
-        }
-    }
-}
-
-pub fn generate_offline_uuid(username: &str) -> String {
-    let namespace = Uuid::NAMESPACE_OID;
-    Uuid::new_v3(&namespace, username.as_bytes()).to_string()
-}
-
 const CLIENT_ID: &str = "fe165602-5410-4441-92f7-326e10a7cb82";
 const SCOPE: &str = "XboxLive.SignIn XboxLive.offline_access";
 
 #[derive(Debug, Serialize, Deserialize, TS)]
 #[serde(rename_all(serialize = "camelCase"))]
-#[ts(export, export_to = "auth.ts", rename_all = "camelCase")]
+#[ts(export, export_to = "dropout-core/auth.ts", rename_all = "camelCase")]
 pub struct DeviceCodeResponse {
     pub user_code: String,
     pub device_code: String,
@@ -97,7 +31,7 @@ pub struct DeviceCodeResponse {
 
 #[derive(Debug, Serialize, Deserialize, TS)]
 #[serde(rename_all(serialize = "camelCase"))]
-#[ts(export, export_to = "auth.ts")]
+#[ts(export, export_to = "dropout-core/auth.ts")]
 pub struct TokenResponse {
     pub access_token: String,
     pub refresh_token: Option<String>,
@@ -221,7 +155,7 @@ pub struct MinecraftAuthResponse {
 
 #[derive(Debug, Serialize, Deserialize, TS)]
 #[serde(rename_all = "camelCase")]
-#[ts(export, export_to = "auth.ts")]
+#[ts(export, export_to = "dropout-core/auth.ts")]
 pub struct MinecraftProfile {
     pub id: String,
     pub name: String,
@@ -0,0 +1,270 @@
+//! Third-party Yggdrasil authentication (LittleSkin, Ely.by, Blessing Skin
+//! and other authlib-injector compatible servers), enabled by the
+//! `auth-yggdrasil` feature.
+//!
+//! These servers implement Mojang's legacy authserver protocol under an API
+//! root such as `https://littleskin.cn/api/yggdrasil`. The game itself is
+//! pointed at the same root by authlib-injector, which the launcher adds as a
+//! Java agent (`-javaagent:authlib-injector.jar=<api root>`).
+
+use serde::Deserialize;
+use serde_json::json;
+
+use super::YggdrasilAccount;
+
+/// Header a website returns to point at its Yggdrasil API root
+/// (authlib-injector's "API Location Indication")
+const API_LOCATION_HEADER: &str = "x-authlib-injector-api-location";
+
+fn get_client() -> reqwest::Client {
+    reqwest::Client::builder()
+        .user_agent("DropOut/1.0")
+        .build()
+        .unwrap_or_else(|_| reqwest::Client::new())
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct ErrorResponse {
+    error: String,
+    error_message: Option<String>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+struct Profile {
+    id: String,
+    name: String,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct AuthResponse {
+    access_token: String,
+    client_token: String,
+    #[serde(default)]
+    available_profiles: Vec<Profile>,
+    selected_profile: Option<Profile>,
+}
+
+#[derive(Debug, Deserialize)]
+struct MetaInfo {
+    #[serde(rename = "serverName")]
+    server_name: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct Metadata {
+    meta: Option<MetaInfo>,
+}
+
+/// Add the `https://` users usually leave out and drop trailing slashes
+fn normalize_url(url: &str) -> String {
+    let url = url.trim().trim_end_matches('/');
+    if url.contains("://") {
+        url.to_string()
+    } else {
+        format!("https://{}", url)
+    }
+}
+
+/// Resolve an API location header value (absolute or relative) against the
+/// URL that returned it
+fn resolve_location(base: &str, location: &str) -> Result<String, String> {
+    let resolved = reqwest::Url::parse(base)
+        .and_then(|base| base.join(location))
+        .map_err(|e| format!("Invalid authentication server URL: {}", e))?;
+    Ok(resolved.as_str().trim_end_matches('/').to_string())
+}
+
+/// Turn what the user typed (a website or the API root itself) into the
+/// Yggdrasil API root, following the API location header if there is one
+pub async fn resolve_api_root(url: &str) -> Result<String, String> {
+    let url = normalize_url(url);
+    let resp = get_client()
+        .get(&url)
+        .send()
+        .await
+        .map_err(|e| format!("Failed to reach authentication server: {}", e))?;
+    match resp
+        .headers()
+        .get(API_LOCATION_HEADER)
+        .and_then(|v| v.to_str().ok())
+    {
+        Some(location) => resolve_location(&url, location),
+        None => Ok(url),
+    }
+}
+
+/// Display name the server advertises in its metadata
+pub async fn fetch_server_name(api_root: &str) -> Option<String> {
+    let metadata: Metadata = get_client()
+        .get(api_root)
+        .send()
+        .await
+        .ok()?
+        .json()
+        .await
+        .ok()?;
+    metadata.meta?.server_name
+}
+
+async fn post(api_root: &str, path: &str, body: serde_json::Value) -> Result<String, String> {
+    let resp = get_client()
+        .post(format!("{}{}", api_root, path))
+        .json(&body)
+        .send()
+        .await
+        .map_err(|e| format!("Failed to reach authentication server: {}", e))?;
+    let status = resp.status();
+    let text = resp.text().await.map_err(|e| e.to_string())?;
+    if status.is_success() {
+        return Ok(text);
+    }
+    match serde_json::from_str::<ErrorResponse>(&text) {
+        Ok(err) => Err(err.error_message.unwrap_or(err.error)),
+        Err(_) => Err(format!("Authentication server returned {}", status)),
+    }
+}
+
+fn parse_auth_response(text: &str) -> Result<AuthResponse, String> {
+    serde_json::from_str(text).map_err(|e| format!("Invalid authentication response: {}", e))
+}
+
+fn to_account(
+    api_root: &str,
+    server_name: Option<String>,
+    profile: Profile,
+    resp: AuthResponse,
+) -> YggdrasilAccount {
+    YggdrasilAccount {
+        username: profile.name,
+        uuid: profile.id,
+        access_token: resp.access_token,
+        client_token: resp.client_token,
+        server_url: api_root.to_string(),
+        server_name,
+    }
+}
+
+/// Sign in with the account's email (or username) and password. When the
+/// server leaves the profile to the client, the first one is bound to the
+/// token.
+pub async fn authenticate(
+    api_root: &str,
+    username: &str,
+    password: &str,
+) -> Result<YggdrasilAccount, String> {
+    let text = post(
+        api_root,
+        "/authserver/authenticate",
+        json!({
+            "agent": { "name": "Minecraft", "version": 1 },
+            "username": username,
+            "password": password,
+            "clientToken": uuid::Uuid::new_v4().simple().to_string(),
+            "requestUser": false,
+        }),
+    )
+    .await?;
+    let resp = parse_auth_response(&text)?;
+    let server_name = fetch_server_name(api_root).await;
+
+    if let Some(profile) = resp.selected_profile.clone() {
+        return Ok(to_account(api_root, server_name, profile, resp));
+    }
+    let profile = resp
+        .available_profiles
+        .first()
+        .cloned()
+        .ok_or("This account has no Minecraft profile on the server")?;
+    let text = post(
+        api_root,
+        "/authserver/refresh",
+        json!({
+            "accessToken": resp.access_token,
+            "clientToken": resp.client_token,
+            "selectedProfile": { "id": profile.id, "name": profile.name },
+        }),
+    )
+    .await?;
+    let resp = parse_auth_response(&text)?;
+    let profile = resp.selected_profile.clone().unwrap_or(profile);
+    Ok(to_account(api_root, server_name, profile, resp))
+}
+
+/// Whether the account's access token is still accepted
+pub async fn validate(account: &YggdrasilAccount) -> Result<bool, String> {
+    let resp = get_client()
+        .post(format!("{}/authserver/validate", account.server_url))
+        .json(&json!({
+            "accessToken": account.access_token,
+            "clientToken": account.client_token,
+        }))
+        .send()
+        .await
+        .map_err(|e| format!("Failed to reach authentication server: {}", e))?;
+    Ok(resp.status().is_success())
+}
+
+/// Exchange the account's access token for a new one
+pub async fn refresh(account: &YggdrasilAccount) -> Result<YggdrasilAccount, String> {
+    let text = post(
+        &account.server_url,
+        "/authserver/refresh",
+        json!({
+            "accessToken": account.access_token,
+            "clientToken": account.client_token,
+        }),
+    )
+    .await?;
+    let resp = parse_auth_response(&text)?;
+    let profile = resp.selected_profile.clone().unwrap_or(Profile {
+        id: account.uuid.clone(),
+        name: account.username.clone(),
+    });
+    Ok(to_account(
+        &account.server_url,
+        account.server_name.clone(),
+        profile,
+        resp,
+    ))
+}
+
+/// Revoke the account's access token (on sign-out)
+pub async fn invalidate(account: &YggdrasilAccount) -> Result<(), String> {
+    post(
+        &account.server_url,
+        "/authserver/invalidate",
+        json!({
+            "accessToken": account.access_token,
+            "clientToken": account.client_token,
+        }),
+    )
+    .await
+    .map(|_| ())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_api_root_urls() {
+        assert_eq!(
+            normalize_url("littleskin.cn/api/yggdrasil/"),
+            "https://littleskin.cn/api/yggdrasil"
+        );
+        assert_eq!(
+            normalize_url("http://localhost:8080"),
+            "http://localhost:8080"
+        );
+        assert_eq!(
+            resolve_location("https://littleskin.cn", "/api/yggdrasil/").unwrap(),
+            "https://littleskin.cn/api/yggdrasil"
+        );
+        assert_eq!(
+            resolve_location("https://skin.example.com", "https://auth.example.com/api").unwrap(),
+            "https://auth.example.com/api"
+        );
+    }
+}
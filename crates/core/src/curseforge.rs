@@ -0,0 +1,256 @@
+//! Browsing and installing mods from CurseForge.
+//!
+//! Search and file listings go through the same API key and helpers as
+//! CurseForge packs in [`crate::modpack`]. Files are filtered by Minecraft
+//! version and loader like the Modrinth lookups of [`crate::mod_list`], and
+//! [`to_download`] turns a file into a [`ModpackFile`] under `mods/` (or
+//! `resourcepacks/` / `shaderpacks/`), falling back to the forgecdn URL for
+//! mods that opted out of third-party downloads.
+
+use crate::mod_list::{CURSEFORGE_MINECRAFT, curseforge_loader_type};
+use crate::modpack::{ModpackFile, cf_download_url, cf_get};
+use serde::{Deserialize, Serialize};
+use ts_rs::TS;
+
+const CLASS_RESOURCE_PACKS: u64 = 12;
+const CLASS_SHADERS: u64 = 6552;
+const PAGE_SIZE: u32 = 20;
+
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export, export_to = "dropout-core/curseforge.ts")]
+pub struct CurseForgeProject {
+    pub id: u64,
+    pub slug: String,
+    pub name: String,
+    pub summary: String,
+    pub authors: Vec<String>,
+    pub download_count: u64,
+    pub icon_url: Option<String>,
+    pub class_id: Option<u64>,
+    pub website_url: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export, export_to = "dropout-core/curseforge.ts")]
+pub struct CurseForgeFile {
+    pub id: u64,
+    pub mod_id: u64,
+    pub display_name: String,
+    pub file_name: String,
+    pub file_date: String,
+    pub file_length: u64,
+    pub release_type: String, // "release" | "beta" | "alpha"
+    pub game_versions: Vec<String>,
+    pub download_url: Option<String>, // None when only the CDN fallback works
+    pub sha1: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export, export_to = "dropout-core/curseforge.ts")]
+pub struct CurseForgeSearch {
+    pub projects: Vec<CurseForgeProject>,
+    pub total: u64,
+    pub page: u32,
+}
+
+fn parse_project(value: &serde_json::Value) -> Option<CurseForgeProject> {
+    Some(CurseForgeProject {
+        id: value["id"].as_u64()?,
+        slug: value["slug"].as_str()?.to_string(),
+        name: value["name"].as_str()?.to_string(),
+        summary: value["summary"].as_str().unwrap_or_default().to_string(),
+        authors: value["authors"]
+            .as_array()
+            .map(|authors| {
+                authors
+                    .iter()
+                    .filter_map(|a| a["name"].as_str().map(String::from))
+                    .collect()
+            })
+            .unwrap_or_default(),
+        download_count: value["downloadCount"].as_f64().unwrap_or_default() as u64,
+        icon_url: value["logo"]["thumbnailUrl"].as_str().map(String::from),
+        class_id: value["classId"].as_u64(),
+        website_url: value["links"]["websiteUrl"].as_str().map(String::from),
+    })
+}
+
+fn parse_file(value: &serde_json::Value) -> Option<CurseForgeFile> {
+    let release_type = match value["releaseType"].as_u64() {
+        Some(2) => "beta",
+        Some(3) => "alpha",
+        _ => "release",
+    };
+    Some(CurseForgeFile {
+        id: value["id"].as_u64()?,
+        mod_id: value["modId"].as_u64()?,
+        display_name: value["displayName"]
+            .as_str()
+            .unwrap_or_default()
+            .to_string(),
+        file_name: value["fileName"].as_str()?.to_string(),
+        file_date: value["fileDate"].as_str().unwrap_or_default().to_string(),
+        file_length: value["fileLength"].as_u64().unwrap_or_default(),
+        release_type: release_type.to_string(),
+        game_versions: value["gameVersions"]
+            .as_array()
+            .map(|versions| {
+                versions
+                    .iter()
+                    .filter_map(|v| v.as_str().map(String::from))
+                    .collect()
+            })
+            .unwrap_or_default(),
+        download_url: value["downloadUrl"].as_str().map(String::from),
+        sha1: value["hashes"].as_array().and_then(|hashes| {
+            hashes
+                .iter()
+                .find(|h| h["algo"].as_u64() == Some(1))
+                .and_then(|h| h["value"].as_str())
+                .map(String::from)
+        }),
+    })
+}
+
+/// Search CurseForge for Minecraft projects of `class_id` (mods when unset),
+/// most popular first. `game_version` and `loader` narrow the results to
+/// projects with matching files.
+pub async fn search(
+    query: &str,
+    class_id: Option<u64>,
+    game_version: Option<&str>,
+    loader: Option<&str>,
+    page: u32,
+) -> Result<CurseForgeSearch, String> {
+    let client = reqwest::Client::new();
+    let mut params = vec![
+        ("gameId", CURSEFORGE_MINECRAFT.to_string()),
+        (
+            "classId",
+            class_id
+                .unwrap_or(crate::mod_list::CURSEFORGE_CLASS_MODS as u64)
+                .to_string(),
+        ),
+        ("searchFilter", query.trim().to_string()),
+        ("sortField", "2".to_string()), // popularity
+        ("sortOrder", "desc".to_string()),
+        ("index", (page * PAGE_SIZE).to_string()),
+        ("pageSize", PAGE_SIZE.to_string()),
+    ];
+    if let Some(version) = game_version {
+        params.push(("gameVersion", version.to_string()));
+    }
+    if let Some(loader_type) = loader.and_then(curseforge_loader_type) {
+        params.push(("modLoaderType", loader_type.to_string()));
+    }
+    let body = cf_get(&client, "/v1/mods/search", &params).await?;
+    Ok(CurseForgeSearch {
+        projects: body["data"]
+            .as_array()
+            .map(|projects| projects.iter().filter_map(parse_project).collect())
+            .unwrap_or_default(),
+        total: body["pagination"]["totalCount"]
+            .as_u64()
+            .unwrap_or_default(),
+        page,
+    })
+}
+
+/// A project by its ID
+pub async fn project(mod_id: u64) -> Result<CurseForgeProject, String> {
+    let client = reqwest::Client::new();
+    let body = cf_get(&client, &format!("/v1/mods/{mod_id}"), &[]).await?;
+    parse_project(&body["data"]).ok_or_else(|| format!("Project {mod_id} not found on CurseForge"))
+}
+
+/// Files of a project for `game_version` and `loader`, newest first
+pub async fn files(
+    mod_id: u64,
+    game_version: Option<&str>,
+    loader: Option<&str>,
+) -> Result<Vec<CurseForgeFile>, String> {
+    let client = reqwest::Client::new();
+    let mut query = vec![("pageSize", "50".to_string())];
+    if let Some(version) = game_version {
+        query.push(("gameVersion", version.to_string()));
+    }
+    if let Some(loader_type) = loader.and_then(curseforge_loader_type) {
+        query.push(("modLoaderType", loader_type.to_string()));
+    }
+    let body = cf_get(&client, &format!("/v1/mods/{mod_id}/files"), &query).await?;
+    let mut files: Vec<CurseForgeFile> = body["data"]
+        .as_array()
+        .map(|files| files.iter().filter_map(parse_file).collect())
+        .unwrap_or_default();
+    // `fileDate` is ISO 8601, so it sorts chronologically
+    files.sort_by(|a, b| b.file_date.cmp(&a.file_date));
+    Ok(files)
+}
+
+/// A file by its project and file ID
+pub async fn file(mod_id: u64, file_id: u64) -> Result<CurseForgeFile, String> {
+    let client = reqwest::Client::new();
+    let body = cf_get(&client, &format!("/v1/mods/{mod_id}/files/{file_id}"), &[]).await?;
+    parse_file(&body["data"]).ok_or_else(|| format!("File {file_id} not found on CurseForge"))
+}
+
+/// Where a file of a project of `class_id` goes in the instance, with its
+/// download URL
+pub fn to_download(file: &CurseForgeFile, class_id: Option<u64>) -> ModpackFile {
+    let dir = match class_id {
+        Some(CLASS_RESOURCE_PACKS) => "resourcepacks",
+        Some(CLASS_SHADERS) => "shaderpacks",
+        _ => "mods",
+    };
+    let url = cf_download_url(&serde_json::json!({
+        "id": file.id,
+        "fileName": file.file_name,
+        "downloadUrl": file.download_url,
+    }))
+    .unwrap_or_default();
+    ModpackFile {
+        url,
+        path: format!("{dir}/{}", file.file_name),
+        size: Some(file.file_length),
+        sha1: file.sha1.clone(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_file_and_cdn_fallback() {
+        let value = serde_json::json!({
+            "id": 4712345,
+            "modId": 238222,
+            "displayName": "jei-1.20.1-forge-15.2.0.27",
+            "fileName": "jei-1.20.1-forge-15.2.0.27.jar",
+            "fileDate": "2023-09-01T12:00:00Z",
+            "fileLength": 1234567,
+            "releaseType": 2,
+            "gameVersions": ["1.20.1", "Forge"],
+            "downloadUrl": null,
+            "hashes": [{ "value": "abc", "algo": 1 }, { "value": "def", "algo": 2 }],
+        });
+        let file = parse_file(&value).unwrap();
+        assert_eq!(file.release_type, "beta");
+        assert_eq!(file.sha1.as_deref(), Some("abc"));
+        assert!(file.download_url.is_none());
+
+        let download = to_download(&file, Some(6));
+        assert_eq!(download.path, "mods/jei-1.20.1-forge-15.2.0.27.jar");
+        assert_eq!(
+            download.url,
+            "https://edge.forgecdn.net/files/4712/345/jei-1.20.1-forge-15.2.0.27.jar"
+        );
+        assert_eq!(
+            to_download(&file, Some(CLASS_SHADERS)).path,
+            "shaderpacks/jei-1.20.1-forge-15.2.0.27.jar"
+        );
+    }
+}
@@ -0,0 +1,213 @@
+//! Import of an official-launcher `.minecraft` directory as an instance.
+//!
+//! Its layout (`versions/`, `libraries/`, `assets/`, `mods/`, `saves/`) is the
+//! same as an instance directory, so it can be used in place or copied. The
+//! installed versions and their mod loaders are read from the version JSONs.
+
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+use ts_rs::TS;
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export, export_to = "dropout-core/dot_minecraft.ts")]
+pub struct DetectedVersion {
+    pub id: String,
+    pub minecraft_version: String, // `inheritsFrom`, or the id for vanilla
+    pub mod_loader: String,        // "vanilla" | "fabric" | "quilt" | "forge" | "neoforge"
+    pub mod_loader_version: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export, export_to = "dropout-core/dot_minecraft.ts")]
+pub struct DotMinecraftImport {
+    pub instance: crate::instance::Instance,
+    pub versions: Vec<DetectedVersion>,
+}
+
+/// Where the official launcher keeps the game by default
+pub fn default_dir() -> Option<PathBuf> {
+    if cfg!(target_os = "windows") {
+        dirs::config_dir().map(|d| d.join(".minecraft"))
+    } else if cfg!(target_os = "macos") {
+        dirs::config_dir().map(|d| d.join("minecraft"))
+    } else {
+        dirs::home_dir().map(|d| d.join(".minecraft"))
+    }
+}
+
+pub fn is_dot_minecraft(path: &Path) -> bool {
+    path.join("versions").is_dir()
+}
+
+/// Loader and its version from the coordinates of a version JSON's libraries
+fn loader_from_libraries(json: &serde_json::Value) -> Option<(&'static str, String)> {
+    const LOADERS: &[(&str, &str)] = &[
+        ("net.fabricmc:fabric-loader:", "fabric"),
+        ("org.quiltmc:quilt-loader:", "quilt"),
+        ("net.neoforged:neoforge:", "neoforge"),
+        ("net.neoforged:forge:", "neoforge"), // 1.20.1 NeoForge
+        ("net.minecraftforge:forge:", "forge"),
+        ("net.minecraftforge:fmlloader:", "forge"),
+    ];
+    let libraries = json.get("libraries")?.as_array()?;
+    LOADERS.iter().find_map(|(prefix, loader)| {
+        libraries.iter().find_map(|lib| {
+            let version = lib.get("name")?.as_str()?.strip_prefix(prefix)?;
+            let version = version.split(':').next().unwrap_or(version);
+            // Forge coordinates carry the game version: "1.20.1-47.2.0"
+            let version = match *loader {
+                "forge" | "neoforge" => version.split_once('-').map_or(version, |(_, v)| v),
+                _ => version,
+            };
+            Some((*loader, version.to_string()))
+        })
+    })
+}
+
+/// Describe the version `id` from its JSON
+pub fn detect_version(id: &str, json: &serde_json::Value) -> DetectedVersion {
+    let minecraft_version = json
+        .get("inheritsFrom")
+        .and_then(|v| v.as_str())
+        .unwrap_or(id)
+        .to_string();
+    let main_class = json
+        .get("mainClass")
+        .and_then(|m| m.as_str())
+        .unwrap_or_default();
+    let (mod_loader, mod_loader_version) = match loader_from_libraries(json) {
+        Some((loader, version)) => (loader.to_string(), Some(version)),
+        // Forge 1.12 and older ship without a loader library in the JSON
+        None if main_class == "net.minecraft.launchwrapper.Launch"
+            && id.to_lowercase().contains("forge") =>
+        {
+            ("forge".to_string(), None)
+        }
+        None => ("vanilla".to_string(), None),
+    };
+    DetectedVersion {
+        id: id.to_string(),
+        minecraft_version,
+        mod_loader,
+        mod_loader_version,
+    }
+}
+
+/// Versions installed in `dot_minecraft`, modded first
+pub fn scan_versions(dot_minecraft: &Path) -> Result<Vec<DetectedVersion>, String> {
+    let versions_dir = dot_minecraft.join("versions");
+    let mut versions: Vec<DetectedVersion> = std::fs::read_dir(&versions_dir)
+        .map_err(|e| format!("Failed to read {}: {}", versions_dir.display(), e))?
+        .filter_map(|e| e.ok())
+        .filter_map(|e| {
+            let id = e.file_name().to_string_lossy().to_string();
+            let content = std::fs::read_to_string(e.path().join(format!("{}.json", id))).ok()?;
+            let json = serde_json::from_str(&content).ok()?;
+            Some(detect_version(&id, &json))
+        })
+        .collect();
+    versions.sort_by(|a, b| {
+        (a.mod_loader == "vanilla")
+            .cmp(&(b.mod_loader == "vanilla"))
+            .then_with(|| b.id.cmp(&a.id))
+    });
+    Ok(versions)
+}
+
+/// `lastVersionId` of the most recently used profile in `launcher_profiles.json`
+pub fn last_used_version(launcher_profiles: &str) -> Option<String> {
+    let json: serde_json::Value = serde_json::from_str(launcher_profiles).ok()?;
+    json.get("profiles")?
+        .as_object()?
+        .values()
+        .filter_map(|p| {
+            let version = p.get("lastVersionId")?.as_str()?;
+            let last_used = p.get("lastUsed").and_then(|l| l.as_str()).unwrap_or("");
+            Some((last_used, version))
+        })
+        // ISO 8601 timestamps sort chronologically
+        .max_by_key(|(last_used, _)| *last_used)
+        .map(|(_, version)| version.to_string())
+}
+
+/// Version to select for the new instance: the one last played in the
+/// official launcher if it's installed, else the first detected
+pub fn preferred_version<'a>(
+    dot_minecraft: &Path,
+    versions: &'a [DetectedVersion],
+) -> Option<&'a DetectedVersion> {
+    std::fs::read_to_string(dot_minecraft.join("launcher_profiles.json"))
+        .ok()
+        .and_then(|content| last_used_version(&content))
+        .and_then(|last| versions.iter().find(|v| v.id == last))
+        .or_else(|| versions.first())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_detect_version() {
+        let fabric = json!({
+            "inheritsFrom": "1.20.1",
+            "mainClass": "net.fabricmc.loader.impl.launch.knot.KnotClient",
+            "libraries": [
+                { "name": "org.ow2.asm:asm:9.6" },
+                { "name": "net.fabricmc:fabric-loader:0.15.7" }
+            ]
+        });
+        let detected = detect_version("fabric-loader-0.15.7-1.20.1", &fabric);
+        assert_eq!(detected.minecraft_version, "1.20.1");
+        assert_eq!(detected.mod_loader, "fabric");
+        assert_eq!(detected.mod_loader_version.as_deref(), Some("0.15.7"));
+
+        let forge = json!({
+            "inheritsFrom": "1.20.1",
+            "libraries": [{ "name": "net.minecraftforge:forge:1.20.1-47.2.0:universal" }]
+        });
+        let detected = detect_version("1.20.1-forge-47.2.0", &forge);
+        assert_eq!(detected.mod_loader, "forge");
+        assert_eq!(detected.mod_loader_version.as_deref(), Some("47.2.0"));
+
+        let neoforge = json!({
+            "inheritsFrom": "1.21.1",
+            "libraries": [{ "name": "net.neoforged:neoforge:21.1.77:universal" }]
+        });
+        let detected = detect_version("neoforge-21.1.77", &neoforge);
+        assert_eq!(detected.mod_loader, "neoforge");
+        assert_eq!(detected.mod_loader_version.as_deref(), Some("21.1.77"));
+
+        let old_forge = json!({
+            "inheritsFrom": "1.7.10",
+            "mainClass": "net.minecraft.launchwrapper.Launch"
+        });
+        assert_eq!(
+            detect_version("1.7.10-Forge10.13.4.1614", &old_forge).mod_loader,
+            "forge"
+        );
+
+        let vanilla = json!({ "mainClass": "net.minecraft.client.main.Main" });
+        let detected = detect_version("1.20.4", &vanilla);
+        assert_eq!(detected.minecraft_version, "1.20.4");
+        assert_eq!(detected.mod_loader, "vanilla");
+        assert_eq!(detected.mod_loader_version, None);
+    }
+
+    #[test]
+    fn test_last_used_version() {
+        let profiles = r#"{"profiles": {
+            "a": { "lastVersionId": "1.20.4", "lastUsed": "2024-01-02T10:00:00.000Z" },
+            "b": { "lastVersionId": "fabric-loader-0.15.7-1.20.1", "lastUsed": "2024-03-01T08:00:00.000Z" },
+            "c": { "lastVersionId": "latest-release" }
+        }}"#;
+        assert_eq!(
+            last_used_version(profiles).as_deref(),
+            Some("fabric-loader-0.15.7-1.20.1")
+        );
+        assert_eq!(last_used_version("{}"), None);
+    }
+}
@@ -0,0 +1,204 @@
+//! Control of running download sessions.
+//!
+//! Every [`download_planned`](crate::downloader::download_planned) call
+//! (installing a version, preparing a launch, fetching loader libraries, ...)
+//! given a [`DownloadManager`] registers a session there for as long as it
+//! runs. The desktop app lists them with `get_active_downloads` and can
+//! pause, resume or cancel each one. Tasks
+//! check their session before starting and between chunks: a paused session
+//! stops reading (and starts nothing new) until resumed, and a cancelled one
+//! fails its remaining tasks so the command that started it returns an error.
+//! Sessions started while a game is running may be low priority, with their
+//! own speed cap on top of the global one.
+
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use std::time::Instant;
+use tokio::sync::watch;
+use ts_rs::TS;
+
+use crate::downloader::{GlobalProgress, TokenBucket};
+
+pub const CANCELLED: &str = "Download cancelled";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, TS)]
+#[serde(rename_all = "lowercase")]
+#[ts(export, export_to = "dropout-core/download_manager.ts")]
+pub enum SessionState {
+    Running,
+    Paused,
+    Cancelled,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export, export_to = "dropout-core/download_manager.ts")]
+pub struct DownloadSessionInfo {
+    pub id: String,
+    pub window: String, // label of the window (or other frontend) the progress events go to
+    pub state: SessionState,
+    pub completed_files: usize,
+    pub total_files: usize, // planned so far
+    pub downloaded_bytes: u64,
+    pub started_at: i64,
+    pub low_priority: bool, // started while a game was running
+}
+
+pub struct DownloadSession {
+    pub id: String,
+    window: String,
+    started_at: i64,
+    state: watch::Sender<SessionState>,
+    pub(crate) progress: Arc<GlobalProgress>,
+    low_priority: bool,
+    bandwidth: Option<Mutex<TokenBucket>>, // the low-priority speed cap
+}
+
+impl DownloadSession {
+    /// Wait while the session is paused; `Err` once it is cancelled
+    pub async fn checkpoint(&self) -> Result<(), String> {
+        let mut state = self.state.subscribe();
+        loop {
+            match *state.borrow_and_update() {
+                SessionState::Running => return Ok(()),
+                SessionState::Cancelled => return Err(CANCELLED.to_string()),
+                SessionState::Paused => {}
+            }
+            if state.changed().await.is_err() {
+                return Err(CANCELLED.to_string());
+            }
+        }
+    }
+
+    pub fn is_cancelled(&self) -> bool {
+        *self.state.borrow() == SessionState::Cancelled
+    }
+
+    /// Wait until `bytes` more may be received under the session's own cap
+    pub async fn throttle(&self, bytes: u64) {
+        let Some(bucket) = &self.bandwidth else {
+            return;
+        };
+        let wait = bucket.lock().unwrap().take(bytes, Instant::now());
+        if !wait.is_zero() {
+            tokio::time::sleep(wait).await;
+        }
+    }
+
+    fn info(&self) -> DownloadSessionInfo {
+        let snapshot = self.progress.snapshot();
+        DownloadSessionInfo {
+            id: self.id.clone(),
+            window: self.window.clone(),
+            state: *self.state.borrow(),
+            completed_files: snapshot.completed_files,
+            total_files: snapshot.total_files,
+            downloaded_bytes: snapshot.total_downloaded_bytes,
+            started_at: self.started_at,
+            low_priority: self.low_priority,
+        }
+    }
+
+    /// Move to `to` unless the session is already cancelled
+    fn transition(&self, to: SessionState) -> Result<(), String> {
+        let mut result = Ok(());
+        self.state.send_if_modified(|state| {
+            if *state == SessionState::Cancelled {
+                result = Err(format!("Download session {} was cancelled", self.id));
+                false
+            } else {
+                let changed = *state != to;
+                *state = to;
+                changed
+            }
+        });
+        result
+    }
+}
+
+/// Sessions of all running downloads (Tauri state in the desktop app)
+#[derive(Default)]
+pub struct DownloadManager {
+    sessions: Mutex<HashMap<String, Arc<DownloadSession>>>,
+}
+
+impl DownloadManager {
+    fn get(&self, id: &str) -> Result<Arc<DownloadSession>, String> {
+        self.sessions
+            .lock()
+            .unwrap()
+            .get(id)
+            .cloned()
+            .ok_or_else(|| format!("No active download session {}", id))
+    }
+
+    pub fn active(&self) -> Vec<DownloadSessionInfo> {
+        let mut sessions: Vec<_> = self
+            .sessions
+            .lock()
+            .unwrap()
+            .values()
+            .map(|s| s.info())
+            .collect();
+        sessions.sort_by_key(|s| s.started_at);
+        sessions
+    }
+
+    pub fn pause(&self, id: &str) -> Result<(), String> {
+        self.get(id)?.transition(SessionState::Paused)
+    }
+
+    pub fn resume(&self, id: &str) -> Result<(), String> {
+        self.get(id)?.transition(SessionState::Running)
+    }
+
+    pub fn cancel(&self, id: &str) -> Result<(), String> {
+        self.get(id)?.state.send_replace(SessionState::Cancelled);
+        Ok(())
+    }
+}
+
+/// A registered session; unregisters it when dropped
+pub struct SessionGuard<'a> {
+    manager: Option<&'a DownloadManager>,
+    pub session: Arc<DownloadSession>,
+}
+
+impl Drop for SessionGuard<'_> {
+    fn drop(&mut self) {
+        if let Some(manager) = self.manager {
+            manager.sessions.lock().unwrap().remove(&self.session.id);
+        }
+    }
+}
+
+/// Start a session for downloads reporting to `window`, registered with
+/// `manager` if given. `low_priority` is the session's speed cap in bytes
+/// per second (0 = none).
+pub(crate) fn start_session<'a>(
+    manager: Option<&'a DownloadManager>,
+    window: &str,
+    progress: Arc<GlobalProgress>,
+    low_priority: Option<u64>,
+) -> SessionGuard<'a> {
+    let session = Arc::new(DownloadSession {
+        id: uuid::Uuid::new_v4().to_string(),
+        window: window.to_string(),
+        started_at: chrono::Utc::now().timestamp_millis(),
+        state: watch::Sender::new(SessionState::Running),
+        progress,
+        low_priority: low_priority.is_some(),
+        bandwidth: low_priority
+            .filter(|speed| *speed > 0)
+            .map(|speed| Mutex::new(TokenBucket::new(speed, Instant::now()))),
+    });
+    if let Some(manager) = manager {
+        manager
+            .sessions
+            .lock()
+            .unwrap()
+            .insert(session.id.clone(), session.clone());
+    }
+    SessionGuard { manager, session }
+}
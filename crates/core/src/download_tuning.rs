@@ -0,0 +1,250 @@
+//! Adaptive download concurrency.
+//!
+//! In auto mode [`download_files`](crate::downloader::download_files) starts with a conservative number of
+//! parallel downloads and periodically adjusts it from the observed
+//! throughput and error rate (additive increase, multiplicative decrease).
+//! The concurrency that gave the best throughput is remembered per network
+//! in a JSON file (`download_tuning.json` in the desktop app's data
+//! directory) and used as the starting point next time.
+
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::path::Path;
+use std::sync::Arc;
+use std::sync::Mutex;
+use std::sync::atomic::{AtomicU64, AtomicUsize, Ordering};
+use tokio::sync::{Semaphore, SemaphorePermit};
+
+pub const MIN_CONCURRENCY: usize = 2;
+pub const MAX_CONCURRENCY: usize = 128;
+/// Starting point when nothing has been learned for the current network
+pub const INITIAL_CONCURRENCY: usize = 8;
+/// Interval between adjustments
+pub const TUNING_INTERVAL: std::time::Duration = std::time::Duration::from_secs(2);
+
+/// Learned concurrency per network
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct TuningStore {
+    pub networks: HashMap<String, usize>,
+}
+
+impl TuningStore {
+    pub fn load(path: &Path) -> Self {
+        std::fs::read_to_string(path)
+            .ok()
+            .and_then(|content| serde_json::from_str(&content).ok())
+            .unwrap_or_default()
+    }
+
+    pub fn save(&self, path: &Path) -> Result<(), String> {
+        let content = serde_json::to_string_pretty(self).map_err(|e| e.to_string())?;
+        std::fs::write(path, content).map_err(|e| e.to_string())?;
+        Ok(())
+    }
+}
+
+/// Identify the current network by the subnet of the outbound interface.
+///
+/// Connecting a UDP socket sends no packets; it only selects the route, so
+/// this works offline and without elevated permissions.
+pub fn current_network_key() -> String {
+    let local = std::net::UdpSocket::bind("0.0.0.0:0")
+        .and_then(|socket| {
+            socket.connect("1.1.1.1:80")?;
+            socket.local_addr()
+        })
+        .map(|addr| addr.ip());
+
+    match local {
+        Ok(std::net::IpAddr::V4(ip)) => {
+            let o = ip.octets();
+            format!("{}.{}.{}.0/24", o[0], o[1], o[2])
+        }
+        Ok(std::net::IpAddr::V6(ip)) => {
+            let s = ip.segments();
+            format!("{:x}:{:x}:{:x}:{:x}::/64", s[0], s[1], s[2], s[3])
+        }
+        Err(_) => "default".to_string(),
+    }
+}
+
+/// Compute the next concurrency level from one measurement window
+pub fn next_concurrency(
+    current: usize,
+    throughput: f64,
+    last_throughput: f64,
+    error_rate: f64,
+) -> usize {
+    let next = if error_rate > 0.1 {
+        current / 2
+    } else if last_throughput <= 0.0 || throughput >= last_throughput * 1.05 {
+        current + (current / 4).max(1)
+    } else if throughput < last_throughput * 0.8 {
+        current - (current / 8).max(1)
+    } else {
+        current
+    };
+    next.clamp(MIN_CONCURRENCY, MAX_CONCURRENCY)
+}
+
+/// Runtime state of one auto-tuned [`download_files`](crate::downloader::download_files) run
+pub struct AdaptiveConcurrency {
+    semaphore: Semaphore,
+    limit: AtomicUsize,
+    /// Permits still to be retired once in-flight downloads release them
+    pending_shrink: AtomicUsize,
+    bytes: AtomicU64,
+    finished: AtomicUsize,
+    errors: AtomicUsize,
+    best: Mutex<(usize, f64)>,
+}
+
+impl AdaptiveConcurrency {
+    pub fn new(initial: usize) -> Arc<Self> {
+        let initial = initial.clamp(1, MAX_CONCURRENCY);
+        Arc::new(Self {
+            semaphore: Semaphore::new(initial),
+            limit: AtomicUsize::new(initial),
+            pending_shrink: AtomicUsize::new(0),
+            bytes: AtomicU64::new(0),
+            finished: AtomicUsize::new(0),
+            errors: AtomicUsize::new(0),
+            best: Mutex::new((initial, 0.0)),
+        })
+    }
+
+    pub async fn acquire(&self) -> SemaphorePermit<'_> {
+        self.semaphore.acquire().await.unwrap()
+    }
+
+    /// Return a permit, retiring it instead if the limit was lowered
+    pub fn release(&self, permit: SemaphorePermit<'_>) {
+        let retired = self
+            .pending_shrink
+            .fetch_update(Ordering::AcqRel, Ordering::Acquire, |n| n.checked_sub(1))
+            .is_ok();
+        if retired {
+            permit.forget();
+        }
+    }
+
+    pub fn record_bytes(&self, bytes: u64) {
+        self.bytes.fetch_add(bytes, Ordering::Relaxed);
+    }
+
+    pub fn record_result(&self, ok: bool) {
+        if ok {
+            self.finished.fetch_add(1, Ordering::Relaxed);
+        } else {
+            self.errors.fetch_add(1, Ordering::Relaxed);
+        }
+    }
+
+    pub fn limit(&self) -> usize {
+        self.limit.load(Ordering::Acquire)
+    }
+
+    fn set_limit(&self, next: usize) {
+        let current = self.limit.swap(next, Ordering::AcqRel);
+        if next > current {
+            let mut grow = next - current;
+            // Cancel outstanding shrinks before adding new permits
+            while grow > 0
+                && self
+                    .pending_shrink
+                    .fetch_update(Ordering::AcqRel, Ordering::Acquire, |n| n.checked_sub(1))
+                    .is_ok()
+            {
+                grow -= 1;
+            }
+            self.semaphore.add_permits(grow);
+        } else if next < current {
+            let shrink = current - next;
+            let forgotten = self.semaphore.forget_permits(shrink);
+            self.pending_shrink
+                .fetch_add(shrink - forgotten, Ordering::AcqRel);
+        }
+    }
+
+    /// Adjust the limit periodically until the returned handle is aborted
+    pub fn spawn_tuner(self: &Arc<Self>) -> tokio::task::JoinHandle<()> {
+        let this = self.clone();
+        tokio::spawn(async move {
+            let mut last_bytes = 0u64;
+            let mut last_done = 0usize;
+            let mut last_errors = 0usize;
+            let mut last_throughput = 0.0f64;
+            loop {
+                tokio::time::sleep(TUNING_INTERVAL).await;
+
+                let bytes = this.bytes.load(Ordering::Relaxed);
+                let done = this.finished.load(Ordering::Relaxed);
+                let errors = this.errors.load(Ordering::Relaxed);
+                let window_done = done - last_done;
+                let window_errors = errors - last_errors;
+                let throughput = (bytes - last_bytes) as f64 / TUNING_INTERVAL.as_secs_f64();
+                last_bytes = bytes;
+                last_done = done;
+                last_errors = errors;
+
+                // Nothing finished or transferred: no signal to act on
+                if window_done + window_errors == 0 && throughput == 0.0 {
+                    continue;
+                }
+
+                let current = this.limit();
+                {
+                    let mut best = this.best.lock().unwrap();
+                    if throughput > best.1 {
+                        *best = (current, throughput);
+                    }
+                }
+
+                let error_rate = window_errors as f64 / (window_done + window_errors).max(1) as f64;
+                let next = next_concurrency(current, throughput, last_throughput, error_rate);
+                if next != current {
+                    println!(
+                        "[Downloader] Concurrency {} -> {} ({:.1} MB/s, {:.0}% errors)",
+                        current,
+                        next,
+                        throughput / 1_048_576.0,
+                        error_rate * 100.0
+                    );
+                    this.set_limit(next);
+                }
+                last_throughput = throughput;
+            }
+        })
+    }
+
+    /// Concurrency that achieved the best throughput, if any was measured
+    pub fn learned(&self) -> Option<usize> {
+        let best = self.best.lock().unwrap();
+        (best.1 > 0.0).then_some(best.0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_next_concurrency_grows_when_faster() {
+        assert_eq!(next_concurrency(8, 10.0, 0.0, 0.0), 10);
+        assert_eq!(next_concurrency(8, 12.0, 10.0, 0.0), 10);
+    }
+
+    #[test]
+    fn test_next_concurrency_holds_on_plateau() {
+        assert_eq!(next_concurrency(16, 10.0, 10.0, 0.0), 16);
+    }
+
+    #[test]
+    fn test_next_concurrency_backs_off() {
+        assert_eq!(next_concurrency(16, 5.0, 10.0, 0.0), 14);
+        assert_eq!(next_concurrency(16, 10.0, 10.0, 0.5), 8);
+        assert_eq!(next_concurrency(2, 10.0, 10.0, 0.5), MIN_CONCURRENCY);
+        assert_eq!(next_concurrency(128, 20.0, 10.0, 0.0), MAX_CONCURRENCY);
+    }
+}
@@ -0,0 +1,1098 @@
+//! Downloading files: the parallel downloader with retries, checksums,
+//! mirrors, speed caps and pause/cancel ([`download_files`],
+//! [`download_planned`]), and the primitives the other modules use for
+//! one-off requests.
+
+use futures::StreamExt;
+use serde::{Deserialize, Serialize};
+use sha1::Digest as Sha1Digest;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicU8, AtomicU64, AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+use tokio::io::AsyncWriteExt;
+use ts_rs::TS;
+
+use crate::download_manager::{self, DownloadManager, DownloadSession};
+use crate::download_tuning::{self, AdaptiveConcurrency};
+use crate::events::{EventSink, emit};
+use crate::long_path::long_path;
+
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export, export_to = "dropout-core/downloader.ts")]
+pub struct DownloadTask {
+    pub url: String,
+    pub path: PathBuf,
+    #[serde(default)]
+    pub sha1: Option<String>,
+    #[serde(default)]
+    pub sha256: Option<String>,
+}
+
+/// Where Mojang and mod loader files are downloaded from
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, TS)]
+#[serde(rename_all = "lowercase")]
+#[ts(export, export_to = "dropout-core/downloader.ts")]
+pub enum DownloadMirror {
+    #[default]
+    Official,
+    /// bmclapi2.bangbang93.com, fast from mainland China
+    Bmclapi,
+}
+
+const BMCLAPI: &str = "https://bmclapi2.bangbang93.com/";
+
+/// Official URL prefixes BMCLAPI mirrors, and the path they map to
+const BMCLAPI_PREFIXES: &[(&str, &str)] = &[
+    ("https://piston-meta.mojang.com/", ""),
+    ("https://launchermeta.mojang.com/", ""),
+    ("https://piston-data.mojang.com/", ""),
+    ("https://launcher.mojang.com/", ""),
+    ("https://libraries.minecraft.net/", "maven/"),
+    ("https://resources.download.minecraft.net/", "assets/"),
+    ("https://maven.minecraftforge.net/", "maven/"),
+    ("https://maven.fabricmc.net/", "maven/"),
+    ("https://maven.neoforged.net/releases/", "maven/"),
+];
+
+impl DownloadMirror {
+    /// `url` on this mirror; `None` for the official servers or hosts the
+    /// mirror doesn't carry
+    pub fn rewrite(self, url: &str) -> Option<String> {
+        match self {
+            Self::Official => None,
+            Self::Bmclapi => BMCLAPI_PREFIXES.iter().find_map(|(prefix, path)| {
+                let rest = url.strip_prefix(prefix)?;
+                Some(format!("{}{}{}", BMCLAPI, path, rest))
+            }),
+        }
+    }
+}
+
+static MIRROR: AtomicU8 = AtomicU8::new(0);
+
+/// Set the mirror used by all downloads (from the launcher config)
+pub fn set_mirror(mirror: DownloadMirror) {
+    MIRROR.store(mirror as u8, Ordering::Relaxed);
+}
+
+pub fn mirror() -> DownloadMirror {
+    match MIRROR.load(Ordering::Relaxed) {
+        1 => DownloadMirror::Bmclapi,
+        _ => DownloadMirror::Official,
+    }
+}
+
+/// GET `url` from the configured mirror, falling back to the official URL
+/// when the mirror fails or doesn't have the file
+pub async fn get_mirrored(
+    client: &reqwest::Client,
+    url: &str,
+) -> reqwest::Result<reqwest::Response> {
+    if let Some(mirrored) = mirror().rewrite(url) {
+        match client.get(&mirrored).send().await {
+            Ok(resp) if resp.status().is_success() => return Ok(resp),
+            Ok(resp) => println!(
+                "[Downloader] Mirror returned {} for {}, using the official URL",
+                resp.status(),
+                mirrored
+            ),
+            Err(e) => println!(
+                "[Downloader] Mirror request failed ({}), using the official URL",
+                e
+            ),
+        }
+    }
+    client.get(url).send().await
+}
+
+/// Token bucket for a byte rate, allowing bursts of up to one second's worth
+#[derive(Debug, Clone)]
+pub struct TokenBucket {
+    rate: f64, // bytes per second
+    tokens: f64,
+    last: Instant,
+}
+
+impl TokenBucket {
+    pub fn new(bytes_per_sec: u64, now: Instant) -> Self {
+        Self {
+            rate: bytes_per_sec as f64,
+            tokens: bytes_per_sec as f64,
+            last: now,
+        }
+    }
+
+    /// Take `bytes` and return how long to wait before using them. Tokens
+    /// may go negative, so callers waiting concurrently queue up fairly.
+    pub fn take(&mut self, bytes: u64, now: Instant) -> Duration {
+        let elapsed = now.saturating_duration_since(self.last).as_secs_f64();
+        self.last = now;
+        self.tokens = (self.tokens + elapsed * self.rate).min(self.rate) - bytes as f64;
+        if self.tokens >= 0.0 {
+            Duration::ZERO
+        } else {
+            Duration::from_secs_f64(-self.tokens / self.rate)
+        }
+    }
+}
+
+static BANDWIDTH: Mutex<Option<TokenBucket>> = Mutex::new(None);
+
+/// Cap the combined speed of all downloads; 0 removes the cap. Setting the
+/// current cap again keeps the bucket, so running downloads stay throttled.
+pub fn set_max_download_speed(bytes_per_sec: u64) {
+    let mut bandwidth = BANDWIDTH.lock().unwrap();
+    let current = bandwidth.as_ref().map(|b| b.rate as u64).unwrap_or(0);
+    if current != bytes_per_sec {
+        *bandwidth = (bytes_per_sec > 0).then(|| TokenBucket::new(bytes_per_sec, Instant::now()));
+    }
+}
+
+/// Wait until `bytes` more may be received under the speed cap
+pub async fn throttle(bytes: u64) {
+    let wait = match BANDWIDTH.lock().unwrap().as_mut() {
+        Some(bucket) => bucket.take(bytes, Instant::now()),
+        None => return,
+    };
+    if !wait.is_zero() {
+        tokio::time::sleep(wait).await;
+    }
+}
+
+/// Delay before retry `attempt` (1-based) of a failed download: 0.5s,
+/// doubling up to 8s
+pub fn retry_delay(attempt: u32) -> Duration {
+    let ms = 500u64 << attempt.saturating_sub(1).min(4);
+    Duration::from_millis(ms)
+}
+
+/// Whether an HTTP error status may go away on a retry (timeouts, rate
+/// limits, server errors), unlike a missing file
+pub fn is_retryable_status(status: u16) -> bool {
+    status == 408 || status == 429 || status >= 500
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export, export_to = "dropout-core/downloader.ts")]
+pub struct ProgressEvent {
+    pub file: String,
+    pub downloaded: u64,
+    pub total: u64,
+    pub status: String, // "Downloading", "Verifying", "Finished", "Error"
+    pub completed_files: usize,
+    pub total_files: usize,
+    pub total_downloaded_bytes: u64,
+}
+
+/// calculate SHA256 hash of data
+pub fn compute_sha256(data: &[u8]) -> String {
+    let mut hasher = sha2::Sha256::new();
+    hasher.update(data);
+    hex::encode(hasher.finalize())
+}
+
+/// calculate SHA1 hash of data
+pub fn compute_sha1(data: &[u8]) -> String {
+    let mut hasher = sha1::Sha1::new();
+    hasher.update(data);
+    hex::encode(hasher.finalize())
+}
+
+/// calculate SHA512 hash of data
+pub fn compute_sha512(data: &[u8]) -> String {
+    let mut hasher = sha2::Sha512::new();
+    hasher.update(data);
+    hex::encode(hasher.finalize())
+}
+
+/// verify file checksum, prefer SHA256, fallback to SHA1
+pub fn verify_checksum(data: &[u8], sha256: Option<&str>, sha1: Option<&str>) -> bool {
+    if let Some(expected) = sha256 {
+        return compute_sha256(data) == expected;
+    }
+    if let Some(expected) = sha1 {
+        return compute_sha1(data) == expected;
+    }
+    // No checksum provided, default to true
+    true
+}
+
+/// Write through a uniquely named temporary file, so readers never see a
+/// partial file and concurrent writers never share one
+pub fn write_atomic(path: &Path, data: &[u8]) -> Result<(), String> {
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent).map_err(|e| e.to_string())?;
+    }
+    let mut name = path.file_name().unwrap_or_default().to_os_string();
+    name.push(format!(".{}.part", uuid::Uuid::new_v4().simple()));
+    let tmp = path.with_file_name(name);
+    let written = std::fs::write(&tmp, data).and_then(|()| std::fs::rename(&tmp, path));
+    if written.is_err() {
+        let _ = std::fs::remove_file(&tmp);
+    }
+    written.map_err(|e| e.to_string())
+}
+
+/// Drop tasks writing to a path another task already writes to, so no two
+/// downloads race on one file. A library listed both by a loader profile and
+/// the version it inherits from is the usual case; the task with a checksum
+/// is kept, otherwise the first one. Order is preserved.
+pub fn dedup_tasks(tasks: Vec<DownloadTask>) -> Vec<DownloadTask> {
+    let mut index: std::collections::HashMap<PathBuf, usize> = std::collections::HashMap::new();
+    let mut unique: Vec<DownloadTask> = Vec::with_capacity(tasks.len());
+    for task in tasks {
+        match index.get(&task.path) {
+            Some(&i) => {
+                let kept = &unique[i];
+                if kept.sha1.is_none()
+                    && kept.sha256.is_none()
+                    && (task.sha1.is_some() || task.sha256.is_some())
+                {
+                    unique[i] = task;
+                }
+            }
+            None => {
+                index.insert(task.path.clone(), unique.len());
+                unique.push(task);
+            }
+        }
+    }
+    unique
+}
+
+/// A file that still failed after all retries; the payload of the
+/// `download-failed` event is the list of them
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export, export_to = "dropout-core/downloader.ts")]
+pub struct FailedDownload {
+    pub url: String,
+    pub path: String,
+    pub error: String,
+    pub attempts: u32,
+}
+
+/// Why a download attempt failed
+struct AttemptError {
+    message: String,
+    /// Network errors and 5xx/408/429 responses may succeed on a retry; a
+    /// 404 or a disk error won't
+    retryable: bool,
+    /// The file arrived but failed its checksum, so the mirror shouldn't be
+    /// trusted with the retry
+    corrupt: bool,
+}
+
+impl AttemptError {
+    fn transient(message: String) -> Self {
+        Self {
+            message,
+            retryable: true,
+            corrupt: false,
+        }
+    }
+
+    fn permanent(message: String) -> Self {
+        Self {
+            message,
+            retryable: false,
+            corrupt: false,
+        }
+    }
+
+    fn request(e: reqwest::Error) -> Self {
+        Self {
+            retryable: e.status().is_none_or(|s| is_retryable_status(s.as_u16())),
+            message: format!("Request error: {}", e),
+            corrupt: false,
+        }
+    }
+
+    fn corrupt(message: String) -> Self {
+        Self {
+            message,
+            retryable: true,
+            corrupt: true,
+        }
+    }
+}
+
+/// Snapshot of global progress state
+pub(crate) struct ProgressSnapshot {
+    pub(crate) completed_files: usize,
+    pub(crate) total_files: usize,
+    pub(crate) total_downloaded_bytes: u64,
+}
+
+/// Centralized progress tracking with atomic counters
+pub(crate) struct GlobalProgress {
+    completed_files: AtomicUsize,
+    total_downloaded_bytes: AtomicU64,
+    /// Tasks planned so far; grows while a [`TaskSender`] is still producing
+    planned_files: Arc<AtomicUsize>,
+}
+
+impl GlobalProgress {
+    fn new(planned_files: Arc<AtomicUsize>) -> Self {
+        Self {
+            completed_files: AtomicUsize::new(0),
+            total_downloaded_bytes: AtomicU64::new(0),
+            planned_files,
+        }
+    }
+
+    fn total_files(&self) -> usize {
+        self.planned_files.load(Ordering::Acquire)
+    }
+
+    /// Get current progress snapshot without modification
+    pub(crate) fn snapshot(&self) -> ProgressSnapshot {
+        ProgressSnapshot {
+            completed_files: self.completed_files.load(Ordering::Acquire),
+            total_files: self.total_files(),
+            total_downloaded_bytes: self.total_downloaded_bytes.load(Ordering::Acquire),
+        }
+    }
+
+    /// Increment completed files counter and return updated snapshot
+    fn inc_completed(&self) -> ProgressSnapshot {
+        let completed = self.completed_files.fetch_add(1, Ordering::Release) + 1;
+        ProgressSnapshot {
+            completed_files: completed,
+            total_files: self.total_files(),
+            total_downloaded_bytes: self.total_downloaded_bytes.load(Ordering::Acquire),
+        }
+    }
+
+    /// Take back bytes of an attempt that failed (they will be counted again
+    /// by the retry)
+    fn discard_bytes(&self, delta: u64) {
+        self.total_downloaded_bytes
+            .fetch_sub(delta, Ordering::AcqRel);
+    }
+
+    /// Add downloaded bytes and return updated snapshot
+    fn add_bytes(&self, delta: u64) -> ProgressSnapshot {
+        let total_bytes = self
+            .total_downloaded_bytes
+            .fetch_add(delta, Ordering::AcqRel)
+            + delta;
+        ProgressSnapshot {
+            completed_files: self.completed_files.load(Ordering::Acquire),
+            total_files: self.total_files(),
+            total_downloaded_bytes: total_bytes,
+        }
+    }
+}
+
+/// Emit a progress event to the frontend
+fn emit_progress(
+    events: &dyn EventSink,
+    file_name: &str,
+    status: &str,
+    downloaded: u64,
+    total: u64,
+    snapshot: &ProgressSnapshot,
+) {
+    emit(
+        events,
+        "download-progress",
+        ProgressEvent {
+            file: file_name.to_string(),
+            downloaded,
+            total,
+            status: status.into(),
+            completed_files: snapshot.completed_files,
+            total_files: snapshot.total_files,
+            total_downloaded_bytes: snapshot.total_downloaded_bytes,
+        },
+    );
+}
+
+/// How many files [`download_files`] fetches in parallel
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DownloadConcurrency {
+    /// A fixed number of parallel downloads (1-128)
+    Fixed(usize),
+    /// Adapt to observed throughput and errors, remembering the result per network
+    Auto,
+}
+
+impl DownloadConcurrency {
+    /// At most `max` parallel downloads
+    fn capped(self, max: usize) -> Self {
+        match self {
+            Self::Fixed(n) => Self::Fixed(n.min(max)),
+            Self::Auto => Self::Fixed(max),
+        }
+    }
+}
+
+impl std::fmt::Display for DownloadConcurrency {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Fixed(n) => write!(f, "{}", n),
+            Self::Auto => write!(f, "auto"),
+        }
+    }
+}
+
+/// Per-file download behaviour
+#[derive(Debug, Clone, Copy)]
+pub struct TaskSettings {
+    /// Extra attempts for a file that failed with a retryable error
+    pub retries: u32,
+    /// Chunks are collected up to this size before hitting the disk, so
+    /// dozens of parallel downloads don't thrash an HDD with small writes
+    pub buffer_size: usize,
+    /// Flush each finished file to the disk before moving it into place
+    pub fsync: bool,
+}
+
+impl Default for TaskSettings {
+    fn default() -> Self {
+        Self {
+            retries: 3,
+            buffer_size: 256 * 1024,
+            fsync: false,
+        }
+    }
+}
+
+/// What the host application adds to a download run; every method does
+/// nothing by default
+pub trait DownloadHooks: Send + Sync {
+    /// Outcome of a task that was fetched (not one already present):
+    /// `Ok((size, sha1))` or the error. The desktop app's audit log.
+    fn record(&self, _task: &DownloadTask, _result: Result<(u64, String), &str>) {}
+
+    /// Called once at the end with the bytes actually received
+    fn finished(&self, _received_bytes: u64, _elapsed: std::time::Duration) {}
+}
+
+/// No hooks
+pub struct NoHooks;
+
+impl DownloadHooks for NoHooks {}
+
+/// Check run on a downloaded, checksum-verified file (at its temporary
+/// path) before it is moved into place; `Err` rejects the file. Runs on a
+/// blocking thread.
+pub type InstallCheck = fn(&DownloadTask, &Path) -> Result<(), String>;
+
+/// How a [`download_planned`] run behaves besides its tasks
+pub struct DownloadOptions<'a> {
+    pub concurrency: DownloadConcurrency,
+    pub settings: TaskSettings,
+    /// (parallel downloads, bytes per second or 0) when the run should
+    /// leave the network to a running game
+    pub low_priority: Option<(usize, u64)>,
+    /// Where auto concurrency remembers what worked per network; without
+    /// it every auto run starts from scratch
+    pub tuning_store: Option<&'a Path>,
+    /// Lists the run as a session that can be paused or cancelled
+    pub sessions: Option<&'a DownloadManager>,
+    /// Reported as the session's window
+    pub label: &'a str,
+    pub hooks: &'a dyn DownloadHooks,
+    pub check: Option<InstallCheck>,
+}
+
+impl Default for DownloadOptions<'_> {
+    fn default() -> Self {
+        Self {
+            concurrency: DownloadConcurrency::Auto,
+            settings: TaskSettings::default(),
+            low_priority: None,
+            tuning_store: None,
+            sessions: None,
+            label: "",
+            hooks: &NoHooks,
+            check: None,
+        }
+    }
+}
+
+/// Producer side of [`download_planned`]: tasks sent here start downloading
+/// while the producer is still planning the rest
+#[derive(Clone)]
+pub struct TaskSender {
+    tx: tokio::sync::mpsc::UnboundedSender<DownloadTask>,
+    planned: Arc<AtomicUsize>,
+}
+
+impl TaskSender {
+    pub fn send(&self, task: DownloadTask) {
+        self.planned.fetch_add(1, Ordering::AcqRel);
+        if self.tx.send(task).is_err() {
+            // The downloader is gone (it failed); nothing left to count
+            self.planned.fetch_sub(1, Ordering::AcqRel);
+        }
+    }
+}
+
+pub struct TaskReceiver {
+    rx: tokio::sync::mpsc::UnboundedReceiver<DownloadTask>,
+    planned: Arc<AtomicUsize>,
+}
+
+pub fn task_channel() -> (TaskSender, TaskReceiver) {
+    let (tx, rx) = tokio::sync::mpsc::unbounded_channel();
+    let planned = Arc::new(AtomicUsize::new(0));
+    (
+        TaskSender {
+            tx,
+            planned: planned.clone(),
+        },
+        TaskReceiver { rx, planned },
+    )
+}
+
+/// Download `tasks` (deduplicated by path), reporting `download-start`,
+/// `download-progress`, `download-failed` and `download-complete` to `events`
+pub async fn download_files(
+    events: &dyn EventSink,
+    tasks: Vec<DownloadTask>,
+    options: DownloadOptions<'_>,
+) -> Result<(), String> {
+    let (sender, receiver) = task_channel();
+    for task in dedup_tasks(tasks) {
+        sender.send(task);
+    }
+    drop(sender);
+    download_planned(events, receiver, options).await
+}
+
+/// Download tasks as they arrive on `tasks`, until every [`TaskSender`] is
+/// dropped. Progress events report the number of tasks planned so far.
+pub async fn download_planned(
+    events: &dyn EventSink,
+    tasks: TaskReceiver,
+    options: DownloadOptions<'_>,
+) -> Result<(), String> {
+    let DownloadOptions {
+        concurrency,
+        settings,
+        low_priority,
+        tuning_store,
+        sessions,
+        label,
+        hooks,
+        check,
+    } = options;
+    // Leave the network to a running game
+    let concurrency = match low_priority {
+        Some((threads, speed)) => {
+            println!(
+                "[Downloader] A game is running, downloading with low priority ({} threads, {} KB/s)",
+                threads,
+                speed / 1024
+            );
+            concurrency.capped(threads)
+        }
+        None => concurrency,
+    };
+    let (limiter, max_concurrent, network_key) = match concurrency {
+        DownloadConcurrency::Fixed(n) => {
+            // Clamp max_concurrent to a valid range (1-128) to prevent edge cases
+            let n = n.clamp(1, download_tuning::MAX_CONCURRENCY);
+            (AdaptiveConcurrency::new(n), n, None)
+        }
+        DownloadConcurrency::Auto => {
+            let key = download_tuning::current_network_key();
+            let initial = tuning_store
+                .map(download_tuning::TuningStore::load)
+                .unwrap_or_default()
+                .networks
+                .get(&key)
+                .copied()
+                .unwrap_or(download_tuning::INITIAL_CONCURRENCY)
+                .clamp(
+                    download_tuning::MIN_CONCURRENCY,
+                    download_tuning::MAX_CONCURRENCY,
+                );
+            println!(
+                "[Downloader] Auto concurrency on network {}, starting at {}",
+                key, initial
+            );
+            (
+                AdaptiveConcurrency::new(initial),
+                download_tuning::MAX_CONCURRENCY,
+                Some(key),
+            )
+        }
+    };
+    let tuner = network_key.as_ref().map(|_| limiter.spawn_tuner());
+
+    let client = reqwest::Client::new();
+    let TaskReceiver { mut rx, planned } = tasks;
+    let progress = Arc::new(GlobalProgress::new(planned));
+
+    // Registered so the UI can pause or cancel it until this returns
+    let guard = download_manager::start_session(
+        sessions,
+        label,
+        progress.clone(),
+        low_priority.map(|(_, speed)| speed),
+    );
+    let session = guard.session.clone();
+
+    // Notify start (files planned so far)
+    emit(events, "download-start", progress.total_files());
+
+    let tasks_stream = futures::stream::poll_fn(|cx| rx.poll_recv(cx)).map(|task| {
+        let client = client.clone();
+        let limiter = limiter.clone();
+        let progress = progress.clone();
+        let session = session.clone();
+
+        async move {
+            let mut attempts = 0;
+            let mut official_only = false;
+            let result = loop {
+                attempts += 1;
+                // A retry gets a new connection instead of a pooled one that
+                // may be what broke
+                let client = if attempts == 1 {
+                    client.clone()
+                } else {
+                    reqwest::Client::new()
+                };
+                // Don't take a slot while paused
+                let result = match session.checkpoint().await {
+                    Ok(()) => {
+                        let permit = limiter.acquire().await;
+                        let result = download_task(
+                            &client,
+                            events,
+                            &task,
+                            &progress,
+                            &limiter,
+                            &settings,
+                            &session,
+                            check,
+                            official_only,
+                        )
+                        .await;
+                        if !session.is_cancelled() {
+                            limiter.record_result(result.is_ok());
+                        }
+                        limiter.release(permit);
+                        result
+                    }
+                    Err(e) => Err(AttemptError::permanent(e)),
+                };
+                match result {
+                    Err(e) if e.retryable && attempts <= settings.retries => {
+                        official_only |= e.corrupt;
+                        let delay = retry_delay(attempts);
+                        println!(
+                            "[Downloader] {} failed ({}), retrying in {:?}",
+                            task.url, e.message, delay
+                        );
+                        tokio::time::sleep(delay).await;
+                    }
+                    result => break result.map_err(|e| e.message),
+                }
+            };
+            match &result {
+                Ok(Some(received)) => hooks.record(&task, Ok(received.clone())),
+                Ok(None) => {} // already present and valid
+                Err(_) if session.is_cancelled() => {}
+                Err(e) => hooks.record(&task, Err(e)),
+            }
+            match result {
+                Ok(received) => Ok(received.map_or(0, |(size, _)| size)),
+                Err(error) => Err(FailedDownload {
+                    url: task.url.clone(),
+                    path: task.path.to_string_lossy().to_string(),
+                    error,
+                    attempts,
+                }),
+            }
+        }
+    });
+
+    // Buffer unordered to run concurrently; the limiter gates actual parallelism
+    let started = std::time::Instant::now();
+    let mut received_bytes = 0;
+    let mut failed = Vec::new();
+    for result in tasks_stream
+        .buffer_unordered(max_concurrent)
+        .collect::<Vec<_>>()
+        .await
+    {
+        match result {
+            Ok(size) => received_bytes += size,
+            Err(failure) => failed.push(failure),
+        }
+    }
+    hooks.finished(received_bytes, started.elapsed());
+
+    if let Some(tuner) = tuner {
+        tuner.abort();
+    }
+    if let (Some(path), Some(key), Some(learned)) = (tuning_store, network_key, limiter.learned()) {
+        let mut store = download_tuning::TuningStore::load(path);
+        store.networks.insert(key, learned);
+        if let Err(e) = store.save(path) {
+            eprintln!("[Downloader] Failed to save tuning data: {}", e);
+        }
+    }
+
+    let cancelled = session.is_cancelled();
+    drop(guard);
+    if !failed.is_empty() && !cancelled {
+        eprintln!("[Downloader] {} file(s) failed to download", failed.len());
+        emit(events, "download-failed", &failed);
+    }
+    emit(events, "download-complete", ());
+    if cancelled {
+        return Err(download_manager::CANCELLED.to_string());
+    }
+    if let Some(first) = failed.first() {
+        return Err(format!(
+            "{} file(s) failed to download, e.g. {}: {}",
+            failed.len(),
+            first.path,
+            first.error
+        ));
+    }
+    Ok(())
+}
+
+/// Unique sibling path for an in-progress download of `path`
+fn part_path_for(path: &Path) -> PathBuf {
+    let mut name = path.file_name().unwrap_or_default().to_os_string();
+    name.push(format!(".{}.part", uuid::Uuid::new_v4().simple()));
+    path.with_file_name(name)
+}
+
+/// Move a finished temp file into place. If another writer got there first
+/// with a file that passes the checksum, keep theirs and drop ours.
+async fn commit_part_file(
+    part_path: &Path,
+    path: &Path,
+    task: &DownloadTask,
+) -> Result<(), String> {
+    let has_checksum = task.sha256.is_some() || task.sha1.is_some();
+    if has_checksum
+        && let Ok(existing) = tokio::fs::read(path).await
+        && verify_checksum(&existing, task.sha256.as_deref(), task.sha1.as_deref())
+    {
+        let _ = tokio::fs::remove_file(part_path).await;
+        return Ok(());
+    }
+    if let Err(e) = tokio::fs::rename(part_path, path).await {
+        // Renaming over a file another process holds open can fail on Windows;
+        // that is fine as long as the file in place is complete
+        let _ = tokio::fs::remove_file(part_path).await;
+        let in_place = tokio::fs::read(path).await.is_ok_and(|data| {
+            has_checksum && verify_checksum(&data, task.sha256.as_deref(), task.sha1.as_deref())
+        });
+        if !in_place {
+            return Err(format!("Failed to move download into place: {}", e));
+        }
+    }
+    Ok(())
+}
+
+/// Download a single task, skipping it if a valid copy already exists.
+/// `official_only` bypasses the mirror. Returns the size and SHA1 of the
+/// received file, or `None` if skipped.
+#[allow(clippy::too_many_arguments)]
+async fn download_task(
+    client: &reqwest::Client,
+    events: &dyn EventSink,
+    task: &DownloadTask,
+    progress: &GlobalProgress,
+    limiter: &AdaptiveConcurrency,
+    settings: &TaskSettings,
+    session: &DownloadSession,
+    check: Option<InstallCheck>,
+    official_only: bool,
+) -> Result<Option<(u64, String)>, AttemptError> {
+    let file_name = task.path.file_name().unwrap().to_string_lossy().to_string();
+    // Deep library paths can exceed MAX_PATH on Windows
+    let path = long_path(&task.path);
+
+    // 1. Check if file exists and verify checksum
+    if path.exists() {
+        emit_progress(events, &file_name, "Verifying", 0, 0, &progress.snapshot());
+
+        if (task.sha256.is_some() || task.sha1.is_some())
+            && let Ok(data) = tokio::fs::read(&path).await
+            && verify_checksum(&data, task.sha256.as_deref(), task.sha1.as_deref())
+        {
+            // Already valid, skip download
+            let skipped_size = tokio::fs::metadata(&path)
+                .await
+                .map(|m| m.len())
+                .unwrap_or(0);
+            if skipped_size > 0 {
+                let _ = progress.add_bytes(skipped_size);
+            }
+            emit_progress(
+                events,
+                &file_name,
+                "Skipped",
+                0,
+                0,
+                &progress.inc_completed(),
+            );
+            return Ok(None);
+        }
+    }
+
+    // 2. Download
+    if let Some(parent) = path.parent() {
+        let _ = tokio::fs::create_dir_all(parent).await;
+    }
+
+    // Stream into a private temp file and rename it into place, so that
+    // instances installing into the shared cache at the same time never
+    // write into (or read) each other's half-finished files.
+    let part_path = part_path_for(&path);
+    let mut hasher = sha1::Sha1::new();
+    let mut sha256 = task.sha256.as_ref().map(|_| sha2::Sha256::new());
+    let mut downloaded: u64 = 0;
+    // From the configured mirror if it has the file; error pages must not
+    // end up on disk
+    let response = if official_only {
+        client.get(&task.url).send().await
+    } else {
+        get_mirrored(client, &task.url).await
+    };
+    match response.and_then(|resp| resp.error_for_status()) {
+        Ok(mut resp) => {
+            let total_size = resp.content_length().unwrap_or(0);
+            let mut file = match tokio::fs::File::create(&part_path).await {
+                Ok(f) => tokio::io::BufWriter::with_capacity(settings.buffer_size, f),
+                Err(e) => return Err(AttemptError::permanent(format!("Create file error: {}", e))),
+            };
+
+            loop {
+                if let Err(e) = session.checkpoint().await {
+                    let _ = tokio::fs::remove_file(&part_path).await;
+                    progress.discard_bytes(downloaded);
+                    return Err(AttemptError::permanent(e));
+                }
+                match resp.chunk().await {
+                    Ok(Some(chunk)) => {
+                        throttle(chunk.len() as u64).await;
+                        session.throttle(chunk.len() as u64).await;
+                        if let Err(e) = file.write_all(&chunk).await {
+                            let _ = tokio::fs::remove_file(&part_path).await;
+                            progress.discard_bytes(downloaded);
+                            return Err(AttemptError::permanent(format!("Write error: {}", e)));
+                        }
+                        hasher.update(&chunk);
+                        if let Some(sha256) = &mut sha256 {
+                            sha256.update(&chunk);
+                        }
+                        downloaded += chunk.len() as u64;
+                        limiter.record_bytes(chunk.len() as u64);
+                        let snapshot = progress.add_bytes(chunk.len() as u64);
+                        emit_progress(
+                            events,
+                            &file_name,
+                            "Downloading",
+                            downloaded,
+                            total_size,
+                            &snapshot,
+                        );
+                    }
+                    Ok(None) => break,
+                    Err(e) => {
+                        let _ = tokio::fs::remove_file(&part_path).await;
+                        progress.discard_bytes(downloaded);
+                        return Err(AttemptError::transient(format!("Download error: {}", e)));
+                    }
+                }
+            }
+            let mut written = file.flush().await;
+            if settings.fsync && written.is_ok() {
+                written = file.get_ref().sync_all().await;
+            }
+            if let Err(e) = written {
+                let _ = tokio::fs::remove_file(&part_path).await;
+                return Err(AttemptError::permanent(format!("Write error: {}", e)));
+            }
+        }
+        Err(e) => return Err(AttemptError::request(e)),
+    }
+    let sha1 = hex::encode(hasher.finalize());
+
+    // A corrupt file is retried, from the official URL rather than a mirror
+    let sha1_ok = task
+        .sha1
+        .as_deref()
+        .is_none_or(|expected| expected.eq_ignore_ascii_case(&sha1));
+    let sha256_ok = match (task.sha256.as_deref(), sha256) {
+        (Some(expected), Some(sha256)) => {
+            expected.eq_ignore_ascii_case(&hex::encode(sha256.finalize()))
+        }
+        _ => true,
+    };
+    if !sha1_ok || !sha256_ok {
+        let _ = tokio::fs::remove_file(&part_path).await;
+        progress.discard_bytes(downloaded);
+        return Err(AttemptError::corrupt(format!(
+            "Checksum mismatch for {}",
+            file_name
+        )));
+    }
+    // E.g. the desktop app scans mods before they land in the instance,
+    // whichever source (Modrinth, CurseForge, a pack or a mod list) planned them
+    if let Some(check) = check {
+        let (task, part) = (task.clone(), part_path.clone());
+        let scanned = tokio::task::spawn_blocking(move || check(&task, &part))
+            .await
+            .map_err(|e| AttemptError::permanent(e.to_string()))?;
+        if let Err(e) = scanned {
+            let _ = tokio::fs::remove_file(&part_path).await;
+            return Err(AttemptError::permanent(e));
+        }
+    }
+    commit_part_file(&part_path, &path, task)
+        .await
+        .map_err(AttemptError::permanent)?;
+
+    emit_progress(
+        events,
+        &file_name,
+        "Finished",
+        0,
+        0,
+        &progress.inc_completed(),
+    );
+    Ok(Some((downloaded, sha1)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn task(path: &str, url: &str, sha1: Option<&str>) -> DownloadTask {
+        DownloadTask {
+            url: url.to_string(),
+            path: PathBuf::from(path),
+            sha1: sha1.map(str::to_string),
+            sha256: None,
+        }
+    }
+
+    #[test]
+    fn test_dedup_tasks_prefers_checksum() {
+        let tasks = vec![
+            task(
+                "libraries/asm-9.6.jar",
+                "https://maven.fabricmc.net/asm",
+                None,
+            ),
+            task(
+                "libraries/guava.jar",
+                "https://libraries.minecraft.net/guava",
+                Some("g"),
+            ),
+            task(
+                "libraries/asm-9.6.jar",
+                "https://libraries.minecraft.net/asm",
+                Some("a"),
+            ),
+            task("libraries/guava.jar", "https://maven.example/guava", None),
+        ];
+        let unique = dedup_tasks(tasks);
+        assert_eq!(unique.len(), 2);
+        assert_eq!(unique[0].url, "https://libraries.minecraft.net/asm");
+        assert_eq!(unique[1].url, "https://libraries.minecraft.net/guava");
+    }
+
+    #[test]
+    fn test_bmclapi_rewrite() {
+        let mirror = DownloadMirror::Bmclapi;
+        assert_eq!(
+            mirror
+                .rewrite("https://piston-meta.mojang.com/mc/game/version_manifest_v2.json")
+                .as_deref(),
+            Some("https://bmclapi2.bangbang93.com/mc/game/version_manifest_v2.json")
+        );
+        assert_eq!(
+            mirror
+                .rewrite("https://libraries.minecraft.net/com/mojang/brigadier/1.0.18/brigadier-1.0.18.jar")
+                .as_deref(),
+            Some("https://bmclapi2.bangbang93.com/maven/com/mojang/brigadier/1.0.18/brigadier-1.0.18.jar")
+        );
+        assert_eq!(
+            mirror
+                .rewrite("https://resources.download.minecraft.net/ab/ab12")
+                .as_deref(),
+            Some("https://bmclapi2.bangbang93.com/assets/ab/ab12")
+        );
+        assert_eq!(mirror.rewrite("https://cdn.modrinth.com/data/x.jar"), None);
+        assert_eq!(
+            DownloadMirror::Official.rewrite("https://libraries.minecraft.net/x.jar"),
+            None
+        );
+    }
+
+    #[test]
+    fn test_token_bucket() {
+        let start = Instant::now();
+        let mut bucket = TokenBucket::new(1000, start);
+        // One second's worth is available right away
+        assert_eq!(bucket.take(1000, start), Duration::ZERO);
+        assert_eq!(bucket.take(500, start), Duration::from_millis(500));
+        // Half a second later the debt is paid off
+        let later = start + Duration::from_millis(500);
+        assert_eq!(bucket.take(0, later), Duration::ZERO);
+        // Idle time doesn't build up more than a second of burst
+        let idle = later + Duration::from_secs(10);
+        assert_eq!(bucket.take(2000, idle), Duration::from_secs(1));
+    }
+
+    #[test]
+    fn test_same_speed_keeps_bucket() {
+        let take = |bytes| {
+            BANDWIDTH
+                .lock()
+                .unwrap()
+                .as_mut()
+                .map(|b| b.take(bytes, Instant::now()))
+        };
+        set_max_download_speed(1000);
+        assert_eq!(take(1000), Some(Duration::ZERO));
+        // Saving the same cap doesn't hand out a fresh second of burst
+        set_max_download_speed(1000);
+        assert!(take(500).unwrap() > Duration::ZERO);
+        set_max_download_speed(0);
+        assert_eq!(take(500), None);
+    }
+
+    #[test]
+    fn test_retry_policy() {
+        assert_eq!(retry_delay(1), Duration::from_millis(500));
+        assert_eq!(retry_delay(3), Duration::from_secs(2));
+        assert_eq!(retry_delay(5), Duration::from_secs(8));
+        assert_eq!(retry_delay(20), Duration::from_secs(8));
+
+        assert!(is_retryable_status(503));
+        assert!(is_retryable_status(429));
+        assert!(!is_retryable_status(404));
+        assert!(!is_retryable_status(403));
+    }
+
+    #[test]
+    fn test_write_atomic_leaves_no_temp_file() {
+        let dir = std::env::temp_dir().join(format!("dropout-atomic-{}", uuid::Uuid::new_v4()));
+        let path = dir.join("indexes/17.json");
+        write_atomic(&path, b"{}").unwrap();
+        write_atomic(&path, b"{\"objects\":{}}").unwrap();
+        assert_eq!(std::fs::read(&path).unwrap(), b"{\"objects\":{}}");
+        assert_eq!(std::fs::read_dir(dir.join("indexes")).unwrap().count(), 1);
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+}
@@ -0,0 +1,24 @@
+//! Event delivery to whatever frontend hosts the core.
+
+use serde::Serialize;
+
+/// Receives named events with a JSON payload (e.g. `download-progress`).
+/// The Tauri app forwards them to the window; a CLI might print them.
+pub trait EventSink: Send + Sync {
+    fn emit_json(&self, event: &str, payload: serde_json::Value);
+}
+
+/// Serialize `payload` and hand it to `sink`
+pub fn emit<T: Serialize>(sink: &dyn EventSink, event: &str, payload: T) {
+    match serde_json::to_value(payload) {
+        Ok(value) => sink.emit_json(event, value),
+        Err(e) => eprintln!("[Events] Failed to serialize {}: {}", event, e),
+    }
+}
+
+/// Discards all events
+pub struct NullSink;
+
+impl EventSink for NullSink {
+    fn emit_json(&self, _event: &str, _payload: serde_json::Value) {}
+}
@@ -4,18 +4,22 @@
 //! - Fetch available Fabric loader versions from the Fabric Meta API
 //! - Generate version JSON files for Fabric-enabled Minecraft versions
 //! - Install Fabric loader for a specific Minecraft version
+//! - List the loader's libraries (intermediary mappings included) for download
 
 use serde::{Deserialize, Serialize};
 use std::error::Error;
 use std::path::PathBuf;
 use ts_rs::TS;
 
+use crate::downloader::DownloadTask;
+use crate::maven;
+
 const FABRIC_META_URL: &str = "https://meta.fabricmc.net/v2";
 
 /// Represents a Fabric loader version from the Meta API.
 #[derive(Debug, Deserialize, Serialize, Clone, TS)]
 #[serde(rename_all = "camelCase")]
-#[ts(export, export_to = "fabric.ts")]
+#[ts(export, export_to = "dropout-core/fabric.ts")]
 pub struct FabricLoaderVersion {
     pub separator: String,
     pub build: i32,
@@ -27,7 +31,7 @@ pub struct FabricLoaderVersion {
 /// Represents a Fabric intermediary mapping version.
 #[derive(Debug, Deserialize, Serialize, Clone, TS)]
 #[serde(rename_all = "camelCase")]
-#[ts(export, export_to = "fabric.ts")]
+#[ts(export, export_to = "dropout-core/fabric.ts")]
 pub struct FabricIntermediaryVersion {
     pub maven: String,
     pub version: String,
@@ -37,7 +41,7 @@ pub struct FabricIntermediaryVersion {
 /// Represents a combined loader + intermediary version entry.
 #[derive(Debug, Deserialize, Serialize, Clone, TS)]
 #[serde(rename_all = "camelCase")]
-#[ts(export, export_to = "fabric.ts")]
+#[ts(export, export_to = "dropout-core/fabric.ts")]
 pub struct FabricLoaderEntry {
     pub loader: FabricLoaderVersion,
     pub intermediary: FabricIntermediaryVersion,
@@ -48,7 +52,7 @@ pub struct FabricLoaderEntry {
 /// Launcher metadata from Fabric Meta API.
 #[derive(Debug, Deserialize, Serialize, Clone, TS)]
 #[serde(rename_all = "camelCase")]
-#[ts(export, export_to = "fabric.ts")]
+#[ts(export, export_to = "dropout-core/fabric.ts")]
 pub struct FabricLauncherMeta {
     pub version: i32,
     pub libraries: FabricLibraries,
@@ -59,7 +63,7 @@ pub struct FabricLauncherMeta {
 /// Libraries required by Fabric loader.
 #[derive(Debug, Deserialize, Serialize, Clone, TS)]
 #[serde(rename_all = "camelCase")]
-#[ts(export, export_to = "fabric.ts")]
+#[ts(export, export_to = "dropout-core/fabric.ts")]
 pub struct FabricLibraries {
     pub client: Vec<FabricLibrary>,
     pub common: Vec<FabricLibrary>,
@@ -69,7 +73,7 @@ pub struct FabricLibraries {
 /// A single Fabric library dependency.
 #[derive(Debug, Deserialize, Serialize, Clone, TS)]
 #[serde(rename_all = "camelCase")]
-#[ts(export, export_to = "fabric.ts")]
+#[ts(export, export_to = "dropout-core/fabric.ts")]
 pub struct FabricLibrary {
     pub name: String,
     pub url: Option<String>,
@@ -79,7 +83,7 @@ pub struct FabricLibrary {
 /// Can be either a struct with client/server fields or a simple string.
 #[derive(Debug, Deserialize, Serialize, Clone, TS)]
 #[serde(rename_all = "camelCase")]
-#[ts(export, export_to = "fabric.ts", untagged)]
+#[ts(export, export_to = "dropout-core/fabric.ts", untagged)]
 #[serde(untagged)]
 pub enum FabricMainClass {
     Structured { client: String, server: String },
@@ -106,7 +110,7 @@ impl FabricMainClass {
 /// Represents a Minecraft version supported by Fabric.
 #[derive(Debug, Deserialize, Serialize, Clone, TS)]
 #[serde(rename_all = "camelCase")]
-#[ts(export, export_to = "fabric.ts")]
+#[ts(export, export_to = "dropout-core/fabric.ts")]
 pub struct FabricGameVersion {
     pub version: String,
     pub stable: bool,
@@ -115,7 +119,7 @@ pub struct FabricGameVersion {
 /// Information about an installed Fabric version.
 #[derive(Debug, Serialize, Clone, TS)]
 #[serde(rename_all = "camelCase")]
-#[ts(export, export_to = "fabric.ts")]
+#[ts(export, export_to = "dropout-core/fabric.ts")]
 pub struct InstalledFabricVersion {
     pub id: String,
     pub minecraft_version: String,
@@ -207,7 +211,8 @@ pub fn generate_version_id(game_version: &str, loader_version: &str) -> String {
 /// Install Fabric loader for a specific Minecraft version.
 ///
 /// This creates the version JSON file in the versions directory.
-/// The actual library downloads happen during game launch.
+/// The libraries are downloaded during game launch, unless the caller
+/// downloads [`library_tasks`] right away.
 ///
 /// # Arguments
 /// * `game_dir` - The .minecraft directory path
@@ -248,6 +253,39 @@ pub async fn install_fabric(
     })
 }
 
+/// Download tasks for the libraries of a Fabric version profile: the loader,
+/// the intermediary mappings and their dependencies. None of them are in the
+/// vanilla version JSON, so without this they are fetched on first launch.
+///
+/// # Arguments
+/// * `profile` - The version JSON written by [`install_fabric`]
+/// * `libraries_dir` - The libraries directory the game will launch with
+pub fn library_tasks(
+    profile: &serde_json::Value,
+    libraries_dir: &std::path::Path,
+) -> Vec<DownloadTask> {
+    let Some(libraries) = profile.get("libraries").and_then(|l| l.as_array()) else {
+        return Vec::new();
+    };
+    libraries
+        .iter()
+        .filter_map(|lib| {
+            let name = lib.get("name")?.as_str()?;
+            let checksum = |key: &str| lib.get(key).and_then(|v| v.as_str()).map(String::from);
+            Some(DownloadTask {
+                url: maven::resolve_library_url(
+                    name,
+                    None,
+                    lib.get("url").and_then(|u| u.as_str()),
+                )?,
+                path: maven::get_library_path(name, libraries_dir)?,
+                sha1: checksum("sha1"),
+                sha256: checksum("sha256"),
+            })
+        })
+        .collect()
+}
+
 /// Check if Fabric is installed for a specific version combination.
 ///
 /// # Arguments
@@ -313,4 +351,39 @@ mod tests {
             "fabric-loader-0.15.6-1.20.4"
         );
     }
+
+    #[test]
+    fn test_library_tasks() {
+        let profile = serde_json::json!({
+            "id": "fabric-loader-0.15.7-1.20.4",
+            "libraries": [
+                {
+                    "name": "net.fabricmc:intermediary:1.20.4",
+                    "url": "https://maven.fabricmc.net/"
+                },
+                {
+                    "name": "org.ow2.asm:asm:9.6",
+                    "url": "https://maven.fabricmc.net/",
+                    "sha1": "aa205cf0a06dbd8e04ece91c0b37c3f5d567546a"
+                },
+                { "url": "https://maven.fabricmc.net/" }
+            ]
+        });
+        let libraries_dir = std::path::Path::new("/libraries");
+        let tasks = library_tasks(&profile, libraries_dir);
+        assert_eq!(tasks.len(), 2);
+        assert_eq!(
+            tasks[0].url,
+            "https://maven.fabricmc.net/net/fabricmc/intermediary/1.20.4/intermediary-1.20.4.jar"
+        );
+        assert_eq!(
+            tasks[0].path,
+            libraries_dir.join("net/fabricmc/intermediary/1.20.4/intermediary-1.20.4.jar")
+        );
+        assert_eq!(tasks[0].sha1, None);
+        assert_eq!(
+            tasks[1].sha1.as_deref(),
+            Some("aa205cf0a06dbd8e04ece91c0b37c3f5d567546a")
+        );
+    }
 }
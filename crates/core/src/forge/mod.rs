@@ -14,6 +14,8 @@ use std::os::windows::process::CommandExt;
 use std::path::PathBuf;
 use ts_rs::TS;
 
+pub mod processors;
+
 const FORGE_PROMOTIONS_URL: &str =
     "https://files.minecraftforge.net/net/minecraftforge/forge/promotions_slim.json";
 const FORGE_MAVEN_URL: &str = "https://maven.minecraftforge.net/";
@@ -22,7 +24,7 @@ const FORGE_FILES_URL: &str = "https://files.minecraftforge.net/";
 /// Represents a Forge version entry.
 #[derive(Debug, Deserialize, Serialize, Clone, TS)]
 #[serde(rename_all = "camelCase")]
-#[ts(export, export_to = "forge.ts")]
+#[ts(export, export_to = "dropout-core/forge.ts")]
 pub struct ForgeVersion {
     pub version: String,
     pub minecraft_version: String,
@@ -41,7 +43,7 @@ struct ForgePromotions {
 /// Information about an installed Forge version.
 #[derive(Debug, Serialize, Clone, TS)]
 #[serde(rename_all = "camelCase")]
-#[ts(export, export_to = "forge.ts")]
+#[ts(export, export_to = "dropout-core/forge.ts")]
 pub struct InstalledForgeVersion {
     pub id: String,
     pub minecraft_version: String,
@@ -268,6 +270,20 @@ async fn try_download_forge_installer(
     .into())
 }
 
+/// Download the installer jar to `dest` (used by the native processor pipeline)
+pub async fn download_installer(
+    game_version: &str,
+    forge_version: &str,
+    dest: &std::path::Path,
+) -> Result<(), Box<dyn Error + Send + Sync>> {
+    let bytes = try_download_forge_installer(game_version, forge_version).await?;
+    if let Some(parent) = dest.parent() {
+        tokio::fs::create_dir_all(parent).await?;
+    }
+    tokio::fs::write(dest, &bytes).await?;
+    Ok(())
+}
+
 /// Fetch the Forge installer manifest to get the library list
 async fn fetch_forge_installer_manifest(
     game_version: &str,
@@ -342,10 +358,10 @@ pub async fn install_forge(
 /// # Returns
 /// Result indicating success or failure
 pub async fn run_forge_installer(
-    game_dir: &PathBuf,
+    game_dir: &std::path::Path,
     game_version: &str,
     forge_version: &str,
-    java_path: &PathBuf,
+    java_path: &std::path::Path,
 ) -> Result<(), Box<dyn Error + Send + Sync>> {
     let installer_path = game_dir.join("forge-installer.jar");
 
@@ -353,22 +369,38 @@ pub async fn run_forge_installer(
     let bytes = try_download_forge_installer(game_version, forge_version).await?;
     tokio::fs::write(&installer_path, &bytes).await?;
 
-    // Run the installer in headless mode
+    let result = run_installer_jar(&installer_path, game_dir, java_path, false).await;
+
+    // Clean up installer
+    let _ = tokio::fs::remove_file(&installer_path).await;
+    result
+}
+
+/// Run an already downloaded installer jar in headless mode. With `offline`
+/// the installer downloads nothing, so its libraries (see
+/// [`processors::installer_library_tasks`]) and the vanilla client jar must
+/// already be in `game_dir`.
+pub async fn run_installer_jar(
+    installer_path: &std::path::Path,
+    game_dir: &std::path::Path,
+    java_path: &std::path::Path,
+    offline: bool,
+) -> Result<(), Box<dyn Error + Send + Sync>> {
     // The installer accepts --installClient <path> to install to a specific directory
     let mut cmd = tokio::process::Command::new(java_path);
     cmd.arg("-jar")
-        .arg(&installer_path)
+        .arg(installer_path)
         .arg("--installClient")
         .arg(game_dir);
+    if offline {
+        cmd.arg("--offline");
+    }
 
     #[cfg(target_os = "windows")]
     cmd.creation_flags(0x08000000);
 
     let output = cmd.output().await?;
 
-    // Clean up installer
-    let _ = tokio::fs::remove_file(&installer_path).await;
-
     if !output.status.success() {
         let stderr = String::from_utf8_lossy(&output.stderr);
         let stdout = String::from_utf8_lossy(&output.stdout);
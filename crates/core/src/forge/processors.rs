@@ -0,0 +1,480 @@
+//! Native execution of the Forge/NeoForge installer `processors` pipeline.
+//!
+//! Modern installers (1.13+) carry an `install_profile.json` listing tool jars
+//! (binary patcher, jar splitter, ...) to run against the vanilla client.
+//! Instead of running the installer itself, this module reads the profile,
+//! resolves the `{DATA}` / `[maven]` argument placeholders the same way the
+//! installer does, and runs each client-side processor directly. Processors
+//! whose declared outputs already exist with the right SHA1 are skipped, so a
+//! re-install usually doesn't spawn Java at all.
+
+use serde::Deserialize;
+use std::collections::HashMap;
+use std::io::Read;
+#[cfg(target_os = "windows")]
+use std::os::windows::process::CommandExt;
+use std::path::{Path, PathBuf};
+
+use crate::downloader::{DownloadTask, compute_sha1};
+use crate::maven::{FORGE_MAVEN, MavenCoordinate, NEOFORGE_MAVEN};
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct SidedData {
+    pub client: String,
+    pub server: Option<String>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct Processor {
+    pub jar: String,
+    #[serde(default)]
+    pub classpath: Vec<String>,
+    #[serde(default)]
+    pub args: Vec<String>,
+    #[serde(default)]
+    pub outputs: HashMap<String, String>,
+    pub sides: Option<Vec<String>>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct ProfileArtifact {
+    pub path: Option<String>,
+    pub url: Option<String>,
+    pub sha1: Option<String>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct ProfileLibraryDownloads {
+    pub artifact: Option<ProfileArtifact>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct ProfileLibrary {
+    pub name: String,
+    pub downloads: Option<ProfileLibraryDownloads>,
+}
+
+/// `install_profile.json` of a 1.13+ installer
+#[derive(Debug, Clone, Deserialize)]
+pub struct InstallProfile {
+    pub version: String,
+    pub minecraft: String,
+    #[serde(default)]
+    pub data: HashMap<String, SidedData>,
+    #[serde(default)]
+    pub processors: Vec<Processor>,
+    #[serde(default)]
+    pub libraries: Vec<ProfileLibrary>,
+}
+
+/// Paths the processors work with
+pub struct ProcessorContext {
+    pub installer_path: PathBuf,
+    pub libraries_dir: PathBuf,
+    pub minecraft_jar: PathBuf,
+    pub game_dir: PathBuf,
+    /// Where `/data/...` entries of the installer are extracted
+    pub work_dir: PathBuf,
+}
+
+fn read_entry(archive: &mut zip::ZipArchive<std::fs::File>, name: &str) -> Result<Vec<u8>, String> {
+    let mut entry = archive
+        .by_name(name)
+        .map_err(|e| format!("Installer has no {}: {}", name, e))?;
+    let mut data = Vec::new();
+    entry.read_to_end(&mut data).map_err(|e| e.to_string())?;
+    Ok(data)
+}
+
+fn open_installer(path: &Path) -> Result<zip::ZipArchive<std::fs::File>, String> {
+    let file = std::fs::File::open(path).map_err(|e| e.to_string())?;
+    zip::ZipArchive::new(file).map_err(|e| format!("Invalid installer jar: {}", e))
+}
+
+/// Read the install profile and the version JSON from an installer jar.
+/// Legacy (pre-1.13) installers have no processors and are rejected.
+pub fn read_installer(
+    installer_path: &Path,
+) -> Result<(InstallProfile, serde_json::Value), String> {
+    let mut archive = open_installer(installer_path)?;
+    let profile_data = read_entry(&mut archive, "install_profile.json")?;
+    let raw: serde_json::Value =
+        serde_json::from_slice(&profile_data).map_err(|e| e.to_string())?;
+    if raw.get("install").is_some() || raw.get("versionInfo").is_some() {
+        return Err("Legacy Forge installer format (no processors)".to_string());
+    }
+    let profile: InstallProfile = serde_json::from_value(raw).map_err(|e| e.to_string())?;
+    let version_json = serde_json::from_slice(&read_entry(&mut archive, "version.json")?)
+        .map_err(|e| e.to_string())?;
+    Ok((profile, version_json))
+}
+
+fn library_path(coord: &str, libraries_dir: &Path) -> Result<PathBuf, String> {
+    MavenCoordinate::parse(coord)
+        .map(|c| c.to_local_path(libraries_dir))
+        .ok_or_else(|| format!("Invalid Maven coordinate: {}", coord))
+}
+
+/// Download tasks for the libraries the processors need. Libraries without a
+/// download URL ship inside the installer under `maven/` and are extracted
+/// directly.
+pub fn prepare_libraries(
+    installer_path: &Path,
+    profile: &InstallProfile,
+    libraries_dir: &Path,
+) -> Result<Vec<DownloadTask>, String> {
+    let mut archive = open_installer(installer_path)?;
+    let mut tasks = Vec::new();
+    for lib in &profile.libraries {
+        let artifact = lib.downloads.as_ref().and_then(|d| d.artifact.as_ref());
+        let rel_path = match artifact.and_then(|a| a.path.clone()) {
+            Some(path) => path,
+            None => MavenCoordinate::parse(&lib.name)
+                .ok_or_else(|| format!("Invalid Maven coordinate: {}", lib.name))?
+                .to_path(),
+        };
+        let dest = libraries_dir.join(&rel_path);
+        match artifact
+            .and_then(|a| a.url.clone())
+            .filter(|u| !u.is_empty())
+        {
+            Some(url) => tasks.push(DownloadTask {
+                url,
+                path: dest,
+                sha1: artifact.and_then(|a| a.sha1.clone()),
+                sha256: None,
+            }),
+            None => {
+                if let Ok(data) = read_entry(&mut archive, &format!("maven/{}", rel_path)) {
+                    if let Some(parent) = dest.parent() {
+                        std::fs::create_dir_all(parent).map_err(|e| e.to_string())?;
+                    }
+                    std::fs::write(&dest, data).map_err(|e| e.to_string())?;
+                } else {
+                    // Not embedded: fall back to the loader's Maven
+                    let maven = if lib.name.starts_with("net.neoforged") {
+                        NEOFORGE_MAVEN
+                    } else {
+                        FORGE_MAVEN
+                    };
+                    tasks.push(DownloadTask {
+                        url: format!("{}{}", maven, rel_path),
+                        path: dest,
+                        sha1: artifact.and_then(|a| a.sha1.clone()),
+                        sha256: None,
+                    });
+                }
+            }
+        }
+    }
+    Ok(tasks)
+}
+
+/// Download tasks for everything the official installer would fetch itself:
+/// the profile's libraries and the downloadable ones of the version JSON it
+/// installs. Once they are in place the installer can run with `--offline`.
+pub fn installer_library_tasks(
+    installer_path: &Path,
+    libraries_dir: &Path,
+) -> Result<Vec<DownloadTask>, String> {
+    let (mut profile, version_json) = read_installer(installer_path)?;
+    let version_libraries: Vec<ProfileLibrary> = match version_json.get("libraries") {
+        Some(libraries) => serde_json::from_value(libraries.clone()).map_err(|e| e.to_string())?,
+        None => Vec::new(),
+    };
+    // Entries without a URL (the loader's own jars) are produced or
+    // extracted by the installer
+    profile
+        .libraries
+        .extend(version_libraries.into_iter().filter(|lib| {
+            lib.downloads
+                .as_ref()
+                .and_then(|d| d.artifact.as_ref())
+                .and_then(|a| a.url.as_deref())
+                .is_some_and(|url| !url.is_empty())
+        }));
+    let mut tasks = prepare_libraries(installer_path, &profile, libraries_dir)?;
+    tasks.sort_by(|a, b| a.path.cmp(&b.path));
+    tasks.dedup_by(|a, b| a.path == b.path);
+    Ok(tasks)
+}
+
+/// Resolve the client-side `data` entries into plain values: Maven
+/// references become library paths and `/data/...` entries are extracted
+/// into the work dir.
+fn resolve_data(
+    profile: &InstallProfile,
+    ctx: &ProcessorContext,
+) -> Result<HashMap<String, String>, String> {
+    let mut archive = open_installer(&ctx.installer_path)?;
+    let mut data = HashMap::new();
+    for (key, value) in &profile.data {
+        let value = &value.client;
+        let resolved = if let Some(coord) =
+            value.strip_prefix('[').and_then(|v| v.strip_suffix(']'))
+        {
+            library_path(coord, &ctx.libraries_dir)?
+                .to_string_lossy()
+                .to_string()
+        } else if let Some(literal) = value.strip_prefix('\'').and_then(|v| v.strip_suffix('\'')) {
+            literal.to_string()
+        } else if let Some(entry) = value.strip_prefix('/') {
+            let dest = ctx.work_dir.join(entry);
+            if let Some(parent) = dest.parent() {
+                std::fs::create_dir_all(parent).map_err(|e| e.to_string())?;
+            }
+            std::fs::write(&dest, read_entry(&mut archive, entry)?).map_err(|e| e.to_string())?;
+            dest.to_string_lossy().to_string()
+        } else {
+            value.clone()
+        };
+        data.insert(key.clone(), resolved);
+    }
+
+    let builtins = [
+        ("SIDE", "client".to_string()),
+        (
+            "MINECRAFT_JAR",
+            ctx.minecraft_jar.to_string_lossy().to_string(),
+        ),
+        ("MINECRAFT_VERSION", profile.minecraft.clone()),
+        ("ROOT", ctx.game_dir.to_string_lossy().to_string()),
+        (
+            "INSTALLER",
+            ctx.installer_path.to_string_lossy().to_string(),
+        ),
+        (
+            "LIBRARY_DIR",
+            ctx.libraries_dir.to_string_lossy().to_string(),
+        ),
+    ];
+    for (key, value) in builtins {
+        data.insert(key.to_string(), value);
+    }
+    Ok(data)
+}
+
+/// Substitute one processor argument: `[coord]` is a library path, `{KEY}`
+/// a data value; anything else is passed through.
+pub fn resolve_arg(
+    arg: &str,
+    data: &HashMap<String, String>,
+    libraries_dir: &Path,
+) -> Result<String, String> {
+    if let Some(coord) = arg.strip_prefix('[').and_then(|a| a.strip_suffix(']')) {
+        return Ok(library_path(coord, libraries_dir)?
+            .to_string_lossy()
+            .to_string());
+    }
+    if let Some(key) = arg.strip_prefix('{').and_then(|a| a.strip_suffix('}')) {
+        return data
+            .get(key)
+            .cloned()
+            .ok_or_else(|| format!("Unknown processor data key: {}", key));
+    }
+    Ok(arg.to_string())
+}
+
+/// `Main-Class` from a jar's manifest
+fn main_class(jar: &Path) -> Result<String, String> {
+    let mut archive = open_installer(jar)?;
+    let manifest =
+        String::from_utf8_lossy(&read_entry(&mut archive, "META-INF/MANIFEST.MF")?).to_string();
+    manifest
+        .lines()
+        .find_map(|line| line.strip_prefix("Main-Class:"))
+        .map(|class| class.trim().to_string())
+        .ok_or_else(|| format!("{} has no Main-Class", jar.display()))
+}
+
+/// Whether every declared output exists with the expected SHA1
+fn outputs_valid(
+    processor: &Processor,
+    data: &HashMap<String, String>,
+    libraries_dir: &Path,
+) -> bool {
+    !processor.outputs.is_empty()
+        && processor.outputs.iter().all(|(file, sha1)| {
+            let (Ok(file), Ok(sha1)) = (
+                resolve_arg(file, data, libraries_dir),
+                resolve_arg(sha1, data, libraries_dir),
+            ) else {
+                return false;
+            };
+            std::fs::read(&file).is_ok_and(|content| {
+                compute_sha1(&content).eq_ignore_ascii_case(sha1.trim_matches('\''))
+            })
+        })
+}
+
+/// Run the client-side processors. `log` receives one line per step.
+pub async fn run_processors(
+    profile: &InstallProfile,
+    ctx: &ProcessorContext,
+    java_path: &Path,
+    log: impl Fn(String),
+) -> Result<(), String> {
+    let data = resolve_data(profile, ctx)?;
+    let cp_separator = if cfg!(windows) { ";" } else { ":" };
+
+    for (index, processor) in profile.processors.iter().enumerate() {
+        if processor
+            .sides
+            .as_ref()
+            .is_some_and(|sides| !sides.iter().any(|s| s == "client"))
+        {
+            continue;
+        }
+        if outputs_valid(processor, &data, &ctx.libraries_dir) {
+            log(format!(
+                "Processor {} ({}) is up to date",
+                index + 1,
+                processor.jar
+            ));
+            continue;
+        }
+
+        let jar = library_path(&processor.jar, &ctx.libraries_dir)?;
+        let mut classpath = vec![jar.to_string_lossy().to_string()];
+        for entry in &processor.classpath {
+            classpath.push(
+                library_path(entry, &ctx.libraries_dir)?
+                    .to_string_lossy()
+                    .to_string(),
+            );
+        }
+        let args = processor
+            .args
+            .iter()
+            .map(|arg| resolve_arg(arg, &data, &ctx.libraries_dir))
+            .collect::<Result<Vec<_>, _>>()?;
+
+        log(format!(
+            "Running processor {} ({})",
+            index + 1,
+            processor.jar
+        ));
+        let mut cmd = tokio::process::Command::new(java_path);
+        cmd.arg("-cp")
+            .arg(classpath.join(cp_separator))
+            .arg(main_class(&jar)?)
+            .args(&args)
+            .current_dir(&ctx.work_dir);
+        #[cfg(target_os = "windows")]
+        cmd.creation_flags(0x08000000);
+
+        let output = cmd.output().await.map_err(|e| e.to_string())?;
+        if !output.status.success() {
+            return Err(format!(
+                "Processor {} failed:\n{}",
+                processor.jar,
+                String::from_utf8_lossy(&output.stderr)
+            ));
+        }
+        if !processor.outputs.is_empty() && !outputs_valid(processor, &data, &ctx.libraries_dir) {
+            return Err(format!(
+                "Processor {} produced unexpected outputs",
+                processor.jar
+            ));
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_resolve_arg() {
+        let libraries = Path::new("/libs");
+        let data = HashMap::from([("SIDE".to_string(), "client".to_string())]);
+        assert_eq!(resolve_arg("--side", &data, libraries).unwrap(), "--side");
+        assert_eq!(resolve_arg("{SIDE}", &data, libraries).unwrap(), "client");
+        assert!(resolve_arg("{MISSING}", &data, libraries).is_err());
+        assert_eq!(
+            PathBuf::from(
+                resolve_arg(
+                    "[de.oceanlabs.mcp:mcp_config:1.20.1-20230612.114412:mappings@txt]",
+                    &data,
+                    libraries
+                )
+                .unwrap()
+            ),
+            libraries.join(
+                "de/oceanlabs/mcp/mcp_config/1.20.1-20230612.114412/mcp_config-1.20.1-20230612.114412-mappings.txt"
+            )
+        );
+    }
+
+    #[test]
+    fn test_parse_install_profile() {
+        let profile: InstallProfile = serde_json::from_str(
+            r#"{"spec": 1, "profile": "forge", "version": "1.20.1-forge-47.2.0",
+                "minecraft": "1.20.1", "json": "/version.json",
+                "data": {"MAPPINGS": {"client": "[de.oceanlabs.mcp:mcp_config:1.20.1:mappings@txt]",
+                                      "server": "[de.oceanlabs.mcp:mcp_config:1.20.1:mappings@txt]"}},
+                "processors": [
+                    {"sides": ["server"], "jar": "net.minecraftforge:installertools:1.3.0", "args": []},
+                    {"jar": "net.minecraftforge:binarypatcher:1.1.1",
+                     "classpath": ["net.sf.jopt-simple:jopt-simple:6.0-alpha-3"],
+                     "args": ["--clean", "{MINECRAFT_JAR}"],
+                     "outputs": {"{PATCHED}": "{PATCHED_SHA}"}}
+                ],
+                "libraries": []}"#,
+        )
+        .unwrap();
+        assert_eq!(profile.processors.len(), 2);
+        assert_eq!(
+            profile.processors[0].sides.as_deref(),
+            Some(&["server".to_string()][..])
+        );
+        assert_eq!(profile.processors[1].outputs.len(), 1);
+    }
+
+    #[test]
+    fn test_installer_library_tasks() {
+        use std::io::Write;
+
+        let dir = std::env::temp_dir().join(format!("dropout-installer-{}", uuid::Uuid::new_v4()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let installer = dir.join("installer.jar");
+        let mut jar = zip::ZipWriter::new(std::fs::File::create(&installer).unwrap());
+        let options = zip::write::SimpleFileOptions::default();
+        jar.start_file("install_profile.json", options).unwrap();
+        jar.write_all(
+            br#"{"version": "1.20.1-forge-47.2.0", "minecraft": "1.20.1",
+                "libraries": [{"name": "net.minecraftforge:binarypatcher:1.1.1",
+                    "downloads": {"artifact": {"path": "net/minecraftforge/binarypatcher/1.1.1/binarypatcher-1.1.1.jar",
+                        "url": "https://maven.minecraftforge.net/net/minecraftforge/binarypatcher/1.1.1/binarypatcher-1.1.1.jar",
+                        "sha1": "abc"}}}]}"#,
+        )
+        .unwrap();
+        jar.start_file("version.json", options).unwrap();
+        jar.write_all(
+            br#"{"id": "1.20.1-forge-47.2.0", "libraries": [
+                {"name": "net.minecraftforge:forge:1.20.1-47.2.0:universal",
+                    "downloads": {"artifact": {"path": "net/minecraftforge/forge/1.20.1-47.2.0/forge-1.20.1-47.2.0-universal.jar", "url": ""}}},
+                {"name": "net.minecraftforge:binarypatcher:1.1.1",
+                    "downloads": {"artifact": {"path": "net/minecraftforge/binarypatcher/1.1.1/binarypatcher-1.1.1.jar",
+                        "url": "https://maven.minecraftforge.net/net/minecraftforge/binarypatcher/1.1.1/binarypatcher-1.1.1.jar"}}},
+                {"name": "cpw.mods:securejarhandler:2.1.10",
+                    "downloads": {"artifact": {"path": "cpw/mods/securejarhandler/2.1.10/securejarhandler-2.1.10.jar",
+                        "url": "https://maven.minecraftforge.net/cpw/mods/securejarhandler/2.1.10/securejarhandler-2.1.10.jar"}}}]}"#,
+        )
+        .unwrap();
+        jar.finish().unwrap();
+
+        let libraries = dir.join("libraries");
+        let tasks = installer_library_tasks(&installer, &libraries).unwrap();
+        let paths: Vec<_> = tasks.iter().map(|t| t.path.clone()).collect();
+        assert_eq!(
+            paths,
+            vec![
+                libraries.join("cpw/mods/securejarhandler/2.1.10/securejarhandler-2.1.10.jar"),
+                libraries.join("net/minecraftforge/binarypatcher/1.1.1/binarypatcher-1.1.1.jar"),
+            ]
+        );
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+}
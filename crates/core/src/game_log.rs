@@ -0,0 +1,189 @@
+//! Parser for the game's console output.
+//!
+//! Log4j console lines look like
+//! - `[12:34:56] [Render thread/INFO]: Message` (vanilla)
+//! - `[12:34:56] [main/INFO] (FabricLoader/GameProvider) Message` (Fabric)
+//! - `[20Jan2024 12:34:56.789] [main/INFO] [cpw.mods.modlauncher.Launcher/MODLAUNCHER]: Message` (Forge)
+//!
+//! Lines that don't match (stack traces, output of old versions, the JVM's
+//! own messages) are continuations of the previous line when indented or
+//! part of a stack trace, and otherwise get a level from their stream.
+
+use serde::{Deserialize, Serialize};
+use ts_rs::TS;
+
+/// Payload of the `game-log` event
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export, export_to = "dropout-core/game_log.ts")]
+pub struct GameLogLine {
+    pub time: Option<String>,
+    pub thread: Option<String>,
+    pub level: String, // "trace" | "debug" | "info" | "warn" | "error" | "fatal"
+    pub logger: Option<String>,
+    pub message: String,
+    pub stderr: bool,
+}
+
+fn normalize_level(level: &str) -> Option<&'static str> {
+    Some(match level.to_ascii_uppercase().as_str() {
+        "TRACE" | "FINEST" | "FINER" => "trace",
+        "DEBUG" | "FINE" => "debug",
+        "INFO" => "info",
+        "WARN" | "WARNING" => "warn",
+        "ERROR" | "SEVERE" => "error",
+        "FATAL" => "fatal",
+        _ => return None,
+    })
+}
+
+/// `[content]` at the start of `s`, and what follows it
+fn bracketed(s: &str) -> Option<(&str, &str)> {
+    let rest = s.strip_prefix('[')?;
+    let end = rest.find(']')?;
+    Some((&rest[..end], &rest[end + 1..]))
+}
+
+/// Parse one Log4j-formatted line; `None` if it isn't one
+pub fn parse_line(line: &str, stderr: bool) -> Option<GameLogLine> {
+    let (time, rest) = bracketed(line)?;
+    if !time.contains(':') {
+        return None;
+    }
+    let (thread_level, rest) = bracketed(rest.trim_start())?;
+    let (thread, level) = thread_level.rsplit_once('/')?;
+    let level = normalize_level(level)?;
+    let rest = rest.trim_start();
+
+    let (logger, message) = if let Some(inner) = rest.strip_prefix('(') {
+        // Fabric: "(Logger) message"
+        let (logger, message) = inner.split_once(')')?;
+        (Some(logger.to_string()), message.trim_start())
+    } else if let Some((logger, after)) = bracketed(rest) {
+        // Forge: "[Logger/MARKER]: message"
+        let message = after.strip_prefix(':').unwrap_or(after).trim_start();
+        (Some(logger.to_string()), message)
+    } else {
+        (None, rest.strip_prefix(':').unwrap_or(rest).trim_start())
+    };
+    Some(GameLogLine {
+        time: Some(time.to_string()),
+        thread: Some(thread.to_string()),
+        level: level.to_string(),
+        logger,
+        message: message.to_string(),
+        stderr,
+    })
+}
+
+fn is_continuation(line: &str) -> bool {
+    line.starts_with([' ', '\t'])
+        || line.starts_with("Caused by: ")
+        || line.starts_with("Suppressed: ")
+}
+
+/// Stateful parser for one output stream, so that stack traces keep the
+/// level and source of the line that started them
+#[derive(Debug, Default)]
+pub struct GameLogParser {
+    last: Option<GameLogLine>,
+}
+
+impl GameLogParser {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn parse(&mut self, line: &str, stderr: bool) -> GameLogLine {
+        let parsed = match parse_line(line, stderr) {
+            Some(parsed) => parsed,
+            None => match &self.last {
+                Some(last) if is_continuation(line) => GameLogLine {
+                    message: line.to_string(),
+                    stderr,
+                    ..last.clone()
+                },
+                _ => GameLogLine {
+                    time: None,
+                    thread: None,
+                    level: if stderr { "error" } else { "info" }.to_string(),
+                    logger: None,
+                    message: line.to_string(),
+                    stderr,
+                },
+            },
+        };
+        self.last = Some(parsed.clone());
+        parsed
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_formats() {
+        let vanilla = parse_line(
+            "[12:34:56] [Render thread/INFO]: Setting user: Steve",
+            false,
+        );
+        assert_eq!(
+            vanilla,
+            Some(GameLogLine {
+                time: Some("12:34:56".to_string()),
+                thread: Some("Render thread".to_string()),
+                level: "info".to_string(),
+                logger: None,
+                message: "Setting user: Steve".to_string(),
+                stderr: false,
+            })
+        );
+
+        let fabric = parse_line(
+            "[12:34:56] [main/WARN] (FabricLoader/Mixin) Reference map 'x.refmap.json' could not be read",
+            false,
+        )
+        .unwrap();
+        assert_eq!(fabric.thread.as_deref(), Some("main"));
+        assert_eq!(fabric.level, "warn");
+        assert_eq!(fabric.logger.as_deref(), Some("FabricLoader/Mixin"));
+        assert!(fabric.message.starts_with("Reference map"));
+
+        let forge = parse_line(
+            "[20Jan2024 12:34:56.789] [main/INFO] [cpw.mods.modlauncher.Launcher/MODLAUNCHER]: ModLauncher running",
+            false,
+        )
+        .unwrap();
+        assert_eq!(forge.time.as_deref(), Some("20Jan2024 12:34:56.789"));
+        assert_eq!(
+            forge.logger.as_deref(),
+            Some("cpw.mods.modlauncher.Launcher/MODLAUNCHER")
+        );
+        assert_eq!(forge.message, "ModLauncher running");
+
+        assert!(parse_line("Exception in thread \"main\" java.lang.Error", true).is_none());
+        assert!(parse_line("[LWJGL] GLFW_PLATFORM_ERROR", true).is_none());
+        assert!(parse_line("[12:00:00] [main/VERBOSE]: x", false).is_none());
+    }
+
+    #[test]
+    fn test_stack_traces_inherit_level() {
+        let mut parser = GameLogParser::new();
+        let first = parser.parse(
+            "[12:34:56] [Worker-Main-1/ERROR]: Failed to load texture",
+            false,
+        );
+        assert_eq!(first.level, "error");
+        let trace = parser.parse("\tat net.minecraft.client.Foo.bar(Foo.java:10)", false);
+        assert_eq!(trace.level, "error");
+        assert_eq!(trace.thread.as_deref(), Some("Worker-Main-1"));
+        let cause = parser.parse("Caused by: java.io.IOException", false);
+        assert_eq!(cause.level, "error");
+
+        let plain = parser.parse("OpenJDK 64-Bit Server VM warning: x", true);
+        assert_eq!(plain.level, "error");
+        assert_eq!(plain.thread, None);
+        assert_eq!(parser.parse("plain output", false).level, "info");
+    }
+}
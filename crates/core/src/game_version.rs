@@ -4,7 +4,7 @@ use ts_rs::TS;
 /// Represents a Minecraft version JSON, supporting both vanilla and modded (Fabric/Forge) formats.
 /// Modded versions use `inheritsFrom` to reference a parent vanilla version.
 #[derive(Debug, Deserialize, Serialize, Clone, TS)]
-#[ts(export, export_to = "game-version.ts")]
+#[ts(export, export_to = "dropout-core/game-version.ts")]
 pub struct GameVersion {
     pub id: String,
     /// Optional for mod loaders that inherit from vanilla
@@ -28,17 +28,44 @@ pub struct GameVersion {
     /// Release type (release, snapshot, old_beta, etc.)
     #[serde(rename = "type")]
     pub version_type: Option<String>,
+    /// Log4j configuration for the client (patched by Mojang against Log4Shell)
+    pub logging: Option<Logging>,
 }
 
 #[derive(Debug, Deserialize, Serialize, Clone, TS)]
-#[ts(export, export_to = "game-version.ts")]
+#[ts(export, export_to = "dropout-core/game-version.ts")]
+pub struct Logging {
+    pub client: Option<LoggingConfig>,
+}
+
+#[derive(Debug, Deserialize, Serialize, Clone, TS)]
+#[ts(export, export_to = "dropout-core/game-version.ts")]
+pub struct LoggingConfig {
+    /// JVM argument with a `${path}` placeholder for the config file
+    pub argument: String,
+    pub file: LoggingFile,
+    #[serde(rename = "type")]
+    pub config_type: Option<String>,
+}
+
+#[derive(Debug, Deserialize, Serialize, Clone, TS)]
+#[ts(export, export_to = "dropout-core/game-version.ts")]
+pub struct LoggingFile {
+    pub id: String,
+    pub sha1: Option<String>,
+    pub size: Option<u64>,
+    pub url: String,
+}
+
+#[derive(Debug, Deserialize, Serialize, Clone, TS)]
+#[ts(export, export_to = "dropout-core/game-version.ts")]
 pub struct Downloads {
     pub client: DownloadArtifact,
     pub server: Option<DownloadArtifact>,
 }
 
 #[derive(Debug, Deserialize, Serialize, Clone, TS)]
-#[ts(export, export_to = "game-version.ts")]
+#[ts(export, export_to = "dropout-core/game-version.ts")]
 pub struct DownloadArtifact {
     pub sha1: Option<String>,
     pub size: Option<u64>,
@@ -47,7 +74,7 @@ pub struct DownloadArtifact {
 }
 
 #[derive(Debug, Deserialize, Serialize, Clone, TS)]
-#[ts(export, export_to = "game-version.ts")]
+#[ts(export, export_to = "dropout-core/game-version.ts")]
 pub struct AssetIndex {
     pub id: String,
     pub sha1: String,
@@ -58,7 +85,7 @@ pub struct AssetIndex {
 }
 
 #[derive(Debug, Deserialize, Serialize, Clone, TS)]
-#[ts(export, export_to = "game-version.ts")]
+#[ts(export, export_to = "dropout-core/game-version.ts")]
 pub struct Library {
     pub downloads: Option<LibraryDownloads>,
     pub name: String,
@@ -67,10 +94,18 @@ pub struct Library {
     pub natives: Option<serde_json::Value>,
     /// Maven repository URL for mod loader libraries
     pub url: Option<String>,
+    /// Entries to skip when extracting natives (LWJGL 2 era)
+    pub extract: Option<ExtractRules>,
+}
+
+#[derive(Debug, Deserialize, Serialize, Clone, TS)]
+#[ts(export, export_to = "dropout-core/game-version.ts")]
+pub struct ExtractRules {
+    pub exclude: Option<Vec<String>>, // path prefixes, e.g. "META-INF/"
 }
 
 #[derive(Debug, Deserialize, Serialize, Clone, TS)]
-#[ts(export, export_to = "game-version.ts")]
+#[ts(export, export_to = "dropout-core/game-version.ts")]
 pub struct Rule {
     pub action: String, // "allow" or "disallow"
     pub os: Option<OsRule>,
@@ -79,7 +114,7 @@ pub struct Rule {
 }
 
 #[derive(Debug, Deserialize, Serialize, Clone, TS)]
-#[ts(export, export_to = "game-version.ts")]
+#[ts(export, export_to = "dropout-core/game-version.ts")]
 pub struct OsRule {
     pub name: Option<String>,    // "linux", "osx", "windows"
     pub version: Option<String>, // Regex
@@ -87,7 +122,7 @@ pub struct OsRule {
 }
 
 #[derive(Debug, Deserialize, Serialize, Clone, TS)]
-#[ts(export, export_to = "game-version.ts")]
+#[ts(export, export_to = "dropout-core/game-version.ts")]
 pub struct LibraryDownloads {
     pub artifact: Option<DownloadArtifact>,
     #[ts(type = "Record<string, unknown>")]
@@ -95,7 +130,7 @@ pub struct LibraryDownloads {
 }
 
 #[derive(Debug, Deserialize, Serialize, Clone, TS)]
-#[ts(export, export_to = "game-version.ts")]
+#[ts(export, export_to = "dropout-core/game-version.ts")]
 pub struct Arguments {
     #[ts(type = "Record<string, unknown>")]
     pub game: Option<serde_json::Value>,
@@ -104,7 +139,7 @@ pub struct Arguments {
 }
 
 #[derive(Debug, Deserialize, Serialize, Clone, TS)]
-#[ts(export, export_to = "game-version.ts")]
+#[ts(export, export_to = "dropout-core/game-version.ts")]
 pub struct JavaVersion {
     pub component: String,
     #[serde(rename = "majorVersion")]
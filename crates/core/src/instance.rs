@@ -5,18 +5,20 @@
 //! - Each instance has its own versions, libraries, assets, mods, and saves
 //! - Support for instance switching and isolation
 
+use crate::agents::JavaAgent;
+use crate::dot_minecraft::{self, DotMinecraftImport};
+use crate::sandbox::SandboxSettings;
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 use std::fs;
 use std::path::{Path, PathBuf};
 use std::sync::Mutex;
-use tauri::{AppHandle, Manager};
 use ts_rs::TS;
 
 /// Represents a game instance/profile
 #[derive(Debug, Clone, Serialize, Deserialize, TS)]
 #[serde(rename_all = "camelCase")]
-#[ts(export, export_to = "instance.ts")]
+#[ts(export, export_to = "dropout-core/instance.ts")]
 pub struct Instance {
     pub id: String,                         // 唯一标识符（UUID）
     pub name: String,                       // 显示名称
@@ -26,54 +28,131 @@ pub struct Instance {
     pub last_played: Option<i64>,           // 最后游玩时间
     pub icon_path: Option<String>,          // 图标路径（可选）
     pub notes: Option<String>,              // 备注（可选）
-    pub mod_loader: Option<String>,         // 模组加载器类型："fabric", "forge", "vanilla"
+    pub mod_loader: Option<String>, // 模组加载器类型："fabric", "forge", "neoforge", "vanilla"
     pub mod_loader_version: Option<String>, // 模组加载器版本
-    pub jvm_args_override: Option<String>,  // JVM参数覆盖（可选）
+    pub jvm_args_override: Option<String>, // JVM参数覆盖（可选）
     #[serde(default)]
     pub memory_override: Option<MemoryOverride>, // 内存设置覆盖（可选）
     pub java_path_override: Option<String>, // 实例级Java路径覆盖（可选）
+    #[serde(default)]
+    pub default_account_uuid: Option<String>, // 启动时使用的账户（为空则使用当前账户）
+    #[serde(default)]
+    pub sandbox: SandboxSettings, // 游戏进程沙箱设置
+    #[serde(default)]
+    pub java_agents: Vec<JavaAgent>, // Java agent 列表（-javaagent）
+    #[serde(default)]
+    pub pinned_versions: Vec<String>, // 置顶的版本ID
+    #[serde(default)]
+    pub game_settings: GameSettings, // 窗口大小与初始语言
+    #[serde(default)]
+    pub external_game_dir: bool, // 游戏目录不归启动器管理（如引用的 .minecraft），删除实例时保留
+    #[serde(default)]
+    pub status: InstanceStatus, // 完整性检查结果（启动器启动时计算）
+    #[serde(default)]
+    pub auto_update_mods: bool, // 启动前自动应用安全的模组更新
+    #[serde(default)]
+    pub download_threads: Option<u32>, // 实例级下载线程数覆盖（可选）
+    #[cfg(feature = "modpacks")]
+    #[serde(default)]
+    pub pack: Option<super::modpack::InstalledPack>, // 安装的整合包（用于增量更新）
+    #[cfg(not(feature = "modpacks"))]
+    #[serde(default)]
+    #[ts(type = "unknown")]
+    pub pack: Option<serde_json::Value>, // 原样保留，避免保存实例时丢失
+}
+
+/// Integrity of an instance's directory; broken instances can be inspected
+/// but not launched
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, TS)]
+#[serde(rename_all = "kebab-case")]
+#[ts(export, export_to = "dropout-core/instance.ts")]
+pub enum InstanceStatus {
+    #[default]
+    Ok,
+    MissingDir, // the game directory is gone
+    Partial,    // the selected modded version's files are gone
 }
 
 /// Memory settings override for an instance
 #[derive(Debug, Clone, Serialize, Deserialize, TS)]
 #[serde(rename_all = "camelCase")]
-#[ts(export, export_to = "instance.ts")]
+#[ts(export, export_to = "dropout-core/instance.ts")]
 pub struct MemoryOverride {
     pub min: u32, // MB
     pub max: u32, // MB
 }
 
+/// Window and language settings passed to the game of an instance
+#[derive(Debug, Clone, Default, Serialize, Deserialize, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export, export_to = "dropout-core/instance.ts")]
+pub struct GameSettings {
+    pub width: Option<u32>,  // --width, unset = game default
+    pub height: Option<u32>, // --height
+    #[serde(default)]
+    pub fullscreen: bool, // --fullscreen
+    pub language: Option<String>, // e.g. "de_de", written to options.txt on first launch
+}
+
 /// Configuration for all instances
 #[derive(Debug, Clone, Serialize, Deserialize, Default, TS)]
 #[serde(rename_all = "camelCase")]
-#[ts(export, export_to = "instance.ts")]
+#[ts(export, export_to = "dropout-core/instance.ts")]
 pub struct InstanceConfig {
     pub instances: Vec<Instance>,
     pub active_instance_id: Option<String>, // 当前活动的实例ID
 }
 
+/// Folders preserved by `delete_instance` when asked to keep the saves
+pub const KEPT_DIRS: &[&str] = &["saves", "screenshots"];
+
+/// What deleting an instance would remove, for the confirmation dialog
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export, export_to = "dropout-core/instance.ts")]
+pub struct DeletePreview {
+    pub total_bytes: u64,
+    pub worlds: Vec<String>, // folder names under saves/
+    pub screenshot_count: usize,
+    pub external_game_dir: bool, // the directory is kept anyway
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export, export_to = "dropout-core/instance.ts")]
+pub struct DeletedInstance {
+    pub deleted_bytes: u64,
+    pub kept_path: Option<String>, // where saves/ and screenshots/ were moved
+}
+
 /// State management for instances
 pub struct InstanceState {
     pub instances: Mutex<InstanceConfig>,
     pub file_path: PathBuf,
+    /// Launcher data directory; new instances go under `instances/` there
+    pub data_dir: PathBuf,
 }
 
 impl InstanceState {
     /// Create a new InstanceState
-    pub fn new(app_handle: &AppHandle) -> Self {
-        let app_dir = app_handle.path().app_data_dir().unwrap();
-        let file_path = app_dir.join("instances.json");
+    pub fn new(data_dir: &Path) -> Self {
+        let file_path = data_dir.join("instances.json");
 
-        let config = if file_path.exists() {
+        let mut config = if file_path.exists() {
             let content = fs::read_to_string(&file_path).unwrap_or_default();
             serde_json::from_str(&content).unwrap_or_else(|_| InstanceConfig::default())
         } else {
             InstanceConfig::default()
         };
+        let shared_versions_dir = data_dir.join("versions");
+        for instance in &mut config.instances {
+            instance.status = check_integrity(instance, &shared_versions_dir);
+        }
 
         Self {
             instances: Mutex::new(config),
             file_path,
+            data_dir: data_dir.to_path_buf(),
         }
     }
 
@@ -87,14 +166,9 @@ impl InstanceState {
     }
 
     /// Create a new instance
-    pub fn create_instance(
-        &self,
-        name: String,
-        app_handle: &AppHandle,
-    ) -> Result<Instance, String> {
-        let app_dir = app_handle.path().app_data_dir().unwrap();
+    pub fn create_instance(&self, name: String) -> Result<Instance, String> {
         let instance_id = uuid::Uuid::new_v4().to_string();
-        let instance_dir = app_dir.join("instances").join(&instance_id);
+        let instance_dir = self.data_dir.join("instances").join(&instance_id);
         let game_dir = instance_dir.clone();
 
         // Create instance directory structure
@@ -120,6 +194,16 @@ impl InstanceState {
             jvm_args_override: None,
             memory_override: None,
             java_path_override: None,
+            default_account_uuid: None,
+            sandbox: SandboxSettings::default(),
+            java_agents: Vec::new(),
+            pinned_versions: Vec::new(),
+            game_settings: GameSettings::default(),
+            external_game_dir: false,
+            status: InstanceStatus::Ok,
+            auto_update_mods: false,
+            download_threads: None,
+            pack: None,
         };
 
         let mut config = self.instances.lock().unwrap();
@@ -136,8 +220,30 @@ impl InstanceState {
         Ok(instance)
     }
 
-    /// Delete an instance
-    pub fn delete_instance(&self, id: &str) -> Result<(), String> {
+    /// Delete an instance. With `keep_dir`, its worlds and screenshots are
+    /// first moved into a new folder there.
+    pub fn delete_instance(
+        &self,
+        id: &str,
+        keep_dir: Option<&Path>,
+    ) -> Result<DeletedInstance, String> {
+        let instance = self
+            .get_instance(id)
+            .ok_or_else(|| format!("Instance {} not found", id))?;
+        let owns_dir = !instance.external_game_dir && instance.game_dir.exists();
+
+        // Move the kept data out before anything is removed, so a failure
+        // leaves the instance intact
+        let kept_path = match keep_dir {
+            Some(keep_dir) if owns_dir => keep_user_data(&instance, keep_dir)?,
+            _ => None,
+        };
+        let deleted_bytes = if owns_dir {
+            dir_size(&instance.game_dir)
+        } else {
+            0
+        };
+
         let mut config = self.instances.lock().unwrap();
 
         // Find the instance
@@ -147,8 +253,6 @@ impl InstanceState {
             .position(|i| i.id == id)
             .ok_or_else(|| format!("Instance {} not found", id))?;
 
-        let instance = config.instances[instance_index].clone();
-
         // Remove from list
         config.instances.remove(instance_index);
 
@@ -160,13 +264,16 @@ impl InstanceState {
         drop(config);
         self.save()?;
 
-        // Delete the instance directory
-        if instance.game_dir.exists() {
+        // Delete the instance directory, unless it belongs to another launcher
+        if owns_dir {
             fs::remove_dir_all(&instance.game_dir)
                 .map_err(|e| format!("Failed to delete instance directory: {}", e))?;
         }
 
-        Ok(())
+        Ok(DeletedInstance {
+            deleted_bytes,
+            kept_path: kept_path.map(|p| p.to_string_lossy().to_string()),
+        })
     }
 
     /// Update an instance
@@ -224,29 +331,36 @@ impl InstanceState {
             .cloned()
     }
 
+    /// Re-run the integrity check of an instance and store its status
+    pub fn refresh_status(
+        &self,
+        id: &str,
+        shared_versions_dir: &Path,
+    ) -> Result<InstanceStatus, String> {
+        let mut config = self.instances.lock().unwrap();
+        let instance = config
+            .instances
+            .iter_mut()
+            .find(|i| i.id == id)
+            .ok_or_else(|| format!("Instance {} not found", id))?;
+        instance.status = check_integrity(instance, shared_versions_dir);
+        Ok(instance.status)
+    }
+
     /// Get the game directory for an instance
     pub fn get_instance_game_dir(&self, id: &str) -> Option<PathBuf> {
         self.get_instance(id).map(|i| i.game_dir)
     }
 
     /// Duplicate an instance
-    pub fn duplicate_instance(
-        &self,
-        id: &str,
-        new_name: String,
-        app_handle: &AppHandle,
-    ) -> Result<Instance, String> {
+    pub fn duplicate_instance(&self, id: &str, new_name: String) -> Result<Instance, String> {
         let source_instance = self
             .get_instance(id)
             .ok_or_else(|| format!("Instance {} not found", id))?;
 
         // Prepare new instance metadata (but don't save yet)
         let new_id = uuid::Uuid::new_v4().to_string();
-        let instances_dir = app_handle
-            .path()
-            .app_data_dir()
-            .map_err(|e| e.to_string())?
-            .join("instances");
+        let instances_dir = self.data_dir.join("instances");
         let new_game_dir = instances_dir.join(&new_id);
 
         // Copy directory FIRST - if this fails, don't create metadata
@@ -277,12 +391,247 @@ impl InstanceState {
             jvm_args_override: source_instance.jvm_args_override.clone(),
             memory_override: source_instance.memory_override.clone(),
             java_path_override: source_instance.java_path_override.clone(),
+            default_account_uuid: source_instance.default_account_uuid.clone(),
+            sandbox: source_instance.sandbox.clone(),
+            java_agents: source_instance.java_agents.clone(),
+            pinned_versions: source_instance.pinned_versions.clone(),
+            game_settings: source_instance.game_settings.clone(),
+            external_game_dir: false,
+            status: InstanceStatus::Ok,
+            auto_update_mods: source_instance.auto_update_mods,
+            download_threads: source_instance.download_threads,
+            pack: source_instance.pack.clone(),
         };
 
         self.update_instance(new_instance.clone())?;
 
         Ok(new_instance)
     }
+
+    /// Create an instance from an official-launcher `.minecraft` directory,
+    /// either using it in place or copying it into the instances directory
+    pub async fn import_dot_minecraft(
+        &self,
+        source: &Path,
+        name: String,
+        copy: bool,
+    ) -> Result<DotMinecraftImport, String> {
+        if !dot_minecraft::is_dot_minecraft(source) {
+            return Err(format!(
+                "{} is not a .minecraft directory (no versions folder)",
+                source.display()
+            ));
+        }
+        let versions = dot_minecraft::scan_versions(source)?;
+        let preferred = dot_minecraft::preferred_version(source, &versions);
+
+        let instance_id = uuid::Uuid::new_v4().to_string();
+        let game_dir = if copy {
+            let game_dir = self.data_dir.join("instances").join(&instance_id);
+            let (src, dst) = (source.to_path_buf(), game_dir.clone());
+            tokio::task::spawn_blocking(move || copy_dir_all(&src, &dst))
+                .await
+                .map_err(|e| e.to_string())?
+                .map_err(|e| format!("Failed to copy {}: {}", source.display(), e))?;
+            game_dir
+        } else {
+            source.to_path_buf()
+        };
+
+        let instance = Instance {
+            id: instance_id.clone(),
+            name,
+            game_dir,
+            version_id: preferred.map(|v| v.id.clone()),
+            created_at: chrono::Utc::now().timestamp(),
+            last_played: None,
+            icon_path: None,
+            notes: None,
+            mod_loader: Some(
+                preferred
+                    .map(|v| v.mod_loader.clone())
+                    .unwrap_or_else(|| "vanilla".to_string()),
+            ),
+            mod_loader_version: preferred.and_then(|v| v.mod_loader_version.clone()),
+            jvm_args_override: None,
+            memory_override: None,
+            java_path_override: None,
+            default_account_uuid: None,
+            sandbox: SandboxSettings::default(),
+            java_agents: Vec::new(),
+            pinned_versions: Vec::new(),
+            game_settings: GameSettings::default(),
+            external_game_dir: !copy,
+            status: InstanceStatus::Ok,
+            auto_update_mods: false,
+            download_threads: None,
+            pack: None,
+        };
+
+        let mut config = self.instances.lock().unwrap();
+        config.instances.push(instance.clone());
+        if config.active_instance_id.is_none() {
+            config.active_instance_id = Some(instance_id);
+        }
+        drop(config);
+        self.save()?;
+
+        Ok(DotMinecraftImport { instance, versions })
+    }
+}
+
+/// Check that the directory of `instance` is still there and, for a modded
+/// version (whose JSON can't be downloaded again), that its JSON is in the
+/// instance or in `shared_versions_dir`
+pub fn check_integrity(instance: &Instance, shared_versions_dir: &Path) -> InstanceStatus {
+    match instance.version_id.as_deref() {
+        Some(version_id) => check_version_integrity(instance, version_id, shared_versions_dir),
+        None if !instance.game_dir.is_dir() => InstanceStatus::MissingDir,
+        None => InstanceStatus::Ok,
+    }
+}
+
+/// [`check_integrity`] for launching `version_id`, which need not be the
+/// version selected in the instance
+pub fn check_version_integrity(
+    instance: &Instance,
+    version_id: &str,
+    shared_versions_dir: &Path,
+) -> InstanceStatus {
+    if !instance.game_dir.is_dir() {
+        return InstanceStatus::MissingDir;
+    }
+    // The selected version of a modded instance, or a version of its loader
+    let modded = instance
+        .mod_loader
+        .as_deref()
+        .filter(|l| *l != "vanilla")
+        .is_some_and(|loader| {
+            instance.version_id.as_deref() == Some(version_id) || version_id.contains(loader)
+        });
+    if modded {
+        let json = Path::new(version_id).join(format!("{}.json", version_id));
+        let found = [
+            instance.game_dir.join("versions"),
+            shared_versions_dir.to_path_buf(),
+        ]
+        .iter()
+        .any(|dir| dir.join(&json).is_file());
+        if !found {
+            return InstanceStatus::Partial;
+        }
+    }
+    InstanceStatus::Ok
+}
+
+/// Error shown when launching `version_id` of a broken instance
+pub fn launch_blocked_reason(
+    instance: &Instance,
+    version_id: &str,
+    status: InstanceStatus,
+) -> Option<String> {
+    match status {
+        InstanceStatus::Ok => None,
+        InstanceStatus::MissingDir => Some(format!(
+            "Instance \"{}\" can't be launched: its directory {} is missing. Restore it or delete the instance.",
+            instance.name,
+            instance.game_dir.display()
+        )),
+        InstanceStatus::Partial => Some(format!(
+            "Instance \"{}\" can't be launched: the files of version {} are missing. Reinstall the version or pick another one.",
+            instance.name, version_id
+        )),
+    }
+}
+
+/// Total size of the files under `dir`
+pub fn dir_size(dir: &Path) -> u64 {
+    let Ok(entries) = fs::read_dir(dir) else {
+        return 0;
+    };
+    entries
+        .filter_map(|e| e.ok())
+        .map(|e| match e.file_type() {
+            Ok(ty) if ty.is_dir() => dir_size(&e.path()),
+            Ok(ty) if ty.is_file() => e.metadata().map(|m| m.len()).unwrap_or(0),
+            _ => 0,
+        })
+        .sum()
+}
+
+/// Folder name for kept data: "<instance name>-<timestamp>", without
+/// characters that aren't allowed in file names
+fn kept_folder_name(instance_name: &str, timestamp: &str) -> String {
+    let name: String = instance_name
+        .trim()
+        .chars()
+        .map(|c| {
+            if c.is_alphanumeric() || matches!(c, '-' | '_' | ' ') {
+                c
+            } else {
+                '_'
+            }
+        })
+        .collect();
+    let name = name.trim();
+    if name.is_empty() {
+        format!("instance-{}", timestamp)
+    } else {
+        format!("{}-{}", name, timestamp)
+    }
+}
+
+/// Worlds, screenshots and size of an instance's directory
+pub fn delete_preview(instance: &Instance) -> DeletePreview {
+    let list = |dir: &str, want_dirs: bool| -> Vec<String> {
+        let mut names: Vec<String> = fs::read_dir(instance.game_dir.join(dir))
+            .map(|entries| {
+                entries
+                    .filter_map(|e| e.ok())
+                    .filter(|e| e.file_type().is_ok_and(|t| t.is_dir() == want_dirs))
+                    .map(|e| e.file_name().to_string_lossy().to_string())
+                    .collect()
+            })
+            .unwrap_or_default();
+        names.sort();
+        names
+    };
+    DeletePreview {
+        total_bytes: if instance.external_game_dir {
+            0
+        } else {
+            dir_size(&instance.game_dir)
+        },
+        worlds: list("saves", true),
+        screenshot_count: list("screenshots", false).len(),
+        external_game_dir: instance.external_game_dir,
+    }
+}
+
+/// Move `KEPT_DIRS` of `instance` into a new folder under `keep_dir`;
+/// `None` if there was nothing to keep
+fn keep_user_data(instance: &Instance, keep_dir: &Path) -> Result<Option<PathBuf>, String> {
+    let present: Vec<&str> = KEPT_DIRS
+        .iter()
+        .copied()
+        .filter(|d| instance.game_dir.join(d).is_dir())
+        .collect();
+    if present.is_empty() {
+        return Ok(None);
+    }
+    let timestamp = chrono::Local::now().format("%Y%m%d-%H%M%S").to_string();
+    let target = keep_dir.join(kept_folder_name(&instance.name, &timestamp));
+    fs::create_dir_all(&target)
+        .map_err(|e| format!("Failed to create {}: {}", target.display(), e))?;
+    for dir in present {
+        let source = instance.game_dir.join(dir);
+        let dest = target.join(dir);
+        // Renaming fails across file systems; copy then
+        if fs::rename(&source, &dest).is_err() {
+            copy_dir_all(&source, &dest).map_err(|e| format!("Failed to keep {}: {}", dir, e))?;
+        }
+    }
+    Ok(Some(target))
 }
 
 /// Copy a directory recursively
@@ -301,11 +650,8 @@ fn copy_dir_all(src: &Path, dst: &Path) -> Result<(), std::io::Error> {
 }
 
 /// Migrate legacy data to instance system
-pub fn migrate_legacy_data(
-    app_handle: &AppHandle,
-    instance_state: &InstanceState,
-) -> Result<(), String> {
-    let app_dir = app_handle.path().app_data_dir().unwrap();
+pub fn migrate_legacy_data(instance_state: &InstanceState) -> Result<(), String> {
+    let app_dir = &instance_state.data_dir;
     let old_versions_dir = app_dir.join("versions");
     let old_libraries_dir = app_dir.join("libraries");
     let old_assets_dir = app_dir.join("assets");
@@ -328,7 +674,7 @@ pub fn migrate_legacy_data(
 
     // Create default instance
     let default_instance = instance_state
-        .create_instance("Default".to_string(), app_handle)
+        .create_instance("Default".to_string())
         .map_err(|e| format!("Failed to create default instance: {}", e))?;
 
     let new_versions_dir = default_instance.game_dir.join("versions");
@@ -377,17 +723,15 @@ pub fn migrate_legacy_data(
 /// falls back to copying if hard links are not supported.
 ///
 /// # Arguments
-/// * `app_handle` - Tauri app handle
 /// * `instance_state` - Instance state management
 ///
 /// # Returns
 /// * `Ok((moved_count, hardlink_count, copy_count, saved_bytes))` on success
 /// * `Err(String)` on failure
 pub fn migrate_to_shared_caches(
-    app_handle: &AppHandle,
     instance_state: &InstanceState,
 ) -> Result<(usize, usize, usize, u64), String> {
-    let app_dir = app_handle.path().app_data_dir().unwrap();
+    let app_dir = &instance_state.data_dir;
 
     // Global shared cache directories
     let global_versions = app_dir.join("versions");
@@ -408,6 +752,10 @@ pub fn migrate_to_shared_caches(
     let instances = instance_state.list_instances();
 
     for instance in instances {
+        // Files of another launcher's directory must stay where they are
+        if instance.external_game_dir {
+            continue;
+        }
         let instance_versions = instance.game_dir.join("versions");
         let instance_libraries = instance.game_dir.join("libraries");
         let instance_assets = instance.game_dir.join("assets");
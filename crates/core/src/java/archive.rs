@@ -1,3 +1,6 @@
+//! Extraction of downloaded Java archives.
+
+use crate::long_path::long_path;
 use flate2::read::GzDecoder;
 use std::fs;
 use std::path::Path;
@@ -14,7 +17,7 @@ pub fn extract_zip(zip_path: &Path, extract_to: &Path) -> Result<(), String> {
             .by_index(i)
             .map_err(|e| format!("Failed to read zip entry: {}", e))?;
         let outpath = match file.enclosed_name() {
-            Some(path) => extract_to.join(path),
+            Some(path) => long_path(&extract_to.join(path)),
             None => continue,
         };
 
@@ -79,7 +82,7 @@ pub fn extract_tar_gz(archive_path: &Path, extract_to: &Path) -> Result<String,
             }
         }
 
-        let outpath = extract_to.join(&entry_path);
+        let outpath = long_path(&extract_to.join(&entry_path));
 
         if entry.header().entry_type().is_dir() {
             fs::create_dir_all(&outpath)
@@ -0,0 +1,94 @@
+//! Cache of downloaded Java archives.
+//!
+//! An archive is moved here after a successful install instead of being
+//! deleted, so installing the same release again (another install path, or a
+//! repair after the runtime was removed) extracts it without downloading.
+//! Only archives with a SHA256 checksum from the provider are kept, and they
+//! are verified both on the way in and before reuse. The least recently used
+//! archives are evicted once the cache grows past
+//! [`JavaDownloadSettings::archive_cache_mb`](super::JavaDownloadSettings).
+
+use std::path::{Path, PathBuf};
+use std::time::SystemTime;
+
+use crate::downloader::verify_checksum;
+
+fn cache_dir(data_dir: &Path) -> PathBuf {
+    data_dir.join("cache").join("java")
+}
+
+fn matches(path: &Path, sha256: &str) -> bool {
+    std::fs::read(path).is_ok_and(|data| verify_checksum(&data, Some(sha256), None))
+}
+
+/// A cached copy of `file_name` that matches `sha256`
+pub fn find(data_dir: &Path, file_name: &str, sha256: Option<&str>) -> Option<PathBuf> {
+    let sha256 = sha256?;
+    let path = cache_dir(data_dir).join(file_name);
+    if !path.exists() {
+        return None;
+    }
+    if !matches(&path, sha256) {
+        let _ = std::fs::remove_file(&path);
+        return None;
+    }
+    // Mark as recently used
+    if let Ok(file) = std::fs::File::options().append(true).open(&path) {
+        let _ = file.set_modified(SystemTime::now());
+    }
+    Some(path)
+}
+
+/// Move a verified archive into the cache, or delete it if it can't be kept.
+/// `max_mb` caps the cache size; 0 disables it.
+pub fn store(data_dir: &Path, archive: &Path, sha256: Option<&str>, max_mb: u32) {
+    let max_bytes = max_mb as u64 * 1024 * 1024;
+    let keep = sha256.is_some_and(|sha256| max_bytes > 0 && matches(archive, sha256));
+    let dir = cache_dir(data_dir);
+    if keep && let Some(file_name) = archive.file_name() {
+        let dest = dir.join(file_name);
+        let moved = std::fs::create_dir_all(&dir).is_ok()
+            && (std::fs::rename(archive, &dest).is_ok()
+                // Across file systems (custom install path)
+                || std::fs::copy(archive, &dest).is_ok());
+        if moved {
+            let _ = std::fs::File::options()
+                .append(true)
+                .open(&dest)
+                .and_then(|file| file.set_modified(SystemTime::now()));
+            prune(&dir, max_bytes);
+        }
+    }
+    let _ = std::fs::remove_file(archive);
+}
+
+/// Evict the least recently used archives until the cache fits `max_bytes`
+fn prune(dir: &Path, max_bytes: u64) {
+    let Ok(entries) = std::fs::read_dir(dir) else {
+        return;
+    };
+    let mut archives: Vec<(SystemTime, u64, PathBuf)> = entries
+        .flatten()
+        .filter_map(|entry| {
+            let metadata = entry.metadata().ok()?;
+            metadata.is_file().then(|| {
+                (
+                    metadata.modified().unwrap_or(SystemTime::UNIX_EPOCH),
+                    metadata.len(),
+                    entry.path(),
+                )
+            })
+        })
+        .collect();
+    archives.sort();
+    let mut total: u64 = archives.iter().map(|(_, size, _)| size).sum();
+    for (_, size, path) in archives {
+        if total <= max_bytes {
+            break;
+        }
+        if std::fs::remove_file(&path).is_ok() {
+            println!("[Java] Evicted {} from the archive cache", path.display());
+            total -= size;
+        }
+    }
+}
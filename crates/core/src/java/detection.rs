@@ -7,7 +7,7 @@ use std::time::Duration;
 #[cfg(target_os = "windows")]
 use std::os::windows::process::CommandExt;
 
-use crate::core::java::strip_unc_prefix;
+use crate::java::strip_unc_prefix;
 
 const WHICH_TIMEOUT: Duration = Duration::from_secs(2);
 
@@ -0,0 +1,405 @@
+//! Catalog fetching, download and installation of Adoptium runtimes.
+
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, Ordering};
+
+use super::download::{self, DownloadQueue, JavaDownloadProgress, PendingJavaDownload};
+use super::provider::JavaProvider;
+use super::providers::AdoptiumProvider;
+use super::{
+    CatalogFilter, ImageType, JavaCatalog, JavaDownloadInfo, JavaDownloadMirror, JavaInstallation,
+    archive, archive_cache, get_java_install_dir, strip_unc_prefix, validation,
+};
+use crate::events::{self, EventSink};
+
+const CACHE_DURATION_SECS: u64 = 24 * 60 * 60;
+
+/// Download settings taken from the launcher config
+#[derive(Debug, Clone, Copy)]
+pub struct JavaDownloadSettings {
+    pub mirror: JavaDownloadMirror,
+    /// Size cap of the archive cache in MB (0 disables it)
+    pub archive_cache_mb: u32,
+}
+
+impl Default for JavaDownloadSettings {
+    fn default() -> Self {
+        Self {
+            mirror: JavaDownloadMirror::Official,
+            archive_cache_mb: 1024,
+        }
+    }
+}
+
+fn get_catalog_cache_path(data_dir: &Path) -> PathBuf {
+    data_dir.join("java_catalog_cache.json")
+}
+
+pub fn load_cached_catalog(data_dir: &Path) -> Option<JavaCatalog> {
+    let cache_path = get_catalog_cache_path(data_dir);
+    if !cache_path.exists() {
+        return None;
+    }
+
+    // Read cache file
+    let content = std::fs::read_to_string(&cache_path).ok()?;
+    let catalog: JavaCatalog = serde_json::from_str(&content).ok()?;
+
+    // Get current time in seconds since UNIX_EPOCH
+    let now = std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .unwrap()
+        .as_secs();
+
+    // Check if cache is still valid
+    if now - catalog.cached_at < CACHE_DURATION_SECS {
+        Some(catalog)
+    } else {
+        None
+    }
+}
+
+pub fn save_catalog_cache(data_dir: &Path, catalog: &JavaCatalog) -> Result<(), String> {
+    let cache_path = get_catalog_cache_path(data_dir);
+    let content = serde_json::to_string_pretty(catalog).map_err(|e| e.to_string())?;
+    std::fs::write(&cache_path, content).map_err(|e| e.to_string())?;
+    Ok(())
+}
+
+#[allow(dead_code)]
+pub fn clear_catalog_cache(data_dir: &Path) -> Result<(), String> {
+    let cache_path = get_catalog_cache_path(data_dir);
+    if cache_path.exists() {
+        std::fs::remove_file(&cache_path).map_err(|e| e.to_string())?;
+    }
+    Ok(())
+}
+
+/// Set while the full catalog is loading in the background
+static FULL_CATALOG_LOADING: AtomicBool = AtomicBool::new(false);
+
+/// Fetch the catalog, or only the part of it matching `filter`. A filtered
+/// fetch that can't be answered from the cache returns the matching releases
+/// right away and keeps loading the full catalog in the background, emitting
+/// it to `events` as `java-catalog-updated` once cached. Concurrent filtered
+/// fetches share one background load. Must be called within a Tokio runtime.
+pub async fn fetch_java_catalog(
+    data_dir: &Path,
+    events: Arc<dyn EventSink>,
+    force_refresh: bool,
+    filter: CatalogFilter,
+) -> Result<JavaCatalog, String> {
+    let provider = AdoptiumProvider::new();
+    if filter.is_empty() {
+        return provider
+            .fetch_catalog(data_dir, force_refresh, &filter)
+            .await
+            .map_err(|e| e.to_string());
+    }
+    if !force_refresh && let Some(cached) = load_cached_catalog(data_dir) {
+        return Ok(filter.apply(cached));
+    }
+
+    let catalog = provider
+        .fetch_catalog(data_dir, true, &filter)
+        .await
+        .map_err(|e| e.to_string())?;
+    if FULL_CATALOG_LOADING.swap(true, Ordering::AcqRel) {
+        return Ok(catalog);
+    }
+    let data_dir = data_dir.to_path_buf();
+    tokio::spawn(async move {
+        let result = AdoptiumProvider::new()
+            .fetch_catalog(&data_dir, true, &CatalogFilter::default())
+            .await;
+        FULL_CATALOG_LOADING.store(false, Ordering::Release);
+        match result {
+            Ok(full) => {
+                events::emit(events.as_ref(), "java-catalog-updated", &full);
+            }
+            Err(e) => eprintln!("[Java] Failed to load the full catalog: {}", e),
+        }
+    });
+    Ok(catalog)
+}
+
+pub async fn fetch_java_release(
+    mirror: JavaDownloadMirror,
+    major_version: u32,
+    image_type: ImageType,
+) -> Result<JavaDownloadInfo, String> {
+    let provider = AdoptiumProvider::with_mirror(mirror);
+    provider
+        .fetch_release(major_version, image_type)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+pub async fn fetch_available_versions() -> Result<Vec<u32>, String> {
+    let provider = AdoptiumProvider::new();
+    provider
+        .available_versions()
+        .await
+        .map_err(|e| e.to_string())
+}
+
+/// Download and install a runtime under `custom_path`, or the `java`
+/// directory of `data_dir`, reporting `java-download-progress` to `events`
+pub async fn download_and_install_java(
+    data_dir: &Path,
+    events: &dyn EventSink,
+    settings: JavaDownloadSettings,
+    major_version: u32,
+    image_type: ImageType,
+    custom_path: Option<PathBuf>,
+) -> Result<JavaInstallation, String> {
+    let mirror = settings.mirror;
+    let provider = AdoptiumProvider::with_mirror(mirror);
+    let info = provider.fetch_release(major_version, image_type).await?;
+    let file_name = info.file_name.clone();
+
+    let install_base = custom_path.unwrap_or_else(|| get_java_install_dir(data_dir));
+    let version_dir = install_base.join(format!(
+        "{}-{}-{}",
+        provider.install_prefix(),
+        major_version,
+        image_type
+    ));
+
+    std::fs::create_dir_all(&install_base)
+        .map_err(|e| format!("Failed to create installation directory: {}", e))?;
+
+    let mut queue = DownloadQueue::load(data_dir);
+    queue.add(PendingJavaDownload {
+        major_version,
+        image_type: image_type.to_string(),
+        download_url: info.download_url.clone(),
+        file_name: info.file_name.clone(),
+        file_size: info.file_size,
+        checksum: info.checksum.clone(),
+        install_path: install_base.to_string_lossy().to_string(),
+        created_at: std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .unwrap()
+            .as_secs(),
+    });
+    queue.save(data_dir)?;
+
+    let cached_archive = archive_cache::find(data_dir, &info.file_name, info.checksum.as_deref());
+    let archive_path = match &cached_archive {
+        Some(cached) => {
+            println!("[Java] Using cached archive {}", cached.display());
+            cached.clone()
+        }
+        None => install_base.join(&info.file_name),
+    };
+
+    let need_download = if cached_archive.is_some() {
+        false
+    } else if archive_path.exists() {
+        if let Some(expected_checksum) = &info.checksum {
+            let data = std::fs::read(&archive_path)
+                .map_err(|e| format!("Failed to read downloaded file: {}", e))?;
+            !crate::downloader::verify_checksum(&data, Some(expected_checksum), None)
+        } else {
+            false
+        }
+    } else {
+        true
+    };
+
+    if need_download {
+        let result = download::download_with_resume(
+            events,
+            &info.download_url,
+            &archive_path,
+            info.checksum.as_deref(),
+            info.file_size,
+        )
+        .await;
+        match result {
+            // A mirror may not have synced the newest build yet
+            Err(e)
+                if mirror != JavaDownloadMirror::Official
+                    && !download::is_java_download_cancelled() =>
+            {
+                eprintln!(
+                    "[Java] Mirror download failed ({}), using the official URL",
+                    e
+                );
+                let official = AdoptiumProvider::new()
+                    .fetch_release(major_version, image_type)
+                    .await?;
+                download::download_with_resume(
+                    events,
+                    &official.download_url,
+                    &archive_path,
+                    official.checksum.as_deref(),
+                    official.file_size,
+                )
+                .await?;
+            }
+            result => result?,
+        }
+    }
+
+    events::emit(
+        events,
+        "java-download-progress",
+        JavaDownloadProgress {
+            file_name: file_name.clone(),
+            downloaded_bytes: info.file_size,
+            total_bytes: info.file_size,
+            speed_bytes_per_sec: 0,
+            eta_seconds: 0,
+            status: "Extracting".to_string(),
+            percentage: 100.0,
+        },
+    );
+
+    if version_dir.exists() {
+        std::fs::remove_dir_all(&version_dir)
+            .map_err(|e| format!("Failed to remove old version directory: {}", e))?;
+    }
+
+    std::fs::create_dir_all(&version_dir)
+        .map_err(|e| format!("Failed to create version directory: {}", e))?;
+
+    let top_level_dir = if info.file_name.ends_with(".tar.gz") || info.file_name.ends_with(".tgz") {
+        archive::extract_tar_gz(&archive_path, &version_dir)?
+    } else if info.file_name.ends_with(".zip") {
+        archive::extract_zip(&archive_path, &version_dir)?;
+        find_top_level_dir(&version_dir)?
+    } else {
+        return Err(format!("Unsupported archive format: {}", info.file_name));
+    };
+
+    if cached_archive.is_none() {
+        archive_cache::store(
+            data_dir,
+            &archive_path,
+            info.checksum.as_deref(),
+            settings.archive_cache_mb,
+        );
+    }
+
+    let java_home = version_dir.join(&top_level_dir);
+    let java_bin = if cfg!(target_os = "macos") {
+        java_home
+            .join("Contents")
+            .join("Home")
+            .join("bin")
+            .join("java")
+    } else if cfg!(windows) {
+        java_home.join("bin").join("java.exe")
+    } else {
+        java_home.join("bin").join("java")
+    };
+
+    if !java_bin.exists() {
+        return Err(format!(
+            "Installation completed but Java executable not found: {}",
+            java_bin.display()
+        ));
+    }
+
+    let java_bin = std::fs::canonicalize(&java_bin).map_err(|e| e.to_string())?;
+    let java_bin = strip_unc_prefix(java_bin);
+
+    let installation = validation::check_java_installation(&java_bin)
+        .await
+        .ok_or_else(|| "Failed to verify Java installation".to_string())?;
+
+    queue.remove(major_version, &image_type.to_string());
+    queue.save(data_dir)?;
+
+    events::emit(
+        events,
+        "java-download-progress",
+        JavaDownloadProgress {
+            file_name,
+            downloaded_bytes: info.file_size,
+            total_bytes: info.file_size,
+            speed_bytes_per_sec: 0,
+            eta_seconds: 0,
+            status: "Completed".to_string(),
+            percentage: 100.0,
+        },
+    );
+
+    Ok(installation)
+}
+
+fn find_top_level_dir(extract_dir: &Path) -> Result<String, String> {
+    let entries: Vec<_> = std::fs::read_dir(extract_dir)
+        .map_err(|e| format!("Failed to read directory: {}", e))?
+        .filter_map(|e| e.ok())
+        .filter(|e| e.path().is_dir())
+        .collect();
+
+    if entries.len() == 1 {
+        Ok(entries[0].file_name().to_string_lossy().to_string())
+    } else {
+        Ok(String::new())
+    }
+}
+
+pub async fn resume_pending_downloads(
+    data_dir: &Path,
+    events: &dyn EventSink,
+    settings: JavaDownloadSettings,
+) -> Result<Vec<JavaInstallation>, String> {
+    let queue = DownloadQueue::load(data_dir);
+    let mut installed = Vec::new();
+
+    for pending in queue.pending_downloads.iter() {
+        let image_type = if pending.image_type == "jdk" {
+            ImageType::Jdk
+        } else {
+            ImageType::Jre
+        };
+
+        match download_and_install_java(
+            data_dir,
+            events,
+            settings,
+            pending.major_version,
+            image_type,
+            Some(PathBuf::from(&pending.install_path)),
+        )
+        .await
+        {
+            Ok(installation) => {
+                installed.push(installation);
+            }
+            Err(e) => {
+                eprintln!(
+                    "Failed to resume Java {} {} download: {}",
+                    pending.major_version, pending.image_type, e
+                );
+            }
+        }
+    }
+
+    Ok(installed)
+}
+
+pub fn cancel_current_download() {
+    download::cancel_java_download();
+}
+
+pub fn get_pending_downloads(data_dir: &Path) -> Vec<PendingJavaDownload> {
+    let queue = DownloadQueue::load(data_dir);
+    queue.pending_downloads
+}
+
+pub fn clear_pending_download(
+    data_dir: &Path,
+    major_version: u32,
+    image_type: &str,
+) -> Result<(), String> {
+    let mut queue = DownloadQueue::load(data_dir);
+    queue.remove(major_version, image_type);
+    queue.save(data_dir)
+}
@@ -0,0 +1,327 @@
+//! Java runtimes: detection of installed ones and, with the
+//! `java-provisioning` feature, download and installation from Adoptium.
+
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+
+#[cfg(feature = "java-provisioning")]
+pub mod archive;
+#[cfg(feature = "java-provisioning")]
+pub mod archive_cache;
+pub mod detection;
+#[cfg(feature = "java-provisioning")]
+pub mod download;
+pub mod error;
+#[cfg(feature = "java-provisioning")]
+mod install;
+pub mod persistence;
+pub mod priority;
+#[cfg(feature = "java-provisioning")]
+pub mod provider;
+#[cfg(feature = "java-provisioning")]
+pub mod providers;
+pub mod validation;
+
+#[cfg(feature = "java-provisioning")]
+pub use download::{JavaDownloadProgress, PendingJavaDownload};
+pub use error::JavaError;
+#[cfg(feature = "java-provisioning")]
+pub use install::*;
+use ts_rs::TS;
+
+/// Remove the UNC prefix (\\?\) from Windows paths
+pub fn strip_unc_prefix(path: PathBuf) -> PathBuf {
+    #[cfg(target_os = "windows")]
+    {
+        let s = path.to_string_lossy().to_string();
+        if s.starts_with(r"\\?\\") {
+            return PathBuf::from(&s[4..]);
+        }
+    }
+    path
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export, export_to = "dropout-core/java/core.ts")]
+pub struct JavaInstallation {
+    pub path: String,
+    pub version: String,
+    pub arch: String,
+    pub vendor: String,
+    pub source: String,
+    pub is_64bit: bool,
+    /// x86/x64 runtime running under emulation on an ARM64 host (e.g. Rosetta)
+    #[serde(default)]
+    pub emulated: bool,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ImageType {
+    Jre,
+    Jdk,
+}
+
+impl Default for ImageType {
+    fn default() -> Self {
+        Self::Jre
+    }
+}
+
+impl std::fmt::Display for ImageType {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Jre => write!(f, "jre"),
+            Self::Jdk => write!(f, "jdk"),
+        }
+    }
+}
+
+/// Where Java archives are downloaded from. Mirrors only serve the files;
+/// release metadata and checksums still come from the provider's API.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, TS)]
+#[serde(rename_all = "lowercase")]
+#[ts(export, export_to = "dropout-core/java/core.ts")]
+pub enum JavaDownloadMirror {
+    #[default]
+    Official,
+    /// mirrors.tuna.tsinghua.edu.cn
+    Tuna,
+    /// mirrors.ustc.edu.cn
+    Ustc,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+#[ts(export, export_to = "dropout-core/java/core.ts")]
+#[serde(rename_all = "camelCase")]
+pub struct JavaReleaseInfo {
+    pub major_version: u32,
+    pub image_type: String,
+    pub version: String,
+    pub release_name: String,
+    pub release_date: Option<String>,
+    pub file_size: u64,
+    pub checksum: Option<String>,
+    pub download_url: String,
+    pub is_lts: bool,
+    pub is_available: bool,
+    pub architecture: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, Default, TS)]
+#[ts(export, export_to = "dropout-core/java/core.ts")]
+#[serde(rename_all = "camelCase")]
+pub struct JavaCatalog {
+    pub releases: Vec<JavaReleaseInfo>,
+    pub available_major_versions: Vec<u32>,
+    pub lts_versions: Vec<u32>,
+    pub cached_at: u64,
+    /// Only the releases matching a [`CatalogFilter`]; the full catalog
+    /// follows in a `java-catalog-updated` event
+    #[serde(default)]
+    pub partial: bool,
+}
+
+/// Subset of the catalog to fetch, so a page that needs one Java version
+/// doesn't wait for every release of every version
+#[derive(Debug, Clone, Default, Serialize, Deserialize, TS)]
+#[ts(export, export_to = "dropout-core/java/core.ts")]
+#[serde(rename_all = "camelCase")]
+pub struct CatalogFilter {
+    #[serde(default)]
+    pub major_versions: Vec<u32>, // empty for all
+    #[serde(default)]
+    pub jre_only: bool,
+}
+
+impl CatalogFilter {
+    pub fn is_empty(&self) -> bool {
+        self.major_versions.is_empty() && !self.jre_only
+    }
+
+    pub fn includes_major(&self, major_version: u32) -> bool {
+        self.major_versions.is_empty() || self.major_versions.contains(&major_version)
+    }
+
+    pub fn image_types(&self) -> &'static [&'static str] {
+        if self.jre_only {
+            &["jre"]
+        } else {
+            &["jre", "jdk"]
+        }
+    }
+
+    /// Keep only the matching releases of `catalog`
+    pub fn apply(&self, mut catalog: JavaCatalog) -> JavaCatalog {
+        if self.is_empty() {
+            return catalog;
+        }
+        catalog.releases.retain(|r| {
+            self.includes_major(r.major_version)
+                && self.image_types().contains(&r.image_type.as_str())
+        });
+        catalog.partial = true;
+        catalog
+    }
+}
+
+#[derive(Debug, Clone, Serialize, TS)]
+#[ts(export, export_to = "dropout-core/java/core.ts")]
+pub struct JavaDownloadInfo {
+    pub version: String,          // e.g., "17.0.2+8"
+    pub release_name: String,     // e.g., "jdk-17.0.2+8"
+    pub download_url: String,     // Direct download URL
+    pub file_name: String,        // e.g., "OpenJDK17U-jre_x64_linux_hotspot_17.0.2_8.tar.gz"
+    pub file_size: u64,           // in bytes
+    pub checksum: Option<String>, // SHA256 checksum
+    pub image_type: String,       // "jre" or "jdk"
+}
+
+pub fn get_java_install_dir(data_dir: &Path) -> PathBuf {
+    data_dir.join("java")
+}
+
+pub async fn detect_java_installations() -> Vec<JavaInstallation> {
+    let mut installations = Vec::new();
+    let candidates = detection::get_java_candidates();
+
+    for candidate in candidates {
+        if let Some(java) = validation::check_java_installation(&candidate).await {
+            if !installations
+                .iter()
+                .any(|j: &JavaInstallation| j.path == java.path)
+            {
+                installations.push(java);
+            }
+        }
+    }
+
+    installations.sort_by(|a, b| {
+        let v_a = validation::parse_java_version(&a.version);
+        let v_b = validation::parse_java_version(&b.version);
+        v_b.cmp(&v_a)
+    });
+
+    installations
+}
+
+pub async fn get_recommended_java(required_major_version: Option<u64>) -> Option<JavaInstallation> {
+    let installations = detect_java_installations().await;
+
+    if let Some(required) = required_major_version {
+        installations.into_iter().find(|java| {
+            let major = validation::parse_java_version(&java.version);
+            major >= required as u32
+        })
+    } else {
+        installations.into_iter().next()
+    }
+}
+
+pub async fn get_compatible_java(
+    data_dir: &Path,
+    required_major_version: Option<u64>,
+    max_major_version: Option<u32>,
+) -> Option<JavaInstallation> {
+    let installations = detect_all_java_installations(data_dir).await;
+
+    // Prefer runtimes native to the host over emulated ones
+    installations
+        .into_iter()
+        .filter(|java| {
+            let major = validation::parse_java_version(&java.version);
+            validation::is_version_compatible(major, required_major_version, max_major_version)
+        })
+        .min_by_key(|java| java.emulated)
+}
+
+pub async fn is_java_compatible(
+    java_path: &str,
+    required_major_version: Option<u64>,
+    max_major_version: Option<u32>,
+) -> bool {
+    let java_path_buf = PathBuf::from(java_path);
+    if let Some(java) = validation::check_java_installation(&java_path_buf).await {
+        let major = validation::parse_java_version(&java.version);
+        validation::is_version_compatible(major, required_major_version, max_major_version)
+    } else {
+        false
+    }
+}
+
+pub async fn detect_all_java_installations(data_dir: &Path) -> Vec<JavaInstallation> {
+    let mut installations = detect_java_installations().await;
+
+    let dropout_java_dir = get_java_install_dir(data_dir);
+    if dropout_java_dir.exists() {
+        if let Ok(entries) = std::fs::read_dir(&dropout_java_dir) {
+            for entry in entries.flatten() {
+                let path = entry.path();
+                if path.is_dir() {
+                    let java_bin = find_java_executable(&path);
+                    if let Some(java_path) = java_bin {
+                        if let Some(java) = validation::check_java_installation(&java_path).await {
+                            if !installations.iter().any(|j| j.path == java.path) {
+                                installations.push(java);
+                            }
+                        }
+                    }
+                }
+            }
+        }
+    }
+
+    installations.sort_by(|a, b| {
+        let v_a = validation::parse_java_version(&a.version);
+        let v_b = validation::parse_java_version(&b.version);
+        v_b.cmp(&v_a)
+    });
+
+    installations
+}
+
+fn find_java_executable(dir: &PathBuf) -> Option<PathBuf> {
+    let bin_name = if cfg!(windows) { "java.exe" } else { "java" };
+
+    let direct_bin = dir.join("bin").join(bin_name);
+    if direct_bin.exists() {
+        let resolved = std::fs::canonicalize(&direct_bin).unwrap_or(direct_bin);
+        return Some(strip_unc_prefix(resolved));
+    }
+
+    #[cfg(target_os = "macos")]
+    {
+        let macos_bin = dir.join("Contents").join("Home").join("bin").join(bin_name);
+        if macos_bin.exists() {
+            return Some(macos_bin);
+        }
+    }
+
+    if let Ok(entries) = std::fs::read_dir(dir) {
+        for entry in entries.flatten() {
+            let path = entry.path();
+            if path.is_dir() {
+                let nested_bin = path.join("bin").join(bin_name);
+                if nested_bin.exists() {
+                    let resolved = std::fs::canonicalize(&nested_bin).unwrap_or(nested_bin);
+                    return Some(strip_unc_prefix(resolved));
+                }
+
+                #[cfg(target_os = "macos")]
+                {
+                    let macos_nested = path
+                        .join("Contents")
+                        .join("Home")
+                        .join("bin")
+                        .join(bin_name);
+                    if macos_nested.exists() {
+                        return Some(macos_nested);
+                    }
+                }
+            }
+        }
+    }
+
+    None
+}
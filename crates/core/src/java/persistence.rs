@@ -1,11 +1,10 @@
-use crate::core::java::error::JavaError;
+use crate::java::error::JavaError;
 use serde::{Deserialize, Serialize};
-use std::path::PathBuf;
-use tauri::{AppHandle, Manager};
+use std::path::{Path, PathBuf};
 use ts_rs::TS;
 
 #[derive(Debug, Clone, Serialize, Deserialize, TS)]
-#[ts(export, export_to = "java/persistence.ts")]
+#[ts(export, export_to = "dropout-core/java/persistence.ts")]
 pub struct JavaConfig {
     pub user_defined_paths: Vec<String>,
     pub preferred_java_path: Option<String>,
@@ -22,16 +21,12 @@ impl Default for JavaConfig {
     }
 }
 
-fn get_java_config_path(app_handle: &AppHandle) -> PathBuf {
-    app_handle
-        .path()
-        .app_data_dir()
-        .unwrap()
-        .join("java_config.json")
+fn get_java_config_path(data_dir: &Path) -> PathBuf {
+    data_dir.join("java_config.json")
 }
 
-pub fn load_java_config(app_handle: &AppHandle) -> JavaConfig {
-    let config_path = get_java_config_path(app_handle);
+pub fn load_java_config(data_dir: &Path) -> JavaConfig {
+    let config_path = get_java_config_path(data_dir);
     if !config_path.exists() {
         return JavaConfig::default();
     }
@@ -41,8 +36,8 @@ pub fn load_java_config(app_handle: &AppHandle) -> JavaConfig {
             Ok(config) => config,
             Err(err) => {
                 // Log the error but don't panic - return default config
-                log::warn!(
-                    "Failed to parse Java config at {}: {}. Using default configuration.",
+                eprintln!(
+                    "[Java] Failed to parse Java config at {}: {}. Using default configuration.",
                     config_path.display(),
                     err
                 );
@@ -50,8 +45,8 @@ pub fn load_java_config(app_handle: &AppHandle) -> JavaConfig {
             }
         },
         Err(err) => {
-            log::warn!(
-                "Failed to read Java config at {}: {}. Using default configuration.",
+            eprintln!(
+                "[Java] Failed to read Java config at {}: {}. Using default configuration.",
                 config_path.display(),
                 err
             );
@@ -60,8 +55,8 @@ pub fn load_java_config(app_handle: &AppHandle) -> JavaConfig {
     }
 }
 
-pub fn save_java_config(app_handle: &AppHandle, config: &JavaConfig) -> Result<(), JavaError> {
-    let config_path = get_java_config_path(app_handle);
+pub fn save_java_config(data_dir: &Path, config: &JavaConfig) -> Result<(), JavaError> {
+    let config_path = get_java_config_path(data_dir);
     let content = serde_json::to_string_pretty(config)?;
 
     std::fs::create_dir_all(config_path.parent().ok_or_else(|| {
@@ -73,43 +68,40 @@ pub fn save_java_config(app_handle: &AppHandle, config: &JavaConfig) -> Result<(
 }
 
 #[allow(dead_code)]
-pub fn add_user_defined_path(app_handle: &AppHandle, path: String) -> Result<(), JavaError> {
-    let mut config = load_java_config(app_handle);
+pub fn add_user_defined_path(data_dir: &Path, path: String) -> Result<(), JavaError> {
+    let mut config = load_java_config(data_dir);
     if !config.user_defined_paths.contains(&path) {
         config.user_defined_paths.push(path);
     }
-    save_java_config(app_handle, &config)
+    save_java_config(data_dir, &config)
 }
 
 #[allow(dead_code)]
-pub fn remove_user_defined_path(app_handle: &AppHandle, path: &str) -> Result<(), JavaError> {
-    let mut config = load_java_config(app_handle);
+pub fn remove_user_defined_path(data_dir: &Path, path: &str) -> Result<(), JavaError> {
+    let mut config = load_java_config(data_dir);
     config.user_defined_paths.retain(|p| p != path);
-    save_java_config(app_handle, &config)
+    save_java_config(data_dir, &config)
 }
 
 #[allow(dead_code)]
-pub fn set_preferred_java_path(
-    app_handle: &AppHandle,
-    path: Option<String>,
-) -> Result<(), JavaError> {
-    let mut config = load_java_config(app_handle);
+pub fn set_preferred_java_path(data_dir: &Path, path: Option<String>) -> Result<(), JavaError> {
+    let mut config = load_java_config(data_dir);
     config.preferred_java_path = path;
-    save_java_config(app_handle, &config)
+    save_java_config(data_dir, &config)
 }
 
 #[allow(dead_code)]
-pub fn get_preferred_java_path(app_handle: &AppHandle) -> Option<String> {
-    let config = load_java_config(app_handle);
+pub fn get_preferred_java_path(data_dir: &Path) -> Option<String> {
+    let config = load_java_config(data_dir);
     config.preferred_java_path
 }
 
 #[allow(dead_code)]
-pub fn update_last_detection_time(app_handle: &AppHandle) -> Result<(), JavaError> {
-    let mut config = load_java_config(app_handle);
+pub fn update_last_detection_time(data_dir: &Path) -> Result<(), JavaError> {
+    let mut config = load_java_config(data_dir);
     config.last_detection_time = std::time::SystemTime::now()
         .duration_since(std::time::UNIX_EPOCH)
         .map_err(|e| JavaError::Other(format!("System time error: {}", e)))?
         .as_secs();
-    save_java_config(app_handle, &config)
+    save_java_config(data_dir, &config)
 }
@@ -1,11 +1,11 @@
-use tauri::AppHandle;
+use std::path::Path;
 
-use crate::core::java::JavaInstallation;
-use crate::core::java::persistence;
-use crate::core::java::validation;
+use crate::java::JavaInstallation;
+use crate::java::persistence;
+use crate::java::validation;
 
 pub async fn resolve_java_for_launch(
-    app_handle: &AppHandle,
+    data_dir: &Path,
     instance_java_override: Option<&str>,
     global_java_path: Option<&str>,
     required_major_version: Option<u64>,
@@ -33,7 +33,7 @@ pub async fn resolve_java_for_launch(
         }
     }
 
-    let preferred = persistence::get_preferred_java_path(app_handle);
+    let preferred = persistence::get_preferred_java_path(data_dir);
     if let Some(pref_path) = preferred {
         let path_buf = std::path::PathBuf::from(&pref_path);
         if let Some(java) = validation::check_java_installation(&path_buf).await {
@@ -43,10 +43,11 @@ pub async fn resolve_java_for_launch(
         }
     }
 
-    let installations = super::detect_all_java_installations(app_handle).await;
+    let installations = super::detect_all_java_installations(data_dir).await;
     installations
         .into_iter()
-        .find(|java| is_version_compatible(java, required_major_version, max_major_version))
+        .filter(|java| is_version_compatible(java, required_major_version, max_major_version))
+        .min_by_key(|java| java.emulated)
 }
 
 fn is_version_compatible(
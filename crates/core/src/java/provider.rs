@@ -1,5 +1,6 @@
-use crate::core::java::{ImageType, JavaCatalog, JavaDownloadInfo, JavaError};
-use tauri::AppHandle;
+use crate::java::{CatalogFilter, ImageType, JavaCatalog, JavaDownloadInfo, JavaError};
+use std::future::Future;
+use std::path::Path;
 
 /// Trait for Java distribution providers (e.g., Adoptium, Corretto)
 ///
@@ -9,17 +10,19 @@ pub trait JavaProvider: Send + Sync {
     /// Fetch the Java catalog (all available versions for this provider)
     ///
     /// # Arguments
-    /// * `app_handle` - The Tauri app handle for cache access
+    /// * `data_dir` - Launcher data directory holding the catalog cache
     /// * `force_refresh` - If true, bypass cache and fetch fresh data
+    /// * `filter` - Only fetch these releases; a filtered catalog isn't cached
     ///
     /// # Returns
     /// * `Ok(JavaCatalog)` with available versions
     /// * `Err(JavaError)` if fetch or parsing fails
-    async fn fetch_catalog(
+    fn fetch_catalog(
         &self,
-        app_handle: &AppHandle,
+        data_dir: &Path,
         force_refresh: bool,
-    ) -> Result<JavaCatalog, JavaError>;
+        filter: &CatalogFilter,
+    ) -> impl Future<Output = Result<JavaCatalog, JavaError>> + Send;
 
     /// Fetch a specific Java release
     ///
@@ -30,18 +33,18 @@ pub trait JavaProvider: Send + Sync {
     /// # Returns
     /// * `Ok(JavaDownloadInfo)` with download details
     /// * `Err(JavaError)` if fetch or parsing fails
-    async fn fetch_release(
+    fn fetch_release(
         &self,
         major_version: u32,
         image_type: ImageType,
-    ) -> Result<JavaDownloadInfo, JavaError>;
+    ) -> impl Future<Output = Result<JavaDownloadInfo, JavaError>> + Send;
 
     /// Get list of available major versions
     ///
     /// # Returns
     /// * `Ok(Vec<u32>)` with available major versions
     /// * `Err(JavaError)` if fetch fails
-    async fn available_versions(&self) -> Result<Vec<u32>, JavaError>;
+    fn available_versions(&self) -> impl Future<Output = Result<Vec<u32>, JavaError>> + Send;
 
     /// Get provider name (e.g., "adoptium", "corretto")
     #[allow(dead_code)]
@@ -1,15 +1,18 @@
-use crate::core::java::error::JavaError;
-use crate::core::java::provider::JavaProvider;
-use crate::core::java::save_catalog_cache;
-use crate::core::java::{ImageType, JavaCatalog, JavaDownloadInfo, JavaReleaseInfo};
+use crate::java::error::JavaError;
+use crate::java::provider::JavaProvider;
+use crate::java::save_catalog_cache;
+use crate::java::{
+    CatalogFilter, ImageType, JavaCatalog, JavaDownloadInfo, JavaDownloadMirror, JavaReleaseInfo,
+};
 use serde::Deserialize;
-use tauri::AppHandle;
+use std::path::Path;
 use ts_rs::TS;
 
 const ADOPTIUM_API_BASE: &str = "https://api.adoptium.net/v3";
+const GITHUB_RELEASES: &str = "https://github.com/adoptium/";
 
 #[derive(Debug, Clone, Deserialize, TS)]
-#[ts(export, export_to = "java/providers/adoptium.ts")]
+#[ts(export, export_to = "dropout-core/java/providers/adoptium.ts")]
 pub struct AdoptiumAsset {
     pub binary: AdoptiumBinary,
     pub release_name: String,
@@ -18,7 +21,7 @@ pub struct AdoptiumAsset {
 
 #[derive(Debug, Clone, Deserialize, TS)]
 #[allow(dead_code)]
-#[ts(export, export_to = "java/providers/adoptium.ts")]
+#[ts(export, export_to = "dropout-core/java/providers/adoptium.ts")]
 pub struct AdoptiumBinary {
     pub os: String,
     pub architecture: String,
@@ -29,7 +32,7 @@ pub struct AdoptiumBinary {
 }
 
 #[derive(Debug, Clone, Deserialize, TS)]
-#[ts(export, export_to = "java/providers/adoptium.ts")]
+#[ts(export, export_to = "dropout-core/java/providers/adoptium.ts")]
 pub struct AdoptiumPackage {
     pub name: String,
     pub link: String,
@@ -39,7 +42,7 @@ pub struct AdoptiumPackage {
 
 #[derive(Debug, Clone, Deserialize, TS)]
 #[allow(dead_code)]
-#[ts(export, export_to = "java/providers/adoptium.ts")]
+#[ts(export, export_to = "dropout-core/java/providers/adoptium.ts")]
 pub struct AdoptiumVersionData {
     pub major: u32,
     pub minor: u32,
@@ -50,7 +53,7 @@ pub struct AdoptiumVersionData {
 
 #[derive(Debug, Clone, Deserialize, TS)]
 #[allow(dead_code)]
-#[ts(export, export_to = "java/providers/adoptium.ts")]
+#[ts(export, export_to = "dropout-core/java/providers/adoptium.ts")]
 pub struct AvailableReleases {
     pub available_releases: Vec<u32>,
     pub available_lts_releases: Vec<u32>,
@@ -58,11 +61,51 @@ pub struct AvailableReleases {
     pub most_recent_feature_release: Option<u32>,
 }
 
-pub struct AdoptiumProvider;
+pub struct AdoptiumProvider {
+    mirror: JavaDownloadMirror,
+}
 
 impl AdoptiumProvider {
     pub fn new() -> Self {
-        Self
+        Self::with_mirror(JavaDownloadMirror::Official)
+    }
+
+    /// Provider whose download links point at `mirror`
+    pub fn with_mirror(mirror: JavaDownloadMirror) -> Self {
+        Self { mirror }
+    }
+
+    /// `link` (a GitHub release asset) on the configured mirror
+    fn mirror_link(
+        &self,
+        link: &str,
+        major_version: u32,
+        image_type: &str,
+        file_name: &str,
+    ) -> String {
+        match self.mirror {
+            JavaDownloadMirror::Official => link.to_string(),
+            // Tuna keeps only the latest build: Adoptium/<major>/<type>/<arch>/<os>/<file>
+            JavaDownloadMirror::Tuna => format!(
+                "https://mirrors.tuna.tsinghua.edu.cn/Adoptium/{}/{}/{}/{}/{}",
+                major_version,
+                image_type,
+                self.arch_name(),
+                self.os_name(),
+                file_name
+            ),
+            // USTC mirrors the GitHub releases: releases/temurin<major>-binaries/<tag>/<file>
+            JavaDownloadMirror::Ustc => match link
+                .strip_prefix(GITHUB_RELEASES)
+                .and_then(|rest| rest.split_once("/releases/download/"))
+            {
+                Some((repo, rest)) => format!(
+                    "https://mirrors.ustc.edu.cn/adoptium/releases/{}/{}",
+                    repo, rest
+                ),
+                None => link.to_string(),
+            },
+        }
     }
 }
 
@@ -75,12 +118,13 @@ impl Default for AdoptiumProvider {
 impl JavaProvider for AdoptiumProvider {
     async fn fetch_catalog(
         &self,
-        app_handle: &AppHandle,
+        data_dir: &Path,
         force_refresh: bool,
+        filter: &CatalogFilter,
     ) -> Result<JavaCatalog, JavaError> {
         if !force_refresh {
-            if let Some(cached) = crate::core::java::load_cached_catalog(app_handle) {
-                return Ok(cached);
+            if let Some(cached) = crate::java::load_cached_catalog(data_dir) {
+                return Ok(filter.apply(cached));
             }
         }
 
@@ -107,7 +151,10 @@ impl JavaProvider for AdoptiumProvider {
         let mut fetch_tasks = Vec::new();
 
         for major_version in &available.available_releases {
-            for image_type in &["jre", "jdk"] {
+            if !filter.includes_major(*major_version) {
+                continue;
+            }
+            for image_type in filter.image_types() {
                 let major_version = *major_version;
                 let image_type = image_type.to_string();
                 let url = format!(
@@ -209,9 +256,12 @@ impl JavaProvider for AdoptiumProvider {
             available_major_versions: available.available_releases,
             lts_versions: available.available_lts_releases,
             cached_at: now,
+            partial: !filter.is_empty(),
         };
 
-        let _ = save_catalog_cache(app_handle, &catalog);
+        if !catalog.partial {
+            let _ = save_catalog_cache(data_dir, &catalog);
+        }
 
         Ok(catalog)
     }
@@ -254,10 +304,17 @@ impl JavaProvider for AdoptiumProvider {
             .next()
             .ok_or_else(|| JavaError::NotFound)?;
 
+        let download_url = self.mirror_link(
+            &asset.binary.package.link,
+            major_version,
+            &asset.binary.image_type,
+            &asset.binary.package.name,
+        );
+
         Ok(JavaDownloadInfo {
             version: asset.version.semver.clone(),
             release_name: asset.release_name,
-            download_url: asset.binary.package.link,
+            download_url,
             file_name: asset.binary.package.name,
             file_size: asset.binary.package.size,
             checksum: asset.binary.package.checksum,
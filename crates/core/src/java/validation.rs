@@ -5,7 +5,7 @@ use std::process::Command;
 #[cfg(target_os = "windows")]
 use std::os::windows::process::CommandExt;
 
-use crate::core::java::JavaInstallation;
+use crate::java::JavaInstallation;
 
 pub async fn check_java_installation(path: &PathBuf) -> Option<JavaInstallation> {
     let path = path.clone();
@@ -16,7 +16,8 @@ pub async fn check_java_installation(path: &PathBuf) -> Option<JavaInstallation>
 
 fn check_java_installation_blocking(path: &PathBuf) -> Option<JavaInstallation> {
     let mut cmd = Command::new(path);
-    cmd.arg("-version");
+    // Print system properties as well to read the real `os.arch`
+    cmd.arg("-XshowSettings:properties").arg("-version");
 
     // Hide console window
     #[cfg(target_os = "windows")]
@@ -27,9 +28,11 @@ fn check_java_installation_blocking(path: &PathBuf) -> Option<JavaInstallation>
     let version_output = String::from_utf8_lossy(&output.stderr);
 
     let version = parse_version_string(&version_output)?;
-    let arch = extract_architecture(&version_output);
+    let arch =
+        parse_os_arch(&version_output).unwrap_or_else(|| extract_architecture(&version_output));
     let vendor = extract_vendor(&version_output);
     let is_64bit = version_output.to_lowercase().contains("64-bit") || arch == "aarch64";
+    let emulated = is_emulated(&arch, host_is_arm64());
 
     Some(JavaInstallation {
         path: path.to_string_lossy().to_string(),
@@ -38,9 +41,61 @@ fn check_java_installation_blocking(path: &PathBuf) -> Option<JavaInstallation>
         vendor,
         source: "system".to_string(),
         is_64bit,
+        emulated,
     })
 }
 
+/// Read `os.arch` from `-XshowSettings:properties` output, normalized to
+/// the names used by `extract_architecture`
+pub fn parse_os_arch(output: &str) -> Option<String> {
+    let value = output.lines().find_map(|line| {
+        let (key, value) = line.split_once('=')?;
+        (key.trim() == "os.arch").then(|| value.trim().to_lowercase())
+    })?;
+    Some(
+        match value.as_str() {
+            "amd64" | "x86_64" => "x64",
+            "x86" | "i386" | "i486" | "i586" | "i686" => "x86",
+            "aarch64" | "arm64" => "aarch64",
+            other => other,
+        }
+        .to_string(),
+    )
+}
+
+/// Whether the machine has an ARM64 CPU, even if DropOut itself runs
+/// translated (Rosetta 2 or Windows on ARM x64 emulation)
+pub fn host_is_arm64() -> bool {
+    if std::env::consts::ARCH == "aarch64" {
+        return true;
+    }
+
+    #[cfg(target_os = "macos")]
+    {
+        Command::new("sysctl")
+            .args(["-n", "hw.optional.arm64"])
+            .output()
+            .map(|o| String::from_utf8_lossy(&o.stdout).trim() == "1")
+            .unwrap_or(false)
+    }
+
+    #[cfg(target_os = "windows")]
+    {
+        ["PROCESSOR_ARCHITEW6432", "PROCESSOR_ARCHITECTURE"]
+            .iter()
+            .any(|var| std::env::var(var).is_ok_and(|v| v.eq_ignore_ascii_case("ARM64")))
+    }
+
+    #[cfg(not(any(target_os = "macos", target_os = "windows")))]
+    {
+        false
+    }
+}
+
+pub fn is_emulated(java_arch: &str, host_is_arm64: bool) -> bool {
+    host_is_arm64 && matches!(java_arch, "x64" | "x86")
+}
+
 pub fn parse_version_string(output: &str) -> Option<String> {
     for line in output.lines() {
         if line.contains("version") {
@@ -144,3 +199,24 @@ pub fn is_version_compatible(
     let meets_max = max_major_version.map(|m| major <= m).unwrap_or(true);
     meets_min && meets_max
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_os_arch() {
+        let output = "Property settings:\n    os.arch = aarch64\n    os.name = Mac OS X\n\nopenjdk version \"21.0.2\" 2024-01-16\nOpenJDK 64-Bit Server VM";
+        assert_eq!(parse_os_arch(output).as_deref(), Some("aarch64"));
+        assert_eq!(parse_version_string(output).as_deref(), Some("21.0.2"));
+        assert_eq!(parse_os_arch("    os.arch = amd64").as_deref(), Some("x64"));
+        assert_eq!(parse_os_arch("java version \"1.8.0_381\""), None);
+    }
+
+    #[test]
+    fn test_is_emulated() {
+        assert!(is_emulated("x64", true));
+        assert!(!is_emulated("aarch64", true));
+        assert!(!is_emulated("x64", false));
+    }
+}
@@ -0,0 +1,169 @@
+//! Recommended extra JVM arguments by Minecraft version, mod loader and Java
+//! version, from the curated list in `data/jvm_args.json`.
+//!
+//! Version bounds are inclusive and compare releases only: snapshots and
+//! other ids that aren't `1.x[.y]` match only rules without version bounds.
+
+use serde::Deserialize;
+use std::sync::LazyLock;
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct JvmArgsRule {
+    #[serde(default)]
+    pub min_version: Option<String>,
+    #[serde(default)]
+    pub max_version: Option<String>,
+    #[serde(default)]
+    pub min_java: Option<u32>,
+    #[serde(default)]
+    pub max_java: Option<u32>,
+    #[serde(default)]
+    pub loaders: Vec<String>, // empty = any, including vanilla
+    pub args: Vec<String>,
+    pub reason: String,
+}
+
+static RULES: LazyLock<Vec<JvmArgsRule>> = LazyLock::new(|| {
+    serde_json::from_str(include_str!("../data/jvm_args.json"))
+        .expect("data/jvm_args.json is invalid")
+});
+
+pub fn rules() -> &'static [JvmArgsRule] {
+    &RULES
+}
+
+/// "1.16.5" -> (16, 5); `None` for snapshots and other non-release ids
+fn release_parts(version: &str) -> Option<(u32, u32)> {
+    let mut parts = version.split('.');
+    if parts.next()? != "1" {
+        return None;
+    }
+    let minor = parts.next()?.parse().ok()?;
+    let patch = match parts.next() {
+        Some(patch) => patch.parse().ok()?,
+        None => 0,
+    };
+    Some((minor, patch))
+}
+
+impl JvmArgsRule {
+    pub fn matches(&self, minecraft_version: &str, java_major: u32, loader: Option<&str>) -> bool {
+        let bounded = self.min_version.is_some() || self.max_version.is_some();
+        let version_ok = match release_parts(minecraft_version) {
+            Some(version) => {
+                self.min_version
+                    .as_deref()
+                    .and_then(release_parts)
+                    .is_none_or(|min| version >= min)
+                    && self
+                        .max_version
+                        .as_deref()
+                        .and_then(release_parts)
+                        .is_none_or(|max| version <= max)
+            }
+            None => !bounded,
+        };
+        let loader_ok = self.loaders.is_empty()
+            || loader.is_some_and(|l| self.loaders.iter().any(|r| r.eq_ignore_ascii_case(l)));
+        version_ok
+            && loader_ok
+            && self.min_java.is_none_or(|min| java_major >= min)
+            && self.max_java.is_none_or(|max| java_major <= max)
+    }
+}
+
+/// Option name of an argument, so `-XX:G1ReservePercent=20` and
+/// `-XX:G1ReservePercent=30` count as the same setting
+fn arg_key(arg: &str) -> &str {
+    let arg = arg.split('=').next().unwrap_or(arg);
+    // -XX:+UseG1GC / -XX:-UseG1GC
+    arg.strip_prefix("-XX:+")
+        .or_else(|| arg.strip_prefix("-XX:-"))
+        .unwrap_or(arg)
+}
+
+/// Recommended arguments for a launch, leaving out options already set in
+/// `existing` (e.g. by the version JSON)
+pub fn recommended_args(
+    minecraft_version: &str,
+    java_major: u32,
+    loader: Option<&str>,
+    existing: &[String],
+) -> Vec<String> {
+    let mut args: Vec<String> = Vec::new();
+    for rule in rules()
+        .iter()
+        .filter(|r| r.matches(minecraft_version, java_major, loader))
+    {
+        for arg in &rule.args {
+            let key = arg_key(arg);
+            // `--add-opens=` repeats with different values
+            let duplicate = if arg.starts_with("--add-opens") {
+                existing.iter().chain(&args).any(|a| a == arg)
+            } else {
+                existing.iter().chain(&args).any(|a| arg_key(a) == key)
+            };
+            if !duplicate {
+                args.push(arg.clone());
+            }
+        }
+    }
+    args
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_database_parses() {
+        assert!(!rules().is_empty());
+        assert!(
+            rules()
+                .iter()
+                .all(|r| !r.args.is_empty() && !r.reason.is_empty())
+        );
+        for rule in rules() {
+            for bound in [&rule.min_version, &rule.max_version].into_iter().flatten() {
+                assert!(release_parts(bound).is_some(), "bad bound {}", bound);
+            }
+        }
+    }
+
+    #[test]
+    fn test_version_ranges() {
+        let forge_1710 = recommended_args("1.7.10", 8, Some("forge"), &[]);
+        assert!(forge_1710.contains(&"-Dfml.ignorePatchDiscrepancies=true".to_string()));
+        assert!(!forge_1710.contains(&"-XX:+UseG1GC".to_string()));
+
+        let vanilla_189 = recommended_args("1.8.9", 8, None, &[]);
+        assert!(vanilla_189.contains(&"-XX:+UseG1GC".to_string()));
+        assert!(!vanilla_189.iter().any(|a| a.starts_with("-Dfml")));
+
+        let modern = recommended_args("1.20.4", 17, Some("fabric"), &[]);
+        assert!(modern.contains(&"-XX:+UseStringDeduplication".to_string()));
+        assert!(!modern.iter().any(|a| a.starts_with("--add-opens")));
+
+        let old_on_new_java = recommended_args("1.12.2", 17, Some("forge"), &[]);
+        assert!(old_on_new_java.iter().any(|a| a.starts_with("--add-opens")));
+
+        // Snapshots only get unbounded rules
+        assert_eq!(
+            recommended_args("24w14a", 21, None, &[]),
+            vec!["-XX:+UseStringDeduplication"]
+        );
+    }
+
+    #[test]
+    fn test_existing_args_win() {
+        let existing = vec![
+            "-XX:-UseG1GC".to_string(),
+            "-XX:G1ReservePercent=30".to_string(),
+        ];
+        let args = recommended_args("1.20.4", 17, None, &existing);
+        assert!(!args.iter().any(|a| a.contains("UseG1GC")));
+        assert!(!args.iter().any(|a| a.starts_with("-XX:G1ReservePercent")));
+        assert!(args.contains(&"-XX:MaxGCPauseMillis=50".to_string()));
+    }
+}
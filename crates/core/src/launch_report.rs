@@ -0,0 +1,291 @@
+//! Report of the classpath of the last launch, for debugging.
+//!
+//! A mod loader's version JSON is merged in front of the vanilla one, so a
+//! library both ship (ASM, Guava, ...) would otherwise be on the classpath
+//! twice in different versions. [`resolve_libraries`] keeps the first one —
+//! the loader's — and records the dropped one as a substitution.
+//!
+//! The report also keeps how long each phase of the launch took
+//! ([`LaunchTimer`]), to tell where a slow launch spent its time.
+
+use crate::maven::MavenCoordinate;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::path::Path;
+use std::time::Instant;
+use ts_rs::TS;
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export, export_to = "dropout-core/launch_report.ts")]
+pub struct ResolvedLibrary {
+    pub name: String,    // Maven coordinate from the version JSON
+    pub version: String, // empty if the name isn't a coordinate
+    pub origin: String,  // "vanilla" | "loader" | "custom"
+    pub path: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export, export_to = "dropout-core/launch_report.ts")]
+pub struct LibrarySubstitution {
+    pub library: String, // group:artifact[:classifier]
+    pub kept: ResolvedLibrary,
+    pub dropped: ResolvedLibrary,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export, export_to = "dropout-core/launch_report.ts")]
+pub enum LaunchPhase {
+    Verification, // instance integrity check
+    Auth,         // account selection and token refresh
+    Metadata,     // version JSON and inherited profiles
+    ModUpdates,   // only with automatic mod updates
+    Java,
+    Download, // includes hashing files that are already present
+    Assets,   // virtual/legacy asset copies
+    Natives,
+    Spawn, // classpath, arguments and starting the process
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export, export_to = "dropout-core/launch_report.ts")]
+pub struct PhaseTiming {
+    pub phase: LaunchPhase,
+    pub ms: u64,
+}
+
+/// Times consecutive launch phases: each [`LaunchTimer::finish`] ends the
+/// phase that started when the previous one ended
+pub struct LaunchTimer {
+    started: Instant,
+    phase_started: Instant,
+    timings: Vec<PhaseTiming>,
+}
+
+impl Default for LaunchTimer {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl LaunchTimer {
+    pub fn new() -> Self {
+        let now = Instant::now();
+        Self {
+            started: now,
+            phase_started: now,
+            timings: Vec::new(),
+        }
+    }
+
+    /// Record the time since the last phase ended as `phase`
+    pub fn finish(&mut self, phase: LaunchPhase) {
+        let now = Instant::now();
+        self.timings.push(PhaseTiming {
+            phase,
+            ms: now.duration_since(self.phase_started).as_millis() as u64,
+        });
+        self.phase_started = now;
+    }
+
+    pub fn timings(&self) -> &[PhaseTiming] {
+        &self.timings
+    }
+
+    pub fn total_ms(&self) -> u64 {
+        self.started.elapsed().as_millis() as u64
+    }
+
+    /// "verification 3 ms, auth 410 ms, ..."
+    pub fn summary(&self) -> String {
+        self.timings
+            .iter()
+            .map(|t| {
+                let name = serde_json::to_value(t.phase)
+                    .ok()
+                    .and_then(|v| v.as_str().map(String::from))
+                    .unwrap_or_default();
+                format!("{} {} ms", name, t.ms)
+            })
+            .collect::<Vec<_>>()
+            .join(", ")
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export, export_to = "dropout-core/launch_report.ts")]
+pub struct LaunchReport {
+    pub instance_id: String,
+    pub version_id: String,
+    pub main_class: String,
+    pub created_at: i64,
+    pub libraries: Vec<ResolvedLibrary>,
+    pub substitutions: Vec<LibrarySubstitution>,
+    #[serde(default)]
+    pub timings: Vec<PhaseTiming>, // empty in reports of older launchers
+    #[serde(default)]
+    pub total_ms: u64,
+}
+
+/// Payload of the `game-launched` event, sent once the game process runs
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export, export_to = "dropout-core/launch_report.ts")]
+pub struct GameLaunched {
+    pub instance_id: String,
+    pub version_id: String,
+    pub launch_id: String,
+    pub pid: Option<u32>,
+    pub timings: Vec<PhaseTiming>,
+    pub total_ms: u64,
+}
+
+/// Identity of a library regardless of its version: `group:artifact`, plus
+/// the classifier (natives of one library are separate entries)
+pub fn library_key(name: &str) -> (String, String) {
+    match MavenCoordinate::parse(name) {
+        Some(coord) => {
+            let key = match &coord.classifier {
+                Some(classifier) => format!("{}:{}:{}", coord.group, coord.artifact, classifier),
+                None => format!("{}:{}", coord.group, coord.artifact),
+            };
+            (key, coord.version)
+        }
+        None => (name.to_string(), String::new()),
+    }
+}
+
+/// Drop later duplicates of a library from `entries` (name, origin, path),
+/// in classpath order
+pub fn resolve_libraries(
+    entries: Vec<(String, String, String)>,
+) -> (Vec<ResolvedLibrary>, Vec<LibrarySubstitution>) {
+    let mut libraries: Vec<ResolvedLibrary> = Vec::new();
+    let mut substitutions = Vec::new();
+    let mut seen: HashMap<String, usize> = HashMap::new();
+    for (name, origin, path) in entries {
+        let (key, version) = library_key(&name);
+        let library = ResolvedLibrary {
+            name,
+            version,
+            origin,
+            path,
+        };
+        match seen.get(&key) {
+            // The same file listed twice isn't a substitution
+            Some(&index) if libraries[index].path == library.path => {}
+            Some(&index) => substitutions.push(LibrarySubstitution {
+                library: key,
+                kept: libraries[index].clone(),
+                dropped: library,
+            }),
+            None => {
+                seen.insert(key, libraries.len());
+                libraries.push(library);
+            }
+        }
+    }
+    (libraries, substitutions)
+}
+
+fn report_path(dir: &Path, instance_id: &str) -> std::path::PathBuf {
+    dir.join(format!("{}.json", instance_id))
+}
+
+pub fn save(dir: &Path, report: &LaunchReport) -> Result<(), String> {
+    std::fs::create_dir_all(dir).map_err(|e| e.to_string())?;
+    let content = serde_json::to_string_pretty(report).map_err(|e| e.to_string())?;
+    std::fs::write(report_path(dir, &report.instance_id), content).map_err(|e| e.to_string())
+}
+
+pub fn load(dir: &Path, instance_id: &str) -> Result<Option<LaunchReport>, String> {
+    match std::fs::read_to_string(report_path(dir, instance_id)) {
+        Ok(content) => serde_json::from_str(&content)
+            .map(Some)
+            .map_err(|e| e.to_string()),
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(None),
+        Err(e) => Err(e.to_string()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entry(name: &str, origin: &str) -> (String, String, String) {
+        (
+            name.to_string(),
+            origin.to_string(),
+            format!("/libraries/{}.jar", name.replace(':', "/")),
+        )
+    }
+
+    #[test]
+    fn test_library_key() {
+        assert_eq!(
+            library_key("org.ow2.asm:asm:9.6"),
+            ("org.ow2.asm:asm".to_string(), "9.6".to_string())
+        );
+        assert_eq!(
+            library_key("org.lwjgl:lwjgl:3.3.1:natives-linux").0,
+            "org.lwjgl:lwjgl:natives-linux"
+        );
+    }
+
+    #[test]
+    fn test_loader_library_wins() {
+        let (libraries, substitutions) = resolve_libraries(vec![
+            entry("net.fabricmc:fabric-loader:0.15.7", "loader"),
+            entry("org.ow2.asm:asm:9.6", "loader"),
+            entry("org.ow2.asm:asm:9.3", "vanilla"),
+            entry("org.lwjgl:lwjgl:3.3.1", "vanilla"),
+            entry("org.lwjgl:lwjgl:3.3.1:natives-linux", "vanilla"),
+        ]);
+        assert_eq!(libraries.len(), 4);
+        assert_eq!(substitutions.len(), 1);
+        assert_eq!(substitutions[0].library, "org.ow2.asm:asm");
+        assert_eq!(substitutions[0].kept.version, "9.6");
+        assert_eq!(substitutions[0].dropped.origin, "vanilla");
+
+        // A library listed twice with the same file is just deduplicated
+        let (libraries, substitutions) = resolve_libraries(vec![
+            entry("com.google.guava:guava:31.1-jre", "loader"),
+            entry("com.google.guava:guava:31.1-jre", "vanilla"),
+        ]);
+        assert_eq!(libraries.len(), 1);
+        assert!(substitutions.is_empty());
+    }
+
+    #[test]
+    fn test_launch_timer() {
+        let mut timer = LaunchTimer::new();
+        timer.finish(LaunchPhase::Verification);
+        std::thread::sleep(std::time::Duration::from_millis(5));
+        timer.finish(LaunchPhase::Download);
+        let phases: Vec<LaunchPhase> = timer.timings().iter().map(|t| t.phase).collect();
+        assert_eq!(
+            phases,
+            vec![LaunchPhase::Verification, LaunchPhase::Download]
+        );
+        assert!(timer.timings()[1].ms >= 5);
+        assert!(timer.total_ms() >= 5);
+        assert!(timer.summary().starts_with("verification "));
+        assert!(timer.summary().contains(", download "));
+
+        // Reports saved before timings were recorded still load
+        let report: LaunchReport = serde_json::from_value(serde_json::json!({
+            "instanceId": "a",
+            "versionId": "1.20.1",
+            "mainClass": "net.minecraft.client.main.Main",
+            "createdAt": 0,
+            "libraries": [],
+            "substitutions": [],
+        }))
+        .unwrap();
+        assert!(report.timings.is_empty());
+    }
+}
@@ -0,0 +1,71 @@
+//! Frontend-agnostic core of the DropOut launcher.
+//!
+//! Nothing in this crate depends on Tauri: progress and other notifications
+//! go through [`events::EventSink`], so the same logic can back the desktop
+//! app, a CLI or tests. `src-tauri` re-exports these modules under
+//! `crate::core` and adapts them to windows and managed state.
+//!
+//! # Features
+//!
+//! All enabled by default:
+//! - `auth-msa`: Microsoft account sign-in and [`skin`] management. Offline
+//!   accounts are always available.
+//! - `auth-yggdrasil`: sign-in to third-party Yggdrasil servers (`auth::yggdrasil`).
+//! - `modpacks`: the [`modpack`] module (Modrinth, CurseForge and MultiMC packs)
+//!   [`mod_list`] (shared lists of mods), [`mod_updates`], [`curseforge`]
+//!   (the CurseForge mod browser) and [`modrinth`] (mods, resource packs and
+//!   shader packs from Modrinth).
+//! - `java-provisioning`: catalog, download and installation of Java runtimes
+//!   in [`java`]. Detection of installed runtimes is always available.
+//! - `assistant`: reserved for the assistant, which has not moved out of the
+//!   desktop app yet.
+//!
+//! The minimum supported Rust version is set by `rust-version` in
+//! `Cargo.toml`; raising it is treated as a breaking change.
+
+pub mod agents;
+pub mod args;
+pub mod asset_index;
+pub mod auth;
+pub mod config;
+#[cfg(feature = "modpacks")]
+pub mod curseforge;
+pub mod dot_minecraft;
+pub mod download_manager;
+pub mod download_tuning;
+pub mod downloader;
+pub mod events;
+pub mod fabric;
+pub mod forge;
+pub mod game_log;
+pub mod game_version;
+pub mod instance;
+pub mod java;
+pub mod jvm_defaults;
+pub mod launch_report;
+pub mod log4j;
+pub mod long_path;
+pub mod manifest;
+pub mod maven;
+#[cfg(feature = "modpacks")]
+pub mod mod_list;
+pub mod mod_rollback;
+#[cfg(feature = "modpacks")]
+pub mod mod_updates;
+#[cfg(feature = "modpacks")]
+pub mod modpack;
+#[cfg(feature = "modpacks")]
+pub mod modrinth;
+pub mod mods;
+pub mod natives;
+pub mod neoforge;
+pub mod optifine;
+pub mod protocol;
+pub mod rules;
+pub mod safe_path;
+pub mod sandbox;
+pub mod server;
+#[cfg(feature = "auth-msa")]
+pub mod skin;
+pub mod version_diff;
+pub mod version_merge;
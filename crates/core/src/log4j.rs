@@ -0,0 +1,129 @@
+//! Log4Shell (CVE-2021-44228) mitigation for Minecraft 1.7 - 1.18.0.
+//!
+//! - 1.17 - 1.18.0 ship Log4j 2.14, which honours
+//!   `-Dlog4j2.formatMsgNoLookups=true`.
+//! - 1.7 - 1.16.5 ship older Log4j without that switch. As the vanilla
+//!   launcher does, the client `logging` config from the version JSON (which
+//!   Mojang replaced with a patched one) is downloaded and passed with
+//!   `-Dlog4j.configurationFile`.
+
+use super::downloader::DownloadTask;
+use super::game_version::{GameVersion, LoggingConfig};
+use std::path::{Path, PathBuf};
+
+pub const NO_LOOKUPS_ARG: &str = "-Dlog4j2.formatMsgNoLookups=true";
+
+/// Parse a release id ("1.16.5") into (minor, patch); snapshots yield `None`
+fn release_parts(minecraft_version: &str) -> Option<(u32, u32)> {
+    let mut parts = minecraft_version.split('.');
+    if parts.next()? != "1" {
+        return None;
+    }
+    let minor = parts.next()?.parse().ok()?;
+    let patch = match parts.next() {
+        Some(patch) => patch.parse().ok()?,
+        None => 0,
+    };
+    Some((minor, patch))
+}
+
+/// Whether the bundled Log4j is vulnerable (1.7 up to and including 1.18.0)
+pub fn is_vulnerable(minecraft_version: &str) -> bool {
+    match release_parts(minecraft_version) {
+        Some((minor, patch)) => (7..=17).contains(&minor) || (minor == 18 && patch == 0),
+        None => false,
+    }
+}
+
+/// Whether the Log4j is too old for `formatMsgNoLookups` (1.7 - 1.16.5)
+pub fn needs_config_file(minecraft_version: &str) -> bool {
+    release_parts(minecraft_version).is_some_and(|(minor, _)| (7..=16).contains(&minor))
+}
+
+fn client_config(version: &GameVersion) -> Option<&LoggingConfig> {
+    version.logging.as_ref()?.client.as_ref()
+}
+
+fn config_path(assets_dir: &Path, config: &LoggingConfig) -> PathBuf {
+    assets_dir.join("log_configs").join(&config.file.id)
+}
+
+/// Download task for the patched Log4j config, when the version needs one
+pub fn config_download_task(
+    version: &GameVersion,
+    minecraft_version: &str,
+    assets_dir: &Path,
+) -> Option<DownloadTask> {
+    if !needs_config_file(minecraft_version) {
+        return None;
+    }
+    let config = client_config(version)?;
+    Some(DownloadTask {
+        url: config.file.url.clone(),
+        path: config_path(assets_dir, config),
+        sha1: config.file.sha1.clone(),
+        sha256: None,
+    })
+}
+
+/// JVM arguments mitigating Log4Shell; empty for versions that aren't affected.
+/// Without a downloaded config on 1.7 - 1.16.5 the flag is still added, but
+/// it has no effect there.
+pub fn jvm_args(version: &GameVersion, minecraft_version: &str, assets_dir: &Path) -> Vec<String> {
+    if !is_vulnerable(minecraft_version) {
+        return Vec::new();
+    }
+    if needs_config_file(minecraft_version)
+        && let Some(config) = client_config(version)
+    {
+        let path = config_path(assets_dir, config);
+        if path.exists() {
+            return vec![config.argument.replace("${path}", &path.to_string_lossy())];
+        }
+    }
+    vec![NO_LOOKUPS_ARG.to_string()]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_is_vulnerable() {
+        assert!(!is_vulnerable("1.6.4"));
+        assert!(is_vulnerable("1.7"));
+        assert!(is_vulnerable("1.12.2"));
+        assert!(is_vulnerable("1.17.1"));
+        assert!(is_vulnerable("1.18"));
+        assert!(!is_vulnerable("1.18.1"));
+        assert!(!is_vulnerable("1.20.4"));
+        assert!(!is_vulnerable("21w44a"));
+    }
+
+    #[test]
+    fn test_fallback_without_logging_section() {
+        let version: GameVersion = serde_json::from_str(
+            r#"{"id": "1.17.1", "libraries": [], "mainClass": "net.minecraft.client.main.Main"}"#,
+        )
+        .unwrap();
+        assert_eq!(
+            jvm_args(&version, "1.17.1", Path::new("/nonexistent")),
+            vec![NO_LOOKUPS_ARG]
+        );
+        assert!(jvm_args(&version, "1.19.2", Path::new("/nonexistent")).is_empty());
+    }
+
+    #[test]
+    fn test_config_only_for_old_log4j() {
+        let version: GameVersion = serde_json::from_str(
+            r#"{"id": "1.12.2", "libraries": [], "mainClass": "net.minecraft.client.main.Main",
+                "logging": {"client": {"argument": "-Dlog4j.configurationFile=${path}",
+                "file": {"id": "client-1.12.xml", "sha1": null,
+                "size": 888, "url": "https://example.com/client-1.12.xml"}, "type": "log4j2-xml"}}}"#,
+        )
+        .unwrap();
+        let task = config_download_task(&version, "1.12.2", Path::new("/assets")).unwrap();
+        assert_eq!(task.path, Path::new("/assets/log_configs/client-1.12.xml"));
+        assert!(config_download_task(&version, "1.17.1", Path::new("/assets")).is_none());
+    }
+}
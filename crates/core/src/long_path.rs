@@ -0,0 +1,125 @@
+//! Windows long-path handling.
+//!
+//! Win32 file APIs reject paths of `MAX_PATH` (260) characters or more unless
+//! they use the extended-length `\\?\` form, and deep Maven paths under a
+//! long user profile easily get there. Extended paths are passed to the OS
+//! verbatim, so they must be absolute with `\` separators and no `.` or `..`
+//! components; [`extended`] takes care of that.
+
+use std::path::{Path, PathBuf};
+
+/// Length from which paths are converted. Directories are limited to 248
+/// characters (room for an 8.3 file name), so convert from there.
+pub const LONG_PATH_THRESHOLD: usize = 248;
+
+/// Extended-length form of an absolute Windows path ("C:\..." or
+/// "\\server\share\..."); `None` for relative paths. Paths already in
+/// `\\?\` or device (`\\.\`) form are returned as they are.
+pub fn extended(path: &str) -> Option<String> {
+    if path.starts_with(r"\\?\") || path.starts_with(r"\\.\") {
+        return Some(path.to_string());
+    }
+    let path = path.replace('/', "\\");
+    let (prefix, rest) = if let Some(unc) = path.strip_prefix(r"\\") {
+        let mut parts = unc.splitn(3, '\\');
+        let server = parts.next().filter(|s| !s.is_empty())?;
+        let share = parts.next().filter(|s| !s.is_empty())?;
+        (
+            format!(r"\\?\UNC\{}\{}", server, share),
+            parts.next().unwrap_or(""),
+        )
+    } else {
+        let bytes = path.as_bytes();
+        let is_drive = bytes.len() >= 3
+            && bytes[0].is_ascii_alphabetic()
+            && bytes[1] == b':'
+            && bytes[2] == b'\\';
+        if !is_drive {
+            return None;
+        }
+        (format!(r"\\?\{}", &path[..2]), &path[3..])
+    };
+
+    let mut components: Vec<&str> = Vec::new();
+    for component in rest.split('\\') {
+        match component {
+            "" | "." => {}
+            ".." => {
+                components.pop();
+            }
+            c => components.push(c),
+        }
+    }
+    let mut out = prefix;
+    for component in components {
+        out.push('\\');
+        out.push_str(component);
+    }
+    if out.ends_with(':') {
+        out.push('\\'); // drive root
+    }
+    Some(out)
+}
+
+/// `path`, in extended-length form if this is Windows and it's long enough
+/// to need it
+pub fn long_path(path: &Path) -> PathBuf {
+    if cfg!(target_os = "windows")
+        && path.as_os_str().len() >= LONG_PATH_THRESHOLD
+        && let Some(extended) = path.to_str().and_then(extended)
+    {
+        return PathBuf::from(extended);
+    }
+    path.to_path_buf()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn deep_windows_path() -> String {
+        let mut path =
+            r"C:\Users\Some User\AppData\Roaming\com.dropout.launcher\libraries".to_string();
+        while path.len() <= 300 {
+            path.push_str(r"\org\example\very-long-artifact-name");
+        }
+        path.push_str(r"\1.0.0\very-long-artifact-name-1.0.0-natives-windows.jar");
+        path
+    }
+
+    #[test]
+    fn test_extended_drive_paths() {
+        let path = deep_windows_path();
+        assert!(path.len() > 260);
+        assert_eq!(extended(&path).unwrap(), format!(r"\\?\{}", path));
+
+        assert_eq!(
+            extended(r"C:/games/./mc/../dropout\libraries/").as_deref(),
+            Some(r"\\?\C:\games\dropout\libraries")
+        );
+        assert_eq!(extended(r"D:\").as_deref(), Some(r"\\?\D:\"));
+        assert_eq!(extended(r"D:\..").as_deref(), Some(r"\\?\D:\"));
+    }
+
+    #[test]
+    fn test_extended_unc_and_relative() {
+        assert_eq!(
+            extended(r"\\nas\games\.minecraft\libraries").as_deref(),
+            Some(r"\\?\UNC\nas\games\.minecraft\libraries")
+        );
+        let already = r"\\?\C:\games\x.jar";
+        assert_eq!(extended(already).as_deref(), Some(already));
+        assert_eq!(extended(r"libraries\x.jar"), None);
+        assert_eq!(extended(r"\\nas"), None);
+    }
+
+    #[test]
+    fn test_long_path_keeps_short_paths() {
+        let short = Path::new("/tmp/dropout/x.jar");
+        assert_eq!(long_path(short), short);
+        if !cfg!(target_os = "windows") {
+            let long = PathBuf::from(format!("/tmp/{}", "a/".repeat(200)));
+            assert_eq!(long_path(&long), long);
+        }
+    }
+}
@@ -2,12 +2,13 @@ use serde::{Deserialize, Serialize};
 use std::error::Error;
 use std::path::PathBuf;
 
-use crate::core::game_version::GameVersion;
+use crate::downloader::{get_mirrored, verify_checksum};
+use crate::game_version::GameVersion;
 use ts_rs::TS;
 
 #[derive(Debug, Deserialize, Serialize, TS)]
 #[serde(rename_all = "camelCase")]
-#[ts(export, export_to = "manifest.ts")]
+#[ts(export, export_to = "dropout-core/manifest.ts")]
 pub struct VersionManifest {
     pub latest: Latest,
     pub versions: Vec<Version>,
@@ -15,7 +16,7 @@ pub struct VersionManifest {
 
 #[derive(Debug, Deserialize, Serialize, TS)]
 #[serde(rename_all = "camelCase")]
-#[ts(export, export_to = "manifest.ts")]
+#[ts(export, export_to = "dropout-core/manifest.ts")]
 pub struct Latest {
     pub release: String,
     pub snapshot: String,
@@ -23,7 +24,7 @@ pub struct Latest {
 
 #[derive(Debug, Deserialize, Serialize, TS)]
 #[serde(rename_all = "camelCase")]
-#[ts(export, export_to = "manifest.ts")]
+#[ts(export, export_to = "dropout-core/manifest.ts")]
 pub struct Version {
     pub id: String,
     #[serde(rename = "type")]
@@ -32,6 +33,13 @@ pub struct Version {
     pub time: String,
     #[serde(rename = "releaseTime")]
     pub release_time: String,
+    /// SHA1 of the version JSON (manifest v2)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub sha1: Option<String>,
+    /// 1 for versions that show the player safety warning and need the
+    /// newer Microsoft authentication; 0 before that
+    #[serde(default)]
+    pub compliance_level: u32,
     /// Java version requirement (major version number)
     /// This is populated from the version JSON file if the version is installed locally
     #[serde(skip_serializing_if = "Option::is_none")]
@@ -39,11 +47,26 @@ pub struct Version {
     /// Whether this version is installed locally
     #[serde(rename = "isInstalled", skip_serializing_if = "Option::is_none")]
     pub is_installed: Option<bool>,
+    /// Whether the instance pinned this version to the top of the picker
+    #[serde(rename = "isPinned", skip_serializing_if = "Option::is_none")]
+    pub is_pinned: Option<bool>,
+}
+
+/// Mark pinned versions and move them to the front, keeping the manifest
+/// order (newest first) within pinned and unpinned entries.
+pub fn mark_pinned(versions: &mut [Version], pinned: &[String]) {
+    for version in versions.iter_mut() {
+        version.is_pinned = Some(pinned.contains(&version.id));
+    }
+    versions.sort_by_key(|v| v.is_pinned != Some(true));
 }
 
 pub async fn fetch_version_manifest() -> Result<VersionManifest, Box<dyn Error + Send + Sync>> {
     let url = "https://piston-meta.mojang.com/mc/game/version_manifest_v2.json";
-    let resp = reqwest::get(url).await?.json::<VersionManifest>().await?;
+    let resp = get_mirrored(&reqwest::Client::new(), url)
+        .await?
+        .json::<VersionManifest>()
+        .await?;
     Ok(resp)
 }
 
@@ -86,6 +109,14 @@ pub async fn load_local_version(
 pub async fn fetch_vanilla_version(
     version_id: &str,
 ) -> Result<GameVersion, Box<dyn Error + Send + Sync>> {
+    Ok(fetch_vanilla_version_json(version_id).await?.0)
+}
+
+/// Fetch a version JSON like [`fetch_vanilla_version`], also returning the
+/// verified bytes as served so they can be stored unchanged.
+pub async fn fetch_vanilla_version_json(
+    version_id: &str,
+) -> Result<(GameVersion, Vec<u8>), Box<dyn Error + Send + Sync>> {
     // First, get the manifest to find the version URL
     let manifest = fetch_version_manifest().await?;
 
@@ -96,12 +127,25 @@ pub async fn fetch_vanilla_version(
         .ok_or_else(|| format!("Version {} not found in manifest", version_id))?;
 
     // Fetch the actual version JSON
-    let resp = reqwest::get(&version_entry.url)
+    let data = get_mirrored(&reqwest::Client::new(), &version_entry.url)
         .await?
-        .json::<GameVersion>()
+        .error_for_status()?
+        .bytes()
         .await?;
+    let version = parse_version_json(&data, version_entry.sha1.as_deref())?;
+    Ok((version, data.to_vec()))
+}
 
-    Ok(resp)
+/// Parse a downloaded version JSON, checking it against the manifest's SHA1
+/// (a mirror could serve a stale or truncated file)
+pub fn parse_version_json(
+    data: &[u8],
+    sha1: Option<&str>,
+) -> Result<GameVersion, Box<dyn Error + Send + Sync>> {
+    if !verify_checksum(data, None, sha1) {
+        return Err("Version JSON doesn't match the checksum in the version manifest".into());
+    }
+    Ok(serde_json::from_slice(data)?)
 }
 
 /// Find the root vanilla version by following the inheritance chain.
@@ -174,7 +218,7 @@ pub async fn load_version(
         };
 
         // Merge child into parent
-        version = crate::core::version_merge::merge_versions(version, parent);
+        version = crate::version_merge::merge_versions(version, parent);
     }
 
     Ok(version)
@@ -197,11 +241,26 @@ pub async fn save_local_version(
 
     let json_path = version_dir.join(format!("{}.json", version.id));
     let content = serde_json::to_string_pretty(version)?;
-    tokio::fs::write(&json_path, content).await?;
+    crate::downloader::write_atomic(&json_path, content.as_bytes())?;
 
     Ok(json_path)
 }
 
+/// Save a version JSON fetched with [`fetch_vanilla_version_json`] as is, so
+/// it still matches the manifest's SHA1.
+pub fn save_version_json(
+    game_dir: &std::path::Path,
+    version_id: &str,
+    data: &[u8],
+) -> Result<PathBuf, String> {
+    let json_path = game_dir
+        .join("versions")
+        .join(version_id)
+        .join(format!("{}.json", version_id));
+    crate::downloader::write_atomic(&json_path, data)?;
+    Ok(json_path)
+}
+
 /// List all locally installed versions.
 ///
 /// # Arguments
@@ -233,3 +292,55 @@ pub async fn list_local_versions(
 
     Ok(versions)
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn version(id: &str) -> Version {
+        Version {
+            id: id.to_string(),
+            type_: "release".to_string(),
+            url: String::new(),
+            time: String::new(),
+            release_time: String::new(),
+            sha1: None,
+            compliance_level: 0,
+            java_version: None,
+            is_installed: None,
+            is_pinned: None,
+        }
+    }
+
+    #[test]
+    fn test_mark_pinned() {
+        let mut versions = vec![version("1.21.1"), version("1.20.4"), version("1.12.2")];
+        mark_pinned(&mut versions, &["1.12.2".to_string()]);
+        let ids: Vec<&str> = versions.iter().map(|v| v.id.as_str()).collect();
+        assert_eq!(ids, vec!["1.12.2", "1.21.1", "1.20.4"]);
+        assert_eq!(versions[0].is_pinned, Some(true));
+        assert_eq!(versions[1].is_pinned, Some(false));
+    }
+
+    #[test]
+    fn test_manifest_v2_entry() {
+        let json = r#"{
+            "id": "1.21.1",
+            "type": "release",
+            "url": "https://piston-meta.mojang.com/v1/packages/aaa/1.21.1.json",
+            "time": "2024-08-08T12:24:45+00:00",
+            "releaseTime": "2024-08-08T12:24:45+00:00",
+            "sha1": "aaa",
+            "complianceLevel": 1
+        }"#;
+        let version: Version = serde_json::from_str(json).unwrap();
+        assert_eq!(version.sha1.as_deref(), Some("aaa"));
+        assert_eq!(version.compliance_level, 1);
+
+        let data =
+            br#"{"id":"1.21.1","libraries":[],"mainClass":"net.minecraft.client.main.Main"}"#;
+        let sha1 = crate::downloader::compute_sha1(data);
+        assert!(parse_version_json(data, Some(&sha1)).is_ok());
+        assert!(parse_version_json(data, Some("0000")).is_err());
+    }
+}
@@ -12,6 +12,7 @@ use std::path::PathBuf;
 pub const MAVEN_CENTRAL: &str = "https://repo1.maven.org/maven2/";
 pub const FABRIC_MAVEN: &str = "https://maven.fabricmc.net/";
 pub const FORGE_MAVEN: &str = "https://maven.minecraftforge.net/";
+pub const NEOFORGE_MAVEN: &str = "https://maven.neoforged.net/releases/";
 pub const MOJANG_LIBRARIES: &str = "https://libraries.minecraft.net/";
 
 /// Represents a parsed Maven coordinate.
@@ -42,6 +43,7 @@ impl MavenCoordinate {
     ///
     /// # Examples
     /// ```
+    /// # use dropout_core::maven::MavenCoordinate;
     /// let coord = MavenCoordinate::parse("net.fabricmc:fabric-loader:0.14.21").unwrap();
     /// assert_eq!(coord.group, "net.fabricmc");
     /// assert_eq!(coord.artifact, "fabric-loader");
@@ -85,6 +87,7 @@ impl MavenCoordinate {
     ///
     /// # Examples
     /// ```
+    /// # use dropout_core::maven::MavenCoordinate;
     /// let coord = MavenCoordinate::parse("net.fabricmc:fabric-loader:0.14.21").unwrap();
     /// assert_eq!(coord.to_path(), "net/fabricmc/fabric-loader/0.14.21/fabric-loader-0.14.21.jar");
     /// ```
@@ -168,6 +171,8 @@ pub fn resolve_library_url(
             || coord.group.starts_with("cpw.mods")
         {
             FORGE_MAVEN
+        } else if coord.group.starts_with("net.neoforged") {
+            NEOFORGE_MAVEN
         } else {
             MOJANG_LIBRARIES
         }
@@ -261,4 +266,10 @@ mod tests {
         let url = resolve_library_url("net.fabricmc:fabric-loader:0.14.21", None, None);
         assert!(url.unwrap().starts_with(FABRIC_MAVEN));
     }
+
+    #[test]
+    fn test_resolve_library_url_neoforge() {
+        let url = resolve_library_url("net.neoforged.fancymodloader:loader:4.0.24", None, None);
+        assert!(url.unwrap().starts_with(NEOFORGE_MAVEN));
+    }
 }
@@ -0,0 +1,315 @@
+//! Shared mod lists: plain lists of Modrinth and CurseForge projects that are
+//! installed at the newest version compatible with an instance.
+//!
+//! A list is either JSON — `["sodium", "curseforge:jei"]`,
+//! `[{"source": "modrinth", "slug": "sodium"}]`, or either of those under a
+//! `"mods"` key — or text/Markdown with one project per line, written as
+//! `slug`, `source:slug` or a project URL, optionally as a list item or a
+//! `[name](url)` link. Headings and blank lines are ignored. Projects without
+//! a source are looked up on Modrinth.
+
+use crate::modpack::ModpackFile;
+use serde::{Deserialize, Serialize};
+use ts_rs::TS;
+
+pub(crate) const CURSEFORGE_MINECRAFT: u32 = 432;
+pub(crate) const CURSEFORGE_CLASS_MODS: u32 = 6;
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, TS)]
+#[ts(export, export_to = "dropout-core/mod_list.ts")]
+pub struct ModListEntry {
+    pub source: String, // "modrinth" | "curseforge"
+    pub slug: String,
+}
+
+impl std::fmt::Display for ModListEntry {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}:{}", self.source, self.slug)
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+#[ts(export, export_to = "dropout-core/mod_list.ts")]
+pub struct ModListFailure {
+    pub entry: String,
+    pub error: String,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize, TS)]
+#[ts(export, export_to = "dropout-core/mod_list.ts")]
+pub struct ModListImport {
+    pub installed: Vec<String>, // file names under mods/
+    pub failed: Vec<ModListFailure>,
+}
+
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum JsonEntry {
+    Text(String),
+    Entry {
+        #[serde(default)]
+        source: Option<String>,
+        #[serde(alias = "id", alias = "project")]
+        slug: String,
+    },
+}
+
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum JsonList {
+    List(Vec<JsonEntry>),
+    Wrapped { mods: Vec<JsonEntry> },
+}
+
+fn is_slug(value: &str) -> bool {
+    !value.is_empty()
+        && value.len() <= 64
+        && value
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || matches!(c, '-' | '_' | '.'))
+}
+
+fn normalize_source(source: &str) -> Option<&'static str> {
+    match source.trim().to_ascii_lowercase().as_str() {
+        "modrinth" | "mr" => Some("modrinth"),
+        "curseforge" | "cf" => Some("curseforge"),
+        _ => None,
+    }
+}
+
+fn entry(source: &str, slug: &str) -> Result<ModListEntry, String> {
+    let source = normalize_source(source).ok_or_else(|| format!("Unknown source '{}'", source))?;
+    let slug = slug.trim().trim_end_matches('/');
+    if !is_slug(slug) {
+        return Err(format!("Invalid project slug '{}'", slug));
+    }
+    Ok(ModListEntry {
+        source: source.to_string(),
+        slug: slug.to_string(),
+    })
+}
+
+/// Project from a modrinth.com or curseforge.com URL
+fn entry_from_url(url: &str) -> Result<ModListEntry, String> {
+    let rest = url
+        .split_once("://")
+        .map_or(url, |(_, rest)| rest)
+        .trim_start_matches("www.");
+    let (host, path) = rest.split_once('/').unwrap_or((rest, ""));
+    let segments: Vec<&str> = path
+        .split(['?', '#'])
+        .next()
+        .unwrap_or_default()
+        .split('/')
+        .filter(|s| !s.is_empty())
+        .collect();
+    match (host, segments.as_slice()) {
+        ("modrinth.com", [_kind, slug, ..]) => entry("modrinth", slug),
+        ("curseforge.com", ["minecraft", _class, slug, ..]) => entry("curseforge", slug),
+        _ => Err(format!("Unsupported project URL '{}'", url)),
+    }
+}
+
+/// One line of a text list: `slug`, `source:slug`, a URL or a Markdown link
+fn parse_line(line: &str) -> Option<Result<ModListEntry, String>> {
+    let mut line = line.trim();
+    if line.is_empty() || line.starts_with('#') {
+        return None;
+    }
+    // List markers: "-", "*", "+", "1."
+    if let Some(rest) = line.strip_prefix(['-', '*', '+']) {
+        line = rest.trim_start();
+    } else if let Some((number, rest)) = line.split_once(". ")
+        && number.chars().all(|c| c.is_ascii_digit())
+    {
+        line = rest.trim_start();
+    }
+    // "[Sodium](https://modrinth.com/mod/sodium)" -> the URL
+    if let Some(start) = line.find("](")
+        && let Some(len) = line[start + 2..].find(')')
+    {
+        line = &line[start + 2..start + 2 + len];
+    }
+    let line = line.trim();
+    if line.is_empty() {
+        return None;
+    }
+    if line.contains("://") || line.contains(".com/") {
+        return Some(entry_from_url(line));
+    }
+    Some(match line.split_once(':') {
+        Some((source, slug)) => entry(source, slug),
+        None => entry("modrinth", line),
+    })
+}
+
+/// Parse a shared mod list; fails on the first malformed entry
+pub fn parse_manifest(text: &str) -> Result<Vec<ModListEntry>, String> {
+    let text = text.trim();
+    let mut entries: Vec<ModListEntry> = Vec::new();
+    if text.starts_with('[') || text.starts_with('{') {
+        let list: JsonList =
+            serde_json::from_str(text).map_err(|e| format!("Invalid mod list JSON: {}", e))?;
+        let (JsonList::List(items) | JsonList::Wrapped { mods: items }) = list;
+        for item in items {
+            let parsed = match item {
+                JsonEntry::Text(line) => parse_line(&line)
+                    .unwrap_or_else(|| Err(format!("Invalid mod list entry '{}'", line)))?,
+                JsonEntry::Entry { source, slug } => {
+                    entry(source.as_deref().unwrap_or("modrinth"), &slug)?
+                }
+            };
+            entries.push(parsed);
+        }
+    } else {
+        for line in text.lines() {
+            if let Some(parsed) = parse_line(line) {
+                entries.push(parsed?);
+            }
+        }
+    }
+    entries.dedup_by(|a, b| a == b);
+    if entries.is_empty() {
+        return Err("The mod list is empty".to_string());
+    }
+    Ok(entries)
+}
+
+/// CurseForge `modLoaderType` of a loader name
+pub(crate) fn curseforge_loader_type(loader: &str) -> Option<u32> {
+    match loader {
+        "forge" => Some(1),
+        "fabric" => Some(4),
+        "quilt" => Some(5),
+        "neoforge" => Some(6),
+        _ => None,
+    }
+}
+
+/// The newest file of `entry` for `game_version` and `loader` ("fabric",
+/// "forge", "quilt" or "neoforge"), placed under `mods/`
+pub async fn resolve(
+    entry: &ModListEntry,
+    game_version: &str,
+    loader: &str,
+) -> Result<ModpackFile, String> {
+    match entry.source.as_str() {
+        "modrinth" => resolve_modrinth(&entry.slug, game_version, loader).await,
+        "curseforge" => resolve_curseforge(&entry.slug, game_version, loader).await,
+        other => Err(format!("Unknown source '{}'", other)),
+    }
+}
+
+async fn resolve_modrinth(
+    slug: &str,
+    game_version: &str,
+    loader: &str,
+) -> Result<ModpackFile, String> {
+    let versions =
+        crate::modrinth::versions(slug, Some(game_version), &[loader.to_string()]).await?;
+    // Versions come newest first
+    let version = versions
+        .first()
+        .ok_or_else(|| format!("No version of {slug} for {loader} {game_version}"))?;
+    crate::modrinth::to_download(version, "mod")
+}
+
+async fn resolve_curseforge(
+    slug: &str,
+    game_version: &str,
+    loader: &str,
+) -> Result<ModpackFile, String> {
+    let client = reqwest::Client::new();
+    let search = crate::modpack::cf_get(
+        &client,
+        "/v1/mods/search",
+        &[
+            ("gameId", CURSEFORGE_MINECRAFT.to_string()),
+            ("classId", CURSEFORGE_CLASS_MODS.to_string()),
+            ("slug", slug.to_string()),
+        ],
+    )
+    .await?;
+    let mod_id = search["data"][0]["id"]
+        .as_u64()
+        .ok_or_else(|| format!("Project {slug} not found on CurseForge"))?;
+
+    let mut query = vec![("gameVersion", game_version.to_string())];
+    if let Some(loader_type) = curseforge_loader_type(loader) {
+        query.push(("modLoaderType", loader_type.to_string()));
+    }
+    let files =
+        crate::modpack::cf_get(&client, &format!("/v1/mods/{mod_id}/files"), &query).await?;
+    // `fileDate` is ISO 8601, so the newest file sorts last
+    let file = files["data"]
+        .as_array()
+        .and_then(|files| {
+            files
+                .iter()
+                .max_by_key(|f| f["fileDate"].as_str().unwrap_or_default())
+        })
+        .ok_or_else(|| format!("No file of {slug} for {loader} {game_version}"))?;
+    let name = file["fileName"].as_str().ok_or("Missing file name")?;
+    let sha1 = file["hashes"].as_array().and_then(|hashes| {
+        hashes
+            .iter()
+            .find(|h| h["algo"].as_u64() == Some(1))
+            .and_then(|h| h["value"].as_str())
+            .map(String::from)
+    });
+    Ok(ModpackFile {
+        url: crate::modpack::cf_download_url(file).ok_or("Missing file URL")?,
+        path: format!("mods/{name}"),
+        size: file["fileLength"].as_u64(),
+        sha1,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn e(source: &str, slug: &str) -> ModListEntry {
+        ModListEntry {
+            source: source.to_string(),
+            slug: slug.to_string(),
+        }
+    }
+
+    #[test]
+    fn test_parse_markdown() {
+        let text = "# My mods\n\n\
+            - sodium\n\
+            * curseforge:jei\n\
+            1. [Lithium](https://modrinth.com/mod/lithium)\n\
+            - https://www.curseforge.com/minecraft/mc-mods/appleskin/files\n";
+        assert_eq!(
+            parse_manifest(text).unwrap(),
+            vec![
+                e("modrinth", "sodium"),
+                e("curseforge", "jei"),
+                e("modrinth", "lithium"),
+                e("curseforge", "appleskin"),
+            ]
+        );
+        assert!(parse_manifest("- github:foo").is_err());
+        assert!(parse_manifest("- ../../etc").is_err());
+        assert!(parse_manifest("# nothing here").is_err());
+    }
+
+    #[test]
+    fn test_parse_json() {
+        assert_eq!(
+            parse_manifest(r#"["sodium", "cf:jei"]"#).unwrap(),
+            vec![e("modrinth", "sodium"), e("curseforge", "jei")]
+        );
+        assert_eq!(
+            parse_manifest(
+                r#"{"mods": [{"source": "curseforge", "slug": "jei"}, {"id": "iris"}]}"#
+            )
+            .unwrap(),
+            vec![e("curseforge", "jei"), e("modrinth", "iris")]
+        );
+        assert!(parse_manifest(r#"{"mods": 1}"#).is_err());
+    }
+}
@@ -0,0 +1,212 @@
+//! Rollback point for the last update of an instance's `mods/` folder.
+//!
+//! Before mod updates (or a modpack upgrade) touch `mods/`, [`create_point`]
+//! records the name and SHA1 of every jar there, the names of the jars the
+//! update will write, and copies the jars about to be replaced or removed
+//! into `<game_dir>/rollback/`. [`restore`] then undoes the update: the jars
+//! it wrote are deleted and the copies are moved back. Jars the user added or
+//! changed since are left alone. Only the most recent update can be undone;
+//! a new point replaces the previous one.
+
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use ts_rs::TS;
+
+use crate::downloader::compute_sha1;
+
+const ROLLBACK_DIR: &str = "rollback";
+const MANIFEST_FILE: &str = "manifest.json";
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export, export_to = "dropout-core/mod_rollback.ts")]
+pub struct RollbackEntry {
+    pub file_name: String,
+    pub sha1: String,
+    pub saved: bool, // a copy is in the rollback folder
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export, export_to = "dropout-core/mod_rollback.ts")]
+pub struct RollbackPoint {
+    pub reason: String, // e.g. "mod updates"
+    pub created_at: i64,
+    pub files: Vec<RollbackEntry>, // mods/ before the update
+    #[serde(default)]
+    pub written: Vec<String>, // file names the update writes into mods/
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export, export_to = "dropout-core/mod_rollback.ts")]
+pub struct RollbackResult {
+    pub restored: Vec<String>,
+    pub removed: Vec<String>,
+}
+
+fn rollback_dir(game_dir: &Path) -> PathBuf {
+    game_dir.join(ROLLBACK_DIR)
+}
+
+/// Name and SHA1 of every file in `mods/` (jars and disabled jars)
+fn hash_mods(mods_dir: &Path) -> Result<HashMap<String, String>, String> {
+    let mut files = HashMap::new();
+    let Ok(entries) = std::fs::read_dir(mods_dir) else {
+        return Ok(files);
+    };
+    for entry in entries.flatten() {
+        let path = entry.path();
+        if !path.is_file() {
+            continue;
+        }
+        let data = std::fs::read(&path).map_err(|e| e.to_string())?;
+        files.insert(
+            entry.file_name().to_string_lossy().to_string(),
+            compute_sha1(&data),
+        );
+    }
+    Ok(files)
+}
+
+/// Record `mods/` and save copies of `replaced` (file names in `mods/`)
+/// before an update that writes the jars named in `written`
+pub fn create_point(
+    game_dir: &Path,
+    reason: &str,
+    replaced: &[&str],
+    written: &[&str],
+) -> Result<RollbackPoint, String> {
+    let dir = rollback_dir(game_dir);
+    if dir.exists() {
+        std::fs::remove_dir_all(&dir).map_err(|e| e.to_string())?;
+    }
+    std::fs::create_dir_all(&dir).map_err(|e| e.to_string())?;
+
+    let mods_dir = game_dir.join("mods");
+    let mut files: Vec<RollbackEntry> = hash_mods(&mods_dir)?
+        .into_iter()
+        .map(|(file_name, sha1)| RollbackEntry {
+            saved: false,
+            file_name,
+            sha1,
+        })
+        .collect();
+    files.sort_by(|a, b| a.file_name.cmp(&b.file_name));
+    for entry in files
+        .iter_mut()
+        .filter(|e| replaced.contains(&e.file_name.as_str()))
+    {
+        std::fs::copy(mods_dir.join(&entry.file_name), dir.join(&entry.file_name))
+            .map_err(|e| format!("Failed to back up {}: {}", entry.file_name, e))?;
+        entry.saved = true;
+    }
+
+    let point = RollbackPoint {
+        reason: reason.to_string(),
+        created_at: std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .map_or(0, |d| d.as_secs() as i64),
+        files,
+        written: written.iter().map(|name| name.to_string()).collect(),
+    };
+    let content = serde_json::to_string_pretty(&point).map_err(|e| e.to_string())?;
+    std::fs::write(dir.join(MANIFEST_FILE), content).map_err(|e| e.to_string())?;
+    Ok(point)
+}
+
+/// The current rollback point, if any
+pub fn load_point(game_dir: &Path) -> Result<Option<RollbackPoint>, String> {
+    match std::fs::read_to_string(rollback_dir(game_dir).join(MANIFEST_FILE)) {
+        Ok(content) => serde_json::from_str(&content)
+            .map(Some)
+            .map_err(|e| e.to_string()),
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(None),
+        Err(e) => Err(e.to_string()),
+    }
+}
+
+/// Undo the update recorded in the rollback point and remove the point
+pub fn restore(game_dir: &Path) -> Result<RollbackResult, String> {
+    let point = load_point(game_dir)?.ok_or("There is no update to roll back")?;
+    let dir = rollback_dir(game_dir);
+    let mods_dir = game_dir.join("mods");
+    let current = hash_mods(&mods_dir)?;
+    let before: HashMap<&str, &RollbackEntry> = point
+        .files
+        .iter()
+        .map(|e| (e.file_name.as_str(), e))
+        .collect();
+
+    let mut result = RollbackResult::default();
+    // Only what the update wrote goes; anything else new is the user's
+    for file_name in &point.written {
+        let Some(sha1) = current.get(file_name) else {
+            continue;
+        };
+        let unchanged = before
+            .get(file_name.as_str())
+            .is_some_and(|e| &e.sha1 == sha1);
+        if !unchanged {
+            std::fs::remove_file(mods_dir.join(file_name)).map_err(|e| e.to_string())?;
+            result.removed.push(file_name.clone());
+        }
+    }
+    std::fs::create_dir_all(&mods_dir).map_err(|e| e.to_string())?;
+    for entry in point.files.iter().filter(|e| e.saved) {
+        if current.get(&entry.file_name) == Some(&entry.sha1) {
+            continue;
+        }
+        std::fs::copy(dir.join(&entry.file_name), mods_dir.join(&entry.file_name))
+            .map_err(|e| format!("Failed to restore {}: {}", entry.file_name, e))?;
+        result.restored.push(entry.file_name.clone());
+    }
+    result.removed.sort();
+    result.restored.sort();
+
+    std::fs::remove_dir_all(&dir).map_err(|e| e.to_string())?;
+    Ok(result)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+
+    #[test]
+    fn test_restore_undoes_update() {
+        let dir = std::env::temp_dir().join(format!("dropout-rollback-{}", uuid::Uuid::new_v4()));
+        let mods = dir.join("mods");
+        fs::create_dir_all(&mods).unwrap();
+        fs::write(mods.join("sodium-0.5.3.jar"), b"old sodium").unwrap();
+        fs::write(mods.join("lithium.jar"), b"lithium").unwrap();
+
+        let point = create_point(
+            &dir,
+            "mod updates",
+            &["sodium-0.5.3.jar"],
+            &["sodium-0.5.8.jar"],
+        )
+        .unwrap();
+        assert_eq!(point.files.len(), 2);
+
+        // The update swaps the sodium jar, then the user adds a mod
+        fs::remove_file(mods.join("sodium-0.5.3.jar")).unwrap();
+        fs::write(mods.join("sodium-0.5.8.jar"), b"new sodium").unwrap();
+        fs::write(mods.join("iris.jar"), b"iris").unwrap();
+
+        let result = restore(&dir).unwrap();
+        assert_eq!(result.restored, vec!["sodium-0.5.3.jar"]);
+        assert_eq!(result.removed, vec!["sodium-0.5.8.jar"]);
+        assert_eq!(
+            fs::read(mods.join("sodium-0.5.3.jar")).unwrap(),
+            b"old sodium"
+        );
+        assert!(mods.join("lithium.jar").exists());
+        assert!(mods.join("iris.jar").exists());
+        assert!(load_point(&dir).unwrap().is_none());
+        assert!(restore(&dir).is_err());
+        let _ = fs::remove_dir_all(&dir);
+    }
+}
@@ -0,0 +1,292 @@
+//! Update checks for installed mods.
+//!
+//! Jars are identified on Modrinth by their SHA1, and the newest version of
+//! the same project for the instance's Minecraft version and loader is the
+//! update. Jars Modrinth doesn't know (CurseForge-only or local builds) are
+//! left alone.
+//!
+//! An update is *safe* when its version number keeps the major version (the
+//! minor one for 0.x versions, as with semver caret ranges), so it can be
+//! applied without asking, e.g. right before a launch.
+
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::path::Path;
+use ts_rs::TS;
+
+use crate::downloader::compute_sha1;
+
+const MODRINTH_API: &str = "https://api.modrinth.com/v2";
+
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export, export_to = "dropout-core/mod_updates.ts")]
+pub struct ModUpdate {
+    pub file_name: String, // current jar under mods/
+    pub project_id: String,
+    pub current_version: String,
+    pub new_version: String,
+    pub new_file_name: String,
+    pub url: String,
+    pub sha1: String,
+    pub safe: bool,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export, export_to = "dropout-core/mod_updates.ts")]
+pub struct ModUpdateFailure {
+    pub file_name: String,
+    pub error: String,
+}
+
+/// Result of [`apply_safe_updates`]
+#[derive(Debug, Clone, Default, Serialize, Deserialize, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export, export_to = "dropout-core/mod_updates.ts")]
+pub struct AutoUpdateSummary {
+    pub updated: Vec<ModUpdate>,
+    pub skipped: Vec<ModUpdate>, // major updates, left for the user to apply
+    pub failed: Vec<ModUpdateFailure>,
+}
+
+#[derive(Deserialize)]
+struct ModrinthFile {
+    url: String,
+    filename: String,
+    primary: bool,
+    hashes: HashMap<String, String>,
+}
+
+#[derive(Deserialize)]
+struct ModrinthVersion {
+    id: String,
+    project_id: String,
+    version_number: String,
+    files: Vec<ModrinthFile>,
+}
+
+/// Leading numeric components of the part of `version` that is the mod's own
+/// version: "mc1.20.1-0.5.3" and "0.5.3+1.20.1" both give [0, 5, 3]
+fn version_components(version: &str, game_version: &str) -> Vec<u64> {
+    version
+        .split(['-', '+', ' '])
+        .map(|part| part.trim_start_matches(['v', 'V']))
+        .filter(|part| *part != game_version && !part.starts_with("mc"))
+        .find(|part| part.starts_with(|c: char| c.is_ascii_digit()))
+        .map(|part| {
+            part.split('.')
+                .map_while(|n| n.parse::<u64>().ok())
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+/// Whether updating from `current` to `new` stays within the same major
+/// version (the same minor for 0.x)
+pub fn is_safe_update(current: &str, new: &str, game_version: &str) -> bool {
+    let current = version_components(current, game_version);
+    let new = version_components(new, game_version);
+    match (current.as_slice(), new.as_slice()) {
+        ([], _) | (_, []) => false,
+        ([0, minor, ..], [0, new_minor, ..]) => minor == new_minor,
+        ([0], [0]) => true,
+        ([major, ..], [new_major, ..]) => *major != 0 && major == new_major,
+    }
+}
+
+async fn post_hashes(
+    client: &reqwest::Client,
+    path: &str,
+    body: serde_json::Value,
+) -> Result<HashMap<String, ModrinthVersion>, String> {
+    let resp = client
+        .post(format!("{MODRINTH_API}{path}"))
+        .json(&body)
+        .send()
+        .await
+        .map_err(|e| format!("Modrinth API error: {e}"))?;
+    if !resp.status().is_success() {
+        return Err(format!("Modrinth API returned {}", resp.status()));
+    }
+    resp.json().await.map_err(|e| e.to_string())
+}
+
+/// Available updates for the enabled jars in `mods_dir`, for `game_version`
+/// and `loader` ("fabric", "forge", "quilt" or "neoforge")
+pub async fn check_updates(
+    mods_dir: &Path,
+    game_version: &str,
+    loader: &str,
+) -> Result<Vec<ModUpdate>, String> {
+    let mut files: HashMap<String, String> = HashMap::new(); // sha1 -> file name
+    if let Ok(entries) = std::fs::read_dir(mods_dir) {
+        for entry in entries.flatten() {
+            let file_name = entry.file_name().to_string_lossy().to_string();
+            if !file_name.ends_with(".jar") {
+                continue;
+            }
+            if let Ok(data) = std::fs::read(entry.path()) {
+                files.insert(compute_sha1(&data), file_name);
+            }
+        }
+    }
+    if files.is_empty() {
+        return Ok(Vec::new());
+    }
+    let hashes: Vec<&String> = files.keys().collect();
+
+    let client = reqwest::Client::builder()
+        .user_agent("DropOut/1.0")
+        .build()
+        .map_err(|e| e.to_string())?;
+    let current = post_hashes(
+        &client,
+        "/version_files",
+        serde_json::json!({ "hashes": hashes, "algorithm": "sha1" }),
+    )
+    .await?;
+    let latest = post_hashes(
+        &client,
+        "/version_files/update",
+        serde_json::json!({
+            "hashes": hashes,
+            "algorithm": "sha1",
+            "loaders": [loader],
+            "game_versions": [game_version],
+        }),
+    )
+    .await?;
+
+    let mut updates = Vec::new();
+    for (hash, file_name) in &files {
+        let (Some(current), Some(latest)) = (current.get(hash), latest.get(hash)) else {
+            continue;
+        };
+        if latest.id == current.id {
+            continue;
+        }
+        let Some(file) = latest
+            .files
+            .iter()
+            .find(|f| f.primary)
+            .or(latest.files.first())
+        else {
+            continue;
+        };
+        let Some(sha1) = file.hashes.get("sha1") else {
+            continue;
+        };
+        // A jar listed twice in the project isn't an update
+        if sha1 == hash {
+            continue;
+        }
+        updates.push(ModUpdate {
+            file_name: file_name.clone(),
+            project_id: latest.project_id.clone(),
+            current_version: current.version_number.clone(),
+            new_version: latest.version_number.clone(),
+            new_file_name: file.filename.clone(),
+            url: file.url.clone(),
+            sha1: sha1.clone(),
+            safe: is_safe_update(
+                &current.version_number,
+                &latest.version_number,
+                game_version,
+            ),
+        });
+    }
+    updates.sort_by(|a, b| a.file_name.cmp(&b.file_name));
+    Ok(updates)
+}
+
+/// Download the new jar of `update` and replace the old one with it
+pub async fn apply_update(mods_dir: &Path, update: &ModUpdate) -> Result<(), String> {
+    if update.new_file_name.contains(['/', '\\']) {
+        return Err(format!("Invalid file name {}", update.new_file_name));
+    }
+    let data = reqwest::get(&update.url)
+        .await
+        .and_then(|resp| resp.error_for_status())
+        .map_err(|e| format!("Failed to download {}: {}", update.new_file_name, e))?
+        .bytes()
+        .await
+        .map_err(|e| e.to_string())?;
+    if compute_sha1(&data) != update.sha1 {
+        return Err(format!("Checksum mismatch for {}", update.new_file_name));
+    }
+    tokio::fs::write(mods_dir.join(&update.new_file_name), &data)
+        .await
+        .map_err(|e| e.to_string())?;
+    if update.new_file_name != update.file_name {
+        tokio::fs::remove_file(mods_dir.join(&update.file_name))
+            .await
+            .map_err(|e| e.to_string())?;
+    }
+    Ok(())
+}
+
+/// Check the mods of the instance in `game_dir` for updates and apply the
+/// safe ones, leaving a rollback point (see [`crate::mod_rollback`])
+pub async fn apply_safe_updates(
+    game_dir: &Path,
+    game_version: &str,
+    loader: &str,
+) -> Result<AutoUpdateSummary, String> {
+    let mods_dir = game_dir.join("mods");
+    let (safe, skipped): (Vec<_>, Vec<_>) = check_updates(&mods_dir, game_version, loader)
+        .await?
+        .into_iter()
+        .partition(|u| u.safe);
+    let mut summary = AutoUpdateSummary {
+        skipped,
+        ..Default::default()
+    };
+    if safe.is_empty() {
+        return Ok(summary);
+    }
+    let replaced: Vec<&str> = safe.iter().map(|u| u.file_name.as_str()).collect();
+    let written: Vec<&str> = safe.iter().map(|u| u.new_file_name.as_str()).collect();
+    crate::mod_rollback::create_point(game_dir, "mod updates", &replaced, &written)?;
+
+    for update in safe {
+        match apply_update(&mods_dir, &update).await {
+            Ok(()) => summary.updated.push(update),
+            Err(error) => summary.failed.push(ModUpdateFailure {
+                file_name: update.file_name,
+                error,
+            }),
+        }
+    }
+    Ok(summary)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_version_components() {
+        assert_eq!(version_components("0.5.3", "1.20.1"), vec![0, 5, 3]);
+        assert_eq!(
+            version_components("mc1.20.1-0.5.3", "1.20.1"),
+            vec![0, 5, 3]
+        );
+        assert_eq!(version_components("1.20.1-2.1.0", "1.20.1"), vec![2, 1, 0]);
+        assert_eq!(version_components("v3.2.1+fabric", "1.20.1"), vec![3, 2, 1]);
+        assert_eq!(
+            version_components("fabric-build", "1.20.1"),
+            Vec::<u64>::new()
+        );
+    }
+
+    #[test]
+    fn test_is_safe_update() {
+        assert!(is_safe_update("2.1.0", "2.3.4", "1.20.1"));
+        assert!(!is_safe_update("2.1.0", "3.0.0", "1.20.1"));
+        assert!(is_safe_update("mc1.20.1-0.5.3", "mc1.20.1-0.5.8", "1.20.1"));
+        assert!(!is_safe_update("0.5.3", "0.6.0", "1.20.1"));
+        // Unparseable versions are never applied automatically
+        assert!(!is_safe_update("beta", "release", "1.20.1"));
+    }
+}
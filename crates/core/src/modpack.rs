@@ -0,0 +1,1510 @@
+//! Modpack parsing and extraction module.
+//!
+//! Supported formats:
+//! - Modrinth (.mrpack / zip with `modrinth.index.json`)
+//! - CurseForge (zip with `manifest.json`, manifestType = "minecraftModpack")
+//! - MultiMC / PrismLauncher (zip with `instance.cfg`)
+//!
+//! Packs installed into an instance are recorded as an [`InstalledPack`];
+//! newer versions are found with [`latest_release`] and applied as a delta
+//! with [`sync_file_manifest`] (or [`plan_file_sync`] and
+//! [`remove_dropped_files`]) and [`update_overrides`].
+//!
+//! Instances are exported as a plain zip of the game directory
+//! ([`export_zip`]) or as a Modrinth pack ([`resolve_mrpack_files`], then
+//! [`write_mrpack`]).
+//!
+//! ## Usage
+//!
+//! ```ignore
+//! // 1. Parse modpack → get metadata + file list + override prefixes
+//! let pack = modpack::import(&path).await?;
+//!
+//! // 2. These can run in parallel for Modrinth/CurseForge:
+//! //    a) Extract override files (configs, resource packs, etc.)
+//! modpack::extract_overrides(&path, &game_dir, &pack.override_prefixes, |cur, total, name| {
+//!     println!("Extracting ({cur}/{total}) {name}");
+//! })?;
+//! //    b) Install Minecraft version — use pack.info.minecraft_version (e.g. "1.20.1")
+//! //       → Fetch version manifest, download client jar, assets, libraries.
+//! //    c) Install mod loader — use pack.info.mod_loader + mod_loader_version
+//! //       → Download loader installer/profile, patch version JSON.
+//!
+//! // 3. Download mod files (use pack.files)
+//! //    Each ModpackFile has url, path (relative to game_dir), sha1, size.
+//! //    Partial failure is acceptable — missing mods can be retried on next launch.
+//! ```
+
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fs;
+use std::io::{Read, Write};
+use std::path::Path;
+use ts_rs::TS;
+
+type Archive = zip::ZipArchive<fs::File>;
+
+// ── Public types ──────────────────────────────────────────────────────────
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ModpackInfo {
+    pub name: String,
+    pub minecraft_version: Option<String>,
+    pub mod_loader: Option<String>,
+    pub mod_loader_version: Option<String>,
+    pub modpack_type: String,
+    #[serde(default)]
+    pub instance_id: Option<String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, TS)]
+#[ts(export, export_to = "dropout-core/modpack.ts")]
+pub struct ModpackFile {
+    pub url: String,
+    pub path: String,
+    pub size: Option<u64>,
+    pub sha1: Option<String>,
+}
+
+/// Unified parse result from any modpack format.
+pub struct ParsedModpack {
+    pub info: ModpackInfo,
+    pub files: Vec<ModpackFile>,
+    pub override_prefixes: Vec<String>,
+}
+
+// ── Public API ────────────────────────────────────────────────────────────
+
+/// Parse a modpack zip and return metadata only (no network, no side effects).
+pub fn detect(path: &Path) -> Result<ModpackInfo, String> {
+    Ok(parse(path)?.info)
+}
+
+/// Parse a modpack zip, resolve download URLs, and return everything needed
+/// to complete the installation.
+pub async fn import(path: &Path) -> Result<ParsedModpack, String> {
+    let mut result = parse(path)?;
+    if result.info.modpack_type == "curseforge" {
+        result.files = resolve_curseforge_files(&result.files).await?;
+    }
+    Ok(result)
+}
+
+/// Extract override files from the modpack zip into the game directory.
+pub fn extract_overrides(
+    path: &Path,
+    game_dir: &Path,
+    override_prefixes: &[String],
+    on_progress: impl Fn(usize, usize, &str),
+) -> Result<(), String> {
+    let file = fs::File::open(path).map_err(|e| format!("Failed to open: {e}"))?;
+    let mut archive = zip::ZipArchive::new(file).map_err(|e| format!("Invalid zip: {e}"))?;
+
+    // Collect which prefixes actually exist
+    let all_names: Vec<String> = (0..archive.len())
+        .filter_map(|i| Some(archive.by_index_raw(i).ok()?.name().to_string()))
+        .collect();
+    let prefixes: Vec<&str> = override_prefixes
+        .iter()
+        .filter(|pfx| all_names.iter().any(|n| n.starts_with(pfx.as_str())))
+        .map(|s| s.as_str())
+        .collect();
+
+    let strip = |name: &str| -> Option<String> {
+        prefixes.iter().find_map(|pfx| {
+            let rel = name.strip_prefix(*pfx)?;
+            (!rel.is_empty()).then(|| rel.to_string())
+        })
+    };
+
+    let total = all_names.iter().filter(|n| strip(n).is_some()).count();
+    let mut current = 0;
+
+    for i in 0..archive.len() {
+        let mut entry = archive.by_index(i).map_err(|e| e.to_string())?;
+        if entry.enclosed_name().is_none() {
+            continue;
+        }
+        let name = entry.name().to_string();
+        let Some(relative) = strip(&name) else {
+            continue;
+        };
+        let Some(outpath) = crate::safe_path::join(game_dir, &relative) else {
+            continue;
+        };
+
+        if entry.is_dir() {
+            fs::create_dir_all(&outpath).map_err(|e| e.to_string())?;
+        } else {
+            if let Some(p) = outpath.parent() {
+                fs::create_dir_all(p).map_err(|e| e.to_string())?;
+            }
+            let mut f = fs::File::create(&outpath).map_err(|e| e.to_string())?;
+            std::io::copy(&mut entry, &mut f).map_err(|e| e.to_string())?;
+        }
+        current += 1;
+        on_progress(current, total, &relative);
+    }
+    Ok(())
+}
+
+// ── Delta sync ────────────────────────────────────────────────────────────
+
+/// Difference between two file manifests of the same modpack.
+#[derive(Debug, Clone, Default)]
+pub struct ManifestDiff {
+    /// Files that are new or whose content changed.
+    pub changed: Vec<ModpackFile>,
+    /// Paths present in the old manifest but not in the new one.
+    pub removed: Vec<String>,
+    /// Files identical in both manifests.
+    pub unchanged: Vec<ModpackFile>,
+}
+
+/// Content identity of a manifest entry: its hash, or url + size without one.
+fn file_identity(file: &ModpackFile) -> (Option<&str>, Option<&str>, Option<u64>) {
+    match &file.sha1 {
+        Some(sha1) => (Some(sha1.as_str()), None, None),
+        None => (None, Some(file.url.as_str()), file.size),
+    }
+}
+
+/// Compare manifests by path and content hash (no I/O).
+pub fn diff_file_manifests(old: &[ModpackFile], new: &[ModpackFile]) -> ManifestDiff {
+    let old_by_path: HashMap<&str, &ModpackFile> =
+        old.iter().map(|f| (f.path.as_str(), f)).collect();
+    let new_paths: std::collections::HashSet<&str> = new.iter().map(|f| f.path.as_str()).collect();
+
+    let mut diff = ManifestDiff::default();
+    for file in new {
+        match old_by_path.get(file.path.as_str()) {
+            Some(prev) if file_identity(prev) == file_identity(file) => {
+                diff.unchanged.push(file.clone())
+            }
+            _ => diff.changed.push(file.clone()),
+        }
+    }
+    diff.removed = old
+        .iter()
+        .filter(|f| !new_paths.contains(f.path.as_str()))
+        .map(|f| f.path.clone())
+        .collect();
+    diff
+}
+
+/// Bring `game_dir` from the `old` manifest to the `new` one.
+///
+/// Files dropped from the manifest are deleted, and download tasks are
+/// returned for changed files plus unchanged ones missing on disk. Hashes of
+/// existing files are re-checked by the downloader, so unchanged files are
+/// never fetched again.
+pub fn sync_file_manifest(
+    game_dir: &Path,
+    old: &[ModpackFile],
+    new: &[ModpackFile],
+) -> Result<(ManifestDiff, Vec<crate::downloader::DownloadTask>), String> {
+    let (diff, tasks) = plan_file_sync(game_dir, old, new);
+    remove_dropped_files(game_dir, &diff.removed)?;
+    Ok((diff, tasks))
+}
+
+/// The diff and download tasks of [`sync_file_manifest`] without deleting
+/// anything, so the dropped files can be removed once the downloads succeeded.
+pub fn plan_file_sync(
+    game_dir: &Path,
+    old: &[ModpackFile],
+    new: &[ModpackFile],
+) -> (ManifestDiff, Vec<crate::downloader::DownloadTask>) {
+    let diff = diff_file_manifests(old, new);
+    let tasks = diff
+        .changed
+        .iter()
+        .chain(
+            diff.unchanged
+                .iter()
+                .filter(|f| !game_dir.join(&f.path).exists()),
+        )
+        .filter_map(|f| {
+            Some(crate::downloader::DownloadTask {
+                url: f.url.clone(),
+                path: crate::safe_path::join(game_dir, &f.path)?,
+                sha1: f.sha1.clone(),
+                sha256: None,
+            })
+        })
+        .collect();
+    (diff, tasks)
+}
+
+/// Delete the files of `removed` (manifest paths) from `game_dir`.
+pub fn remove_dropped_files(game_dir: &Path, removed: &[String]) -> Result<(), String> {
+    for relative in removed {
+        let Some(target) = crate::safe_path::join(game_dir, relative) else {
+            continue;
+        };
+        if target.is_file() {
+            fs::remove_file(&target)
+                .map_err(|e| format!("Failed to remove {}: {e}", target.display()))?;
+        }
+    }
+    Ok(())
+}
+
+impl ManifestDiff {
+    /// File names in `mods/` that applying the diff overwrites or deletes,
+    /// for a [`crate::mod_rollback`] point.
+    pub fn replaced_mods(&self) -> Vec<&str> {
+        mod_file_names(
+            self.changed
+                .iter()
+                .map(|f| f.path.as_str())
+                .chain(self.removed.iter().map(String::as_str)),
+        )
+    }
+
+    /// File names in `mods/` that applying the diff writes.
+    pub fn written_mods(&self) -> Vec<&str> {
+        mod_file_names(self.changed.iter().map(|f| f.path.as_str()))
+    }
+}
+
+/// File names directly in `mods/` among `paths` relative to the game
+/// directory.
+pub fn mod_file_names<'a>(paths: impl IntoIterator<Item = &'a str>) -> Vec<&'a str> {
+    paths
+        .into_iter()
+        .filter_map(|path| path.strip_prefix("mods/"))
+        .filter(|name| !name.contains('/'))
+        .collect()
+}
+
+// ── Updates ───────────────────────────────────────────────────────────────
+
+/// The Modrinth project or CurseForge mod a pack archive was published as.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export, export_to = "dropout-core/modpack.ts")]
+pub struct PackOrigin {
+    /// "modrinth" or "curseforge".
+    pub platform: String,
+    pub project_id: String,
+    /// Modrinth version ID or CurseForge file ID of the installed archive.
+    pub version_id: String,
+    pub version_name: String,
+}
+
+/// A pack installed into an instance, as needed to update it later.
+#[derive(Debug, Clone, Default, Serialize, Deserialize, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export, export_to = "dropout-core/modpack.ts")]
+pub struct InstalledPack {
+    pub name: String,
+    pub modpack_type: String,
+    /// Unknown for packs that aren't published (or for MultiMC zips).
+    pub origin: Option<PackOrigin>,
+    /// The pack's file manifest.
+    pub files: Vec<ModpackFile>,
+    /// Override files as extracted: relative path -> SHA1.
+    pub overrides: HashMap<String, String>,
+}
+
+/// A newer published version of a pack.
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export, export_to = "dropout-core/modpack.ts")]
+pub struct PackRelease {
+    pub version_id: String,
+    pub version_name: String,
+    pub url: String,
+    pub sha1: Option<String>,
+}
+
+/// What an update changed in the instance.
+#[derive(Debug, Clone, Default, Serialize, Deserialize, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export, export_to = "dropout-core/modpack.ts")]
+pub struct PackUpdateSummary {
+    pub added: Vec<String>,
+    pub changed: Vec<String>,
+    pub removed: Vec<String>,
+    /// Override files the user edited, left as they are.
+    pub kept: Vec<String>,
+}
+
+/// SHA1 of every override file in the archive, keyed by relative path.
+pub fn override_hashes(
+    path: &Path,
+    override_prefixes: &[String],
+) -> Result<HashMap<String, String>, String> {
+    let file = fs::File::open(path).map_err(|e| format!("Failed to open: {e}"))?;
+    let mut archive = zip::ZipArchive::new(file).map_err(|e| format!("Invalid zip: {e}"))?;
+    let mut hashes = HashMap::new();
+    for i in 0..archive.len() {
+        let mut entry = archive.by_index(i).map_err(|e| e.to_string())?;
+        if entry.is_dir() {
+            continue;
+        }
+        let name = entry.name().to_string();
+        let Some(relative) = override_prefixes.iter().find_map(|pfx| {
+            let rel = name.strip_prefix(pfx.as_str())?;
+            (!rel.is_empty()).then(|| rel.to_string())
+        }) else {
+            continue;
+        };
+        let mut data = Vec::new();
+        entry.read_to_end(&mut data).map_err(|e| e.to_string())?;
+        hashes.insert(relative, crate::downloader::compute_sha1(&data));
+    }
+    Ok(hashes)
+}
+
+fn disk_sha1(path: &Path) -> Option<String> {
+    fs::read(path)
+        .ok()
+        .map(|data| crate::downloader::compute_sha1(&data))
+}
+
+/// Bring the override files of `game_dir` from the `old` pack version to the
+/// one in the archive at `path`, keeping files the user has edited.
+///
+/// A file is written when it's missing, or still as the old version left it.
+/// Overrides dropped by the new version are deleted unless edited. Returns
+/// the new override hashes and the edited files that were kept.
+pub fn update_overrides(
+    path: &Path,
+    game_dir: &Path,
+    override_prefixes: &[String],
+    old: &HashMap<String, String>,
+) -> Result<(HashMap<String, String>, Vec<String>), String> {
+    let new = override_hashes(path, override_prefixes)?;
+    let mut kept = Vec::new();
+    let mut write = Vec::new();
+    for (relative, hash) in &new {
+        let Some(target) = crate::safe_path::join(game_dir, relative) else {
+            continue;
+        };
+        match disk_sha1(&target) {
+            None => write.push(relative.clone()),
+            Some(current) if current == *hash => {}
+            Some(current) if old.get(relative) == Some(&current) => write.push(relative.clone()),
+            Some(_) => kept.push(relative.clone()),
+        }
+    }
+
+    let file = fs::File::open(path).map_err(|e| format!("Failed to open: {e}"))?;
+    let mut archive = zip::ZipArchive::new(file).map_err(|e| format!("Invalid zip: {e}"))?;
+    for i in 0..archive.len() {
+        let mut entry = archive.by_index(i).map_err(|e| e.to_string())?;
+        let name = entry.name().to_string();
+        let Some(relative) = override_prefixes.iter().find_map(|pfx| {
+            let rel = name.strip_prefix(pfx.as_str())?;
+            write.contains(&rel.to_string()).then(|| rel.to_string())
+        }) else {
+            continue;
+        };
+        let outpath = game_dir.join(&relative);
+        if let Some(p) = outpath.parent() {
+            fs::create_dir_all(p).map_err(|e| e.to_string())?;
+        }
+        let mut f = fs::File::create(&outpath).map_err(|e| e.to_string())?;
+        std::io::copy(&mut entry, &mut f).map_err(|e| e.to_string())?;
+    }
+
+    for (relative, hash) in old {
+        if new.contains_key(relative) {
+            continue;
+        }
+        let Some(target) = crate::safe_path::join(game_dir, relative) else {
+            continue;
+        };
+        match disk_sha1(&target) {
+            Some(current) if current == *hash => {
+                fs::remove_file(&target)
+                    .map_err(|e| format!("Failed to remove {}: {e}", target.display()))?;
+            }
+            Some(_) => kept.push(relative.clone()),
+            None => {}
+        }
+    }
+    kept.sort();
+    Ok((new, kept))
+}
+
+/// Summarize a manifest diff (plus the edited overrides that were kept).
+pub fn update_summary(
+    old: &[ModpackFile],
+    diff: &ManifestDiff,
+    kept: Vec<String>,
+) -> PackUpdateSummary {
+    let (added, changed) = diff
+        .changed
+        .iter()
+        .map(|f| f.path.clone())
+        .partition(|path| !old.iter().any(|f| f.path == *path));
+    PackUpdateSummary {
+        added,
+        changed,
+        removed: diff.removed.clone(),
+        kept,
+    }
+}
+
+/// CurseForge's file fingerprint: MurmurHash2 (seed 1) of the file with
+/// whitespace bytes left out.
+fn cf_fingerprint(data: &[u8]) -> u32 {
+    const M: u32 = 0x5bd1_e995;
+    let bytes: Vec<u8> = data
+        .iter()
+        .copied()
+        .filter(|b| !matches!(b, 9 | 10 | 13 | 32))
+        .collect();
+    let mut h: u32 = 1 ^ bytes.len() as u32;
+    let mut chunks = bytes.chunks_exact(4);
+    for chunk in &mut chunks {
+        let mut k = u32::from_le_bytes([chunk[0], chunk[1], chunk[2], chunk[3]]);
+        k = k.wrapping_mul(M);
+        k ^= k >> 24;
+        k = k.wrapping_mul(M);
+        h = h.wrapping_mul(M) ^ k;
+    }
+    let rest = chunks.remainder();
+    if !rest.is_empty() {
+        for (i, b) in rest.iter().enumerate() {
+            h ^= (*b as u32) << (8 * i);
+        }
+        h = h.wrapping_mul(M);
+    }
+    h ^= h >> 13;
+    h = h.wrapping_mul(M);
+    h ^ (h >> 15)
+}
+
+/// Look the archive at `path` up on Modrinth (by SHA1) or CurseForge (by
+/// fingerprint). `None` if it isn't published there.
+pub async fn identify_origin(path: &Path, modpack_type: &str) -> Option<PackOrigin> {
+    let data = fs::read(path).ok()?;
+    match modpack_type {
+        "modrinth" => {
+            let sha1 = crate::downloader::compute_sha1(&data);
+            let version = crate::modrinth::get(
+                &format!("/version_file/{sha1}"),
+                &[("algorithm", "sha1".to_string())],
+            )
+            .await
+            .ok()??;
+            Some(PackOrigin {
+                platform: "modrinth".into(),
+                project_id: version["project_id"].as_str()?.to_string(),
+                version_id: version["id"].as_str()?.to_string(),
+                version_name: version["version_number"].as_str()?.to_string(),
+            })
+        }
+        "curseforge" => {
+            let client = reqwest::Client::new();
+            let body = cf_post(
+                &client,
+                "/v1/fingerprints",
+                &serde_json::json!({ "fingerprints": [cf_fingerprint(&data)] }),
+            )
+            .await
+            .ok()?;
+            let file = &body["data"]["exactMatches"].as_array()?.first()?["file"];
+            Some(PackOrigin {
+                platform: "curseforge".into(),
+                project_id: file["modId"].as_u64()?.to_string(),
+                version_id: file["id"].as_u64()?.to_string(),
+                version_name: file["displayName"].as_str()?.to_string(),
+            })
+        }
+        _ => None,
+    }
+}
+
+/// Download of a Modrinth version object
+fn modrinth_release(version: &serde_json::Value) -> Result<PackRelease, String> {
+    let files = version["files"].as_array().cloned().unwrap_or_default();
+    let file = files
+        .iter()
+        .find(|f| f["primary"].as_bool() == Some(true))
+        .or_else(|| files.first())
+        .ok_or("The version has no files")?;
+    Ok(PackRelease {
+        version_id: version["id"].as_str().unwrap_or_default().to_string(),
+        version_name: version["version_number"]
+            .as_str()
+            .unwrap_or_default()
+            .to_string(),
+        url: file["url"].as_str().unwrap_or_default().to_string(),
+        sha1: file["hashes"]["sha1"].as_str().map(String::from),
+    })
+}
+
+/// Download of a CurseForge file object
+fn curseforge_release(file: &serde_json::Value) -> Result<PackRelease, String> {
+    Ok(PackRelease {
+        version_id: file["id"].as_u64().unwrap_or_default().to_string(),
+        version_name: file["displayName"].as_str().unwrap_or_default().to_string(),
+        url: cf_download_url(file).ok_or("The file has no download URL")?,
+        sha1: None,
+    })
+}
+
+/// The newest version of a pack (preferring releases over betas), or `None`
+/// when the installed one is the newest.
+pub async fn latest_release(origin: &PackOrigin) -> Result<Option<PackRelease>, String> {
+    let latest = match origin.platform.as_str() {
+        "modrinth" => {
+            let versions =
+                crate::modrinth::get(&format!("/project/{}/version", origin.project_id), &[])
+                    .await?
+                    .ok_or_else(|| format!("Modrinth project {} not found", origin.project_id))?;
+            let versions = versions.as_array().cloned().unwrap_or_default();
+            // Listed newest first
+            let newest = versions
+                .iter()
+                .find(|v| v["version_type"].as_str() == Some("release"))
+                .or_else(|| versions.first())
+                .ok_or("The project has no versions")?;
+            modrinth_release(newest)?
+        }
+        "curseforge" => {
+            let client = reqwest::Client::new();
+            let body = cf_get(
+                &client,
+                &format!("/v1/mods/{}/files", origin.project_id),
+                &[("pageSize", "50".to_string())],
+            )
+            .await?;
+            let files = body["data"].as_array().cloned().unwrap_or_default();
+            let newest_of = |release_only: bool| {
+                files
+                    .iter()
+                    .filter(|f| !release_only || f["releaseType"].as_u64() == Some(1))
+                    .max_by_key(|f| f["fileDate"].as_str().unwrap_or_default().to_string())
+            };
+            let newest = newest_of(true)
+                .or_else(|| newest_of(false))
+                .ok_or("The project has no files")?;
+            curseforge_release(newest)?
+        }
+        other => return Err(format!("Unknown modpack platform: {other}")),
+    };
+    Ok((latest.version_id != origin.version_id).then_some(latest))
+}
+
+/// A published version of a pack: `version` (Modrinth version ID or number,
+/// CurseForge file ID), or the newest one without it.
+pub async fn find_release(
+    platform: &str,
+    project_id: &str,
+    version: Option<&str>,
+) -> Result<PackRelease, String> {
+    let Some(version) = version else {
+        let origin = PackOrigin {
+            platform: platform.to_string(),
+            project_id: project_id.to_string(),
+            version_id: String::new(),
+            version_name: String::new(),
+        };
+        return latest_release(&origin)
+            .await?
+            .ok_or_else(|| "The project has no versions".to_string());
+    };
+    match platform {
+        "modrinth" => {
+            // Listing the project's versions also accepts slugs as project IDs
+            let versions = crate::modrinth::get(&format!("/project/{project_id}/version"), &[])
+                .await?
+                .ok_or_else(|| format!("Modrinth project {project_id} not found"))?;
+            let found = versions
+                .as_array()
+                .into_iter()
+                .flatten()
+                .find(|v| {
+                    v["id"].as_str() == Some(version)
+                        || v["version_number"].as_str() == Some(version)
+                })
+                .ok_or_else(|| format!("Version {version} not found"))?;
+            modrinth_release(found)
+        }
+        "curseforge" => {
+            let client = reqwest::Client::new();
+            let body = cf_get(
+                &client,
+                &format!("/v1/mods/{project_id}/files/{version}"),
+                &[],
+            )
+            .await?;
+            let file = &body["data"];
+            if file["modId"].as_u64().map(|id| id.to_string()).as_deref() != Some(project_id) {
+                return Err(format!(
+                    "File {version} is not part of project {project_id}"
+                ));
+            }
+            curseforge_release(file)
+        }
+        other => Err(format!("Unknown modpack platform: {other}")),
+    }
+}
+
+// ── Export ────────────────────────────────────────────────────────────────
+
+/// Top-level entries of an instance left out of a `.mrpack`: game files the
+/// importing launcher installs itself, output of this machine, and the
+/// player's own worlds, server list and options
+const MRPACK_EXCLUDED: &[&str] = &[
+    "assets",
+    "libraries",
+    "versions",
+    "natives",
+    "logs",
+    "crash-reports",
+    "rollback",
+    "saves",
+    "screenshots",
+    "servers.dat",
+    "options.txt",
+];
+
+/// Folders whose files may be published on Modrinth
+const MRPACK_LOOKUP_DIRS: &[&str] = &["mods", "resourcepacks", "shaderpacks"];
+
+/// Result of [`export_zip`] and [`write_mrpack`].
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export, export_to = "dropout-core/modpack.ts")]
+pub struct ExportSummary {
+    pub path: String,
+    /// Files listed in `modrinth.index.json` (always 0 for a plain zip).
+    pub referenced: usize,
+    /// Files stored in the archive.
+    pub stored: usize,
+}
+
+/// Paths (relative, with `/`) of all files under `dir`, sorted.
+fn list_files(base: &Path, dir: &Path, out: &mut Vec<String>) -> Result<(), String> {
+    for entry in fs::read_dir(dir).map_err(|e| e.to_string())?.flatten() {
+        let path = entry.path();
+        if path.is_dir() {
+            list_files(base, &path, out)?;
+        } else if let Ok(relative) = path.strip_prefix(base) {
+            out.push(relative.to_string_lossy().replace('\\', "/"));
+        }
+    }
+    Ok(())
+}
+
+fn game_files(game_dir: &Path, dest: &Path) -> Result<Vec<String>, String> {
+    let mut files = Vec::new();
+    list_files(game_dir, game_dir, &mut files)?;
+    // The archive may be written into the instance itself
+    files.retain(|f| game_dir.join(f) != dest);
+    files.sort();
+    Ok(files)
+}
+
+fn create_archive(dest: &Path) -> Result<zip::ZipWriter<fs::File>, String> {
+    if let Some(parent) = dest.parent() {
+        fs::create_dir_all(parent).map_err(|e| e.to_string())?;
+    }
+    let file =
+        fs::File::create(dest).map_err(|e| format!("Failed to create {}: {e}", dest.display()))?;
+    Ok(zip::ZipWriter::new(file))
+}
+
+fn zip_options() -> zip::write::SimpleFileOptions {
+    zip::write::SimpleFileOptions::default()
+        .compression_method(zip::CompressionMethod::Deflated)
+        .large_file(true)
+}
+
+fn add_file(zip: &mut zip::ZipWriter<fs::File>, name: &str, path: &Path) -> Result<(), String> {
+    let mut file = fs::File::open(path).map_err(|e| format!("Failed to read {name}: {e}"))?;
+    zip.start_file(name, zip_options())
+        .map_err(|e| e.to_string())?;
+    std::io::copy(&mut file, zip).map_err(|e| format!("Failed to add {name}: {e}"))?;
+    Ok(())
+}
+
+/// Package all of `game_dir` into a plain zip at `dest`.
+pub fn export_zip(game_dir: &Path, dest: &Path) -> Result<ExportSummary, String> {
+    let files = game_files(game_dir, dest)?;
+    let mut zip = create_archive(dest)?;
+    for relative in &files {
+        add_file(&mut zip, relative, &game_dir.join(relative))?;
+    }
+    zip.finish().map_err(|e| e.to_string())?;
+    Ok(ExportSummary {
+        path: dest.to_string_lossy().to_string(),
+        referenced: 0,
+        stored: files.len(),
+    })
+}
+
+fn mrpack_files(game_dir: &Path, dest: &Path) -> Result<Vec<String>, String> {
+    let mut files = game_files(game_dir, dest)?;
+    files.retain(|f| {
+        let top = f.split('/').next().unwrap_or_default();
+        !MRPACK_EXCLUDED.contains(&top)
+    });
+    Ok(files)
+}
+
+/// Files of `game_dir` that Modrinth hosts, keyed by relative path, with the
+/// download URL and SHA1 of the published file. `dest` is left out.
+pub async fn resolve_mrpack_files(
+    game_dir: &Path,
+    dest: &Path,
+) -> Result<HashMap<String, ModpackFile>, String> {
+    let mut by_hash: HashMap<String, String> = HashMap::new(); // sha1 -> path
+    for relative in mrpack_files(game_dir, dest)? {
+        let in_lookup_dir = relative
+            .split_once('/')
+            .is_some_and(|(dir, name)| MRPACK_LOOKUP_DIRS.contains(&dir) && !name.contains('/'));
+        if !in_lookup_dir || !(relative.ends_with(".jar") || relative.ends_with(".zip")) {
+            continue;
+        }
+        if let Ok(data) = fs::read(game_dir.join(&relative)) {
+            by_hash.insert(crate::downloader::compute_sha1(&data), relative);
+        }
+    }
+    if by_hash.is_empty() {
+        return Ok(HashMap::new());
+    }
+
+    let client = crate::modrinth::client()?;
+    let hashes: Vec<&String> = by_hash.keys().collect();
+    let resp = client
+        .post("https://api.modrinth.com/v2/version_files")
+        .json(&serde_json::json!({ "hashes": hashes, "algorithm": "sha1" }))
+        .send()
+        .await
+        .map_err(|e| format!("Modrinth API error: {e}"))?;
+    if !resp.status().is_success() {
+        return Err(format!("Modrinth API returned {}", resp.status()));
+    }
+    let versions: HashMap<String, serde_json::Value> =
+        resp.json().await.map_err(|e| e.to_string())?;
+
+    Ok(versions
+        .iter()
+        .filter_map(|(hash, version)| {
+            let path = by_hash.get(hash)?;
+            let file = version["files"]
+                .as_array()?
+                .iter()
+                .find(|f| f["hashes"]["sha1"].as_str() == Some(hash))?;
+            Some((
+                path.clone(),
+                ModpackFile {
+                    url: file["url"].as_str()?.to_string(),
+                    path: path.clone(),
+                    size: file["size"].as_u64(),
+                    sha1: Some(hash.clone()),
+                },
+            ))
+        })
+        .collect())
+}
+
+/// `dependencies` key of a mod loader in `modrinth.index.json`
+fn modrinth_loader_key(loader: &str) -> Option<&'static str> {
+    match loader {
+        "fabric" => Some("fabric-loader"),
+        "quilt" => Some("quilt-loader"),
+        "forge" => Some("forge"),
+        "neoforge" => Some("neoforge"),
+        _ => None,
+    }
+}
+
+fn modrinth_index(
+    info: &ModpackInfo,
+    version: &str,
+    files: Vec<serde_json::Value>,
+) -> Result<serde_json::Value, String> {
+    let minecraft = info
+        .minecraft_version
+        .as_deref()
+        .ok_or("The Minecraft version of the instance is unknown")?;
+    let mut dependencies = serde_json::json!({ "minecraft": minecraft });
+    if let (Some(key), Some(loader_version)) = (
+        info.mod_loader.as_deref().and_then(modrinth_loader_key),
+        info.mod_loader_version.as_deref(),
+    ) {
+        dependencies[key] = loader_version.into();
+    }
+    Ok(serde_json::json!({
+        "formatVersion": 1,
+        "game": "minecraft",
+        "versionId": version,
+        "name": info.name,
+        "files": files,
+        "dependencies": dependencies,
+    }))
+}
+
+/// Write a Modrinth `.mrpack` of `game_dir` to `dest`: the files in
+/// `referenced` (from [`resolve_mrpack_files`]) are listed in
+/// `modrinth.index.json` and everything else goes to `overrides/`.
+pub fn write_mrpack(
+    game_dir: &Path,
+    dest: &Path,
+    info: &ModpackInfo,
+    version: &str,
+    referenced: &HashMap<String, ModpackFile>,
+) -> Result<ExportSummary, String> {
+    let mut index_files = Vec::new();
+    let mut overrides = Vec::new();
+    for relative in mrpack_files(game_dir, dest)? {
+        let Some(file) = referenced.get(&relative) else {
+            overrides.push(relative);
+            continue;
+        };
+        let data = fs::read(game_dir.join(&relative)).map_err(|e| e.to_string())?;
+        index_files.push(serde_json::json!({
+            "path": relative,
+            "hashes": {
+                "sha1": crate::downloader::compute_sha1(&data),
+                "sha512": crate::downloader::compute_sha512(&data),
+            },
+            "downloads": [file.url],
+            "fileSize": data.len(),
+        }));
+    }
+    let referenced_count = index_files.len();
+    let index = modrinth_index(info, version, index_files)?;
+
+    let mut zip = create_archive(dest)?;
+    zip.start_file("modrinth.index.json", zip_options())
+        .map_err(|e| e.to_string())?;
+    let content = serde_json::to_vec_pretty(&index).map_err(|e| e.to_string())?;
+    zip.write_all(&content).map_err(|e| e.to_string())?;
+    for relative in &overrides {
+        add_file(
+            &mut zip,
+            &format!("overrides/{relative}"),
+            &game_dir.join(relative),
+        )?;
+    }
+    zip.finish().map_err(|e| e.to_string())?;
+    Ok(ExportSummary {
+        path: dest.to_string_lossy().to_string(),
+        referenced: referenced_count,
+        stored: overrides.len(),
+    })
+}
+
+// ── Core parse dispatch ───────────────────────────────────────────────────
+
+type ParserFn = fn(&mut Archive) -> Result<ParsedModpack, String>;
+
+const PARSERS: &[ParserFn] = &[parse_modrinth, parse_curseforge, parse_multimc];
+
+fn parse(path: &Path) -> Result<ParsedModpack, String> {
+    let file = fs::File::open(path).map_err(|e| format!("Failed to open: {e}"))?;
+    let mut archive = zip::ZipArchive::new(file).map_err(|e| format!("Invalid zip: {e}"))?;
+
+    for parser in PARSERS {
+        if let Ok(result) = parser(&mut archive) {
+            return Ok(result);
+        }
+    }
+    Ok(ParsedModpack {
+        info: ModpackInfo {
+            name: path
+                .file_stem()
+                .and_then(|s| s.to_str())
+                .unwrap_or("Imported Modpack")
+                .to_string(),
+            minecraft_version: None,
+            mod_loader: None,
+            mod_loader_version: None,
+            modpack_type: "unknown".into(),
+            instance_id: None,
+        },
+        files: vec![],
+        override_prefixes: vec![],
+    })
+}
+
+// ── Format parsers ────────────────────────────────────────────────────────
+
+fn parse_modrinth(archive: &mut Archive) -> Result<ParsedModpack, String> {
+    let json = read_json(archive, "modrinth.index.json")?;
+    let (mod_loader, mod_loader_version) = parse_modrinth_loader(&json["dependencies"]);
+
+    let files = json["files"]
+        .as_array()
+        .map(|arr| {
+            arr.iter()
+                .filter_map(|f| {
+                    if f["env"]["client"].as_str() == Some("unsupported") {
+                        return None;
+                    }
+                    let path = f["path"].as_str()?;
+                    if path.contains("..") {
+                        return None;
+                    }
+                    Some(ModpackFile {
+                        path: path.to_string(),
+                        url: f["downloads"].as_array()?.first()?.as_str()?.to_string(),
+                        size: f["fileSize"].as_u64(),
+                        sha1: f["hashes"]["sha1"].as_str().map(String::from),
+                    })
+                })
+                .collect()
+        })
+        .unwrap_or_default();
+
+    Ok(ParsedModpack {
+        info: ModpackInfo {
+            name: json["name"].as_str().unwrap_or("Modrinth Modpack").into(),
+            minecraft_version: json["dependencies"]["minecraft"].as_str().map(Into::into),
+            mod_loader,
+            mod_loader_version,
+            modpack_type: "modrinth".into(),
+            instance_id: None,
+        },
+        files,
+        override_prefixes: vec!["client-overrides/".into(), "overrides/".into()],
+    })
+}
+
+fn parse_curseforge(archive: &mut Archive) -> Result<ParsedModpack, String> {
+    let json = read_json(archive, "manifest.json")?;
+    if json["manifestType"].as_str() != Some("minecraftModpack") {
+        return Err("not curseforge".into());
+    }
+
+    let (loader, loader_ver) = json["minecraft"]["modLoaders"]
+        .as_array()
+        .and_then(|arr| {
+            arr.iter()
+                .find(|ml| ml["primary"].as_bool() == Some(true))
+                .or_else(|| arr.first())
+        })
+        .and_then(|ml| {
+            let (l, v) = ml["id"].as_str()?.split_once('-')?;
+            Some((Some(l.to_string()), Some(v.to_string())))
+        })
+        .unwrap_or((None, None));
+
+    let files = json["files"]
+        .as_array()
+        .map(|arr| {
+            arr.iter()
+                .filter_map(|f| {
+                    Some(ModpackFile {
+                        url: format!(
+                            "curseforge://{}:{}",
+                            f["projectID"].as_u64()?,
+                            f["fileID"].as_u64()?
+                        ),
+                        path: String::new(),
+                        size: None,
+                        sha1: None,
+                    })
+                })
+                .collect()
+        })
+        .unwrap_or_default();
+
+    let overrides = json["overrides"].as_str().unwrap_or("overrides");
+
+    Ok(ParsedModpack {
+        info: ModpackInfo {
+            name: json["name"].as_str().unwrap_or("CurseForge Modpack").into(),
+            minecraft_version: json["minecraft"]["version"].as_str().map(Into::into),
+            mod_loader: loader,
+            mod_loader_version: loader_ver,
+            modpack_type: "curseforge".into(),
+            instance_id: None,
+        },
+        files,
+        override_prefixes: vec![format!("{overrides}/")],
+    })
+}
+
+fn parse_multimc(archive: &mut Archive) -> Result<ParsedModpack, String> {
+    let root = find_multimc_root(archive).ok_or("not multimc")?;
+    let cfg = read_entry(archive, &format!("{root}instance.cfg")).ok_or("not multimc")?;
+
+    let name = cfg_value(&cfg, "name").unwrap_or_else(|| "MultiMC Modpack".into());
+
+    let (mc, loader, loader_ver) = read_json(archive, &format!("{root}mmc-pack.json"))
+        .map(|j| parse_mmc_components(&j))
+        .unwrap_or_default();
+    let mc = mc.or_else(|| cfg_value(&cfg, "IntendedVersion"));
+
+    Ok(ParsedModpack {
+        info: ModpackInfo {
+            name,
+            minecraft_version: mc,
+            mod_loader: loader,
+            mod_loader_version: loader_ver,
+            modpack_type: "multimc".into(),
+            instance_id: None,
+        },
+        files: vec![],
+        override_prefixes: vec![format!("{root}.minecraft/"), format!("{root}minecraft/")],
+    })
+}
+
+// ── CurseForge API resolution ─────────────────────────────────────────────
+
+const CURSEFORGE_API_KEY: &str = env!("CURSEFORGE_API_KEY");
+
+async fn resolve_curseforge_files(files: &[ModpackFile]) -> Result<Vec<ModpackFile>, String> {
+    let file_ids: Vec<u64> = files
+        .iter()
+        .filter_map(|f| {
+            f.url
+                .strip_prefix("curseforge://")?
+                .split(':')
+                .nth(1)?
+                .parse()
+                .ok()
+        })
+        .collect();
+    if file_ids.is_empty() {
+        return Ok(vec![]);
+    }
+
+    let client = reqwest::Client::new();
+
+    // 1. Batch-resolve file metadata
+    let body = cf_post(
+        &client,
+        "/v1/mods/files",
+        &serde_json::json!({ "fileIds": file_ids }),
+    )
+    .await?;
+    let file_arr = body["data"].as_array().cloned().unwrap_or_default();
+
+    // 2. Batch-resolve mod classIds for directory placement
+    let mod_ids: Vec<u64> = file_arr
+        .iter()
+        .filter_map(|f| f["modId"].as_u64())
+        .collect::<std::collections::HashSet<_>>()
+        .into_iter()
+        .collect();
+    let class_map = cf_class_ids(&client, &mod_ids).await;
+
+    // 3. Build results
+    Ok(file_arr
+        .iter()
+        .filter_map(|f| {
+            let name = f["fileName"].as_str()?;
+            let url = cf_download_url(f)?;
+            let dir = match f["modId"].as_u64().and_then(|mid| class_map.get(&mid)) {
+                Some(12) => "resourcepacks",
+                Some(6552) => "shaderpacks",
+                _ => "mods",
+            };
+            Some(ModpackFile {
+                url,
+                path: format!("{dir}/{name}"),
+                size: f["fileLength"].as_u64(),
+                sha1: None,
+            })
+        })
+        .collect())
+}
+
+/// A file's download URL; files of mods that opted out of third-party
+/// downloads have none, but are still served by the CDN
+pub(crate) fn cf_download_url(file: &serde_json::Value) -> Option<String> {
+    if let Some(url) = file["downloadUrl"].as_str() {
+        return Some(url.to_string());
+    }
+    let name = file["fileName"].as_str()?;
+    let id = file["id"].as_u64()?;
+    Some(format!(
+        "https://edge.forgecdn.net/files/{}/{}/{name}",
+        id / 1000,
+        id % 1000
+    ))
+}
+
+pub(crate) async fn cf_get(
+    client: &reqwest::Client,
+    endpoint: &str,
+    query: &[(&str, String)],
+) -> Result<serde_json::Value, String> {
+    let resp = client
+        .get(format!("https://api.curseforge.com{endpoint}"))
+        .header("x-api-key", CURSEFORGE_API_KEY)
+        .query(query)
+        .send()
+        .await
+        .map_err(|e| format!("CurseForge API error: {e}"))?;
+    if !resp.status().is_success() {
+        return Err(format!("CurseForge API returned {}", resp.status()));
+    }
+    resp.json().await.map_err(|e| e.to_string())
+}
+
+async fn cf_post(
+    client: &reqwest::Client,
+    endpoint: &str,
+    body: &serde_json::Value,
+) -> Result<serde_json::Value, String> {
+    let resp = client
+        .post(format!("https://api.curseforge.com{endpoint}"))
+        .header("x-api-key", CURSEFORGE_API_KEY)
+        .json(body)
+        .send()
+        .await
+        .map_err(|e| format!("CurseForge API error: {e}"))?;
+    if !resp.status().is_success() {
+        return Err(format!("CurseForge API returned {}", resp.status()));
+    }
+    resp.json().await.map_err(|e| e.to_string())
+}
+
+async fn cf_class_ids(client: &reqwest::Client, mod_ids: &[u64]) -> HashMap<u64, u64> {
+    if mod_ids.is_empty() {
+        return Default::default();
+    }
+    let Ok(body) = cf_post(
+        client,
+        "/v1/mods",
+        &serde_json::json!({ "modIds": mod_ids }),
+    )
+    .await
+    else {
+        return Default::default();
+    };
+    body["data"]
+        .as_array()
+        .map(|arr| {
+            arr.iter()
+                .filter_map(|m| Some((m["id"].as_u64()?, m["classId"].as_u64()?)))
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+// ── Helpers ───────────────────────────────────────────────────────────────
+
+fn read_entry(archive: &mut Archive, name: &str) -> Option<String> {
+    let mut buf = String::new();
+    archive.by_name(name).ok()?.read_to_string(&mut buf).ok()?;
+    Some(buf)
+}
+
+fn read_json(archive: &mut Archive, name: &str) -> Result<serde_json::Value, String> {
+    let content = read_entry(archive, name).ok_or_else(|| format!("{name} not found"))?;
+    serde_json::from_str(&content).map_err(|e| e.to_string())
+}
+
+fn cfg_value(content: &str, key: &str) -> Option<String> {
+    let prefix = format!("{key}=");
+    content
+        .lines()
+        .find_map(|l| Some(l.strip_prefix(&prefix)?.trim().to_string()))
+}
+
+fn find_multimc_root(archive: &mut Archive) -> Option<String> {
+    for i in 0..archive.len() {
+        let name = archive.by_index_raw(i).ok()?.name().to_string();
+        if name == "instance.cfg" {
+            return Some(String::new());
+        }
+        if name.ends_with("/instance.cfg") && name.matches('/').count() == 1 {
+            return Some(name.strip_suffix("instance.cfg")?.to_string());
+        }
+    }
+    None
+}
+
+fn parse_modrinth_loader(deps: &serde_json::Value) -> (Option<String>, Option<String>) {
+    const LOADERS: &[(&str, &str)] = &[
+        ("fabric-loader", "fabric"),
+        ("forge", "forge"),
+        ("quilt-loader", "quilt"),
+        ("neoforge", "neoforge"),
+        ("neo-forge", "neoforge"),
+    ];
+    LOADERS
+        .iter()
+        .find_map(|(key, name)| {
+            let v = deps[*key].as_str()?;
+            Some((Some((*name).into()), Some(v.into())))
+        })
+        .unwrap_or((None, None))
+}
+
+fn parse_mmc_components(
+    json: &serde_json::Value,
+) -> (Option<String>, Option<String>, Option<String>) {
+    let (mut mc, mut loader, mut loader_ver) = (None, None, None);
+    for c in json["components"].as_array().into_iter().flatten() {
+        let ver = c["version"].as_str().map(String::from);
+        match c["uid"].as_str().unwrap_or("") {
+            "net.minecraft" => mc = ver,
+            "net.minecraftforge" => {
+                loader = Some("forge".into());
+                loader_ver = ver;
+            }
+            "net.neoforged" => {
+                loader = Some("neoforge".into());
+                loader_ver = ver;
+            }
+            "net.fabricmc.fabric-loader" => {
+                loader = Some("fabric".into());
+                loader_ver = ver;
+            }
+            "org.quiltmc.quilt-loader" => {
+                loader = Some("quilt".into());
+                loader_ver = ver;
+            }
+            "com.mumfrey.liteloader" => {
+                loader = Some("liteloader".into());
+                loader_ver = ver;
+            }
+            _ => {}
+        }
+    }
+    (mc, loader, loader_ver)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn file(path: &str, sha1: Option<&str>) -> ModpackFile {
+        ModpackFile {
+            url: format!("https://cdn.example/{path}"),
+            path: path.to_string(),
+            size: Some(1),
+            sha1: sha1.map(str::to_string),
+        }
+    }
+
+    #[test]
+    fn test_diff_file_manifests() {
+        let old = vec![
+            file("mods/a.jar", Some("aaa")),
+            file("mods/b.jar", Some("bbb")),
+            file("mods/c.jar", Some("ccc")),
+        ];
+        let new = vec![
+            file("mods/a.jar", Some("aaa")),
+            file("mods/b.jar", Some("bbb2")),
+            file("mods/d.jar", Some("ddd")),
+        ];
+        let diff = diff_file_manifests(&old, &new);
+        let changed: Vec<&str> = diff.changed.iter().map(|f| f.path.as_str()).collect();
+        assert_eq!(changed, vec!["mods/b.jar", "mods/d.jar"]);
+        assert_eq!(diff.removed, vec!["mods/c.jar"]);
+        assert_eq!(diff.unchanged.len(), 1);
+    }
+
+    #[test]
+    fn test_diff_without_hash_uses_url() {
+        let old = vec![file("mods/a.jar", None)];
+        let mut moved = file("mods/a.jar", None);
+        moved.url = "https://mirror.example/mods/a.jar".to_string();
+        assert!(diff_file_manifests(&old, &old).changed.is_empty());
+        assert_eq!(diff_file_manifests(&old, &[moved]).changed.len(), 1);
+    }
+
+    #[test]
+    fn test_sync_file_manifest_deletes_removed() {
+        let dir = std::env::temp_dir().join(format!("dropout-sync-{}", std::process::id()));
+        fs::create_dir_all(dir.join("mods")).unwrap();
+        fs::write(dir.join("mods/old.jar"), b"x").unwrap();
+        fs::write(dir.join("mods/keep.jar"), b"x").unwrap();
+
+        let old = vec![
+            file("mods/old.jar", Some("1")),
+            file("mods/keep.jar", Some("2")),
+        ];
+        let new = vec![
+            file("mods/keep.jar", Some("2")),
+            file("mods/new.jar", Some("3")),
+        ];
+        let (_, tasks) = sync_file_manifest(&dir, &old, &new).unwrap();
+
+        assert!(!dir.join("mods/old.jar").exists());
+        assert!(dir.join("mods/keep.jar").exists());
+        assert_eq!(tasks.len(), 1);
+        assert!(tasks[0].path.ends_with("mods/new.jar"));
+        let _ = fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn test_replaced_mods() {
+        let old = vec![
+            file("mods/a.jar", Some("1")),
+            file("mods/b.jar", Some("2")),
+            file("config/c.toml", Some("3")),
+        ];
+        let new = vec![
+            file("mods/a.jar", Some("1b")),
+            file("config/c.toml", Some("3b")),
+            file("mods/new.jar", Some("4")),
+        ];
+        let diff = diff_file_manifests(&old, &new);
+        assert_eq!(diff.replaced_mods(), vec!["a.jar", "new.jar", "b.jar"]);
+    }
+
+    #[test]
+    fn test_sync_file_manifest_rejects_traversal() {
+        let root = std::env::temp_dir().join(format!("dropout-sync-{}", uuid::Uuid::new_v4()));
+        let dir = root.join("game");
+        fs::create_dir_all(dir.join("mods")).unwrap();
+        fs::write(root.join("victim.txt"), b"x").unwrap();
+
+        let old = vec![file("mods/../../victim.txt", Some("1"))];
+        let new = vec![file("mods/../../evil.sh", Some("2"))];
+        let (_, tasks) = sync_file_manifest(&dir, &old, &new).unwrap();
+
+        assert!(root.join("victim.txt").exists());
+        assert!(tasks.is_empty());
+        let _ = fs::remove_dir_all(&root);
+    }
+
+    fn write_pack(path: &Path, entries: &[(&str, &[u8])]) {
+        let mut zip = create_archive(path).unwrap();
+        for (name, data) in entries {
+            zip.start_file(*name, zip_options()).unwrap();
+            zip.write_all(data).unwrap();
+        }
+        zip.finish().unwrap();
+    }
+
+    #[test]
+    fn test_extract_overrides_rejects_traversal() {
+        let dir = std::env::temp_dir().join(format!("dropout-extract-{}", uuid::Uuid::new_v4()));
+        let game_dir = dir.join("game");
+        let pack = dir.join("evil.mrpack");
+        write_pack(
+            &pack,
+            &[
+                ("overrides/config/a.toml", b"a"),
+                ("overrides/config/../../../evil.sh", b"x"),
+            ],
+        );
+        let prefixes = vec!["overrides/".to_string()];
+        extract_overrides(&pack, &game_dir, &prefixes, |_, _, _| {}).unwrap();
+
+        assert!(game_dir.join("config/a.toml").exists());
+        assert!(!dir.join("evil.sh").exists());
+        let _ = fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn test_update_overrides_keeps_user_edits() {
+        let dir = std::env::temp_dir().join(format!("dropout-update-{}", uuid::Uuid::new_v4()));
+        let game_dir = dir.join("game");
+        let prefixes = vec!["overrides/".to_string()];
+        let v1 = dir.join("v1.mrpack");
+        write_pack(
+            &v1,
+            &[
+                ("overrides/config/a.toml", b"a1"),
+                ("overrides/config/b.toml", b"b1"),
+                ("overrides/config/gone.toml", b"g1"),
+            ],
+        );
+        extract_overrides(&v1, &game_dir, &prefixes, |_, _, _| {}).unwrap();
+        let old = override_hashes(&v1, &prefixes).unwrap();
+        fs::write(game_dir.join("config/b.toml"), b"edited").unwrap();
+
+        let v2 = dir.join("v2.mrpack");
+        write_pack(
+            &v2,
+            &[
+                ("overrides/config/a.toml", b"a2"),
+                ("overrides/config/b.toml", b"b2"),
+                ("overrides/config/new.toml", b"n2"),
+            ],
+        );
+        let (new, kept) = update_overrides(&v2, &game_dir, &prefixes, &old).unwrap();
+
+        assert_eq!(fs::read(game_dir.join("config/a.toml")).unwrap(), b"a2");
+        assert_eq!(fs::read(game_dir.join("config/b.toml")).unwrap(), b"edited");
+        assert_eq!(fs::read(game_dir.join("config/new.toml")).unwrap(), b"n2");
+        assert!(!game_dir.join("config/gone.toml").exists());
+        assert_eq!(kept, vec!["config/b.toml"]);
+        assert_eq!(new.len(), 3);
+        let _ = fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn test_update_summary() {
+        let old = vec![file("mods/a.jar", Some("1")), file("mods/b.jar", Some("2"))];
+        let new = vec![
+            file("mods/a.jar", Some("1b")),
+            file("mods/c.jar", Some("3")),
+        ];
+        let diff = diff_file_manifests(&old, &new);
+        let summary = update_summary(&old, &diff, vec![]);
+        assert_eq!(summary.added, vec!["mods/c.jar"]);
+        assert_eq!(summary.changed, vec!["mods/a.jar"]);
+        assert_eq!(summary.removed, vec!["mods/b.jar"]);
+    }
+
+    #[test]
+    fn test_cf_fingerprint_ignores_whitespace() {
+        assert_eq!(cf_fingerprint(b"a b\r\nc\td"), cf_fingerprint(b"abcd"));
+        assert_ne!(cf_fingerprint(b"abcd"), cf_fingerprint(b"abce"));
+        assert_eq!(cf_fingerprint(b""), 0x5bd1_5e36);
+    }
+
+    #[test]
+    fn test_write_mrpack_round_trip() {
+        let dir = std::env::temp_dir().join(format!("dropout-export-{}", uuid::Uuid::new_v4()));
+        let game_dir = dir.join("game");
+        fs::create_dir_all(game_dir.join("mods")).unwrap();
+        fs::create_dir_all(game_dir.join("config")).unwrap();
+        fs::create_dir_all(game_dir.join("logs")).unwrap();
+        fs::write(game_dir.join("mods/sodium.jar"), b"sodium").unwrap();
+        fs::write(game_dir.join("mods/local.jar"), b"local build").unwrap();
+        fs::write(game_dir.join("config/sodium.json"), b"{}").unwrap();
+        fs::write(game_dir.join("logs/latest.log"), b"log").unwrap();
+        fs::create_dir_all(game_dir.join("saves/World")).unwrap();
+        fs::write(game_dir.join("saves/World/level.dat"), b"world").unwrap();
+        fs::write(game_dir.join("options.txt"), b"fov:1.0").unwrap();
+
+        let mut referenced = HashMap::new();
+        referenced.insert(
+            "mods/sodium.jar".to_string(),
+            file("mods/sodium.jar", Some("unused")),
+        );
+        let info = ModpackInfo {
+            name: "My Pack".into(),
+            minecraft_version: Some("1.20.1".into()),
+            mod_loader: Some("fabric".into()),
+            mod_loader_version: Some("0.15.11".into()),
+            modpack_type: "modrinth".into(),
+            instance_id: None,
+        };
+        let dest = dir.join("My Pack.mrpack");
+        let summary = write_mrpack(&game_dir, &dest, &info, "1.0.0", &referenced).unwrap();
+        assert_eq!(summary.referenced, 1);
+        assert_eq!(summary.stored, 2);
+
+        let pack = parse(&dest).unwrap();
+        assert_eq!(pack.info.modpack_type, "modrinth");
+        assert_eq!(pack.info.mod_loader.as_deref(), Some("fabric"));
+        assert_eq!(pack.info.mod_loader_version.as_deref(), Some("0.15.11"));
+        assert_eq!(pack.files.len(), 1);
+        assert_eq!(pack.files[0].url, "https://cdn.example/mods/sodium.jar");
+        assert_eq!(
+            pack.files[0].sha1.as_deref(),
+            Some(crate::downloader::compute_sha1(b"sodium").as_str())
+        );
+
+        let mut archive = zip::ZipArchive::new(fs::File::open(&dest).unwrap()).unwrap();
+        assert!(archive.by_name("overrides/mods/local.jar").is_ok());
+        assert!(archive.by_name("overrides/config/sodium.json").is_ok());
+        assert!(archive.by_name("overrides/logs/latest.log").is_err());
+        assert!(archive.by_name("overrides/saves/World/level.dat").is_err());
+        assert!(archive.by_name("overrides/options.txt").is_err());
+        let _ = fs::remove_dir_all(&dir);
+    }
+}
@@ -0,0 +1,448 @@
+//! Browsing and installing Modrinth projects: mods, resource packs and
+//! shader packs.
+//!
+//! Modrinth tags a version with the loaders it works with. For resource
+//! packs that is `minecraft`, and for shader packs the shader loader
+//! (`iris`, `optifine`, ...), so [`project_loaders`] picks the tags that fit
+//! an instance from its mod loader and, for shaders, the shader loader found
+//! by [`detect_shader_loader`]. [`to_download`] places the file under `mods/`,
+//! `resourcepacks/` or `shaderpacks/`.
+
+use crate::modpack::ModpackFile;
+use crate::mods::InstalledMod;
+use serde::{Deserialize, Serialize};
+use ts_rs::TS;
+
+const MODRINTH_API: &str = "https://api.modrinth.com/v2";
+const PAGE_SIZE: u32 = 20;
+
+/// Project types that can be installed into an instance
+pub const PROJECT_TYPES: &[&str] = &["mod", "resourcepack", "shader"];
+
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export, export_to = "dropout-core/modrinth.ts")]
+pub struct ModrinthProject {
+    pub id: String,
+    pub slug: String,
+    pub title: String,
+    pub description: String,
+    pub author: Option<String>, // only in search results
+    pub downloads: u64,
+    pub icon_url: Option<String>,
+    pub project_type: String, // "mod" | "resourcepack" | "shader" | ...
+    pub categories: Vec<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export, export_to = "dropout-core/modrinth.ts")]
+pub struct ModrinthSearch {
+    pub projects: Vec<ModrinthProject>,
+    pub total: u64,
+    pub page: u32,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export, export_to = "dropout-core/modrinth.ts")]
+pub struct ModrinthFile {
+    pub url: String,
+    pub filename: String,
+    pub size: Option<u64>,
+    pub sha1: Option<String>,
+    pub primary: bool,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export, export_to = "dropout-core/modrinth.ts")]
+pub struct ModrinthVersion {
+    pub id: String,
+    pub project_id: String,
+    pub name: String,
+    pub version_number: String,
+    pub version_type: String, // "release" | "beta" | "alpha"
+    pub date_published: String,
+    pub game_versions: Vec<String>,
+    pub loaders: Vec<String>,
+    pub files: Vec<ModrinthFile>,
+}
+
+pub(crate) fn client() -> Result<reqwest::Client, String> {
+    reqwest::Client::builder()
+        .user_agent("DropOut/1.0")
+        .build()
+        .map_err(|e| e.to_string())
+}
+
+/// GET `path` from the Modrinth API, `None` when it doesn't exist
+pub(crate) async fn get(
+    path: &str,
+    query: &[(&str, String)],
+) -> Result<Option<serde_json::Value>, String> {
+    let resp = client()?
+        .get(format!("{MODRINTH_API}{path}"))
+        .query(query)
+        .send()
+        .await
+        .map_err(|e| format!("Modrinth API error: {e}"))?;
+    if resp.status() == reqwest::StatusCode::NOT_FOUND {
+        return Ok(None);
+    }
+    if !resp.status().is_success() {
+        return Err(format!("Modrinth API returned {}", resp.status()));
+    }
+    resp.json().await.map(Some).map_err(|e| e.to_string())
+}
+
+fn strings(value: &serde_json::Value) -> Vec<String> {
+    value
+        .as_array()
+        .map(|values| {
+            values
+                .iter()
+                .filter_map(|v| v.as_str().map(String::from))
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+/// A project from a search hit (`project_id`, `author`) or from `/project`
+fn parse_project(value: &serde_json::Value) -> Option<ModrinthProject> {
+    Some(ModrinthProject {
+        id: value["project_id"]
+            .as_str()
+            .or(value["id"].as_str())?
+            .to_string(),
+        slug: value["slug"].as_str()?.to_string(),
+        title: value["title"].as_str()?.to_string(),
+        description: value["description"]
+            .as_str()
+            .unwrap_or_default()
+            .to_string(),
+        author: value["author"].as_str().map(String::from),
+        downloads: value["downloads"].as_u64().unwrap_or_default(),
+        icon_url: value["icon_url"]
+            .as_str()
+            .filter(|url| !url.is_empty())
+            .map(String::from),
+        project_type: value["project_type"].as_str()?.to_string(),
+        categories: strings(&value["categories"]),
+    })
+}
+
+fn parse_version(value: &serde_json::Value) -> Option<ModrinthVersion> {
+    Some(ModrinthVersion {
+        id: value["id"].as_str()?.to_string(),
+        project_id: value["project_id"].as_str()?.to_string(),
+        name: value["name"].as_str().unwrap_or_default().to_string(),
+        version_number: value["version_number"]
+            .as_str()
+            .unwrap_or_default()
+            .to_string(),
+        version_type: value["version_type"]
+            .as_str()
+            .unwrap_or("release")
+            .to_string(),
+        date_published: value["date_published"]
+            .as_str()
+            .unwrap_or_default()
+            .to_string(),
+        game_versions: strings(&value["game_versions"]),
+        loaders: strings(&value["loaders"]),
+        files: value["files"]
+            .as_array()
+            .map(|files| {
+                files
+                    .iter()
+                    .filter_map(|file| {
+                        Some(ModrinthFile {
+                            url: file["url"].as_str()?.to_string(),
+                            filename: file["filename"].as_str()?.to_string(),
+                            size: file["size"].as_u64(),
+                            sha1: file["hashes"]["sha1"].as_str().map(String::from),
+                            primary: file["primary"].as_bool().unwrap_or(false),
+                        })
+                    })
+                    .collect()
+            })
+            .unwrap_or_default(),
+    })
+}
+
+/// Shader loader of an instance: "iris" when Iris (or its Forge port Oculus)
+/// is installed, "optifine" for an OptiFine version or an OptiFine jar in
+/// `mods/`, `None` when shaders can't be loaded
+pub fn detect_shader_loader(
+    version_id: Option<&str>,
+    mods: &[InstalledMod],
+) -> Option<&'static str> {
+    let enabled = || mods.iter().filter(|m| m.enabled);
+    if enabled().any(|m| {
+        m.metadata
+            .as_ref()
+            .is_some_and(|meta| matches!(meta.id.as_str(), "iris" | "oculus"))
+    }) {
+        return Some("iris");
+    }
+    let optifine_version = version_id.is_some_and(|id| id.contains("-OptiFine_"));
+    let optifine_jar = enabled().any(|m| {
+        m.file_name.to_ascii_lowercase().starts_with("optifine")
+            || m.metadata
+                .as_ref()
+                .is_some_and(|meta| meta.id == "optifine")
+    });
+    (optifine_version || optifine_jar).then_some("optifine")
+}
+
+/// Loader tags of versions that work in an instance with `mod_loader` and
+/// `shader_loader`, for a project of `project_type`. Empty means any.
+pub fn project_loaders(
+    project_type: &str,
+    mod_loader: Option<&str>,
+    shader_loader: Option<&str>,
+) -> Vec<String> {
+    match project_type {
+        "resourcepack" => vec!["minecraft".to_string()],
+        // Iris also loads packs made for OptiFine
+        "shader" => match shader_loader {
+            Some("iris") => vec!["iris".to_string(), "optifine".to_string()],
+            other => other.map(String::from).into_iter().collect(),
+        },
+        _ => mod_loader.map(String::from).into_iter().collect(),
+    }
+}
+
+/// Search Modrinth for projects of `project_type`, most relevant first.
+/// `game_version` and `loaders` narrow the results to projects with
+/// matching versions.
+pub async fn search(
+    query: &str,
+    project_type: &str,
+    game_version: Option<&str>,
+    loaders: &[String],
+    page: u32,
+) -> Result<ModrinthSearch, String> {
+    if !PROJECT_TYPES.contains(&project_type) {
+        return Err(format!("Unsupported project type '{}'", project_type));
+    }
+    // Facets are ANDed between the inner lists and ORed within one
+    let mut facets = vec![vec![format!("project_type:{project_type}")]];
+    if let Some(version) = game_version {
+        facets.push(vec![format!("versions:{version}")]);
+    }
+    if !loaders.is_empty() {
+        facets.push(loaders.iter().map(|l| format!("categories:{l}")).collect());
+    }
+    let body = get(
+        "/search",
+        &[
+            ("query", query.trim().to_string()),
+            ("facets", serde_json::json!(facets).to_string()),
+            ("offset", (page * PAGE_SIZE).to_string()),
+            ("limit", PAGE_SIZE.to_string()),
+        ],
+    )
+    .await?
+    .unwrap_or_default();
+    Ok(ModrinthSearch {
+        projects: body["hits"]
+            .as_array()
+            .map(|hits| hits.iter().filter_map(parse_project).collect())
+            .unwrap_or_default(),
+        total: body["total_hits"].as_u64().unwrap_or_default(),
+        page,
+    })
+}
+
+/// A project by its ID or slug
+pub async fn project(id: &str) -> Result<ModrinthProject, String> {
+    get(&format!("/project/{id}"), &[])
+        .await?
+        .as_ref()
+        .and_then(parse_project)
+        .ok_or_else(|| format!("Project {id} not found on Modrinth"))
+}
+
+/// Versions of a project for `game_version` and `loaders`, newest first
+pub async fn versions(
+    id: &str,
+    game_version: Option<&str>,
+    loaders: &[String],
+) -> Result<Vec<ModrinthVersion>, String> {
+    let mut query = Vec::new();
+    if let Some(version) = game_version {
+        query.push(("game_versions", serde_json::json!([version]).to_string()));
+    }
+    if !loaders.is_empty() {
+        query.push(("loaders", serde_json::json!(loaders).to_string()));
+    }
+    let body = get(&format!("/project/{id}/version"), &query)
+        .await?
+        .ok_or_else(|| format!("Project {id} not found on Modrinth"))?;
+    Ok(body
+        .as_array()
+        .map(|versions| versions.iter().filter_map(parse_version).collect())
+        .unwrap_or_default())
+}
+
+/// A version by its ID
+pub async fn version(id: &str) -> Result<ModrinthVersion, String> {
+    get(&format!("/version/{id}"), &[])
+        .await?
+        .as_ref()
+        .and_then(parse_version)
+        .ok_or_else(|| format!("Version {id} not found on Modrinth"))
+}
+
+/// The primary file of `version` (or its first one) under the instance
+/// folder for `project_type`
+pub fn to_download(version: &ModrinthVersion, project_type: &str) -> Result<ModpackFile, String> {
+    let dir = match project_type {
+        "resourcepack" => "resourcepacks",
+        "shader" => "shaderpacks",
+        _ => "mods",
+    };
+    let file = version
+        .files
+        .iter()
+        .find(|f| f.primary)
+        .or(version.files.first())
+        .ok_or_else(|| format!("Version {} has no files", version.version_number))?;
+    if file.filename.contains(['/', '\\']) || file.filename.starts_with('.') {
+        return Err(format!("Invalid file name: {}", file.filename));
+    }
+    Ok(ModpackFile {
+        url: file.url.clone(),
+        path: format!("{dir}/{}", file.filename),
+        size: file.size,
+        sha1: file.sha1.clone(),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::mods::ModMetadata;
+
+    fn installed(file_name: &str, id: Option<&str>, enabled: bool) -> InstalledMod {
+        InstalledMod {
+            file_name: file_name.to_string(),
+            enabled,
+            metadata: id.map(|id| ModMetadata {
+                id: id.to_string(),
+                name: id.to_string(),
+                version: "1.0".to_string(),
+                authors: vec![],
+                description: None,
+                url: None,
+                loader: "fabric".to_string(),
+                dependencies: vec![],
+                icon: None,
+            }),
+        }
+    }
+
+    #[test]
+    fn test_detect_shader_loader() {
+        let sodium = installed("sodium.jar", Some("sodium"), true);
+        assert_eq!(
+            detect_shader_loader(Some("1.20.1"), std::slice::from_ref(&sodium)),
+            None
+        );
+        assert_eq!(
+            detect_shader_loader(
+                Some("fabric-loader-0.15.7-1.20.1"),
+                &[sodium.clone(), installed("iris.jar", Some("iris"), true)]
+            ),
+            Some("iris")
+        );
+        assert_eq!(
+            detect_shader_loader(None, &[installed("oculus.jar", Some("oculus"), true)]),
+            Some("iris")
+        );
+        assert_eq!(
+            detect_shader_loader(Some("1.20.1-OptiFine_HD_U_I6"), &[]),
+            Some("optifine")
+        );
+        assert_eq!(
+            detect_shader_loader(
+                None,
+                &[installed("OptiFine_1.20.1_HD_U_I6.jar", None, true)]
+            ),
+            Some("optifine")
+        );
+        // Disabled mods don't count
+        assert_eq!(
+            detect_shader_loader(None, &[installed("iris.jar.disabled", Some("iris"), false)]),
+            None
+        );
+    }
+
+    #[test]
+    fn test_project_loaders() {
+        assert_eq!(
+            project_loaders("resourcepack", Some("fabric"), None),
+            vec!["minecraft"]
+        );
+        assert_eq!(
+            project_loaders("shader", Some("fabric"), Some("iris")),
+            vec!["iris", "optifine"]
+        );
+        assert_eq!(
+            project_loaders("shader", None, Some("optifine")),
+            vec!["optifine"]
+        );
+        assert!(project_loaders("shader", Some("fabric"), None).is_empty());
+        assert_eq!(project_loaders("mod", Some("quilt"), None), vec!["quilt"]);
+    }
+
+    #[test]
+    fn test_parse_version_and_download() {
+        let value = serde_json::json!({
+            "id": "AbCd1234",
+            "project_id": "HVnmMxH1",
+            "name": "Complementary Shaders r5.2",
+            "version_number": "r5.2",
+            "version_type": "release",
+            "date_published": "2024-05-01T00:00:00Z",
+            "game_versions": ["1.20.1", "1.20.4"],
+            "loaders": ["iris", "optifine"],
+            "files": [
+                {
+                    "url": "https://cdn.modrinth.com/data/HVnmMxH1/versions/AbCd1234/extra.txt",
+                    "filename": "extra.txt",
+                    "size": 10,
+                    "hashes": {},
+                    "primary": false
+                },
+                {
+                    "url": "https://cdn.modrinth.com/data/HVnmMxH1/versions/AbCd1234/ComplementaryReimagined_r5.2.zip",
+                    "filename": "ComplementaryReimagined_r5.2.zip",
+                    "size": 1234,
+                    "hashes": {"sha1": "abc"},
+                    "primary": true
+                }
+            ]
+        });
+        let version = parse_version(&value).unwrap();
+        assert_eq!(version.loaders, vec!["iris", "optifine"]);
+        let download = to_download(&version, "shader").unwrap();
+        assert_eq!(
+            download.path,
+            "shaderpacks/ComplementaryReimagined_r5.2.zip"
+        );
+        assert_eq!(download.sha1.as_deref(), Some("abc"));
+        assert!(
+            to_download(&version, "resourcepack")
+                .unwrap()
+                .path
+                .starts_with("resourcepacks/")
+        );
+
+        let mut bad = version.clone();
+        bad.files[1].filename = "../options.txt".to_string();
+        assert!(to_download(&bad, "shader").is_err());
+    }
+}
@@ -0,0 +1,676 @@
+//! Metadata of installed mods, read from the descriptors inside their jars:
+//! `fabric.mod.json`, `quilt.mod.json` and `META-INF/mods.toml`
+//! (`neoforge.mods.toml` for NeoForge 1.20.5+).
+//!
+//! Besides id, name and version this gives the declared dependencies and the
+//! path of the mod's icon inside the jar ([`read_icon`] loads it).
+
+use serde::{Deserialize, Serialize};
+use std::io::{Read, Seek};
+use std::path::Path;
+use ts_rs::TS;
+
+/// Descriptors are small; anything larger is not one
+const MAX_DESCRIPTOR_SIZE: u64 = 1024 * 1024;
+/// Icons larger than this are not loaded
+const MAX_ICON_SIZE: u64 = 512 * 1024;
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export, export_to = "dropout-core/mods.ts")]
+pub struct ModDependency {
+    pub id: String,
+    pub version: Option<String>, // version range as written in the descriptor
+    pub required: bool,          // false for recommended/optional dependencies
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export, export_to = "dropout-core/mods.ts")]
+pub struct ModMetadata {
+    pub id: String,
+    pub name: String,
+    pub version: String,
+    pub authors: Vec<String>,
+    pub description: Option<String>,
+    pub url: Option<String>, // homepage, falling back to the source repository
+    pub loader: String,      // "fabric" | "quilt" | "forge" | "neoforge"
+    pub dependencies: Vec<ModDependency>,
+    pub icon: Option<String>, // path of the icon inside the jar
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export, export_to = "dropout-core/mods.ts")]
+pub struct InstalledMod {
+    pub file_name: String,
+    pub enabled: bool, // false for `.jar.disabled`
+    pub metadata: Option<ModMetadata>,
+}
+
+impl InstalledMod {
+    pub fn display_name(&self) -> &str {
+        self.metadata
+            .as_ref()
+            .map(|m| m.name.as_str())
+            .unwrap_or(&self.file_name)
+    }
+}
+
+fn str_field(value: &serde_json::Value, key: &str) -> Option<String> {
+    value[key]
+        .as_str()
+        .map(str::trim)
+        .filter(|s| !s.is_empty())
+        .map(String::from)
+}
+
+fn contact_url(contact: &serde_json::Value) -> Option<String> {
+    str_field(contact, "homepage").or_else(|| str_field(contact, "sources"))
+}
+
+/// `"icon"` is either a path or a map of sizes to paths; the largest wins
+fn json_icon(icon: &serde_json::Value) -> Option<String> {
+    if let Some(path) = icon.as_str() {
+        return Some(path.trim_start_matches('/').to_string());
+    }
+    icon.as_object()?
+        .iter()
+        .max_by_key(|(size, _)| size.parse::<u32>().unwrap_or(0))
+        .and_then(|(_, path)| path.as_str())
+        .map(|path| path.trim_start_matches('/').to_string())
+}
+
+/// Version ranges of fabric.mod.json are a string or a list of alternatives
+fn json_version_range(value: &serde_json::Value) -> Option<String> {
+    match value {
+        serde_json::Value::String(range) => Some(range.clone()),
+        serde_json::Value::Array(ranges) => Some(
+            ranges
+                .iter()
+                .filter_map(|r| r.as_str())
+                .collect::<Vec<_>>()
+                .join(" || "),
+        ),
+        _ => None,
+    }
+}
+
+fn fabric_dependencies(json: &serde_json::Value) -> Vec<ModDependency> {
+    let mut dependencies = Vec::new();
+    for (key, required) in [
+        ("depends", true),
+        ("recommends", false),
+        ("suggests", false),
+    ] {
+        for (id, range) in json[key].as_object().into_iter().flatten() {
+            dependencies.push(ModDependency {
+                id: id.clone(),
+                version: json_version_range(range).filter(|r| r != "*"),
+                required,
+            });
+        }
+    }
+    dependencies
+}
+
+/// quilt.mod.json lists dependencies as ids or `{ "id", "versions", "optional" }`
+fn quilt_dependencies(depends: &serde_json::Value) -> Vec<ModDependency> {
+    depends
+        .as_array()
+        .into_iter()
+        .flatten()
+        .filter_map(|dep| {
+            if let Some(id) = dep.as_str() {
+                return Some(ModDependency {
+                    id: id.to_string(),
+                    version: None,
+                    required: true,
+                });
+            }
+            Some(ModDependency {
+                id: str_field(dep, "id")?,
+                version: json_version_range(&dep["versions"]).filter(|r| r != "*"),
+                required: !dep["optional"].as_bool().unwrap_or(false),
+            })
+        })
+        .collect()
+}
+
+pub fn parse_fabric(content: &str) -> Result<ModMetadata, String> {
+    let json: serde_json::Value =
+        serde_json::from_str(content).map_err(|e| format!("Invalid fabric.mod.json: {}", e))?;
+    let id = str_field(&json, "id").ok_or("fabric.mod.json has no id")?;
+    // Authors are either names or `{ "name": ..., "contact": ... }` objects
+    let authors = json["authors"]
+        .as_array()
+        .map(|authors| {
+            authors
+                .iter()
+                .filter_map(|a| {
+                    a.as_str()
+                        .map(String::from)
+                        .or_else(|| str_field(a, "name"))
+                })
+                .collect()
+        })
+        .unwrap_or_default();
+    Ok(ModMetadata {
+        name: str_field(&json, "name").unwrap_or_else(|| id.clone()),
+        version: str_field(&json, "version").unwrap_or_default(),
+        authors,
+        description: str_field(&json, "description"),
+        url: contact_url(&json["contact"]),
+        loader: "fabric".to_string(),
+        dependencies: fabric_dependencies(&json),
+        icon: json_icon(&json["icon"]),
+        id,
+    })
+}
+
+pub fn parse_quilt(content: &str) -> Result<ModMetadata, String> {
+    let json: serde_json::Value =
+        serde_json::from_str(content).map_err(|e| format!("Invalid quilt.mod.json: {}", e))?;
+    let loader = &json["quilt_loader"];
+    let metadata = &loader["metadata"];
+    let id = str_field(loader, "id").ok_or("quilt.mod.json has no id")?;
+    // `contributors` maps names to roles
+    let authors = metadata["contributors"]
+        .as_object()
+        .map(|contributors| contributors.keys().cloned().collect())
+        .unwrap_or_default();
+    Ok(ModMetadata {
+        name: str_field(metadata, "name").unwrap_or_else(|| id.clone()),
+        version: str_field(loader, "version").unwrap_or_default(),
+        authors,
+        description: str_field(metadata, "description"),
+        url: contact_url(&metadata["contact"]),
+        loader: "quilt".to_string(),
+        dependencies: quilt_dependencies(&loader["depends"]),
+        icon: json_icon(&metadata["icon"]),
+        id,
+    })
+}
+
+/// The first mod of a `mods.toml`. `jar_version` replaces the
+/// `${file.jarVersion}` placeholder most Forge mods use as their version.
+pub fn parse_mods_toml(
+    content: &str,
+    jar_version: Option<&str>,
+    loader: &str,
+) -> Result<ModMetadata, String> {
+    let toml: toml::Value =
+        toml::from_str(content).map_err(|e| format!("Invalid mods.toml: {}", e))?;
+    let field = |table: &toml::Value, key: &str| {
+        table
+            .get(key)
+            .and_then(|v| v.as_str())
+            .map(str::trim)
+            .filter(|s| !s.is_empty())
+            .map(String::from)
+    };
+    let first = toml
+        .get("mods")
+        .and_then(|mods| mods.as_array())
+        .and_then(|mods| mods.first())
+        .ok_or("mods.toml declares no mods")?;
+    let id = field(first, "modId").ok_or("mods.toml has no modId")?;
+    let version = match field(first, "version") {
+        Some(v) if v.contains("${file.jarVersion}") => jar_version.unwrap_or_default().to_string(),
+        Some(v) => v,
+        None => String::new(),
+    };
+    // `authors` is free text, sometimes set for the whole file
+    let authors = field(first, "authors")
+        .or_else(|| field(&toml, "authors"))
+        .map(|a| {
+            a.split([',', '&'])
+                .map(|a| a.trim().to_string())
+                .filter(|a| !a.is_empty())
+                .collect()
+        })
+        .unwrap_or_default();
+    // `[[dependencies.<modId>]]`: Forge says `mandatory`, NeoForge `type`.
+    // NeoForge's "incompatible" and "discouraged" entries are conflicts,
+    // not dependencies, and are left out.
+    let dependencies = toml
+        .get("dependencies")
+        .and_then(|deps| deps.get(&id))
+        .and_then(|deps| deps.as_array())
+        .into_iter()
+        .flatten()
+        .filter_map(|dep| {
+            let required = match field(dep, "type") {
+                Some(kind)
+                    if kind.eq_ignore_ascii_case("incompatible")
+                        || kind.eq_ignore_ascii_case("discouraged") =>
+                {
+                    return None;
+                }
+                Some(kind) => kind.eq_ignore_ascii_case("required"),
+                None => dep
+                    .get("mandatory")
+                    .and_then(|m| m.as_bool())
+                    .unwrap_or(true),
+            };
+            Some(ModDependency {
+                id: field(dep, "modId")?,
+                version: field(dep, "versionRange"),
+                required,
+            })
+        })
+        .collect();
+    Ok(ModMetadata {
+        name: field(first, "displayName").unwrap_or_else(|| id.clone()),
+        version,
+        authors,
+        description: field(first, "description"),
+        url: field(first, "displayURL").or_else(|| field(&toml, "issueTrackerURL")),
+        loader: loader.to_string(),
+        dependencies,
+        icon: field(first, "logoFile")
+            .or_else(|| field(&toml, "logoFile"))
+            .map(|path| path.trim_start_matches('/').to_string()),
+        id,
+    })
+}
+
+fn read_entry<R: Read + Seek>(archive: &mut zip::ZipArchive<R>, name: &str) -> Option<String> {
+    let entry = archive.by_name(name).ok()?;
+    let mut content = String::new();
+    entry
+        .take(MAX_DESCRIPTOR_SIZE)
+        .read_to_string(&mut content)
+        .ok()?;
+    Some(content)
+}
+
+fn manifest_value(manifest: &str, key: &str) -> Option<String> {
+    manifest.lines().find_map(|line| {
+        let (k, v) = line.split_once(':')?;
+        (k.trim() == key).then(|| v.trim().to_string())
+    })
+}
+
+/// Metadata of a mod jar, `None` if it has no descriptor we understand
+pub fn read_jar<R: Read + Seek>(reader: R) -> Result<Option<ModMetadata>, String> {
+    let mut archive = zip::ZipArchive::new(reader).map_err(|e| e.to_string())?;
+    if let Some(content) = read_entry(&mut archive, "fabric.mod.json") {
+        return parse_fabric(&content).map(Some);
+    }
+    if let Some(content) = read_entry(&mut archive, "quilt.mod.json") {
+        return parse_quilt(&content).map(Some);
+    }
+    for (name, loader) in [
+        ("META-INF/neoforge.mods.toml", "neoforge"),
+        ("META-INF/mods.toml", "forge"),
+    ] {
+        if let Some(content) = read_entry(&mut archive, name) {
+            let jar_version = read_entry(&mut archive, "META-INF/MANIFEST.MF")
+                .and_then(|m| manifest_value(&m, "Implementation-Version"));
+            return parse_mods_toml(&content, jar_version.as_deref(), loader).map(Some);
+        }
+    }
+    Ok(None)
+}
+
+/// The icon of a mod jar (usually a PNG), if it declares one
+pub fn read_icon(jar: &Path) -> Result<Option<Vec<u8>>, String> {
+    let file = std::fs::File::open(jar).map_err(|e| e.to_string())?;
+    let Some(icon) = read_jar(std::io::BufReader::new(file))?.and_then(|meta| meta.icon) else {
+        return Ok(None);
+    };
+    let file = std::fs::File::open(jar).map_err(|e| e.to_string())?;
+    let mut archive =
+        zip::ZipArchive::new(std::io::BufReader::new(file)).map_err(|e| e.to_string())?;
+    let Ok(entry) = archive.by_name(&icon) else {
+        return Ok(None);
+    };
+    if entry.size() > MAX_ICON_SIZE {
+        return Ok(None);
+    }
+    let mut data = Vec::new();
+    entry
+        .take(MAX_ICON_SIZE)
+        .read_to_end(&mut data)
+        .map_err(|e| e.to_string())?;
+    Ok(Some(data))
+}
+
+/// Every jar in `<game_dir>/mods`, disabled ones included, sorted by file name.
+/// Jars whose metadata can't be read are listed without it.
+pub fn scan_mods(game_dir: &Path) -> Result<Vec<InstalledMod>, String> {
+    let mods_dir = game_dir.join("mods");
+    if !mods_dir.is_dir() {
+        return Ok(Vec::new());
+    }
+    let mut mods = Vec::new();
+    for entry in std::fs::read_dir(&mods_dir).map_err(|e| e.to_string())? {
+        let path = entry.map_err(|e| e.to_string())?.path();
+        let file_name = path
+            .file_name()
+            .unwrap_or_default()
+            .to_string_lossy()
+            .to_string();
+        let enabled = file_name.ends_with(".jar");
+        if !path.is_file() || !(enabled || file_name.ends_with(".jar.disabled")) {
+            continue;
+        }
+        let metadata = std::fs::File::open(&path)
+            .map_err(|e| e.to_string())
+            .and_then(|file| read_jar(std::io::BufReader::new(file)))
+            .unwrap_or_else(|e| {
+                eprintln!("[Mods] Failed to read {}: {}", file_name, e);
+                None
+            });
+        mods.push(InstalledMod {
+            file_name,
+            enabled,
+            metadata,
+        });
+    }
+    mods.sort_by(|a, b| a.file_name.cmp(&b.file_name));
+    Ok(mods)
+}
+
+/// Enable or disable a jar in `<game_dir>/mods` by renaming it to and from
+/// `<name>.jar.disabled`, the convention MultiMC and Prism use. `file_name`
+/// may be either form; returns the jar's new file name. Doing nothing is not
+/// an error when the jar already is in the requested state.
+pub fn set_enabled(game_dir: &Path, file_name: &str, enabled: bool) -> Result<String, String> {
+    if file_name.contains(['/', '\\']) || file_name.starts_with('.') {
+        return Err(format!("Invalid file name: {}", file_name));
+    }
+    let jar_name = file_name.strip_suffix(".disabled").unwrap_or(file_name);
+    if !jar_name.ends_with(".jar") {
+        return Err(format!("{} is not a mod jar", file_name));
+    }
+    let mods_dir = game_dir.join("mods");
+    let enabled_path = mods_dir.join(jar_name);
+    let disabled_path = mods_dir.join(format!("{}.disabled", jar_name));
+    let (from, to) = if enabled {
+        (disabled_path, enabled_path)
+    } else {
+        (enabled_path, disabled_path)
+    };
+    let new_name = to
+        .file_name()
+        .unwrap_or_default()
+        .to_string_lossy()
+        .to_string();
+    if !from.is_file() {
+        return if to.is_file() {
+            Ok(new_name)
+        } else {
+            Err(format!("Mod {} not found", jar_name))
+        };
+    }
+    if to.exists() {
+        return Err(format!(
+            "Both {0} and {0}.disabled exist; remove one of them first",
+            jar_name
+        ));
+    }
+    std::fs::rename(&from, &to).map_err(|e| format!("Failed to rename {}: {}", file_name, e))?;
+    Ok(new_name)
+}
+
+// ── Shareable lists ───────────────────────────────────────────────────────
+
+fn escape_html(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+fn escape_markdown(text: &str) -> String {
+    text.replace('|', "\\|")
+        .replace('[', "\\[")
+        .replace(']', "\\]")
+}
+
+/// Links are only kept if they are plain web URLs
+fn web_url(url: Option<&str>) -> Option<&str> {
+    url.filter(|u| u.starts_with("https://") || u.starts_with("http://"))
+        .filter(|u| !u.contains(['"', '<', '>', ' ', '(', ')']))
+}
+
+/// Render the enabled mods as `format` ("markdown", "json" or "html")
+pub fn export_list(title: &str, mods: &[InstalledMod], format: &str) -> Result<String, String> {
+    let mods: Vec<&InstalledMod> = mods.iter().filter(|m| m.enabled).collect();
+    let field = |m: &InstalledMod, f: fn(&ModMetadata) -> String| {
+        m.metadata.as_ref().map(f).unwrap_or_default()
+    };
+    match format {
+        "json" => {
+            let list: Vec<serde_json::Value> = mods
+                .iter()
+                .map(|m| {
+                    serde_json::json!({
+                        "name": m.display_name(),
+                        "version": field(m, |md| md.version.clone()),
+                        "authors": m.metadata.as_ref().map(|md| md.authors.clone()).unwrap_or_default(),
+                        "url": m.metadata.as_ref().and_then(|md| md.url.clone()),
+                        "fileName": m.file_name,
+                    })
+                })
+                .collect();
+            serde_json::to_string_pretty(&serde_json::json!({ "title": title, "mods": list }))
+                .map_err(|e| e.to_string())
+        }
+        "markdown" => {
+            let mut out = format!(
+                "# {}\n\n{} mods\n\n| Mod | Version | Authors |\n| --- | --- | --- |\n",
+                title,
+                mods.len()
+            );
+            for m in &mods {
+                let name = escape_markdown(m.display_name());
+                let url = m
+                    .metadata
+                    .as_ref()
+                    .and_then(|md| web_url(md.url.as_deref()));
+                let name = match url {
+                    Some(url) => format!("[{}]({})", name, url),
+                    None => name,
+                };
+                out.push_str(&format!(
+                    "| {} | {} | {} |\n",
+                    name,
+                    escape_markdown(&field(m, |md| md.version.clone())),
+                    escape_markdown(&field(m, |md| md.authors.join(", ")))
+                ));
+            }
+            Ok(out)
+        }
+        "html" => {
+            let title = escape_html(title);
+            let mut out = format!(
+                "<!DOCTYPE html>\n<html>\n<head>\n<meta charset=\"utf-8\">\n<title>{title}</title>\n</head>\n<body>\n<h1>{title}</h1>\n<p>{} mods</p>\n<table>\n<tr><th>Mod</th><th>Version</th><th>Authors</th></tr>\n",
+                mods.len()
+            );
+            for m in &mods {
+                let name = escape_html(m.display_name());
+                let url = m
+                    .metadata
+                    .as_ref()
+                    .and_then(|md| web_url(md.url.as_deref()));
+                let name = match url {
+                    Some(url) => format!("<a href=\"{}\">{}</a>", escape_html(url), name),
+                    None => name,
+                };
+                out.push_str(&format!(
+                    "<tr><td>{}</td><td>{}</td><td>{}</td></tr>\n",
+                    name,
+                    escape_html(&field(m, |md| md.version.clone())),
+                    escape_html(&field(m, |md| md.authors.join(", ")))
+                ));
+            }
+            out.push_str("</table>\n</body>\n</html>\n");
+            Ok(out)
+        }
+        other => Err(format!("Unknown mod list format '{}'", other)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_fabric() {
+        let meta = parse_fabric(
+            r#"{"schemaVersion": 1, "id": "sodium", "version": "0.5.3", "name": "Sodium",
+                "authors": ["JellySquid", {"name": "IMS"}],
+                "contact": {"sources": "https://github.com/CaffeineMC/sodium-fabric"},
+                "icon": {"16": "assets/sodium/icon-16.png", "128": "assets/sodium/icon.png"},
+                "depends": {"fabricloader": ">=0.12.0", "minecraft": ["1.20", "1.20.1"]},
+                "suggests": {"indium": "*"}}"#,
+        )
+        .unwrap();
+        assert_eq!(meta.name, "Sodium");
+        assert_eq!(meta.authors, vec!["JellySquid", "IMS"]);
+        assert_eq!(
+            meta.url.as_deref(),
+            Some("https://github.com/CaffeineMC/sodium-fabric")
+        );
+        assert_eq!(meta.icon.as_deref(), Some("assets/sodium/icon.png"));
+        assert_eq!(meta.dependencies.len(), 3);
+        let minecraft = meta
+            .dependencies
+            .iter()
+            .find(|d| d.id == "minecraft")
+            .unwrap();
+        assert_eq!(minecraft.version.as_deref(), Some("1.20 || 1.20.1"));
+        assert!(minecraft.required);
+        let indium = meta.dependencies.iter().find(|d| d.id == "indium").unwrap();
+        assert_eq!(indium.version, None);
+        assert!(!indium.required);
+        assert!(parse_fabric(r#"{"name": "no id"}"#).is_err());
+    }
+
+    #[test]
+    fn test_parse_mods_toml() {
+        let content = r#"
+modLoader = "javafml"
+loaderVersion = "[47,)"
+license = "MIT"
+
+[[mods]]
+modId = "jei"
+version = "${file.jarVersion}"
+displayName = "Just Enough Items"
+authors = "mezz, Ranger"
+displayURL = "https://www.curseforge.com/minecraft/mc-mods/jei"
+logoFile = "logo.png"
+
+[[dependencies.jei]]
+modId = "forge"
+mandatory = true
+versionRange = "[47,)"
+
+[[dependencies.jei]]
+modId = "emi"
+type = "optional"
+versionRange = "*"
+
+[[dependencies.jei]]
+modId = "roughlyenoughitems"
+type = "incompatible"
+
+[[dependencies.jei]]
+modId = "optifine"
+type = "discouraged"
+"#;
+        let meta = parse_mods_toml(content, Some("15.2.0.27"), "forge").unwrap();
+        assert_eq!(meta.id, "jei");
+        assert_eq!(meta.version, "15.2.0.27");
+        assert_eq!(meta.authors, vec!["mezz", "Ranger"]);
+        assert_eq!(meta.icon.as_deref(), Some("logo.png"));
+        assert_eq!(
+            meta.dependencies,
+            vec![
+                ModDependency {
+                    id: "forge".to_string(),
+                    version: Some("[47,)".to_string()),
+                    required: true,
+                },
+                ModDependency {
+                    id: "emi".to_string(),
+                    version: Some("*".to_string()),
+                    required: false,
+                },
+            ]
+        );
+        assert!(parse_mods_toml("modLoader = \"javafml\"", None, "forge").is_err());
+    }
+
+    #[test]
+    fn test_set_enabled() {
+        let dir = std::env::temp_dir().join(format!("dropout-mods-{}", uuid::Uuid::new_v4()));
+        std::fs::create_dir_all(dir.join("mods")).unwrap();
+        std::fs::write(dir.join("mods/sodium.jar"), b"").unwrap();
+
+        assert_eq!(
+            set_enabled(&dir, "sodium.jar", false).unwrap(),
+            "sodium.jar.disabled"
+        );
+        assert!(dir.join("mods/sodium.jar.disabled").is_file());
+        let mods = scan_mods(&dir).unwrap();
+        assert!(!mods[0].enabled);
+        // Already disabled
+        assert_eq!(
+            set_enabled(&dir, "sodium.jar", false).unwrap(),
+            "sodium.jar.disabled"
+        );
+        assert_eq!(
+            set_enabled(&dir, "sodium.jar.disabled", true).unwrap(),
+            "sodium.jar"
+        );
+        assert!(scan_mods(&dir).unwrap()[0].enabled);
+
+        std::fs::write(dir.join("mods/sodium.jar.disabled"), b"").unwrap();
+        assert!(set_enabled(&dir, "sodium.jar", false).is_err());
+        assert!(set_enabled(&dir, "missing.jar", true).is_err());
+        assert!(set_enabled(&dir, "../sodium.jar", true).is_err());
+        assert!(set_enabled(&dir, "options.txt", true).is_err());
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn test_export_list() {
+        let mods = vec![
+            InstalledMod {
+                file_name: "a.jar".to_string(),
+                enabled: true,
+                metadata: Some(ModMetadata {
+                    id: "a".to_string(),
+                    name: "A <b>|".to_string(),
+                    version: "1.0".to_string(),
+                    authors: vec!["x".to_string()],
+                    description: None,
+                    url: Some("javascript:alert(1)".to_string()),
+                    loader: "fabric".to_string(),
+                    dependencies: vec![],
+                    icon: None,
+                }),
+            },
+            InstalledMod {
+                file_name: "off.jar.disabled".to_string(),
+                enabled: false,
+                metadata: None,
+            },
+        ];
+        let markdown = export_list("Pack", &mods, "markdown").unwrap();
+        assert!(markdown.contains("| A <b>\\| | 1.0 | x |"));
+        assert!(!markdown.contains("off.jar"));
+        let html = export_list("Pack", &mods, "html").unwrap();
+        assert!(html.contains("<td>A &lt;b&gt;|</td>"));
+        assert!(!html.contains("javascript:"));
+        let json: serde_json::Value =
+            serde_json::from_str(&export_list("Pack", &mods, "json").unwrap()).unwrap();
+        assert_eq!(json["mods"].as_array().unwrap().len(), 1);
+        assert!(export_list("Pack", &mods, "pdf").is_err());
+    }
+}
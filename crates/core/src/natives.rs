@@ -0,0 +1,482 @@
+//! Native library classifier selection.
+//!
+//! Libraries with platform natives list them under `downloads.classifiers`
+//! (`natives-windows`, `natives-linux-arm64`, ...). Older manifests also map
+//! each OS to a classifier in `natives`, with `${arch}` standing for the
+//! pointer width. Candidates are tried from most to least specific so an
+//! ARM machine gets ARM natives when they exist and falls back to the
+//! generic (x86_64) ones otherwise.
+//!
+//! Since LWJGL 3.3, version JSONs list each native jar as a library of its
+//! own (`org.lwjgl:lwjgl:3.3.1:natives-windows-arm64`) gated only by an
+//! `os.name` rule, so the jars of every architecture pass the rules.
+//! [`crate::rules::explain_library`] keeps the best fitting one per artifact
+//! using the same candidate order.
+//!
+//! Each launch extracts its natives into its own `natives-<uuid>` directory
+//! (see [`create_launch_dir`]) so concurrent games of the same version never
+//! share or wipe each other's libraries.
+
+use super::game_version::{DownloadArtifact, GameVersion, Library};
+use super::long_path::long_path;
+use serde::{Deserialize, Serialize};
+use std::io::Read;
+use std::path::{Path, PathBuf};
+use ts_rs::TS;
+
+/// Payload of the `natives-classifier-missing` event
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export, export_to = "dropout-core/natives.ts")]
+pub struct NativesWarning {
+    pub library: String,
+    pub tried: Vec<String>,
+    pub available: Vec<String>,
+}
+
+/// OS key used by the `natives` map and `os.name` rules
+pub fn os_key(os: &str) -> &str {
+    match os {
+        "macos" => "osx",
+        other => other,
+    }
+}
+
+/// Architecture suffixes used by classifiers for `std::env::consts::ARCH`.
+/// LWJGL 3.3.3+ publishes Linux ARM natives as `aarch_64`.
+fn arch_suffixes(arch: &str) -> &'static [&'static str] {
+    match arch {
+        "aarch64" => &["arm64", "aarch_64"],
+        "arm" => &["arm32"],
+        "x86" => &["x86"],
+        "riscv64" => &["riscv64"],
+        "powerpc64" => &["ppc64le"],
+        _ => &[],
+    }
+}
+
+/// Classifier keys to try, most specific first
+pub fn classifier_candidates(
+    os: &str,
+    arch: &str,
+    natives_map: Option<&serde_json::Value>,
+) -> Vec<String> {
+    let mut candidates = Vec::new();
+    let mut push = |key: String| {
+        if !candidates.contains(&key) {
+            candidates.push(key);
+        }
+    };
+
+    let bases: &[&str] = match os {
+        "windows" => &["natives-windows"],
+        "linux" => &["natives-linux"],
+        "macos" => &["natives-macos", "natives-osx"],
+        _ => &[],
+    };
+
+    for suffix in arch_suffixes(arch) {
+        for base in bases {
+            push(format!("{}-{}", base, suffix));
+        }
+    }
+    for base in bases {
+        push(format!("{}-{}", base, arch));
+    }
+
+    // Legacy `natives` map, e.g. "windows": "natives-windows-${arch}"
+    if let Some(mapped) = natives_map
+        .and_then(|map| map.get(os_key(os)))
+        .and_then(|v| v.as_str())
+    {
+        let bits = if arch.contains("64") { "64" } else { "32" };
+        push(mapped.replace("${arch}", bits));
+    }
+
+    for base in bases {
+        push(base.to_string());
+    }
+    candidates
+}
+
+/// Split a rule-gated native artifact such as
+/// `org.lwjgl:lwjgl:3.3.1:natives-linux-arm64` into its coordinates without
+/// the classifier and the classifier. `None` for any other library.
+pub fn split_native_artifact(name: &str) -> Option<(&str, &str)> {
+    let (coordinates, classifier) = name.rsplit_once(':')?;
+    (coordinates.matches(':').count() == 2 && classifier.starts_with("natives-"))
+        .then_some((coordinates, classifier))
+}
+
+/// How well a native classifier fits `os`/`arch`, lower is better. `None`
+/// for classifiers of another OS or architecture.
+pub fn classifier_rank(classifier: &str, os: &str, arch: &str) -> Option<usize> {
+    classifier_candidates(os, arch, None)
+        .iter()
+        .position(|c| c == classifier)
+}
+
+/// Pick the native artifact for this platform.
+///
+/// `Ok(None)` means the library intentionally has no natives for this OS;
+/// `Err` carries the warning to report when natives exist but none fit.
+pub fn select_native(
+    lib: &Library,
+    classifiers: &serde_json::Value,
+    override_classifier: Option<&str>,
+) -> Result<Option<(String, DownloadArtifact)>, NativesWarning> {
+    let os = std::env::consts::OS;
+    let mut candidates = Vec::new();
+    if let Some(forced) = override_classifier.filter(|c| !c.is_empty()) {
+        candidates.push(forced.to_string());
+    }
+    candidates.extend(classifier_candidates(
+        os,
+        std::env::consts::ARCH,
+        lib.natives.as_ref(),
+    ));
+
+    for key in &candidates {
+        if let Some(artifact) = classifiers
+            .get(key)
+            .and_then(|v| serde_json::from_value::<DownloadArtifact>(v.clone()).ok())
+        {
+            return Ok(Some((key.clone(), artifact)));
+        }
+    }
+
+    // A `natives` map without this OS means there is nothing to extract here
+    if lib
+        .natives
+        .as_ref()
+        .is_some_and(|map| map.get(os_key(os)).is_none())
+    {
+        return Ok(None);
+    }
+
+    Err(NativesWarning {
+        library: lib.name.clone(),
+        tried: candidates,
+        available: classifiers
+            .as_object()
+            .map(|map| map.keys().cloned().collect())
+            .unwrap_or_default(),
+    })
+}
+
+/// Archive path prefixes to skip when extracting `lib`'s natives. Libraries
+/// without `extract` rules still skip signature files.
+pub fn extract_excludes(lib: &Library) -> Vec<String> {
+    lib.extract
+        .as_ref()
+        .and_then(|e| e.exclude.clone())
+        .unwrap_or_else(|| vec!["META-INF/".to_string()])
+}
+
+/// Extract a natives jar into `dest`, skipping entries under `excludes`
+pub fn extract_natives(jar: &Path, dest: &Path, excludes: &[String]) -> Result<(), String> {
+    let file = std::fs::File::open(long_path(jar))
+        .map_err(|e| format!("Failed to open natives {}: {}", jar.display(), e))?;
+    let mut archive =
+        zip::ZipArchive::new(file).map_err(|e| format!("Failed to read zip: {}", e))?;
+
+    for i in 0..archive.len() {
+        let mut entry = archive
+            .by_index(i)
+            .map_err(|e| format!("Failed to read zip entry: {}", e))?;
+        if excludes
+            .iter()
+            .any(|prefix| entry.name().starts_with(prefix.as_str()))
+        {
+            continue;
+        }
+        let Some(relative) = entry.enclosed_name() else {
+            continue;
+        };
+        let out = long_path(&dest.join(relative));
+        if entry.is_dir() {
+            std::fs::create_dir_all(&out).map_err(|e| format!("Failed to create dir: {}", e))?;
+            continue;
+        }
+        if let Some(parent) = out.parent() {
+            std::fs::create_dir_all(parent).map_err(|e| format!("Failed to create dir: {}", e))?;
+        }
+        let mut data = Vec::new();
+        entry
+            .read_to_end(&mut data)
+            .map_err(|e| format!("Failed to read {}: {}", entry.name(), e))?;
+        std::fs::write(&out, data).map_err(|e| format!("Failed to write file: {}", e))?;
+    }
+    Ok(())
+}
+
+/// Whether the version ships LWJGL 2 (group `org.lwjgl.lwjgl`), which looks
+/// up its natives through `org.lwjgl.librarypath`
+pub fn uses_lwjgl2(version: &GameVersion) -> bool {
+    version
+        .libraries
+        .iter()
+        .any(|lib| lib.name.starts_with("org.lwjgl.lwjgl:"))
+}
+
+/// Native library path properties missing from `jvm_args`
+pub fn library_path_args(
+    version: &GameVersion,
+    natives_dir: &Path,
+    jvm_args: &[String],
+) -> Vec<String> {
+    let natives = natives_dir.to_string_lossy();
+    let mut properties = vec!["-Djava.library.path"];
+    if uses_lwjgl2(version) {
+        properties.push("-Dorg.lwjgl.librarypath");
+    }
+    properties
+        .into_iter()
+        .filter(|key| !jvm_args.iter().any(|a| a.starts_with(&format!("{}=", key))))
+        .map(|key| format!("{}={}", key, natives))
+        .collect()
+}
+
+const LAUNCH_DIR_PREFIX: &str = "natives-";
+
+/// Create a fresh, empty natives directory for one launch under `root`
+pub fn create_launch_dir(root: &Path) -> std::io::Result<PathBuf> {
+    let dir = root.join(format!(
+        "{}{}",
+        LAUNCH_DIR_PREFIX,
+        uuid::Uuid::new_v4().simple()
+    ));
+    std::fs::create_dir_all(&dir)?;
+    Ok(dir)
+}
+
+/// Remove a launch's natives directory once the game has exited
+pub fn remove_launch_dir(dir: &Path) {
+    if let Err(e) = std::fs::remove_dir_all(dir) {
+        eprintln!("[Natives] Failed to remove {}: {}", dir.display(), e);
+    }
+}
+
+const OWNER_FILE: &str = ".owner";
+
+/// Record the game process using a launch directory, so [`sweep_stale`]
+/// leaves it alone while the game runs
+pub fn mark_owner(dir: &Path, pid: u32) {
+    if let Err(e) = std::fs::write(dir.join(OWNER_FILE), pid.to_string()) {
+        eprintln!(
+            "[Natives] Failed to record owner of {}: {}",
+            dir.display(),
+            e
+        );
+    }
+}
+
+/// Whether a process with `pid` is running
+pub fn process_alive(pid: u32) -> bool {
+    #[cfg(target_os = "windows")]
+    {
+        use std::os::windows::process::CommandExt;
+        const CREATE_NO_WINDOW: u32 = 0x08000000;
+        std::process::Command::new("tasklist")
+            .args(["/FI", &format!("PID eq {}", pid), "/NH", "/FO", "CSV"])
+            .creation_flags(CREATE_NO_WINDOW)
+            .output()
+            .is_ok_and(|o| String::from_utf8_lossy(&o.stdout).contains(&format!("\"{}\"", pid)))
+    }
+    #[cfg(not(target_os = "windows"))]
+    {
+        std::process::Command::new("kill")
+            .args(["-0", &pid.to_string()])
+            .stderr(std::process::Stdio::null())
+            .status()
+            .is_ok_and(|s| s.success())
+    }
+}
+
+fn owner_running(dir: &Path) -> bool {
+    std::fs::read_to_string(dir.join(OWNER_FILE))
+        .ok()
+        .and_then(|pid| pid.trim().parse().ok())
+        .is_some_and(process_alive)
+}
+
+/// Remove leftover launch directories under `root`, e.g. after a crash.
+///
+/// Meant to run at startup. Directories whose game (see [`mark_owner`]) is
+/// still running are kept for a later sweep. Returns the number of
+/// directories removed.
+pub fn sweep_stale(root: &Path) -> usize {
+    let Ok(entries) = std::fs::read_dir(root) else {
+        return 0;
+    };
+    entries
+        .flatten()
+        .filter(|e| {
+            e.file_name()
+                .to_string_lossy()
+                .starts_with(LAUNCH_DIR_PREFIX)
+        })
+        .filter(|e| e.path().is_dir() && !owner_running(&e.path()))
+        .filter(|e| std::fs::remove_dir_all(e.path()).is_ok())
+        .count()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_windows_arm64_candidates() {
+        let candidates = classifier_candidates("windows", "aarch64", None);
+        assert_eq!(candidates[0], "natives-windows-arm64");
+        assert_eq!(candidates.last().unwrap(), "natives-windows");
+    }
+
+    #[test]
+    fn test_linux_x86_64_candidates() {
+        let candidates = classifier_candidates("linux", "x86_64", None);
+        assert_eq!(candidates, vec!["natives-linux-x86_64", "natives-linux"]);
+    }
+
+    #[test]
+    fn test_legacy_natives_map() {
+        let map = serde_json::json!({"windows": "natives-windows-${arch}"});
+        let candidates = classifier_candidates("windows", "x86_64", Some(&map));
+        assert!(candidates.contains(&"natives-windows-64".to_string()));
+        let candidates = classifier_candidates("windows", "x86", Some(&map));
+        assert!(candidates.contains(&"natives-windows-32".to_string()));
+    }
+
+    #[test]
+    fn test_macos_arm64_prefers_native() {
+        let candidates = classifier_candidates("macos", "aarch64", None);
+        assert_eq!(candidates[0], "natives-macos-arm64");
+        assert!(candidates.contains(&"natives-osx".to_string()));
+    }
+
+    #[test]
+    fn test_linux_aarch64_candidates() {
+        let candidates = classifier_candidates("linux", "aarch64", None);
+        assert_eq!(
+            candidates,
+            vec![
+                "natives-linux-arm64",
+                "natives-linux-aarch_64",
+                "natives-linux-aarch64",
+                "natives-linux"
+            ]
+        );
+    }
+
+    #[test]
+    fn test_split_native_artifact() {
+        assert_eq!(
+            split_native_artifact("org.lwjgl:lwjgl:3.3.3:natives-linux-aarch_64"),
+            Some(("org.lwjgl:lwjgl:3.3.3", "natives-linux-aarch_64"))
+        );
+        assert_eq!(split_native_artifact("org.lwjgl:lwjgl:3.3.3"), None);
+        assert_eq!(
+            split_native_artifact("com.mojang:text2speech:1.17.9:sources"),
+            None
+        );
+        assert_eq!(
+            classifier_rank("natives-windows-x86", "windows", "x86_64"),
+            None
+        );
+        assert_eq!(
+            classifier_rank("natives-windows", "windows", "x86_64"),
+            Some(1)
+        );
+    }
+
+    #[test]
+    fn test_launch_dirs_and_sweep() {
+        let root = std::env::temp_dir().join(format!("dropout-natives-{}", uuid::Uuid::new_v4()));
+        let first = create_launch_dir(&root).unwrap();
+        let second = create_launch_dir(&root).unwrap();
+        assert_ne!(first, second);
+        std::fs::write(first.join("liblwjgl.so"), b"").unwrap();
+        std::fs::create_dir_all(root.join("unrelated")).unwrap();
+
+        let live = create_launch_dir(&root).unwrap();
+        mark_owner(&live, std::process::id());
+
+        assert_eq!(sweep_stale(&root), 2);
+        assert!(!first.exists() && !second.exists());
+        assert!(live.exists());
+        assert!(root.join("unrelated").exists());
+        std::fs::remove_dir_all(&root).unwrap();
+    }
+
+    fn fixture(json: &str) -> GameVersion {
+        serde_json::from_str(json).unwrap()
+    }
+
+    #[test]
+    fn test_lwjgl2_library_path() {
+        let natives = Path::new("natives-x");
+        for json in [
+            include_str!("../tests/fixtures/versions/1.7.10.json"),
+            include_str!("../tests/fixtures/versions/1.8.9.json"),
+        ] {
+            let version = fixture(json);
+            assert!(uses_lwjgl2(&version));
+            let args = library_path_args(&version, natives, &[]);
+            assert_eq!(
+                args,
+                vec![
+                    format!("-Djava.library.path={}", natives.display()),
+                    format!("-Dorg.lwjgl.librarypath={}", natives.display()),
+                ]
+            );
+            // Already present properties aren't duplicated
+            assert_eq!(library_path_args(&version, natives, &args).len(), 0);
+        }
+
+        let modern = fixture(include_str!("../tests/fixtures/versions/1.13.2.json"));
+        assert!(!uses_lwjgl2(&modern));
+        assert_eq!(library_path_args(&modern, natives, &[]).len(), 1);
+    }
+
+    #[test]
+    fn test_extract_excludes() {
+        let version = fixture(include_str!("../tests/fixtures/versions/1.7.10.json"));
+        let platform = version
+            .libraries
+            .iter()
+            .find(|l| l.name.starts_with("org.lwjgl.lwjgl:lwjgl-platform:"))
+            .unwrap();
+        assert_eq!(extract_excludes(platform), vec!["META-INF/"]);
+
+        let dir = std::env::temp_dir().join(format!("dropout-extract-{}", uuid::Uuid::new_v4()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let jar = dir.join("natives.jar");
+        {
+            let mut zip = zip::ZipWriter::new(std::fs::File::create(&jar).unwrap());
+            let options = zip::write::SimpleFileOptions::default();
+            for name in [
+                "META-INF/MANIFEST.MF",
+                "liblwjgl64.so",
+                "sub/libopenal64.so",
+            ] {
+                zip.start_file(name, options).unwrap();
+                std::io::Write::write_all(&mut zip, b"x").unwrap();
+            }
+            zip.finish().unwrap();
+        }
+        let out = dir.join("out");
+        extract_natives(&jar, &out, &extract_excludes(platform)).unwrap();
+        assert!(out.join("liblwjgl64.so").exists());
+        assert!(out.join("sub/libopenal64.so").exists());
+        assert!(!out.join("META-INF").exists());
+
+        // Destinations past MAX_PATH
+        let mut deep = dir.join("natives");
+        while deep.as_os_str().len() <= 300 {
+            deep.push("a-fairly-long-directory-name");
+        }
+        extract_natives(&jar, &deep, &extract_excludes(platform)).unwrap();
+        assert!(long_path(&deep.join("sub/libopenal64.so")).exists());
+        std::fs::remove_dir_all(long_path(&dir)).unwrap();
+    }
+}
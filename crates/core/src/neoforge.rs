@@ -0,0 +1,372 @@
+//! NeoForge Loader support module.
+//!
+//! This module provides functionality to:
+//! - Fetch available NeoForge versions from the NeoForged Maven
+//! - Install NeoForge loader for a specific Minecraft version
+//!
+//! NeoForge versions encode the Minecraft version they target: `21.1.77` is
+//! for 1.21.1 and `21.0.167` for 1.21. The 1.20.1 releases predate the rename
+//! and are published as `net.neoforged:forge:1.20.1-47.1.106`. The installer
+//! itself uses the same `install_profile.json` format as Forge, so it runs
+//! through [`crate::forge::processors`].
+
+use serde::{Deserialize, Serialize};
+use std::error::Error;
+#[cfg(target_os = "windows")]
+use std::os::windows::process::CommandExt;
+use std::path::PathBuf;
+use ts_rs::TS;
+
+use crate::maven::NEOFORGE_MAVEN;
+
+const NEOFORGE_METADATA_URL: &str =
+    "https://maven.neoforged.net/releases/net/neoforged/neoforge/maven-metadata.xml";
+const LEGACY_METADATA_URL: &str =
+    "https://maven.neoforged.net/releases/net/neoforged/forge/maven-metadata.xml";
+
+/// The only Minecraft version published under the legacy `forge` artifact
+const LEGACY_GAME_VERSION: &str = "1.20.1";
+
+/// Represents a NeoForge version entry.
+#[derive(Debug, Deserialize, Serialize, Clone, PartialEq, Eq, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export, export_to = "dropout-core/neoforge.ts")]
+pub struct NeoForgeVersion {
+    pub version: String,
+    pub minecraft_version: String,
+    #[serde(default)]
+    pub beta: bool,
+    #[serde(default)]
+    pub latest: bool,
+}
+
+/// Information about an installed NeoForge version.
+#[derive(Debug, Serialize, Clone, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export, export_to = "dropout-core/neoforge.ts")]
+pub struct InstalledNeoForgeVersion {
+    pub id: String,
+    pub minecraft_version: String,
+    pub neoforge_version: String,
+    #[ts(type = "string")]
+    pub path: PathBuf,
+}
+
+/// The `<version>` entries of a `maven-metadata.xml`, in publishing order
+fn parse_metadata_versions(xml: &str) -> Vec<String> {
+    xml.split("<version>")
+        .skip(1)
+        .filter_map(|part| part.split_once("</version>"))
+        .map(|(version, _)| version.trim().to_string())
+        .filter(|version| !version.is_empty())
+        .collect()
+}
+
+/// Minecraft version a NeoForge version is built for: "21.1.77" -> "1.21.1",
+/// "20.4.237-beta" -> "1.20.4", "21.0.167" -> "1.21". Versions following the
+/// year-based game versioning carry one more component: "26.1.0.5" -> "26.1".
+/// `None` for special builds (April Fools snapshots are "0.25w14craftmine.3").
+pub fn game_version_of(neoforge_version: &str) -> Option<String> {
+    let numbers: Vec<u32> = neoforge_version
+        .split('-')
+        .next()?
+        .split('.')
+        .map(|n| n.parse().ok())
+        .collect::<Option<_>>()?;
+    let with_patch = |base: String, patch: u32| {
+        if patch == 0 {
+            base
+        } else {
+            format!("{}.{}", base, patch)
+        }
+    };
+    match numbers.as_slice() {
+        [major, minor, _] if (20..26).contains(major) => {
+            Some(with_patch(format!("1.{}", major), *minor))
+        }
+        [major, minor, patch, _] if *major >= 26 => {
+            Some(with_patch(format!("{}.{}", major, minor), *patch))
+        }
+        _ => None,
+    }
+}
+
+/// Split a legacy `forge` artifact version ("1.20.1-47.1.106") into the
+/// Minecraft version and the NeoForge version
+fn split_legacy_version(version: &str) -> Option<(&str, &str)> {
+    version
+        .split_once('-')
+        .filter(|(game_version, _)| game_version.starts_with("1."))
+}
+
+async fn fetch_metadata_versions(url: &str) -> Result<Vec<String>, Box<dyn Error + Send + Sync>> {
+    let xml = reqwest::get(url).await?.error_for_status()?.text().await?;
+    Ok(parse_metadata_versions(&xml))
+}
+
+/// All NeoForge versions, newest first
+async fn fetch_all_versions() -> Result<Vec<NeoForgeVersion>, Box<dyn Error + Send + Sync>> {
+    let mut versions: Vec<NeoForgeVersion> = fetch_metadata_versions(NEOFORGE_METADATA_URL)
+        .await?
+        .into_iter()
+        .filter_map(|version| {
+            Some(NeoForgeVersion {
+                minecraft_version: game_version_of(&version)?,
+                beta: version.contains("-beta") || version.contains("-alpha"),
+                latest: false,
+                version,
+            })
+        })
+        .collect();
+
+    // The legacy artifact is optional: a failure there shouldn't hide the
+    // current releases
+    if let Ok(legacy) = fetch_metadata_versions(LEGACY_METADATA_URL).await {
+        versions.splice(
+            0..0,
+            legacy.iter().filter_map(|version| {
+                let (game_version, version) = split_legacy_version(version)?;
+                Some(NeoForgeVersion {
+                    version: version.to_string(),
+                    minecraft_version: game_version.to_string(),
+                    beta: false,
+                    latest: false,
+                })
+            }),
+        );
+    }
+
+    versions.reverse(); // Newest first
+    Ok(versions)
+}
+
+/// Fetch all Minecraft versions supported by NeoForge.
+///
+/// # Returns
+/// A list of Minecraft version strings that have NeoForge available, newest first.
+pub async fn fetch_supported_game_versions() -> Result<Vec<String>, Box<dyn Error + Send + Sync>> {
+    let mut game_versions: Vec<String> = Vec::new();
+    for version in fetch_all_versions().await? {
+        if !game_versions.contains(&version.minecraft_version) {
+            game_versions.push(version.minecraft_version);
+        }
+    }
+    Ok(game_versions)
+}
+
+/// Fetch available NeoForge versions for a specific Minecraft version.
+///
+/// # Arguments
+/// * `game_version` - The Minecraft version (e.g., "1.21.1")
+///
+/// # Returns
+/// A list of NeoForge versions for the game version, newest first. The newest
+/// stable one (or the newest one, if all are betas) is marked `latest`.
+pub async fn fetch_neoforge_versions(
+    game_version: &str,
+) -> Result<Vec<NeoForgeVersion>, Box<dyn Error + Send + Sync>> {
+    let mut versions: Vec<NeoForgeVersion> = fetch_all_versions()
+        .await?
+        .into_iter()
+        .filter(|v| v.minecraft_version == game_version)
+        .collect();
+    let latest = versions
+        .iter()
+        .position(|v| !v.beta)
+        .or((!versions.is_empty()).then_some(0));
+    if let Some(index) = latest {
+        versions[index].latest = true;
+    }
+    Ok(versions)
+}
+
+/// Generate the version ID for a NeoForge installation.
+///
+/// # Arguments
+/// * `game_version` - The Minecraft version
+/// * `neoforge_version` - The NeoForge version
+///
+/// # Returns
+/// The version ID string (e.g., "1.21.1-neoforge-21.1.77")
+pub fn generate_version_id(game_version: &str, neoforge_version: &str) -> String {
+    format!("{}-neoforge-{}", game_version, neoforge_version)
+}
+
+/// Maven URL of the installer jar
+pub fn installer_url(game_version: &str, neoforge_version: &str) -> String {
+    if game_version == LEGACY_GAME_VERSION {
+        let full = format!("{}-{}", game_version, neoforge_version);
+        format!(
+            "{}net/neoforged/forge/{}/forge-{}-installer.jar",
+            NEOFORGE_MAVEN, full, full
+        )
+    } else {
+        format!(
+            "{}net/neoforged/neoforge/{}/neoforge-{}-installer.jar",
+            NEOFORGE_MAVEN, neoforge_version, neoforge_version
+        )
+    }
+}
+
+async fn fetch_installer(
+    game_version: &str,
+    neoforge_version: &str,
+) -> Result<bytes::Bytes, Box<dyn Error + Send + Sync>> {
+    let url = installer_url(game_version, neoforge_version);
+    let response = reqwest::get(&url).await?;
+    if !response.status().is_success() {
+        return Err(format!(
+            "Failed to download NeoForge installer: HTTP {}: {}",
+            response.status(),
+            url
+        )
+        .into());
+    }
+    Ok(response.bytes().await?)
+}
+
+/// Download the installer jar to `dest` (used by the native processor pipeline)
+pub async fn download_installer(
+    game_version: &str,
+    neoforge_version: &str,
+    dest: &std::path::Path,
+) -> Result<(), Box<dyn Error + Send + Sync>> {
+    let bytes = fetch_installer(game_version, neoforge_version).await?;
+    if let Some(parent) = dest.parent() {
+        tokio::fs::create_dir_all(parent).await?;
+    }
+    tokio::fs::write(dest, &bytes).await?;
+    Ok(())
+}
+
+/// Write the version JSON for a NeoForge installation.
+///
+/// The installer's own `version.json` is used as it is, under the launcher's
+/// version ID; the libraries it references are produced by the installer's
+/// processors, which must have run before launching.
+///
+/// # Arguments
+/// * `game_dir` - The .minecraft directory path
+/// * `game_version` - The Minecraft version (e.g., "1.21.1")
+/// * `neoforge_version` - The NeoForge version (e.g., "21.1.77")
+///
+/// # Returns
+/// Information about the installed version.
+pub async fn install_neoforge(
+    game_dir: &std::path::Path,
+    game_version: &str,
+    neoforge_version: &str,
+) -> Result<InstalledNeoForgeVersion, Box<dyn Error + Send + Sync>> {
+    let version_id = generate_version_id(game_version, neoforge_version);
+
+    let bytes = fetch_installer(game_version, neoforge_version).await?;
+    let cursor = std::io::Cursor::new(bytes.as_ref());
+    let mut archive = zip::ZipArchive::new(cursor)?;
+    let mut version_json: serde_json::Value =
+        serde_json::from_reader(archive.by_name("version.json")?)?;
+    version_json["id"] = serde_json::Value::String(version_id.clone());
+
+    let version_dir = game_dir.join("versions").join(&version_id);
+    tokio::fs::create_dir_all(&version_dir).await?;
+    let json_path = version_dir.join(format!("{}.json", version_id));
+    tokio::fs::write(&json_path, serde_json::to_string_pretty(&version_json)?).await?;
+
+    Ok(InstalledNeoForgeVersion {
+        id: version_id,
+        minecraft_version: game_version.to_string(),
+        neoforge_version: neoforge_version.to_string(),
+        path: json_path,
+    })
+}
+
+/// Install NeoForge using the official installer JAR in headless mode.
+///
+/// # Arguments
+/// * `game_dir` - The .minecraft directory path
+/// * `game_version` - The Minecraft version
+/// * `neoforge_version` - The NeoForge version
+/// * `java_path` - Path to the Java executable
+///
+/// # Returns
+/// Result indicating success or failure
+pub async fn run_neoforge_installer(
+    game_dir: &std::path::Path,
+    game_version: &str,
+    neoforge_version: &str,
+    java_path: &std::path::Path,
+) -> Result<(), Box<dyn Error + Send + Sync>> {
+    let installer_path = game_dir.join("neoforge-installer.jar");
+    download_installer(game_version, neoforge_version, &installer_path).await?;
+
+    // Like Forge's, the installer refuses to run without a launcher profile
+    let profiles_path = game_dir.join("launcher_profiles.json");
+    if !profiles_path.exists() {
+        tokio::fs::write(&profiles_path, r#"{"profiles": {}}"#).await?;
+    }
+
+    let mut cmd = tokio::process::Command::new(java_path);
+    cmd.arg("-jar")
+        .arg(&installer_path)
+        .arg("--installClient")
+        .arg(game_dir);
+
+    #[cfg(target_os = "windows")]
+    cmd.creation_flags(0x08000000);
+
+    let output = cmd.output().await?;
+
+    let _ = tokio::fs::remove_file(&installer_path).await;
+
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        let stdout = String::from_utf8_lossy(&output.stdout);
+        return Err(format!(
+            "NeoForge installer failed:\nstdout: {}\nstderr: {}",
+            stdout, stderr
+        )
+        .into());
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_game_version_of() {
+        assert_eq!(game_version_of("21.1.77").as_deref(), Some("1.21.1"));
+        assert_eq!(game_version_of("21.0.167").as_deref(), Some("1.21"));
+        assert_eq!(game_version_of("20.4.237-beta").as_deref(), Some("1.20.4"));
+        assert_eq!(game_version_of("26.1.0.5-beta").as_deref(), Some("26.1"));
+        assert_eq!(game_version_of("26.1.2.1").as_deref(), Some("26.1.2"));
+        assert_eq!(game_version_of("0.25w14craftmine.3-beta"), None);
+        assert_eq!(
+            split_legacy_version("1.20.1-47.1.106"),
+            Some(("1.20.1", "47.1.106"))
+        );
+        assert_eq!(split_legacy_version("47.1.3"), None);
+    }
+
+    #[test]
+    fn test_parse_metadata_and_urls() {
+        let xml = r#"<metadata><versioning><latest>21.1.77</latest>
+            <versions><version>20.2.3-beta</version><version>21.1.77</version></versions>
+            </versioning></metadata>"#;
+        assert_eq!(parse_metadata_versions(xml), vec!["20.2.3-beta", "21.1.77"]);
+
+        assert_eq!(
+            generate_version_id("1.21.1", "21.1.77"),
+            "1.21.1-neoforge-21.1.77"
+        );
+        assert_eq!(
+            installer_url("1.21.1", "21.1.77"),
+            "https://maven.neoforged.net/releases/net/neoforged/neoforge/21.1.77/neoforge-21.1.77-installer.jar"
+        );
+        assert_eq!(
+            installer_url("1.20.1", "47.1.106"),
+            "https://maven.neoforged.net/releases/net/neoforged/forge/1.20.1-47.1.106/forge-1.20.1-47.1.106-installer.jar"
+        );
+    }
+}
@@ -0,0 +1,410 @@
+//! OptiFine support module.
+//!
+//! This module provides functionality to:
+//! - Scrape the available OptiFine releases from optifine.net
+//! - Resolve the (ad-gated) download link of a release
+//! - Install OptiFine as a version inheriting from vanilla
+//!
+//! The OptiFine jar's own installer is a GUI, so it isn't run. Instead its
+//! `optifine.Patcher` class builds the patched library jar from the vanilla
+//! client (older releases ship the library jar as it is), and OptiFine is
+//! loaded through LaunchWrapper with `optifine.OptiFineTweaker`. On Forge,
+//! OptiFine is a regular mod and is just placed in `mods/`.
+
+use serde::{Deserialize, Serialize};
+use std::error::Error;
+use std::io::Read;
+#[cfg(target_os = "windows")]
+use std::os::windows::process::CommandExt;
+use std::path::{Path, PathBuf};
+use ts_rs::TS;
+
+use crate::downloader::compute_sha1;
+
+const OPTIFINE_URL: &str = "https://optifine.net/";
+const TWEAK_CLASS: &str = "optifine.OptiFineTweaker";
+/// LaunchWrapper for releases that don't bundle their own
+const LAUNCHWRAPPER: &str = "net.minecraft:launchwrapper:1.12";
+
+/// Represents an OptiFine release.
+#[derive(Debug, Deserialize, Serialize, Clone, PartialEq, Eq, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export, export_to = "dropout-core/optifine.ts")]
+pub struct OptiFineVersion {
+    pub file_name: String,         // e.g. "OptiFine_1.20.1_HD_U_I6.jar"
+    pub minecraft_version: String, // e.g. "1.20.1"
+    pub edition: String,           // e.g. "HD_U_I6"
+    pub forge: Option<String>,     // Forge version it was tested with, e.g. "Forge 47.1.0"
+}
+
+/// Information about an installed OptiFine version.
+#[derive(Debug, Serialize, Clone, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export, export_to = "dropout-core/optifine.ts")]
+pub struct InstalledOptiFineVersion {
+    pub id: String,
+    pub minecraft_version: String,
+    pub edition: String,
+    #[ts(type = "string")]
+    pub path: PathBuf,
+}
+
+/// File name of a release on optifine.net: "OptiFine_1.20.1_HD_U_I6.jar"
+pub fn release_file_name(game_version: &str, edition: &str) -> String {
+    format!("OptiFine_{}_{}.jar", game_version, edition)
+}
+
+/// Split "OptiFine_1.20.1_HD_U_I6.jar" into ("1.20.1", "HD_U_I6")
+fn parse_file_name(file_name: &str) -> Option<(String, String)> {
+    let stem = file_name.strip_prefix("OptiFine_")?.strip_suffix(".jar")?;
+    let (game_version, edition) = stem.split_once("_HD_")?;
+    Some((game_version.to_string(), format!("HD_{}", edition)))
+}
+
+/// Text of the first `<td class='...class_name...'>` cell in `row`
+fn cell_text<'a>(row: &'a str, class_name: &str) -> Option<&'a str> {
+    let start = row.find(class_name)?;
+    let content = &row[start..];
+    let content = &content[content.find('>')? + 1..];
+    let text = content[..content.find('<')?].trim();
+    (!text.is_empty()).then_some(text)
+}
+
+/// Releases listed on the optifine.net downloads page, in page order (newest first)
+pub fn parse_downloads_page(html: &str) -> Vec<OptiFineVersion> {
+    html.split("downloadLine")
+        .skip(1)
+        .filter_map(|row| {
+            let start = row.find("adloadx?f=")? + "adloadx?f=".len();
+            let file_name = &row[start..];
+            let file_name = &file_name[..file_name.find(['"', '\'', '&'])?];
+            let (minecraft_version, edition) = parse_file_name(file_name)?;
+            Some(OptiFineVersion {
+                file_name: file_name.to_string(),
+                minecraft_version,
+                edition,
+                forge: cell_text(row, "colForge")
+                    .filter(|forge| forge.starts_with("Forge"))
+                    .map(String::from),
+            })
+        })
+        .collect()
+}
+
+/// The real download link on an `adloadx` page
+pub fn parse_download_link(html: &str) -> Option<String> {
+    let start = html.find("downloadx?f=")?;
+    let link = &html[start..];
+    let link = &link[..link.find(['"', '\''])?];
+    Some(format!("{}{}", OPTIFINE_URL, link.replace("&amp;", "&")))
+}
+
+/// Fetch all OptiFine releases.
+pub async fn fetch_versions() -> Result<Vec<OptiFineVersion>, Box<dyn Error + Send + Sync>> {
+    let html = reqwest::get(format!("{}downloads", OPTIFINE_URL))
+        .await?
+        .error_for_status()?
+        .text()
+        .await?;
+    Ok(parse_downloads_page(&html))
+}
+
+/// Fetch the OptiFine releases for a specific Minecraft version, newest first.
+///
+/// # Arguments
+/// * `game_version` - The Minecraft version (e.g., "1.20.1")
+pub async fn fetch_versions_for_game(
+    game_version: &str,
+) -> Result<Vec<OptiFineVersion>, Box<dyn Error + Send + Sync>> {
+    Ok(fetch_versions()
+        .await?
+        .into_iter()
+        .filter(|v| v.minecraft_version == game_version)
+        .collect())
+}
+
+/// Generate the version ID for an OptiFine installation, as OptiFine's own
+/// installer names it.
+///
+/// # Returns
+/// The version ID string (e.g., "1.20.1-OptiFine_HD_U_I6")
+pub fn generate_version_id(game_version: &str, edition: &str) -> String {
+    format!("{}-OptiFine_{}", game_version, edition)
+}
+
+/// Download the OptiFine jar `file_name` to `dest`.
+pub async fn download(file_name: &str, dest: &Path) -> Result<(), Box<dyn Error + Send + Sync>> {
+    let client = reqwest::Client::new();
+    let page = client
+        .get(format!("{}adloadx?f={}", OPTIFINE_URL, file_name))
+        .send()
+        .await?
+        .error_for_status()?
+        .text()
+        .await?;
+    let url = parse_download_link(&page)
+        .ok_or_else(|| format!("No download link for {} on optifine.net", file_name))?;
+    let bytes = client
+        .get(&url)
+        .send()
+        .await?
+        .error_for_status()?
+        .bytes()
+        .await?;
+    if bytes.len() < 4 || &bytes[..4] != b"PK\x03\x04" {
+        return Err(format!("Download of {} is not a jar", file_name).into());
+    }
+    if let Some(parent) = dest.parent() {
+        tokio::fs::create_dir_all(parent).await?;
+    }
+    tokio::fs::write(dest, &bytes).await?;
+    Ok(())
+}
+
+fn library_rel_path(group: &str, artifact: &str, version: &str) -> String {
+    format!(
+        "{}/{}/{}/{}-{}.jar",
+        group.replace('.', "/"),
+        artifact,
+        version,
+        artifact,
+        version
+    )
+}
+
+/// Library entry for a jar that exists only locally. The checksum lets the
+/// launcher recognize the file as complete instead of trying to download it.
+fn local_library(name: &str, rel_path: &str, data: &[u8]) -> serde_json::Value {
+    serde_json::json!({
+        "name": name,
+        "downloads": {
+            "artifact": {
+                "path": rel_path,
+                "sha1": compute_sha1(data),
+                "size": data.len(),
+                "url": ""
+            }
+        }
+    })
+}
+
+/// Version JSON of an OptiFine installation. `legacy_arguments` is the
+/// parent's `minecraftArguments` for versions before 1.13, which a child
+/// can only replace, not extend.
+pub fn version_json(
+    game_version: &str,
+    edition: &str,
+    libraries: Vec<serde_json::Value>,
+    legacy_arguments: Option<&str>,
+) -> serde_json::Value {
+    let mut json = serde_json::json!({
+        "id": generate_version_id(game_version, edition),
+        "inheritsFrom": game_version,
+        "type": "release",
+        "mainClass": "net.minecraft.launchwrapper.Launch",
+        "libraries": libraries,
+    });
+    match legacy_arguments {
+        Some(arguments) => {
+            json["minecraftArguments"] =
+                format!("{} --tweakClass {}", arguments, TWEAK_CLASS).into();
+        }
+        None => {
+            json["arguments"] = serde_json::json!({ "game": ["--tweakClass", TWEAK_CLASS] });
+        }
+    }
+    json
+}
+
+fn read_entry(archive: &mut zip::ZipArchive<std::fs::File>, name: &str) -> Option<Vec<u8>> {
+    let mut entry = archive.by_name(name).ok()?;
+    let mut data = Vec::new();
+    entry.read_to_end(&mut data).ok()?;
+    Some(data)
+}
+
+/// Install OptiFine from a downloaded OptiFine jar.
+///
+/// # Arguments
+/// * `game_dir` - The .minecraft directory path (the version JSON goes here)
+/// * `libraries_dir` - The libraries directory the game launches with
+/// * `minecraft_jar` - The vanilla client jar, with its version JSON next to it
+/// * `installer_path` - The downloaded OptiFine jar
+/// * `java_path` - Path to the Java executable
+///
+/// # Returns
+/// Information about the installed version.
+pub async fn install_optifine(
+    game_dir: &Path,
+    libraries_dir: &Path,
+    minecraft_jar: &Path,
+    installer_path: &Path,
+    java_path: &Path,
+) -> Result<InstalledOptiFineVersion, Box<dyn Error + Send + Sync>> {
+    let file_name = installer_path
+        .file_name()
+        .map(|f| f.to_string_lossy().to_string())
+        .unwrap_or_default();
+    let (game_version, edition) = parse_file_name(&file_name)
+        .ok_or_else(|| format!("Not an OptiFine release: {}", file_name))?;
+
+    let mut archive = zip::ZipArchive::new(std::fs::File::open(installer_path)?)?;
+    let has_patcher = archive.by_name("optifine/Patcher.class").is_ok();
+
+    // 1. The OptiFine library, patched against the vanilla client
+    let library_version = format!("{}_{}", game_version, edition);
+    let library_rel = library_rel_path("optifine", "OptiFine", &library_version);
+    let library_path = libraries_dir.join(&library_rel);
+    if let Some(parent) = library_path.parent() {
+        tokio::fs::create_dir_all(parent).await?;
+    }
+    if has_patcher {
+        let mut cmd = tokio::process::Command::new(java_path);
+        cmd.arg("-cp")
+            .arg(installer_path)
+            .arg("optifine.Patcher")
+            .arg(minecraft_jar)
+            .arg(installer_path)
+            .arg(&library_path);
+        #[cfg(target_os = "windows")]
+        cmd.creation_flags(0x08000000);
+        let output = cmd.output().await?;
+        if !output.status.success() {
+            return Err(format!(
+                "OptiFine patcher failed:\nstdout: {}\nstderr: {}",
+                String::from_utf8_lossy(&output.stdout),
+                String::from_utf8_lossy(&output.stderr)
+            )
+            .into());
+        }
+    } else {
+        tokio::fs::copy(installer_path, &library_path).await?;
+    }
+    let library_data = tokio::fs::read(&library_path).await?;
+    let mut libraries = vec![local_library(
+        &format!("optifine:OptiFine:{}", library_version),
+        &library_rel,
+        &library_data,
+    )];
+
+    // 2. LaunchWrapper: newer releases bundle a fork of it
+    let bundled = match read_entry(&mut archive, "launchwrapper-of.txt") {
+        Some(version) => {
+            let version = String::from_utf8_lossy(&version).trim().to_string();
+            let jar = format!("launchwrapper-of-{}.jar", version);
+            read_entry(&mut archive, &jar).map(|data| ("launchwrapper-of", version, data))
+        }
+        None => read_entry(&mut archive, "launchwrapper-2.0.jar")
+            .map(|data| ("launchwrapper", "2.0".to_string(), data)),
+    };
+    match bundled {
+        Some((artifact, version, data)) => {
+            let rel = library_rel_path("optifine", artifact, &version);
+            let path = libraries_dir.join(&rel);
+            if let Some(parent) = path.parent() {
+                tokio::fs::create_dir_all(parent).await?;
+            }
+            tokio::fs::write(&path, &data).await?;
+            libraries.push(local_library(
+                &format!("optifine:{}:{}", artifact, version),
+                &rel,
+                &data,
+            ));
+        }
+        None => libraries.push(serde_json::json!({ "name": LAUNCHWRAPPER })),
+    }
+
+    // 3. The version JSON
+    let parent_json: serde_json::Value = serde_json::from_str(
+        &tokio::fs::read_to_string(minecraft_jar.with_extension("json")).await?,
+    )?;
+    let legacy_arguments = parent_json
+        .get("minecraftArguments")
+        .and_then(|a| a.as_str());
+    let json = version_json(&game_version, &edition, libraries, legacy_arguments);
+
+    let version_id = generate_version_id(&game_version, &edition);
+    let version_dir = game_dir.join("versions").join(&version_id);
+    tokio::fs::create_dir_all(&version_dir).await?;
+    let json_path = version_dir.join(format!("{}.json", version_id));
+    tokio::fs::write(&json_path, serde_json::to_string_pretty(&json)?).await?;
+
+    Ok(InstalledOptiFineVersion {
+        id: version_id,
+        minecraft_version: game_version,
+        edition,
+        path: json_path,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_downloads_page() {
+        let html = r#"
+<tr class='downloadLine downloadLineMain'>
+<td class='colFile'>OptiFine HD U I6</td>
+<td class='colDownload'><a href="http://optifine.net/adloadx?f=OptiFine_1.20.1_HD_U_I6.jar">Download</a></td>
+<td class='colMirror'><a href="http://optifine.net/adloadx?f=OptiFine_1.20.1_HD_U_I6.jar">(Mirror)</a></td>
+<td class='colForge'>Forge 47.1.0</td>
+<td class='colDate'>12.06.2023</td>
+</tr>
+<tr class='downloadLine downloadLineMore'>
+<td class='colFile'>OptiFine HD U E3</td>
+<td class='colDownload'><a href="http://optifine.net/adloadx?f=OptiFine_1.7.10_HD_U_E3.jar">Download</a></td>
+<td class='colForge'>n/a</td>
+</tr>"#;
+        let versions = parse_downloads_page(html);
+        assert_eq!(versions.len(), 2);
+        assert_eq!(
+            versions[0],
+            OptiFineVersion {
+                file_name: "OptiFine_1.20.1_HD_U_I6.jar".to_string(),
+                minecraft_version: "1.20.1".to_string(),
+                edition: "HD_U_I6".to_string(),
+                forge: Some("Forge 47.1.0".to_string()),
+            }
+        );
+        assert_eq!(versions[1].minecraft_version, "1.7.10");
+        assert_eq!(versions[1].forge, None);
+        assert_eq!(
+            parse_file_name(&release_file_name("1.20.1", "HD_U_I6")),
+            Some(("1.20.1".to_string(), "HD_U_I6".to_string()))
+        );
+    }
+
+    #[test]
+    fn test_parse_download_link() {
+        let html = r#"<a href='downloadx?f=OptiFine_1.20.1_HD_U_I6.jar&amp;x=5f1e2d' onclick='onDownload()'>OptiFine_1.20.1_HD_U_I6.jar</a>"#;
+        assert_eq!(
+            parse_download_link(html).as_deref(),
+            Some("https://optifine.net/downloadx?f=OptiFine_1.20.1_HD_U_I6.jar&x=5f1e2d")
+        );
+        assert_eq!(parse_download_link("<html></html>"), None);
+    }
+
+    #[test]
+    fn test_version_json() {
+        let modern = version_json("1.20.1", "HD_U_I6", Vec::new(), None);
+        assert_eq!(modern["id"], "1.20.1-OptiFine_HD_U_I6");
+        assert_eq!(modern["inheritsFrom"], "1.20.1");
+        assert_eq!(
+            modern["arguments"]["game"],
+            serde_json::json!(["--tweakClass", "optifine.OptiFineTweaker"])
+        );
+
+        let legacy = version_json(
+            "1.12.2",
+            "HD_U_G5",
+            Vec::new(),
+            Some("--username ${auth_player_name}"),
+        );
+        assert_eq!(
+            legacy["minecraftArguments"],
+            "--username ${auth_player_name} --tweakClass optifine.OptiFineTweaker"
+        );
+        assert!(legacy.get("arguments").is_none());
+    }
+}
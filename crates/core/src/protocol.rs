@@ -0,0 +1,150 @@
+//! Minimal RCON client for locally managed servers.
+//!
+//! Packets are `length | request id | type | body \0 \0` with little-endian
+//! `i32` fields. The server answers a failed login with request id -1 and
+//! splits replies longer than 4096 bytes over several packets.
+
+use std::time::Duration;
+use tokio::io::{AsyncRead, AsyncReadExt, AsyncWrite, AsyncWriteExt};
+use tokio::net::TcpStream;
+
+pub const DEFAULT_RCON_PORT: u16 = 25575;
+
+const TYPE_RESPONSE: i32 = 0;
+const TYPE_COMMAND: i32 = 2;
+const TYPE_LOGIN: i32 = 3;
+
+/// Largest body the server puts in one reply packet
+const MAX_FRAGMENT: usize = 4096;
+/// Largest packet we accept (body plus the 10 bytes of header and padding)
+const MAX_PACKET: usize = 4110;
+const TIMEOUT: Duration = Duration::from_secs(5);
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Packet {
+    pub id: i32,
+    pub kind: i32,
+    pub body: String,
+}
+
+pub fn encode_packet(id: i32, kind: i32, body: &str) -> Vec<u8> {
+    let length = (4 + 4 + body.len() + 2) as i32;
+    let mut buf = Vec::with_capacity(length as usize + 4);
+    buf.extend_from_slice(&length.to_le_bytes());
+    buf.extend_from_slice(&id.to_le_bytes());
+    buf.extend_from_slice(&kind.to_le_bytes());
+    buf.extend_from_slice(body.as_bytes());
+    buf.extend_from_slice(&[0, 0]);
+    buf
+}
+
+/// Decode a packet without its length prefix
+pub fn decode_packet(data: &[u8]) -> Result<Packet, String> {
+    if data.len() < 10 {
+        return Err(format!("RCON packet too short ({} bytes)", data.len()));
+    }
+    let id = i32::from_le_bytes(data[0..4].try_into().unwrap());
+    let kind = i32::from_le_bytes(data[4..8].try_into().unwrap());
+    let body = &data[8..data.len() - 2];
+    Ok(Packet {
+        id,
+        kind,
+        body: String::from_utf8_lossy(body).into_owned(),
+    })
+}
+
+async fn read_packet<R: AsyncRead + Unpin>(reader: &mut R) -> Result<Packet, String> {
+    let length = reader.read_i32_le().await.map_err(|e| e.to_string())?;
+    if length < 10 || length as usize > MAX_PACKET {
+        return Err(format!("Invalid RCON packet length {}", length));
+    }
+    let mut data = vec![0u8; length as usize];
+    reader
+        .read_exact(&mut data)
+        .await
+        .map_err(|e| e.to_string())?;
+    decode_packet(&data)
+}
+
+async fn write_packet<W: AsyncWrite + Unpin>(
+    writer: &mut W,
+    id: i32,
+    kind: i32,
+    body: &str,
+) -> Result<(), String> {
+    writer
+        .write_all(&encode_packet(id, kind, body))
+        .await
+        .map_err(|e| e.to_string())
+}
+
+/// Log in and run one command, returning the server's reply
+pub async fn send_command<S: AsyncRead + AsyncWrite + Unpin>(
+    stream: &mut S,
+    password: &str,
+    command: &str,
+) -> Result<String, String> {
+    write_packet(stream, 1, TYPE_LOGIN, password).await?;
+    // Some servers send an empty response packet before the login reply
+    let mut login = read_packet(stream).await?;
+    if login.kind == TYPE_RESPONSE {
+        login = read_packet(stream).await?;
+    }
+    if login.id == -1 {
+        return Err("RCON authentication failed: wrong password".to_string());
+    }
+
+    write_packet(stream, 2, TYPE_COMMAND, command).await?;
+    let mut reply = String::new();
+    loop {
+        let packet = read_packet(stream).await?;
+        let full = packet.body.len() >= MAX_FRAGMENT;
+        reply.push_str(&packet.body);
+        if !full {
+            break;
+        }
+    }
+    Ok(reply)
+}
+
+/// Connect to `host:port` and run `command`
+pub async fn execute(
+    host: &str,
+    port: u16,
+    password: &str,
+    command: &str,
+) -> Result<String, String> {
+    let run = async {
+        let mut stream = TcpStream::connect((host, port))
+            .await
+            .map_err(|e| format!("Failed to connect to RCON at {}:{}: {}", host, port, e))?;
+        send_command(&mut stream, password, command).await
+    };
+    tokio::time::timeout(TIMEOUT, run)
+        .await
+        .map_err(|_| format!("RCON at {}:{} timed out", host, port))?
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_encode_packet() {
+        let packet = encode_packet(7, TYPE_COMMAND, "list");
+        assert_eq!(
+            packet,
+            vec![
+                14, 0, 0, 0, 7, 0, 0, 0, 2, 0, 0, 0, b'l', b'i', b's', b't', 0, 0
+            ]
+        );
+        assert_eq!(
+            decode_packet(&packet[4..]).unwrap(),
+            Packet {
+                id: 7,
+                kind: TYPE_COMMAND,
+                body: "list".to_string()
+            }
+        );
+    }
+}
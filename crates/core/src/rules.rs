@@ -0,0 +1,771 @@
+use crate::config::FeatureFlags;
+use crate::game_version::{GameVersion, Library, Rule};
+use crate::natives::{classifier_rank, split_native_artifact};
+use serde::{Deserialize, Serialize};
+use std::env;
+use ts_rs::TS;
+
+/// OS and architecture rules are evaluated against, in `std::env::consts` terms
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Platform {
+    pub os: String,
+    pub arch: String,
+}
+
+impl Platform {
+    pub fn current() -> Self {
+        Self::new(env::consts::OS, env::consts::ARCH)
+    }
+
+    pub fn new(os: &str, arch: &str) -> Self {
+        Self {
+            os: os.to_string(),
+            arch: arch.to_string(),
+        }
+    }
+}
+
+/// Why a library or argument was included or excluded
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export, export_to = "dropout-core/rules.ts")]
+pub struct RuleTrace {
+    pub kind: String, // "library" | "game" | "jvm"
+    pub subject: String,
+    pub included: bool,
+    pub reason: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export, export_to = "dropout-core/rules.ts")]
+pub struct RulesReport {
+    pub version_id: String,
+    pub os: String,
+    pub arch: String,
+    pub entries: Vec<RuleTrace>,
+}
+
+/// Feature keys Mojang uses in argument rules. Rules naming any other key
+/// are treated as disallowed rather than guessed at.
+pub const KNOWN_FEATURES: &[&str] = &[
+    "is_demo_user",
+    "has_custom_resolution",
+    "has_quick_plays_support",
+    "is_quick_play_singleplayer",
+    "is_quick_play_multiplayer",
+    "is_quick_play_realms",
+];
+
+/// Value of a known feature under `ctx`, `None` for unknown keys
+fn feature_value(key: &str, ctx: &FeatureFlags) -> Option<bool> {
+    let value = match key {
+        "is_demo_user" => ctx.demo_user,
+        "has_quick_plays_support" => ctx.quick_play_enabled,
+        "is_quick_play_singleplayer" => ctx.quick_play_enabled && ctx.quick_play_singleplayer,
+        "is_quick_play_multiplayer" => {
+            ctx.quick_play_enabled
+                && ctx
+                    .quick_play_multiplayer_server
+                    .as_ref()
+                    .map(|s| !s.is_empty())
+                    .unwrap_or(false)
+        }
+        // Resolution is passed separately and Realms isn't supported
+        "has_custom_resolution" | "is_quick_play_realms" => false,
+        _ => return None,
+    };
+    Some(value)
+}
+
+/// Feature keys in `rules` that aren't in [`KNOWN_FEATURES`]
+fn unknown_features(rules: &[Rule]) -> Vec<&str> {
+    rules
+        .iter()
+        .filter_map(|rule| rule.features.as_ref().and_then(|f| f.as_object()))
+        .flat_map(|map| map.keys())
+        .map(String::as_str)
+        .filter(|key| !KNOWN_FEATURES.contains(key))
+        .collect()
+}
+
+pub fn is_library_allowed(rules: &Option<Vec<Rule>>, features: Option<&FeatureFlags>) -> bool {
+    is_allowed_on(rules, features, &Platform::current())
+}
+
+pub fn is_allowed_on(
+    rules: &Option<Vec<Rule>>,
+    features: Option<&FeatureFlags>,
+    platform: &Platform,
+) -> bool {
+    explain_rules(rules, features, platform).0
+}
+
+/// Evaluate rules and describe which one decided the outcome
+pub fn explain_rules(
+    rules: &Option<Vec<Rule>>,
+    features: Option<&FeatureFlags>,
+    platform: &Platform,
+) -> (bool, String) {
+    // If no rules, it's allowed by default
+    let Some(rules) = rules else {
+        return (true, "no rules".to_string());
+    };
+
+    if rules.is_empty() {
+        return (true, "no rules".to_string());
+    }
+
+    let unknown = unknown_features(rules);
+    if !unknown.is_empty() {
+        // Rules run for every library and argument on each launch; release
+        // builds report the key through the returned reason only
+        if cfg!(debug_assertions) {
+            eprintln!("[Rules] Unknown feature keys: {}", unknown.join(", "));
+        }
+        return (
+            false,
+            format!("unknown feature: {} (disallowed)", unknown.join(", ")),
+        );
+    }
+
+    // Default depends on the first rule theoretically, but usually "allow" if no "disallow" matches?
+    // Actually MC logic: implicit disallow? No, implicit allow usually?
+    // Official launcher Rule logic:
+    // "Libraries are allowed unless restricted by a rule."
+    // Actually detailed logic:
+    // Check all rules. if action is "allow" and condition matches, allowed = true.
+    // if action is "disallow" and condition matches, allowed = false.
+    // Typically base state is false if rules exist? No.
+    // Let's check common pattern.
+    // Usually: [ {action: allow}, {action: disallow, os: "osx"} ]
+    // This implies base allowed, but OS X disallowed.
+    // Pattern 2: [ {action: allow, os: "osx"} ]
+    // This implies ONLY osx allowed?
+
+    // Correct logic:
+    // If rules are present, start with result = false (deny all).
+    // Loop through rules. If a rule applies (os matches), update result to (action == "allow").
+    // Wait, let's verify.
+    // If the list is [ {action: allow} ], result becomes true.
+    // If list is [ {action: allow}, {action: disallow, os: "osx"} ].
+    // On Linux: Rule 1 matches -> true. Rule 2 (osx) doesn't match -> ignore. Final: true.
+    // On OSX: Rule 1 matches -> true. Rule 2 matches -> false. Final: false.
+
+    // So: Start false. Apply rules in order.
+
+    let mut decision = (false, "no rule matched (disallowed by default)".to_string());
+
+    for (index, rule) in rules.iter().enumerate() {
+        if rule_matches(rule, features, platform) {
+            decision = (
+                rule.action == "allow",
+                format!("rule #{} matched: {}", index + 1, describe_rule(rule)),
+            );
+        }
+    }
+    decision
+}
+
+/// Evaluate a library's rules and, for rule-gated native artifacts, whether
+/// a sibling allowed on `platform` fits its architecture better. A forced
+/// `native_override` classifier outranks every other one.
+pub fn explain_library(
+    lib: &Library,
+    libraries: &[Library],
+    features: Option<&FeatureFlags>,
+    native_override: Option<&str>,
+    platform: &Platform,
+) -> (bool, String) {
+    let decision = explain_rules(&lib.rules, features, platform);
+    if !decision.0 {
+        return decision;
+    }
+    let Some((coordinates, classifier)) = split_native_artifact(&lib.name) else {
+        return decision;
+    };
+    let forced = native_override.filter(|c| !c.is_empty());
+    let rank = |c: &str| {
+        if forced == Some(c) {
+            Some(0)
+        } else {
+            classifier_rank(c, &platform.os, &platform.arch).map(|r| r + 1)
+        }
+    };
+    let best = libraries
+        .iter()
+        .filter_map(|other| split_native_artifact(&other.name).map(|split| (other, split)))
+        .filter(|(_, (other_coordinates, _))| *other_coordinates == coordinates)
+        .filter(|(other, _)| explain_rules(&other.rules, features, platform).0)
+        .filter_map(|(_, (_, other_classifier))| {
+            rank(other_classifier).map(|r| (r, other_classifier))
+        })
+        .min();
+    match best {
+        // Nothing fits better than anything else, keep what the rules allow
+        None => decision,
+        Some((best_rank, _)) if rank(classifier) == Some(best_rank) => decision,
+        Some((_, best_classifier)) => (
+            false,
+            if forced == Some(best_classifier) {
+                format!("{} is forced", best_classifier)
+            } else {
+                format!("{} fits {} better", best_classifier, platform.arch)
+            },
+        ),
+    }
+}
+
+/// [`explain_library`] on the current platform
+pub fn is_library_included(
+    lib: &Library,
+    libraries: &[Library],
+    features: Option<&FeatureFlags>,
+    native_override: Option<&str>,
+) -> bool {
+    explain_library(
+        lib,
+        libraries,
+        features,
+        native_override,
+        &Platform::current(),
+    )
+    .0
+}
+
+/// Architecture names of `os.arch` rules and classifiers in
+/// `std::env::consts` terms
+fn normalize_arch(arch: &str) -> &str {
+    match arch {
+        "x86_64" | "amd64" | "x64" => "x86_64",
+        "x86" | "i386" | "i686" => "x86",
+        "aarch64" | "arm64" | "aarch_64" => "aarch64",
+        "arm" | "arm32" => "arm",
+        other => other,
+    }
+}
+
+fn describe_rule(rule: &Rule) -> String {
+    let mut conditions = Vec::new();
+    if let Some(os) = &rule.os {
+        if let Some(name) = &os.name {
+            conditions.push(format!("os.name={}", name));
+        }
+        if let Some(arch) = &os.arch {
+            conditions.push(format!("os.arch={}", arch));
+        }
+        if let Some(version) = &os.version {
+            conditions.push(format!("os.version~{}", version));
+        }
+    }
+    if let Some(map) = rule.features.as_ref().and_then(|f| f.as_object()) {
+        for (key, value) in map {
+            conditions.push(format!("{}={}", key, value));
+        }
+    }
+    if conditions.is_empty() {
+        rule.action.clone()
+    } else {
+        format!("{} if {}", rule.action, conditions.join(", "))
+    }
+}
+
+fn rule_matches(rule: &Rule, features: Option<&FeatureFlags>, platform: &Platform) -> bool {
+    // Feature-based rules: apply only if all listed features evaluate to true
+    if let Some(f) = &rule.features {
+        if let Some(map) = f.as_object() {
+            // If no feature flags provided, we cannot satisfy feature rules
+            let ctx = match features {
+                Some(ff) => ff,
+                None => return false,
+            };
+
+            for (key, val) in map.iter() {
+                let required = val.as_bool().unwrap_or(false);
+                let Some(actual) = feature_value(key, ctx) else {
+                    return false;
+                };
+                if required && !actual {
+                    return false;
+                }
+                if !required && actual {
+                    // If rule specifies feature must be false, but it's true, do not match
+                    return false;
+                }
+            }
+        } else {
+            // Malformed features object
+            return false;
+        }
+    }
+
+    match &rule.os {
+        None => true, // No OS condition means it applies to all
+        Some(os_rule) => {
+            // Check OS name
+            if let Some(os_name) = &os_rule.name {
+                let os_match = match os_name.as_str() {
+                    "osx" | "macos" => platform.os == "macos",
+                    "linux" => platform.os == "linux",
+                    "windows" => platform.os == "windows",
+                    _ => false, // Unknown OS name in rule
+                };
+
+                if !os_match {
+                    return false;
+                }
+            }
+
+            // Check architecture if specified. Strict: "x86" never matches
+            // x86_64, but Mojang's and Java's names for the same
+            // architecture do ("arm64" is aarch64).
+            if let Some(arch) = &os_rule.arch
+                && normalize_arch(arch) != normalize_arch(&platform.arch)
+            {
+                return false;
+            }
+
+            // Check version if specified (for OS version compatibility)
+            if let Some(_version) = &os_rule.version {
+                // Version checking would require parsing OS version strings
+                // For now, we accept all versions (conservative approach)
+                // In the future, parse version and compare
+            }
+
+            true
+        }
+    }
+}
+
+/// Trace every rule-bearing entry of an argument list
+fn trace_arguments(
+    kind: &str,
+    list: Option<&serde_json::Value>,
+    features: Option<&FeatureFlags>,
+    platform: &Platform,
+    entries: &mut Vec<RuleTrace>,
+) {
+    for item in list.and_then(|l| l.as_array()).into_iter().flatten() {
+        let Some(obj) = item.as_object() else {
+            continue; // plain strings are always included
+        };
+        let subject = match obj.get("value") {
+            Some(serde_json::Value::String(s)) => s.clone(),
+            Some(serde_json::Value::Array(values)) => values
+                .iter()
+                .filter_map(|v| v.as_str())
+                .collect::<Vec<_>>()
+                .join(" "),
+            _ => continue,
+        };
+        let (included, reason) = match obj.get("rules") {
+            Some(rules_val) => match serde_json::from_value::<Vec<Rule>>(rules_val.clone()) {
+                Ok(rules) => explain_rules(&Some(rules), features, platform),
+                Err(e) => (false, format!("malformed rules: {}", e)),
+            },
+            None => (true, "no rules".to_string()),
+        };
+        entries.push(RuleTrace {
+            kind: kind.to_string(),
+            subject,
+            included,
+            reason,
+        });
+    }
+}
+
+/// Explain, for every library and conditional argument of a version, why it
+/// is included or excluded on `platform`
+pub fn evaluate_rules_report(
+    version: &GameVersion,
+    features: Option<&FeatureFlags>,
+    native_override: Option<&str>,
+    platform: &Platform,
+) -> RulesReport {
+    let mut entries: Vec<RuleTrace> = version
+        .libraries
+        .iter()
+        .map(|lib| {
+            let (included, reason) =
+                explain_library(lib, &version.libraries, features, native_override, platform);
+            RuleTrace {
+                kind: "library".to_string(),
+                subject: lib.name.clone(),
+                included,
+                reason,
+            }
+        })
+        .collect();
+    if let Some(arguments) = &version.arguments {
+        trace_arguments(
+            "game",
+            arguments.game.as_ref(),
+            features,
+            platform,
+            &mut entries,
+        );
+        trace_arguments(
+            "jvm",
+            arguments.jvm.as_ref(),
+            features,
+            platform,
+            &mut entries,
+        );
+    }
+    RulesReport {
+        version_id: version.id.clone(),
+        os: platform.os.clone(),
+        arch: platform.arch.clone(),
+        entries,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::{HashMap, HashSet};
+
+    const FIXTURES: &[(&str, &str)] = &[
+        (
+            "1.7.10",
+            include_str!("../tests/fixtures/versions/1.7.10.json"),
+        ),
+        (
+            "1.8.9",
+            include_str!("../tests/fixtures/versions/1.8.9.json"),
+        ),
+        (
+            "1.9.4",
+            include_str!("../tests/fixtures/versions/1.9.4.json"),
+        ),
+        (
+            "1.10.2",
+            include_str!("../tests/fixtures/versions/1.10.2.json"),
+        ),
+        (
+            "1.11.2",
+            include_str!("../tests/fixtures/versions/1.11.2.json"),
+        ),
+        (
+            "1.12.2",
+            include_str!("../tests/fixtures/versions/1.12.2.json"),
+        ),
+        (
+            "1.13.2",
+            include_str!("../tests/fixtures/versions/1.13.2.json"),
+        ),
+        (
+            "1.14.4",
+            include_str!("../tests/fixtures/versions/1.14.4.json"),
+        ),
+        (
+            "1.16.5",
+            include_str!("../tests/fixtures/versions/1.16.5.json"),
+        ),
+        (
+            "1.17.1",
+            include_str!("../tests/fixtures/versions/1.17.1.json"),
+        ),
+        (
+            "1.19.4",
+            include_str!("../tests/fixtures/versions/1.19.4.json"),
+        ),
+        (
+            "1.20.4",
+            include_str!("../tests/fixtures/versions/1.20.4.json"),
+        ),
+        (
+            "1.21.1",
+            include_str!("../tests/fixtures/versions/1.21.1.json"),
+        ),
+    ];
+
+    fn platforms() -> Vec<Platform> {
+        vec![
+            Platform::new("linux", "x86_64"),
+            Platform::new("linux", "aarch64"),
+            Platform::new("windows", "x86_64"),
+            Platform::new("macos", "x86_64"),
+            Platform::new("macos", "aarch64"),
+        ]
+    }
+
+    fn load(id: &str) -> GameVersion {
+        let (_, json) = FIXTURES.iter().find(|(v, _)| *v == id).unwrap();
+        serde_json::from_str(json).unwrap()
+    }
+
+    fn included(report: &RulesReport, kind: &str) -> Vec<String> {
+        report
+            .entries
+            .iter()
+            .filter(|e| e.kind == kind && e.included)
+            .map(|e| e.subject.clone())
+            .collect()
+    }
+
+    #[test]
+    fn test_fixtures_parse() {
+        for (id, json) in FIXTURES {
+            let version: GameVersion = serde_json::from_str(json).unwrap();
+            assert_eq!(&version.id, id);
+        }
+    }
+
+    #[test]
+    fn test_single_library_version_per_platform() {
+        for (id, _) in FIXTURES {
+            let version = load(id);
+            for platform in platforms() {
+                let report = evaluate_rules_report(&version, None, None, &platform);
+                let mut versions: HashMap<String, HashSet<String>> = HashMap::new();
+                for name in included(&report, "library") {
+                    let parts: Vec<&str> = name.split(':').collect();
+                    let key = format!("{}:{}:{}", parts[0], parts[1], parts[3..].join(":"));
+                    versions
+                        .entry(key)
+                        .or_default()
+                        .insert(parts[2].to_string());
+                }
+                for (key, set) in versions {
+                    assert_eq!(set.len(), 1, "{} on {:?}: {} {:?}", id, platform, key, set);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_legacy_lwjgl_split() {
+        let version = load("1.12.2");
+        let mac = evaluate_rules_report(&version, None, None, &Platform::new("macos", "x86_64"));
+        let linux = evaluate_rules_report(&version, None, None, &Platform::new("linux", "x86_64"));
+        assert!(
+            included(&mac, "library")
+                .contains(&"org.lwjgl.lwjgl:lwjgl:2.9.2-nightly-20140822".to_string())
+        );
+        assert!(
+            included(&linux, "library")
+                .contains(&"org.lwjgl.lwjgl:lwjgl:2.9.4-nightly-20150209".to_string())
+        );
+
+        let twitch = load("1.8.9");
+        let linux = evaluate_rules_report(&twitch, None, None, &Platform::new("linux", "x86_64"));
+        let trace = linux
+            .entries
+            .iter()
+            .find(|e| e.subject == "tv.twitch:twitch-platform:6.5")
+            .unwrap();
+        assert!(!trace.included);
+        assert_eq!(trace.reason, "rule #2 matched: disallow if os.name=linux");
+    }
+
+    #[test]
+    fn test_modern_natives_follow_os() {
+        let version = load("1.21.1");
+        let windows =
+            evaluate_rules_report(&version, None, None, &Platform::new("windows", "x86_64"));
+        let libs = included(&windows, "library");
+        assert!(libs.contains(&"org.lwjgl:lwjgl:3.3.3:natives-windows".to_string()));
+        assert!(
+            !libs
+                .iter()
+                .any(|l| l.contains("natives-linux") || l.contains("natives-macos"))
+        );
+    }
+
+    fn natives_of(report: &RulesReport, artifact: &str) -> Vec<String> {
+        included(report, "library")
+            .into_iter()
+            .filter_map(|name| {
+                let (coordinates, classifier) = split_native_artifact(&name)?;
+                coordinates
+                    .starts_with(artifact)
+                    .then(|| classifier.to_string())
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_one_native_artifact_per_platform() {
+        for id in ["1.19.4", "1.20.4", "1.21.1"] {
+            let version = load(id);
+            for platform in platforms() {
+                let report = evaluate_rules_report(&version, None, None, &platform);
+                let natives = natives_of(&report, "org.lwjgl:lwjgl:");
+                assert_eq!(natives.len(), 1, "{} on {:?}: {:?}", id, platform, natives);
+            }
+        }
+
+        let version = load("1.19.4");
+        let windows =
+            evaluate_rules_report(&version, None, None, &Platform::new("windows", "x86_64"));
+        assert_eq!(
+            natives_of(&windows, "org.lwjgl:lwjgl-glfw:"),
+            vec!["natives-windows"]
+        );
+        let trace = windows
+            .entries
+            .iter()
+            .find(|e| e.subject == "org.lwjgl:lwjgl-glfw:3.3.1:natives-windows-arm64")
+            .unwrap();
+        assert!(!trace.included);
+        assert_eq!(trace.reason, "natives-windows fits x86_64 better");
+        let mac = evaluate_rules_report(&version, None, None, &Platform::new("macos", "aarch64"));
+        assert_eq!(
+            natives_of(&mac, "org.lwjgl:lwjgl-glfw:"),
+            vec!["natives-macos-arm64"]
+        );
+        // A forced classifier wins over the one that fits the architecture
+        let forced = evaluate_rules_report(
+            &version,
+            None,
+            Some("natives-windows"),
+            &Platform::new("windows", "aarch64"),
+        );
+        assert_eq!(
+            natives_of(&forced, "org.lwjgl:lwjgl-glfw:"),
+            vec!["natives-windows"]
+        );
+        let trace = forced
+            .entries
+            .iter()
+            .find(|e| e.subject == "org.lwjgl:lwjgl-glfw:3.3.1:natives-windows-arm64")
+            .unwrap();
+        assert_eq!(trace.reason, "natives-windows is forced");
+    }
+
+    #[test]
+    fn test_linux_aarch64_natives() {
+        let platform = Platform::new("linux", "aarch64");
+        // LWJGL 3.3.3 ships ARM natives for Linux
+        let report = evaluate_rules_report(&load("1.21.1"), None, None, &platform);
+        assert_eq!(
+            natives_of(&report, "org.lwjgl:lwjgl-opengl:"),
+            vec!["natives-linux-aarch_64"]
+        );
+        // Older versions only have the x86_64 jar, which is kept as a fallback
+        for id in ["1.19.4", "1.20.4"] {
+            let report = evaluate_rules_report(&load(id), None, None, &platform);
+            assert_eq!(
+                natives_of(&report, "org.lwjgl:lwjgl-opengl:"),
+                vec!["natives-linux"],
+                "{}",
+                id
+            );
+        }
+    }
+
+    #[test]
+    fn test_arch_conditioned_rules() {
+        // Patched JSONs gate the ARM natives with `os.arch` instead
+        let version: GameVersion = serde_json::from_value(serde_json::json!({
+            "id": "1.19.4-arm64",
+            "type": "release",
+            "mainClass": "net.minecraft.client.main.Main",
+            "libraries": [
+                {
+                    "name": "org.lwjgl:lwjgl:3.3.1:natives-linux",
+                    "rules": [
+                        {"action": "allow", "os": {"name": "linux"}},
+                        {"action": "disallow", "os": {"name": "linux", "arch": "arm64"}}
+                    ]
+                },
+                {
+                    "name": "org.lwjgl:lwjgl:3.3.1:natives-linux-arm64",
+                    "rules": [{"action": "allow", "os": {"name": "linux", "arch": "arm64"}}]
+                },
+                {
+                    "name": "org.lwjgl:lwjgl:3.3.1:natives-windows-x86",
+                    "rules": [{"action": "allow", "os": {"name": "windows", "arch": "x86"}}]
+                }
+            ]
+        }))
+        .unwrap();
+        let arm = evaluate_rules_report(&version, None, None, &Platform::new("linux", "aarch64"));
+        assert_eq!(
+            natives_of(&arm, "org.lwjgl:lwjgl:"),
+            vec!["natives-linux-arm64"]
+        );
+        let x64 = evaluate_rules_report(&version, None, None, &Platform::new("linux", "x86_64"));
+        assert_eq!(natives_of(&x64, "org.lwjgl:lwjgl:"), vec!["natives-linux"]);
+        let windows =
+            evaluate_rules_report(&version, None, None, &Platform::new("windows", "x86_64"));
+        assert!(natives_of(&windows, "org.lwjgl:lwjgl:").is_empty());
+        let windows32 =
+            evaluate_rules_report(&version, None, None, &Platform::new("windows", "x86"));
+        assert_eq!(
+            natives_of(&windows32, "org.lwjgl:lwjgl:"),
+            vec!["natives-windows-x86"]
+        );
+    }
+
+    #[test]
+    fn test_jvm_argument_rules() {
+        for (id, _) in FIXTURES {
+            let version = load(id);
+            if version.arguments.is_none() {
+                continue; // legacy `minecraftArguments`
+            }
+            for platform in platforms() {
+                let jvm = included(
+                    &evaluate_rules_report(&version, None, None, &platform),
+                    "jvm",
+                );
+                assert_eq!(
+                    jvm.contains(&"-XstartOnFirstThread".to_string()),
+                    platform.os == "macos",
+                    "{} {:?}",
+                    id,
+                    platform
+                );
+                // 64-bit platforms never get the 32-bit stack size
+                assert!(!jvm.contains(&"-Xss1M".to_string()));
+            }
+        }
+    }
+
+    #[test]
+    fn test_feature_rules() {
+        let version = load("1.20.4");
+        let platform = Platform::new("linux", "x86_64");
+        let default =
+            evaluate_rules_report(&version, Some(&FeatureFlags::default()), None, &platform);
+        assert!(included(&default, "game").is_empty());
+
+        let flags = FeatureFlags {
+            demo_user: true,
+            quick_play_enabled: true,
+            quick_play_singleplayer: true,
+            ..FeatureFlags::default()
+        };
+        let game = included(
+            &evaluate_rules_report(&version, Some(&flags), None, &platform),
+            "game",
+        );
+        assert!(game.contains(&"--demo".to_string()));
+        assert!(game.contains(&"--quickPlayPath ${quickPlayPath}".to_string()));
+        assert!(game.contains(&"--quickPlaySingleplayer ${quickPlaySingleplayer}".to_string()));
+        assert!(!game.iter().any(|a| a.starts_with("--quickPlayRealms")));
+    }
+
+    #[test]
+    fn test_unknown_feature_disallowed() {
+        let platform = Platform::new("linux", "x86_64");
+        let flags = FeatureFlags::default();
+        let rules: Vec<Rule> = serde_json::from_value(serde_json::json!([
+            {"action": "allow", "features": {"is_future_feature": false}}
+        ]))
+        .unwrap();
+        let (allowed, reason) = explain_rules(&Some(rules), Some(&flags), &platform);
+        assert!(!allowed);
+        assert!(reason.contains("is_future_feature"));
+
+        // Disallow rules with unknown keys don't flip an entry to allowed either
+        let rules: Vec<Rule> = serde_json::from_value(serde_json::json!([
+            {"action": "allow"},
+            {"action": "disallow", "features": {"is_future_feature": true}}
+        ]))
+        .unwrap();
+        assert!(!is_allowed_on(&Some(rules), Some(&flags), &platform));
+    }
+}
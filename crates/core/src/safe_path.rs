@@ -0,0 +1,46 @@
+//! Relative paths that come from outside the launcher.
+//!
+//! Pack manifests, zip entries and LAN peers name files relative to an
+//! instance folder. `Path::starts_with` compares components without
+//! resolving them, so `game_dir.join("mods/../../x").starts_with(game_dir)`
+//! holds although the path leaves `game_dir`. Such paths are only accepted
+//! when every component is a plain name.
+
+use std::path::{Component, Path, PathBuf};
+
+/// True when `path` is relative and made of plain names only: no `..`, `.`,
+/// root or drive prefix
+pub fn is_safe_relative(path: &str) -> bool {
+    !path.is_empty()
+        && Path::new(path)
+            .components()
+            .all(|c| matches!(c, Component::Normal(_)))
+}
+
+/// `base/path`, or `None` when `path` could escape `base`
+pub fn join(base: &Path, path: &str) -> Option<PathBuf> {
+    is_safe_relative(path).then(|| base.join(path))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_is_safe_relative() {
+        assert!(is_safe_relative("mods/sodium.jar"));
+        assert!(is_safe_relative("options.txt"));
+        assert!(!is_safe_relative(""));
+        assert!(!is_safe_relative("mods/../../evil.sh"));
+        assert!(!is_safe_relative("../evil.sh"));
+        assert!(!is_safe_relative("./mods/a.jar"));
+        assert!(!is_safe_relative("/etc/passwd"));
+    }
+
+    #[test]
+    fn test_join() {
+        let base = Path::new("/game");
+        assert_eq!(join(base, "mods/a.jar"), Some(base.join("mods/a.jar")));
+        assert_eq!(join(base, "mods/../../a.jar"), None);
+    }
+}
@@ -0,0 +1,415 @@
+//! Optional sandboxing of the game process for untrusted modpacks.
+//!
+//! - Linux: the Java command is wrapped in bubblewrap (`bwrap`) or firejail.
+//!   The home directory is hidden except for the instance folder (writable)
+//!   and the launcher data and Java runtime (read-only); network access can
+//!   be cut off. Of `$XDG_RUNTIME_DIR` only the Wayland, PulseAudio and
+//!   PipeWire sockets are visible, so the D-Bus session bus is out of reach.
+//! - Windows: the process is started suspended and put in a job object that
+//!   forbids creating child processes and restricts desktop/system-wide UI
+//!   changes before it runs any code. Job objects cannot restrict file
+//!   access, so the filesystem is not limited.
+//!
+//! Other platforms refuse to launch with sandboxing enabled rather than
+//! silently running unsandboxed.
+
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+use ts_rs::TS;
+
+/// Per-instance sandbox settings
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export, export_to = "dropout-core/instance.ts")]
+#[serde(default)]
+pub struct SandboxSettings {
+    pub enabled: bool,
+    pub backend: String, // "auto", "bwrap" or "firejail" (Linux only)
+    pub allow_network: bool,
+    pub extra_read_paths: Vec<String>, // additional read-only paths (Linux only)
+}
+
+impl Default for SandboxSettings {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            backend: "auto".to_string(),
+            allow_network: true,
+            extra_read_paths: Vec::new(),
+        }
+    }
+}
+
+fn find_in_path(program: &str) -> Option<PathBuf> {
+    let paths = std::env::var_os("PATH")?;
+    std::env::split_paths(&paths)
+        .map(|dir| dir.join(program))
+        .find(|candidate| candidate.is_file())
+}
+
+fn path_str(path: &Path) -> String {
+    path.to_string_lossy().to_string()
+}
+
+/// Sockets of `$XDG_RUNTIME_DIR` the game needs: Wayland, PulseAudio and
+/// PipeWire. Everything else there (notably the D-Bus session bus) stays
+/// hidden.
+pub fn runtime_sockets(runtime_dir: &Path, wayland_display: Option<&str>) -> Vec<PathBuf> {
+    let wayland = wayland_display.unwrap_or("wayland-0");
+    vec![
+        // WAYLAND_DISPLAY may also be an absolute path
+        runtime_dir.join(wayland),
+        runtime_dir.join("pulse").join("native"),
+        runtime_dir.join("pipewire-0"),
+    ]
+}
+
+/// bubblewrap arguments up to (not including) the wrapped command
+pub fn bwrap_args(
+    settings: &SandboxSettings,
+    game_dir: &Path,
+    read_only: &[PathBuf],
+    home: Option<&Path>,
+) -> Vec<String> {
+    let mut args: Vec<String> = [
+        "--die-with-parent",
+        "--unshare-pid",
+        "--unshare-uts",
+        "--ro-bind",
+        "/",
+        "/",
+        "--dev",
+        "/dev",
+        "--dev-bind-try",
+        "/dev/dri",
+        "/dev/dri",
+        "--dev-bind-try",
+        "/dev/snd",
+        "/dev/snd",
+        "--proc",
+        "/proc",
+        "--tmpfs",
+        "/tmp",
+        "--ro-bind-try",
+        "/tmp/.X11-unix",
+        "/tmp/.X11-unix",
+    ]
+    .iter()
+    .map(|s| s.to_string())
+    .collect();
+
+    if !settings.allow_network {
+        args.push("--unshare-net".to_string());
+    }
+
+    // Hide the user's files, then expose only what the game needs
+    if let Some(home) = home {
+        args.extend(["--tmpfs".to_string(), path_str(home)]);
+    }
+    if let Ok(xauthority) = std::env::var("XAUTHORITY") {
+        args.extend(["--ro-bind-try".to_string(), xauthority.clone(), xauthority]);
+    }
+    if let Ok(runtime_dir) = std::env::var("XDG_RUNTIME_DIR") {
+        let runtime_dir = PathBuf::from(runtime_dir);
+        let wayland_display = std::env::var("WAYLAND_DISPLAY").ok();
+        args.extend(["--tmpfs".to_string(), path_str(&runtime_dir)]);
+        for socket in runtime_sockets(&runtime_dir, wayland_display.as_deref()) {
+            args.extend([
+                "--bind-try".to_string(),
+                path_str(&socket),
+                path_str(&socket),
+            ]);
+        }
+    }
+    for path in read_only {
+        args.extend(["--ro-bind-try".to_string(), path_str(path), path_str(path)]);
+    }
+    for path in &settings.extra_read_paths {
+        args.extend(["--ro-bind-try".to_string(), path.clone(), path.clone()]);
+    }
+    args.extend([
+        "--bind".to_string(),
+        path_str(game_dir),
+        path_str(game_dir),
+        "--chdir".to_string(),
+        path_str(game_dir),
+        "--".to_string(),
+    ]);
+    args
+}
+
+/// firejail arguments up to (not including) the wrapped command
+pub fn firejail_args(
+    settings: &SandboxSettings,
+    game_dir: &Path,
+    read_only: &[PathBuf],
+) -> Vec<String> {
+    let mut args: Vec<String> = [
+        "--quiet",
+        "--noprofile",
+        "--nonewprivs",
+        "--seccomp",
+        "--caps.drop=all",
+    ]
+    .iter()
+    .map(|s| s.to_string())
+    .collect();
+
+    if !settings.allow_network {
+        args.push("--net=none".to_string());
+    }
+    for path in read_only {
+        args.push(format!("--whitelist={}", path_str(path)));
+        args.push(format!("--read-only={}", path_str(path)));
+    }
+    for path in &settings.extra_read_paths {
+        args.push(format!("--whitelist={}", path));
+        args.push(format!("--read-only={}", path));
+    }
+    args.push(format!("--whitelist={}", path_str(game_dir)));
+    args.push(format!("--read-write={}", path_str(game_dir)));
+    args.push("--".to_string());
+    args
+}
+
+/// Turn `java args...` into the sandboxed command line.
+///
+/// `read_only` lists paths the game must read (Java runtime, shared
+/// libraries and assets); the instance folder is the only writable one.
+pub fn wrap_command(
+    settings: &SandboxSettings,
+    java_path: &str,
+    args: &[String],
+    game_dir: &Path,
+    read_only: &[PathBuf],
+) -> Result<(String, Vec<String>), String> {
+    if !settings.enabled {
+        return Ok((java_path.to_string(), args.to_vec()));
+    }
+
+    if cfg!(target_os = "windows") {
+        // Restrictions are applied after spawning, see `apply_process_limits`
+        return Ok((java_path.to_string(), args.to_vec()));
+    }
+
+    if !cfg!(target_os = "linux") {
+        return Err("Sandboxing is not supported on this platform; disable it in the instance settings to launch".to_string());
+    }
+
+    let backend = match settings.backend.as_str() {
+        "bwrap" | "firejail" => settings.backend.clone(),
+        _ if find_in_path("bwrap").is_some() => "bwrap".to_string(),
+        _ if find_in_path("firejail").is_some() => "firejail".to_string(),
+        _ => {
+            return Err(
+                "Sandboxing requires bubblewrap (bwrap) or firejail; install one of them or disable the sandbox for this instance"
+                    .to_string(),
+            );
+        }
+    };
+    let program = find_in_path(&backend).ok_or_else(|| format!("{} is not installed", backend))?;
+
+    let mut wrapped = if backend == "bwrap" {
+        bwrap_args(settings, game_dir, read_only, dirs::home_dir().as_deref())
+    } else {
+        firejail_args(settings, game_dir, read_only)
+    };
+    wrapped.push(java_path.to_string());
+    wrapped.extend(args.iter().cloned());
+    Ok((path_str(&program), wrapped))
+}
+
+/// Extra process creation flags: with the sandbox on the game starts
+/// suspended, and [`apply_process_limits`] resumes it once it is confined.
+#[cfg(target_os = "windows")]
+pub fn creation_flags(settings: &SandboxSettings) -> u32 {
+    if settings.enabled {
+        windows_sys::Win32::System::Threading::CREATE_SUSPENDED
+    } else {
+        0
+    }
+}
+
+/// Resume every thread of the suspended process `pid`
+#[cfg(target_os = "windows")]
+fn resume_process(pid: u32) -> Result<(), String> {
+    use windows_sys::Win32::Foundation::{CloseHandle, INVALID_HANDLE_VALUE};
+    use windows_sys::Win32::System::Diagnostics::ToolHelp::{
+        CreateToolhelp32Snapshot, TH32CS_SNAPTHREAD, THREADENTRY32, Thread32First, Thread32Next,
+    };
+    use windows_sys::Win32::System::Threading::{OpenThread, ResumeThread, THREAD_SUSPEND_RESUME};
+
+    let mut resumed = 0;
+    // SAFETY: the snapshot and thread handles are checked before use and
+    // closed afterwards; THREADENTRY32 is zeroed with its size set.
+    unsafe {
+        let snapshot = CreateToolhelp32Snapshot(TH32CS_SNAPTHREAD, 0);
+        if snapshot == INVALID_HANDLE_VALUE {
+            return Err(format!(
+                "Failed to list game threads: {}",
+                std::io::Error::last_os_error()
+            ));
+        }
+        let mut entry: THREADENTRY32 = std::mem::zeroed();
+        entry.dwSize = std::mem::size_of::<THREADENTRY32>() as u32;
+        let mut more = Thread32First(snapshot, &mut entry) != 0;
+        while more {
+            if entry.th32OwnerProcessID == pid {
+                let thread = OpenThread(THREAD_SUSPEND_RESUME, 0, entry.th32ThreadID);
+                if !thread.is_null() {
+                    if ResumeThread(thread) != u32::MAX {
+                        resumed += 1;
+                    }
+                    CloseHandle(thread);
+                }
+            }
+            more = Thread32Next(snapshot, &mut entry) != 0;
+        }
+        CloseHandle(snapshot);
+    }
+    if resumed == 0 {
+        return Err("Failed to resume the game process".to_string());
+    }
+    Ok(())
+}
+
+/// Confine the game spawned with [`creation_flags`] and let it start
+#[cfg(target_os = "windows")]
+pub fn apply_process_limits(
+    settings: &SandboxSettings,
+    child: &tokio::process::Child,
+) -> Result<(), String> {
+    use windows_sys::Win32::Foundation::CloseHandle;
+    use windows_sys::Win32::System::JobObjects::{
+        AssignProcessToJobObject, CreateJobObjectW, JOB_OBJECT_LIMIT_ACTIVE_PROCESS,
+        JOB_OBJECT_UILIMIT_DESKTOP, JOB_OBJECT_UILIMIT_EXITWINDOWS,
+        JOB_OBJECT_UILIMIT_SYSTEMPARAMETERS, JOBOBJECT_BASIC_LIMIT_INFORMATION,
+        JOBOBJECT_BASIC_UI_RESTRICTIONS, JobObjectBasicLimitInformation,
+        JobObjectBasicUIRestrictions, SetInformationJobObject,
+    };
+
+    if !settings.enabled {
+        return Ok(());
+    }
+    let process = child
+        .raw_handle()
+        .ok_or_else(|| "Game process already exited".to_string())?;
+
+    // SAFETY: plain Win32 calls on a fresh job handle and the child's live
+    // process handle; the structs are fully initialized before use.
+    unsafe {
+        let job = CreateJobObjectW(std::ptr::null(), std::ptr::null());
+        if job.is_null() {
+            return Err("Failed to create job object".to_string());
+        }
+
+        let mut limits: JOBOBJECT_BASIC_LIMIT_INFORMATION = std::mem::zeroed();
+        limits.LimitFlags = JOB_OBJECT_LIMIT_ACTIVE_PROCESS;
+        limits.ActiveProcessLimit = 1;
+        let ui = JOBOBJECT_BASIC_UI_RESTRICTIONS {
+            UIRestrictionsClass: JOB_OBJECT_UILIMIT_DESKTOP
+                | JOB_OBJECT_UILIMIT_EXITWINDOWS
+                | JOB_OBJECT_UILIMIT_SYSTEMPARAMETERS,
+        };
+
+        let ok = SetInformationJobObject(
+            job,
+            JobObjectBasicLimitInformation,
+            &limits as *const _ as *const std::ffi::c_void,
+            std::mem::size_of::<JOBOBJECT_BASIC_LIMIT_INFORMATION>() as u32,
+        ) != 0
+            && SetInformationJobObject(
+                job,
+                JobObjectBasicUIRestrictions,
+                &ui as *const _ as *const std::ffi::c_void,
+                std::mem::size_of::<JOBOBJECT_BASIC_UI_RESTRICTIONS>() as u32,
+            ) != 0
+            && AssignProcessToJobObject(job, process as _) != 0;
+
+        // The job lives on as long as the game process is in it
+        CloseHandle(job);
+        if !ok {
+            return Err(format!(
+                "Failed to restrict the game process: {}",
+                std::io::Error::last_os_error()
+            ));
+        }
+    }
+    let pid = child
+        .id()
+        .ok_or_else(|| "Game process already exited".to_string())?;
+    resume_process(pid)
+}
+
+#[cfg(not(target_os = "windows"))]
+pub fn apply_process_limits(
+    _settings: &SandboxSettings,
+    _child: &tokio::process::Child,
+) -> Result<(), String> {
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_disabled_sandbox_passes_through() {
+        let settings = SandboxSettings::default();
+        let (program, args) = wrap_command(
+            &settings,
+            "/usr/bin/java",
+            &["-version".to_string()],
+            Path::new("/tmp/game"),
+            &[],
+        )
+        .unwrap();
+        assert_eq!(program, "/usr/bin/java");
+        assert_eq!(args, vec!["-version"]);
+    }
+
+    #[test]
+    fn test_bwrap_args() {
+        let settings = SandboxSettings {
+            enabled: true,
+            allow_network: false,
+            ..Default::default()
+        };
+        let args = bwrap_args(
+            &settings,
+            Path::new("/home/u/.dropout/instances/a"),
+            &[PathBuf::from("/home/u/.dropout/libraries")],
+            Some(Path::new("/home/u")),
+        );
+        assert!(args.contains(&"--unshare-net".to_string()));
+        let home = args.iter().position(|a| a == "/home/u").unwrap();
+        let game = args
+            .iter()
+            .position(|a| a == "/home/u/.dropout/instances/a")
+            .unwrap();
+        // The home tmpfs must be mounted before the instance bind
+        assert!(home < game);
+        assert_eq!(args.last().unwrap(), "--");
+    }
+
+    #[test]
+    fn test_runtime_sockets() {
+        let sockets = runtime_sockets(Path::new("/run/user/1000"), Some("wayland-1"));
+        assert!(sockets.contains(&PathBuf::from("/run/user/1000/wayland-1")));
+        assert!(sockets.contains(&PathBuf::from("/run/user/1000/pulse/native")));
+        assert!(!sockets.contains(&PathBuf::from("/run/user/1000/bus")));
+        let absolute = runtime_sockets(Path::new("/run/user/1000"), Some("/tmp/wl"));
+        assert!(absolute.contains(&PathBuf::from("/tmp/wl")));
+    }
+
+    #[test]
+    fn test_firejail_args() {
+        let settings = SandboxSettings {
+            enabled: true,
+            ..Default::default()
+        };
+        let args = firejail_args(&settings, Path::new("/games/a"), &[]);
+        assert!(!args.contains(&"--net=none".to_string()));
+        assert!(args.contains(&"--read-write=/games/a".to_string()));
+    }
+}
@@ -0,0 +1,371 @@
+//! Files of locally managed dedicated servers: `eula.txt` and
+//! `server.properties`.
+//!
+//! `server.properties` is a Java properties file. It is edited line by line
+//! so comments and key order written by the server survive, and values of
+//! known keys are validated before they are stored.
+
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+use ts_rs::TS;
+
+pub mod players;
+
+pub const PROPERTIES_FILE: &str = "server.properties";
+pub const EULA_FILE: &str = "eula.txt";
+pub const EULA_URL: &str = "https://aka.ms/MinecraftEULA";
+/// Jar the server is started from, in the server directory
+pub const SERVER_JAR: &str = "server.jar";
+
+/// Settings for a freshly generated `server.properties`
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export, export_to = "dropout-core/server.ts")]
+#[serde(default)]
+pub struct ServerTemplate {
+    pub port: u16,
+    pub motd: String,
+    pub whitelist: bool,
+}
+
+impl Default for ServerTemplate {
+    fn default() -> Self {
+        Self {
+            port: 25565,
+            motd: "A Minecraft Server".to_string(),
+            whitelist: false,
+        }
+    }
+}
+
+enum PropertyType {
+    Bool,
+    Int(i64, i64),
+    Choice(&'static [&'static str]),
+}
+
+const BOOL_KEYS: &[&str] = &[
+    "allow-flight",
+    "allow-nether",
+    "enable-command-block",
+    "enable-query",
+    "enable-rcon",
+    "enforce-whitelist",
+    "force-gamemode",
+    "generate-structures",
+    "hardcore",
+    "online-mode",
+    "pvp",
+    "spawn-animals",
+    "spawn-monsters",
+    "spawn-npcs",
+    "white-list",
+];
+
+fn property_type(key: &str) -> Option<PropertyType> {
+    if BOOL_KEYS.contains(&key) {
+        return Some(PropertyType::Bool);
+    }
+    Some(match key {
+        "server-port" | "rcon.port" | "query.port" => PropertyType::Int(1, 65535),
+        "max-players" => PropertyType::Int(0, i32::MAX as i64),
+        "view-distance" | "simulation-distance" => PropertyType::Int(2, 32),
+        "spawn-protection" => PropertyType::Int(0, i32::MAX as i64),
+        "op-permission-level" | "function-permission-level" => PropertyType::Int(1, 4),
+        "difficulty" => PropertyType::Choice(&["peaceful", "easy", "normal", "hard"]),
+        "gamemode" => PropertyType::Choice(&["survival", "creative", "adventure", "spectator"]),
+        _ => return None,
+    })
+}
+
+/// Check `value` against the type of `key` and return it normalized.
+/// Unknown keys are stored as given.
+pub fn validate_property(key: &str, value: &str) -> Result<String, String> {
+    if key.is_empty() || key.contains(['=', ':', '\n']) {
+        return Err(format!("Invalid property name '{}'", key));
+    }
+    let value = value.trim();
+    match property_type(key) {
+        None => Ok(value.to_string()),
+        Some(PropertyType::Bool) => match value.to_ascii_lowercase().as_str() {
+            "true" => Ok("true".to_string()),
+            "false" => Ok("false".to_string()),
+            _ => Err(format!("{} must be true or false, got '{}'", key, value)),
+        },
+        Some(PropertyType::Int(min, max)) => match value.parse::<i64>() {
+            Ok(n) if (min..=max).contains(&n) => Ok(n.to_string()),
+            _ => Err(format!(
+                "{} must be a whole number between {} and {}, got '{}'",
+                key, min, max, value
+            )),
+        },
+        Some(PropertyType::Choice(choices)) => {
+            let lower = value.to_ascii_lowercase();
+            if choices.contains(&lower.as_str()) {
+                Ok(lower)
+            } else {
+                Err(format!(
+                    "{} must be one of {}, got '{}'",
+                    key,
+                    choices.join(", "),
+                    value
+                ))
+            }
+        }
+    }
+}
+
+/// Escape a value the way `java.util.Properties` reads it back; non-ASCII
+/// characters become `\uXXXX` so older servers read them correctly too
+fn escape(value: &str) -> String {
+    let mut out = String::with_capacity(value.len());
+    for c in value.chars() {
+        match c {
+            '\\' => out.push_str("\\\\"),
+            '\n' => out.push_str("\\n"),
+            '\r' => out.push_str("\\r"),
+            '\t' => out.push_str("\\t"),
+            c if c.is_ascii() => out.push(c),
+            c => {
+                let mut units = [0u16; 2];
+                for unit in c.encode_utf16(&mut units) {
+                    out.push_str(&format!("\\u{:04x}", unit));
+                }
+            }
+        }
+    }
+    out
+}
+
+fn unescape(value: &str) -> String {
+    let mut units: Vec<u16> = Vec::with_capacity(value.len());
+    let mut chars = value.chars();
+    while let Some(c) = chars.next() {
+        let c = if c == '\\' {
+            match chars.next() {
+                Some('n') => '\n',
+                Some('r') => '\r',
+                Some('t') => '\t',
+                Some('u') => {
+                    let hex: String = chars.by_ref().take(4).collect();
+                    if let Ok(unit) = u16::from_str_radix(&hex, 16) {
+                        units.push(unit);
+                    }
+                    continue;
+                }
+                Some(other) => other,
+                None => break,
+            }
+        } else {
+            c
+        };
+        let mut buf = [0u16; 2];
+        units.extend_from_slice(c.encode_utf16(&mut buf));
+    }
+    String::from_utf16_lossy(&units)
+}
+
+/// Key of a property line, `None` for comments and blank lines
+fn line_key(line: &str) -> Option<&str> {
+    let line = line.trim_start();
+    if line.is_empty() || line.starts_with('#') || line.starts_with('!') {
+        return None;
+    }
+    let end = line.find(['=', ':']).unwrap_or(line.len());
+    Some(line[..end].trim_end())
+}
+
+/// Value of `key` in `server.properties` content
+pub fn get_property(content: &str, key: &str) -> Option<String> {
+    content.lines().find_map(|line| {
+        if line_key(line)? != key {
+            return None;
+        }
+        let line = line.trim_start();
+        let value = line.find(['=', ':']).map(|i| &line[i + 1..]).unwrap_or("");
+        Some(unescape(value.trim_start()))
+    })
+}
+
+/// Set `key` in `server.properties` content, replacing the existing line or
+/// appending a new one
+pub fn set_property(content: &str, key: &str, value: &str) -> String {
+    let new_line = format!("{}={}", key, escape(value));
+    let mut replaced = false;
+    let mut lines: Vec<String> = content
+        .lines()
+        .map(|line| {
+            if !replaced && line_key(line) == Some(key) {
+                replaced = true;
+                new_line.clone()
+            } else {
+                line.to_string()
+            }
+        })
+        .collect();
+    if !replaced {
+        lines.push(new_line);
+    }
+    let mut out = lines.join("\n");
+    out.push('\n');
+    out
+}
+
+/// `server.properties` content for a new server
+pub fn render_template(template: &ServerTemplate) -> String {
+    let whitelist = template.whitelist.to_string();
+    let properties = [
+        ("server-port", template.port.to_string()),
+        ("motd", template.motd.clone()),
+        ("white-list", whitelist.clone()),
+        ("enforce-whitelist", whitelist),
+        ("online-mode", "true".to_string()),
+        ("difficulty", "easy".to_string()),
+        ("gamemode", "survival".to_string()),
+        ("max-players", "20".to_string()),
+    ];
+    let mut content = "#Minecraft server properties\n#Generated by DropOut\n".to_string();
+    for (key, value) in properties {
+        content.push_str(&format!("{}={}\n", key, escape(&value)));
+    }
+    content
+}
+
+/// Write `server.properties` from `template` unless the server already has
+/// one, and accept the EULA if the user agreed to it
+pub fn init_server_files(
+    server_dir: &Path,
+    template: &ServerTemplate,
+    accept_eula: bool,
+) -> Result<(), String> {
+    std::fs::create_dir_all(server_dir).map_err(|e| e.to_string())?;
+    let properties = server_dir.join(PROPERTIES_FILE);
+    if !properties.exists() {
+        std::fs::write(&properties, render_template(template)).map_err(|e| e.to_string())?;
+    }
+    if accept_eula {
+        std::fs::write(
+            server_dir.join(EULA_FILE),
+            format!(
+                "#By changing the setting below to TRUE you are indicating your agreement to our EULA ({}).\neula=true\n",
+                EULA_URL
+            ),
+        )
+        .map_err(|e| e.to_string())?;
+    }
+    Ok(())
+}
+
+/// Whether `server_dir` holds a dedicated server set up with
+/// [`init_server_files`]
+pub fn is_server_dir(server_dir: &Path) -> bool {
+    server_dir.join(PROPERTIES_FILE).is_file()
+}
+
+/// JVM and server arguments to start [`SERVER_JAR`] without its GUI
+pub fn launch_args(min_memory: u32, max_memory: u32) -> Vec<String> {
+    vec![
+        format!("-Xms{}M", min_memory),
+        format!("-Xmx{}M", max_memory),
+        "-jar".to_string(),
+        SERVER_JAR.to_string(),
+        "nogui".to_string(),
+    ]
+}
+
+/// RCON port and password from `server.properties` content, or why RCON
+/// can't be used
+pub fn rcon_settings(content: &str) -> Result<(u16, String), String> {
+    if get_property(content, "enable-rcon").as_deref() != Some("true") {
+        return Err("RCON is disabled; set enable-rcon=true and restart the server".to_string());
+    }
+    let password = get_property(content, "rcon.password").unwrap_or_default();
+    if password.is_empty() {
+        return Err("RCON needs a non-empty rcon.password".to_string());
+    }
+    let port = match get_property(content, "rcon.port") {
+        Some(port) => port
+            .parse()
+            .map_err(|_| format!("Invalid rcon.port '{}'", port))?,
+        None => crate::protocol::DEFAULT_RCON_PORT,
+    };
+    Ok((port, password))
+}
+
+/// Validate and store one property, returning the normalized value
+pub fn update_property(server_dir: &Path, key: &str, value: &str) -> Result<String, String> {
+    let value = validate_property(key, value)?;
+    let path = server_dir.join(PROPERTIES_FILE);
+    let content = std::fs::read_to_string(&path).unwrap_or_default();
+    std::fs::write(&path, set_property(&content, key, &value)).map_err(|e| e.to_string())?;
+    Ok(value)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_validate_property() {
+        assert_eq!(validate_property("pvp", "TRUE").unwrap(), "true");
+        assert_eq!(
+            validate_property("server-port", " 25566 ").unwrap(),
+            "25566"
+        );
+        assert!(validate_property("server-port", "70000").is_err());
+        assert!(validate_property("white-list", "yes").is_err());
+        assert_eq!(validate_property("difficulty", "Hard").unwrap(), "hard");
+        assert!(validate_property("gamemode", "god").is_err());
+        assert_eq!(validate_property("level-seed", "abc").unwrap(), "abc");
+        assert!(validate_property("bad=key", "x").is_err());
+    }
+
+    #[test]
+    fn test_set_property_keeps_layout() {
+        let content = "#Minecraft server properties\n#Mon Jan 01\nmotd=Old\npvp=true\n";
+        let updated = set_property(content, "motd", "Hallo Wélt");
+        assert_eq!(
+            updated,
+            "#Minecraft server properties\n#Mon Jan 01\nmotd=Hallo W\\u00e9lt\npvp=true\n"
+        );
+        assert_eq!(get_property(&updated, "motd").unwrap(), "Hallo Wélt");
+        let appended = set_property(&updated, "max-players", "5");
+        assert!(appended.ends_with("pvp=true\nmax-players=5\n"));
+    }
+
+    #[test]
+    fn test_rcon_settings() {
+        assert!(rcon_settings("enable-rcon=false\nrcon.password=x\n").is_err());
+        assert!(rcon_settings("enable-rcon=true\nrcon.password=\n").is_err());
+        assert_eq!(
+            rcon_settings("enable-rcon=true\nrcon.password=pw\n").unwrap(),
+            (25575, "pw".to_string())
+        );
+        assert_eq!(
+            rcon_settings("enable-rcon=true\nrcon.port=25580\nrcon.password=pw\n")
+                .unwrap()
+                .0,
+            25580
+        );
+    }
+
+    #[test]
+    fn test_render_template() {
+        let content = render_template(&ServerTemplate {
+            port: 25570,
+            motd: "Test pack".to_string(),
+            whitelist: true,
+        });
+        assert_eq!(get_property(&content, "server-port").unwrap(), "25570");
+        assert_eq!(get_property(&content, "motd").unwrap(), "Test pack");
+        assert_eq!(get_property(&content, "white-list").unwrap(), "true");
+    }
+
+    #[test]
+    fn test_launch_args() {
+        assert_eq!(
+            launch_args(1024, 4096),
+            vec!["-Xms1024M", "-Xmx4096M", "-jar", "server.jar", "nogui"]
+        );
+    }
+}
@@ -0,0 +1,230 @@
+//! `whitelist.json` and `ops.json` of managed servers.
+//!
+//! Names are resolved to UUIDs through the Mojang API, or to the
+//! `OfflinePlayer:<name>` UUID when the server has `online-mode=false`, so the
+//! entries match what the server itself would write.
+
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+use ts_rs::TS;
+
+pub const WHITELIST_FILE: &str = "whitelist.json";
+pub const OPS_FILE: &str = "ops.json";
+
+const PROFILE_API: &str = "https://api.mojang.com/users/profiles/minecraft";
+
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+#[ts(export, export_to = "dropout-core/server.ts")]
+pub struct PlayerEntry {
+    pub uuid: String,
+    pub name: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export, export_to = "dropout-core/server.ts")]
+pub struct OpEntry {
+    pub uuid: String,
+    pub name: String,
+    pub level: u8, // 1-4, see `op-permission-level`
+    #[serde(default)]
+    pub bypasses_player_limit: bool,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export, export_to = "dropout-core/server.ts")]
+pub struct ServerPlayers {
+    pub whitelist: Vec<PlayerEntry>,
+    pub ops: Vec<OpEntry>,
+}
+
+#[derive(Deserialize)]
+struct MojangProfile {
+    id: String,
+    name: String,
+}
+
+/// Whether the server in `server_dir` authenticates players (the default)
+pub fn is_online_mode(server_dir: &Path) -> bool {
+    std::fs::read_to_string(server_dir.join(super::PROPERTIES_FILE))
+        .ok()
+        .and_then(|content| super::get_property(&content, "online-mode"))
+        .is_none_or(|value| value != "false")
+}
+
+/// Resolve a player name to the entry the server would store for it
+pub async fn resolve_player(name: &str, online_mode: bool) -> Result<PlayerEntry, String> {
+    let name = name.trim();
+    if name.is_empty() {
+        return Err("Player name is empty".to_string());
+    }
+    if !online_mode {
+        return Ok(PlayerEntry {
+            uuid: crate::auth::generate_offline_uuid(name, "vanilla")?,
+            name: name.to_string(),
+        });
+    }
+
+    let resp = reqwest::get(format!("{}/{}", PROFILE_API, name))
+        .await
+        .map_err(|e| format!("Request error: {}", e))?;
+    let status = resp.status();
+    if status == reqwest::StatusCode::NOT_FOUND || status == reqwest::StatusCode::NO_CONTENT {
+        return Err(format!("Player {} not found", name));
+    }
+    if !status.is_success() {
+        return Err(format!("Mojang API returned {} for {}", status, name));
+    }
+    let profile: MojangProfile = resp
+        .json()
+        .await
+        .map_err(|e| format!("Invalid Mojang API response: {}", e))?;
+    Ok(PlayerEntry {
+        uuid: hyphenate(&profile.id)?,
+        name: profile.name,
+    })
+}
+
+/// Mojang returns UUIDs without hyphens; server files use the hyphenated form
+fn hyphenate(id: &str) -> Result<String, String> {
+    uuid::Uuid::parse_str(id)
+        .map(|u| u.hyphenated().to_string())
+        .map_err(|_| format!("Invalid UUID from Mojang API: {}", id))
+}
+
+/// Whether an entry is meant by `player`, a name (case-insensitive) or UUID
+fn matches(uuid: &str, name: &str, player: &str) -> bool {
+    name.eq_ignore_ascii_case(player) || uuid.eq_ignore_ascii_case(player)
+}
+
+fn read_list<T: DeserializeOwned>(path: &Path) -> Result<Vec<T>, String> {
+    match std::fs::read_to_string(path) {
+        Ok(content) if content.trim().is_empty() => Ok(Vec::new()),
+        Ok(content) => serde_json::from_str(&content)
+            .map_err(|e| format!("Failed to parse {}: {}", path.display(), e)),
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(Vec::new()),
+        Err(e) => Err(e.to_string()),
+    }
+}
+
+fn write_list<T: Serialize>(path: &Path, list: &[T]) -> Result<(), String> {
+    let content = serde_json::to_string_pretty(list).map_err(|e| e.to_string())?;
+    std::fs::write(path, content).map_err(|e| e.to_string())
+}
+
+pub fn read_players(server_dir: &Path) -> Result<ServerPlayers, String> {
+    Ok(ServerPlayers {
+        whitelist: read_list(&server_dir.join(WHITELIST_FILE))?,
+        ops: read_list(&server_dir.join(OPS_FILE))?,
+    })
+}
+
+/// Add or replace a whitelist entry
+pub fn whitelist_add(players: &mut ServerPlayers, entry: PlayerEntry) {
+    players
+        .whitelist
+        .retain(|e| !e.uuid.eq_ignore_ascii_case(&entry.uuid));
+    players.whitelist.push(entry);
+}
+
+/// Add an operator or change their level
+pub fn op_add(players: &mut ServerPlayers, entry: PlayerEntry, level: u8) -> Result<(), String> {
+    if !(1..=4).contains(&level) {
+        return Err(format!(
+            "Operator level must be between 1 and 4, got {}",
+            level
+        ));
+    }
+    let bypasses_player_limit = players
+        .ops
+        .iter()
+        .find(|e| e.uuid.eq_ignore_ascii_case(&entry.uuid))
+        .is_some_and(|e| e.bypasses_player_limit);
+    players
+        .ops
+        .retain(|e| !e.uuid.eq_ignore_ascii_case(&entry.uuid));
+    players.ops.push(OpEntry {
+        uuid: entry.uuid,
+        name: entry.name,
+        level,
+        bypasses_player_limit,
+    });
+    Ok(())
+}
+
+/// Remove `player` (name or UUID) from the whitelist or the operators;
+/// returns whether anything was removed
+pub fn remove(players: &mut ServerPlayers, list: &str, player: &str) -> Result<bool, String> {
+    let before = players.whitelist.len() + players.ops.len();
+    match list {
+        "whitelist" => players
+            .whitelist
+            .retain(|e| !matches(&e.uuid, &e.name, player)),
+        "ops" => players.ops.retain(|e| !matches(&e.uuid, &e.name, player)),
+        other => return Err(format!("Unknown player list '{}'", other)),
+    }
+    Ok(players.whitelist.len() + players.ops.len() < before)
+}
+
+pub fn write_players(server_dir: &Path, players: &ServerPlayers) -> Result<(), String> {
+    write_list(&server_dir.join(WHITELIST_FILE), &players.whitelist)?;
+    write_list(&server_dir.join(OPS_FILE), &players.ops)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entry(uuid: &str, name: &str) -> PlayerEntry {
+        PlayerEntry {
+            uuid: uuid.to_string(),
+            name: name.to_string(),
+        }
+    }
+
+    #[test]
+    fn test_hyphenate() {
+        assert_eq!(
+            hyphenate("069a79f444e94726a5befca90e38aaf5").unwrap(),
+            "069a79f4-44e9-4726-a5be-fca90e38aaf5"
+        );
+        assert!(hyphenate("notch").is_err());
+    }
+
+    #[test]
+    fn test_ops_json_format() {
+        let mut players = ServerPlayers::default();
+        op_add(&mut players, entry("a-1", "Alex"), 4).unwrap();
+        let json = serde_json::to_value(&players.ops).unwrap();
+        assert_eq!(
+            json,
+            serde_json::json!([
+                {"uuid": "a-1", "name": "Alex", "level": 4, "bypassesPlayerLimit": false}
+            ])
+        );
+        assert!(op_add(&mut players, entry("a-1", "Alex"), 5).is_err());
+    }
+
+    #[test]
+    fn test_add_and_remove() {
+        let mut players = ServerPlayers::default();
+        whitelist_add(&mut players, entry("a-1", "Alex"));
+        whitelist_add(&mut players, entry("A-1", "Alex"));
+        whitelist_add(&mut players, entry("s-2", "Steve"));
+        assert_eq!(players.whitelist.len(), 2);
+
+        op_add(&mut players, entry("s-2", "Steve"), 2).unwrap();
+        op_add(&mut players, entry("s-2", "Steve"), 4).unwrap();
+        assert_eq!(players.ops.len(), 1);
+        assert_eq!(players.ops[0].level, 4);
+
+        assert!(remove(&mut players, "whitelist", "alex").unwrap());
+        assert!(!remove(&mut players, "whitelist", "alex").unwrap());
+        assert!(remove(&mut players, "ops", "S-2").unwrap());
+        assert!(remove(&mut players, "bans", "x").is_err());
+        assert_eq!(players.whitelist.len(), 1);
+        assert!(players.ops.is_empty());
+    }
+}
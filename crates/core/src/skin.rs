@@ -0,0 +1,245 @@
+//! Skins and capes of Microsoft accounts.
+//!
+//! The Minecraft Services API lists the textures of a profile and changes the
+//! active skin and cape. Texture URLs end in the texture's hash, which is
+//! used as the file name of a local cache so the UI can show the player's
+//! skin (and head) without downloading it on every start.
+
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+use ts_rs::TS;
+
+const PROFILE_URL: &str = "https://api.minecraftservices.com/minecraft/profile";
+
+fn get_client() -> reqwest::Client {
+    reqwest::Client::builder()
+        .user_agent("DropOut/1.0")
+        .build()
+        .unwrap_or_else(|_| reqwest::Client::new())
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, TS)]
+#[serde(rename_all = "UPPERCASE")]
+#[ts(export, export_to = "dropout-core/skin.ts")]
+pub enum SkinVariant {
+    Classic,
+    Slim,
+}
+
+impl SkinVariant {
+    pub fn parse(variant: &str) -> Result<Self, String> {
+        match variant.to_ascii_lowercase().as_str() {
+            "classic" | "wide" => Ok(SkinVariant::Classic),
+            "slim" => Ok(SkinVariant::Slim),
+            other => Err(format!("Unknown skin variant: {}", other)),
+        }
+    }
+
+    fn as_str(self) -> &'static str {
+        match self {
+            SkinVariant::Classic => "classic",
+            SkinVariant::Slim => "slim",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export, export_to = "dropout-core/skin.ts")]
+pub struct SkinTexture {
+    pub id: String,
+    pub state: String, // "ACTIVE" or "INACTIVE"
+    pub url: String,
+    pub variant: Option<SkinVariant>,
+    #[serde(default)]
+    pub alias: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export, export_to = "dropout-core/skin.ts")]
+pub struct CapeTexture {
+    pub id: String,
+    pub state: String,
+    pub url: String,
+    #[serde(default)]
+    pub alias: Option<String>,
+}
+
+/// A profile with its skins and capes
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export, export_to = "dropout-core/skin.ts")]
+pub struct SkinProfile {
+    pub id: String,
+    pub name: String,
+    #[serde(default)]
+    pub skins: Vec<SkinTexture>,
+    #[serde(default)]
+    pub capes: Vec<CapeTexture>,
+}
+
+impl SkinProfile {
+    pub fn active_skin(&self) -> Option<&SkinTexture> {
+        self.skins.iter().find(|s| s.state == "ACTIVE")
+    }
+
+    pub fn active_cape(&self) -> Option<&CapeTexture> {
+        self.capes.iter().find(|c| c.state == "ACTIVE")
+    }
+}
+
+/// A profile and the cached files of its active textures
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export, export_to = "dropout-core/skin.ts")]
+pub struct PlayerSkin {
+    pub profile: SkinProfile,
+    pub skin_path: Option<String>,
+    pub cape_path: Option<String>,
+}
+
+async fn read_profile(resp: reqwest::Response, action: &str) -> Result<SkinProfile, String> {
+    if !resp.status().is_success() {
+        let status = resp.status();
+        let text = resp.text().await.unwrap_or_default();
+        return Err(format!("{} failed: {} - {}", action, status, text));
+    }
+    resp.json().await.map_err(|e| e.to_string())
+}
+
+/// Skins and capes of the account `access_token` belongs to
+pub async fn fetch_profile(access_token: &str) -> Result<SkinProfile, String> {
+    let resp = get_client()
+        .get(PROFILE_URL)
+        .bearer_auth(access_token)
+        .send()
+        .await
+        .map_err(|e| e.to_string())?;
+    read_profile(resp, "Profile fetch").await
+}
+
+/// Upload a skin PNG and make it the active skin
+pub async fn upload_skin(
+    access_token: &str,
+    png: Vec<u8>,
+    variant: SkinVariant,
+) -> Result<SkinProfile, String> {
+    crate::auth::validate_skin_png(&png)?;
+    let file = reqwest::multipart::Part::bytes(png)
+        .file_name("skin.png")
+        .mime_str("image/png")
+        .map_err(|e| e.to_string())?;
+    let form = reqwest::multipart::Form::new()
+        .text("variant", variant.as_str())
+        .part("file", file);
+    let resp = get_client()
+        .post(format!("{}/skins", PROFILE_URL))
+        .bearer_auth(access_token)
+        .multipart(form)
+        .send()
+        .await
+        .map_err(|e| e.to_string())?;
+    read_profile(resp, "Skin upload").await
+}
+
+/// Show one of the account's capes, or hide the cape with `None`
+pub async fn set_cape(access_token: &str, cape_id: Option<&str>) -> Result<SkinProfile, String> {
+    let url = format!("{}/capes/active", PROFILE_URL);
+    let request = match cape_id {
+        Some(id) => get_client()
+            .put(url)
+            .json(&serde_json::json!({ "capeId": id })),
+        None => get_client().delete(url),
+    };
+    let resp = request
+        .bearer_auth(access_token)
+        .send()
+        .await
+        .map_err(|e| e.to_string())?;
+    read_profile(resp, "Cape change").await
+}
+
+/// Hash at the end of a texture URL
+/// (`http://textures.minecraft.net/texture/<hash>`)
+fn texture_hash(url: &str) -> Option<&str> {
+    url.rsplit('/')
+        .next()
+        .filter(|hash| !hash.is_empty() && hash.chars().all(|c| c.is_ascii_alphanumeric()))
+}
+
+/// Local copy of a texture in `cache_dir`, downloaded on first use.
+/// Textures never change under the same hash, so a cached file is reused.
+pub async fn cache_texture(url: &str, cache_dir: &Path) -> Result<PathBuf, String> {
+    let hash = texture_hash(url).ok_or_else(|| format!("Unexpected texture URL: {}", url))?;
+    let path = cache_dir.join(format!("{}.png", hash));
+    if path.exists() {
+        return Ok(path);
+    }
+    // The API hands out http:// URLs; the texture server serves both
+    let url = url.replacen("http://", "https://", 1);
+    let data = get_client()
+        .get(&url)
+        .send()
+        .await
+        .and_then(|resp| resp.error_for_status())
+        .map_err(|e| format!("Failed to download texture: {}", e))?
+        .bytes()
+        .await
+        .map_err(|e| e.to_string())?;
+    tokio::fs::create_dir_all(cache_dir)
+        .await
+        .map_err(|e| e.to_string())?;
+    tokio::fs::write(&path, &data)
+        .await
+        .map_err(|e| e.to_string())?;
+    Ok(path)
+}
+
+/// Cache the active skin and cape of `profile`. A texture that can't be
+/// downloaded is left out rather than failing the whole lookup.
+pub async fn cache_active(profile: SkinProfile, cache_dir: &Path) -> PlayerSkin {
+    let skin_path = match profile.active_skin() {
+        Some(skin) => cache_texture(&skin.url, cache_dir).await.ok(),
+        None => None,
+    };
+    let cape_path = match profile.active_cape() {
+        Some(cape) => cache_texture(&cape.url, cache_dir).await.ok(),
+        None => None,
+    };
+    PlayerSkin {
+        profile,
+        skin_path: skin_path.map(|p| p.to_string_lossy().to_string()),
+        cape_path: cape_path.map(|p| p.to_string_lossy().to_string()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_profile_textures() {
+        let profile: SkinProfile = serde_json::from_str(
+            r#"{"id": "069a79f444e94726a5befca90e38aaf5", "name": "Notch",
+                "skins": [
+                    {"id": "a", "state": "INACTIVE", "url": "http://textures.minecraft.net/texture/111", "variant": "CLASSIC"},
+                    {"id": "b", "state": "ACTIVE", "url": "http://textures.minecraft.net/texture/292009a4925b58f02c77dadc3ecef07ea4c7472f64e0fdc32ce5522489362680", "variant": "SLIM"}
+                ],
+                "capes": [{"id": "c", "state": "INACTIVE", "url": "http://textures.minecraft.net/texture/333", "alias": "Migrator"}]}"#,
+        )
+        .unwrap();
+        let skin = profile.active_skin().unwrap();
+        assert_eq!(skin.id, "b");
+        assert_eq!(skin.variant, Some(SkinVariant::Slim));
+        assert!(profile.active_cape().is_none());
+        assert_eq!(
+            texture_hash(&skin.url),
+            Some("292009a4925b58f02c77dadc3ecef07ea4c7472f64e0fdc32ce5522489362680")
+        );
+        assert_eq!(texture_hash("http://textures.minecraft.net/texture/"), None);
+        assert_eq!(texture_hash("http://example.com/skin.png"), None);
+        assert_eq!(SkinVariant::parse("Slim").unwrap(), SkinVariant::Slim);
+        assert!(SkinVariant::parse("tall").is_err());
+    }
+}
@@ -0,0 +1,156 @@
+//! Differences between two version JSONs, e.g. to explain why an upgrade
+//! suddenly needs a newer Java.
+
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, BTreeSet};
+use ts_rs::TS;
+
+use super::game_version::GameVersion;
+
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export, export_to = "dropout-core/version_diff.ts")]
+pub struct Change<T> {
+    pub from: Option<T>,
+    pub to: Option<T>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export, export_to = "dropout-core/version_diff.ts")]
+pub struct LibraryChange {
+    pub name: String, // "group:artifact[:classifier]"
+    pub from: String,
+    pub to: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export, export_to = "dropout-core/version_diff.ts")]
+pub struct VersionDiff {
+    pub from_id: String,
+    pub to_id: String,
+    /// Java major version; `None` in a `Change` when the JSON doesn't say
+    pub java: Option<Change<u64>>,
+    pub main_class: Option<Change<String>>,
+    pub asset_index: Option<Change<String>>,
+    pub libraries_added: Vec<String>,
+    pub libraries_removed: Vec<String>,
+    pub libraries_changed: Vec<LibraryChange>,
+}
+
+fn change<T: PartialEq>(from: Option<T>, to: Option<T>) -> Option<Change<T>> {
+    (from != to).then_some(Change { from, to })
+}
+
+/// Split "group:artifact:version[:classifier]" into ("group:artifact[:classifier]", version)
+fn library_key(name: &str) -> (String, String) {
+    let parts: Vec<&str> = name.split(':').collect();
+    match parts.as_slice() {
+        [group, artifact, version] => (format!("{}:{}", group, artifact), version.to_string()),
+        [group, artifact, version, classifier @ ..] => (
+            format!("{}:{}:{}", group, artifact, classifier.join(":")),
+            version.to_string(),
+        ),
+        _ => (name.to_string(), String::new()),
+    }
+}
+
+/// Library versions by key. A key can map to several versions when the JSON
+/// picks one per platform through rules (e.g. LWJGL 3.2.1 on macOS only).
+fn libraries(version: &GameVersion) -> BTreeMap<String, String> {
+    let mut versions: BTreeMap<String, BTreeSet<String>> = BTreeMap::new();
+    for lib in &version.libraries {
+        let (key, version) = library_key(&lib.name);
+        versions.entry(key).or_default().insert(version);
+    }
+    versions
+        .into_iter()
+        .map(|(key, set)| (key, set.into_iter().collect::<Vec<_>>().join(", ")))
+        .collect()
+}
+
+pub fn diff(from: &GameVersion, to: &GameVersion) -> VersionDiff {
+    let from_libs = libraries(from);
+    let to_libs = libraries(to);
+
+    let mut libraries_added = Vec::new();
+    let mut libraries_changed = Vec::new();
+    for (name, version) in &to_libs {
+        match from_libs.get(name) {
+            None => libraries_added.push(format!("{}:{}", name, version)),
+            Some(old) if old != version => libraries_changed.push(LibraryChange {
+                name: name.clone(),
+                from: old.clone(),
+                to: version.clone(),
+            }),
+            Some(_) => {}
+        }
+    }
+    let libraries_removed = from_libs
+        .iter()
+        .filter(|(name, _)| !to_libs.contains_key(*name))
+        .map(|(name, version)| format!("{}:{}", name, version))
+        .collect();
+
+    VersionDiff {
+        from_id: from.id.clone(),
+        to_id: to.id.clone(),
+        java: change(
+            from.java_version.as_ref().map(|j| j.major_version),
+            to.java_version.as_ref().map(|j| j.major_version),
+        ),
+        main_class: change(Some(from.main_class.clone()), Some(to.main_class.clone())),
+        asset_index: change(
+            from.asset_index.as_ref().map(|a| a.id.clone()),
+            to.asset_index.as_ref().map(|a| a.id.clone()),
+        ),
+        libraries_added,
+        libraries_removed,
+        libraries_changed,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn fixture(json: &str) -> GameVersion {
+        serde_json::from_str(json).unwrap()
+    }
+
+    #[test]
+    fn test_library_key() {
+        assert_eq!(
+            library_key("org.lwjgl:lwjgl:3.3.3:natives-linux"),
+            (
+                "org.lwjgl:lwjgl:natives-linux".to_string(),
+                "3.3.3".to_string()
+            )
+        );
+        assert_eq!(
+            library_key("com.mojang:brigadier:1.0.18"),
+            ("com.mojang:brigadier".to_string(), "1.0.18".to_string())
+        );
+    }
+
+    #[test]
+    fn test_diff_java_upgrade() {
+        let from = fixture(include_str!("../tests/fixtures/versions/1.16.5.json"));
+        let to = fixture(include_str!("../tests/fixtures/versions/1.20.4.json"));
+        let diff = diff(&from, &to);
+
+        let java = diff.java.unwrap();
+        assert_eq!((java.from, java.to), (Some(8), Some(17)));
+        assert!(diff.main_class.is_none());
+        assert!(
+            diff.libraries_changed
+                .iter()
+                .any(|c| c.name == "org.lwjgl:lwjgl" && c.from.starts_with("3.2"))
+        );
+
+        let same = super::diff(&to, &to);
+        assert!(same.java.is_none());
+        assert!(same.libraries_added.is_empty() && same.libraries_changed.is_empty());
+    }
+}
@@ -5,7 +5,7 @@
 //! This module provides functionality to merge these partial versions with
 //! their parent versions to create a complete, launchable version profile.
 
-use crate::core::game_version::{Arguments, GameVersion};
+use crate::game_version::{Arguments, GameVersion};
 use std::error::Error;
 
 /// Merge a child version (mod loader) with its parent version (vanilla).
@@ -50,6 +50,8 @@ pub fn merge_versions(child: GameVersion, parent: GameVersion) -> GameVersion {
         assets: child.assets.or(parent.assets),
         // Use parent's version type if child doesn't specify
         version_type: child.version_type.or(parent.version_type),
+        // Loaders don't ship their own logging config
+        logging: child.logging.or(parent.logging),
     }
 }
 
@@ -140,7 +142,7 @@ where
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::core::game_version::{DownloadArtifact, Downloads, Library};
+    use crate::game_version::{DownloadArtifact, Downloads, Library};
 
     fn create_test_library(name: &str) -> Library {
         Library {
@@ -149,6 +151,7 @@ mod tests {
             rules: None,
             natives: None,
             url: None,
+            extract: None,
         }
     }
 
@@ -166,6 +169,7 @@ mod tests {
             inherits_from: Some("1.20.4".to_string()),
             assets: None,
             version_type: None,
+            logging: None,
         };
 
         let parent = GameVersion {
@@ -188,6 +192,7 @@ mod tests {
             inherits_from: None,
             assets: None,
             version_type: Some("release".to_string()),
+            logging: None,
         };
 
         let merged = merge_versions(child, parent);
@@ -224,6 +229,7 @@ mod tests {
             inherits_from: Some("1.20.4".to_string()),
             assets: None,
             version_type: None,
+            logging: None,
         };
 
         let without_inheritance = GameVersion {
@@ -238,6 +244,7 @@ mod tests {
             inherits_from: None,
             assets: None,
             version_type: None,
+            logging: None,
         };
 
         assert!(needs_inheritance_resolution(&with_inheritance));
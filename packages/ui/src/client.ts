@@ -1,30 +1,119 @@
 import { invoke } from "@tauri-apps/api/core";
 import type {
   Account,
+  ActiveWork,
+  AssistantHealth,
+  BackupEntry,
+  CatalogFilter,
+  ConfigChange,
+  CurseForgeFile,
+  CurseForgeSearch,
+  DeepLink,
+  DeletePreview,
+  DeletedInstance,
   DeviceCodeResponse,
+  DotMinecraftImport,
+  DownloadAuditEntry,
+  DownloadSessionInfo,
+  ExportSummary,
   FabricGameVersion,
   FabricLoaderEntry,
   FabricLoaderVersion,
   FileInfo,
   ForgeVersion,
-  GithubRelease,
+  GithubReleasesResponse,
+  GpuInfo,
   InstalledFabricVersion,
   InstalledForgeVersion,
+  InstalledMod,
+  InstalledNeoForgeVersion,
+  InstalledOptiFineVersion,
   InstalledVersion,
   Instance,
+  InstanceNotes,
+  InstanceStatus,
+  JavaAgent,
   JavaCatalog,
   JavaDownloadInfo,
   JavaInstallation,
+  KnowledgeEntry,
+  LanPeer,
+  LanSyncInfo,
+  LaunchRecord,
+  LaunchReport,
   LauncherConfig,
+  LocalApiInfo,
+  LocaleInfo,
   Message,
   MigrationResult,
+  ModListImport,
+  ModScanReport,
   ModelInfo,
+  ModrinthSearch,
+  ModrinthVersion,
+  NeoForgeVersion,
+  OptiFineVersion,
+  PackRelease,
+  PackSource,
+  PackUpdateSummary,
+  ParentalStatus,
   PastebinResponse,
   PendingJavaDownload,
+  PlayerSkin,
+  ProfileImportSummary,
+  RecoveryAction,
+  RecoveryReport,
+  ResetReport,
+  ResetScope,
+  RollbackResult,
+  RulesReport,
+  Schedule,
+  SearchResult,
+  ServerPlayers,
+  ServerTemplate,
+  ServiceStatus,
+  UsageStats,
   Version,
+  VersionDiff,
   VersionMetadata,
 } from "@/types";
 
+export function addNoteAttachment(
+  instanceId: string,
+  sourcePath: string,
+): Promise<string> {
+  return invoke<string>("add_note_attachment", {
+    instanceId,
+    sourcePath,
+  });
+}
+
+export function addSchedule(schedule: Schedule): Promise<Schedule> {
+  return invoke<Schedule>("add_schedule", {
+    schedule,
+  });
+}
+
+export function addServerPlayer(
+  instanceId: string,
+  list: string,
+  name: string,
+  level: number | null,
+): Promise<ServerPlayers> {
+  return invoke<ServerPlayers>("add_server_player", {
+    instanceId,
+    list,
+    name,
+    level,
+  });
+}
+
+export function applyRecovery(action: RecoveryAction): Promise<void> {
+  return invoke<void>("apply_recovery", {
+    action,
+  });
+}
+
 export function assistantChat(messages: Message[]): Promise<Message> {
   return invoke<Message>("assistant_chat", {
     messages,
@@ -41,10 +130,46 @@ export function assistantCheckHealth(): Promise<boolean> {
   return invoke<boolean>("assistant_check_health");
 }
 
+export function assistantCheckHealthDetailed(): Promise<AssistantHealth> {
+  return invoke<AssistantHealth>("assistant_check_health_detailed");
+}
+
+export function backupInstanceToCloud(
+  instanceId: string,
+  folders: string[] | null,
+): Promise<BackupEntry> {
+  return invoke<BackupEntry>("backup_instance_to_cloud", {
+    instanceId,
+    folders,
+  });
+}
+
+export function cancelDownload(sessionId: string): Promise<void> {
+  return invoke<void>("cancel_download", {
+    sessionId,
+  });
+}
+
 export function cancelJavaDownload(): Promise<void> {
   return invoke<void>("cancel_java_download");
 }
 
+export function checkInstanceIntegrity(
+  instanceId: string,
+): Promise<InstanceStatus> {
+  return invoke<InstanceStatus>("check_instance_integrity", {
+    instanceId,
+  });
+}
+
+export function checkModpackUpdate(
+  instanceId: string,
+): Promise<PackRelease | null> {
+  return invoke<PackRelease | null>("check_modpack_update", {
+    instanceId,
+  });
+}
+
 export function checkVersionInstalled(
   instanceId: string,
   versionId: string,
@@ -61,15 +186,31 @@ export function completeMicrosoftLogin(deviceCode: string): Promise<Account> {
   });
 }
 
+export function confirmExit(): Promise<void> {
+  return invoke<void>("confirm_exit");
+}
+
 export function createInstance(name: string): Promise<Instance> {
   return invoke<Instance>("create_instance", {
     name,
   });
 }
 
-export function deleteInstance(instanceId: string): Promise<void> {
-  return invoke<void>("delete_instance", {
+export function deleteCloudBackup(backupId: string): Promise<void> {
+  return invoke<void>("delete_cloud_backup", {
+    backupId,
+  });
+}
+
+export function deleteInstance(
+  instanceId: string,
+  keepSaves: boolean | null,
+  exportPath: string | null,
+): Promise<DeletedInstance> {
+  return invoke<DeletedInstance>("delete_instance", {
     instanceId,
+    keepSaves,
+    exportPath,
   });
 }
 
@@ -97,6 +238,16 @@ export function detectJava(): Promise<JavaInstallation[]> {
   return invoke<JavaInstallation[]>("detect_java");
 }
 
+export function diffVersions(
+  fromVersionId: string,
+  toVersionId: string,
+): Promise<VersionDiff> {
+  return invoke<VersionDiff>("diff_versions", {
+    fromVersionId,
+    toVersionId,
+  });
+}
+
 export function downloadAdoptiumJava(
   majorVersion: number,
   imageType: string,
@@ -109,6 +260,12 @@ export function downloadAdoptiumJava(
   });
 }
 
+export function downloadModpackArchive(source: PackSource): Promise<string> {
+  return invoke<string>("download_modpack_archive", {
+    source,
+  });
+}
+
 export function duplicateInstance(
   instanceId: string,
   newName: string,
@@ -119,6 +276,60 @@ export function duplicateInstance(
   });
 }
 
+export function evaluateRulesReport(
+  instanceId: string,
+  versionId: string,
+  os: string | null,
+  arch: string | null,
+): Promise<RulesReport> {
+  return invoke<RulesReport>("evaluate_rules_report", {
+    instanceId,
+    versionId,
+    os,
+    arch,
+  });
+}
+
+export function exportDiagnostics(instanceId: string): Promise<string> {
+  return invoke<string>("export_diagnostics", {
+    instanceId,
+  });
+}
+
+export function exportInstance(
+  instanceId: string,
+  format: string,
+  dest: string | null,
+): Promise<ExportSummary> {
+  return invoke<ExportSummary>("export_instance", {
+    instanceId,
+    format,
+    dest,
+  });
+}
+
+export function exportModList(
+  instanceId: string,
+  format: string,
+): Promise<string> {
+  return invoke<string>("export_mod_list", {
+    instanceId,
+    format,
+  });
+}
+
+export function exportProfile(
+  path: string,
+  passphrase: string,
+  includeAccounts: boolean,
+): Promise<void> {
+  return invoke<void>("export_profile", {
+    path,
+    passphrase,
+    includeAccounts,
+  });
+}
+
 export function fetchAdoptiumJava(
   majorVersion: number,
   imageType: string,
@@ -133,22 +344,58 @@ export function fetchAvailableJavaVersions(): Promise<number[]> {
   return invoke<number[]>("fetch_available_java_versions");
 }
 
-export function fetchJavaCatalog(): Promise<JavaCatalog> {
-  return invoke<JavaCatalog>("fetch_java_catalog");
+export function fetchJavaCatalog(
+  filter: CatalogFilter | null,
+): Promise<JavaCatalog> {
+  return invoke<JavaCatalog>("fetch_java_catalog", {
+    filter,
+  });
+}
+
+export function fetchPlayerSkin(): Promise<PlayerSkin> {
+  return invoke<PlayerSkin>("fetch_player_skin");
 }
 
 export function getActiveAccount(): Promise<Account | null> {
   return invoke<Account | null>("get_active_account");
 }
 
+export function getActiveDownloads(): Promise<DownloadSessionInfo[]> {
+  return invoke<DownloadSessionInfo[]>("get_active_downloads");
+}
+
 export function getActiveInstance(): Promise<Instance | null> {
   return invoke<Instance | null>("get_active_instance");
 }
 
+export function getActiveWork(): Promise<ActiveWork> {
+  return invoke<ActiveWork>("get_active_work");
+}
+
 export function getConfigPath(): Promise<string> {
   return invoke<string>("get_config_path");
 }
 
+export function getCurseforgeFiles(
+  modId: number,
+  instanceId: string | null,
+): Promise<CurseForgeFile[]> {
+  return invoke<CurseForgeFile[]>("get_curseforge_files", {
+    modId,
+    instanceId,
+  });
+}
+
+export function getDownloadAudit(
+  instanceId: string,
+  limit: number | null,
+): Promise<DownloadAuditEntry[]> {
+  return invoke<DownloadAuditEntry[]>("get_download_audit", {
+    instanceId,
+    limit,
+  });
+}
+
 export function getFabricGameVersions(): Promise<FabricGameVersion[]> {
   return invoke<FabricGameVersion[]>("get_fabric_game_versions");
 }
@@ -177,8 +424,16 @@ export function getForgeVersionsForGame(
   });
 }
 
-export function getGithubReleases(): Promise<GithubRelease[]> {
-  return invoke<GithubRelease[]>("get_github_releases");
+export function getGithubReleases(
+  force: boolean | null,
+): Promise<GithubReleasesResponse> {
+  return invoke<GithubReleasesResponse>("get_github_releases", {
+    force,
+  });
+}
+
+export function getGpuInfo(): Promise<GpuInfo> {
+  return invoke<GpuInfo>("get_gpu_info");
 }
 
 export function getInstance(instanceId: string): Promise<Instance> {
@@ -187,6 +442,90 @@ export function getInstance(instanceId: string): Promise<Instance> {
   });
 }
 
+export function getInstanceDeletePreview(
+  instanceId: string,
+): Promise<DeletePreview> {
+  return invoke<DeletePreview>("get_instance_delete_preview", {
+    instanceId,
+  });
+}
+
+export function getInstanceNotes(instanceId: string): Promise<InstanceNotes> {
+  return invoke<InstanceNotes>("get_instance_notes", {
+    instanceId,
+  });
+}
+
+export function getLanSyncInfo(): Promise<LanSyncInfo> {
+  return invoke<LanSyncInfo>("get_lan_sync_info");
+}
+
+export function getLastLaunchReport(
+  instanceId: string,
+): Promise<LaunchReport | null> {
+  return invoke<LaunchReport | null>("get_last_launch_report", {
+    instanceId,
+  });
+}
+
+export function getLaunchHistory(instanceId: string): Promise<LaunchRecord[]> {
+  return invoke<LaunchRecord[]>("get_launch_history", {
+    instanceId,
+  });
+}
+
+export function getLocalApiInfo(): Promise<LocalApiInfo> {
+  return invoke<LocalApiInfo>("get_local_api_info");
+}
+
+export function getLocaleInfo(): Promise<LocaleInfo> {
+  return invoke<LocaleInfo>("get_locale_info");
+}
+
+export function getModIcon(
+  instanceId: string,
+  fileName: string,
+): Promise<string | null> {
+  return invoke<string | null>("get_mod_icon", {
+    instanceId,
+    fileName,
+  });
+}
+
+export function getModrinthVersions(
+  projectId: string,
+  instanceId: string | null,
+): Promise<ModrinthVersion[]> {
+  return invoke<ModrinthVersion[]>("get_modrinth_versions", {
+    projectId,
+    instanceId,
+  });
+}
+
+export function getNeoforgeGameVersions(): Promise<string[]> {
+  return invoke<string[]>("get_neoforge_game_versions");
+}
+
+export function getNeoforgeVersionsForGame(
+  gameVersion: string,
+): Promise<NeoForgeVersion[]> {
+  return invoke<NeoForgeVersion[]>("get_neoforge_versions_for_game", {
+    gameVersion,
+  });
+}
+
+export function getOptifineVersionsForGame(
+  gameVersion: string,
+): Promise<OptiFineVersion[]> {
+  return invoke<OptiFineVersion[]>("get_optifine_versions_for_game", {
+    gameVersion,
+  });
+}
+
+export function getParentalStatus(): Promise<ParentalStatus> {
+  return invoke<ParentalStatus>("get_parental_status");
+}
+
 export function getPendingJavaDownloads(): Promise<PendingJavaDownload[]> {
   return invoke<PendingJavaDownload[]>("get_pending_java_downloads");
 }
@@ -199,10 +538,34 @@ export function getRecommendedJava(
   });
 }
 
+export function getRecoveryReport(): Promise<RecoveryReport | null> {
+  return invoke<RecoveryReport | null>("get_recovery_report");
+}
+
+export function getServerPlayers(instanceId: string): Promise<ServerPlayers> {
+  return invoke<ServerPlayers>("get_server_players", {
+    instanceId,
+  });
+}
+
+export function getServiceStatus(): Promise<ServiceStatus[]> {
+  return invoke<ServiceStatus[]>("get_service_status");
+}
+
 export function getSettings(): Promise<LauncherConfig> {
   return invoke<LauncherConfig>("get_settings");
 }
 
+export function getShaderLoader(instanceId: string): Promise<string | null> {
+  return invoke<string | null>("get_shader_loader", {
+    instanceId,
+  });
+}
+
+export function getUsageStats(): Promise<UsageStats> {
+  return invoke<UsageStats>("get_usage_stats");
+}
+
 export function getVersionJavaVersion(
   instanceId: string,
   versionId: string,
@@ -233,6 +596,72 @@ export function getVersionsOfInstance(instanceId: string): Promise<Version[]> {
   });
 }
 
+export function importDotMinecraft(
+  path: string | null,
+  name: string | null,
+  copy: boolean,
+): Promise<DotMinecraftImport> {
+  return invoke<DotMinecraftImport>("import_dot_minecraft", {
+    path,
+    name,
+    copy,
+  });
+}
+
+export function importInstanceFromZip(
+  path: string,
+  name: string | null,
+): Promise<Instance> {
+  return invoke<Instance>("import_instance_from_zip", {
+    path,
+    name,
+  });
+}
+
+export function importModList(
+  instanceId: string,
+  manifest: string,
+): Promise<ModListImport> {
+  return invoke<ModListImport>("import_mod_list", {
+    instanceId,
+    manifest,
+  });
+}
+
+export function importProfile(
+  path: string,
+  passphrase: string,
+): Promise<ProfileImportSummary> {
+  return invoke<ProfileImportSummary>("import_profile", {
+    path,
+    passphrase,
+  });
+}
+
+export function initServerFiles(
+  instanceId: string,
+  template: ServerTemplate,
+  acceptEula: boolean,
+): Promise<void> {
+  return invoke<void>("init_server_files", {
+    instanceId,
+    template,
+    acceptEula,
+  });
+}
+
+export function installCurseforgeMod(
+  instanceId: string,
+  modId: number,
+  fileId: number | null,
+): Promise<string> {
+  return invoke<string>("install_curseforge_mod", {
+    instanceId,
+    modId,
+    fileId,
+  });
+}
+
 export function installFabric(
   instanceId: string,
   gameVersion: string,
@@ -257,6 +686,64 @@ export function installForge(
   });
 }
 
+export function installJavaAgent(
+  instanceId: string,
+  agent: string,
+  args: string | null,
+): Promise<JavaAgent> {
+  return invoke<JavaAgent>("install_java_agent", {
+    instanceId,
+    agent,
+    args,
+  });
+}
+
+export function installModpack(
+  instanceId: string,
+  path: string,
+): Promise<Instance> {
+  return invoke<Instance>("install_modpack", {
+    instanceId,
+    path,
+  });
+}
+
+export function installModrinthProject(
+  instanceId: string,
+  projectId: string,
+  versionId: string | null,
+): Promise<string> {
+  return invoke<string>("install_modrinth_project", {
+    instanceId,
+    projectId,
+    versionId,
+  });
+}
+
+export function installNeoforge(
+  instanceId: string,
+  gameVersion: string,
+  neoforgeVersion: string,
+): Promise<InstalledNeoForgeVersion> {
+  return invoke<InstalledNeoForgeVersion>("install_neoforge", {
+    instanceId,
+    gameVersion,
+    neoforgeVersion,
+  });
+}
+
+export function installOptifine(
+  instanceId: string,
+  gameVersion: string,
+  edition: string,
+): Promise<InstalledOptiFineVersion> {
+  return invoke<InstalledOptiFineVersion>("install_optifine", {
+    instanceId,
+    gameVersion,
+    edition,
+  });
+}
+
 export function installVersion(
   instanceId: string,
   versionId: string,
@@ -279,6 +766,14 @@ export function isFabricInstalled(
   });
 }
 
+export function listCloudBackups(
+  instanceId: string | null,
+): Promise<BackupEntry[]> {
+  return invoke<BackupEntry[]>("list_cloud_backups", {
+    instanceId,
+  });
+}
+
 export function listInstalledFabricVersions(
   instanceId: string,
 ): Promise<string[]> {
@@ -287,6 +782,12 @@ export function listInstalledFabricVersions(
   });
 }
 
+export function listInstalledMods(instanceId: string): Promise<InstalledMod[]> {
+  return invoke<InstalledMod[]>("list_installed_mods", {
+    instanceId,
+  });
+}
+
 export function listInstalledVersions(
   instanceId: string,
 ): Promise<InstalledVersion[]> {
@@ -309,6 +810,10 @@ export function listInstances(): Promise<Instance[]> {
   return invoke<Instance[]>("list_instances");
 }
 
+export function listLanPeers(): Promise<LanPeer[]> {
+  return invoke<LanPeer[]>("list_lan_peers");
+}
+
 export function listOllamaModels(endpoint: string): Promise<ModelInfo[]> {
   return invoke<ModelInfo[]>("list_ollama_models", {
     endpoint,
@@ -319,12 +824,32 @@ export function listOpenaiModels(): Promise<ModelInfo[]> {
   return invoke<ModelInfo[]>("list_openai_models");
 }
 
+export function listSchedules(): Promise<Schedule[]> {
+  return invoke<Schedule[]>("list_schedules");
+}
+
+export function lockLauncher(): Promise<void> {
+  return invoke<void>("lock_launcher");
+}
+
 export function loginOffline(username: string): Promise<Account> {
   return invoke<Account>("login_offline", {
     username,
   });
 }
 
+export function loginYggdrasil(
+  serverUrl: string,
+  username: string,
+  password: string,
+): Promise<Account> {
+  return invoke<Account>("login_yggdrasil", {
+    serverUrl,
+    username,
+    password,
+  });
+}
+
 export function logout(): Promise<void> {
   return invoke<void>("logout");
 }
@@ -339,6 +864,38 @@ export function openFileExplorer(path: string): Promise<void> {
   });
 }
 
+export function openPathInExplorer(
+  kind: string,
+  instanceId: string,
+): Promise<void> {
+  return invoke<void>("open_path_in_explorer", {
+    kind,
+    instanceId,
+  });
+}
+
+export function pauseDownload(sessionId: string): Promise<void> {
+  return invoke<void>("pause_download", {
+    sessionId,
+  });
+}
+
+export function pinVersion(
+  instanceId: string,
+  versionId: string,
+): Promise<string[]> {
+  return invoke<string[]>("pin_version", {
+    instanceId,
+    versionId,
+  });
+}
+
+export function pullOllamaModel(model: string): Promise<void> {
+  return invoke<void>("pull_ollama_model", {
+    model,
+  });
+}
+
 export function readRawConfig(): Promise<string> {
   return invoke<string>("read_raw_config");
 }
@@ -351,12 +908,100 @@ export function refreshJavaCatalog(): Promise<JavaCatalog> {
   return invoke<JavaCatalog>("refresh_java_catalog");
 }
 
+export function registerUrlScheme(): Promise<void> {
+  return invoke<void>("register_url_scheme");
+}
+
+export function removeNoteAttachment(
+  instanceId: string,
+  name: string,
+): Promise<void> {
+  return invoke<void>("remove_note_attachment", {
+    instanceId,
+    name,
+  });
+}
+
+export function removeSchedule(scheduleId: string): Promise<void> {
+  return invoke<void>("remove_schedule", {
+    scheduleId,
+  });
+}
+
+export function removeServerPlayer(
+  instanceId: string,
+  list: string,
+  player: string,
+): Promise<ServerPlayers> {
+  return invoke<ServerPlayers>("remove_server_player", {
+    instanceId,
+    list,
+    player,
+  });
+}
+
+export function renameOfflineAccount(
+  uuid: string,
+  newName: string,
+  keepUuid: boolean | null,
+): Promise<Account> {
+  return invoke<Account>("rename_offline_account", {
+    uuid,
+    newName,
+    keepUuid,
+  });
+}
+
+export function resetLauncher(
+  scope: ResetScope,
+  dryRun: boolean,
+): Promise<ResetReport> {
+  return invoke<ResetReport>("reset_launcher", {
+    scope,
+    dryRun,
+  });
+}
+
+export function restoreCloudBackup(
+  backupId: string,
+  instanceId: string | null,
+): Promise<BackupEntry> {
+  return invoke<BackupEntry>("restore_cloud_backup", {
+    backupId,
+    instanceId,
+  });
+}
+
+export function resumeDownload(sessionId: string): Promise<void> {
+  return invoke<void>("resume_download", {
+    sessionId,
+  });
+}
+
 export function resumeJavaDownloads(): Promise<JavaInstallation[]> {
   return invoke<JavaInstallation[]>("resume_java_downloads");
 }
 
-export function saveRawConfig(content: string): Promise<void> {
-  return invoke<void>("save_raw_config", {
+export function rollbackLastUpdate(
+  instanceId: string,
+): Promise<RollbackResult> {
+  return invoke<RollbackResult>("rollback_last_update", {
+    instanceId,
+  });
+}
+
+export function saveInstanceNotes(
+  instanceId: string,
+  content: string,
+): Promise<void> {
+  return invoke<void>("save_instance_notes", {
+    instanceId,
+    content,
+  });
+}
+
+export function saveRawConfig(content: string): Promise<ConfigChange[]> {
+  return invoke<ConfigChange[]>("save_raw_config", {
     content,
   });
 }
@@ -367,12 +1012,112 @@ export function saveSettings(config: LauncherConfig): Promise<void> {
   });
 }
 
+export function scanInstanceMods(instanceId: string): Promise<ModScanReport> {
+  return invoke<ModScanReport>("scan_instance_mods", {
+    instanceId,
+  });
+}
+
+export function searchAssistantKnowledge(
+  query: string,
+): Promise<KnowledgeEntry[]> {
+  return invoke<KnowledgeEntry[]>("search_assistant_knowledge", {
+    query,
+  });
+}
+
+export function searchCurseforge(
+  query: string,
+  classId: number | null,
+  instanceId: string | null,
+  page: number | null,
+): Promise<CurseForgeSearch> {
+  return invoke<CurseForgeSearch>("search_curseforge", {
+    query,
+    classId,
+    instanceId,
+    page,
+  });
+}
+
+export function searchEverything(
+  query: string,
+  limit: number | null,
+): Promise<SearchResult[]> {
+  return invoke<SearchResult[]>("search_everything", {
+    query,
+    limit,
+  });
+}
+
+export function searchModrinth(
+  query: string,
+  projectType: string,
+  instanceId: string | null,
+  page: number | null,
+): Promise<ModrinthSearch> {
+  return invoke<ModrinthSearch>("search_modrinth", {
+    query,
+    projectType,
+    instanceId,
+    page,
+  });
+}
+
+export function sendRconCommand(
+  instanceId: string,
+  command: string,
+): Promise<string> {
+  return invoke<string>("send_rcon_command", {
+    instanceId,
+    command,
+  });
+}
+
 export function setActiveInstance(instanceId: string): Promise<void> {
   return invoke<void>("set_active_instance", {
     instanceId,
   });
 }
 
+export function setCape(capeId: string | null): Promise<PlayerSkin> {
+  return invoke<PlayerSkin>("set_cape", {
+    capeId,
+  });
+}
+
+export function setLauncherPin(
+  currentPin: string | null,
+  newPin: string | null,
+): Promise<void> {
+  return invoke<void>("set_launcher_pin", {
+    currentPin,
+    newPin,
+  });
+}
+
+export function setOfflineSkin(
+  uuid: string,
+  skinPath: string | null,
+): Promise<Account> {
+  return invoke<Account>("set_offline_skin", {
+    uuid,
+    skinPath,
+  });
+}
+
+export function setServerProperty(
+  instanceId: string,
+  key: string,
+  value: string,
+): Promise<string> {
+  return invoke<string>("set_server_property", {
+    instanceId,
+    key,
+    value,
+  });
+}
+
 export function startGame(
   instanceId: string,
   versionId: string,
@@ -383,18 +1128,99 @@ export function startGame(
   });
 }
 
+export function startLanSync(): Promise<LanSyncInfo> {
+  return invoke<LanSyncInfo>("start_lan_sync");
+}
+
+export function startLocalApi(): Promise<LocalApiInfo> {
+  return invoke<LocalApiInfo>("start_local_api");
+}
+
 export function startMicrosoftLogin(): Promise<DeviceCodeResponse> {
   return invoke<DeviceCodeResponse>("start_microsoft_login");
 }
 
+export function stopLanSync(): Promise<void> {
+  return invoke<void>("stop_lan_sync");
+}
+
+export function stopLocalApi(): Promise<void> {
+  return invoke<void>("stop_local_api");
+}
+
+export function syncInstanceToPeer(
+  instanceId: string,
+  peerId: string,
+  pairingCode: string,
+): Promise<void> {
+  return invoke<void>("sync_instance_to_peer", {
+    instanceId,
+    peerId,
+    pairingCode,
+  });
+}
+
+export function takePendingDeepLinks(): Promise<DeepLink[]> {
+  return invoke<DeepLink[]>("take_pending_deep_links");
+}
+
+export function toggleMod(
+  instanceId: string,
+  fileName: string,
+  enabled: boolean,
+): Promise<string> {
+  return invoke<string>("toggle_mod", {
+    instanceId,
+    fileName,
+    enabled,
+  });
+}
+
+export function unlockLauncher(pin: string): Promise<void> {
+  return invoke<void>("unlock_launcher", {
+    pin,
+  });
+}
+
+export function unpinVersion(
+  instanceId: string,
+  versionId: string,
+): Promise<string[]> {
+  return invoke<string[]>("unpin_version", {
+    instanceId,
+    versionId,
+  });
+}
+
 export function updateInstance(instance: Instance): Promise<void> {
   return invoke<void>("update_instance", {
     instance,
   });
 }
 
+export function updateModpack(
+  instanceId: string,
+  path: string | null,
+): Promise<PackUpdateSummary> {
+  return invoke<PackUpdateSummary>("update_modpack", {
+    instanceId,
+    path,
+  });
+}
+
+export function uploadSkin(path: string, variant: string): Promise<PlayerSkin> {
+  return invoke<PlayerSkin>("upload_skin", {
+    path,
+    variant,
+  });
+}
+
 export function uploadToPastebin(content: string): Promise<PastebinResponse> {
   return invoke<PastebinResponse>("upload_to_pastebin", {
     content,
   });
 }
+
+export function wasLastShutdownClean(): Promise<boolean> {
+  return invoke<boolean>("was_last_shutdown_clean");
+}
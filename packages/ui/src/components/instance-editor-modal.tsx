@@ -19,7 +19,7 @@ import { toNumber } from "@/lib/tsrs-utils";
 import { useInstanceStore } from "@/models/instance";
 import { useSettingsStore } from "@/models/settings";
 import type { FileInfo } from "../types/bindings/core";
-import type { Instance } from "../types/bindings/instance";
+import type { Instance } from "../types/bindings/dropout-core/instance";
 
 type Props = {
   open: boolean;
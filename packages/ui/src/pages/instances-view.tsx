@@ -14,7 +14,7 @@ import {
 import { Input } from "@/components/ui/input";
 import { toNumber } from "@/lib/tsrs-utils";
 import { useInstanceStore } from "@/models/instance";
-import type { Instance } from "../types/bindings/instance";
+import type { Instance } from "../types/bindings/dropout-core/instance";
 
 export function InstancesView() {
   const instancesStore = useInstanceStore();
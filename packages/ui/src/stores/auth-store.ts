@@ -3,7 +3,10 @@ import { listen, type UnlistenFn } from "@tauri-apps/api/event";
 import { open } from "@tauri-apps/plugin-shell";
 import { toast } from "sonner";
 import { create } from "zustand";
-import type { Account, DeviceCodeResponse } from "../types/bindings/auth";
+import type {
+  Account,
+  DeviceCodeResponse,
+} from "../types/bindings/dropout-core/auth";
 
 interface AuthState {
   // State
@@ -137,18 +140,18 @@ export const useAuthStore = create<AuthState>((set, get) => ({
     });
 
     // Listen to general launcher logs so we can display progress to the user.
-    // The backend emits logs via "launcher-log"; using that keeps this store decoupled
-    // from a dedicated auth event channel (backend may reuse launcher-log).
+    // The backend emits logs in batches via "launcher-log-batch"; using that keeps
+    // this store decoupled from a dedicated auth event channel.
     try {
-      const unlisten = await listen("launcher-log", (event) => {
-        const payload = event.payload;
-        // Normalize payload to string if possible
-        const message =
-          typeof payload === "string"
-            ? payload
-            : (payload?.toString?.() ?? JSON.stringify(payload));
-        set({ msLoginStatus: message });
-      });
+      const unlisten = await listen<{ message: string }[]>(
+        "launcher-log-batch",
+        (event) => {
+          const last = event.payload[event.payload.length - 1];
+          if (last) {
+            set({ msLoginStatus: last.message });
+          }
+        },
+      );
       set({ authProgressUnlisten: unlisten });
     } catch (err) {
       console.warn("Failed to attach launcher-log listener:", err);
@@ -1,7 +1,7 @@
 import { toast } from "sonner";
 import { create } from "zustand";
 import { getVersions } from "@/client";
-import type { Version } from "@/types/bindings/manifest";
+import type { Version } from "@/types/bindings/dropout-core/manifest";
 
 interface GameState {
   // State
@@ -9,26 +9,14 @@ export interface LogEntry {
   message: string;
 }
 
-// Parse Minecraft/Java log format: [HH:MM:SS] [Thread/LEVEL]: message
-// or: [HH:MM:SS] [Thread/LEVEL] [Source]: message
-const GAME_LOG_REGEX =
-  /^\[[\d:]+\]\s*\[([^\]]+)\/(\w+)\](?:\s*\[([^\]]+)\])?:\s*(.*)$/;
-
-function parseGameLogLevel(levelStr: string): LogEntry["level"] {
-  const upper = levelStr.toUpperCase();
-  if (upper === "INFO") return "info";
-  if (upper === "WARN" || upper === "WARNING") return "warn";
-  if (upper === "ERROR" || upper === "SEVERE") return "error";
-  if (
-    upper === "DEBUG" ||
-    upper === "TRACE" ||
-    upper === "FINE" ||
-    upper === "FINER" ||
-    upper === "FINEST"
-  )
-    return "debug";
-  if (upper === "FATAL") return "fatal";
-  return "info";
+// Payload of the "game-log" event, parsed by the backend
+interface GameLogLine {
+  time: string | null;
+  thread: string | null;
+  level: "trace" | "debug" | "info" | "warn" | "error" | "fatal";
+  logger: string | null;
+  message: string;
+  stderr: boolean;
 }
 
 interface LogsState {
@@ -41,7 +29,7 @@ interface LogsState {
 
   // Actions
   addLog: (level: LogEntry["level"], source: string, message: string) => void;
-  addGameLog: (rawLine: string, isStderr: boolean) => void;
+  addGameLog: (line: GameLogLine) => void;
   clear: () => void;
   exportLogs: (filteredLogs: LogEntry[]) => string;
   init: () => Promise<void>;
@@ -93,20 +81,13 @@ export const useLogsStore = create<LogsState>((set, get) => ({
     });
   },
 
-  addGameLog: (rawLine, isStderr) => {
-    const match = rawLine.match(GAME_LOG_REGEX);
-
-    if (match) {
-      const [, thread, levelStr, extraSource, message] = match;
-      const level = parseGameLogLevel(levelStr);
-      // Use extraSource if available, otherwise use thread name as source hint
-      const source = extraSource || `Game/${thread.split("-")[0]}`;
-      get().addLog(level, source, message);
-    } else {
-      // Fallback: couldn't parse, use stderr as error indicator
-      const level = isStderr ? "error" : "info";
-      get().addLog(level, "Game", rawLine);
-    }
+  addGameLog: (line) => {
+    const level = line.level === "trace" ? "debug" : line.level;
+    // Use the logger if available, otherwise use thread name as source hint
+    const source =
+      line.logger ??
+      (line.thread ? `Game/${line.thread.split("-")[0]}` : "Game");
+    get().addLog(level, source, line.message);
   },
 
   clear: () => {
@@ -135,19 +116,19 @@ export const useLogsStore = create<LogsState>((set, get) => ({
     // Initial log
     get().addLog("info", "Launcher", "Logs initialized");
 
-    // General Launcher Logs
-    await listen<string>("launcher-log", (e) => {
-      get().addLog("info", "Launcher", e.payload);
-    });
-
-    // Game Stdout - parse log level
-    await listen<string>("game-stdout", (e) => {
-      get().addGameLog(e.payload, false);
-    });
+    // General Launcher Logs, batched by the backend
+    await listen<{ level: LogEntry["level"]; message: string }[]>(
+      "launcher-log-batch",
+      (e) => {
+        for (const line of e.payload) {
+          get().addLog(line.level, "Launcher", line.message);
+        }
+      },
+    );
 
-    // Game Stderr - parse log level, default to error
-    await listen<string>("game-stderr", (e) => {
-      get().addGameLog(e.payload, true);
+    // Game output, with level and source parsed by the backend
+    await listen<GameLogLine>("game-log", (e) => {
+      get().addGameLog(e.payload);
     });
 
     // Download Events (Summarized)
@@ -159,6 +140,19 @@ export const useLogsStore = create<LogsState>((set, get) => ({
       );
     });
 
+    // Files that still failed after the backend's retries
+    await listen<
+      { url: string; path: string; error: string; attempts: number }[]
+    >("download-failed", (e) => {
+      for (const f of e.payload) {
+        get().addLog(
+          "error",
+          "Downloader",
+          `Failed to download ${f.url} after ${f.attempts} attempts: ${f.error}`,
+        );
+      }
+    });
+
     await listen("download-complete", () => {
       get().addLog("info", "Downloader", "All downloads completed.");
     });
@@ -34,7 +34,9 @@ export const useReleasesStore = create<ReleasesState>((set, get) => ({
     set({ isLoading: true, error: null });
 
     try {
-      const releases = await invoke<GithubRelease[]>("get_github_releases");
+      const { releases } = await invoke<{ releases: GithubRelease[] }>(
+        "get_github_releases",
+      );
       set({ releases, isLoaded: true });
     } catch (e) {
       const error = e instanceof Error ? e.message : String(e);
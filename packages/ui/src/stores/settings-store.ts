@@ -5,15 +5,13 @@ import { create } from "zustand";
 import { downloadAdoptiumJava } from "@/client";
 import type { ModelInfo } from "../types/bindings/assistant";
 import type { LauncherConfig } from "../types/bindings/config";
-import type {
-  JavaDownloadProgress,
-  PendingJavaDownload,
-} from "../types/bindings/downloader";
 import type {
   JavaCatalog,
+  JavaDownloadProgress,
   JavaInstallation,
   JavaReleaseInfo,
-} from "../types/bindings/java";
+  PendingJavaDownload,
+} from "../types/bindings/dropout-core/java";
 
 type JavaDownloadSource = "adoptium" | "mojang" | "azul";
 
@@ -1,5 +1,6 @@
 // This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
-import type { OfflineAccount } from "./auth";
+
+import type { OfflineAccount, YggdrasilAccount } from "./dropout-core/auth";
 
 export type AccountStorage = { file_path: string };
 
@@ -13,7 +14,8 @@ export type AccountStore = {
 
 export type StoredAccount =
   | ({ type: "Offline" } & OfflineAccount)
-  | ({ type: "Microsoft" } & StoredMicrosoftAccount);
+  | ({ type: "Microsoft" } & StoredMicrosoftAccount)
+  | ({ type: "Yggdrasil" } & YggdrasilAccount);
 
 /**
  * Microsoft account with refresh token for persistence
@@ -1,5 +1,15 @@
 // This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
 
+/**
+ * Result of a deep health check
+ */
+export type AssistantHealth = {
+  reachable: boolean;
+  modelAvailable: boolean;
+  model: string;
+  message: string | null;
+};
+
 export type GenerationStats = {
   totalDuration: bigint;
   loadDuration: bigint;
@@ -9,6 +19,19 @@ export type GenerationStats = {
   evalDuration: bigint;
 };
 
+export type KnowledgeEntry = {
+  id: string;
+  title: string;
+  patterns: Array<string>;
+  explanation: string;
+  remedies: Array<string>;
+};
+
+/**
+ * A knowledge base entry that matched, with the text that triggered it
+ */
+export type KnowledgeMatch = { entry: KnowledgeEntry; matchedText: string };
+
 export type Message = { role: string; content: string };
 
 export type ModelInfo = {
@@ -18,6 +41,15 @@ export type ModelInfo = {
   details: string | null;
 };
 
+export type OllamaPullProgress = {
+  model: string;
+  status: string;
+  total: bigint | null;
+  completed: bigint | null;
+  percent: number | null;
+  done: boolean;
+};
+
 export type StreamChunk = {
   content: string;
   done: boolean;
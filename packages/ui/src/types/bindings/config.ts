@@ -1,5 +1,10 @@
 // This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
 
+import type { FeatureFlags } from "./dropout-core/config";
+import type { DownloadMirror } from "./dropout-core/downloader";
+import type { JavaDownloadMirror } from "./dropout-core/java/core";
+import type { Schedule } from "./scheduler";
+
 export type AssistantConfig = {
   enabled: boolean;
   llmProvider: string;
@@ -10,34 +15,41 @@ export type AssistantConfig = {
   openaiModel: string;
   systemPrompt: string;
   responseLanguage: string;
+  contextWindow: number;
+  logSearchEnabled: boolean;
+  ollamaEmbeddingModel: string;
+  openaiEmbeddingModel: string;
+  logSearchTopK: number;
   ttsEnabled: boolean;
   ttsProvider: string;
 };
 
 /**
- * Feature-gated arguments configuration
+ * Cloud backup of instance folders (see `core::sync`)
+ */
+export type CloudSyncConfig = {
+  provider: string;
+  webdavUrl: string;
+  webdavUsername: string | null;
+  webdavPassword: string | null;
+  s3Endpoint: string;
+  s3Region: string;
+  s3Bucket: string;
+  s3AccessKey: string | null;
+  s3SecretKey: string | null;
+  s3PathStyle: boolean;
+  remotePrefix: string;
+  passphrase: string | null;
+  folders: Array<string>;
+};
+
+/**
+ * A setting changed by `save_raw_config`
  */
-export type FeatureFlags = {
-  /**
-   * Demo user: enables demo-related arguments when rules require it
-   */
-  demoUser: boolean;
-  /**
-   * Quick Play: enable quick play arguments
-   */
-  quickPlayEnabled: boolean;
-  /**
-   * Quick Play singleplayer world path (if provided)
-   */
-  quickPlayPath: string | null;
-  /**
-   * Quick Play singleplayer flag
-   */
-  quickPlaySingleplayer: boolean;
-  /**
-   * Quick Play multiplayer server address (optional)
-   */
-  quickPlayMultiplayerServer: string | null;
+export type ConfigChange = {
+  key: string;
+  oldValue: string | null;
+  newValue: string | null;
 };
 
 export type LauncherConfig = {
@@ -47,6 +59,7 @@ export type LauncherConfig = {
   width: number;
   height: number;
   downloadThreads: number;
+  autoDownloadThreads: boolean;
   customBackgroundPath: string | null;
   enableGpuAcceleration: boolean;
   enableVisualEffects: boolean;
@@ -58,4 +71,32 @@ export type LauncherConfig = {
   useSharedCaches: boolean;
   keepLegacyPerInstanceStorage: boolean;
   featureFlags: FeatureFlags;
+  githubToken: string | null;
+  releaseCheckIntervalHours: number;
+  lanSyncEnabled: boolean;
+  cloudSync: CloudSyncConfig;
+  offlineUuidNamespace: string;
+  utf8JvmArgs: string;
+  autoGameLanguage: boolean;
+  nativeClassifierOverride: string | null;
+  usageStatsEnabled: boolean;
+  localApiEnabled: boolean;
+  localApiPort: number;
+  localApiToken: string | null;
+  schedules: Array<Schedule>;
+  assetVirtualization: string;
+  predownloadLoaderLibraries: boolean;
+  downloadMirror: DownloadMirror;
+  downloadRetries: number;
+  downloadBufferKb: number;
+  downloadFsync: boolean;
+  maxDownloadSpeed: number;
+  lowPriorityWhilePlaying: boolean;
+  lowPriorityDownloadThreads: number;
+  lowPriorityDownloadSpeed: number;
+  javaDownloadMirror: JavaDownloadMirror;
+  javaArchiveCacheMb: number;
+  parental: ParentalConfig;
 };
+
+export type ParentalConfig = { pinHash: string | null; pinSalt: string | null };
@@ -19,6 +19,15 @@ export type GithubRelease = {
   htmlUrl: string;
 };
 
+/**
+ * Result of a release check, including when GitHub was last contacted
+ */
+export type GithubReleasesResponse = {
+  releases: Array<GithubRelease>;
+  lastChecked: bigint;
+  fromCache: boolean;
+};
+
 /**
  * Installed version info
  */
@@ -0,0 +1,23 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * Payload of the `crash-detected` event
+ */
+export type CrashDetected = {
+  instanceId: string;
+  exitCode: number | null;
+  dumps: Array<CrashDump>;
+};
+
+export type CrashDump = {
+  path: string;
+  kind: string;
+  summary: HsErrSummary | null;
+};
+
+export type HsErrSummary = {
+  error: string | null;
+  problematicFrame: string | null;
+  nativeLibrary: string | null;
+  javaVersion: string | null;
+};
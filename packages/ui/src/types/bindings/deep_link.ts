@@ -0,0 +1,10 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+export type DeepLink =
+  | {
+      action: "installModpack";
+      source: string;
+      id: string;
+      version: string | null;
+    }
+  | { action: "join"; server: string; instanceId: string | null };
@@ -0,0 +1,14 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+export type DownloadAuditEntry = {
+  timestamp: bigint;
+  url: string;
+  destination: string;
+  trigger: string;
+  status: string;
+  size: bigint | null;
+  sha1: string | null;
+  expectedSha1: string | null;
+  expectedSha256: string | null;
+  error: string | null;
+};
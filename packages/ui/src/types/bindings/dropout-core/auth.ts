@@ -2,7 +2,8 @@
 
 export type Account =
   | ({ type: "offline" } & OfflineAccount)
-  | ({ type: "microsoft" } & MicrosoftAccount);
+  | ({ type: "microsoft" } & MicrosoftAccount)
+  | ({ type: "yggdrasil" } & YggdrasilAccount);
 
 export type DeviceCodeResponse = {
   userCode: string;
@@ -23,10 +24,30 @@ export type MicrosoftAccount = {
 
 export type MinecraftProfile = { id: string; name: string };
 
-export type OfflineAccount = { username: string; uuid: string };
+export type OfflineAccount = {
+  username: string;
+  uuid: string;
+  /**
+   * Local PNG used as the player's skin (via CustomSkinLoader)
+   */
+  skinPath: string | null;
+};
 
 export type TokenResponse = {
   access_token: string;
   refresh_token: string | null;
   expires_in: bigint;
 };
+
+/**
+ * Account on a third-party Yggdrasil server, used in game through
+ * authlib-injector
+ */
+export type YggdrasilAccount = {
+  username: string;
+  uuid: string;
+  accessToken: string;
+  clientToken: string;
+  serverUrl: string;
+  serverName: string | null;
+};
@@ -0,0 +1,27 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * Feature-gated arguments configuration
+ */
+export type FeatureFlags = {
+  /**
+   * Demo user: enables demo-related arguments when rules require it
+   */
+  demoUser: boolean;
+  /**
+   * Quick Play: enable quick play arguments
+   */
+  quickPlayEnabled: boolean;
+  /**
+   * Quick Play singleplayer world path (if provided)
+   */
+  quickPlayPath: string | null;
+  /**
+   * Quick Play singleplayer flag
+   */
+  quickPlaySingleplayer: boolean;
+  /**
+   * Quick Play multiplayer server address (optional)
+   */
+  quickPlayMultiplayerServer: string | null;
+};
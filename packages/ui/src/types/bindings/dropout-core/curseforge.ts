@@ -0,0 +1,32 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+export type CurseForgeFile = {
+  id: bigint;
+  modId: bigint;
+  displayName: string;
+  fileName: string;
+  fileDate: string;
+  fileLength: bigint;
+  releaseType: string;
+  gameVersions: Array<string>;
+  downloadUrl: string | null;
+  sha1: string | null;
+};
+
+export type CurseForgeProject = {
+  id: bigint;
+  slug: string;
+  name: string;
+  summary: string;
+  authors: Array<string>;
+  downloadCount: bigint;
+  iconUrl: string | null;
+  classId: bigint | null;
+  websiteUrl: string | null;
+};
+
+export type CurseForgeSearch = {
+  projects: Array<CurseForgeProject>;
+  total: bigint;
+  page: number;
+};
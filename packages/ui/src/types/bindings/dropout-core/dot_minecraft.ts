@@ -0,0 +1,15 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+import type { Instance } from "./instance";
+
+export type DetectedVersion = {
+  id: string;
+  minecraftVersion: string;
+  modLoader: string;
+  modLoaderVersion: string | null;
+};
+
+export type DotMinecraftImport = {
+  instance: Instance;
+  versions: Array<DetectedVersion>;
+};
@@ -0,0 +1,14 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+export type DownloadSessionInfo = {
+  id: string;
+  window: string;
+  state: SessionState;
+  completedFiles: number;
+  totalFiles: number;
+  downloadedBytes: bigint;
+  startedAt: bigint;
+  lowPriority: boolean;
+};
+
+export type SessionState = "running" | "paused" | "cancelled";
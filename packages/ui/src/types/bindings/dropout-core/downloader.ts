@@ -0,0 +1,34 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * Where Mojang and mod loader files are downloaded from
+ */
+export type DownloadMirror = "official" | "bmclapi";
+
+export type DownloadTask = {
+  url: string;
+  path: string;
+  sha1: string | null;
+  sha256: string | null;
+};
+
+/**
+ * A file that still failed after all retries; the payload of the
+ * `download-failed` event is the list of them
+ */
+export type FailedDownload = {
+  url: string;
+  path: string;
+  error: string;
+  attempts: number;
+};
+
+export type ProgressEvent = {
+  file: string;
+  downloaded: bigint;
+  total: bigint;
+  status: string;
+  completedFiles: number;
+  totalFiles: number;
+  totalDownloadedBytes: bigint;
+};
@@ -25,6 +25,8 @@ export type Downloads = {
   server: DownloadArtifact | null;
 };
 
+export type ExtractRules = { exclude: Array<string> | null };
+
 /**
  * Represents a Minecraft version JSON, supporting both vanilla and modded (Fabric/Forge) formats.
  * Modded versions use `inheritsFrom` to reference a parent vanilla version.
@@ -56,6 +58,10 @@ export type GameVersion = {
    * Release type (release, snapshot, old_beta, etc.)
    */
   type: string | null;
+  /**
+   * Log4j configuration for the client (patched by Mojang against Log4Shell)
+   */
+  logging: Logging | null;
 };
 
 export type JavaVersion = { component: string; majorVersion: bigint };
@@ -69,6 +75,10 @@ export type Library = {
    * Maven repository URL for mod loader libraries
    */
   url: string | null;
+  /**
+   * Entries to skip when extracting natives (LWJGL 2 era)
+   */
+  extract: ExtractRules | null;
 };
 
 export type LibraryDownloads = {
@@ -76,6 +86,24 @@ export type LibraryDownloads = {
   classifiers: Record<string, unknown>;
 };
 
+export type Logging = { client: LoggingConfig | null };
+
+export type LoggingConfig = {
+  /**
+   * JVM argument with a `${path}` placeholder for the config file
+   */
+  argument: string;
+  file: LoggingFile;
+  type: string | null;
+};
+
+export type LoggingFile = {
+  id: string;
+  sha1: string | null;
+  size: bigint | null;
+  url: string;
+};
+
 export type OsRule = {
   name: string | null;
   version: string | null;
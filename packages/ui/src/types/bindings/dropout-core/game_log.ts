@@ -0,0 +1,13 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * Payload of the `game-log` event
+ */
+export type GameLogLine = {
+  time: string | null;
+  thread: string | null;
+  level: string;
+  logger: string | null;
+  message: string;
+  stderr: boolean;
+};
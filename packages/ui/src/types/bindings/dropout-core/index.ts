@@ -0,0 +1,27 @@
+export * from "./auth";
+export * from "./config";
+export * from "./curseforge";
+export * from "./dot_minecraft";
+export * from "./download_manager";
+export * from "./downloader";
+export * from "./fabric";
+export * from "./forge";
+export * from "./game-version";
+export * from "./game_log";
+export * from "./instance";
+export * from "./java";
+export * from "./launch_report";
+export * from "./manifest";
+export * from "./mod_list";
+export * from "./mod_rollback";
+export * from "./mod_updates";
+export * from "./modpack";
+export * from "./modrinth";
+export * from "./mods";
+export * from "./natives";
+export * from "./neoforge";
+export * from "./optifine";
+export * from "./rules";
+export * from "./server";
+export * from "./skin";
+export * from "./version_diff";
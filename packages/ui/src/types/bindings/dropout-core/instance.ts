@@ -0,0 +1,85 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+import type { InstalledPack } from "./modpack";
+
+/**
+ * What deleting an instance would remove, for the confirmation dialog
+ */
+export type DeletePreview = {
+  totalBytes: bigint;
+  worlds: Array<string>;
+  screenshotCount: number;
+  externalGameDir: boolean;
+};
+
+export type DeletedInstance = { deletedBytes: bigint; keptPath: string | null };
+
+/**
+ * Window and language settings passed to the game of an instance
+ */
+export type GameSettings = {
+  width: number | null;
+  height: number | null;
+  fullscreen: boolean;
+  language: string | null;
+};
+
+/**
+ * Represents a game instance/profile
+ */
+export type Instance = {
+  id: string;
+  name: string;
+  gameDir: string;
+  versionId: string | null;
+  createdAt: bigint;
+  lastPlayed: bigint | null;
+  iconPath: string | null;
+  notes: string | null;
+  modLoader: string | null;
+  modLoaderVersion: string | null;
+  jvmArgsOverride: string | null;
+  memoryOverride: MemoryOverride | null;
+  javaPathOverride: string | null;
+  defaultAccountUuid: string | null;
+  sandbox: SandboxSettings;
+  javaAgents: Array<JavaAgent>;
+  pinnedVersions: Array<string>;
+  gameSettings: GameSettings;
+  externalGameDir: boolean;
+  status: InstanceStatus;
+  autoUpdateMods: boolean;
+  downloadThreads: number | null;
+  pack: InstalledPack | null;
+};
+
+/**
+ * Configuration for all instances
+ */
+export type InstanceConfig = {
+  instances: Array<Instance>;
+  activeInstanceId: string | null;
+};
+
+/**
+ * Integrity of an instance's directory; broken instances can be inspected
+ * but not launched
+ */
+export type InstanceStatus = "ok" | "missing-dir" | "partial";
+
+export type JavaAgent = { path: string; args: string | null; enabled: boolean };
+
+/**
+ * Memory settings override for an instance
+ */
+export type MemoryOverride = { min: number; max: number };
+
+/**
+ * Per-instance sandbox settings
+ */
+export type SandboxSettings = {
+  enabled: boolean;
+  backend: string;
+  allowNetwork: boolean;
+  extraReadPaths: Array<string>;
+};
@@ -1,10 +1,21 @@
 // This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
 
+/**
+ * Subset of the catalog to fetch, so a page that needs one Java version
+ * doesn't wait for every release of every version
+ */
+export type CatalogFilter = { majorVersions: Array<number>; jreOnly: boolean };
+
 export type JavaCatalog = {
   releases: Array<JavaReleaseInfo>;
   availableMajorVersions: Array<number>;
   ltsVersions: Array<number>;
   cachedAt: bigint;
+  /**
+   * Only the releases matching a [`CatalogFilter`]; the full catalog
+   * follows in a `java-catalog-updated` event
+   */
+  partial: boolean;
 };
 
 export type JavaDownloadInfo = {
@@ -17,6 +28,12 @@ export type JavaDownloadInfo = {
   image_type: string;
 };
 
+/**
+ * Where Java archives are downloaded from. Mirrors only serve the files;
+ * release metadata and checksums still come from the provider's API.
+ */
+export type JavaDownloadMirror = "official" | "tuna" | "ustc";
+
 export type JavaInstallation = {
   path: string;
   version: string;
@@ -24,6 +41,10 @@ export type JavaInstallation = {
   vendor: string;
   source: string;
   is64bit: boolean;
+  /**
+   * x86/x64 runtime running under emulation on an ARM64 host (e.g. Rosetta)
+   */
+  emulated: boolean;
 };
 
 export type JavaReleaseInfo = {
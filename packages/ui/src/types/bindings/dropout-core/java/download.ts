@@ -28,13 +28,6 @@ export type DownloadSegment = {
   completed: boolean;
 };
 
-export type DownloadTask = {
-  url: string;
-  path: string;
-  sha1: string | null;
-  sha256: string | null;
-};
-
 /**
  * Progress event for Java download
  */
@@ -61,13 +54,3 @@ export type PendingJavaDownload = {
   installPath: string;
   createdAt: bigint;
 };
-
-export type ProgressEvent = {
-  file: string;
-  downloaded: bigint;
-  total: bigint;
-  status: string;
-  completedFiles: number;
-  totalFiles: number;
-  totalDownloadedBytes: bigint;
-};
@@ -1,3 +1,4 @@
 export * from "./core";
+export * from "./download";
 export * from "./persistence";
 export * from "./providers";
@@ -0,0 +1,50 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * Payload of the `game-launched` event, sent once the game process runs
+ */
+export type GameLaunched = {
+  instanceId: string;
+  versionId: string;
+  launchId: string;
+  pid: number | null;
+  timings: Array<PhaseTiming>;
+  totalMs: bigint;
+};
+
+export type LaunchPhase =
+  | "verification"
+  | "auth"
+  | "metadata"
+  | "modUpdates"
+  | "java"
+  | "download"
+  | "assets"
+  | "natives"
+  | "spawn";
+
+export type LaunchReport = {
+  instanceId: string;
+  versionId: string;
+  mainClass: string;
+  createdAt: bigint;
+  libraries: Array<ResolvedLibrary>;
+  substitutions: Array<LibrarySubstitution>;
+  timings: Array<PhaseTiming>;
+  totalMs: bigint;
+};
+
+export type LibrarySubstitution = {
+  library: string;
+  kept: ResolvedLibrary;
+  dropped: ResolvedLibrary;
+};
+
+export type PhaseTiming = { phase: LaunchPhase; ms: bigint };
+
+export type ResolvedLibrary = {
+  name: string;
+  version: string;
+  origin: string;
+  path: string;
+};
@@ -8,6 +8,15 @@ export type Version = {
   url: string;
   time: string;
   releaseTime: string;
+  /**
+   * SHA1 of the version JSON (manifest v2)
+   */
+  sha1?: string | null;
+  /**
+   * 1 for versions that show the player safety warning and need the
+   * newer Microsoft authentication; 0 before that
+   */
+  complianceLevel: number;
   /**
    * Java version requirement (major version number)
    * This is populated from the version JSON file if the version is installed locally
@@ -17,6 +26,10 @@ export type Version = {
    * Whether this version is installed locally
    */
   isInstalled: boolean | null;
+  /**
+   * Whether the instance pinned this version to the top of the picker
+   */
+  isPinned: boolean | null;
 };
 
 export type VersionManifest = { latest: Latest; versions: Array<Version> };
@@ -0,0 +1,10 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+export type ModListEntry = { source: string; slug: string };
+
+export type ModListFailure = { entry: string; error: string };
+
+export type ModListImport = {
+  installed: Array<string>;
+  failed: Array<ModListFailure>;
+};
@@ -0,0 +1,15 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+export type RollbackEntry = { fileName: string; sha1: string; saved: boolean };
+
+export type RollbackPoint = {
+  reason: string;
+  createdAt: bigint;
+  files: Array<RollbackEntry>;
+  written: Array<string>;
+};
+
+export type RollbackResult = {
+  restored: Array<string>;
+  removed: Array<string>;
+};
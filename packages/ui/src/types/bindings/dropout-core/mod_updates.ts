@@ -0,0 +1,23 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * Result of [`apply_safe_updates`]
+ */
+export type AutoUpdateSummary = {
+  updated: Array<ModUpdate>;
+  skipped: Array<ModUpdate>;
+  failed: Array<ModUpdateFailure>;
+};
+
+export type ModUpdate = {
+  fileName: string;
+  projectId: string;
+  currentVersion: string;
+  newVersion: string;
+  newFileName: string;
+  url: string;
+  sha1: string;
+  safe: boolean;
+};
+
+export type ModUpdateFailure = { fileName: string; error: string };
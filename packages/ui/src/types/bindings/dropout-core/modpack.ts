@@ -0,0 +1,82 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * Result of [`export_zip`] and [`write_mrpack`].
+ */
+export type ExportSummary = {
+  path: string;
+  /**
+   * Files listed in `modrinth.index.json` (always 0 for a plain zip).
+   */
+  referenced: number;
+  /**
+   * Files stored in the archive.
+   */
+  stored: number;
+};
+
+/**
+ * A pack installed into an instance, as needed to update it later.
+ */
+export type InstalledPack = {
+  name: string;
+  modpackType: string;
+  /**
+   * Unknown for packs that aren't published (or for MultiMC zips).
+   */
+  origin: PackOrigin | null;
+  /**
+   * The pack's file manifest.
+   */
+  files: Array<ModpackFile>;
+  /**
+   * Override files as extracted: relative path -> SHA1.
+   */
+  overrides: { [key in string]?: string };
+};
+
+export type ModpackFile = {
+  url: string;
+  path: string;
+  size: bigint | null;
+  sha1: string | null;
+};
+
+/**
+ * The Modrinth project or CurseForge mod a pack archive was published as.
+ */
+export type PackOrigin = {
+  /**
+   * "modrinth" or "curseforge".
+   */
+  platform: string;
+  projectId: string;
+  /**
+   * Modrinth version ID or CurseForge file ID of the installed archive.
+   */
+  versionId: string;
+  versionName: string;
+};
+
+/**
+ * A newer published version of a pack.
+ */
+export type PackRelease = {
+  versionId: string;
+  versionName: string;
+  url: string;
+  sha1: string | null;
+};
+
+/**
+ * What an update changed in the instance.
+ */
+export type PackUpdateSummary = {
+  added: Array<string>;
+  changed: Array<string>;
+  removed: Array<string>;
+  /**
+   * Override files the user edited, left as they are.
+   */
+  kept: Array<string>;
+};
@@ -0,0 +1,39 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+export type ModrinthFile = {
+  url: string;
+  filename: string;
+  size: bigint | null;
+  sha1: string | null;
+  primary: boolean;
+};
+
+export type ModrinthProject = {
+  id: string;
+  slug: string;
+  title: string;
+  description: string;
+  author: string | null;
+  downloads: bigint;
+  iconUrl: string | null;
+  projectType: string;
+  categories: Array<string>;
+};
+
+export type ModrinthSearch = {
+  projects: Array<ModrinthProject>;
+  total: bigint;
+  page: number;
+};
+
+export type ModrinthVersion = {
+  id: string;
+  projectId: string;
+  name: string;
+  versionNumber: string;
+  versionType: string;
+  datePublished: string;
+  gameVersions: Array<string>;
+  loaders: Array<string>;
+  files: Array<ModrinthFile>;
+};
@@ -0,0 +1,25 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+export type InstalledMod = {
+  fileName: string;
+  enabled: boolean;
+  metadata: ModMetadata | null;
+};
+
+export type ModDependency = {
+  id: string;
+  version: string | null;
+  required: boolean;
+};
+
+export type ModMetadata = {
+  id: string;
+  name: string;
+  version: string;
+  authors: Array<string>;
+  description: string | null;
+  url: string | null;
+  loader: string;
+  dependencies: Array<ModDependency>;
+  icon: string | null;
+};
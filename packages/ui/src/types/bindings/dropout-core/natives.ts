@@ -0,0 +1,10 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * Payload of the `natives-classifier-missing` event
+ */
+export type NativesWarning = {
+  library: string;
+  tried: Array<string>;
+  available: Array<string>;
+};
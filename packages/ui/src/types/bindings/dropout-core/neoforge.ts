@@ -0,0 +1,21 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * Information about an installed NeoForge version.
+ */
+export type InstalledNeoForgeVersion = {
+  id: string;
+  minecraftVersion: string;
+  neoforgeVersion: string;
+  path: string;
+};
+
+/**
+ * Represents a NeoForge version entry.
+ */
+export type NeoForgeVersion = {
+  version: string;
+  minecraftVersion: string;
+  beta: boolean;
+  latest: boolean;
+};
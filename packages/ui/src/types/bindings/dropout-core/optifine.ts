@@ -0,0 +1,21 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * Information about an installed OptiFine version.
+ */
+export type InstalledOptiFineVersion = {
+  id: string;
+  minecraftVersion: string;
+  edition: string;
+  path: string;
+};
+
+/**
+ * Represents an OptiFine release.
+ */
+export type OptiFineVersion = {
+  fileName: string;
+  minecraftVersion: string;
+  edition: string;
+  forge: string | null;
+};
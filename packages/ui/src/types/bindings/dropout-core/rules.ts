@@ -0,0 +1,18 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * Why a library or argument was included or excluded
+ */
+export type RuleTrace = {
+  kind: string;
+  subject: string;
+  included: boolean;
+  reason: string;
+};
+
+export type RulesReport = {
+  versionId: string;
+  os: string;
+  arch: string;
+  entries: Array<RuleTrace>;
+};
@@ -0,0 +1,20 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+export type OpEntry = {
+  uuid: string;
+  name: string;
+  level: number;
+  bypassesPlayerLimit: boolean;
+};
+
+export type PlayerEntry = { uuid: string; name: string };
+
+export type ServerPlayers = {
+  whitelist: Array<PlayerEntry>;
+  ops: Array<OpEntry>;
+};
+
+/**
+ * Settings for a freshly generated `server.properties`
+ */
+export type ServerTemplate = { port: number; motd: string; whitelist: boolean };
@@ -0,0 +1,37 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+export type CapeTexture = {
+  id: string;
+  state: string;
+  url: string;
+  alias: string | null;
+};
+
+/**
+ * A profile and the cached files of its active textures
+ */
+export type PlayerSkin = {
+  profile: SkinProfile;
+  skinPath: string | null;
+  capePath: string | null;
+};
+
+/**
+ * A profile with its skins and capes
+ */
+export type SkinProfile = {
+  id: string;
+  name: string;
+  skins: Array<SkinTexture>;
+  capes: Array<CapeTexture>;
+};
+
+export type SkinTexture = {
+  id: string;
+  state: string;
+  url: string;
+  variant: SkinVariant | null;
+  alias: string | null;
+};
+
+export type SkinVariant = "CLASSIC" | "SLIM";
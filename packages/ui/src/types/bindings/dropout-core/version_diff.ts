@@ -0,0 +1,19 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+export type Change<T> = { from: T | null; to: T | null };
+
+export type LibraryChange = { name: string; from: string; to: string };
+
+export type VersionDiff = {
+  fromId: string;
+  toId: string;
+  /**
+   * Java major version; `None` in a `Change` when the JSON doesn't say
+   */
+  java: Change<bigint> | null;
+  mainClass: Change<string> | null;
+  assetIndex: Change<string> | null;
+  librariesAdded: Array<string>;
+  librariesRemoved: Array<string>;
+  librariesChanged: Array<LibraryChange>;
+};
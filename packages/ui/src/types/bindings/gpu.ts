@@ -0,0 +1,10 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+export type GpuInfo = {
+  vendor: string | null;
+  renderer: string | null;
+  driverVersion: string | null;
+  glVersion: string | null;
+  source: string;
+  warnings: Array<string>;
+};
@@ -1,12 +1,29 @@
 export * from "./account";
 export * from "./assistant";
-export * from "./auth";
 export * from "./config";
 export * from "./core";
-export * from "./downloader";
-export * from "./fabric";
-export * from "./forge";
-export * from "./game-version";
-export * from "./instance";
-export * from "./java";
-export * from "./manifest";
+export * from "./crash";
+export * from "./deep_link";
+export * from "./download_audit";
+export * from "./dropout-core";
+export * from "./gpu";
+export * from "./install";
+export * from "./lan_sync";
+export * from "./launch_history";
+export * from "./local_api";
+export * from "./locale";
+export * from "./log_batch";
+export * from "./memory_monitor";
+export * from "./mod_scan";
+export * from "./notes";
+export * from "./parental";
+export * from "./profile";
+export * from "./recovery";
+export * from "./reset";
+export * from "./scheduler";
+export * from "./search";
+export * from "./shutdown";
+export * from "./status";
+export * from "./sync";
+export * from "./usage_stats";
+export * from "./web_seed";
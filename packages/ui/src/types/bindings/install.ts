@@ -0,0 +1,22 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+export type InstallPhase =
+  | "fetchingMetadata"
+  | "planningDownloads"
+  | "downloading"
+  | "extractingNatives"
+  | "done"
+  | "failed";
+
+/**
+ * Payload of the `install-state` event
+ */
+export type InstallStateEvent = {
+  instanceId: string;
+  versionId: string;
+  phase: InstallPhase;
+  previous: InstallPhase | null;
+  previousMs: bigint;
+  elapsedMs: bigint;
+  error: string | null;
+};
@@ -0,0 +1,24 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+export type LanPeer = {
+  id: string;
+  name: string;
+  address: string;
+  port: number;
+};
+
+export type LanSyncInfo = {
+  running: boolean;
+  deviceName: string;
+  pairingCode: string;
+  port: number | null;
+};
+
+export type LanSyncProgress = {
+  instanceName: string;
+  file: string;
+  transferredBytes: bigint;
+  totalBytes: bigint;
+  completedFiles: number;
+  totalFiles: number;
+};
@@ -0,0 +1,13 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+export type LaunchRecord = {
+  id: string;
+  timestamp: bigint;
+  versionId: string;
+  javaPath: string;
+  javaMajor: number;
+  args: Array<string>;
+  exitCode: number | null;
+  durationMs: bigint | null;
+  memoryPressure: boolean;
+};
@@ -0,0 +1,11 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+export type LocalApiInfo = { running: boolean; port: number | null };
+
+export type RunningGame = {
+  launchId: string;
+  instanceId: string;
+  versionId: string;
+  pid: number | null;
+  startedAt: bigint;
+};
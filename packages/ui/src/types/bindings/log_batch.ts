@@ -0,0 +1,5 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+export type LogLevel = "info" | "warn" | "error";
+
+export type LogLine = { level: LogLevel; message: string; timestamp: bigint };
@@ -0,0 +1,12 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * Payload of the `memory-pressure` event
+ */
+export type MemoryPressure = {
+  instanceId: string;
+  availableMb: bigint;
+  totalMb: bigint;
+  maxMemoryMb: bigint;
+  suggestion: string;
+};
@@ -0,0 +1,24 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+export type ModScanFinding = {
+  severity: string;
+  kind: string;
+  entry: string | null;
+  message: string;
+};
+
+export type ModScanReport = {
+  instanceId: string;
+  scanned: number;
+  flagged: number;
+  critical: boolean;
+  hostIndicators: Array<string>;
+  mods: Array<ModScanResult>;
+};
+
+export type ModScanResult = {
+  fileName: string;
+  sha1: string;
+  signed: boolean;
+  findings: Array<ModScanFinding>;
+};
@@ -0,0 +1,8 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+export type InstanceNotes = {
+  content: string;
+  attachments: Array<NoteAttachment>;
+};
+
+export type NoteAttachment = { name: string; path: string; size: bigint };
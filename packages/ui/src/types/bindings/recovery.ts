@@ -0,0 +1,21 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+import type { PendingJavaDownload } from "./dropout-core/java/download";
+
+export type InterruptedInstall = {
+  instanceId: string;
+  instanceName: string;
+  versionId: string | null;
+  modLoader: string | null;
+};
+
+export type OrphanedFile = { path: string; size: bigint };
+
+export type RecoveryAction = "resume" | "clean" | "ignore";
+
+export type RecoveryReport = {
+  orphanedFiles: Array<OrphanedFile>;
+  staleNatives: number;
+  interruptedInstalls: Array<InterruptedInstall>;
+  pendingJavaDownloads: Array<PendingJavaDownload>;
+};
@@ -0,0 +1,20 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+export type ResetEntry = { path: string; size: bigint; removed: boolean };
+
+export type ResetProgress = { current: number; total: number; path: string };
+
+export type ResetReport = {
+  dryRun: boolean;
+  entries: Array<ResetEntry>;
+  totalBytes: bigint;
+  errors: Array<string>;
+  clearedSecrets: Array<string>;
+};
+
+export type ResetScope =
+  | "accounts"
+  | "assistant"
+  | "logs"
+  | "caches"
+  | "everything";
@@ -0,0 +1,11 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+export type Schedule = {
+  id: string;
+  instanceId: string;
+  action: string;
+  time: string;
+  days: Array<number>;
+  enabled: boolean;
+  lastRun: string | null;
+};
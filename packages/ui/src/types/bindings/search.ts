@@ -0,0 +1,10 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+export type SearchResult = {
+  kind: string;
+  id: string;
+  title: string;
+  subtitle: string | null;
+  instanceId: string | null;
+  score: bigint;
+};
@@ -0,0 +1,12 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+import type { DownloadSessionInfo } from "./dropout-core/download_manager";
+import type { RunningGame } from "./local_api";
+
+/**
+ * What would be interrupted by quitting now
+ */
+export type ActiveWork = {
+  downloads: Array<DownloadSessionInfo>;
+  runningGames: Array<RunningGame>;
+};
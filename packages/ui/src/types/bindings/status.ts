@@ -0,0 +1,13 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+export type ServiceHealth = "up" | "degraded" | "down";
+
+export type ServiceStatus = {
+  id: string;
+  name: string;
+  url: string;
+  status: ServiceHealth;
+  latencyMs: bigint | null;
+  httpStatus: number | null;
+  error: string | null;
+};
@@ -0,0 +1,10 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+export type BackupEntry = {
+  id: string;
+  instanceId: string;
+  instanceName: string;
+  createdAt: bigint;
+  size: bigint;
+  folders: Array<string>;
+};
@@ -0,0 +1,20 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+export type UsageEvent = {
+  timestamp: bigint;
+  kind: string;
+  instanceId: string | null;
+  durationMs: bigint | null;
+  bytes: bigint | null;
+  category: string | null;
+};
+
+export type UsageStats = {
+  enabled: boolean;
+  launches: bigint;
+  failures: bigint;
+  averageLaunchMs: bigint | null;
+  averageDownloadBytesPerSec: bigint | null;
+  failuresByCategory: { [key in string]?: bigint };
+  recent: Array<UsageEvent>;
+};
@@ -0,0 +1,22 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * Where a modpack archive can be downloaded from
+ */
+export type PackSource =
+  | { type: "http"; url: string; sha1: string | null }
+  | {
+      type: "webSeed";
+      /**
+       * URL of the `.torrent` file describing the archive
+       */
+      torrentUrl: string;
+      /**
+       * Expected info hash (hex), if the pack publishes one
+       */
+      infoHash: string | null;
+      /**
+       * Plain HTTP URL used if no web seed works
+       */
+      fallbackUrl: string | null;
+    };
@@ -1,4 +1,4 @@
-use crate::core::auth::{Account, MicrosoftAccount, OfflineAccount};
+use crate::core::auth::{Account, MicrosoftAccount, OfflineAccount, YggdrasilAccount};
 use serde::{Deserialize, Serialize};
 use std::fs;
 use std::path::PathBuf;
@@ -18,6 +18,7 @@ pub struct AccountStore {
 pub enum StoredAccount {
     Offline(OfflineAccount),
     Microsoft(StoredMicrosoftAccount),
+    Yggdrasil(YggdrasilAccount),
 }
 
 /// Microsoft account with refresh token for persistence
@@ -37,6 +38,7 @@ impl StoredAccount {
         match self {
             StoredAccount::Offline(a) => a.uuid.clone(),
             StoredAccount::Microsoft(a) => a.uuid.clone(),
+            StoredAccount::Yggdrasil(a) => a.uuid.clone(),
         }
     }
 
@@ -50,6 +52,7 @@ impl StoredAccount {
                 refresh_token: a.refresh_token.clone(),
                 expires_at: a.expires_at,
             }),
+            StoredAccount::Yggdrasil(a) => Account::Yggdrasil(a.clone()),
         }
     }
 
@@ -64,6 +67,7 @@ impl StoredAccount {
                 ms_refresh_token,
                 expires_at: a.expires_at,
             }),
+            Account::Yggdrasil(a) => StoredAccount::Yggdrasil(a.clone()),
         }
     }
 }
@@ -116,6 +120,44 @@ impl AccountStorage {
         self.save(&store)
     }
 
+    /// Replace a stored account without changing the active selection
+    pub fn update_account(
+        &self,
+        account: &Account,
+        ms_refresh_token: Option<String>,
+    ) -> Result<(), String> {
+        let mut store = self.load();
+        let stored = StoredAccount::from_account(account, ms_refresh_token);
+        let id = stored.id();
+        match store.accounts.iter_mut().find(|a| a.id() == id) {
+            Some(existing) => *existing = stored,
+            None => store.accounts.push(stored),
+        }
+        self.save(&store)
+    }
+
+    /// Replace the account stored under `old_uuid` (which may change its UUID),
+    /// keeping it active if it was, in a single write
+    pub fn replace_account(&self, old_uuid: &str, account: &Account) -> Result<(), String> {
+        let mut store = self.load();
+        let new_uuid = account.uuid();
+        if new_uuid != old_uuid && store.accounts.iter().any(|a| a.id() == new_uuid) {
+            return Err("Another account already uses this name".to_string());
+        }
+        let Some(existing) = store.accounts.iter_mut().find(|a| a.id() == old_uuid) else {
+            return Err("Account not found".to_string());
+        };
+        let ms_refresh_token = match existing {
+            StoredAccount::Microsoft(m) => m.ms_refresh_token.clone(),
+            StoredAccount::Offline(_) | StoredAccount::Yggdrasil(_) => None,
+        };
+        *existing = StoredAccount::from_account(account, ms_refresh_token);
+        if store.active_account_id.as_deref() == Some(old_uuid) {
+            store.active_account_id = Some(new_uuid);
+        }
+        self.save(&store)
+    }
+
     pub fn remove_account(&self, uuid: &str) -> Result<(), String> {
         let mut store = self.load();
         store.accounts.retain(|a| a.id() != uuid);
@@ -125,6 +167,21 @@ impl AccountStorage {
         self.save(&store)
     }
 
+    /// Look up a stored account and its Microsoft refresh token by UUID
+    pub fn get_account(&self, uuid: &str) -> Option<(StoredAccount, Option<String>)> {
+        self.load()
+            .accounts
+            .into_iter()
+            .find(|a| a.id() == uuid)
+            .map(|a| {
+                let ms_token = match &a {
+                    StoredAccount::Microsoft(m) => m.ms_refresh_token.clone(),
+                    _ => None,
+                };
+                (a, ms_token)
+            })
+    }
+
     pub fn get_active_account(&self) -> Option<(StoredAccount, Option<String>)> {
         let store = self.load();
         if let Some(active_id) = &store.active_account_id {
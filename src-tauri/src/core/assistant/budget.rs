@@ -0,0 +1,259 @@
+//! Token budgeting for chat history.
+//!
+//! Token counts are estimated locally (no tokenizer dependency): roughly four
+//! characters per token for Latin text and one token per CJK character. When
+//! a conversation exceeds the model's context window the oldest turns are
+//! dropped and replaced by a short summary note.
+
+use super::Message;
+
+/// Tokens kept free for the model's reply
+const RESPONSE_RESERVE_RATIO: f32 = 0.25;
+/// Per-message overhead for role markers and separators
+const MESSAGE_OVERHEAD_TOKENS: usize = 4;
+/// Maximum characters of each dropped question kept in the summary
+const SUMMARY_SNIPPET_CHARS: usize = 80;
+
+/// Largest detected context window requested from Ollama, which reserves
+/// memory for all of it when the model is loaded
+pub const OLLAMA_AUTO_CONTEXT_CAP: u32 = 8_192;
+
+/// Best-effort context window for well-known models, in tokens
+pub fn default_context_window(model: &str) -> u32 {
+    let model = model.to_lowercase();
+    let table: &[(&str, u32)] = &[
+        ("gpt-4o", 128_000),
+        ("gpt-4-turbo", 128_000),
+        ("gpt-4.1", 1_000_000),
+        ("gpt-4", 8_192),
+        ("gpt-3.5-turbo", 16_385),
+        ("o1", 128_000),
+        ("llama3.1", 131_072),
+        ("llama3.2", 131_072),
+        ("llama3.3", 131_072),
+        ("llama3", 8_192),
+        ("qwen2.5", 32_768),
+        ("qwen", 32_768),
+        ("mistral", 32_768),
+        ("gemma2", 8_192),
+        ("deepseek", 65_536),
+        ("phi3", 4_096),
+    ];
+    table
+        .iter()
+        .find(|(prefix, _)| model.starts_with(prefix))
+        .map(|(_, window)| *window)
+        .unwrap_or(4_096)
+}
+
+pub fn estimate_tokens(text: &str) -> usize {
+    let mut wide = 0usize;
+    let mut narrow = 0usize;
+    for c in text.chars() {
+        if is_wide(c) {
+            wide += 1;
+        } else {
+            narrow += 1;
+        }
+    }
+    wide + narrow.div_ceil(4)
+}
+
+fn is_wide(c: char) -> bool {
+    matches!(c as u32,
+        0x3040..=0x30FF   // Hiragana, Katakana
+        | 0x3400..=0x4DBF // CJK Extension A
+        | 0x4E00..=0x9FFF // CJK Unified Ideographs
+        | 0xAC00..=0xD7AF // Hangul
+        | 0xF900..=0xFAFF // CJK Compatibility Ideographs
+    )
+}
+
+pub fn estimate_message_tokens(message: &Message) -> usize {
+    estimate_tokens(&message.content) + MESSAGE_OVERHEAD_TOKENS
+}
+
+/// Input budget for a context window after reserving room for the reply
+pub fn input_budget(context_window: u32) -> usize {
+    let window = context_window as usize;
+    window - (window as f32 * RESPONSE_RESERVE_RATIO) as usize
+}
+
+fn truncate_middle(text: &str, max_tokens: usize) -> String {
+    if estimate_tokens(text) <= max_tokens {
+        return text.to_string();
+    }
+    let chars: Vec<char> = text.chars().collect();
+    // Keep the instructions at the start and the freshest logs at the end
+    let mut keep = chars.len();
+    while keep > 0 {
+        keep = keep * 3 / 4;
+        let head: String = chars[..keep / 2].iter().collect();
+        let tail: String = chars[chars.len() - keep / 2..].iter().collect();
+        let candidate = format!("{}\n[... truncated ...]\n{}", head, tail);
+        if estimate_tokens(&candidate) <= max_tokens {
+            return candidate;
+        }
+    }
+    String::new()
+}
+
+fn summarize(dropped: &[Message]) -> Message {
+    let questions: Vec<String> = dropped
+        .iter()
+        .filter(|m| m.role == "user")
+        .map(|m| {
+            let snippet: String = m.content.chars().take(SUMMARY_SNIPPET_CHARS).collect();
+            let snippet = snippet.replace('\n', " ");
+            if m.content.chars().count() > SUMMARY_SNIPPET_CHARS {
+                format!("- {}...", snippet)
+            } else {
+                format!("- {}", snippet)
+            }
+        })
+        .collect();
+    let mut content = format!(
+        "{} earlier messages were omitted to fit the context window.",
+        dropped.len()
+    );
+    if !questions.is_empty() {
+        content.push_str(" The user previously asked:\n");
+        content.push_str(&questions.join("\n"));
+    }
+    Message {
+        role: "system".to_string(),
+        content,
+    }
+}
+
+/// Fit `messages` into `context_window` tokens.
+///
+/// Leading system messages and the latest message are always kept (the
+/// system prompt is shortened if needed); the oldest remaining turns are
+/// dropped first and replaced by a single summary message.
+pub fn fit_to_context(messages: Vec<Message>, context_window: u32) -> Vec<Message> {
+    let budget = input_budget(context_window);
+    let total: usize = messages.iter().map(estimate_message_tokens).sum();
+    if total <= budget || messages.len() < 2 {
+        return messages;
+    }
+
+    let system_count = messages.iter().take_while(|m| m.role == "system").count();
+    let mut system: Vec<Message> = messages[..system_count].to_vec();
+    let mut history: Vec<Message> = messages[system_count..].to_vec();
+    let Some(latest) = history.pop() else {
+        return messages;
+    };
+
+    // Reserve space for the latest message and a summary note
+    let latest_tokens = estimate_message_tokens(&latest);
+    let summary_reserve = 64 + SUMMARY_SNIPPET_CHARS / 4 * history.len().min(8);
+    let mut system_tokens: usize = system.iter().map(estimate_message_tokens).sum();
+    let system_budget = budget
+        .saturating_sub(latest_tokens)
+        .saturating_sub(summary_reserve)
+        / 2;
+    if system_tokens > system_budget
+        && let Some(first) = system.first_mut()
+    {
+        let others = system_tokens - estimate_message_tokens(first);
+        first.content = truncate_middle(
+            &first.content,
+            system_budget.saturating_sub(others + MESSAGE_OVERHEAD_TOKENS),
+        );
+        system_tokens = system.iter().map(estimate_message_tokens).sum();
+    }
+
+    let mut remaining = budget
+        .saturating_sub(system_tokens)
+        .saturating_sub(latest_tokens)
+        .saturating_sub(summary_reserve);
+
+    // Keep as many recent turns as fit, newest first
+    let mut kept = Vec::new();
+    while let Some(message) = history.pop() {
+        let tokens = estimate_message_tokens(&message);
+        if tokens > remaining {
+            history.push(message);
+            break;
+        }
+        remaining -= tokens;
+        kept.push(message);
+    }
+    kept.reverse();
+
+    let mut result = system;
+    if !history.is_empty() {
+        result.push(summarize(&history));
+    }
+    result.extend(kept);
+    result.push(latest);
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn msg(role: &str, content: &str) -> Message {
+        Message {
+            role: role.to_string(),
+            content: content.to_string(),
+        }
+    }
+
+    #[test]
+    fn test_estimate_tokens() {
+        assert_eq!(estimate_tokens(""), 0);
+        assert_eq!(estimate_tokens("abcd"), 1);
+        assert_eq!(estimate_tokens("abcde"), 2);
+        assert_eq!(estimate_tokens("你好"), 2);
+    }
+
+    #[test]
+    fn test_default_context_window() {
+        assert_eq!(default_context_window("llama3"), 8_192);
+        assert_eq!(default_context_window("llama3.1:8b"), 131_072);
+        assert_eq!(default_context_window("gpt-3.5-turbo"), 16_385);
+        assert_eq!(default_context_window("unknown-model"), 4_096);
+    }
+
+    #[test]
+    fn test_fit_keeps_short_conversation() {
+        let messages = vec![msg("system", "prompt"), msg("user", "hi")];
+        let fitted = fit_to_context(messages.clone(), 4_096);
+        assert_eq!(fitted.len(), messages.len());
+    }
+
+    #[test]
+    fn test_fit_drops_oldest_turns() {
+        let long = "x".repeat(2_000);
+        let mut messages = vec![msg("system", "prompt")];
+        for i in 0..10 {
+            messages.push(msg("user", &format!("question {} {}", i, long)));
+            messages.push(msg("assistant", &long));
+        }
+        messages.push(msg("user", "latest question"));
+
+        let fitted = fit_to_context(messages, 2_048);
+        let total: usize = fitted.iter().map(estimate_message_tokens).sum();
+        assert!(total <= input_budget(2_048));
+        assert_eq!(fitted[0].content, "prompt");
+        assert!(fitted[1].content.contains("earlier messages were omitted"));
+        assert_eq!(fitted.last().unwrap().content, "latest question");
+    }
+
+    #[test]
+    fn test_fit_truncates_oversized_system_prompt() {
+        let logs = "log line\n".repeat(5_000);
+        let messages = vec![
+            msg("system", &format!("instructions\n{}END", logs)),
+            msg("user", "why did it crash?"),
+        ];
+        let fitted = fit_to_context(messages, 1_024);
+        let total: usize = fitted.iter().map(estimate_message_tokens).sum();
+        assert!(total <= input_budget(1_024));
+        assert!(fitted[0].content.starts_with("instructions"));
+        assert!(fitted[0].content.ends_with("END"));
+    }
+}
@@ -0,0 +1,161 @@
+//! Launcher state injected into the assistant's system prompt.
+
+use super::AssistantState;
+use crate::core::instance::{Instance, InstanceState};
+use tauri::{AppHandle, Manager};
+
+/// Upper bound for the launcher context block, in characters
+pub const MAX_CONTEXT_CHARS: usize = 1500;
+
+/// Snapshot of the launcher state relevant to troubleshooting
+#[derive(Debug, Clone, Default)]
+pub struct LauncherContext {
+    pub instance_name: Option<String>,
+    pub minecraft_version: Option<String>,
+    pub mod_loader: Option<String>,
+    pub mod_loader_version: Option<String>,
+    pub java: Option<String>,
+    pub mod_count: Option<usize>,
+    pub mods: Vec<String>,
+    pub os: String,
+    pub arch: String,
+}
+
+impl LauncherContext {
+    /// Render the context as `key: value` lines, dropping the mod list
+    /// first and then cutting whole lines until it fits in `max_chars`
+    pub fn render(&self, max_chars: usize) -> String {
+        let mut lines = vec![format!("OS: {} ({})", self.os, self.arch)];
+        if let Some(name) = &self.instance_name {
+            lines.push(format!("Active instance: {}", name));
+        }
+        if let Some(version) = &self.minecraft_version {
+            lines.push(format!("Minecraft version: {}", version));
+        }
+        match (&self.mod_loader, &self.mod_loader_version) {
+            (Some(loader), Some(version)) => {
+                lines.push(format!("Mod loader: {} {}", loader, version))
+            }
+            (Some(loader), None) => lines.push(format!("Mod loader: {}", loader)),
+            _ => {}
+        }
+        if let Some(java) = &self.java {
+            lines.push(format!("Java in use: {}", java));
+        }
+        if let Some(count) = self.mod_count {
+            lines.push(format!("Installed mods: {}", count));
+        }
+
+        let base = lines.join("\n");
+        if !self.mods.is_empty() {
+            let with_mods = format!("{}\nMods: {}", base, self.mods.join(", "));
+            if with_mods.chars().count() <= max_chars {
+                return with_mods;
+            }
+        }
+
+        let mut out = String::new();
+        for line in lines {
+            let needed = if out.is_empty() { 0 } else { 1 } + line.chars().count();
+            if out.chars().count() + needed > max_chars {
+                break;
+            }
+            if !out.is_empty() {
+                out.push('\n');
+            }
+            out.push_str(&line);
+        }
+        out
+    }
+}
+
+fn list_mods(instance: &Instance) -> Option<Vec<String>> {
+    let entries = std::fs::read_dir(instance.game_dir.join("mods")).ok()?;
+    let mut mods: Vec<String> = entries
+        .flatten()
+        .filter_map(|e| {
+            let name = e.file_name().to_string_lossy().to_string();
+            name.ends_with(".jar").then_some(name)
+        })
+        .collect();
+    mods.sort();
+    Some(mods)
+}
+
+/// Collect the launcher state the assistant should know about and render it,
+/// capped at [`MAX_CONTEXT_CHARS`]
+pub fn build_assistant_context(app_handle: &AppHandle) -> String {
+    let mut context = LauncherContext {
+        os: std::env::consts::OS.to_string(),
+        arch: std::env::consts::ARCH.to_string(),
+        ..Default::default()
+    };
+
+    if let Some(instance_state) = app_handle.try_state::<InstanceState>()
+        && let Some(instance) = instance_state.get_active_instance()
+    {
+        context.instance_name = Some(instance.name.clone());
+        context.minecraft_version = instance.version_id.clone();
+        context.mod_loader = instance.mod_loader.clone();
+        context.mod_loader_version = instance.mod_loader_version.clone();
+        if let Some(mods) = list_mods(&instance) {
+            context.mod_count = Some(mods.len());
+            context.mods = mods;
+        }
+    }
+
+    if let Some(assistant_state) = app_handle.try_state::<AssistantState>() {
+        context.java = assistant_state
+            .assistant
+            .lock()
+            .unwrap()
+            .java_in_use
+            .clone();
+    }
+
+    context.render(MAX_CONTEXT_CHARS)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sample() -> LauncherContext {
+        LauncherContext {
+            instance_name: Some("Survival".to_string()),
+            minecraft_version: Some("1.20.4".to_string()),
+            mod_loader: Some("fabric".to_string()),
+            mod_loader_version: Some("0.15.7".to_string()),
+            java: Some("17.0.10 (Eclipse Adoptium, x64)".to_string()),
+            mod_count: Some(2),
+            mods: vec!["sodium.jar".to_string(), "lithium.jar".to_string()],
+            os: "linux".to_string(),
+            arch: "x86_64".to_string(),
+        }
+    }
+
+    #[test]
+    fn test_render_includes_all_fields() {
+        let rendered = sample().render(MAX_CONTEXT_CHARS);
+        assert!(rendered.contains("Minecraft version: 1.20.4"));
+        assert!(rendered.contains("Mod loader: fabric 0.15.7"));
+        assert!(rendered.contains("Java in use: 17.0.10"));
+        assert!(rendered.contains("Mods: sodium.jar, lithium.jar"));
+    }
+
+    #[test]
+    fn test_render_drops_mod_list_first() {
+        let mut ctx = sample();
+        ctx.mods = (0..500).map(|i| format!("mod-{}.jar", i)).collect();
+        let rendered = ctx.render(300);
+        assert!(!rendered.contains("Mods:"));
+        assert!(rendered.contains("Installed mods: 2"));
+    }
+
+    #[test]
+    fn test_render_respects_cap() {
+        let rendered = sample().render(40);
+        assert!(rendered.chars().count() <= 40);
+        assert!(rendered.starts_with("OS: linux"));
+    }
+}
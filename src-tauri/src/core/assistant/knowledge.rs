@@ -0,0 +1,195 @@
+//! Embedded knowledge base of common Minecraft errors.
+//!
+//! Entries map error signatures (regular expressions matched against logs or
+//! the user's question) to an explanation and a list of remedies. The
+//! assistant consults it before calling the LLM so classic errors get an
+//! instant answer even when no model is available.
+
+use serde::{Deserialize, Serialize};
+use std::sync::LazyLock;
+use ts_rs::TS;
+
+const KNOWLEDGE_BASE_JSON: &str = include_str!("knowledge_base.json");
+
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export, export_to = "assistant.ts")]
+pub struct KnowledgeEntry {
+    pub id: String,
+    pub title: String,
+    pub patterns: Vec<String>,
+    pub explanation: String,
+    pub remedies: Vec<String>,
+}
+
+/// A knowledge base entry that matched, with the text that triggered it
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export, export_to = "assistant.ts")]
+pub struct KnowledgeMatch {
+    pub entry: KnowledgeEntry,
+    pub matched_text: String,
+}
+
+struct CompiledEntry {
+    entry: KnowledgeEntry,
+    patterns: Vec<regex::Regex>,
+}
+
+static KNOWLEDGE_BASE: LazyLock<Vec<CompiledEntry>> = LazyLock::new(|| {
+    let entries: Vec<KnowledgeEntry> =
+        serde_json::from_str(KNOWLEDGE_BASE_JSON).expect("embedded knowledge base is invalid");
+    entries
+        .into_iter()
+        .map(|entry| {
+            let patterns = entry
+                .patterns
+                .iter()
+                .filter_map(|p| {
+                    regex::RegexBuilder::new(p)
+                        .case_insensitive(true)
+                        .build()
+                        .map_err(|e| eprintln!("[Assistant] Invalid pattern '{}': {}", p, e))
+                        .ok()
+                })
+                .collect();
+            CompiledEntry { entry, patterns }
+        })
+        .collect()
+});
+
+/// Get all entries of the knowledge base
+pub fn entries() -> Vec<KnowledgeEntry> {
+    KNOWLEDGE_BASE.iter().map(|c| c.entry.clone()).collect()
+}
+
+/// Find entries whose error signatures occur in `text`.
+///
+/// Each entry is reported at most once, in knowledge base order.
+pub fn match_text(text: &str) -> Vec<KnowledgeMatch> {
+    let mut matches = Vec::new();
+    for compiled in KNOWLEDGE_BASE.iter() {
+        if let Some(m) = compiled.patterns.iter().find_map(|re| re.find(text)) {
+            matches.push(KnowledgeMatch {
+                entry: compiled.entry.clone(),
+                matched_text: m.as_str().to_string(),
+            });
+        }
+    }
+    matches
+}
+
+/// Free-text search over titles, explanations and signatures.
+///
+/// Signature matches come first, followed by entries where every query word
+/// appears in the title or explanation.
+pub fn search(query: &str) -> Vec<KnowledgeEntry> {
+    let query = query.trim();
+    if query.is_empty() {
+        return entries();
+    }
+
+    let mut results: Vec<KnowledgeEntry> = match_text(query).into_iter().map(|m| m.entry).collect();
+
+    let words: Vec<String> = query.split_whitespace().map(|w| w.to_lowercase()).collect();
+    for compiled in KNOWLEDGE_BASE.iter() {
+        if results.iter().any(|e| e.id == compiled.entry.id) {
+            continue;
+        }
+        let haystack = format!(
+            "{} {} {}",
+            compiled.entry.title,
+            compiled.entry.explanation,
+            compiled.entry.patterns.join(" ")
+        )
+        .to_lowercase();
+        if words.iter().all(|w| haystack.contains(w.as_str())) {
+            results.push(compiled.entry.clone());
+        }
+    }
+    results
+}
+
+/// Render matches as a markdown answer shown directly to the user
+pub fn format_answer(matches: &[KnowledgeMatch]) -> String {
+    let mut out = String::new();
+    for (i, m) in matches.iter().enumerate() {
+        if i > 0 {
+            out.push_str("\n\n");
+        }
+        out.push_str(&format!(
+            "**{}**\n\nDetected: `{}`\n\n{}\n",
+            m.entry.title, m.matched_text, m.entry.explanation
+        ));
+        for remedy in &m.entry.remedies {
+            out.push_str(&format!("\n- {}", remedy));
+        }
+    }
+    out
+}
+
+/// Render matches as plain context for the system prompt
+pub fn format_context(matches: &[KnowledgeMatch]) -> String {
+    matches
+        .iter()
+        .map(|m| {
+            format!(
+                "- {} (matched \"{}\"): {} Remedies: {}",
+                m.entry.title,
+                m.matched_text,
+                m.entry.explanation,
+                m.entry.remedies.join(" ")
+            )
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_embedded_knowledge_base_loads() {
+        let all = entries();
+        assert!(!all.is_empty());
+        for compiled in KNOWLEDGE_BASE.iter() {
+            assert_eq!(
+                compiled.patterns.len(),
+                compiled.entry.patterns.len(),
+                "entry {} has an invalid pattern",
+                compiled.entry.id
+            );
+        }
+    }
+
+    #[test]
+    fn test_match_unsupported_class_version() {
+        let log = "Exception in thread \"main\" java.lang.IllegalArgumentException: Unsupported class file major version 65";
+        let matches = match_text(log);
+        assert_eq!(matches[0].entry.id, "unsupported-class-version");
+        assert_eq!(
+            matches[0].matched_text,
+            "Unsupported class file major version 65"
+        );
+    }
+
+    #[test]
+    fn test_match_is_case_insensitive() {
+        let matches = match_text("got java.lang.outofmemoryerror: Java heap space");
+        assert_eq!(matches.len(), 1);
+        assert_eq!(matches[0].entry.id, "out-of-memory");
+    }
+
+    #[test]
+    fn test_no_match_for_unrelated_text() {
+        assert!(match_text("How do I install shaders?").is_empty());
+    }
+
+    #[test]
+    fn test_search_by_keywords() {
+        let results = search("memory heap");
+        assert!(results.iter().any(|e| e.id == "out-of-memory"));
+        assert!(search("").len() == entries().len());
+    }
+}
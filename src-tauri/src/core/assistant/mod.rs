@@ -1,7 +1,12 @@
+pub mod budget;
+pub mod context;
+pub mod knowledge;
+pub mod rag;
+
 use super::config::AssistantConfig;
 use futures::StreamExt;
 use serde::{Deserialize, Serialize};
-use std::collections::VecDeque;
+use std::collections::{HashMap, VecDeque};
 use std::sync::{Arc, Mutex};
 use tauri::{Emitter, Window};
 use ts_rs::TS;
@@ -19,6 +24,14 @@ pub struct OllamaChatRequest {
     pub model: String,
     pub messages: Vec<Message>,
     pub stream: bool,
+    pub options: OllamaOptions,
+}
+
+#[derive(Debug, Serialize)]
+pub struct OllamaOptions {
+    // Ollama defaults to a small context; match the budget used for truncation
+    // (see context_window)
+    pub num_ctx: u32,
 }
 
 #[derive(Debug, Deserialize)]
@@ -53,6 +66,38 @@ pub struct OllamaTagsResponse {
     pub models: Vec<OllamaModel>,
 }
 
+// Ollama pull progress (each line of /api/pull is a JSON object)
+#[derive(Debug, Deserialize)]
+pub struct OllamaPullResponse {
+    pub status: Option<String>,
+    pub total: Option<u64>,
+    pub completed: Option<u64>,
+    pub error: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export, export_to = "assistant.ts")]
+pub struct OllamaPullProgress {
+    pub model: String,
+    pub status: String,
+    pub total: Option<u64>,
+    pub completed: Option<u64>,
+    pub percent: Option<f64>,
+    pub done: bool,
+}
+
+/// Result of a deep health check
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export, export_to = "assistant.ts")]
+pub struct AssistantHealth {
+    pub reachable: bool,
+    pub model_available: bool,
+    pub model: String,
+    pub message: Option<String>,
+}
+
 // Simplified model info for frontend
 #[derive(Debug, Clone, Serialize, Deserialize, TS)]
 #[serde(rename_all = "camelCase")]
@@ -175,6 +220,14 @@ pub struct GameAssistant {
     client: reqwest::Client,
     pub log_buffer: VecDeque<String>,
     pub max_log_lines: usize,
+    // Longer history used by embedding-based log search
+    pub log_history: VecDeque<String>,
+    pub max_history_lines: usize,
+    // Lines ever added; unlike the history length it changes when a full
+    // history rotates
+    log_count: u64,
+    log_index: Arc<Mutex<rag::LogIndex>>,
+    pub java_in_use: Option<String>, // Java of the last launch, for assistant context
 }
 
 impl GameAssistant {
@@ -183,6 +236,11 @@ impl GameAssistant {
             client: reqwest::Client::new(),
             log_buffer: VecDeque::new(),
             max_log_lines: 100,
+            log_history: VecDeque::new(),
+            max_history_lines: 5000,
+            log_count: 0,
+            log_index: Arc::new(Mutex::new(rag::LogIndex::default())),
+            java_in_use: None,
         }
     }
 
@@ -190,7 +248,20 @@ impl GameAssistant {
         if self.log_buffer.len() >= self.max_log_lines {
             self.log_buffer.pop_front();
         }
-        self.log_buffer.push_back(line);
+        self.log_buffer.push_back(line.clone());
+
+        if self.log_history.len() >= self.max_history_lines {
+            self.log_history.pop_front();
+        }
+        self.log_history.push_back(line);
+        self.log_count += 1;
+    }
+
+    /// Forget the game log history and its search index
+    pub fn clear_logs(&mut self) {
+        self.log_buffer.clear();
+        self.log_history.clear();
+        *self.log_index.lock().unwrap() = rag::LogIndex::default();
     }
 
     pub fn get_log_context(&self) -> String {
@@ -201,6 +272,99 @@ impl GameAssistant {
             .join("\n")
     }
 
+    /// Retrieve the log chunks most relevant to `question` from the log history.
+    ///
+    /// Chunk embeddings are cached and reused across questions; only chunks
+    /// whose text changed since the last call are sent to the provider.
+    pub async fn retrieve_log_context(
+        &self,
+        question: &str,
+        config: &AssistantConfig,
+    ) -> Result<String, String> {
+        let lines: Vec<String> = self.log_history.iter().cloned().collect();
+        if lines.is_empty() {
+            return Ok(String::new());
+        }
+
+        let model = if config.llm_provider == "openai" {
+            config.openai_embedding_model.clone()
+        } else {
+            config.ollama_embedding_model.clone()
+        };
+
+        let cached = self.log_index.lock().unwrap().clone();
+        let mut index = if cached.model == model && cached.indexed_count == self.log_count {
+            cached
+        } else {
+            let chunks = rag::chunk_lines(&lines, rag::CHUNK_LINES, rag::CHUNK_OVERLAP);
+            let known: HashMap<&str, &Vec<f32>> = if cached.model == model {
+                cached
+                    .chunks
+                    .iter()
+                    .map(|c| c.text.as_str())
+                    .zip(cached.embeddings.iter())
+                    .collect()
+            } else {
+                HashMap::new()
+            };
+
+            let missing: Vec<String> = chunks
+                .iter()
+                .filter(|c| !known.contains_key(c.text.as_str()))
+                .map(|c| c.text.clone())
+                .collect();
+            let mut fresh = rag::embed(&self.client, config, &missing)
+                .await?
+                .into_iter();
+
+            let embeddings = chunks
+                .iter()
+                .map(|c| match known.get(c.text.as_str()) {
+                    Some(e) => (*e).clone(),
+                    None => fresh.next().unwrap_or_default(),
+                })
+                .collect();
+
+            rag::LogIndex {
+                model,
+                chunks,
+                embeddings,
+                indexed_count: self.log_count,
+            }
+        };
+
+        let query = rag::embed(&self.client, config, &[question.to_string()])
+            .await?
+            .pop()
+            .unwrap_or_default();
+        let context = index
+            .top_chunks(&query, config.log_search_top_k as usize)
+            .into_iter()
+            .map(|c| format!("[from line {}]\n{}", c.start_line + 1, c.text))
+            .collect::<Vec<_>>()
+            .join("\n...\n");
+
+        std::mem::swap(&mut *self.log_index.lock().unwrap(), &mut index);
+        Ok(context)
+    }
+
+    /// Log context for a conversation: retrieved chunks when log search is
+    /// enabled and the history is longer than the recent buffer, otherwise
+    /// the most recent lines
+    async fn log_context_for(&self, messages: &[Message], config: &AssistantConfig) -> String {
+        if config.log_search_enabled
+            && self.log_history.len() > self.max_log_lines
+            && let Some(question) = last_user_message(messages)
+        {
+            match self.retrieve_log_context(question, config).await {
+                Ok(context) if !context.is_empty() => return context,
+                Ok(_) => {}
+                Err(e) => println!("[Assistant] Log search failed, using recent logs: {}", e),
+            }
+        }
+        self.get_log_context()
+    }
+
     pub async fn check_health(&self, config: &AssistantConfig) -> bool {
         if config.llm_provider == "ollama" {
             match self
@@ -220,48 +384,225 @@ impl GameAssistant {
         }
     }
 
-    pub async fn chat(
-        &self,
-        mut messages: Vec<Message>,
-        config: &AssistantConfig,
-    ) -> Result<Message, String> {
-        // Inject system prompt and log context
-        if !messages.iter().any(|m| m.role == "system") {
-            let context = self.get_log_context();
-            let mut system_content = config.system_prompt.clone();
-
-            // Add language instruction if not auto
-            if config.response_language != "auto" {
-                system_content = format!(
-                    "{}\n\nIMPORTANT: Respond in {}. Do not include Pinyin or English translations unless explicitly requested.",
-                    system_content, config.response_language
-                );
+    /// Like [`check_health`](Self::check_health), but for Ollama also verify
+    /// that the configured model has been pulled
+    pub async fn check_health_detailed(&self, config: &AssistantConfig) -> AssistantHealth {
+        if config.llm_provider == "ollama" {
+            let model = config.ollama_model.clone();
+            match self.list_ollama_models(&config.ollama_endpoint).await {
+                Ok(models) => {
+                    let available = models.iter().any(|m| ollama_model_matches(&m.id, &model));
+                    AssistantHealth {
+                        reachable: true,
+                        model_available: available,
+                        message: (!available).then(|| {
+                            format!(
+                                "Model '{}' is not installed in Ollama. Pull it to use the assistant.",
+                                model
+                            )
+                        }),
+                        model,
+                    }
+                }
+                Err(e) => AssistantHealth {
+                    reachable: false,
+                    model_available: false,
+                    model,
+                    message: Some(e),
+                },
+            }
+        } else if config.llm_provider == "openai" {
+            let healthy = self.check_health(config).await;
+            AssistantHealth {
+                reachable: healthy,
+                model_available: healthy,
+                model: config.openai_model.clone(),
+                message: (!healthy).then(|| "OpenAI API key not configured".to_string()),
+            }
+        } else {
+            AssistantHealth {
+                reachable: false,
+                model_available: false,
+                model: String::new(),
+                message: Some(format!("Unknown LLM provider: {}", config.llm_provider)),
             }
+        }
+    }
+
+    /// Pull a model into Ollama, emitting `ollama-pull-progress` events
+    pub async fn pull_ollama_model(
+        &self,
+        endpoint: &str,
+        model: &str,
+        window: &Window,
+    ) -> Result<(), String> {
+        let response = self
+            .client
+            .post(format!("{}/api/pull", endpoint))
+            .json(&serde_json::json!({ "model": model, "stream": true }))
+            .send()
+            .await
+            .map_err(|e| format!("Failed to connect to Ollama: {}", e))?;
 
-            // Add log context if available
-            if !context.is_empty() {
-                system_content = format!(
-                    "{}\n\nRecent game logs:\n```\n{}\n```",
-                    system_content, context
+        if !response.status().is_success() {
+            return Err(format!("Ollama API error: {}", response.status()));
+        }
+
+        let mut stream = response.bytes_stream();
+        let mut buffer = String::new();
+
+        while let Some(chunk_result) = stream.next().await {
+            let chunk = chunk_result.map_err(|e| format!("Stream error: {}", e))?;
+            buffer.push_str(&String::from_utf8_lossy(&chunk));
+
+            // Process complete lines
+            while let Some(pos) = buffer.find('\n') {
+                let line = buffer[..pos].trim().to_string();
+                buffer = buffer[pos + 1..].to_string();
+                if line.is_empty() {
+                    continue;
+                }
+
+                let Ok(progress) = serde_json::from_str::<OllamaPullResponse>(&line) else {
+                    continue;
+                };
+                if let Some(error) = progress.error {
+                    return Err(format!("Failed to pull {}: {}", model, error));
+                }
+
+                let status = progress.status.unwrap_or_default();
+                let done = status == "success";
+                let percent = match (progress.total, progress.completed) {
+                    (Some(total), Some(completed)) if total > 0 => {
+                        Some(completed as f64 / total as f64 * 100.0)
+                    }
+                    _ => None,
+                };
+                let _ = window.emit(
+                    "ollama-pull-progress",
+                    OllamaPullProgress {
+                        model: model.to_string(),
+                        status,
+                        total: progress.total,
+                        completed: progress.completed,
+                        percent,
+                        done,
+                    },
                 );
             }
+        }
 
-            messages.insert(
-                0,
-                Message {
-                    role: "system".to_string(),
-                    content: system_content,
-                },
-            );
+        Ok(())
+    }
+
+    pub async fn chat(
+        &self,
+        mut messages: Vec<Message>,
+        config: &AssistantConfig,
+        launcher_context: &str,
+    ) -> Result<Message, String> {
+        // Classic errors pasted into the question are answered locally
+        let question_matches = last_user_message(&messages)
+            .map(knowledge::match_text)
+            .unwrap_or_default();
+        if !question_matches.is_empty() {
+            return Ok(Message {
+                role: "assistant".to_string(),
+                content: knowledge::format_answer(&question_matches),
+            });
         }
 
-        if config.llm_provider == "ollama" {
+        let log_context = self.log_context_for(&messages, config).await;
+        let log_matches = knowledge::match_text(&log_context);
+        Self::inject_system_prompt(
+            &mut messages,
+            config,
+            launcher_context,
+            &log_context,
+            &log_matches,
+        );
+        let messages = budget::fit_to_context(messages, context_window(config));
+
+        let result = if config.llm_provider == "ollama" {
             self.chat_ollama(messages, config).await
         } else if config.llm_provider == "openai" {
             self.chat_openai(messages, config).await
         } else {
             Err(format!("Unknown LLM provider: {}", config.llm_provider))
+        };
+
+        // Fall back to the knowledge base when the model is unreachable
+        match result {
+            Err(e) if !log_matches.is_empty() => {
+                println!(
+                    "[Assistant] LLM unavailable ({}), answering from knowledge base",
+                    e
+                );
+                Ok(Message {
+                    role: "assistant".to_string(),
+                    content: knowledge::format_answer(&log_matches),
+                })
+            }
+            other => other,
+        }
+    }
+
+    /// Insert the system prompt, language instruction, launcher state,
+    /// known-error hints and log context unless the caller already supplied
+    /// a system message
+    fn inject_system_prompt(
+        messages: &mut Vec<Message>,
+        config: &AssistantConfig,
+        launcher_context: &str,
+        log_context: &str,
+        knowledge_matches: &[knowledge::KnowledgeMatch],
+    ) {
+        if messages.iter().any(|m| m.role == "system") {
+            return;
+        }
+
+        let mut system_content = config.system_prompt.clone();
+
+        // Add language instruction if not auto
+        if config.response_language != "auto" {
+            system_content = format!(
+                "{}\n\nIMPORTANT: Respond in {}. Do not include Pinyin or English translations unless explicitly requested.",
+                system_content, config.response_language
+            );
+        }
+
+        // Add launcher state (instance, loader, Java, platform)
+        if !launcher_context.is_empty() {
+            system_content = format!(
+                "{}\n\nLauncher context:\n{}",
+                system_content, launcher_context
+            );
         }
+
+        // Add known error explanations so the model starts from a verified diagnosis
+        if !knowledge_matches.is_empty() {
+            system_content = format!(
+                "{}\n\nKnown issues detected in the logs:\n{}",
+                system_content,
+                knowledge::format_context(knowledge_matches)
+            );
+        }
+
+        // Add log context if available
+        if !log_context.is_empty() {
+            system_content = format!(
+                "{}\n\nRecent game logs:\n```\n{}\n```",
+                system_content, log_context
+            );
+        }
+
+        messages.insert(
+            0,
+            Message {
+                role: "system".to_string(),
+                content: system_content,
+            },
+        );
     }
 
     async fn chat_ollama(
@@ -273,6 +614,9 @@ impl GameAssistant {
             model: config.ollama_model.clone(),
             messages,
             stream: false,
+            options: OllamaOptions {
+                num_ctx: context_window(config),
+            },
         };
 
         let response = self
@@ -439,36 +783,37 @@ impl GameAssistant {
         &self,
         mut messages: Vec<Message>,
         config: &AssistantConfig,
+        launcher_context: &str,
         window: &Window,
     ) -> Result<String, String> {
-        // Inject system prompt and log context
-        if !messages.iter().any(|m| m.role == "system") {
-            let context = self.get_log_context();
-            let mut system_content = config.system_prompt.clone();
-
-            if config.response_language != "auto" {
-                system_content = format!(
-                    "{}\n\nIMPORTANT: Respond in {}. Do not include Pinyin or English translations unless explicitly requested.",
-                    system_content, config.response_language
-                );
-            }
-
-            if !context.is_empty() {
-                system_content = format!(
-                    "{}\n\nRecent game logs:\n```\n{}\n```",
-                    system_content, context
-                );
-            }
-
-            messages.insert(
-                0,
-                Message {
-                    role: "system".to_string(),
-                    content: system_content,
+        // Classic errors pasted into the question are answered locally
+        let question_matches = last_user_message(&messages)
+            .map(knowledge::match_text)
+            .unwrap_or_default();
+        if !question_matches.is_empty() {
+            let answer = knowledge::format_answer(&question_matches);
+            let _ = window.emit(
+                "assistant-stream",
+                StreamChunk {
+                    content: answer.clone(),
+                    done: true,
+                    stats: None,
                 },
             );
+            return Ok(answer);
         }
 
+        let log_context = self.log_context_for(&messages, config).await;
+        let log_matches = knowledge::match_text(&log_context);
+        Self::inject_system_prompt(
+            &mut messages,
+            config,
+            launcher_context,
+            &log_context,
+            &log_matches,
+        );
+        let messages = budget::fit_to_context(messages, context_window(config));
+
         if config.llm_provider == "ollama" {
             self.chat_stream_ollama(messages, config, window).await
         } else if config.llm_provider == "openai" {
@@ -488,6 +833,9 @@ impl GameAssistant {
             model: config.ollama_model.clone(),
             messages,
             stream: true,
+            options: OllamaOptions {
+                num_ctx: context_window(config),
+            },
         };
 
         let response = self
@@ -680,6 +1028,36 @@ impl GameAssistant {
     }
 }
 
+/// Ollama reports `name:tag`; a configured name without a tag means `latest`
+fn ollama_model_matches(installed: &str, configured: &str) -> bool {
+    if installed == configured {
+        return true;
+    }
+    !configured.contains(':') && installed == format!("{}:latest", configured)
+}
+
+/// Context window of the configured model, falling back to a per-model
+/// default. Ollama allocates the whole window it is asked for, so a detected
+/// one is capped; a larger one has to be configured explicitly.
+fn context_window(config: &AssistantConfig) -> u32 {
+    if config.context_window > 0 {
+        return config.context_window;
+    }
+    if config.llm_provider == "openai" {
+        budget::default_context_window(&config.openai_model)
+    } else {
+        budget::default_context_window(&config.ollama_model).min(budget::OLLAMA_AUTO_CONTEXT_CAP)
+    }
+}
+
+fn last_user_message(messages: &[Message]) -> Option<&str> {
+    messages
+        .iter()
+        .rev()
+        .find(|m| m.role == "user")
+        .map(|m| m.content.as_str())
+}
+
 fn format_size(bytes: u64) -> String {
     const KB: u64 = 1024;
     const MB: u64 = KB * 1024;
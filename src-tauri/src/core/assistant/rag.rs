@@ -0,0 +1,226 @@
+//! Embedding-based retrieval over game logs.
+//!
+//! Logs are split into overlapping line chunks, embedded with the configured
+//! provider and cached in a [`LogIndex`]. For each question the most similar
+//! chunks are returned so the model sees the relevant part of a long log
+//! instead of only the last lines.
+
+use serde::{Deserialize, Serialize};
+
+/// Lines per chunk
+pub const CHUNK_LINES: usize = 40;
+/// Lines shared between consecutive chunks
+pub const CHUNK_OVERLAP: usize = 8;
+/// Chunks embedded per request
+const EMBED_BATCH_SIZE: usize = 32;
+
+#[derive(Debug, Clone)]
+pub struct LogChunk {
+    pub start_line: usize,
+    pub text: String,
+}
+
+/// Cached embeddings of the log history
+#[derive(Debug, Clone, Default)]
+pub struct LogIndex {
+    pub model: String,
+    pub chunks: Vec<LogChunk>,
+    pub embeddings: Vec<Vec<f32>>,
+    /// Count of log lines ever added when the index was built
+    pub indexed_count: u64,
+}
+
+#[derive(Debug, Serialize)]
+struct OllamaEmbedRequest<'a> {
+    model: &'a str,
+    input: &'a [String],
+}
+
+#[derive(Debug, Deserialize)]
+struct OllamaEmbedResponse {
+    embeddings: Vec<Vec<f32>>,
+}
+
+#[derive(Debug, Serialize)]
+struct OpenAIEmbedRequest<'a> {
+    model: &'a str,
+    input: &'a [String],
+}
+
+#[derive(Debug, Deserialize)]
+struct OpenAIEmbedData {
+    index: usize,
+    embedding: Vec<f32>,
+}
+
+#[derive(Debug, Deserialize)]
+struct OpenAIEmbedResponse {
+    data: Vec<OpenAIEmbedData>,
+}
+
+/// Split log lines into overlapping chunks of `size` lines
+pub fn chunk_lines(lines: &[String], size: usize, overlap: usize) -> Vec<LogChunk> {
+    let size = size.max(1);
+    let step = size.saturating_sub(overlap).max(1);
+    let mut chunks = Vec::new();
+    let mut start = 0;
+    while start < lines.len() {
+        let end = (start + size).min(lines.len());
+        chunks.push(LogChunk {
+            start_line: start,
+            text: lines[start..end].join("\n"),
+        });
+        if end == lines.len() {
+            break;
+        }
+        start += step;
+    }
+    chunks
+}
+
+pub fn cosine_similarity(a: &[f32], b: &[f32]) -> f32 {
+    if a.len() != b.len() || a.is_empty() {
+        return 0.0;
+    }
+    let mut dot = 0.0f32;
+    let mut norm_a = 0.0f32;
+    let mut norm_b = 0.0f32;
+    for (x, y) in a.iter().zip(b) {
+        dot += x * y;
+        norm_a += x * x;
+        norm_b += y * y;
+    }
+    if norm_a == 0.0 || norm_b == 0.0 {
+        return 0.0;
+    }
+    dot / (norm_a.sqrt() * norm_b.sqrt())
+}
+
+impl LogIndex {
+    /// Return the `top_k` chunks most similar to `query`, in log order
+    pub fn top_chunks(&self, query: &[f32], top_k: usize) -> Vec<&LogChunk> {
+        let mut scored: Vec<(usize, f32)> = self
+            .embeddings
+            .iter()
+            .enumerate()
+            .map(|(i, e)| (i, cosine_similarity(query, e)))
+            .collect();
+        scored.sort_by(|a, b| b.1.total_cmp(&a.1));
+        let mut picked: Vec<usize> = scored.into_iter().take(top_k).map(|(i, _)| i).collect();
+        picked.sort_unstable();
+        picked.into_iter().map(|i| &self.chunks[i]).collect()
+    }
+}
+
+/// Embed `input` with the configured provider's embedding model
+pub async fn embed(
+    client: &reqwest::Client,
+    config: &crate::core::config::AssistantConfig,
+    input: &[String],
+) -> Result<Vec<Vec<f32>>, String> {
+    let mut all = Vec::with_capacity(input.len());
+    for batch in input.chunks(EMBED_BATCH_SIZE) {
+        let embeddings = if config.llm_provider == "ollama" {
+            let response = client
+                .post(format!("{}/api/embed", config.ollama_endpoint))
+                .json(&OllamaEmbedRequest {
+                    model: &config.ollama_embedding_model,
+                    input: batch,
+                })
+                .send()
+                .await
+                .map_err(|e| format!("Ollama embedding request failed: {}", e))?;
+            if !response.status().is_success() {
+                return Err(format!(
+                    "Ollama embedding API returned error: {}",
+                    response.status()
+                ));
+            }
+            let parsed: OllamaEmbedResponse = response
+                .json()
+                .await
+                .map_err(|e| format!("Failed to parse Ollama embeddings: {}", e))?;
+            parsed.embeddings
+        } else if config.llm_provider == "openai" {
+            let api_key = config
+                .openai_api_key
+                .as_ref()
+                .ok_or("OpenAI API key not configured")?;
+            let response = client
+                .post(format!("{}/embeddings", config.openai_endpoint))
+                .header("Authorization", format!("Bearer {}", api_key))
+                .json(&OpenAIEmbedRequest {
+                    model: &config.openai_embedding_model,
+                    input: batch,
+                })
+                .send()
+                .await
+                .map_err(|e| format!("OpenAI embedding request failed: {}", e))?;
+            if !response.status().is_success() {
+                let status = response.status();
+                let error_text = response.text().await.unwrap_or_default();
+                return Err(format!("OpenAI API error ({}): {}", status, error_text));
+            }
+            let mut parsed: OpenAIEmbedResponse = response
+                .json()
+                .await
+                .map_err(|e| format!("Failed to parse OpenAI embeddings: {}", e))?;
+            parsed.data.sort_by_key(|d| d.index);
+            parsed.data.into_iter().map(|d| d.embedding).collect()
+        } else {
+            return Err(format!("Unknown LLM provider: {}", config.llm_provider));
+        };
+
+        if embeddings.len() != batch.len() {
+            return Err(format!(
+                "Embedding count mismatch: expected {}, got {}",
+                batch.len(),
+                embeddings.len()
+            ));
+        }
+        all.extend(embeddings);
+    }
+    Ok(all)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn lines(n: usize) -> Vec<String> {
+        (0..n).map(|i| format!("line {}", i)).collect()
+    }
+
+    #[test]
+    fn test_chunk_lines_overlap() {
+        let chunks = chunk_lines(&lines(10), 4, 1);
+        let starts: Vec<usize> = chunks.iter().map(|c| c.start_line).collect();
+        assert_eq!(starts, vec![0, 3, 6]);
+        assert!(chunks[2].text.ends_with("line 9"));
+    }
+
+    #[test]
+    fn test_chunk_lines_empty() {
+        assert!(chunk_lines(&[], CHUNK_LINES, CHUNK_OVERLAP).is_empty());
+    }
+
+    #[test]
+    fn test_cosine_similarity() {
+        assert!((cosine_similarity(&[1.0, 0.0], &[1.0, 0.0]) - 1.0).abs() < 1e-6);
+        assert!(cosine_similarity(&[1.0, 0.0], &[0.0, 1.0]).abs() < 1e-6);
+        assert_eq!(cosine_similarity(&[1.0], &[1.0, 2.0]), 0.0);
+    }
+
+    #[test]
+    fn test_top_chunks_keeps_log_order() {
+        let index = LogIndex {
+            model: "test".to_string(),
+            chunks: chunk_lines(&lines(3), 1, 0),
+            embeddings: vec![vec![0.9, 0.1], vec![0.0, 1.0], vec![1.0, 0.0]],
+            indexed_count: 3,
+        };
+        let top = index.top_chunks(&[1.0, 0.0], 2);
+        let starts: Vec<usize> = top.iter().map(|c| c.start_line).collect();
+        assert_eq!(starts, vec![0, 2]);
+    }
+}
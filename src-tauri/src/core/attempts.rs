@@ -0,0 +1,113 @@
+//! Guessing protection for short secrets (LAN pairing codes, the launcher
+//! PIN).
+//!
+//! An [`AttemptLimiter`] counts failed attempts per key (a peer address, or
+//! a single key for the local UI). After `max_attempts` failures the key is
+//! locked out, and every further failure doubles the lockout, up to an hour.
+//! A success clears the count.
+
+use std::collections::HashMap;
+use std::hash::Hash;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+const MAX_LOCKOUT: Duration = Duration::from_secs(60 * 60);
+
+#[derive(Debug, Default)]
+struct Attempts {
+    failures: u32,
+    locked_until: Option<Instant>,
+}
+
+pub struct AttemptLimiter<K> {
+    max_attempts: u32,
+    lockout: Duration,
+    entries: Mutex<HashMap<K, Attempts>>,
+}
+
+impl<K: Eq + Hash + Clone> AttemptLimiter<K> {
+    pub fn new(max_attempts: u32, lockout: Duration) -> Self {
+        Self {
+            max_attempts,
+            lockout,
+            entries: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// `Err` with the remaining lockout while `key` is locked out
+    pub fn check(&self, key: &K) -> Result<(), Duration> {
+        let entries = self.entries.lock().unwrap();
+        match entries.get(key).and_then(|a| a.locked_until) {
+            Some(until) if until > Instant::now() => Err(until - Instant::now()),
+            _ => Ok(()),
+        }
+    }
+
+    pub fn record_failure(&self, key: &K) {
+        let mut entries = self.entries.lock().unwrap();
+        let attempts = entries.entry(key.clone()).or_default();
+        attempts.failures += 1;
+        if attempts.failures >= self.max_attempts {
+            let doublings = (attempts.failures - self.max_attempts).min(16);
+            let lockout = self.lockout.saturating_mul(1 << doublings).min(MAX_LOCKOUT);
+            attempts.locked_until = Some(Instant::now() + lockout);
+        }
+    }
+
+    pub fn record_success(&self, key: &K) {
+        self.entries.lock().unwrap().remove(key);
+    }
+}
+
+/// Error message for a locked-out key
+pub fn lockout_message(remaining: Duration) -> String {
+    format!(
+        "Too many wrong attempts, try again in {} s",
+        remaining.as_secs().max(1)
+    )
+}
+
+/// Compare secrets without leaking the position of the first difference
+pub fn constant_time_eq(a: &str, b: &str) -> bool {
+    a.len() == b.len()
+        && a.bytes()
+            .zip(b.bytes())
+            .fold(0u8, |acc, (x, y)| acc | (x ^ y))
+            == 0
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_lockout_after_max_attempts() {
+        let limiter = AttemptLimiter::new(3, Duration::from_secs(60));
+        for _ in 0..2 {
+            limiter.record_failure(&"peer");
+            assert!(limiter.check(&"peer").is_ok());
+        }
+        limiter.record_failure(&"peer");
+        assert!(limiter.check(&"peer").is_err());
+        assert!(limiter.check(&"other").is_ok());
+        limiter.record_success(&"peer");
+        assert!(limiter.check(&"peer").is_ok());
+    }
+
+    #[test]
+    fn test_lockout_doubles() {
+        let limiter = AttemptLimiter::new(1, Duration::from_secs(60));
+        limiter.record_failure(&1);
+        let first = limiter.check(&1).unwrap_err();
+        limiter.record_failure(&1);
+        let second = limiter.check(&1).unwrap_err();
+        assert!(second > first + Duration::from_secs(50));
+    }
+
+    #[test]
+    fn test_constant_time_eq() {
+        assert!(constant_time_eq("123456", "123456"));
+        assert!(!constant_time_eq("123456", "123457"));
+        assert!(!constant_time_eq("123456", "12345"));
+    }
+}
@@ -5,6 +5,8 @@ use std::sync::Mutex;
 use tauri::{AppHandle, Manager};
 use ts_rs::TS;
 
+pub use dropout_core::config::FeatureFlags;
+
 #[derive(Debug, Clone, Serialize, Deserialize, TS)]
 #[serde(rename_all = "camelCase")]
 #[ts(export, export_to = "config.ts")]
@@ -22,6 +24,12 @@ pub struct AssistantConfig {
     // Common settings
     pub system_prompt: String,
     pub response_language: String,
+    pub context_window: u32, // model context size in tokens (0 = detect from model name, at most 8192 for Ollama)
+    // Log search (embedding-based retrieval over game logs)
+    pub log_search_enabled: bool,
+    pub ollama_embedding_model: String,
+    pub openai_embedding_model: String,
+    pub log_search_top_k: u32, // number of log chunks sent to the model
     // TTS settings
     pub tts_enabled: bool,
     pub tts_provider: String, // "disabled", "piper", "edge"
@@ -39,38 +47,61 @@ impl Default for AssistantConfig {
             openai_model: "gpt-3.5-turbo".to_string(),
             system_prompt: "You are a helpful Minecraft expert assistant. You help players with game issues, mod installation, performance optimization, and gameplay tips. Analyze any game logs provided and give concise, actionable advice.".to_string(),
             response_language: "auto".to_string(),
+            context_window: 0,
+            log_search_enabled: false,
+            ollama_embedding_model: "nomic-embed-text".to_string(),
+            openai_embedding_model: "text-embedding-3-small".to_string(),
+            log_search_top_k: 4,
             tts_enabled: false,
             tts_provider: "disabled".to_string(),
         }
     }
 }
 
-/// Feature-gated arguments configuration
+/// Cloud backup of instance folders (see `core::sync`)
 #[derive(Debug, Clone, Serialize, Deserialize, TS)]
 #[serde(rename_all = "camelCase")]
 #[ts(export, export_to = "config.ts")]
 #[serde(default)]
-pub struct FeatureFlags {
-    /// Demo user: enables demo-related arguments when rules require it
-    pub demo_user: bool,
-    /// Quick Play: enable quick play arguments
-    pub quick_play_enabled: bool,
-    /// Quick Play singleplayer world path (if provided)
-    pub quick_play_path: Option<String>,
-    /// Quick Play singleplayer flag
-    pub quick_play_singleplayer: bool,
-    /// Quick Play multiplayer server address (optional)
-    pub quick_play_multiplayer_server: Option<String>,
+pub struct CloudSyncConfig {
+    pub provider: String, // "none", "webdav" or "s3"
+    // WebDAV settings
+    pub webdav_url: String,
+    pub webdav_username: Option<String>,
+    pub webdav_password: Option<String>,
+    // S3-compatible settings
+    pub s3_endpoint: String,
+    pub s3_region: String,
+    pub s3_bucket: String,
+    pub s3_access_key: Option<String>,
+    pub s3_secret_key: Option<String>,
+    pub s3_path_style: bool, // bucket in the path instead of the host name (MinIO etc.)
+    // Common settings
+    pub remote_prefix: String,      // folder/key prefix for all backups
+    pub passphrase: Option<String>, // backups are encrypted with this passphrase
+    pub folders: Vec<String>,       // instance folders/files included by default
 }
 
-impl Default for FeatureFlags {
+impl Default for CloudSyncConfig {
     fn default() -> Self {
         Self {
-            demo_user: false,
-            quick_play_enabled: false,
-            quick_play_path: None,
-            quick_play_singleplayer: true,
-            quick_play_multiplayer_server: None,
+            provider: "none".to_string(),
+            webdav_url: String::new(),
+            webdav_username: None,
+            webdav_password: None,
+            s3_endpoint: "https://s3.amazonaws.com".to_string(),
+            s3_region: "us-east-1".to_string(),
+            s3_bucket: String::new(),
+            s3_access_key: None,
+            s3_secret_key: None,
+            s3_path_style: false,
+            remote_prefix: "dropout".to_string(),
+            passphrase: None,
+            folders: vec![
+                "saves".to_string(),
+                "config".to_string(),
+                "options.txt".to_string(),
+            ],
         }
     }
 }
@@ -85,7 +116,8 @@ pub struct LauncherConfig {
     pub java_path: String,
     pub width: u32,
     pub height: u32,
-    pub download_threads: u32, // concurrent download threads (1-128)
+    pub download_threads: u32,       // concurrent download threads (1-128)
+    pub auto_download_threads: bool, // adapt concurrency to the network instead of download_threads
     pub custom_background_path: Option<String>,
     pub enable_gpu_acceleration: bool,
     pub enable_visual_effects: bool,
@@ -99,6 +131,76 @@ pub struct LauncherConfig {
     pub keep_legacy_per_instance_storage: bool, // Keep old per-instance caches (no migration)
     // Feature-gated argument flags
     pub feature_flags: FeatureFlags,
+    // GitHub release checks
+    pub github_token: Option<String>, // Optional personal access token to raise API rate limits
+    pub release_check_interval_hours: u32, // Minimum time between release checks (0 = always refresh)
+    // LAN instance sync
+    pub lan_sync_enabled: bool, // Announce on the LAN and accept instances from peers on startup
+    // Cloud backup
+    pub cloud_sync: CloudSyncConfig,
+    // Offline accounts
+    pub offline_uuid_namespace: String, // "dropout", "vanilla" (OfflinePlayer:<name>) or a custom namespace UUID
+    // Locale
+    pub utf8_jvm_args: String, // "auto" (CJK Windows only), "always" or "never"
+    pub auto_game_language: bool, // Set the game language from the OS locale on an instance's first launch
+    // Natives
+    pub native_classifier_override: Option<String>, // Force a classifier such as "natives-windows" (tried first)
+    // Diagnostics
+    pub usage_stats_enabled: bool, // Record launch/download statistics locally (never uploaded)
+    // Local API
+    pub local_api_enabled: bool, // Serve the token-protected localhost API on startup (see core::local_api)
+    pub local_api_port: u16,
+    pub local_api_token: Option<String>, // Generated when the API is first started
+    // Scheduler
+    pub schedules: Vec<super::scheduler::Schedule>, // Timed server launches/instance backups (see core::scheduler)
+    // Assets
+    pub asset_virtualization: String, // "auto" (as the asset index asks), "always" (for mods reading real files) or "off"
+    // Mod loaders
+    pub predownload_loader_libraries: bool, // Download loader libraries when installing the loader instead of on first launch
+    // Downloads
+    pub download_mirror: dropout_core::downloader::DownloadMirror, // Mirror for Mojang/loader files, falling back to the official URLs
+    pub download_retries: u32, // Extra attempts for a file that failed with a network error, with exponential backoff
+    pub download_buffer_kb: u32, // Write buffer per download; larger means fewer, bigger disk writes
+    pub download_fsync: bool,    // Sync each downloaded file to disk before moving it into place
+    pub max_download_speed: u32, // KB/s shared by all downloads (0 = unlimited)
+    pub low_priority_while_playing: bool, // Cap downloads started while a game is running
+    pub low_priority_download_threads: u32, // Parallel downloads of such a low-priority session
+    pub low_priority_download_speed: u32, // KB/s of such a session (0 = only the global cap)
+    // Java downloads
+    pub java_download_mirror: super::java::JavaDownloadMirror, // Mirror for Java archives (Adoptium API metadata and checksums are unchanged)
+    pub java_archive_cache_mb: u32, // Size cap of the cache of installed Java archives (0 disables it)
+    // Parental mode
+    pub parental: super::parental::ParentalConfig, // Launcher PIN (see core::parental); only changed by set_launcher_pin
+}
+
+impl LauncherConfig {
+    /// Hand the download settings to the downloader, which keeps them globally
+    pub fn apply_download_settings(&self) {
+        dropout_core::downloader::set_mirror(self.download_mirror);
+        dropout_core::downloader::set_max_download_speed(self.max_download_speed as u64 * 1024);
+    }
+
+    /// Drop every stored API key, token, password and passphrase. Returns the
+    /// keys of the settings that had one.
+    pub fn clear_secrets(&mut self) -> Vec<String> {
+        let secrets = [
+            ("assistant.openaiApiKey", &mut self.assistant.openai_api_key),
+            ("pastebinApiKey", &mut self.pastebin_api_key),
+            ("githubToken", &mut self.github_token),
+            (
+                "cloudSync.webdavPassword",
+                &mut self.cloud_sync.webdav_password,
+            ),
+            ("cloudSync.s3AccessKey", &mut self.cloud_sync.s3_access_key),
+            ("cloudSync.s3SecretKey", &mut self.cloud_sync.s3_secret_key),
+            ("cloudSync.passphrase", &mut self.cloud_sync.passphrase),
+            ("localApiToken", &mut self.local_api_token),
+        ];
+        secrets
+            .into_iter()
+            .filter_map(|(key, secret)| secret.take().map(|_| key.to_string()))
+            .collect()
+    }
 }
 
 impl Default for LauncherConfig {
@@ -110,6 +212,7 @@ impl Default for LauncherConfig {
             width: 854,
             height: 480,
             download_threads: 32,
+            auto_download_threads: false,
             custom_background_path: None,
             enable_gpu_acceleration: false,
             enable_visual_effects: true,
@@ -121,10 +224,99 @@ impl Default for LauncherConfig {
             use_shared_caches: false,
             keep_legacy_per_instance_storage: true,
             feature_flags: FeatureFlags::default(),
+            github_token: None,
+            release_check_interval_hours: 6,
+            lan_sync_enabled: false,
+            cloud_sync: CloudSyncConfig::default(),
+            offline_uuid_namespace: "dropout".to_string(),
+            utf8_jvm_args: "auto".to_string(),
+            auto_game_language: true,
+            native_classifier_override: None,
+            usage_stats_enabled: false,
+            local_api_enabled: false,
+            local_api_port: 25590,
+            local_api_token: None,
+            schedules: Vec::new(),
+            asset_virtualization: "auto".to_string(),
+            predownload_loader_libraries: true,
+            download_mirror: Default::default(),
+            java_download_mirror: Default::default(),
+            download_retries: 3,
+            download_buffer_kb: 256,
+            download_fsync: false,
+            max_download_speed: 0,
+            low_priority_while_playing: true,
+            low_priority_download_threads: 4,
+            low_priority_download_speed: 2048,
+            java_archive_cache_mb: 1024,
+            parental: Default::default(),
         }
     }
 }
 
+/// A setting changed by `save_raw_config`
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export, export_to = "config.ts")]
+pub struct ConfigChange {
+    pub key: String,               // dotted path, e.g. "assistant.ollamaModel"
+    pub old_value: Option<String>, // as JSON; None when the key is new
+    pub new_value: Option<String>, // as JSON; None when the key was removed
+}
+
+/// Keys whose values never show up in diffs or logs
+const SECRET_KEY_PARTS: &[&str] = &["key", "token", "password", "passphrase", "secret", "pin"];
+
+fn is_secret(key: &str) -> bool {
+    let name = key.rsplit('.').next().unwrap_or(key).to_lowercase();
+    SECRET_KEY_PARTS.iter().any(|part| name.contains(part))
+}
+
+fn display_value(key: &str, value: &serde_json::Value) -> String {
+    if is_secret(key) && !value.is_null() {
+        "\"********\"".to_string()
+    } else {
+        value.to_string()
+    }
+}
+
+fn diff_values(
+    prefix: &str,
+    old: Option<&serde_json::Value>,
+    new: Option<&serde_json::Value>,
+    out: &mut Vec<ConfigChange>,
+) {
+    if let (Some(serde_json::Value::Object(old)), Some(serde_json::Value::Object(new))) = (old, new)
+    {
+        let mut keys: Vec<&String> = old.keys().chain(new.keys()).collect();
+        keys.sort();
+        keys.dedup();
+        for key in keys {
+            let path = if prefix.is_empty() {
+                key.clone()
+            } else {
+                format!("{}.{}", prefix, key)
+            };
+            diff_values(&path, old.get(key), new.get(key), out);
+        }
+    } else if old != new {
+        out.push(ConfigChange {
+            key: prefix.to_string(),
+            old_value: old.map(|v| display_value(prefix, v)),
+            new_value: new.map(|v| display_value(prefix, v)),
+        });
+    }
+}
+
+/// Settings that differ between two configs, with secrets masked
+pub fn diff_configs(old: &LauncherConfig, new: &LauncherConfig) -> Vec<ConfigChange> {
+    let old = serde_json::to_value(old).unwrap_or_default();
+    let new = serde_json::to_value(new).unwrap_or_default();
+    let mut changes = Vec::new();
+    diff_values("", Some(&old), Some(&new), &mut changes);
+    changes
+}
+
 pub struct ConfigState {
     pub config: Mutex<LauncherConfig>,
     pub file_path: PathBuf,
@@ -141,6 +333,7 @@ impl ConfigState {
         } else {
             LauncherConfig::default()
         };
+        config.apply_download_settings();
 
         Self {
             config: Mutex::new(config),
@@ -150,9 +343,61 @@ impl ConfigState {
 
     pub fn save(&self) -> Result<(), String> {
         let config = self.config.lock().unwrap();
+        config.apply_download_settings();
         let content = serde_json::to_string_pretty(&*config).map_err(|e| e.to_string())?;
         fs::create_dir_all(self.file_path.parent().unwrap()).map_err(|e| e.to_string())?;
         fs::write(&self.file_path, content).map_err(|e| e.to_string())?;
         Ok(())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_diff_configs_masks_secrets() {
+        let old = LauncherConfig::default();
+        let mut new = old.clone();
+        new.max_memory = 4096;
+        new.assistant.ollama_model = "llama3.1".to_string();
+        new.assistant.openai_api_key = Some("sk-secret".to_string());
+
+        let changes = diff_configs(&old, &new);
+        let keys: Vec<&str> = changes.iter().map(|c| c.key.as_str()).collect();
+        assert_eq!(
+            keys,
+            vec![
+                "assistant.ollamaModel",
+                "assistant.openaiApiKey",
+                "maxMemory"
+            ]
+        );
+        let memory = changes.iter().find(|c| c.key == "maxMemory").unwrap();
+        assert_eq!(memory.old_value.as_deref(), Some("2048"));
+        assert_eq!(memory.new_value.as_deref(), Some("4096"));
+        let api_key = changes
+            .iter()
+            .find(|c| c.key == "assistant.openaiApiKey")
+            .unwrap();
+        assert_eq!(api_key.old_value.as_deref(), Some("null"));
+        assert_eq!(api_key.new_value.as_deref(), Some("\"********\""));
+        assert!(diff_configs(&old, &old).is_empty());
+    }
+
+    #[test]
+    fn test_clear_secrets() {
+        let mut config = LauncherConfig {
+            github_token: Some("ghp".to_string()),
+            local_api_token: Some("abc".to_string()),
+            ..Default::default()
+        };
+        config.cloud_sync.passphrase = Some("hunter2".to_string());
+        assert_eq!(
+            config.clear_secrets(),
+            vec!["githubToken", "cloudSync.passphrase", "localApiToken"]
+        );
+        assert!(config.cloud_sync.passphrase.is_none());
+        assert!(config.clear_secrets().is_empty());
+    }
+}
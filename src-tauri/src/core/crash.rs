@@ -0,0 +1,260 @@
+//! Crash artifacts of abnormal game exits.
+//!
+//! When the JVM itself dies (driver bugs, native mods) it leaves an
+//! `hs_err_pid<pid>.log` — and for JIT crashes a `replay_pid<pid>.log` — in
+//! its working directory, i.e. the game directory. Those created during a
+//! launch are reported with the `crash-detected` event and added, with a
+//! short summary, to the diagnostics bundle.
+
+use serde::{Deserialize, Serialize};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::time::SystemTime;
+use ts_rs::TS;
+
+/// Largest file copied into a diagnostics bundle
+const MAX_BUNDLE_FILE: u64 = 16 * 1024 * 1024;
+
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export, export_to = "crash.ts")]
+pub struct HsErrSummary {
+    pub error: Option<String>, // e.g. "EXCEPTION_ACCESS_VIOLATION (0xc0000005)"
+    pub problematic_frame: Option<String>,
+    pub native_library: Option<String>, // library of a native (C) frame
+    pub java_version: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export, export_to = "crash.ts")]
+pub struct CrashDump {
+    pub path: String,
+    pub kind: String, // "hsErr" | "replay"
+    pub summary: Option<HsErrSummary>,
+}
+
+/// Payload of the `crash-detected` event
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export, export_to = "crash.ts")]
+pub struct CrashDetected {
+    pub instance_id: String,
+    pub exit_code: Option<i32>,
+    pub dumps: Vec<CrashDump>,
+}
+
+fn dump_kind(file_name: &str) -> Option<&'static str> {
+    if !file_name.ends_with(".log") {
+        return None;
+    }
+    if file_name.starts_with("hs_err_pid") {
+        Some("hsErr")
+    } else if file_name.starts_with("replay_pid") {
+        Some("replay")
+    } else {
+        None
+    }
+}
+
+/// Summary of the header of an `hs_err_pid*.log`
+pub fn parse_hs_err(content: &str) -> HsErrSummary {
+    let mut summary = HsErrSummary::default();
+    let mut lines = content.lines().map(|l| l.trim_start_matches('#').trim());
+    while let Some(line) = lines.next() {
+        if line.starts_with("---------------") {
+            break; // end of the header
+        }
+        if summary.error.is_none() && line.contains(" at pc=") {
+            summary.error = line.split(" at pc=").next().map(|e| e.trim().to_string());
+        } else if let Some(version) = line.strip_prefix("JRE version:") {
+            summary.java_version = Some(version.trim().to_string());
+        } else if line == "Problematic frame:" {
+            // "C  [atio6axx.dll+0x1a2b3c]" or "J 1234 c2 net.minecraft..."
+            let Some(frame) = lines.next().filter(|f| !f.is_empty()) else {
+                continue;
+            };
+            summary.problematic_frame = Some(frame.to_string());
+            if let Some(rest) = frame.strip_prefix('C')
+                && let Some(start) = rest.find('[')
+            {
+                let library = rest[start + 1..].split(['+', ']']).next().unwrap_or("");
+                if !library.is_empty() {
+                    summary.native_library = Some(library.to_string());
+                }
+            }
+        }
+    }
+    summary
+}
+
+/// Crash dumps in `game_dir` modified at or after `since`
+pub fn find_new_dumps(game_dir: &Path, since: SystemTime) -> Vec<CrashDump> {
+    let Ok(entries) = std::fs::read_dir(game_dir) else {
+        return Vec::new();
+    };
+    let mut dumps: Vec<CrashDump> = entries
+        .filter_map(|e| e.ok())
+        .filter_map(|e| {
+            let name = e.file_name().to_string_lossy().to_string();
+            let kind = dump_kind(&name)?;
+            let modified = e.metadata().and_then(|m| m.modified()).ok()?;
+            if modified < since {
+                return None;
+            }
+            let summary = (kind == "hsErr")
+                .then(|| std::fs::read_to_string(e.path()).ok())
+                .flatten()
+                .map(|content| parse_hs_err(&content));
+            Some(CrashDump {
+                path: e.path().to_string_lossy().to_string(),
+                kind: kind.to_string(),
+                summary,
+            })
+        })
+        .collect();
+    dumps.sort_by(|a, b| a.path.cmp(&b.path));
+    dumps
+}
+
+/// Every crash dump in `game_dir`, newest first
+pub fn all_dumps(game_dir: &Path) -> Vec<CrashDump> {
+    let mut dumps = find_new_dumps(game_dir, SystemTime::UNIX_EPOCH);
+    let modified = |d: &CrashDump| {
+        std::fs::metadata(&d.path)
+            .and_then(|m| m.modified())
+            .unwrap_or(SystemTime::UNIX_EPOCH)
+    };
+    dumps.sort_by_key(|d| std::cmp::Reverse(modified(d)));
+    dumps
+}
+
+/// Plain-text summary placed at the top of a diagnostics bundle
+pub fn render_summary(instance_name: &str, dumps: &[CrashDump]) -> String {
+    let mut out = format!("Instance: {}\n", instance_name);
+    if dumps.is_empty() {
+        out.push_str("No JVM crash dumps found\n");
+    }
+    for dump in dumps {
+        let name = Path::new(&dump.path)
+            .file_name()
+            .map(|n| n.to_string_lossy().to_string())
+            .unwrap_or_default();
+        out.push_str(&format!("\n{} ({})\n", name, dump.kind));
+        if let Some(summary) = &dump.summary {
+            let fields = [
+                ("Error", &summary.error),
+                ("Problematic frame", &summary.problematic_frame),
+                ("Native library", &summary.native_library),
+                ("Java", &summary.java_version),
+            ];
+            for (label, value) in fields {
+                if let Some(value) = value {
+                    out.push_str(&format!("  {}: {}\n", label, value));
+                }
+            }
+        }
+    }
+    out
+}
+
+fn newest_file(dir: &Path, extension: &str) -> Option<PathBuf> {
+    std::fs::read_dir(dir)
+        .ok()?
+        .filter_map(|e| e.ok())
+        .filter(|e| e.path().extension().is_some_and(|ext| ext == extension))
+        .max_by_key(|e| {
+            e.metadata()
+                .and_then(|m| m.modified())
+                .unwrap_or(SystemTime::UNIX_EPOCH)
+        })
+        .map(|e| e.path())
+}
+
+/// Zip the latest log, the newest crash report and the crash dumps of the
+/// instance in `game_dir`, with a summary, into `dest`
+pub fn write_bundle(instance_name: &str, game_dir: &Path, dest: &Path) -> Result<(), String> {
+    let dumps = all_dumps(game_dir);
+    let mut files: Vec<(String, PathBuf)> = Vec::new();
+    let latest_log = game_dir.join("logs").join("latest.log");
+    if latest_log.is_file() {
+        files.push(("logs/latest.log".to_string(), latest_log));
+    }
+    if let Some(report) = newest_file(&game_dir.join("crash-reports"), "txt") {
+        let name = report.file_name().unwrap_or_default().to_string_lossy();
+        files.push((format!("crash-reports/{}", name), report.clone()));
+    }
+    for dump in &dumps {
+        let path = PathBuf::from(&dump.path);
+        let name = path.file_name().unwrap_or_default().to_string_lossy();
+        files.push((format!("dumps/{}", name), path.clone()));
+    }
+
+    if let Some(parent) = dest.parent() {
+        std::fs::create_dir_all(parent).map_err(|e| e.to_string())?;
+    }
+    let file = std::fs::File::create(dest).map_err(|e| e.to_string())?;
+    let mut zip = zip::ZipWriter::new(file);
+    let options = zip::write::SimpleFileOptions::default();
+    zip.start_file("summary.txt", options)
+        .map_err(|e| e.to_string())?;
+    zip.write_all(render_summary(instance_name, &dumps).as_bytes())
+        .map_err(|e| e.to_string())?;
+    for (name, path) in files {
+        if std::fs::metadata(&path).is_ok_and(|m| m.len() > MAX_BUNDLE_FILE) {
+            continue;
+        }
+        let Ok(content) = std::fs::read(&path) else {
+            continue;
+        };
+        zip.start_file(name, options).map_err(|e| e.to_string())?;
+        zip.write_all(&content).map_err(|e| e.to_string())?;
+    }
+    zip.finish().map_err(|e| e.to_string())?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const HS_ERR: &str = "#
+# A fatal error has been detected by the Java Runtime Environment:
+#
+#  EXCEPTION_ACCESS_VIOLATION (0xc0000005) at pc=0x00007ffb1a2b3c4d, pid=1234, tid=5678
+#
+# JRE version: OpenJDK Runtime Environment Temurin-17.0.8+7 (17.0.8+7) (build 17.0.8+7)
+# Java VM: OpenJDK 64-Bit Server VM Temurin-17.0.8+7 (17.0.8+7, mixed mode, tiered, compressed oops, compressed class ptrs, g1 gc, windows-amd64)
+# Problematic frame:
+# C  [atio6axx.dll+0x1a2b3c]
+#
+---------------  S U M M A R Y ------------
+";
+
+    #[test]
+    fn test_parse_hs_err() {
+        let summary = parse_hs_err(HS_ERR);
+        assert_eq!(
+            summary.error.as_deref(),
+            Some("EXCEPTION_ACCESS_VIOLATION (0xc0000005)")
+        );
+        assert_eq!(
+            summary.problematic_frame.as_deref(),
+            Some("C  [atio6axx.dll+0x1a2b3c]")
+        );
+        assert_eq!(summary.native_library.as_deref(), Some("atio6axx.dll"));
+        assert!(summary.java_version.unwrap().starts_with("OpenJDK"));
+
+        let jit = parse_hs_err("# Problematic frame:\n# J 1234 c2 net.minecraft.Foo.bar()V\n");
+        assert!(jit.problematic_frame.is_some());
+        assert_eq!(jit.native_library, None);
+    }
+
+    #[test]
+    fn test_dump_kind() {
+        assert_eq!(dump_kind("hs_err_pid1234.log"), Some("hsErr"));
+        assert_eq!(dump_kind("replay_pid1234.log"), Some("replay"));
+        assert_eq!(dump_kind("latest.log"), None);
+        assert_eq!(dump_kind("hs_err_pid1234.mdmp"), None);
+    }
+}
@@ -0,0 +1,447 @@
+//! `dropout://` links, e.g. `dropout://install-modpack?source=modrinth&id=AANobbMI`
+//! or `dropout://join?server=play.example.com:25565`.
+//!
+//! Links are parsed and validated here but never acted on directly: anything
+//! that isn't a known action with well-formed parameters is rejected, and a
+//! valid link is shown in a native dialog ([`handle_url`]). Only when the
+//! user confirms is it run: a modpack link is handed to the app's
+//! [`LinkRunner`], which downloads the pack into a new instance, and a join
+//! link goes to the UI as a `deep-link` event to pick the instance.
+//!
+//! The OS starts a new launcher process with the link as an argument (macOS
+//! delivers it to the running app instead). A second process forwards the
+//! link to the running launcher over a localhost socket whose port is kept in
+//! `<app_data>/deeplink.port`, then exits. Every forwarded link must start
+//! with the secret from `<app_data>/deeplink.token`, so other programs (and
+//! web pages) that can reach localhost can't inject links.
+
+use futures::future::BoxFuture;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+use tauri::{AppHandle, Emitter, Manager};
+use tauri_plugin_dialog::{DialogExt, MessageDialogButtons};
+use tokio::io::{AsyncBufReadExt, AsyncReadExt, BufReader};
+use tokio::net::TcpListener;
+use ts_rs::TS;
+
+use super::attempts::constant_time_eq;
+
+pub const SCHEME: &str = "dropout";
+
+const PORT_FILE: &str = "deeplink.port";
+const TOKEN_FILE: &str = "deeplink.token";
+const MAX_URL_LEN: usize = 2048;
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, TS)]
+#[serde(tag = "action", rename_all = "camelCase")]
+#[ts(export, export_to = "deep_link.ts")]
+pub enum DeepLink {
+    #[serde(rename_all = "camelCase")]
+    InstallModpack {
+        source: String, // "modrinth" | "curseforge"
+        id: String,
+        version: Option<String>,
+    },
+    #[serde(rename_all = "camelCase")]
+    Join {
+        server: String, // host or host:port
+        instance_id: Option<String>,
+    },
+}
+
+/// Runs a confirmed install link; provided by the app, which owns the
+/// install commands
+pub type LinkRunner =
+    Arc<dyn Fn(AppHandle, DeepLink) -> BoxFuture<'static, Result<String, String>> + Send + Sync>;
+
+pub struct DeepLinkState {
+    /// Confirmed join links the UI hasn't picked up yet
+    pub pending: Mutex<Vec<DeepLink>>,
+    runner: LinkRunner,
+}
+
+impl DeepLinkState {
+    pub fn new(runner: LinkRunner) -> Self {
+        Self {
+            pending: Mutex::new(Vec::new()),
+            runner,
+        }
+    }
+}
+
+/// Question shown before running `link`, and its confirm button
+fn confirmation(link: &DeepLink) -> (String, &'static str) {
+    match link {
+        DeepLink::InstallModpack {
+            source,
+            id,
+            version,
+        } => {
+            let platform = if source == "curseforge" {
+                "CurseForge"
+            } else {
+                "Modrinth"
+            };
+            let version = version
+                .as_ref()
+                .map(|v| format!(" (version {})", v))
+                .unwrap_or_default();
+            (
+                format!(
+                    "A link asks to install the modpack \"{}\"{} from {} as a new instance.",
+                    id, version, platform
+                ),
+                "Install",
+            )
+        }
+        DeepLink::Join { server, .. } => (
+            format!("A link asks to join the server {}.", server),
+            "Join",
+        ),
+    }
+}
+
+fn is_identifier(value: &str) -> bool {
+    !value.is_empty()
+        && value.len() <= 64
+        && value
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || matches!(c, '-' | '_' | '.'))
+}
+
+fn is_server_address(value: &str) -> bool {
+    let (host, port) = match value.rsplit_once(':') {
+        Some((host, port)) => (host, Some(port)),
+        None => (value, None),
+    };
+    !host.is_empty()
+        && host.len() <= 253
+        && host
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || matches!(c, '-' | '.'))
+        && port.is_none_or(|p| p.parse::<u16>().is_ok_and(|p| p > 0))
+}
+
+/// Parse and validate a `dropout://` URL
+pub fn parse(url: &str) -> Result<DeepLink, String> {
+    if url.len() > MAX_URL_LEN {
+        return Err("Link is too long".to_string());
+    }
+    let rest = url
+        .strip_prefix(&format!("{}://", SCHEME))
+        .ok_or_else(|| format!("Not a {}:// link", SCHEME))?;
+    let (action, query) = rest.split_once('?').unwrap_or((rest, ""));
+    let params: HashMap<String, String> =
+        serde_urlencoded::from_str(query).map_err(|e| format!("Malformed link: {}", e))?;
+    let param = |name: &str| params.get(name).map(|v| v.trim().to_string());
+
+    match action.trim_end_matches('/') {
+        "install-modpack" => {
+            let source = param("source").unwrap_or_default();
+            if !matches!(source.as_str(), "modrinth" | "curseforge") {
+                return Err(format!("Unsupported modpack source '{}'", source));
+            }
+            let id = param("id").filter(|id| is_identifier(id));
+            let version = param("version");
+            if version.as_deref().is_some_and(|v| !is_identifier(v)) {
+                return Err("Invalid modpack version".to_string());
+            }
+            Ok(DeepLink::InstallModpack {
+                source,
+                id: id.ok_or("Missing or invalid modpack id")?,
+                version,
+            })
+        }
+        "join" => {
+            let server = param("server")
+                .filter(|s| is_server_address(s))
+                .ok_or("Missing or invalid server address")?;
+            let instance_id = param("instance");
+            if instance_id.as_deref().is_some_and(|id| !is_identifier(id)) {
+                return Err("Invalid instance id".to_string());
+            }
+            Ok(DeepLink::Join {
+                server,
+                instance_id,
+            })
+        }
+        other => Err(format!("Unknown link action '{}'", other)),
+    }
+}
+
+/// The `dropout://` URL among command line arguments, if any
+pub fn url_from_args(args: impl IntoIterator<Item = String>) -> Option<String> {
+    let prefix = format!("{}://", SCHEME);
+    args.into_iter().find(|arg| arg.starts_with(&prefix))
+}
+
+/// Hand `url` to an already running launcher; `false` if none is listening
+pub fn forward_to_running(app_data_dir: &Path, url: &str) -> bool {
+    let Some(port) = std::fs::read_to_string(app_data_dir.join(PORT_FILE))
+        .ok()
+        .and_then(|p| p.trim().parse::<u16>().ok())
+    else {
+        return false;
+    };
+    let Ok(token) = std::fs::read_to_string(app_data_dir.join(TOKEN_FILE)) else {
+        return false;
+    };
+    let timeout = std::time::Duration::from_secs(2);
+    let addr = std::net::SocketAddr::from(([127, 0, 0, 1], port));
+    std::net::TcpStream::connect_timeout(&addr, timeout)
+        .and_then(|mut stream| writeln!(stream, "{} {}", token.trim(), url))
+        .is_ok()
+}
+
+/// The link of a forwarded `<token> <url>` line if the token is right
+fn authenticate<'a>(line: &'a str, token: &str) -> Option<&'a str> {
+    let (given, url) = line.trim().split_once(' ')?;
+    constant_time_eq(given, token).then_some(url)
+}
+
+/// Write the forwarding secret, readable only by the current user
+fn write_token(path: &Path, token: &str) -> std::io::Result<()> {
+    let mut options = std::fs::OpenOptions::new();
+    options.write(true).create(true).truncate(true);
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::OpenOptionsExt;
+        options.mode(0o600);
+    }
+    options.open(path)?.write_all(token.as_bytes())
+}
+
+/// Validate a link and ask the user before running it
+pub fn handle_url(app_handle: &AppHandle, url: &str) {
+    let link = match parse(url) {
+        Ok(link) => link,
+        Err(e) => {
+            eprintln!("[DeepLink] Rejected {}: {}", url, e);
+            let _ = app_handle.emit("deep-link-rejected", e);
+            return;
+        }
+    };
+    let main_window = app_handle.get_webview_window("main");
+    if let Some(window) = &main_window {
+        let _ = window.unminimize();
+        let _ = window.set_focus();
+    }
+
+    let (message, confirm) = confirmation(&link);
+    let mut dialog = app_handle
+        .dialog()
+        .message(message)
+        .title("Open DropOut link?")
+        .buttons(MessageDialogButtons::OkCancelCustom(
+            confirm.to_string(),
+            "Cancel".to_string(),
+        ));
+    if let Some(window) = &main_window {
+        dialog = dialog.parent(window);
+    }
+    let handle = app_handle.clone();
+    dialog.show(move |confirmed| {
+        if confirmed {
+            run(&handle, link);
+        }
+    });
+}
+
+/// Run a link the user confirmed
+fn run(app_handle: &AppHandle, link: DeepLink) {
+    let Some(state) = app_handle.try_state::<DeepLinkState>() else {
+        return;
+    };
+    match link {
+        DeepLink::Join { .. } => {
+            state.pending.lock().unwrap().push(link.clone());
+            let _ = app_handle.emit("deep-link", &link);
+        }
+        DeepLink::InstallModpack { .. } => {
+            let future = (state.runner)(app_handle.clone(), link.clone());
+            let handle = app_handle.clone();
+            tauri::async_runtime::spawn(async move {
+                let result = future.await;
+                if let Err(e) = &result {
+                    eprintln!("[DeepLink] Install failed: {}", e);
+                }
+                let _ = handle.emit(
+                    "deep-link-result",
+                    serde_json::json!({ "link": link, "result": result }),
+                );
+            });
+        }
+    }
+}
+
+/// Accept links forwarded by later launcher processes
+pub async fn listen(app_handle: AppHandle) -> Result<(), String> {
+    let listener = TcpListener::bind("127.0.0.1:0")
+        .await
+        .map_err(|e| e.to_string())?;
+    let port = listener.local_addr().map_err(|e| e.to_string())?.port();
+    let app_data_dir = app_handle
+        .path()
+        .app_data_dir()
+        .map_err(|e| e.to_string())?;
+    std::fs::create_dir_all(&app_data_dir).map_err(|e| e.to_string())?;
+    let token = uuid::Uuid::new_v4().simple().to_string();
+    write_token(&app_data_dir.join(TOKEN_FILE), &token).map_err(|e| e.to_string())?;
+    std::fs::write(app_data_dir.join(PORT_FILE), port.to_string()).map_err(|e| e.to_string())?;
+
+    while let Ok((stream, _)) = listener.accept().await {
+        let mut line = String::new();
+        let limit = (token.len() + 1 + MAX_URL_LEN) as u64 + 1;
+        let mut reader = BufReader::new(stream).take(limit);
+        let read = tokio::time::timeout(
+            std::time::Duration::from_secs(2),
+            reader.read_line(&mut line),
+        );
+        if !matches!(read.await, Ok(Ok(_))) {
+            continue;
+        }
+        match authenticate(&line, &token) {
+            Some(url) => handle_url(&app_handle, url),
+            None => eprintln!("[DeepLink] Ignored a forwarded link without the right token"),
+        }
+    }
+    Ok(())
+}
+
+/// Register the launcher as the handler of `dropout://` links for the
+/// current user. On macOS the scheme comes from the bundle's `Info.plist`.
+pub fn register_scheme(exe: &Path) -> Result<(), String> {
+    #[cfg(target_os = "windows")]
+    {
+        let key = format!(r"HKCU\Software\Classes\{}", SCHEME);
+        let command = format!("\"{}\" \"%1\"", exe.display());
+        let entries: [(String, &str, &str); 3] = [
+            (key.clone(), "", "URL:DropOut"),
+            (key.clone(), "URL Protocol", ""),
+            (format!(r"{}\shell\open\command", key), "", command.as_str()),
+        ];
+        for (path, name, value) in entries {
+            let mut reg = std::process::Command::new("reg");
+            reg.args(["add", &path]);
+            if name.is_empty() {
+                reg.arg("/ve");
+            } else {
+                reg.args(["/v", name]);
+            }
+            let status = reg
+                .args(["/d", value, "/f"])
+                .status()
+                .map_err(|e| e.to_string())?;
+            if !status.success() {
+                return Err(format!("reg add {} failed", path));
+            }
+        }
+        Ok(())
+    }
+    #[cfg(target_os = "linux")]
+    {
+        let applications = dirs::data_dir()
+            .ok_or("No data directory")?
+            .join("applications");
+        std::fs::create_dir_all(&applications).map_err(|e| e.to_string())?;
+        let desktop_file = "dropout-url-handler.desktop";
+        let entry = format!(
+            "[Desktop Entry]\nType=Application\nName=DropOut\nExec=\"{}\" %u\nNoDisplay=true\nMimeType=x-scheme-handler/{};\n",
+            exe.display(),
+            SCHEME
+        );
+        std::fs::write(applications.join(desktop_file), entry).map_err(|e| e.to_string())?;
+        let status = std::process::Command::new("xdg-mime")
+            .args([
+                "default",
+                desktop_file,
+                &format!("x-scheme-handler/{}", SCHEME),
+            ])
+            .status()
+            .map_err(|e| format!("Failed to run xdg-mime: {}", e))?;
+        if !status.success() {
+            return Err("xdg-mime failed".to_string());
+        }
+        Ok(())
+    }
+    #[cfg(not(any(target_os = "windows", target_os = "linux")))]
+    {
+        let _ = exe;
+        Ok(())
+    }
+}
+
+/// Data directory Tauri uses for this app, for use before Tauri is running
+pub fn app_data_dir(identifier: &str) -> Option<PathBuf> {
+    dirs::data_dir().map(|dir| dir.join(identifier))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_install_modpack() {
+        assert_eq!(
+            parse("dropout://install-modpack?source=modrinth&id=AANobbMI").unwrap(),
+            DeepLink::InstallModpack {
+                source: "modrinth".to_string(),
+                id: "AANobbMI".to_string(),
+                version: None,
+            }
+        );
+        assert!(parse("dropout://install-modpack?source=evil&id=x").is_err());
+        assert!(parse("dropout://install-modpack?source=modrinth&id=../../x").is_err());
+        assert!(parse("dropout://install-modpack?source=modrinth").is_err());
+    }
+
+    #[test]
+    fn test_parse_join() {
+        assert_eq!(
+            parse("dropout://join/?server=play.example.com%3A25566").unwrap(),
+            DeepLink::Join {
+                server: "play.example.com:25566".to_string(),
+                instance_id: None,
+            }
+        );
+        assert!(parse("dropout://join?server=a.com:99999").is_err());
+        assert!(parse("dropout://join?server=a.com;rm -rf").is_err());
+    }
+
+    #[test]
+    fn test_authenticate() {
+        assert_eq!(
+            authenticate("abc dropout://join?server=a\n", "abc"),
+            Some("dropout://join?server=a")
+        );
+        assert_eq!(authenticate("abd dropout://join?server=a", "abc"), None);
+        assert_eq!(authenticate("dropout://join?server=a", "abc"), None);
+    }
+
+    #[test]
+    fn test_confirmation_names_the_pack() {
+        let (message, button) = confirmation(&DeepLink::InstallModpack {
+            source: "curseforge".to_string(),
+            id: "123".to_string(),
+            version: None,
+        });
+        assert!(message.contains("\"123\"") && message.contains("CurseForge"));
+        assert_eq!(button, "Install");
+    }
+
+    #[test]
+    fn test_rejects_other_links() {
+        assert!(parse("https://example.com").is_err());
+        assert!(parse("dropout://delete-instance?id=x").is_err());
+        assert_eq!(
+            url_from_args(vec![
+                "dropout".to_string(),
+                "dropout://join?server=a".to_string()
+            ]),
+            Some("dropout://join?server=a".to_string())
+        );
+    }
+}
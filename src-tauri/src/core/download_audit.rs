@@ -0,0 +1,128 @@
+//! Append-only audit log of downloaded files, one JSON line per file.
+//!
+//! Each instance gets its own log under `<app_data>/download_audit/`, so
+//! files fetched into the shared caches are still attributed to the instance
+//! whose install or launch triggered them. Pack archives fetched before their
+//! instance exists go to the [`PACK_ARCHIVES`] log.
+
+use serde::{Deserialize, Serialize};
+use std::io::{BufRead, Write};
+use std::path::PathBuf;
+use std::sync::Mutex;
+use tauri::{AppHandle, Manager};
+use ts_rs::TS;
+
+use super::downloader::DownloadTask;
+
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export, export_to = "download_audit.ts")]
+pub struct DownloadAuditEntry {
+    pub timestamp: i64,
+    pub url: String,
+    pub destination: String,
+    pub trigger: String, // "launch" | "install" | ...
+    pub status: String,  // "downloaded" | "failed"
+    pub size: Option<u64>,
+    pub sha1: Option<String>, // hash of the received file
+    pub expected_sha1: Option<String>,
+    pub expected_sha256: Option<String>,
+    pub error: Option<String>,
+}
+
+/// Log of modpack archives, which are downloaded before any instance exists
+pub const PACK_ARCHIVES: &str = "modpacks";
+
+pub struct DownloadAudit {
+    trigger: String,
+    file: Mutex<std::fs::File>,
+}
+
+fn log_path(app_handle: &AppHandle, instance_id: &str) -> Result<PathBuf, String> {
+    if !super::safe_path::is_safe_relative(instance_id) || instance_id.contains(['/', '\\']) {
+        return Err(format!("Invalid instance id: {}", instance_id));
+    }
+    Ok(app_handle
+        .path()
+        .app_data_dir()
+        .map_err(|e| e.to_string())?
+        .join("download_audit")
+        .join(format!("{}.jsonl", instance_id)))
+}
+
+impl DownloadAudit {
+    pub fn open(app_handle: &AppHandle, instance_id: &str, trigger: &str) -> Result<Self, String> {
+        let path = log_path(app_handle, instance_id)?;
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent).map_err(|e| e.to_string())?;
+        }
+        let file = std::fs::OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&path)
+            .map_err(|e| format!("Failed to open download audit log: {}", e))?;
+        Ok(Self {
+            trigger: trigger.to_string(),
+            file: Mutex::new(file),
+        })
+    }
+
+    /// Open the log like [`DownloadAudit::open`]; a log that can't be opened
+    /// is reported but never stops the download
+    pub fn open_or_warn(app_handle: &AppHandle, instance_id: &str, trigger: &str) -> Option<Self> {
+        Self::open(app_handle, instance_id, trigger)
+            .map_err(|e| eprintln!("[Downloader] {}", e))
+            .ok()
+    }
+
+    /// Record the outcome of a task: `Ok((size, sha1))` or the error
+    pub fn record(&self, task: &DownloadTask, result: Result<(u64, String), &str>) {
+        let (status, size, sha1, error) = match result {
+            Ok((size, sha1)) => ("downloaded", Some(size), Some(sha1), None),
+            Err(e) => ("failed", None, None, Some(e.to_string())),
+        };
+        let entry = DownloadAuditEntry {
+            timestamp: chrono::Utc::now().timestamp(),
+            url: task.url.clone(),
+            destination: task.path.to_string_lossy().to_string(),
+            trigger: self.trigger.clone(),
+            status: status.to_string(),
+            size,
+            sha1,
+            expected_sha1: task.sha1.clone(),
+            expected_sha256: task.sha256.clone(),
+            error,
+        };
+        let Ok(mut line) = serde_json::to_string(&entry) else {
+            return;
+        };
+        line.push('\n');
+        if let Err(e) = self.file.lock().unwrap().write_all(line.as_bytes()) {
+            eprintln!("[Downloader] Failed to write audit log: {}", e);
+        }
+    }
+}
+
+/// Read an instance's audit log, newest entries first
+pub fn read_audit(
+    app_handle: &AppHandle,
+    instance_id: &str,
+    limit: Option<usize>,
+) -> Result<Vec<DownloadAuditEntry>, String> {
+    let path = log_path(app_handle, instance_id)?;
+    if !path.exists() {
+        return Ok(Vec::new());
+    }
+    let file = std::fs::File::open(&path).map_err(|e| e.to_string())?;
+    // Skip lines that fail to parse (e.g. cut short by a crash)
+    let mut entries: Vec<DownloadAuditEntry> = std::io::BufReader::new(file)
+        .lines()
+        .map_while(Result::ok)
+        .filter_map(|line| serde_json::from_str(&line).ok())
+        .collect();
+    entries.reverse();
+    if let Some(limit) = limit {
+        entries.truncate(limit);
+    }
+    Ok(entries)
+}
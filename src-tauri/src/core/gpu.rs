@@ -0,0 +1,223 @@
+//! Graphics driver probe, so preflight can warn before the game fails to
+//! create its OpenGL context.
+//!
+//! There's no GL context in the launcher, so the information comes from the
+//! platform tools: `glxinfo -B` on Linux, WMI (through PowerShell) on Windows
+//! and `system_profiler` on macOS. Windows doesn't report a GL version that
+//! way, so only the driver is shown there.
+
+use serde::{Deserialize, Serialize};
+use std::process::Command;
+use ts_rs::TS;
+
+#[cfg(target_os = "windows")]
+use std::os::windows::process::CommandExt;
+
+/// Minecraft 1.17+ needs an OpenGL 3.2 core profile
+pub const MODERN_MINECRAFT_GL: (u32, u32) = (3, 2);
+/// Compute shaders, used by many shader packs, need OpenGL 4.3
+pub const COMPUTE_SHADER_GL: (u32, u32) = (4, 3);
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export, export_to = "gpu.ts")]
+pub struct GpuInfo {
+    pub vendor: Option<String>,
+    pub renderer: Option<String>,
+    pub driver_version: Option<String>,
+    pub gl_version: Option<String>, // "major.minor", if known
+    pub source: String,             // "glxinfo" | "wmi" | "system_profiler" | "none"
+    pub warnings: Vec<String>,
+}
+
+/// `(major, minor)` at the start of a GL version string such as
+/// "4.6 (Core Profile) Mesa 23.1.4"
+pub fn parse_gl_version(version: &str) -> Option<(u32, u32)> {
+    let number = version.split_whitespace().next()?;
+    let mut parts = number.split('.');
+    let major = parts.next()?.parse().ok()?;
+    let minor = parts.next()?.parse().ok()?;
+    Some((major, minor))
+}
+
+/// Value of the first `key: value` line; the first line wins when several
+/// GPUs are listed
+fn value_of<'a>(output: &'a str, key: &str) -> Option<&'a str> {
+    output.lines().find_map(|line| {
+        let (k, v) = line.split_once(':')?;
+        (k.trim() == key)
+            .then(|| v.trim())
+            .filter(|v| !v.is_empty())
+    })
+}
+
+/// Parse `glxinfo -B`. The core profile version is preferred: the plain
+/// "OpenGL version string" is the (lower) compatibility profile on Mesa.
+pub fn parse_glxinfo(output: &str) -> GpuInfo {
+    let version = value_of(output, "OpenGL core profile version string")
+        .or_else(|| value_of(output, "OpenGL version string"));
+    // "4.6 (Core Profile) Mesa 23.1.4" or "4.6.0 NVIDIA 535.54.03"
+    let driver_version = version
+        .and_then(|v| v.split_once(' '))
+        .map(|(_, rest)| match rest.trim().strip_prefix('(') {
+            Some(profile) => profile.split_once(')').map_or("", |(_, d)| d),
+            None => rest,
+        })
+        .map(str::trim)
+        .filter(|d| !d.is_empty())
+        .map(String::from);
+    GpuInfo {
+        vendor: value_of(output, "OpenGL vendor string").map(String::from),
+        renderer: value_of(output, "OpenGL renderer string").map(String::from),
+        driver_version,
+        gl_version: version
+            .and_then(parse_gl_version)
+            .map(|(major, minor)| format!("{}.{}", major, minor)),
+        source: "glxinfo".to_string(),
+        warnings: Vec::new(),
+    }
+}
+
+/// Parse `Get-CimInstance Win32_VideoController | Format-List Name,AdapterCompatibility,DriverVersion`
+pub fn parse_wmi(output: &str) -> GpuInfo {
+    GpuInfo {
+        vendor: value_of(output, "AdapterCompatibility").map(String::from),
+        renderer: value_of(output, "Name").map(String::from),
+        driver_version: value_of(output, "DriverVersion").map(String::from),
+        gl_version: None,
+        source: "wmi".to_string(),
+        warnings: Vec::new(),
+    }
+}
+
+/// Parse `system_profiler SPDisplaysDataType`. macOS caps OpenGL at 4.1.
+pub fn parse_system_profiler(output: &str) -> GpuInfo {
+    GpuInfo {
+        vendor: value_of(output, "Vendor").map(String::from),
+        renderer: value_of(output, "Chipset Model").map(String::from),
+        driver_version: None,
+        gl_version: Some("4.1".to_string()),
+        source: "system_profiler".to_string(),
+        warnings: Vec::new(),
+    }
+}
+
+/// Warnings for what the reported driver can't run
+pub fn warnings(info: &GpuInfo) -> Vec<String> {
+    let mut warnings = Vec::new();
+    let renderer = info.renderer.as_deref().unwrap_or_default().to_lowercase();
+    if renderer.contains("llvmpipe") || renderer.contains("softpipe") {
+        warnings.push(
+            "OpenGL is software rendered (llvmpipe); install the graphics driver for your GPU"
+                .to_string(),
+        );
+    } else if renderer.contains("microsoft basic render") {
+        warnings.push(
+            "Windows is using the Microsoft Basic Render Driver; install the driver for your GPU"
+                .to_string(),
+        );
+    }
+    if let Some(version) = info.gl_version.as_deref().and_then(parse_gl_version) {
+        if version < MODERN_MINECRAFT_GL {
+            warnings.push(format!(
+                "OpenGL {}.{} is too old for Minecraft 1.17 and newer, which need OpenGL {}.{}",
+                version.0, version.1, MODERN_MINECRAFT_GL.0, MODERN_MINECRAFT_GL.1
+            ));
+        } else if version < COMPUTE_SHADER_GL {
+            warnings.push(format!(
+                "OpenGL {}.{} has no compute shaders (OpenGL {}.{}); some shader packs won't work",
+                version.0, version.1, COMPUTE_SHADER_GL.0, COMPUTE_SHADER_GL.1
+            ));
+        }
+    }
+    warnings
+}
+
+fn run(program: &str, args: &[&str]) -> Option<String> {
+    let mut cmd = Command::new(program);
+    cmd.args(args);
+    #[cfg(target_os = "windows")]
+    cmd.creation_flags(0x08000000);
+    let output = cmd.output().ok()?;
+    output
+        .status
+        .success()
+        .then(|| String::from_utf8_lossy(&output.stdout).into_owned())
+}
+
+fn probe_blocking() -> GpuInfo {
+    let info = if cfg!(target_os = "windows") {
+        run(
+            "powershell",
+            &[
+                "-NoProfile",
+                "-Command",
+                "Get-CimInstance Win32_VideoController | Format-List Name,AdapterCompatibility,DriverVersion",
+            ],
+        )
+        .map(|out| parse_wmi(&out))
+    } else if cfg!(target_os = "macos") {
+        run("system_profiler", &["SPDisplaysDataType"]).map(|out| parse_system_profiler(&out))
+    } else {
+        run("glxinfo", &["-B"]).map(|out| parse_glxinfo(&out))
+    };
+    let mut info = info.unwrap_or_else(|| GpuInfo {
+        source: "none".to_string(),
+        ..Default::default()
+    });
+    info.warnings = warnings(&info);
+    info
+}
+
+pub async fn probe() -> GpuInfo {
+    tokio::task::spawn_blocking(probe_blocking)
+        .await
+        .unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_glxinfo() {
+        let output = "name of display: :0
+display: :0  screen: 0
+direct rendering: Yes
+Extended renderer info (GLX_MESA_query_renderer):
+    Vendor: AMD (0x1002)
+OpenGL vendor string: AMD
+OpenGL renderer string: AMD Radeon RX 6700 XT (navi22, LLVM 15.0.7, DRM 3.49, 6.1.0)
+OpenGL core profile version string: 4.6 (Core Profile) Mesa 23.1.4
+OpenGL version string: 4.6 (Compatibility Profile) Mesa 23.1.4
+";
+        let info = parse_glxinfo(output);
+        assert_eq!(info.vendor.as_deref(), Some("AMD"));
+        assert_eq!(info.gl_version.as_deref(), Some("4.6"));
+        assert_eq!(info.driver_version.as_deref(), Some("Mesa 23.1.4"));
+        assert!(warnings(&info).is_empty());
+
+        let wmi = parse_wmi(
+            "\r\nName                 : NVIDIA GeForce RTX 3070\r\nAdapterCompatibility : NVIDIA\r\nDriverVersion        : 31.0.15.3623\r\n",
+        );
+        assert_eq!(wmi.renderer.as_deref(), Some("NVIDIA GeForce RTX 3070"));
+        assert_eq!(wmi.driver_version.as_deref(), Some("31.0.15.3623"));
+    }
+
+    #[test]
+    fn test_warnings() {
+        let mut info = GpuInfo {
+            renderer: Some("llvmpipe (LLVM 15.0.7, 256 bits)".to_string()),
+            gl_version: Some("3.1".to_string()),
+            ..Default::default()
+        };
+        let w = warnings(&info);
+        assert_eq!(w.len(), 2);
+        assert!(w[1].contains("1.17"));
+
+        info.renderer = Some("Intel HD Graphics 4000".to_string());
+        info.gl_version = Some("4.0".to_string());
+        assert!(warnings(&info)[0].contains("compute shaders"));
+        assert_eq!(parse_gl_version("2.1 Mesa 20.0"), Some((2, 1)));
+    }
+}
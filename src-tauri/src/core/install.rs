@@ -0,0 +1,177 @@
+//! Phases of a version install (`install_version`).
+//!
+//! An install moves forward through [`InstallPhase`]s and ends in `Done` or
+//! `Failed`. Every transition is emitted as an `install-state` event with the
+//! time spent in the phase it leaves, so the UI can show a stepper, and the
+//! finished install logs the duration of each phase.
+
+use serde::{Deserialize, Serialize};
+use std::time::Instant;
+use tauri::{Emitter, Manager, Window};
+use ts_rs::TS;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export, export_to = "install.ts")]
+pub enum InstallPhase {
+    FetchingMetadata,  // version JSON, inherited profiles
+    PlanningDownloads, // client, libraries, natives, asset index
+    Downloading,
+    ExtractingNatives,
+    Done,
+    Failed,
+}
+
+impl InstallPhase {
+    pub fn is_terminal(self) -> bool {
+        matches!(self, InstallPhase::Done | InstallPhase::Failed)
+    }
+
+    /// Phases only move forward; any unfinished phase can fail
+    pub fn can_advance_to(self, next: InstallPhase) -> bool {
+        if self.is_terminal() {
+            return false;
+        }
+        next == InstallPhase::Failed || next > self
+    }
+}
+
+/// Payload of the `install-state` event
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export, export_to = "install.ts")]
+pub struct InstallStateEvent {
+    pub instance_id: String,
+    pub version_id: String,
+    pub phase: InstallPhase,
+    pub previous: Option<InstallPhase>,
+    pub previous_ms: u64, // time spent in `previous`
+    pub elapsed_ms: u64,  // since the install started
+    pub error: Option<String>,
+}
+
+pub struct InstallStateMachine {
+    window: Window,
+    instance_id: String,
+    version_id: String,
+    phase: InstallPhase,
+    started: Instant,
+    phase_started: Instant,
+    durations: Vec<(InstallPhase, u64)>,
+}
+
+impl InstallStateMachine {
+    /// Start an install in `FetchingMetadata`
+    pub fn start(window: &Window, instance_id: &str, version_id: &str) -> Self {
+        let now = Instant::now();
+        let machine = Self {
+            window: window.clone(),
+            instance_id: instance_id.to_string(),
+            version_id: version_id.to_string(),
+            phase: InstallPhase::FetchingMetadata,
+            started: now,
+            phase_started: now,
+            durations: Vec::new(),
+        };
+        machine.emit(None, 0, None);
+        machine
+    }
+
+    /// Move to `next`. Backward moves and moves out of a finished install are
+    /// ignored.
+    pub fn advance(&mut self, next: InstallPhase) {
+        self.transition(next, None);
+    }
+
+    /// Move to `Done` or `Failed` depending on `result` and log the phase
+    /// durations
+    pub fn finish(&mut self, result: &Result<(), String>) {
+        match result {
+            Ok(()) => self.transition(InstallPhase::Done, None),
+            Err(e) => self.transition(InstallPhase::Failed, Some(e.clone())),
+        }
+        let summary = format!(
+            "Install of {} {} in {} ms ({})",
+            self.version_id,
+            if result.is_ok() { "finished" } else { "failed" },
+            self.started.elapsed().as_millis(),
+            format_durations(&self.durations)
+        );
+        super::log_batch::log(self.window.app_handle(), summary);
+    }
+
+    fn transition(&mut self, next: InstallPhase, error: Option<String>) {
+        if !self.phase.can_advance_to(next) {
+            eprintln!(
+                "[Install] Ignoring transition {:?} -> {:?}",
+                self.phase, next
+            );
+            return;
+        }
+        let previous = self.phase;
+        let previous_ms = self.phase_started.elapsed().as_millis() as u64;
+        self.durations.push((previous, previous_ms));
+        self.phase = next;
+        self.phase_started = Instant::now();
+        self.emit(Some(previous), previous_ms, error);
+    }
+
+    fn emit(&self, previous: Option<InstallPhase>, previous_ms: u64, error: Option<String>) {
+        let _ = self.window.emit(
+            "install-state",
+            InstallStateEvent {
+                instance_id: self.instance_id.clone(),
+                version_id: self.version_id.clone(),
+                phase: self.phase,
+                previous,
+                previous_ms,
+                elapsed_ms: self.started.elapsed().as_millis() as u64,
+                error,
+            },
+        );
+    }
+}
+
+/// "fetchingMetadata 120 ms, downloading 5300 ms, ..."
+fn format_durations(durations: &[(InstallPhase, u64)]) -> String {
+    durations
+        .iter()
+        .map(|(phase, ms)| {
+            let name = serde_json::to_value(phase)
+                .ok()
+                .and_then(|v| v.as_str().map(String::from))
+                .unwrap_or_default();
+            format!("{} {} ms", name, ms)
+        })
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_transitions() {
+        use InstallPhase::*;
+        assert!(FetchingMetadata.can_advance_to(PlanningDownloads));
+        assert!(PlanningDownloads.can_advance_to(ExtractingNatives));
+        assert!(Downloading.can_advance_to(Failed));
+        assert!(ExtractingNatives.can_advance_to(Done));
+        assert!(!Downloading.can_advance_to(PlanningDownloads));
+        assert!(!Downloading.can_advance_to(Downloading));
+        assert!(!Done.can_advance_to(Failed));
+        assert!(!Failed.can_advance_to(Done));
+    }
+
+    #[test]
+    fn test_format_durations() {
+        assert_eq!(
+            format_durations(&[
+                (InstallPhase::FetchingMetadata, 120),
+                (InstallPhase::Downloading, 5300)
+            ]),
+            "fetchingMetadata 120 ms, downloading 5300 ms"
+        );
+    }
+}
@@ -0,0 +1,117 @@
+//! Java detection and provisioning live in `dropout_core::java`; these
+//! wrappers take their data directory from the app, download settings from
+//! the launcher config and send events to every window.
+
+use std::path::PathBuf;
+use std::sync::Arc;
+use tauri::{AppHandle, Manager};
+
+pub use dropout_core::java::*;
+
+use super::config::ConfigState;
+use super::downloader::AppEvents;
+
+fn data_dir(app_handle: &AppHandle) -> PathBuf {
+    app_handle.path().app_data_dir().unwrap()
+}
+
+fn download_settings(app_handle: &AppHandle) -> JavaDownloadSettings {
+    app_handle
+        .try_state::<ConfigState>()
+        .map(|state| {
+            let config = state.config.lock().unwrap();
+            JavaDownloadSettings {
+                mirror: config.java_download_mirror,
+                archive_cache_mb: config.java_archive_cache_mb,
+            }
+        })
+        .unwrap_or_default()
+}
+
+pub async fn detect_all_java_installations(app_handle: &AppHandle) -> Vec<JavaInstallation> {
+    dropout_core::java::detect_all_java_installations(&data_dir(app_handle)).await
+}
+
+pub async fn resolve_java_for_launch(
+    app_handle: &AppHandle,
+    instance_java_override: Option<&str>,
+    global_java_path: Option<&str>,
+    required_major_version: Option<u64>,
+    max_major_version: Option<u32>,
+) -> Option<JavaInstallation> {
+    priority::resolve_java_for_launch(
+        &data_dir(app_handle),
+        instance_java_override,
+        global_java_path,
+        required_major_version,
+        max_major_version,
+    )
+    .await
+}
+
+pub async fn fetch_java_catalog(
+    app_handle: &AppHandle,
+    force_refresh: bool,
+    filter: CatalogFilter,
+) -> Result<JavaCatalog, String> {
+    dropout_core::java::fetch_java_catalog(
+        &data_dir(app_handle),
+        Arc::new(AppEvents(app_handle.clone())),
+        force_refresh,
+        filter,
+    )
+    .await
+}
+
+pub async fn fetch_java_release(
+    app_handle: &AppHandle,
+    major_version: u32,
+    image_type: ImageType,
+) -> Result<JavaDownloadInfo, String> {
+    dropout_core::java::fetch_java_release(
+        download_settings(app_handle).mirror,
+        major_version,
+        image_type,
+    )
+    .await
+}
+
+pub async fn download_and_install_java(
+    app_handle: &AppHandle,
+    major_version: u32,
+    image_type: ImageType,
+    custom_path: Option<PathBuf>,
+) -> Result<JavaInstallation, String> {
+    dropout_core::java::download_and_install_java(
+        &data_dir(app_handle),
+        &AppEvents(app_handle.clone()),
+        download_settings(app_handle),
+        major_version,
+        image_type,
+        custom_path,
+    )
+    .await
+}
+
+pub async fn resume_pending_downloads(
+    app_handle: &AppHandle,
+) -> Result<Vec<JavaInstallation>, String> {
+    dropout_core::java::resume_pending_downloads(
+        &data_dir(app_handle),
+        &AppEvents(app_handle.clone()),
+        download_settings(app_handle),
+    )
+    .await
+}
+
+pub fn get_pending_downloads(app_handle: &AppHandle) -> Vec<PendingJavaDownload> {
+    dropout_core::java::get_pending_downloads(&data_dir(app_handle))
+}
+
+pub fn clear_pending_download(
+    app_handle: &AppHandle,
+    major_version: u32,
+    image_type: &str,
+) -> Result<(), String> {
+    dropout_core::java::clear_pending_download(&data_dir(app_handle), major_version, image_type)
+}
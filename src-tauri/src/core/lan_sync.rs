@@ -0,0 +1,721 @@
+//! Instance sync between DropOut launchers on the same LAN.
+//!
+//! Each launcher with LAN sync enabled announces itself over mDNS
+//! (`_dropout._tcp`) and listens on a TCP port. A sender streams an
+//! instance's mods, config and packs to a peer, which creates a new instance
+//! from them. Transfers must carry the receiver's pairing code, shown in its
+//! UI, so nobody on the network can push files without the owner's consent.
+//!
+//! Wire format: the sender writes a [`SyncHeader`] as one JSON line, the
+//! receiver answers with a [`SyncReply`] line, then the file contents follow
+//! back-to-back in header order and the receiver sends a final reply.
+//!
+//! The listener is reachable from the whole network, so a peer that sends
+//! [`MAX_CODE_ATTEMPTS`] wrong codes is locked out (see
+//! [`AttemptLimiter`]), as is the whole listener after
+//! [`MAX_GLOBAL_CODE_ATTEMPTS`] wrong codes from any peers. The pairing code
+//! is replaced after every accepted code and every lockout, so each code
+//! only ever faces a handful of guesses. Header lines are capped at
+//! [`MAX_HEADER_BYTES`], and received mod jars go through the mod scan
+//! before the instance is created.
+
+use super::attempts::{AttemptLimiter, constant_time_eq, lockout_message};
+use super::instance::{Instance, InstanceState};
+use mdns_sd::{ServiceDaemon, ServiceEvent, ServiceInfo};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::net::{IpAddr, SocketAddr};
+use std::path::{Component, Path, PathBuf};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use tauri::{AppHandle, Emitter, Manager};
+use tokio::io::{AsyncBufReadExt, AsyncReadExt, AsyncWriteExt, BufReader};
+use tokio::net::{TcpListener, TcpStream};
+use ts_rs::TS;
+
+const SERVICE_TYPE: &str = "_dropout._tcp.local.";
+const PROTOCOL_VERSION: u32 = 1;
+/// Top-level folders of an instance that are synced
+const SYNC_DIRS: &[&str] = &["mods", "config", "resourcepacks", "shaderpacks"];
+/// Top-level files of an instance that are synced
+const SYNC_FILES: &[&str] = &["options.txt"];
+/// Refuse transfers larger than this
+const MAX_TRANSFER_BYTES: u64 = 8 * 1024 * 1024 * 1024;
+/// Longest header or reply line accepted
+pub const MAX_HEADER_BYTES: u64 = 4 * 1024 * 1024;
+/// Wrong pairing codes a peer may send before it is locked out
+pub const MAX_CODE_ATTEMPTS: u32 = 5;
+/// Wrong pairing codes from all peers together before nobody may pair
+pub const MAX_GLOBAL_CODE_ATTEMPTS: u32 = 20;
+const CODE_LOCKOUT: Duration = Duration::from_secs(60);
+
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export, export_to = "lan_sync.ts")]
+pub struct LanPeer {
+    pub id: String,
+    pub name: String,
+    pub address: String,
+    pub port: u16,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export, export_to = "lan_sync.ts")]
+pub struct LanSyncInfo {
+    pub running: bool,
+    pub device_name: String,
+    pub pairing_code: String,
+    pub port: Option<u16>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export, export_to = "lan_sync.ts")]
+pub struct LanSyncProgress {
+    pub instance_name: String,
+    pub file: String,
+    pub transferred_bytes: u64,
+    pub total_bytes: u64,
+    pub completed_files: usize,
+    pub total_files: usize,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SyncFileEntry {
+    pub path: String,
+    pub size: u64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SyncHeader {
+    pub protocol: u32,
+    pub pairing_code: String,
+    pub sender_name: String,
+    pub instance_name: String,
+    pub version_id: Option<String>,
+    pub mod_loader: Option<String>,
+    pub mod_loader_version: Option<String>,
+    pub files: Vec<SyncFileEntry>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SyncReply {
+    pub accepted: bool,
+    pub error: Option<String>,
+}
+
+/// Runtime state of LAN sync (managed by Tauri)
+pub struct LanSyncState {
+    pub device_name: String,
+    pub peers: Arc<Mutex<HashMap<String, LanPeer>>>,
+    pairing_code: Mutex<String>,
+    daemon: Mutex<Option<ServiceDaemon>>,
+    listener: Mutex<Option<(u16, tauri::async_runtime::JoinHandle<()>)>>,
+    attempts: AttemptLimiter<IpAddr>,
+    global_attempts: AttemptLimiter<()>,
+}
+
+impl Default for LanSyncState {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl LanSyncState {
+    pub fn new() -> Self {
+        let device_name = std::env::var("COMPUTERNAME")
+            .or_else(|_| std::env::var("HOSTNAME"))
+            .unwrap_or_else(|_| "DropOut".to_string());
+        Self {
+            device_name,
+            peers: Arc::new(Mutex::new(HashMap::new())),
+            pairing_code: Mutex::new(new_pairing_code()),
+            daemon: Mutex::new(None),
+            listener: Mutex::new(None),
+            attempts: AttemptLimiter::new(MAX_CODE_ATTEMPTS, CODE_LOCKOUT),
+            global_attempts: AttemptLimiter::new(MAX_GLOBAL_CODE_ATTEMPTS, CODE_LOCKOUT),
+        }
+    }
+
+    pub fn info(&self) -> LanSyncInfo {
+        let port = self.listener.lock().unwrap().as_ref().map(|(p, _)| *p);
+        LanSyncInfo {
+            running: port.is_some(),
+            device_name: self.device_name.clone(),
+            pairing_code: self.pairing_code(),
+            port,
+        }
+    }
+
+    pub fn pairing_code(&self) -> String {
+        self.pairing_code.lock().unwrap().clone()
+    }
+
+    fn rotate_pairing_code(&self) {
+        *self.pairing_code.lock().unwrap() = new_pairing_code();
+    }
+
+    pub fn list_peers(&self) -> Vec<LanPeer> {
+        let mut peers: Vec<LanPeer> = self.peers.lock().unwrap().values().cloned().collect();
+        peers.sort_by(|a, b| a.name.cmp(&b.name));
+        peers
+    }
+}
+
+/// 6-digit code derived from a random UUID
+fn new_pairing_code() -> String {
+    format!("{:06}", uuid::Uuid::new_v4().as_u128() % 1_000_000)
+}
+
+/// Start listening for transfers and announce/browse over mDNS
+pub async fn start(app_handle: &AppHandle) -> Result<LanSyncInfo, String> {
+    let state = app_handle.state::<LanSyncState>();
+    if state.listener.lock().unwrap().is_some() {
+        return Ok(state.info());
+    }
+
+    let listener = TcpListener::bind("0.0.0.0:0")
+        .await
+        .map_err(|e| format!("Failed to open LAN sync port: {}", e))?;
+    let port = listener.local_addr().map_err(|e| e.to_string())?.port();
+
+    let accept_handle = app_handle.clone();
+    let task = tauri::async_runtime::spawn(async move {
+        while let Ok((stream, addr)) = listener.accept().await {
+            let handle = accept_handle.clone();
+            tauri::async_runtime::spawn(async move {
+                if let Err(e) = receive_instance(&handle, stream, addr).await {
+                    eprintln!("[LanSync] Transfer from {} failed: {}", addr, e);
+                }
+            });
+        }
+    });
+
+    let daemon = match announce(&state, port) {
+        Ok(daemon) => daemon,
+        Err(e) => {
+            task.abort();
+            return Err(e);
+        }
+    };
+
+    *state.daemon.lock().unwrap() = Some(daemon);
+    *state.listener.lock().unwrap() = Some((port, task));
+    println!("[LanSync] Listening on port {}", port);
+    Ok(state.info())
+}
+
+/// Register this launcher over mDNS and keep `peers` up to date
+fn announce(state: &LanSyncState, port: u16) -> Result<ServiceDaemon, String> {
+    let daemon = ServiceDaemon::new().map_err(|e| format!("Failed to start mDNS: {}", e))?;
+    if let Err(e) = register(&daemon, state, port) {
+        let _ = daemon.shutdown();
+        return Err(e);
+    }
+    Ok(daemon)
+}
+
+fn register(daemon: &ServiceDaemon, state: &LanSyncState, port: u16) -> Result<(), String> {
+    let instance_name = format!("{}-{}", state.device_name, port);
+    let host_name = format!("{}.local.", state.device_name);
+    let properties = [
+        ("name", state.device_name.as_str()),
+        ("version", env!("CARGO_PKG_VERSION")),
+    ];
+    let service = ServiceInfo::new(
+        SERVICE_TYPE,
+        &instance_name,
+        &host_name,
+        "",
+        port,
+        &properties[..],
+    )
+    .map_err(|e| e.to_string())?
+    .enable_addr_auto();
+    let own_fullname = service.get_fullname().to_string();
+    daemon.register(service).map_err(|e| e.to_string())?;
+
+    let receiver = daemon.browse(SERVICE_TYPE).map_err(|e| e.to_string())?;
+    let peers = state.peers.clone();
+    std::thread::spawn(move || {
+        while let Ok(event) = receiver.recv() {
+            match event {
+                ServiceEvent::ServiceResolved(info) => {
+                    if info.get_fullname() == own_fullname {
+                        continue;
+                    }
+                    let Some(address) = info
+                        .get_addresses()
+                        .iter()
+                        .find(|a| a.is_ipv4())
+                        .or_else(|| info.get_addresses().iter().next())
+                    else {
+                        continue;
+                    };
+                    let peer = LanPeer {
+                        id: info.get_fullname().to_string(),
+                        name: info
+                            .get_property_val_str("name")
+                            .unwrap_or(info.get_hostname())
+                            .to_string(),
+                        address: address.to_string(),
+                        port: info.get_port(),
+                    };
+                    peers.lock().unwrap().insert(peer.id.clone(), peer);
+                }
+                ServiceEvent::ServiceRemoved(_, fullname) => {
+                    peers.lock().unwrap().remove(&fullname);
+                }
+                ServiceEvent::SearchStopped(_) => break,
+                _ => {}
+            }
+        }
+    });
+    Ok(())
+}
+
+/// Stop announcing and refuse further transfers
+pub fn stop(app_handle: &AppHandle) {
+    let state = app_handle.state::<LanSyncState>();
+    if let Some(daemon) = state.daemon.lock().unwrap().take() {
+        let _ = daemon.shutdown();
+    }
+    if let Some((_, task)) = state.listener.lock().unwrap().take() {
+        task.abort();
+    }
+    state.peers.lock().unwrap().clear();
+}
+
+/// Check that a path received from a peer stays inside the synced folders
+pub fn is_syncable_path(path: &str) -> bool {
+    if !super::safe_path::is_safe_relative(path) {
+        return false;
+    }
+    let mut components = Path::new(path).components();
+    let Some(Component::Normal(first)) = components.next() else {
+        return false;
+    };
+    let first = first.to_string_lossy();
+    if components.next().is_none() {
+        SYNC_FILES.contains(&first.as_ref())
+    } else {
+        SYNC_DIRS.contains(&first.as_ref())
+    }
+}
+
+fn collect_files(game_dir: &Path) -> Vec<(String, PathBuf, u64)> {
+    fn walk(dir: &Path, base: &Path, out: &mut Vec<(String, PathBuf, u64)>) {
+        let Ok(entries) = std::fs::read_dir(dir) else {
+            return;
+        };
+        for entry in entries.flatten() {
+            let path = entry.path();
+            if path.is_dir() {
+                walk(&path, base, out);
+            } else if let (Ok(rel), Ok(meta)) = (path.strip_prefix(base), entry.metadata()) {
+                let rel = rel.to_string_lossy().replace('\\', "/");
+                out.push((rel, path.clone(), meta.len()));
+            }
+        }
+    }
+
+    let mut files = Vec::new();
+    for dir in SYNC_DIRS {
+        walk(&game_dir.join(dir), game_dir, &mut files);
+    }
+    for file in SYNC_FILES {
+        let path = game_dir.join(file);
+        if let Ok(meta) = std::fs::metadata(&path) {
+            files.push((file.to_string(), path, meta.len()));
+        }
+    }
+    files
+}
+
+/// Read one `\n`-terminated line of at most [`MAX_HEADER_BYTES`]
+async fn read_bounded_line(reader: &mut BufReader<TcpStream>) -> Result<String, String> {
+    let mut line = String::new();
+    (&mut *reader)
+        .take(MAX_HEADER_BYTES)
+        .read_line(&mut line)
+        .await
+        .map_err(|e| e.to_string())?;
+    if !line.ends_with('\n') {
+        return Err("Header line too long or connection closed".to_string());
+    }
+    Ok(line)
+}
+
+async fn read_reply(reader: &mut BufReader<TcpStream>) -> Result<SyncReply, String> {
+    let line = read_bounded_line(reader).await?;
+    serde_json::from_str(&line).map_err(|e| format!("Invalid reply from peer: {}", e))
+}
+
+async fn write_line<T: Serialize>(stream: &mut TcpStream, value: &T) -> Result<(), String> {
+    let mut line = serde_json::to_string(value).map_err(|e| e.to_string())?;
+    line.push('\n');
+    stream
+        .write_all(line.as_bytes())
+        .await
+        .map_err(|e| e.to_string())
+}
+
+/// Stream an instance's mods and config to a peer
+pub async fn send_instance(
+    app_handle: &AppHandle,
+    instance: &Instance,
+    peer: &LanPeer,
+    pairing_code: &str,
+) -> Result<(), String> {
+    let files = collect_files(&instance.game_dir);
+    let total_bytes: u64 = files.iter().map(|(_, _, size)| size).sum();
+    let device_name = app_handle.state::<LanSyncState>().device_name.clone();
+
+    let header = SyncHeader {
+        protocol: PROTOCOL_VERSION,
+        pairing_code: pairing_code.trim().to_string(),
+        sender_name: device_name,
+        instance_name: instance.name.clone(),
+        version_id: instance.version_id.clone(),
+        mod_loader: instance.mod_loader.clone(),
+        mod_loader_version: instance.mod_loader_version.clone(),
+        files: files
+            .iter()
+            .map(|(path, _, size)| SyncFileEntry {
+                path: path.clone(),
+                size: *size,
+            })
+            .collect(),
+    };
+
+    let stream = TcpStream::connect((peer.address.as_str(), peer.port))
+        .await
+        .map_err(|e| format!("Failed to connect to {}: {}", peer.name, e))?;
+    let mut reader = BufReader::new(stream);
+
+    write_line(reader.get_mut(), &header).await?;
+    let reply = read_reply(&mut reader).await?;
+    if !reply.accepted {
+        return Err(reply
+            .error
+            .unwrap_or_else(|| "Transfer rejected by peer".to_string()));
+    }
+
+    let mut transferred = 0u64;
+    for (i, (rel, path, size)) in files.iter().enumerate() {
+        let mut file = tokio::fs::File::open(path)
+            .await
+            .map_err(|e| format!("Failed to open {}: {}", rel, e))?;
+        // Send exactly the announced size even if the file changed meanwhile
+        let mut limited = (&mut file).take(*size);
+        let copied = tokio::io::copy(&mut limited, reader.get_mut())
+            .await
+            .map_err(|e| e.to_string())?;
+        if copied != *size {
+            return Err(format!("{} changed during transfer", rel));
+        }
+        transferred += size;
+        let _ = app_handle.emit(
+            "lan-sync-progress",
+            LanSyncProgress {
+                instance_name: instance.name.clone(),
+                file: rel.clone(),
+                transferred_bytes: transferred,
+                total_bytes,
+                completed_files: i + 1,
+                total_files: files.len(),
+            },
+        );
+    }
+    reader.get_mut().flush().await.map_err(|e| e.to_string())?;
+
+    let reply = read_reply(&mut reader).await?;
+    if reply.accepted {
+        Ok(())
+    } else {
+        Err(reply
+            .error
+            .unwrap_or_else(|| "Peer failed to store the instance".to_string()))
+    }
+}
+
+fn validate_header(header: &SyncHeader) -> Result<(), String> {
+    if header.protocol != PROTOCOL_VERSION {
+        return Err(format!("Unsupported protocol version {}", header.protocol));
+    }
+    if let Some(bad) = header.files.iter().find(|f| !is_syncable_path(&f.path)) {
+        return Err(format!("Refusing to write {}", bad.path));
+    }
+    let total: u64 = header.files.iter().map(|f| f.size).sum();
+    if total > MAX_TRANSFER_BYTES {
+        return Err("Transfer too large".to_string());
+    }
+    Ok(())
+}
+
+/// `Err` while `peer`, or the listener as a whole, is locked out
+fn check_lockout(state: &LanSyncState, peer: IpAddr) -> Result<(), String> {
+    state
+        .global_attempts
+        .check(&())
+        .and_then(|()| state.attempts.check(&peer))
+        .map_err(lockout_message)
+}
+
+/// Check the pairing code of a peer, counting wrong ones against its address
+/// and the listener. The code is replaced once it has been used, and when a
+/// lockout starts.
+fn check_pairing_code(state: &LanSyncState, peer: IpAddr, code: &str) -> Result<(), String> {
+    check_lockout(state, peer)?;
+    if constant_time_eq(code, &state.pairing_code()) {
+        state.attempts.record_success(&peer);
+        state.rotate_pairing_code();
+        Ok(())
+    } else {
+        state.attempts.record_failure(&peer);
+        state.global_attempts.record_failure(&());
+        if check_lockout(state, peer).is_err() {
+            state.rotate_pairing_code();
+        }
+        Err("Wrong pairing code".to_string())
+    }
+}
+
+/// Run the received mod jars through the mod scan
+fn scan_received(game_dir: &Path, files: &[SyncFileEntry]) -> Result<(), String> {
+    for entry in files {
+        let path = game_dir.join(&entry.path);
+        if super::mod_scan::is_mod_jar(&path) {
+            super::mod_scan::check_before_install(&path, &entry.path)?;
+        }
+    }
+    Ok(())
+}
+
+async fn reject(reader: &mut BufReader<TcpStream>, error: String) -> Result<(), String> {
+    let reply = SyncReply {
+        accepted: false,
+        error: Some(error.clone()),
+    };
+    write_line(reader.get_mut(), &reply).await?;
+    Err(error)
+}
+
+async fn receive_instance(
+    app_handle: &AppHandle,
+    stream: TcpStream,
+    addr: SocketAddr,
+) -> Result<(), String> {
+    let mut reader = BufReader::new(stream);
+    let state = app_handle.state::<LanSyncState>();
+    if let Err(e) = check_lockout(&state, addr.ip()) {
+        return reject(&mut reader, e).await;
+    }
+    let line = read_bounded_line(&mut reader).await?;
+    let header: SyncHeader =
+        serde_json::from_str(&line).map_err(|e| format!("Invalid header: {}", e))?;
+
+    let checked = check_pairing_code(&state, addr.ip(), &header.pairing_code);
+    // The code may have been replaced; let the UI show the current one
+    let _ = app_handle.emit("lan-sync-info", state.info());
+    if let Err(e) = checked
+        .and_then(|()| validate_header(&header))
+        .and_then(|()| super::parental::require_unlocked(app_handle, "receive instances"))
+    {
+        return reject(&mut reader, e).await;
+    }
+
+    let instance_state = app_handle.state::<InstanceState>();
+    let mut instance = instance_state.create_instance(format!(
+        "{} (from {})",
+        header.instance_name, header.sender_name
+    ))?;
+    write_line(
+        reader.get_mut(),
+        &SyncReply {
+            accepted: true,
+            error: None,
+        },
+    )
+    .await?;
+
+    let result = async {
+        for entry in &header.files {
+            let target = instance.game_dir.join(&entry.path);
+            if let Some(parent) = target.parent() {
+                tokio::fs::create_dir_all(parent)
+                    .await
+                    .map_err(|e| e.to_string())?;
+            }
+            let mut file = tokio::fs::File::create(&target)
+                .await
+                .map_err(|e| e.to_string())?;
+            let mut limited = (&mut reader).take(entry.size);
+            let copied = tokio::io::copy(&mut limited, &mut file)
+                .await
+                .map_err(|e| e.to_string())?;
+            if copied != entry.size {
+                return Err(format!("Connection closed while receiving {}", entry.path));
+            }
+        }
+        Ok::<(), String>(())
+    }
+    .await;
+
+    if let Err(e) = result {
+        let _ = instance_state.delete_instance(&instance.id, None);
+        return Err(e);
+    }
+
+    let game_dir = instance.game_dir.clone();
+    let files = header.files.clone();
+    let scanned = tokio::task::spawn_blocking(move || scan_received(&game_dir, &files))
+        .await
+        .map_err(|e| e.to_string())
+        .and_then(|r| r);
+    if let Err(e) = scanned {
+        let _ = instance_state.delete_instance(&instance.id, None);
+        return reject(&mut reader, e).await;
+    }
+
+    instance.version_id = header.version_id;
+    instance.mod_loader = header.mod_loader;
+    instance.mod_loader_version = header.mod_loader_version;
+    instance_state.update_instance(instance.clone())?;
+
+    write_line(
+        reader.get_mut(),
+        &SyncReply {
+            accepted: true,
+            error: None,
+        },
+    )
+    .await?;
+    let _ = app_handle.emit("lan-sync-received", &instance);
+    println!(
+        "[LanSync] Received instance {} ({} files)",
+        instance.name,
+        header.files.len()
+    );
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_is_syncable_path() {
+        assert!(is_syncable_path("mods/sodium.jar"));
+        assert!(is_syncable_path("config/sodium/options.json"));
+        assert!(is_syncable_path("options.txt"));
+        assert!(!is_syncable_path("saves/world/level.dat"));
+        assert!(!is_syncable_path("mods/../../evil.sh"));
+        assert!(!is_syncable_path("/etc/passwd"));
+        assert!(!is_syncable_path("mods"));
+    }
+
+    #[test]
+    fn test_validate_header() {
+        let mut header = SyncHeader {
+            protocol: PROTOCOL_VERSION,
+            pairing_code: "123456".to_string(),
+            sender_name: "peer".to_string(),
+            instance_name: "Pack".to_string(),
+            version_id: None,
+            mod_loader: None,
+            mod_loader_version: None,
+            files: vec![SyncFileEntry {
+                path: "mods/a.jar".to_string(),
+                size: 10,
+            }],
+        };
+        assert!(validate_header(&header).is_ok());
+        header.protocol = PROTOCOL_VERSION + 1;
+        assert!(validate_header(&header).is_err());
+        header.protocol = PROTOCOL_VERSION;
+        header.files[0].path = "versions/evil.json".to_string();
+        assert!(validate_header(&header).is_err());
+    }
+
+    fn wrong_code(state: &LanSyncState) -> &'static str {
+        if state.pairing_code() == "000000" {
+            "000001"
+        } else {
+            "000000"
+        }
+    }
+
+    #[test]
+    fn test_pairing_code_lockout() {
+        let state = LanSyncState::new();
+        let peer: IpAddr = "192.168.1.20".parse().unwrap();
+        assert!(check_pairing_code(&state, peer, &state.pairing_code()).is_ok());
+        let code = state.pairing_code();
+        for _ in 0..MAX_CODE_ATTEMPTS {
+            assert_eq!(
+                check_pairing_code(&state, peer, wrong_code(&state)).unwrap_err(),
+                "Wrong pairing code"
+            );
+        }
+        // Locked out: even the right code is refused now, and the code changed
+        assert!(check_pairing_code(&state, peer, &state.pairing_code()).is_err());
+        assert_ne!(state.pairing_code(), code);
+        let other: IpAddr = "192.168.1.21".parse().unwrap();
+        assert!(check_pairing_code(&state, other, &state.pairing_code()).is_ok());
+    }
+
+    #[test]
+    fn test_pairing_code_rotates_after_use() {
+        let state = LanSyncState::new();
+        let peer: IpAddr = "192.168.1.20".parse().unwrap();
+        let code = state.pairing_code();
+        assert!(check_pairing_code(&state, peer, &code).is_ok());
+        assert_ne!(state.pairing_code(), code);
+        assert!(check_pairing_code(&state, peer, &code).is_err());
+    }
+
+    #[test]
+    fn test_global_attempt_limit() {
+        let state = LanSyncState::new();
+        // Spread the guesses so no single peer reaches its own limit
+        for i in 0..MAX_GLOBAL_CODE_ATTEMPTS {
+            let peer = IpAddr::from([10, 0, (i / 4) as u8, (i % 4) as u8]);
+            assert!(check_pairing_code(&state, peer, wrong_code(&state)).is_err());
+        }
+        let fresh: IpAddr = "192.168.1.30".parse().unwrap();
+        assert!(check_pairing_code(&state, fresh, &state.pairing_code()).is_err());
+    }
+
+    #[test]
+    fn test_scan_received() {
+        use std::io::Write;
+
+        let dir = std::env::temp_dir().join(format!("dropout-lan-{}", uuid::Uuid::new_v4()));
+        std::fs::create_dir_all(dir.join("mods")).unwrap();
+        let mut writer = zip::ZipWriter::new(std::io::Cursor::new(Vec::new()));
+        writer
+            .start_file(
+                "dev/neko/nekoclient/Client.class",
+                zip::write::SimpleFileOptions::default(),
+            )
+            .unwrap();
+        writer.write_all(b"").unwrap();
+        let jar = writer.finish().unwrap().into_inner();
+        std::fs::write(dir.join("mods/bad.jar"), &jar).unwrap();
+        std::fs::write(dir.join("options.txt"), b"fov:1.0").unwrap();
+
+        let entry = |path: &str| SyncFileEntry {
+            path: path.to_string(),
+            size: 0,
+        };
+        assert!(scan_received(&dir, &[entry("options.txt")]).is_ok());
+        let err = scan_received(&dir, &[entry("options.txt"), entry("mods/bad.jar")]).unwrap_err();
+        assert!(err.contains("mods/bad.jar"));
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
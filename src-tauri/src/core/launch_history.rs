@@ -0,0 +1,146 @@
+//! Per-instance history of game launches, stored in
+//! `<app_data>/launch_history/<instance_id>.json` and capped at
+//! [`MAX_ENTRIES`]. Arguments are stored with credentials masked.
+
+use serde::{Deserialize, Serialize};
+use std::path::PathBuf;
+use std::sync::Mutex;
+use tauri::{AppHandle, Manager};
+use ts_rs::TS;
+
+pub const MAX_ENTRIES: usize = 50;
+
+/// Serializes read-modify-write of the history files
+static HISTORY_LOCK: Mutex<()> = Mutex::new(());
+
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export, export_to = "launch_history.ts")]
+pub struct LaunchRecord {
+    pub id: String,
+    pub timestamp: i64,
+    pub version_id: String,
+    pub java_path: String,
+    pub java_major: u32,
+    pub args: Vec<String>, // masked
+    pub exit_code: Option<i32>,
+    pub duration_ms: Option<u64>, // set when the game exits
+    #[serde(default)]
+    pub memory_pressure: bool, // system memory ran low while the game was running
+}
+
+/// Append a record, dropping the oldest beyond [`MAX_ENTRIES`]
+pub fn push_capped(records: &mut Vec<LaunchRecord>, record: LaunchRecord) {
+    records.push(record);
+    if records.len() > MAX_ENTRIES {
+        let excess = records.len() - MAX_ENTRIES;
+        records.drain(..excess);
+    }
+}
+
+fn history_path(app_handle: &AppHandle, instance_id: &str) -> Result<PathBuf, String> {
+    Ok(app_handle
+        .path()
+        .app_data_dir()
+        .map_err(|e| e.to_string())?
+        .join("launch_history")
+        .join(format!("{}.json", instance_id)))
+}
+
+fn read(path: &std::path::Path) -> Vec<LaunchRecord> {
+    std::fs::read_to_string(path)
+        .ok()
+        .and_then(|content| serde_json::from_str(&content).ok())
+        .unwrap_or_default()
+}
+
+fn update(
+    app_handle: &AppHandle,
+    instance_id: &str,
+    f: impl FnOnce(&mut Vec<LaunchRecord>),
+) -> Result<(), String> {
+    let _guard = HISTORY_LOCK.lock().unwrap();
+    let path = history_path(app_handle, instance_id)?;
+    let mut records = read(&path);
+    f(&mut records);
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent).map_err(|e| e.to_string())?;
+    }
+    let content = serde_json::to_string_pretty(&records).map_err(|e| e.to_string())?;
+    std::fs::write(&path, content).map_err(|e| e.to_string())
+}
+
+pub fn record_launch(
+    app_handle: &AppHandle,
+    instance_id: &str,
+    record: LaunchRecord,
+) -> Result<(), String> {
+    update(app_handle, instance_id, |records| {
+        push_capped(records, record)
+    })
+}
+
+pub fn record_exit(
+    app_handle: &AppHandle,
+    instance_id: &str,
+    launch_id: &str,
+    exit_code: Option<i32>,
+    duration_ms: u64,
+) -> Result<(), String> {
+    update(app_handle, instance_id, |records| {
+        if let Some(record) = records.iter_mut().find(|r| r.id == launch_id) {
+            record.exit_code = exit_code;
+            record.duration_ms = Some(duration_ms);
+        }
+    })
+}
+
+pub fn record_memory_pressure(
+    app_handle: &AppHandle,
+    instance_id: &str,
+    launch_id: &str,
+) -> Result<(), String> {
+    update(app_handle, instance_id, |records| {
+        if let Some(record) = records.iter_mut().find(|r| r.id == launch_id) {
+            record.memory_pressure = true;
+        }
+    })
+}
+
+/// Launch history of an instance, newest first
+pub fn get_history(app_handle: &AppHandle, instance_id: &str) -> Result<Vec<LaunchRecord>, String> {
+    let _guard = HISTORY_LOCK.lock().unwrap();
+    let mut records = read(&history_path(app_handle, instance_id)?);
+    records.reverse();
+    Ok(records)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn record(id: usize) -> LaunchRecord {
+        LaunchRecord {
+            id: id.to_string(),
+            timestamp: id as i64,
+            version_id: "1.20.4".to_string(),
+            java_path: "java".to_string(),
+            java_major: 17,
+            args: Vec::new(),
+            exit_code: None,
+            duration_ms: None,
+            memory_pressure: false,
+        }
+    }
+
+    #[test]
+    fn test_push_capped_drops_oldest() {
+        let mut records = Vec::new();
+        for i in 0..MAX_ENTRIES + 3 {
+            push_capped(&mut records, record(i));
+        }
+        assert_eq!(records.len(), MAX_ENTRIES);
+        assert_eq!(records[0].id, "3");
+        assert_eq!(records.last().unwrap().id, (MAX_ENTRIES + 2).to_string());
+    }
+}
@@ -0,0 +1,446 @@
+//! Opt-in HTTP/WebSocket API on localhost for stream overlays and scripts.
+//!
+//! Disabled by default. When `local_api_enabled` is set the launcher listens
+//! on `127.0.0.1:<local_api_port>` and every request must carry the token
+//! from `local_api_token`, either as `Authorization: Bearer <token>` or as a
+//! `?token=` query parameter (browsers can't set headers on WebSockets).
+//!
+//! Endpoints:
+//! - `GET /api/v1/instances`: instances (id, name, version, loader)
+//! - `GET /api/v1/games`: games started by this launcher that are running
+//! - `GET /api/v1/progress`: the latest download progress event
+//! - `POST /api/v1/instances/<id>/launch`: launch an instance
+//! - `GET /api/v1/events`: WebSocket pushing `{event, payload}` messages for
+//!   [`FORWARDED_EVENTS`]
+
+use futures::future::BoxFuture;
+use serde::{Deserialize, Serialize};
+use sha1::{Digest, Sha1};
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use tauri::{AppHandle, Listener, Manager};
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::{TcpListener, TcpStream};
+use tokio::sync::broadcast;
+use ts_rs::TS;
+
+use super::instance::InstanceState;
+
+/// Launcher events pushed to WebSocket clients
+pub const FORWARDED_EVENTS: &[&str] = &[
+    "download-start",
+    "download-progress",
+    "download-complete",
+    "game-launched",
+    "game-exited",
+    "schedule-run",
+];
+
+const MAX_REQUEST_HEAD: usize = 8 * 1024;
+const WEBSOCKET_GUID: &str = "258EAFA5-E914-47DA-95CA-C5AB0DC85B11";
+
+/// Launches an instance by id (wraps the `start_game` command)
+pub type Launcher =
+    Arc<dyn Fn(AppHandle, String) -> BoxFuture<'static, Result<String, String>> + Send + Sync>;
+
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export, export_to = "local_api.ts")]
+pub struct RunningGame {
+    pub launch_id: String,
+    pub instance_id: String,
+    pub version_id: String,
+    pub pid: Option<u32>,
+    pub started_at: i64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export, export_to = "local_api.ts")]
+pub struct LocalApiInfo {
+    pub running: bool,
+    pub port: Option<u16>,
+}
+
+/// Runtime state of the local API (managed by Tauri). Running games are
+/// tracked even while the API is off so enabling it later shows them.
+pub struct LocalApiState {
+    running_games: Mutex<HashMap<String, RunningGame>>,
+    last_progress: Arc<Mutex<Option<serde_json::Value>>>,
+    events: broadcast::Sender<String>,
+    listener: Mutex<Option<(u16, tauri::async_runtime::JoinHandle<()>)>>,
+}
+
+impl Default for LocalApiState {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl LocalApiState {
+    pub fn new() -> Self {
+        Self {
+            running_games: Mutex::new(HashMap::new()),
+            last_progress: Arc::new(Mutex::new(None)),
+            events: broadcast::channel(256).0,
+            listener: Mutex::new(None),
+        }
+    }
+
+    pub fn info(&self) -> LocalApiInfo {
+        let port = self.listener.lock().unwrap().as_ref().map(|(p, _)| *p);
+        LocalApiInfo {
+            running: port.is_some(),
+            port,
+        }
+    }
+}
+
+pub fn game_started(app_handle: &AppHandle, game: RunningGame) {
+    if let Some(state) = app_handle.try_state::<LocalApiState>() {
+        state
+            .running_games
+            .lock()
+            .unwrap()
+            .insert(game.launch_id.clone(), game);
+    }
+}
+
+pub fn game_exited(app_handle: &AppHandle, launch_id: &str) {
+    if let Some(state) = app_handle.try_state::<LocalApiState>() {
+        state.running_games.lock().unwrap().remove(launch_id);
+    }
+}
+
+/// Whether any game launched by this launcher is still running
+pub fn any_game_running(app_handle: &AppHandle) -> bool {
+    app_handle
+        .try_state::<LocalApiState>()
+        .is_some_and(|state| !state.running_games.lock().unwrap().is_empty())
+}
+
+/// Games launched by this launcher that are still running
+pub fn running_games(app_handle: &AppHandle) -> Vec<RunningGame> {
+    app_handle
+        .try_state::<LocalApiState>()
+        .map(|state| {
+            state
+                .running_games
+                .lock()
+                .unwrap()
+                .values()
+                .cloned()
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+/// A parsed HTTP request head
+#[derive(Debug, Default)]
+pub struct Request {
+    pub method: String,
+    pub path: String,
+    pub query: HashMap<String, String>,
+    pub headers: HashMap<String, String>, // lower-case names
+}
+
+pub fn parse_request(head: &str) -> Option<Request> {
+    let mut lines = head.split("\r\n");
+    let mut request_line = lines.next()?.split_whitespace();
+    let method = request_line.next()?.to_string();
+    let target = request_line.next()?;
+    let (path, query) = target.split_once('?').unwrap_or((target, ""));
+    let query = serde_urlencoded::from_str(query).unwrap_or_default();
+    let headers = lines
+        .filter_map(|line| line.split_once(':'))
+        .map(|(name, value)| (name.trim().to_ascii_lowercase(), value.trim().to_string()))
+        .collect();
+    Some(Request {
+        method,
+        path: path.to_string(),
+        query,
+        headers,
+    })
+}
+
+/// Whether the request carries `token`, compared in constant time
+pub fn is_authorized(request: &Request, token: &str) -> bool {
+    let given = request
+        .headers
+        .get("authorization")
+        .and_then(|h| h.strip_prefix("Bearer "))
+        .or_else(|| request.query.get("token").map(String::as_str))
+        .unwrap_or("");
+    !token.is_empty() && super::attempts::constant_time_eq(given, token)
+}
+
+/// `Sec-WebSocket-Accept` value for a client key
+pub fn websocket_accept(key: &str) -> String {
+    use base64::Engine;
+    let mut hasher = Sha1::new();
+    hasher.update(key.as_bytes());
+    hasher.update(WEBSOCKET_GUID.as_bytes());
+    base64::engine::general_purpose::STANDARD.encode(hasher.finalize())
+}
+
+/// Unmasked server-to-client text frame
+pub fn websocket_text_frame(text: &str) -> Vec<u8> {
+    let payload = text.as_bytes();
+    let mut frame = vec![0x81];
+    match payload.len() {
+        len if len < 126 => frame.push(len as u8),
+        len if len <= u16::MAX as usize => {
+            frame.push(126);
+            frame.extend_from_slice(&(len as u16).to_be_bytes());
+        }
+        len => {
+            frame.push(127);
+            frame.extend_from_slice(&(len as u64).to_be_bytes());
+        }
+    }
+    frame.extend_from_slice(payload);
+    frame
+}
+
+fn response(status: &str, body: &serde_json::Value) -> Vec<u8> {
+    let body = body.to_string();
+    format!(
+        "HTTP/1.1 {}\r\nContent-Type: application/json\r\nContent-Length: {}\r\nAccess-Control-Allow-Origin: *\r\nAccess-Control-Allow-Headers: Authorization\r\nConnection: close\r\n\r\n{}",
+        status,
+        body.len(),
+        body
+    )
+    .into_bytes()
+}
+
+fn error(status: &str, message: &str) -> Vec<u8> {
+    response(status, &serde_json::json!({ "error": message }))
+}
+
+async fn read_head(stream: &mut TcpStream) -> Result<String, String> {
+    let mut buf = Vec::new();
+    let mut chunk = [0u8; 1024];
+    while !buf.windows(4).any(|w| w == b"\r\n\r\n") {
+        if buf.len() > MAX_REQUEST_HEAD {
+            return Err("Request head too large".to_string());
+        }
+        let n = stream.read(&mut chunk).await.map_err(|e| e.to_string())?;
+        if n == 0 {
+            return Err("Connection closed".to_string());
+        }
+        buf.extend_from_slice(&chunk[..n]);
+    }
+    Ok(String::from_utf8_lossy(&buf).into_owned())
+}
+
+async fn route(app_handle: &AppHandle, launcher: &Launcher, request: &Request) -> Vec<u8> {
+    let state = app_handle.state::<LocalApiState>();
+    let segments: Vec<&str> = request.path.trim_matches('/').split('/').collect();
+    match (request.method.as_str(), segments.as_slice()) {
+        ("GET", ["api", "v1", "instances"]) => {
+            let instances: Vec<_> = app_handle
+                .state::<InstanceState>()
+                .list_instances()
+                .into_iter()
+                .map(|i| {
+                    serde_json::json!({
+                        "id": i.id,
+                        "name": i.name,
+                        "versionId": i.version_id,
+                        "modLoader": i.mod_loader,
+                    })
+                })
+                .collect();
+            response("200 OK", &serde_json::json!(instances))
+        }
+        ("GET", ["api", "v1", "games"]) => {
+            let games: Vec<RunningGame> = state
+                .running_games
+                .lock()
+                .unwrap()
+                .values()
+                .cloned()
+                .collect();
+            response("200 OK", &serde_json::json!(games))
+        }
+        ("GET", ["api", "v1", "progress"]) => {
+            let progress = state.last_progress.lock().unwrap().clone();
+            response("200 OK", &serde_json::json!(progress))
+        }
+        ("POST", ["api", "v1", "instances", id, "launch"]) => {
+            match launcher(app_handle.clone(), id.to_string()).await {
+                Ok(message) => response("200 OK", &serde_json::json!({ "message": message })),
+                Err(e) => error("500 Internal Server Error", &e),
+            }
+        }
+        _ => error("404 Not Found", "Unknown endpoint"),
+    }
+}
+
+async fn stream_events(
+    mut stream: TcpStream,
+    request: &Request,
+    mut events: broadcast::Receiver<String>,
+) -> Result<(), String> {
+    let key = request
+        .headers
+        .get("sec-websocket-key")
+        .ok_or("Missing Sec-WebSocket-Key")?;
+    let handshake = format!(
+        "HTTP/1.1 101 Switching Protocols\r\nUpgrade: websocket\r\nConnection: Upgrade\r\nSec-WebSocket-Accept: {}\r\n\r\n",
+        websocket_accept(key)
+    );
+    stream
+        .write_all(handshake.as_bytes())
+        .await
+        .map_err(|e| e.to_string())?;
+
+    let mut incoming = [0u8; 256];
+    loop {
+        tokio::select! {
+            message = events.recv() => match message {
+                Ok(text) => stream
+                    .write_all(&websocket_text_frame(&text))
+                    .await
+                    .map_err(|e| e.to_string())?,
+                Err(broadcast::error::RecvError::Lagged(_)) => continue,
+                Err(broadcast::error::RecvError::Closed) => break,
+            },
+            // Client frames are ignored; a close frame or EOF ends the stream
+            read = stream.read(&mut incoming) => match read {
+                Ok(0) | Err(_) => break,
+                Ok(_) if incoming[0] & 0x0f == 0x8 => break,
+                Ok(_) => {}
+            },
+        }
+    }
+    Ok(())
+}
+
+async fn handle_connection(
+    app_handle: AppHandle,
+    launcher: Launcher,
+    token: String,
+    mut stream: TcpStream,
+) -> Result<(), String> {
+    let head = read_head(&mut stream).await?;
+    let request = parse_request(&head).ok_or("Malformed request")?;
+
+    let reply = if request.method == "OPTIONS" {
+        response("204 No Content", &serde_json::Value::Null)
+    } else if !is_authorized(&request, &token) {
+        error("401 Unauthorized", "Missing or invalid token")
+    } else if request.path == "/api/v1/events" {
+        let events = app_handle.state::<LocalApiState>().events.subscribe();
+        return stream_events(stream, &request, events).await;
+    } else {
+        route(&app_handle, &launcher, &request).await
+    };
+    stream.write_all(&reply).await.map_err(|e| e.to_string())
+}
+
+/// Keep the latest progress and feed [`FORWARDED_EVENTS`] to WebSocket
+/// clients; called once at startup
+pub fn track_events(app_handle: &AppHandle) {
+    let state = app_handle.state::<LocalApiState>();
+    for event in FORWARDED_EVENTS {
+        let sender = state.events.clone();
+        let last_progress = state.last_progress.clone();
+        app_handle.listen_any(*event, move |e| {
+            let payload: serde_json::Value =
+                serde_json::from_str(e.payload()).unwrap_or(serde_json::Value::Null);
+            if *event == "download-progress" {
+                *last_progress.lock().unwrap() = Some(payload.clone());
+            }
+            if sender.receiver_count() > 0 {
+                let message = serde_json::json!({ "event": event, "payload": payload });
+                let _ = sender.send(message.to_string());
+            }
+        });
+    }
+}
+
+/// Start listening on `127.0.0.1:port`
+pub async fn start(
+    app_handle: &AppHandle,
+    port: u16,
+    token: String,
+    launcher: Launcher,
+) -> Result<LocalApiInfo, String> {
+    let state = app_handle.state::<LocalApiState>();
+    if state.listener.lock().unwrap().is_some() {
+        return Ok(state.info());
+    }
+
+    let listener = TcpListener::bind(("127.0.0.1", port))
+        .await
+        .map_err(|e| format!("Failed to open local API port {}: {}", port, e))?;
+
+    let accept_handle = app_handle.clone();
+    let task = tauri::async_runtime::spawn(async move {
+        while let Ok((stream, _)) = listener.accept().await {
+            let handle = accept_handle.clone();
+            let launcher = launcher.clone();
+            let token = token.clone();
+            tauri::async_runtime::spawn(async move {
+                if let Err(e) = handle_connection(handle, launcher, token, stream).await {
+                    eprintln!("[LocalApi] Request failed: {}", e);
+                }
+            });
+        }
+    });
+
+    *state.listener.lock().unwrap() = Some((port, task));
+    println!("[LocalApi] Listening on 127.0.0.1:{}", port);
+    Ok(state.info())
+}
+
+/// Stop accepting requests; open WebSocket streams end with the launcher
+pub fn stop(app_handle: &AppHandle) {
+    let state = app_handle.state::<LocalApiState>();
+    if let Some((_, task)) = state.listener.lock().unwrap().take() {
+        task.abort();
+        println!("[LocalApi] Stopped");
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_request_and_auth() {
+        let request = parse_request(
+            "GET /api/v1/games?token=abc HTTP/1.1\r\nHost: localhost\r\nUpgrade: websocket\r\n\r\n",
+        )
+        .unwrap();
+        assert_eq!(request.method, "GET");
+        assert_eq!(request.path, "/api/v1/games");
+        assert_eq!(request.headers.get("upgrade").unwrap(), "websocket");
+        assert!(is_authorized(&request, "abc"));
+        assert!(!is_authorized(&request, "abd"));
+        assert!(!is_authorized(&request, ""));
+
+        let bearer =
+            parse_request("GET / HTTP/1.1\r\nAuthorization: Bearer secret\r\n\r\n").unwrap();
+        assert!(is_authorized(&bearer, "secret"));
+    }
+
+    #[test]
+    fn test_websocket_accept() {
+        // Example from RFC 6455, section 1.3
+        assert_eq!(
+            websocket_accept("dGhlIHNhbXBsZSBub25jZQ=="),
+            "s3pPLMBiTxaQ9kYGzzhZRbK+xOo="
+        );
+    }
+
+    #[test]
+    fn test_websocket_text_frame() {
+        assert_eq!(websocket_text_frame("hi"), vec![0x81, 2, b'h', b'i']);
+        let long = "x".repeat(300);
+        let frame = websocket_text_frame(&long);
+        assert_eq!(&frame[..4], &[0x81, 126, 1, 44]);
+        assert_eq!(frame.len(), 304);
+    }
+}
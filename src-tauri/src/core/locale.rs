@@ -0,0 +1,234 @@
+//! OS locale detection for launch defaults.
+//!
+//! On Windows with a CJK system locale, Java writes its console output in the
+//! legacy code page (GBK, Shift-JIS, ...) and the game log shown in the
+//! launcher gets mangled; forcing UTF-8 through JVM properties fixes it. The
+//! locale is also used to pick the game language for new instances.
+
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+use ts_rs::TS;
+
+/// JVM properties that make Java emit UTF-8 regardless of the code page
+pub const UTF8_JVM_ARGS: &[&str] = &[
+    "-Dfile.encoding=UTF-8",
+    "-Dstdout.encoding=UTF-8",
+    "-Dstderr.encoding=UTF-8",
+];
+
+/// Languages shipped with the game, used to validate region variants
+const GAME_LANGUAGES: &[&str] = &[
+    "ar_sa", "cs_cz", "da_dk", "de_at", "de_ch", "de_de", "el_gr", "en_au", "en_ca", "en_gb",
+    "en_nz", "en_us", "es_ar", "es_es", "es_mx", "fi_fi", "fr_ca", "fr_fr", "he_il", "hu_hu",
+    "id_id", "it_it", "ja_jp", "ko_kr", "nb_no", "nl_nl", "pl_pl", "pt_br", "pt_pt", "ro_ro",
+    "ru_ru", "sk_sk", "sv_se", "th_th", "tr_tr", "uk_ua", "vi_vn", "zh_cn", "zh_hk", "zh_tw",
+];
+
+/// Region assumed when the locale only names a language
+const DEFAULT_REGIONS: &[(&str, &str)] = &[
+    ("ar", "sa"),
+    ("cs", "cz"),
+    ("da", "dk"),
+    ("de", "de"),
+    ("el", "gr"),
+    ("en", "us"),
+    ("es", "es"),
+    ("fi", "fi"),
+    ("fr", "fr"),
+    ("he", "il"),
+    ("hu", "hu"),
+    ("id", "id"),
+    ("it", "it"),
+    ("ja", "jp"),
+    ("ko", "kr"),
+    ("nb", "no"),
+    ("nl", "nl"),
+    ("pl", "pl"),
+    ("pt", "br"),
+    ("ro", "ro"),
+    ("ru", "ru"),
+    ("sk", "sk"),
+    ("sv", "se"),
+    ("th", "th"),
+    ("tr", "tr"),
+    ("uk", "ua"),
+    ("vi", "vn"),
+    ("zh", "cn"),
+];
+
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export, export_to = "locale.ts")]
+pub struct LocaleInfo {
+    pub system_locale: Option<String>,
+    pub game_language: Option<String>,
+    pub is_cjk: bool,
+    pub utf8_args_recommended: bool,
+}
+
+pub fn system_locale() -> Option<String> {
+    sys_locale::get_locale()
+}
+
+/// Map an OS locale ("zh-CN", "zh_TW.UTF-8", "zh-Hant-HK", "ja") to a game
+/// language code ("zh_cn", "zh_tw", "zh_hk", "ja_jp")
+pub fn game_language(locale: &str) -> Option<String> {
+    let locale = locale.split(['.', '@']).next()?;
+    let parts: Vec<String> = locale.split(['-', '_']).map(|p| p.to_lowercase()).collect();
+    let language = parts.first().filter(|l| !l.is_empty())?.as_str();
+    let script = parts
+        .iter()
+        .skip(1)
+        .find(|p| p.len() == 4)
+        .map(String::as_str);
+    let region = parts
+        .iter()
+        .skip(1)
+        .find(|p| p.len() == 2)
+        .map(String::as_str);
+
+    let region = match (language, script, region) {
+        // Traditional Chinese without a region is most likely Taiwan
+        ("zh", Some("hant"), None) => Some("tw"),
+        ("zh", Some("hans"), _) => Some("cn"),
+        ("zh", _, Some("mo")) => Some("hk"),
+        ("zh", _, Some("sg")) => Some("cn"),
+        _ => region,
+    };
+
+    if let Some(region) = region {
+        let code = format!("{}_{}", language, region);
+        if GAME_LANGUAGES.contains(&code.as_str()) {
+            return Some(code);
+        }
+    }
+    DEFAULT_REGIONS
+        .iter()
+        .find(|(lang, _)| *lang == language)
+        .map(|(lang, region)| format!("{}_{}", lang, region))
+}
+
+/// Whether `code` looks like a Minecraft language code ("de_de", "en_US")
+pub fn is_language_code(code: &str) -> bool {
+    match code.split_once('_') {
+        Some((language, region)) => {
+            (2..=3).contains(&language.len())
+                && (2..=3).contains(&region.len())
+                && language.chars().all(|c| c.is_ascii_alphabetic())
+                && region.chars().all(|c| c.is_ascii_alphabetic())
+        }
+        None => false,
+    }
+}
+
+/// Versions before 1.11 spell language codes with an upper-case region
+pub fn format_for_version(code: &str, minecraft_version: &str) -> String {
+    let mut parts = minecraft_version.split('.');
+    let legacy = parts.next() == Some("1")
+        && parts
+            .next()
+            .and_then(|minor| minor.parse::<u32>().ok())
+            .is_some_and(|minor| minor < 11);
+    match code.split_once('_') {
+        Some((language, region)) if legacy => {
+            format!("{}_{}", language, region.to_uppercase())
+        }
+        _ => code.to_string(),
+    }
+}
+
+pub fn is_cjk(locale: &str) -> bool {
+    let language = locale
+        .split(['-', '_', '.'])
+        .next()
+        .unwrap_or("")
+        .to_lowercase();
+    matches!(language.as_str(), "zh" | "ja" | "ko")
+}
+
+/// Whether the UTF-8 JVM properties should be added for `mode`
+/// ("auto", "always" or "never")
+pub fn should_force_utf8(mode: &str, locale: Option<&str>) -> bool {
+    match mode {
+        "always" => true,
+        "never" => false,
+        _ => cfg!(target_os = "windows") && locale.is_some_and(is_cjk),
+    }
+}
+
+pub fn locale_info(utf8_mode: &str) -> LocaleInfo {
+    let locale = system_locale();
+    LocaleInfo {
+        game_language: locale.as_deref().and_then(game_language),
+        is_cjk: locale.as_deref().is_some_and(is_cjk),
+        utf8_args_recommended: should_force_utf8(utf8_mode, locale.as_deref()),
+        system_locale: locale,
+    }
+}
+
+/// Write the language into `options.txt` if the instance has never been
+/// launched (the game creates the file on first start). Returns whether the
+/// file was written.
+pub fn preset_game_language(
+    game_dir: &Path,
+    language: &str,
+    minecraft_version: &str,
+) -> Result<bool, String> {
+    let options = game_dir.join("options.txt");
+    if options.exists() {
+        return Ok(false);
+    }
+    std::fs::write(
+        &options,
+        format!("lang:{}\n", format_for_version(language, minecraft_version)),
+    )
+    .map_err(|e| format!("Failed to write options.txt: {}", e))?;
+    Ok(true)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_game_language() {
+        assert_eq!(game_language("zh-CN").as_deref(), Some("zh_cn"));
+        assert_eq!(game_language("zh_TW.UTF-8").as_deref(), Some("zh_tw"));
+        assert_eq!(game_language("zh-Hant").as_deref(), Some("zh_tw"));
+        assert_eq!(game_language("zh-Hans-SG").as_deref(), Some("zh_cn"));
+        assert_eq!(game_language("ja").as_deref(), Some("ja_jp"));
+        assert_eq!(game_language("en-IN").as_deref(), Some("en_us"));
+        assert_eq!(game_language("pt-PT").as_deref(), Some("pt_pt"));
+        assert_eq!(game_language("xx-YY"), None);
+        assert_eq!(game_language("C"), None);
+    }
+
+    #[test]
+    fn test_format_for_version() {
+        assert_eq!(format_for_version("zh_cn", "1.20.1"), "zh_cn");
+        assert_eq!(format_for_version("zh_cn", "1.11"), "zh_cn");
+        assert_eq!(format_for_version("zh_cn", "1.8.9"), "zh_CN");
+        assert_eq!(format_for_version("zh_cn", "24w10a"), "zh_cn");
+    }
+
+    #[test]
+    fn test_is_language_code() {
+        assert!(is_language_code("de_de"));
+        assert!(is_language_code("en_US"));
+        assert!(is_language_code("lol_us"));
+        assert!(!is_language_code("en"));
+        assert!(!is_language_code("../x_y"));
+        assert!(!is_language_code("en_us_extra"));
+    }
+
+    #[test]
+    fn test_should_force_utf8() {
+        assert!(should_force_utf8("always", None));
+        assert!(!should_force_utf8("never", Some("zh-CN")));
+        assert!(!should_force_utf8("auto", Some("en-US")));
+        assert_eq!(
+            should_force_utf8("auto", Some("zh-CN")),
+            cfg!(target_os = "windows")
+        );
+    }
+}
@@ -0,0 +1,103 @@
+//! Batching of launcher log lines.
+//!
+//! Install and launch phases log hundreds of lines; sending each one over IPC
+//! floods the bridge and re-renders the console per line. Lines are queued
+//! instead and emitted together as a `launcher-log-batch` event at most every
+//! [`FLUSH_INTERVAL`] (or as soon as [`MAX_BATCH`] lines are waiting).
+
+use serde::{Deserialize, Serialize};
+use std::sync::Mutex;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::Duration;
+use tauri::{AppHandle, Emitter};
+use ts_rs::TS;
+
+pub const FLUSH_INTERVAL: Duration = Duration::from_millis(100);
+pub const MAX_BATCH: usize = 500;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, TS)]
+#[serde(rename_all = "lowercase")]
+#[ts(export, export_to = "log_batch.ts")]
+pub enum LogLevel {
+    Info,
+    Warn,
+    Error,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export, export_to = "log_batch.ts")]
+pub struct LogLine {
+    pub level: LogLevel,
+    pub message: String,
+    pub timestamp: i64, // ms since the epoch
+}
+
+static PENDING: Mutex<Vec<LogLine>> = Mutex::new(Vec::new());
+static FLUSH_SCHEDULED: AtomicBool = AtomicBool::new(false);
+
+/// Level of a line from its wording ("Warning: ...", "Failed to ...")
+pub fn level_of(message: &str) -> LogLevel {
+    let lower = message.trim_start().to_lowercase();
+    if lower.starts_with("error") || lower.starts_with("failed") || lower.contains(" error:") {
+        LogLevel::Error
+    } else if lower.starts_with("warning") || lower.starts_with("warn:") {
+        LogLevel::Warn
+    } else {
+        LogLevel::Info
+    }
+}
+
+/// Emit the queued lines now
+pub fn flush(app_handle: &AppHandle) {
+    let lines = std::mem::take(&mut *PENDING.lock().unwrap());
+    if !lines.is_empty() {
+        let _ = app_handle.emit("launcher-log-batch", lines);
+    }
+}
+
+/// Queue a launcher log line (also printed to stdout)
+pub fn log(app_handle: &AppHandle, message: impl Into<String>) {
+    let message = message.into();
+    println!("[Launcher] {}", message);
+    let line = LogLine {
+        level: level_of(&message),
+        message,
+        timestamp: chrono::Utc::now().timestamp_millis(),
+    };
+    let full = {
+        let mut pending = PENDING.lock().unwrap();
+        pending.push(line);
+        pending.len() >= MAX_BATCH
+    };
+    if full {
+        flush(app_handle);
+    } else if !FLUSH_SCHEDULED.swap(true, Ordering::AcqRel) {
+        let app_handle = app_handle.clone();
+        tauri::async_runtime::spawn(async move {
+            tokio::time::sleep(FLUSH_INTERVAL).await;
+            FLUSH_SCHEDULED.store(false, Ordering::Release);
+            flush(&app_handle);
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_level_of() {
+        assert_eq!(level_of("Warning: natives missing"), LogLevel::Warn);
+        assert_eq!(level_of("Failed to copy assets"), LogLevel::Error);
+        assert_eq!(
+            level_of("Error waiting for game process: x"),
+            LogLevel::Error
+        );
+        assert_eq!(
+            level_of("Install step error: checksum mismatch"),
+            LogLevel::Error
+        );
+        assert_eq!(level_of("Game directory: \"/x\""), LogLevel::Info);
+    }
+}
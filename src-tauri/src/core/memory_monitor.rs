@@ -0,0 +1,218 @@
+//! System memory watch while the game runs.
+//!
+//! Once available memory falls below [`threshold_bytes`] the system starts
+//! swapping and the game stutters or gets killed, so the launcher emits a
+//! `memory-pressure` event (once per episode: it re-arms after memory
+//! recovers) and flags the launch in its history.
+
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::Duration;
+use tauri::{AppHandle, Emitter};
+use ts_rs::TS;
+
+pub const SAMPLE_INTERVAL: Duration = Duration::from_secs(10);
+
+/// Upper bound of the threshold on machines with a lot of memory
+const MAX_THRESHOLD_BYTES: u64 = 1024 * 1024 * 1024;
+
+const MB: u64 = 1024 * 1024;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct MemoryStatus {
+    pub total_bytes: u64,
+    pub available_bytes: u64,
+}
+
+/// Payload of the `memory-pressure` event
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export, export_to = "memory_monitor.ts")]
+pub struct MemoryPressure {
+    pub instance_id: String,
+    pub available_mb: u64,
+    pub total_mb: u64,
+    pub max_memory_mb: u64, // -Xmx of the running game
+    pub suggestion: String,
+}
+
+/// 10% of physical memory, at most 1 GiB
+pub fn threshold_bytes(total_bytes: u64) -> u64 {
+    (total_bytes / 10).min(MAX_THRESHOLD_BYTES)
+}
+
+pub fn is_under_pressure(status: MemoryStatus) -> bool {
+    status.available_bytes < threshold_bytes(status.total_bytes)
+}
+
+/// `MemTotal`/`MemAvailable` of `/proc/meminfo` (values are in kB)
+pub fn parse_meminfo(content: &str) -> Option<MemoryStatus> {
+    let value = |key: &str| {
+        content.lines().find_map(|line| {
+            let rest = line.strip_prefix(key)?.strip_prefix(':')?;
+            rest.split_whitespace().next()?.parse::<u64>().ok()
+        })
+    };
+    Some(MemoryStatus {
+        total_bytes: value("MemTotal")? * 1024,
+        available_bytes: value("MemAvailable")? * 1024,
+    })
+}
+
+/// Free, inactive and speculative pages of `vm_stat`, in bytes
+#[cfg_attr(not(target_os = "macos"), allow(dead_code))]
+pub fn parse_vm_stat(output: &str) -> Option<u64> {
+    let page_size = output
+        .lines()
+        .next()?
+        .split("page size of ")
+        .nth(1)?
+        .split_whitespace()
+        .next()?
+        .parse::<u64>()
+        .ok()?;
+    let pages = |key: &str| {
+        output.lines().find_map(|line| {
+            let rest = line.strip_prefix(key)?.strip_prefix(':')?;
+            rest.trim().trim_end_matches('.').parse::<u64>().ok()
+        })
+    };
+    // vm_stat reports speculative pages separately from the free ones
+    let free = pages("Pages free")?
+        + pages("Pages inactive").unwrap_or(0)
+        + pages("Pages speculative").unwrap_or(0);
+    Some(free * page_size)
+}
+
+#[cfg(target_os = "linux")]
+pub fn system_memory() -> Option<MemoryStatus> {
+    parse_meminfo(&std::fs::read_to_string("/proc/meminfo").ok()?)
+}
+
+#[cfg(target_os = "macos")]
+pub fn system_memory() -> Option<MemoryStatus> {
+    let run = |program: &str, args: &[&str]| {
+        std::process::Command::new(program)
+            .args(args)
+            .output()
+            .ok()
+            .map(|o| String::from_utf8_lossy(&o.stdout).into_owned())
+    };
+    Some(MemoryStatus {
+        total_bytes: run("sysctl", &["-n", "hw.memsize"])?.trim().parse().ok()?,
+        available_bytes: parse_vm_stat(&run("vm_stat", &[])?)?,
+    })
+}
+
+#[cfg(target_os = "windows")]
+pub fn system_memory() -> Option<MemoryStatus> {
+    use windows_sys::Win32::System::SystemInformation::{GlobalMemoryStatusEx, MEMORYSTATUSEX};
+
+    let mut status: MEMORYSTATUSEX = unsafe { std::mem::zeroed() };
+    status.dwLength = std::mem::size_of::<MEMORYSTATUSEX>() as u32;
+    // SAFETY: `status` is a properly sized MEMORYSTATUSEX with dwLength set
+    if unsafe { GlobalMemoryStatusEx(&mut status) } == 0 {
+        return None;
+    }
+    Some(MemoryStatus {
+        total_bytes: status.ullTotalPhys,
+        available_bytes: status.ullAvailPhys,
+    })
+}
+
+#[cfg(not(any(target_os = "linux", target_os = "macos", target_os = "windows")))]
+pub fn system_memory() -> Option<MemoryStatus> {
+    None
+}
+
+pub fn suggestion(status: MemoryStatus, max_memory_mb: u64) -> String {
+    let total_mb = status.total_bytes / MB;
+    // Leave the OS and other apps at least a third of the machine
+    if max_memory_mb > total_mb * 2 / 3 {
+        format!(
+            "Lower the maximum memory (-Xmx, currently {} MB) to about {} MB, or close other applications",
+            max_memory_mb,
+            total_mb / 2
+        )
+    } else {
+        "Close other applications to free up memory".to_string()
+    }
+}
+
+/// Sample system memory until `running` is cleared; `on_pressure` runs at the
+/// start of each low-memory episode
+pub async fn watch(
+    app_handle: AppHandle,
+    instance_id: String,
+    max_memory_mb: u64,
+    running: Arc<AtomicBool>,
+    on_pressure: impl Fn(),
+) {
+    let mut under_pressure = false;
+    while running.load(Ordering::Relaxed) {
+        tokio::time::sleep(SAMPLE_INTERVAL).await;
+        let Some(status) = tokio::task::spawn_blocking(system_memory)
+            .await
+            .ok()
+            .flatten()
+        else {
+            return;
+        };
+        let pressure = is_under_pressure(status);
+        if pressure && !under_pressure && running.load(Ordering::Relaxed) {
+            let _ = app_handle.emit(
+                "memory-pressure",
+                MemoryPressure {
+                    instance_id: instance_id.clone(),
+                    available_mb: status.available_bytes / MB,
+                    total_mb: status.total_bytes / MB,
+                    max_memory_mb,
+                    suggestion: suggestion(status, max_memory_mb),
+                },
+            );
+            on_pressure();
+        }
+        under_pressure = pressure;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_meminfo() {
+        let content = "MemTotal:       16303852 kB\nMemFree:          512000 kB\nMemAvailable:    8151926 kB\n";
+        let status = parse_meminfo(content).unwrap();
+        assert_eq!(status.total_bytes, 16303852 * 1024);
+        assert_eq!(status.available_bytes, 8151926 * 1024);
+        assert!(!is_under_pressure(status));
+        assert!(parse_meminfo("MemTotal: 1 kB\n").is_none());
+    }
+
+    #[test]
+    fn test_parse_vm_stat() {
+        let output = "Mach Virtual Memory Statistics: (page size of 16384 bytes)
+Pages free:                               10000.
+Pages active:                            200000.
+Pages inactive:                           5000.
+Pages speculative:                         1000.
+";
+        assert_eq!(parse_vm_stat(output), Some(16000 * 16384));
+    }
+
+    #[test]
+    fn test_threshold_and_suggestion() {
+        let gb = 1024 * MB;
+        assert_eq!(threshold_bytes(4 * gb), 4 * gb / 10);
+        assert_eq!(threshold_bytes(64 * gb), gb);
+        let low = MemoryStatus {
+            total_bytes: 8 * gb,
+            available_bytes: 300 * MB,
+        };
+        assert!(is_under_pressure(low));
+        assert!(suggestion(low, 7000).contains("4096 MB"));
+        assert!(suggestion(low, 4096).starts_with("Close"));
+    }
+}
@@ -1,15 +1,39 @@
 pub mod account_storage;
 pub mod assistant;
-pub mod auth;
+pub mod attempts;
 pub mod config;
+pub mod crash;
+pub mod deep_link;
+pub mod download_audit;
 pub mod downloader;
-pub mod fabric;
-pub mod forge;
-pub mod game_version;
-pub mod instance;
+pub mod gpu;
+pub mod install;
 pub mod java;
-pub mod manifest;
-pub mod maven;
-pub mod modpack;
-pub mod rules;
-pub mod version_merge;
+pub mod lan_sync;
+pub mod launch_history;
+pub mod local_api;
+pub mod locale;
+pub mod log_batch;
+pub mod memory_monitor;
+pub mod mod_scan;
+pub mod notes;
+pub mod parental;
+pub mod profile;
+pub mod recovery;
+pub mod releases;
+pub mod reset;
+pub mod scheduler;
+pub mod search;
+pub mod shutdown;
+pub mod status;
+pub mod sync;
+pub mod usage_stats;
+pub mod web_seed;
+
+// Frontend-agnostic modules live in the `dropout-core` crate
+pub use dropout_core::{
+    agents, args, asset_index, auth, curseforge, dot_minecraft, download_manager, fabric, forge,
+    game_log, game_version, instance, jvm_defaults, launch_report, log4j, manifest, maven,
+    mod_list, mod_rollback, mod_updates, modpack, modrinth, mods, natives, neoforge, optifine,
+    protocol, rules, safe_path, sandbox, server, skin, version_diff,
+};
@@ -0,0 +1,497 @@
+//! Heuristic malware scan for mod jars.
+//!
+//! Not an antivirus: it looks for the indicators of known Minecraft mod
+//! malware (fractureiser), for classes that load code from remote URLs, and
+//! for signed jars whose contents no longer match their signature digests
+//! (the usual trace of a jar that was patched after release).
+
+use base64::Engine;
+use serde::{Deserialize, Serialize};
+use sha1::Sha1;
+use sha2::{Digest, Sha256};
+use std::collections::HashMap;
+use std::io::{Cursor, Read, Seek};
+use std::path::Path;
+use ts_rs::TS;
+
+/// Stage 0 download servers used by fractureiser
+const KNOWN_MALICIOUS_HOSTS: &[&str] = &["85.217.144.130", "107.189.3.101"];
+
+/// Packages of the fractureiser payload
+const KNOWN_MALICIOUS_CLASSES: &[&str] = &["dev/neko/nekoclient/", "dev/neko/nekoinjector/"];
+
+/// Files dropped by fractureiser stage 1/2, relative to the home directory
+const HOST_INDICATORS: &[&str] = &[
+    ".config/.data/lib.jar",
+    ".config/.data/.ref",
+    ".config/systemd/user/systemd-utility.service",
+    "AppData/Local/Microsoft Edge/libWebGL64.jar",
+    "AppData/Local/Microsoft Edge/lib.dll",
+    "AppData/Local/Microsoft Edge/.ref",
+];
+
+/// Larger entries are skipped rather than read into memory
+const MAX_ENTRY_SIZE: u64 = 64 * 1024 * 1024;
+
+/// Jar-in-jar nesting depth that is still inspected
+const MAX_NESTING: usize = 3;
+
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export, export_to = "mod_scan.ts")]
+pub struct ModScanFinding {
+    pub severity: String, // "critical" | "warning"
+    pub kind: String, // "knownMalware" | "remoteClassLoader" | "hashMismatch" | "unsignedEntry" | "invalidJar" | "unreadable"
+    pub entry: Option<String>,
+    pub message: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export, export_to = "mod_scan.ts")]
+pub struct ModScanResult {
+    pub file_name: String,
+    pub sha1: String,
+    pub signed: bool,
+    pub findings: Vec<ModScanFinding>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export, export_to = "mod_scan.ts")]
+pub struct ModScanReport {
+    pub instance_id: String,
+    pub scanned: usize,
+    pub flagged: usize,
+    pub critical: bool,
+    pub host_indicators: Vec<String>,
+    pub mods: Vec<ModScanResult>,
+}
+
+fn finding(severity: &str, kind: &str, entry: Option<&str>, message: String) -> ModScanFinding {
+    ModScanFinding {
+        severity: severity.to_string(),
+        kind: kind.to_string(),
+        entry: entry.map(str::to_string),
+        message,
+    }
+}
+
+fn contains(haystack: &[u8], needle: &[u8]) -> bool {
+    !needle.is_empty() && haystack.windows(needle.len()).any(|w| w == needle)
+}
+
+/// Bytes pushed with `bipush <b>; bastore`, i.e. a string built from a byte
+/// array literal. Obfuscated loaders hide their URLs this way.
+pub fn byte_array_literals(class: &[u8]) -> Vec<u8> {
+    const BIPUSH: u8 = 0x10;
+    const BASTORE: u8 = 0x54;
+    class
+        .windows(3)
+        .filter(|w| w[0] == BIPUSH && w[2] == BASTORE)
+        .map(|w| w[1])
+        .collect()
+}
+
+/// Heuristics for a single `.class` entry
+pub fn scan_class(entry: &str, class: &[u8]) -> Vec<ModScanFinding> {
+    let mut findings = Vec::new();
+
+    // Entries of nested jars are labelled `outer.jar!/pkg/Class.class`
+    let class_name = entry.rsplit("!/").next().unwrap_or(entry);
+    if let Some(package) = KNOWN_MALICIOUS_CLASSES
+        .iter()
+        .find(|p| class_name.starts_with(*p))
+    {
+        findings.push(finding(
+            "critical",
+            "knownMalware",
+            Some(entry),
+            format!("Contains fractureiser payload class ({})", package),
+        ));
+    }
+
+    let literals = byte_array_literals(class);
+    for host in KNOWN_MALICIOUS_HOSTS {
+        if contains(class, host.as_bytes()) || contains(&literals, host.as_bytes()) {
+            findings.push(finding(
+                "critical",
+                "knownMalware",
+                Some(entry),
+                format!("References fractureiser server {}", host),
+            ));
+        }
+    }
+
+    // A class loader fed with a hard-coded remote URL downloads and runs code
+    let remote_url =
+        contains(class, b"http://") || contains(class, b"https://") || contains(&literals, b"http");
+    if contains(class, b"java/net/URLClassLoader") && contains(class, b"loadClass") && remote_url {
+        findings.push(finding(
+            "warning",
+            "remoteClassLoader",
+            Some(entry),
+            "Loads classes through a URLClassLoader with a hard-coded remote URL".to_string(),
+        ));
+    }
+
+    findings
+}
+
+/// Per-entry digests from a jar manifest: entry name -> (algorithm, base64)
+pub fn parse_manifest_digests(manifest: &str) -> HashMap<String, (String, String)> {
+    // Long lines are wrapped with a leading space on the continuation
+    let mut lines: Vec<String> = Vec::new();
+    for line in manifest.lines() {
+        match line.strip_prefix(' ') {
+            Some(rest) if !lines.is_empty() => lines.last_mut().unwrap().push_str(rest),
+            _ => lines.push(line.to_string()),
+        }
+    }
+
+    let mut digests = HashMap::new();
+    let mut name: Option<String> = None;
+    for line in lines {
+        if line.is_empty() {
+            name = None;
+        } else if let Some(value) = line.strip_prefix("Name: ") {
+            name = Some(value.to_string());
+        } else if let (Some(entry), Some((key, value))) = (&name, line.split_once(": ")) {
+            let algorithm = match key {
+                "SHA-256-Digest" => "SHA-256",
+                "SHA1-Digest" | "SHA-1-Digest" => "SHA-1",
+                _ => continue,
+            };
+            // Prefer SHA-256 when both are listed
+            if algorithm == "SHA-256" || !digests.contains_key(entry) {
+                digests.insert(
+                    entry.clone(),
+                    (algorithm.to_string(), value.trim().to_string()),
+                );
+            }
+        }
+    }
+    digests
+}
+
+fn digest_base64(algorithm: &str, data: &[u8]) -> String {
+    let engine = base64::engine::general_purpose::STANDARD;
+    match algorithm {
+        "SHA-256" => engine.encode(Sha256::digest(data)),
+        _ => engine.encode(Sha1::digest(data)),
+    }
+}
+
+fn is_signature_file(name: &str) -> bool {
+    let upper = name.to_uppercase();
+    upper.starts_with("META-INF/")
+        && [".SF", ".RSA", ".DSA", ".EC"]
+            .iter()
+            .any(|ext| upper.ends_with(ext))
+}
+
+/// Scan an in-memory or on-disk jar. Returns (signed, findings).
+pub fn scan_archive<R: Read + Seek>(
+    reader: R,
+    label: &str,
+    depth: usize,
+) -> (bool, Vec<ModScanFinding>) {
+    let mut archive = match zip::ZipArchive::new(reader) {
+        Ok(archive) => archive,
+        Err(e) => {
+            return (
+                false,
+                vec![finding(
+                    "warning",
+                    "invalidJar",
+                    Some(label),
+                    format!("Not a valid jar: {}", e),
+                )],
+            );
+        }
+    };
+
+    let signed = archive.file_names().any(is_signature_file);
+    let digests = if signed {
+        let mut manifest = String::new();
+        archive
+            .by_name("META-INF/MANIFEST.MF")
+            .ok()
+            .and_then(|mut f| f.read_to_string(&mut manifest).ok());
+        parse_manifest_digests(&manifest)
+    } else {
+        HashMap::new()
+    };
+
+    let mut findings = Vec::new();
+    for index in 0..archive.len() {
+        let mut file = match archive.by_index(index) {
+            Ok(file) => file,
+            Err(_) => continue,
+        };
+        let name = file.name().to_string();
+        if file.is_dir() || file.size() > MAX_ENTRY_SIZE {
+            continue;
+        }
+        let is_class = name.ends_with(".class");
+        let is_nested_jar = name.ends_with(".jar") && depth < MAX_NESTING;
+        let expected = digests.get(&name);
+        if !is_class && !is_nested_jar && expected.is_none() {
+            continue;
+        }
+
+        let mut data = Vec::with_capacity(file.size() as usize);
+        if file.read_to_end(&mut data).is_err() {
+            findings.push(finding(
+                "warning",
+                "invalidJar",
+                Some(&name),
+                "Entry could not be read".to_string(),
+            ));
+            continue;
+        }
+        let display = if depth == 0 {
+            name.clone()
+        } else {
+            format!("{}!/{}", label, name)
+        };
+
+        if let Some((algorithm, digest)) = expected {
+            if &digest_base64(algorithm, &data) != digest {
+                findings.push(finding(
+                    "critical",
+                    "hashMismatch",
+                    Some(&display),
+                    format!("{} digest does not match the jar signature", algorithm),
+                ));
+            }
+        } else if signed && is_class {
+            findings.push(finding(
+                "warning",
+                "unsignedEntry",
+                Some(&display),
+                "Class was added to a signed jar".to_string(),
+            ));
+        }
+
+        if is_class {
+            findings.extend(scan_class(&display, &data));
+        } else if is_nested_jar {
+            let (_, nested) = scan_archive(Cursor::new(data), &display, depth + 1);
+            findings.extend(nested);
+        }
+    }
+
+    (signed, findings)
+}
+
+pub fn scan_jar(path: &Path) -> Result<ModScanResult, String> {
+    let data =
+        std::fs::read(path).map_err(|e| format!("Failed to read {}: {}", path.display(), e))?;
+    let file_name = path
+        .file_name()
+        .map(|n| n.to_string_lossy().to_string())
+        .unwrap_or_default();
+    let (signed, findings) = scan_archive(Cursor::new(&data), &file_name, 0);
+    Ok(ModScanResult {
+        file_name,
+        sha1: hex::encode(Sha1::digest(&data)),
+        signed,
+        findings,
+    })
+}
+
+/// Whether `path` is a jar going into an instance's `mods` folder
+pub fn is_mod_jar(path: &Path) -> bool {
+    path.extension().is_some_and(|ext| ext == "jar")
+        && path
+            .parent()
+            .and_then(|p| p.file_name())
+            .is_some_and(|dir| dir == "mods")
+}
+
+/// Refuse to install `file_name` from `path` (a jar, or a pack whose
+/// overrides carry jars) if the scan finds anything critical
+pub fn check_before_install(path: &Path, file_name: &str) -> Result<(), String> {
+    let data =
+        std::fs::read(path).map_err(|e| format!("Failed to read {}: {}", path.display(), e))?;
+    let (_, findings) = scan_archive(Cursor::new(&data), file_name, 0);
+    match findings.iter().find(|f| f.severity == "critical") {
+        Some(f) => Err(format!(
+            "{} was blocked by the mod scan: {}",
+            file_name, f.message
+        )),
+        None => Ok(()),
+    }
+}
+
+/// Files left on this machine by a fractureiser infection
+pub fn host_indicators() -> Vec<String> {
+    let mut paths: Vec<std::path::PathBuf> = Vec::new();
+    if let Some(home) = dirs::home_dir() {
+        paths.extend(HOST_INDICATORS.iter().map(|p| home.join(p)));
+    }
+    if cfg!(target_os = "linux") {
+        paths.push("/etc/systemd/system/systemd-utility.service".into());
+    }
+    paths
+        .into_iter()
+        .filter(|p| p.exists())
+        .map(|p| p.to_string_lossy().to_string())
+        .collect()
+}
+
+/// Scan every jar in an instance's `mods` folder (disabled mods included)
+pub fn scan_instance_mods(instance_id: &str, game_dir: &Path) -> Result<ModScanReport, String> {
+    let mods_dir = game_dir.join("mods");
+    let mut mods = Vec::new();
+    if mods_dir.is_dir() {
+        let mut jars: Vec<_> = std::fs::read_dir(&mods_dir)
+            .map_err(|e| e.to_string())?
+            .filter_map(|e| e.ok())
+            .map(|e| e.path())
+            .filter(|p| {
+                let name = p.to_string_lossy();
+                p.is_file() && (name.ends_with(".jar") || name.ends_with(".jar.disabled"))
+            })
+            .collect();
+        jars.sort();
+        for jar in jars {
+            // One unreadable jar shouldn't hide the results for the rest
+            let result = scan_jar(&jar).unwrap_or_else(|e| ModScanResult {
+                file_name: jar
+                    .file_name()
+                    .map(|n| n.to_string_lossy().to_string())
+                    .unwrap_or_default(),
+                sha1: String::new(),
+                signed: false,
+                findings: vec![finding("warning", "unreadable", None, e)],
+            });
+            mods.push(result);
+        }
+    }
+
+    let host_indicators = host_indicators();
+    let critical = !host_indicators.is_empty()
+        || mods
+            .iter()
+            .flat_map(|m| &m.findings)
+            .any(|f| f.severity == "critical");
+    Ok(ModScanReport {
+        instance_id: instance_id.to_string(),
+        scanned: mods.len(),
+        flagged: mods.iter().filter(|m| !m.findings.is_empty()).count(),
+        critical,
+        host_indicators,
+        mods,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Write;
+
+    fn build_jar(entries: &[(&str, &[u8])]) -> Vec<u8> {
+        let mut writer = zip::ZipWriter::new(Cursor::new(Vec::new()));
+        let options = zip::write::SimpleFileOptions::default();
+        for (name, data) in entries {
+            writer.start_file(*name, options).unwrap();
+            writer.write_all(data).unwrap();
+        }
+        writer.finish().unwrap().into_inner()
+    }
+
+    #[test]
+    fn test_byte_array_literal_ioc() {
+        // new byte[]{'8','5','.', ...} compiled to bipush/bastore pairs
+        let mut class = Vec::new();
+        for (i, b) in "85.217.144.130".bytes().enumerate() {
+            class.extend([0x59, 0x10, i as u8, 0x10, b, 0x54]);
+        }
+        let findings = scan_class("a/Utility.class", &class);
+        assert!(findings.iter().any(|f| f.kind == "knownMalware"));
+    }
+
+    #[test]
+    fn test_remote_class_loader() {
+        let class = b"java/net/URLClassLoader loadClass https://example.com/x.jar";
+        let findings = scan_class("a/B.class", class);
+        assert_eq!(findings.len(), 1);
+        assert_eq!(findings[0].kind, "remoteClassLoader");
+        assert!(scan_class("a/B.class", b"java/net/URLClassLoader loadClass").is_empty());
+    }
+
+    #[test]
+    fn test_parse_manifest_digests() {
+        let manifest = "Manifest-Version: 1.0\r\n\r\nName: a/VeryLongClassName\r\n Continued.class\r\nSHA1-Digest: abc=\r\nSHA-256-Digest: def=\r\n\r\nName: b.class\r\nSHA1-Digest: ghi=\r\n";
+        let digests = parse_manifest_digests(manifest);
+        assert_eq!(
+            digests.get("a/VeryLongClassNameContinued.class"),
+            Some(&("SHA-256".to_string(), "def=".to_string()))
+        );
+        assert_eq!(digests.get("b.class").unwrap().1, "ghi=");
+    }
+
+    #[test]
+    fn test_signed_jar_tampering() {
+        let original: &[u8] = b"original class";
+        let manifest = format!(
+            "Manifest-Version: 1.0\n\nName: a/A.class\nSHA-256-Digest: {}\n",
+            digest_base64("SHA-256", original)
+        );
+        let clean = build_jar(&[
+            ("META-INF/MANIFEST.MF", manifest.as_bytes()),
+            ("META-INF/MOD.SF", b""),
+            ("a/A.class", original),
+        ]);
+        let (signed, findings) = scan_archive(Cursor::new(clean), "mod.jar", 0);
+        assert!(signed);
+        assert!(findings.is_empty());
+
+        let tampered = build_jar(&[
+            ("META-INF/MANIFEST.MF", manifest.as_bytes()),
+            ("META-INF/MOD.SF", b""),
+            ("a/A.class", b"patched class"),
+            ("a/Extra.class", b""),
+        ]);
+        let (_, findings) = scan_archive(Cursor::new(tampered), "mod.jar", 0);
+        let kinds: Vec<&str> = findings.iter().map(|f| f.kind.as_str()).collect();
+        assert_eq!(kinds, vec!["hashMismatch", "unsignedEntry"]);
+    }
+
+    #[test]
+    fn test_nested_jar_is_scanned() {
+        let inner = build_jar(&[("dev/neko/nekoclient/Client.class", b"")]);
+        let outer = build_jar(&[("META-INF/jars/lib.jar", &inner)]);
+        let (_, findings) = scan_archive(Cursor::new(outer), "mod.jar", 0);
+        assert_eq!(findings.len(), 1);
+        assert_eq!(
+            findings[0].entry.as_deref(),
+            Some("META-INF/jars/lib.jar!/dev/neko/nekoclient/Client.class")
+        );
+    }
+
+    #[test]
+    fn test_pack_overrides_are_checked() {
+        let dir = std::env::temp_dir().join(format!("dropout-scan-{}", uuid::Uuid::new_v4()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let inner = build_jar(&[("dev/neko/nekoclient/Client.class", b"")]);
+        let pack = dir.join("pack.mrpack");
+        std::fs::write(&pack, build_jar(&[("overrides/mods/bad.jar", &inner)])).unwrap();
+        assert!(check_before_install(&pack, "pack.mrpack").is_err());
+
+        let clean = dir.join("clean.mrpack");
+        std::fs::write(
+            &clean,
+            build_jar(&[("overrides/mods/ok.jar", &build_jar(&[]))]),
+        )
+        .unwrap();
+        assert!(check_before_install(&clean, "clean.mrpack").is_ok());
+        std::fs::remove_dir_all(&dir).unwrap();
+
+        assert!(is_mod_jar(Path::new("game/mods/sodium.jar")));
+        assert!(!is_mod_jar(Path::new("libraries/a/b.jar")));
+        assert!(!is_mod_jar(Path::new("game/resourcepacks/pack.zip")));
+    }
+}
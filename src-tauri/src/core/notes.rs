@@ -0,0 +1,174 @@
+//! Per-instance notes: Markdown in `<game_dir>/NOTES.md` plus image
+//! attachments in `<game_dir>/notes/`, referenced from the Markdown as
+//! `notes/<file>`. Living in the game directory, they travel with copies and
+//! exports of the instance so packs can ship play instructions.
+
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+use ts_rs::TS;
+
+pub const NOTES_FILE: &str = "NOTES.md";
+pub const ATTACHMENTS_DIR: &str = "notes";
+
+const IMAGE_EXTENSIONS: &[&str] = &["png", "jpg", "jpeg", "gif", "webp"];
+const MAX_ATTACHMENT_SIZE: u64 = 10 * 1024 * 1024;
+
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export, export_to = "notes.ts")]
+pub struct NoteAttachment {
+    pub name: String,
+    pub path: String, // absolute, for display
+    pub size: u64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export, export_to = "notes.ts")]
+pub struct InstanceNotes {
+    pub content: String,
+    pub attachments: Vec<NoteAttachment>,
+}
+
+fn is_image(name: &str) -> bool {
+    Path::new(name)
+        .extension()
+        .and_then(|e| e.to_str())
+        .is_some_and(|e| IMAGE_EXTENSIONS.contains(&e.to_ascii_lowercase().as_str()))
+}
+
+/// File name safe to store under `notes/`: no separators, no leading dots
+pub fn sanitize_attachment_name(name: &str) -> Result<String, String> {
+    let name: String = name
+        .trim()
+        .chars()
+        .map(|c| {
+            if c.is_alphanumeric() || matches!(c, '-' | '_' | '.' | ' ') {
+                c
+            } else {
+                '_'
+            }
+        })
+        .collect();
+    let name = name.trim_start_matches('.').trim().to_string();
+    if name.is_empty() || !is_image(&name) {
+        return Err(format!(
+            "Attachments must be images ({})",
+            IMAGE_EXTENSIONS.join(", ")
+        ));
+    }
+    Ok(name)
+}
+
+/// `name`, or `name (2).ext`, ... if that is taken in `dir`
+fn unique_name(dir: &Path, name: &str) -> String {
+    let path = Path::new(name);
+    let stem = path.file_stem().and_then(|s| s.to_str()).unwrap_or(name);
+    let ext = path.extension().and_then(|e| e.to_str()).unwrap_or("");
+    let mut candidate = name.to_string();
+    let mut n = 2;
+    while dir.join(&candidate).exists() {
+        candidate = format!("{} ({}).{}", stem, n, ext);
+        n += 1;
+    }
+    candidate
+}
+
+fn list_attachments(game_dir: &Path) -> Result<Vec<NoteAttachment>, String> {
+    let dir = game_dir.join(ATTACHMENTS_DIR);
+    if !dir.is_dir() {
+        return Ok(Vec::new());
+    }
+    let mut attachments: Vec<NoteAttachment> = std::fs::read_dir(&dir)
+        .map_err(|e| e.to_string())?
+        .filter_map(|e| e.ok())
+        .filter_map(|e| {
+            let name = e.file_name().to_string_lossy().to_string();
+            let meta = e.metadata().ok()?;
+            (meta.is_file() && is_image(&name)).then(|| NoteAttachment {
+                path: e.path().to_string_lossy().to_string(),
+                size: meta.len(),
+                name,
+            })
+        })
+        .collect();
+    attachments.sort_by(|a, b| a.name.cmp(&b.name));
+    Ok(attachments)
+}
+
+/// Notes of the instance in `game_dir`; `legacy` is the old single-string
+/// `Instance.notes`, shown until NOTES.md is first saved
+pub fn read(game_dir: &Path, legacy: Option<&str>) -> Result<InstanceNotes, String> {
+    let content = match std::fs::read_to_string(game_dir.join(NOTES_FILE)) {
+        Ok(content) => content,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => {
+            legacy.unwrap_or_default().to_string()
+        }
+        Err(e) => return Err(e.to_string()),
+    };
+    Ok(InstanceNotes {
+        content,
+        attachments: list_attachments(game_dir)?,
+    })
+}
+
+pub fn write(game_dir: &Path, content: &str) -> Result<(), String> {
+    std::fs::create_dir_all(game_dir).map_err(|e| e.to_string())?;
+    std::fs::write(game_dir.join(NOTES_FILE), content).map_err(|e| e.to_string())
+}
+
+/// Copy an image into the instance's attachments; returns the stored name
+pub fn add_attachment(game_dir: &Path, source: &Path) -> Result<String, String> {
+    let meta = std::fs::metadata(source).map_err(|e| e.to_string())?;
+    if !meta.is_file() {
+        return Err(format!("{} is not a file", source.display()));
+    }
+    if meta.len() > MAX_ATTACHMENT_SIZE {
+        return Err(format!(
+            "Attachments are limited to {} MB",
+            MAX_ATTACHMENT_SIZE / 1024 / 1024
+        ));
+    }
+    let name = sanitize_attachment_name(
+        &source
+            .file_name()
+            .map(|n| n.to_string_lossy().to_string())
+            .unwrap_or_default(),
+    )?;
+    let dir = game_dir.join(ATTACHMENTS_DIR);
+    std::fs::create_dir_all(&dir).map_err(|e| e.to_string())?;
+    let name = unique_name(&dir, &name);
+    std::fs::copy(source, dir.join(&name)).map_err(|e| e.to_string())?;
+    Ok(name)
+}
+
+pub fn remove_attachment(game_dir: &Path, name: &str) -> Result<(), String> {
+    if sanitize_attachment_name(name)? != name {
+        return Err(format!("Invalid attachment name '{}'", name));
+    }
+    let path = game_dir.join(ATTACHMENTS_DIR).join(name);
+    std::fs::remove_file(&path).map_err(|e| e.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_sanitize_attachment_name() {
+        assert_eq!(
+            sanitize_attachment_name("Map overview.PNG").unwrap(),
+            "Map overview.PNG"
+        );
+        assert_eq!(
+            sanitize_attachment_name("../../evil.png").unwrap(),
+            "_.._evil.png"
+        );
+        assert_eq!(
+            sanitize_attachment_name("..hidden.jpg").unwrap(),
+            "hidden.jpg"
+        );
+        assert!(sanitize_attachment_name("script.sh").is_err());
+        assert!(sanitize_attachment_name(".png").is_err());
+    }
+}
@@ -0,0 +1,281 @@
+//! Launcher PIN for shared and family computers.
+//!
+//! With a PIN set, the commands that change accounts, add instances or add
+//! mods refuse to run until the launcher has been unlocked with the PIN
+//! (`unlock_launcher`). The lock is checked in the commands themselves, so a
+//! modified frontend can't get around it. Unlocking lasts until
+//! `lock_launcher` is called or the launcher restarts.
+//!
+//! Five wrong PINs in a row lock unlocking out for a minute, doubling with
+//! every further wrong PIN (see [`AttemptLimiter`]).
+//!
+//! Only an Argon2id hash of the PIN is kept in the config. It never leaves
+//! the backend (`get_settings` / `read_raw_config` leave it out) and
+//! `save_settings` / `save_raw_config` can't change it: the PIN is set and
+//! removed with `set_launcher_pin`, which asks for the current one. Hashes
+//! from older versions (salted SHA-256) still verify and are replaced with
+//! an Argon2id one the next time the launcher is unlocked.
+
+use argon2::Argon2;
+use argon2::password_hash::rand_core::OsRng;
+use argon2::password_hash::{PasswordHash, PasswordHasher, PasswordVerifier, SaltString};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::Duration;
+use tauri::{AppHandle, Manager};
+use ts_rs::TS;
+
+use super::attempts::{AttemptLimiter, constant_time_eq, lockout_message};
+use super::config::ConfigState;
+
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export, export_to = "config.ts")]
+#[serde(default)]
+pub struct ParentalConfig {
+    pub pin_hash: Option<String>, // Argon2id PHC string; None = no lock
+    pub pin_salt: Option<String>, // only set for legacy SHA-256 hashes
+}
+
+impl ParentalConfig {
+    pub fn enabled(&self) -> bool {
+        self.pin_hash.is_some()
+    }
+
+    pub fn verify(&self, pin: &str) -> bool {
+        match (&self.pin_hash, &self.pin_salt) {
+            (Some(hash), _) if hash.starts_with("$argon2") => {
+                PasswordHash::new(hash).is_ok_and(|parsed| {
+                    Argon2::default()
+                        .verify_password(pin.as_bytes(), &parsed)
+                        .is_ok()
+                })
+            }
+            (Some(hash), Some(salt)) => constant_time_eq(&legacy_hash_pin(pin, salt), hash),
+            (Some(_), None) => false,
+            (None, _) => true,
+        }
+    }
+
+    /// Whether the hash is still in the old salted SHA-256 format
+    fn is_legacy(&self) -> bool {
+        self.pin_hash
+            .as_deref()
+            .is_some_and(|hash| !hash.starts_with("$argon2"))
+    }
+
+    fn with_pin(pin: &str) -> Result<Self, String> {
+        Ok(Self {
+            pin_hash: Some(hash_pin(pin)?),
+            pin_salt: None,
+        })
+    }
+}
+
+const MAX_PIN_ATTEMPTS: u32 = 5;
+const PIN_LOCKOUT: Duration = Duration::from_secs(60);
+
+/// Whether this session has been unlocked with the PIN
+pub struct ParentalState {
+    unlocked: AtomicBool,
+    attempts: AttemptLimiter<()>,
+}
+
+impl Default for ParentalState {
+    fn default() -> Self {
+        Self {
+            unlocked: AtomicBool::new(false),
+            attempts: AttemptLimiter::new(MAX_PIN_ATTEMPTS, PIN_LOCKOUT),
+        }
+    }
+}
+
+/// Check `pin`, counting wrong ones towards the lockout
+fn verify_limited(
+    app_handle: &AppHandle,
+    config: &ParentalConfig,
+    pin: &str,
+) -> Result<(), String> {
+    let Some(state) = app_handle.try_state::<ParentalState>() else {
+        return if config.verify(pin) {
+            Ok(())
+        } else {
+            Err("Wrong PIN".to_string())
+        };
+    };
+    state.attempts.check(&()).map_err(lockout_message)?;
+    if config.verify(pin) {
+        state.attempts.record_success(&());
+        Ok(())
+    } else {
+        state.attempts.record_failure(&());
+        Err("Wrong PIN".to_string())
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export, export_to = "parental.ts")]
+pub struct ParentalStatus {
+    pub enabled: bool,  // a PIN is set
+    pub unlocked: bool, // restricted actions are allowed right now
+}
+
+fn hash_pin(pin: &str) -> Result<String, String> {
+    let salt = SaltString::generate(&mut OsRng);
+    Argon2::default()
+        .hash_password(pin.as_bytes(), &salt)
+        .map(|hash| hash.to_string())
+        .map_err(|e| format!("Failed to hash the PIN: {}", e))
+}
+
+fn legacy_hash_pin(pin: &str, salt: &str) -> String {
+    let mut hasher = Sha256::new();
+    hasher.update(salt.as_bytes());
+    hasher.update(pin.as_bytes());
+    hex::encode(hasher.finalize())
+}
+
+fn validate_pin(pin: &str) -> Result<(), String> {
+    if pin.len() < 4 || pin.len() > 32 || pin.chars().any(char::is_whitespace) {
+        return Err("The PIN must be 4 to 32 characters without spaces".to_string());
+    }
+    Ok(())
+}
+
+fn current(app_handle: &AppHandle) -> ParentalConfig {
+    app_handle
+        .try_state::<ConfigState>()
+        .map(|state| state.config.lock().unwrap().parental.clone())
+        .unwrap_or_default()
+}
+
+fn is_unlocked(app_handle: &AppHandle) -> bool {
+    app_handle
+        .try_state::<ParentalState>()
+        .is_some_and(|state| state.unlocked.load(Ordering::Acquire))
+}
+
+pub fn status(app_handle: &AppHandle) -> ParentalStatus {
+    let enabled = current(app_handle).enabled();
+    ParentalStatus {
+        enabled,
+        unlocked: !enabled || is_unlocked(app_handle),
+    }
+}
+
+/// Fail with a message naming `action` unless no PIN is set or the launcher
+/// has been unlocked
+pub fn require_unlocked(app_handle: &AppHandle, action: &str) -> Result<(), String> {
+    if status(app_handle).unlocked {
+        Ok(())
+    } else {
+        Err(format!("Enter the launcher PIN to {}", action))
+    }
+}
+
+pub fn unlock(app_handle: &AppHandle, pin: &str) -> Result<(), String> {
+    let config = current(app_handle);
+    verify_limited(app_handle, &config, pin)?;
+    if config.is_legacy() {
+        upgrade_hash(app_handle, pin)?;
+    }
+    if let Some(state) = app_handle.try_state::<ParentalState>() {
+        state.unlocked.store(true, Ordering::Release);
+    }
+    Ok(())
+}
+
+pub fn lock(app_handle: &AppHandle) {
+    if let Some(state) = app_handle.try_state::<ParentalState>() {
+        state.unlocked.store(false, Ordering::Release);
+    }
+}
+
+/// Replace a legacy SHA-256 hash with an Argon2id one of the same PIN
+fn upgrade_hash(app_handle: &AppHandle, pin: &str) -> Result<(), String> {
+    let Some(config_state) = app_handle.try_state::<ConfigState>() else {
+        return Ok(());
+    };
+    config_state.config.lock().unwrap().parental = ParentalConfig::with_pin(pin)?;
+    config_state.save()
+}
+
+/// Set, change or (with `new_pin` = None) remove the PIN. `current_pin` is
+/// required when one is already set.
+pub fn set_pin(
+    app_handle: &AppHandle,
+    current_pin: Option<&str>,
+    new_pin: Option<&str>,
+) -> Result<(), String> {
+    let config_state = app_handle
+        .try_state::<ConfigState>()
+        .ok_or("Config is not loaded")?;
+    {
+        let mut config = config_state.config.lock().unwrap();
+        if config.parental.enabled() {
+            verify_limited(
+                app_handle,
+                &config.parental,
+                current_pin.unwrap_or_default(),
+            )?;
+        }
+        config.parental = match new_pin {
+            Some(pin) => {
+                validate_pin(pin)?;
+                ParentalConfig::with_pin(pin)?
+            }
+            None => ParentalConfig::default(),
+        };
+    }
+    config_state.save()?;
+    // Setting a new PIN locks the launcher right away
+    lock(app_handle);
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_verify_pin() {
+        let config = ParentalConfig::with_pin("2468").unwrap();
+        assert!(
+            config
+                .pin_hash
+                .as_deref()
+                .unwrap()
+                .starts_with("$argon2id$")
+        );
+        assert!(!config.is_legacy());
+        assert!(config.enabled());
+        assert!(config.verify("2468"));
+        assert!(!config.verify("1357"));
+        assert!(!config.verify(""));
+
+        let disabled = ParentalConfig::default();
+        assert!(!disabled.enabled());
+        assert!(disabled.verify("anything"));
+    }
+
+    #[test]
+    fn test_verify_legacy_pin() {
+        let salt = "0f8fad5bd9cb469fa16570867728950e".to_string();
+        let config = ParentalConfig {
+            pin_hash: Some(legacy_hash_pin("2468", &salt)),
+            pin_salt: Some(salt),
+        };
+        assert!(config.is_legacy());
+        assert!(config.verify("2468"));
+        assert!(!config.verify("1357"));
+    }
+
+    #[test]
+    fn test_validate_pin() {
+        assert!(validate_pin("1234").is_ok());
+        assert!(validate_pin("123").is_err());
+        assert!(validate_pin("12 34").is_err());
+    }
+}
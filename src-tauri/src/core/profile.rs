@@ -0,0 +1,231 @@
+//! Launcher profile export/import for moving to a new PC.
+//!
+//! A profile bundle holds the launcher settings, the metadata of every
+//! instance (not their game files) and optionally the saved accounts. It is
+//! serialized to JSON and encrypted with a user-chosen passphrase, since it
+//! may contain refresh tokens and API keys.
+
+use super::account_storage::{AccountStorage, AccountStore};
+use super::config::{ConfigState, LauncherConfig};
+use super::instance::{Instance, InstanceState};
+use super::sync::crypto;
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+use tauri::{AppHandle, Manager};
+use ts_rs::TS;
+
+const BUNDLE_FORMAT_VERSION: u32 = 1;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ProfileBundle {
+    pub format_version: u32,
+    pub exported_at: i64,
+    pub launcher_version: String,
+    pub config: LauncherConfig,
+    pub instances: Vec<Instance>,
+    pub active_instance_id: Option<String>,
+    pub accounts: Option<AccountStore>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export, export_to = "profile.ts")]
+pub struct ProfileImportSummary {
+    pub instances_imported: usize,
+    pub instances_skipped: usize,
+    pub accounts_imported: usize,
+    pub exported_at: i64,
+}
+
+fn app_data_dir(app_handle: &AppHandle) -> Result<std::path::PathBuf, String> {
+    app_handle.path().app_data_dir().map_err(|e| e.to_string())
+}
+
+pub fn export_profile(
+    app_handle: &AppHandle,
+    path: &Path,
+    passphrase: &str,
+    include_accounts: bool,
+) -> Result<(), String> {
+    let config = app_handle
+        .state::<ConfigState>()
+        .config
+        .lock()
+        .unwrap()
+        .clone();
+    let (instances, active_instance_id) = {
+        let instance_state = app_handle.state::<InstanceState>();
+        let instances = instance_state.instances.lock().unwrap();
+        (
+            instances.instances.clone(),
+            instances.active_instance_id.clone(),
+        )
+    };
+    let accounts = if include_accounts {
+        Some(AccountStorage::new(app_data_dir(app_handle)?).load())
+    } else {
+        None
+    };
+
+    let bundle = ProfileBundle {
+        format_version: BUNDLE_FORMAT_VERSION,
+        exported_at: chrono::Utc::now().timestamp(),
+        launcher_version: env!("CARGO_PKG_VERSION").to_string(),
+        config,
+        instances,
+        active_instance_id,
+        accounts,
+    };
+
+    let content = serde_json::to_vec(&bundle).map_err(|e| e.to_string())?;
+    let encrypted = crypto::encrypt(&content, passphrase)?;
+    std::fs::write(path, encrypted).map_err(|e| format!("Failed to write profile: {}", e))?;
+    println!(
+        "[Profile] Exported {} instance(s) to {}",
+        bundle.instances.len(),
+        path.display()
+    );
+    Ok(())
+}
+
+pub fn read_bundle(path: &Path, passphrase: &str) -> Result<ProfileBundle, String> {
+    let data = std::fs::read(path).map_err(|e| format!("Failed to read profile: {}", e))?;
+    let content = crypto::decrypt(&data, passphrase)?;
+    let bundle: ProfileBundle =
+        serde_json::from_slice(&content).map_err(|e| format!("Invalid profile: {}", e))?;
+    if bundle.format_version > BUNDLE_FORMAT_VERSION {
+        return Err(format!(
+            "This profile was exported by a newer DropOut ({})",
+            bundle.launcher_version
+        ));
+    }
+    Ok(bundle)
+}
+
+/// Whether an instance id from a bundle is safe to use as a folder name
+fn is_safe_instance_id(id: &str) -> bool {
+    super::safe_path::is_safe_relative(id) && !id.contains(['/', '\\'])
+}
+
+/// Apply a bundle: settings are replaced, instances and accounts are merged.
+/// The launcher PIN, local API token and schedules of this machine are kept.
+/// Blocking; run it off the async runtime.
+pub fn import_profile(
+    app_handle: &AppHandle,
+    path: &Path,
+    passphrase: &str,
+) -> Result<ProfileImportSummary, String> {
+    let bundle = read_bundle(path, passphrase)?;
+    let app_dir = app_data_dir(app_handle)?;
+
+    // Settings
+    let mut config = bundle.config;
+    if config.java_path != "java" && !Path::new(&config.java_path).exists() {
+        // Paths from the old machine rarely exist here
+        config.java_path = LauncherConfig::default().java_path;
+    }
+    if let Some(bg) = &config.custom_background_path
+        && !Path::new(bg).exists()
+    {
+        config.custom_background_path = None;
+    }
+    {
+        let config_state = app_handle.state::<ConfigState>();
+        let mut current = config_state.config.lock().unwrap();
+        config.parental = current.parental.clone();
+        config.local_api_token = current.local_api_token.clone();
+        config.schedules = current.schedules.clone();
+        *current = config;
+        drop(current);
+        config_state.save()?;
+    }
+
+    // Instances: recreate their folders under this machine's data directory
+    let instance_state = app_handle.state::<InstanceState>();
+    let mut imported = 0;
+    let mut skipped = 0;
+    {
+        let mut instances = instance_state.instances.lock().unwrap();
+        for mut instance in bundle.instances {
+            if !is_safe_instance_id(&instance.id) {
+                // The id becomes a folder name; don't let it point elsewhere
+                instance.id = uuid::Uuid::new_v4().to_string();
+            }
+            if instances.instances.iter().any(|i| i.id == instance.id) {
+                skipped += 1;
+                continue;
+            }
+            let game_dir = app_dir.join("instances").join(&instance.id);
+            for dir in ["mods", "config", "saves"] {
+                std::fs::create_dir_all(game_dir.join(dir)).map_err(|e| e.to_string())?;
+            }
+            instance.game_dir = game_dir;
+            instance.last_played = None;
+            if instance
+                .icon_path
+                .as_ref()
+                .is_some_and(|p| !Path::new(p).exists())
+            {
+                instance.icon_path = None;
+            }
+            if instance
+                .java_path_override
+                .as_ref()
+                .is_some_and(|p| !Path::new(p).exists())
+            {
+                instance.java_path_override = None;
+            }
+            instances.instances.push(instance);
+            imported += 1;
+        }
+        if instances.active_instance_id.is_none() {
+            instances.active_instance_id = bundle
+                .active_instance_id
+                .filter(|id| instances.instances.iter().any(|i| &i.id == id));
+        }
+    }
+    instance_state.save()?;
+
+    // Accounts
+    let mut accounts_imported = 0;
+    if let Some(bundle_accounts) = bundle.accounts {
+        let storage = AccountStorage::new(app_dir);
+        let mut store = storage.load();
+        for account in bundle_accounts.accounts {
+            let id = account.id();
+            store.accounts.retain(|a| a.id() != id);
+            store.accounts.push(account);
+            accounts_imported += 1;
+        }
+        if store.active_account_id.is_none() {
+            store.active_account_id = bundle_accounts.active_account_id;
+        }
+        storage.save(&store)?;
+    }
+
+    println!(
+        "[Profile] Imported {} instance(s), {} account(s)",
+        imported, accounts_imported
+    );
+    Ok(ProfileImportSummary {
+        instances_imported: imported,
+        instances_skipped: skipped,
+        accounts_imported,
+        exported_at: bundle.exported_at,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_is_safe_instance_id() {
+        assert!(is_safe_instance_id("0f8fad5b-d9cb-469f-a165-70867728950e"));
+        assert!(!is_safe_instance_id("../../.ssh"));
+        assert!(!is_safe_instance_id("a/b"));
+        assert!(!is_safe_instance_id("a\\b"));
+        assert!(!is_safe_instance_id(""));
+    }
+}
@@ -0,0 +1,214 @@
+//! Recovery scan after an unclean shutdown.
+//!
+//! When the last session crashed or was killed (see [`super::shutdown`]), the
+//! downloads and installs it had running are left half done. The scan lists
+//! what they left behind and sends it as a `recovery-report` event; the UI
+//! offers to resume, clean up or ignore it ([`RecoveryAction`]).
+//!
+//! - Orphaned `.part` files: temp files of game downloads. They can't be
+//!   continued (only Java archives keep a `.part.meta` to resume from), so
+//!   both resuming and cleaning delete them; the files are fetched again the
+//!   next time they are needed.
+//! - Stale natives directories: already removed at startup, only counted.
+//! - Interrupted installs: instances whose modded version is incomplete.
+//!   The loader has to be installed again from the instance's settings.
+//! - Pending Java downloads: resumed or dropped from the queue.
+
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use tauri::{AppHandle, Manager};
+use ts_rs::TS;
+
+use super::instance::{InstanceState, InstanceStatus};
+use super::java::PendingJavaDownload;
+
+/// Folders of the app data directory and of instances that downloads write to
+const DOWNLOAD_DIRS: &[&str] = &[
+    "versions",
+    "libraries",
+    "assets",
+    "mods",
+    "resourcepacks",
+    "shaderpacks",
+];
+
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export, export_to = "recovery.ts")]
+pub struct OrphanedFile {
+    pub path: String,
+    pub size: u64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export, export_to = "recovery.ts")]
+pub struct InterruptedInstall {
+    pub instance_id: String,
+    pub instance_name: String,
+    pub version_id: Option<String>,
+    pub mod_loader: Option<String>,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export, export_to = "recovery.ts")]
+pub struct RecoveryReport {
+    pub orphaned_files: Vec<OrphanedFile>,
+    pub stale_natives: usize, // directories removed at startup
+    pub interrupted_installs: Vec<InterruptedInstall>,
+    pub pending_java_downloads: Vec<PendingJavaDownload>,
+}
+
+impl RecoveryReport {
+    pub fn is_empty(&self) -> bool {
+        self.orphaned_files.is_empty()
+            && self.stale_natives == 0
+            && self.interrupted_installs.is_empty()
+            && self.pending_java_downloads.is_empty()
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, TS)]
+#[serde(rename_all = "lowercase")]
+#[ts(export, export_to = "recovery.ts")]
+pub enum RecoveryAction {
+    Resume, // continue Java downloads, drop unusable temp files
+    Clean,  // drop temp files and pending Java downloads
+    Ignore, // leave everything as it is
+}
+
+/// The report of this startup until the user acts on it
+#[derive(Default)]
+pub struct RecoveryState {
+    pub report: Mutex<Option<RecoveryReport>>,
+}
+
+/// `.part` files under `dir` that no download can continue
+fn find_orphaned_parts(dir: &Path, out: &mut Vec<OrphanedFile>) {
+    let Ok(entries) = std::fs::read_dir(dir) else {
+        return;
+    };
+    for entry in entries.flatten() {
+        let path = entry.path();
+        let Ok(metadata) = entry.metadata() else {
+            continue;
+        };
+        if metadata.is_dir() {
+            find_orphaned_parts(&path, out);
+            continue;
+        }
+        let name = entry.file_name().to_string_lossy().to_string();
+        let resumable = path.with_file_name(format!("{}.meta", name)).exists();
+        if name.ends_with(".part") && !resumable {
+            out.push(OrphanedFile {
+                path: path.to_string_lossy().to_string(),
+                size: metadata.len(),
+            });
+        }
+    }
+}
+
+/// Look for what an interrupted session left behind. Blocking: walks the
+/// download folders.
+pub fn scan(app_handle: &AppHandle, stale_natives: usize) -> RecoveryReport {
+    let mut roots: Vec<PathBuf> = Vec::new();
+    if let Ok(app_dir) = app_handle.path().app_data_dir() {
+        roots.push(app_dir);
+    }
+    let instances = app_handle
+        .try_state::<InstanceState>()
+        .map(|state| state.list_instances())
+        .unwrap_or_default();
+    roots.extend(instances.iter().map(|i| i.game_dir.clone()));
+    roots.sort();
+    roots.dedup();
+
+    let mut orphaned_files = Vec::new();
+    for root in &roots {
+        for dir in DOWNLOAD_DIRS {
+            find_orphaned_parts(&root.join(dir), &mut orphaned_files);
+        }
+    }
+
+    RecoveryReport {
+        orphaned_files,
+        stale_natives,
+        interrupted_installs: instances
+            .into_iter()
+            .filter(|i| i.status == InstanceStatus::Partial)
+            .map(|i| InterruptedInstall {
+                instance_id: i.id,
+                instance_name: i.name,
+                version_id: i.version_id,
+                mod_loader: i.mod_loader,
+            })
+            .collect(),
+        pending_java_downloads: super::java::get_pending_downloads(app_handle),
+    }
+}
+
+fn remove_orphaned(report: &RecoveryReport) {
+    for file in &report.orphaned_files {
+        if let Err(e) = std::fs::remove_file(&file.path) {
+            eprintln!("[Recovery] Failed to remove {}: {}", file.path, e);
+        }
+    }
+}
+
+/// Act on the stored report and forget it
+pub async fn apply(app_handle: &AppHandle, action: RecoveryAction) -> Result<(), String> {
+    let report = app_handle
+        .try_state::<RecoveryState>()
+        .and_then(|state| state.report.lock().unwrap().take())
+        .unwrap_or_default();
+    match action {
+        RecoveryAction::Ignore => {}
+        RecoveryAction::Resume => {
+            remove_orphaned(&report);
+            if !report.pending_java_downloads.is_empty() {
+                super::java::resume_pending_downloads(app_handle).await?;
+            }
+        }
+        RecoveryAction::Clean => {
+            remove_orphaned(&report);
+            for pending in &report.pending_java_downloads {
+                super::java::clear_pending_download(
+                    app_handle,
+                    pending.major_version,
+                    &pending.image_type,
+                )?;
+            }
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_find_orphaned_parts() {
+        let dir = std::env::temp_dir().join(format!("dropout-recovery-{}", uuid::Uuid::new_v4()));
+        let libs = dir.join("libraries/org/lwjgl");
+        std::fs::create_dir_all(&libs).unwrap();
+        std::fs::write(libs.join("lwjgl.jar"), b"jar").unwrap();
+        std::fs::write(
+            libs.join("lwjgl.jar.0f8fad5bd9cb469fa16570867728950e.part"),
+            b"half",
+        )
+        .unwrap();
+        // A resumable Java archive
+        std::fs::write(dir.join("temurin.zip.part"), b"half").unwrap();
+        std::fs::write(dir.join("temurin.zip.part.meta"), b"{}").unwrap();
+
+        let mut found = Vec::new();
+        find_orphaned_parts(&dir, &mut found);
+        assert_eq!(found.len(), 1);
+        assert!(found[0].path.ends_with(".part"));
+        assert_eq!(found[0].size, 4);
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+}
@@ -0,0 +1,194 @@
+use serde::{Deserialize, Serialize};
+use std::path::PathBuf;
+use tauri::{AppHandle, Manager};
+use ts_rs::TS;
+
+const GITHUB_RELEASES_URL: &str = "https://api.github.com/repos/HydroRoll-Team/DropOut/releases";
+
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export, export_to = "core.ts")]
+pub struct GithubRelease {
+    pub tag_name: String,
+    pub name: String,
+    pub published_at: String,
+    pub body: String,
+    pub html_url: String,
+}
+
+/// Result of a release check, including when GitHub was last contacted
+#[derive(Debug, Clone, Serialize, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export, export_to = "core.ts")]
+pub struct GithubReleasesResponse {
+    pub releases: Vec<GithubRelease>,
+    pub last_checked: u64,
+    pub from_cache: bool,
+}
+
+/// On-disk cache of the releases endpoint, revalidated with ETag
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+#[serde(rename_all = "camelCase")]
+struct ReleaseCache {
+    etag: Option<String>,
+    last_checked: u64,
+    releases: Vec<GithubRelease>,
+}
+
+fn get_cache_path(app_handle: &AppHandle) -> PathBuf {
+    app_handle
+        .path()
+        .app_data_dir()
+        .unwrap()
+        .join("github_releases_cache.json")
+}
+
+fn load_cache(app_handle: &AppHandle) -> Option<ReleaseCache> {
+    let content = std::fs::read_to_string(get_cache_path(app_handle)).ok()?;
+    serde_json::from_str(&content).ok()
+}
+
+fn save_cache(app_handle: &AppHandle, cache: &ReleaseCache) -> Result<(), String> {
+    let cache_path = get_cache_path(app_handle);
+    if let Some(parent) = cache_path.parent() {
+        std::fs::create_dir_all(parent).map_err(|e| e.to_string())?;
+    }
+    let content = serde_json::to_string_pretty(cache).map_err(|e| e.to_string())?;
+    std::fs::write(&cache_path, content).map_err(|e| e.to_string())?;
+    Ok(())
+}
+
+fn now_secs() -> u64 {
+    std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .unwrap()
+        .as_secs()
+}
+
+fn parse_releases(releases: Vec<serde_json::Value>) -> Vec<GithubRelease> {
+    let mut result = Vec::new();
+    for r in releases {
+        if let (Some(tag), Some(name), Some(date), Some(body), Some(url)) = (
+            r["tag_name"].as_str(),
+            r["name"].as_str(),
+            r["published_at"].as_str(),
+            r["body"].as_str(),
+            r["html_url"].as_str(),
+        ) {
+            result.push(GithubRelease {
+                tag_name: tag.to_string(),
+                name: name.to_string(),
+                published_at: date.to_string(),
+                body: body.to_string(),
+                html_url: url.to_string(),
+            });
+        }
+    }
+    result
+}
+
+/// Get the launcher's GitHub releases, using the on-disk cache where possible.
+///
+/// The cache is served as-is while it is younger than `refresh_interval_secs`
+/// (unless `force` is set). Otherwise the API is queried with `If-None-Match`,
+/// so an unchanged release list costs a 304 instead of a full response and,
+/// for authenticated requests, does not count against the rate limit.
+pub async fn get_releases(
+    app_handle: &AppHandle,
+    token: Option<&str>,
+    refresh_interval_secs: u64,
+    force: bool,
+) -> Result<GithubReleasesResponse, String> {
+    let cache = load_cache(app_handle);
+    let now = now_secs();
+
+    if let Some(cache) = &cache
+        && !force
+        && now.saturating_sub(cache.last_checked) < refresh_interval_secs
+    {
+        return Ok(GithubReleasesResponse {
+            releases: cache.releases.clone(),
+            last_checked: cache.last_checked,
+            from_cache: true,
+        });
+    }
+
+    let client = reqwest::Client::new();
+    let mut request = client
+        .get(GITHUB_RELEASES_URL)
+        .header("User-Agent", "DropOut-Launcher")
+        .header("Accept", "application/vnd.github+json");
+    if let Some(token) = token.filter(|t| !t.trim().is_empty()) {
+        request = request.header("Authorization", format!("Bearer {}", token.trim()));
+    }
+    if let Some(etag) = cache.as_ref().and_then(|c| c.etag.as_ref()) {
+        request = request.header("If-None-Match", etag);
+    }
+
+    let res = match request.send().await {
+        Ok(res) => res,
+        Err(e) => {
+            // Offline: fall back to whatever we have on disk
+            if let Some(cache) = cache {
+                return Ok(GithubReleasesResponse {
+                    releases: cache.releases,
+                    last_checked: cache.last_checked,
+                    from_cache: true,
+                });
+            }
+            return Err(e.to_string());
+        }
+    };
+
+    if res.status() == reqwest::StatusCode::NOT_MODIFIED
+        && let Some(mut cache) = cache
+    {
+        cache.last_checked = now;
+        save_cache(app_handle, &cache)?;
+        return Ok(GithubReleasesResponse {
+            releases: cache.releases,
+            last_checked: now,
+            from_cache: true,
+        });
+    }
+
+    if !res.status().is_success() {
+        let status = res.status();
+        // Rate limited or otherwise failing: stale data beats no data
+        if let Some(cache) = cache {
+            println!(
+                "[Releases] GitHub API returned {}, serving cached releases",
+                status
+            );
+            return Ok(GithubReleasesResponse {
+                releases: cache.releases,
+                last_checked: cache.last_checked,
+                from_cache: true,
+            });
+        }
+        return Err(format!("GitHub API returned status: {}", status));
+    }
+
+    let etag = res
+        .headers()
+        .get(reqwest::header::ETAG)
+        .and_then(|v| v.to_str().ok())
+        .map(|s| s.to_string());
+    let releases: Vec<serde_json::Value> = res.json().await.map_err(|e| e.to_string())?;
+    let releases = parse_releases(releases);
+
+    let cache = ReleaseCache {
+        etag,
+        last_checked: now,
+        releases: releases.clone(),
+    };
+    if let Err(e) = save_cache(app_handle, &cache) {
+        eprintln!("[Releases] Failed to save release cache: {}", e);
+    }
+
+    Ok(GithubReleasesResponse {
+        releases,
+        last_checked: now,
+        from_cache: false,
+    })
+}
@@ -0,0 +1,235 @@
+//! Clearing personal data and caches (`reset_launcher`).
+//!
+//! Each [`ResetScope`] maps to files in the app data directory (and, for
+//! logs, in instance directories). A dry run only lists them; otherwise they
+//! are deleted one by one with a `reset-progress` event per entry, and the
+//! matching in-memory state (signed-in account, assistant log history) is
+//! dropped as well. Resetting accounts also clears the keys, tokens and
+//! passwords stored in the settings. Instances, their worlds and the other
+//! launcher settings are never touched.
+
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+use tauri::{AppHandle, Emitter, Manager};
+use ts_rs::TS;
+
+use super::config::ConfigState;
+use super::instance::{InstanceState, dir_size};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export, export_to = "reset.ts")]
+pub enum ResetScope {
+    Accounts,   // saved accounts and offline skins
+    Assistant,  // the assistant's game log history and search index
+    Logs,       // launch reports and history, diagnostics, stats, game logs
+    Caches,     // downloaded metadata, skins and modpack archives
+    Everything, // all of the above and exported instances
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export, export_to = "reset.ts")]
+pub struct ResetEntry {
+    pub path: String,
+    pub size: u64,
+    pub removed: bool, // false for a dry run or when deleting failed
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export, export_to = "reset.ts")]
+pub struct ResetReport {
+    pub dry_run: bool,
+    pub entries: Vec<ResetEntry>,
+    pub total_bytes: u64,
+    pub errors: Vec<String>,
+    pub cleared_secrets: Vec<String>, // config keys, e.g. "githubToken"
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export, export_to = "reset.ts")]
+pub struct ResetProgress {
+    pub current: usize,
+    pub total: usize,
+    pub path: String,
+}
+
+/// Entries of the app data directory per scope
+const ACCOUNT_FILES: &[&str] = &["accounts.json", "skins"];
+const LOG_FILES: &[&str] = &[
+    "launch_reports",
+    "launch_history",
+    "diagnostics",
+    "download_audit",
+    "usage_stats.jsonl",
+];
+const CACHE_FILES: &[&str] = &[
+    "cache",
+    "java_catalog_cache.json",
+    "github_releases_cache.json",
+    "download_tuning.json",
+];
+/// Only cleared with everything else
+const EVERYTHING_FILES: &[&str] = &["exports"];
+/// Folders of an instance directory cleared with the logs
+const INSTANCE_LOG_DIRS: &[&str] = &["logs", "crash-reports"];
+
+impl ResetScope {
+    /// Whether resetting `self` clears what `scope` covers
+    pub fn includes(self, scope: ResetScope) -> bool {
+        self == scope || self == ResetScope::Everything
+    }
+}
+
+/// Paths that `scope` would delete, existing ones only
+pub fn targets(app_dir: &Path, game_dirs: &[PathBuf], scope: ResetScope) -> Vec<PathBuf> {
+    let mut names: Vec<&str> = Vec::new();
+    if scope.includes(ResetScope::Accounts) {
+        names.extend(ACCOUNT_FILES);
+    }
+    if scope.includes(ResetScope::Logs) {
+        names.extend(LOG_FILES);
+    }
+    if scope.includes(ResetScope::Caches) {
+        names.extend(CACHE_FILES);
+    }
+    if scope == ResetScope::Everything {
+        names.extend(EVERYTHING_FILES);
+    }
+    let mut paths: Vec<PathBuf> = names.iter().map(|name| app_dir.join(name)).collect();
+    if scope.includes(ResetScope::Logs) {
+        for game_dir in game_dirs {
+            paths.extend(INSTANCE_LOG_DIRS.iter().map(|dir| game_dir.join(dir)));
+        }
+    }
+    paths.sort();
+    paths.dedup();
+    paths.retain(|path| path.exists());
+    paths
+}
+
+fn size_of(path: &Path) -> u64 {
+    if path.is_dir() {
+        dir_size(path)
+    } else {
+        std::fs::metadata(path).map(|m| m.len()).unwrap_or(0)
+    }
+}
+
+fn remove(path: &Path) -> std::io::Result<()> {
+    if path.is_dir() {
+        std::fs::remove_dir_all(path)
+    } else {
+        std::fs::remove_file(path)
+    }
+}
+
+/// List (with `dry_run`) or delete what `scope` covers. Blocking.
+pub fn reset(
+    app_handle: &AppHandle,
+    scope: ResetScope,
+    dry_run: bool,
+) -> Result<ResetReport, String> {
+    let app_dir = app_handle
+        .path()
+        .app_data_dir()
+        .map_err(|e| e.to_string())?;
+    let game_dirs: Vec<PathBuf> = app_handle
+        .try_state::<InstanceState>()
+        .map(|state| state.list_instances())
+        .unwrap_or_default()
+        .into_iter()
+        .map(|instance| instance.game_dir)
+        .collect();
+
+    let paths = targets(&app_dir, &game_dirs, scope);
+    let mut report = ResetReport {
+        dry_run,
+        ..Default::default()
+    };
+    for (i, path) in paths.iter().enumerate() {
+        let size = size_of(path);
+        let display = path.to_string_lossy().to_string();
+        let mut removed = false;
+        if !dry_run {
+            let _ = app_handle.emit(
+                "reset-progress",
+                ResetProgress {
+                    current: i + 1,
+                    total: paths.len(),
+                    path: display.clone(),
+                },
+            );
+            match remove(path) {
+                Ok(()) => removed = true,
+                Err(e) => report.errors.push(format!("{}: {}", display, e)),
+            }
+        }
+        report.total_bytes += size;
+        report.entries.push(ResetEntry {
+            path: display,
+            size,
+            removed,
+        });
+    }
+
+    if scope.includes(ResetScope::Accounts)
+        && let Some(config_state) = app_handle.try_state::<ConfigState>()
+    {
+        if dry_run {
+            let mut config = config_state.config.lock().unwrap().clone();
+            report.cleared_secrets = config.clear_secrets();
+        } else {
+            report.cleared_secrets = config_state.config.lock().unwrap().clear_secrets();
+            if !report.cleared_secrets.is_empty()
+                && let Err(e) = config_state.save()
+            {
+                report.errors.push(format!("config.json: {}", e));
+            }
+            // A running API would still accept the old token
+            if report
+                .cleared_secrets
+                .iter()
+                .any(|key| key == "localApiToken")
+            {
+                super::local_api::stop(app_handle);
+            }
+        }
+    }
+    Ok(report)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_targets_by_scope() {
+        let dir = std::env::temp_dir().join(format!("dropout-reset-{}", uuid::Uuid::new_v4()));
+        let game_dir = dir.join("instances/a");
+        std::fs::create_dir_all(game_dir.join("logs")).unwrap();
+        std::fs::create_dir_all(dir.join("cache/skins")).unwrap();
+        std::fs::create_dir_all(dir.join("exports")).unwrap();
+        std::fs::write(dir.join("accounts.json"), b"[]").unwrap();
+        std::fs::write(dir.join("config.json"), b"{}").unwrap();
+        let game_dirs = vec![game_dir.clone()];
+
+        assert_eq!(
+            targets(&dir, &game_dirs, ResetScope::Accounts),
+            vec![dir.join("accounts.json")]
+        );
+        assert_eq!(
+            targets(&dir, &game_dirs, ResetScope::Logs),
+            vec![game_dir.join("logs")]
+        );
+        assert!(targets(&dir, &game_dirs, ResetScope::Assistant).is_empty());
+        assert!(!targets(&dir, &game_dirs, ResetScope::Caches).contains(&dir.join("exports")));
+        let everything = targets(&dir, &game_dirs, ResetScope::Everything);
+        assert_eq!(everything.len(), 4);
+        assert!(everything.contains(&dir.join("exports")));
+        assert!(!everything.contains(&dir.join("config.json")));
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+}
@@ -0,0 +1,138 @@
+//! Scheduled server launches and instance backups.
+//!
+//! Schedules are stored in the launcher config. A background loop checks
+//! them every [`TICK`] and runs each at most once per time slot. Slots missed
+//! by more than [`GRACE_MINUTES`] (e.g. while the launcher was closed) are
+//! skipped rather than caught up on.
+
+use chrono::{Datelike, Duration, NaiveDateTime, NaiveTime};
+use serde::{Deserialize, Serialize};
+use ts_rs::TS;
+
+pub const TICK: std::time::Duration = std::time::Duration::from_secs(30);
+pub const GRACE_MINUTES: i64 = 5;
+
+const SLOT_FORMAT: &str = "%Y-%m-%d %H:%M";
+
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export, export_to = "scheduler.ts")]
+pub struct Schedule {
+    #[serde(default)]
+    pub id: String,
+    pub instance_id: String,
+    pub action: String, // "launch" | "backup"
+    pub time: String,   // local time, "HH:MM"
+    #[serde(default)]
+    pub days: Vec<u32>, // ISO weekdays (1 = Monday ... 7 = Sunday), empty = every day
+    #[serde(default = "default_enabled")]
+    pub enabled: bool,
+    #[serde(default)]
+    pub last_run: Option<String>, // last slot that ran, "YYYY-MM-DD HH:MM" local time
+}
+
+fn default_enabled() -> bool {
+    true
+}
+
+pub fn parse_time(time: &str) -> Result<NaiveTime, String> {
+    NaiveTime::parse_from_str(time.trim(), "%H:%M")
+        .map_err(|_| format!("Invalid time '{}', expected HH:MM", time))
+}
+
+pub fn validate(schedule: &Schedule) -> Result<(), String> {
+    if !matches!(schedule.action.as_str(), "launch" | "backup") {
+        return Err(format!("Unknown schedule action '{}'", schedule.action));
+    }
+    parse_time(&schedule.time)?;
+    if let Some(day) = schedule.days.iter().find(|d| !(1..=7).contains(*d)) {
+        return Err(format!("Invalid weekday {}, expected 1-7", day));
+    }
+    Ok(())
+}
+
+/// The slot of `schedule` due at `now` (local time) that hasn't run yet
+pub fn due_slot(schedule: &Schedule, now: NaiveDateTime) -> Option<String> {
+    if !schedule.enabled {
+        return None;
+    }
+    let slot = now.date().and_time(parse_time(&schedule.time).ok()?);
+    let weekday = slot.weekday().number_from_monday();
+    if !schedule.days.is_empty() && !schedule.days.contains(&weekday) {
+        return None;
+    }
+    if now < slot || now >= slot + Duration::minutes(GRACE_MINUTES) {
+        return None;
+    }
+    let key = slot.format(SLOT_FORMAT).to_string();
+    (schedule.last_run.as_deref() != Some(key.as_str())).then_some(key)
+}
+
+/// Mark every due schedule as run and return copies of them
+pub fn take_due(schedules: &mut [Schedule], now: NaiveDateTime) -> Vec<Schedule> {
+    schedules
+        .iter_mut()
+        .filter_map(|schedule| {
+            let slot = due_slot(schedule, now)?;
+            schedule.last_run = Some(slot);
+            Some(schedule.clone())
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn schedule(time: &str, days: Vec<u32>) -> Schedule {
+        Schedule {
+            id: "s".to_string(),
+            instance_id: "i".to_string(),
+            action: "launch".to_string(),
+            time: time.to_string(),
+            days,
+            enabled: true,
+            last_run: None,
+        }
+    }
+
+    fn at(s: &str) -> NaiveDateTime {
+        NaiveDateTime::parse_from_str(s, SLOT_FORMAT).unwrap()
+    }
+
+    #[test]
+    fn test_due_slot_window() {
+        let s = schedule("18:30", vec![]);
+        assert_eq!(due_slot(&s, at("2026-10-16 18:29")), None);
+        assert_eq!(
+            due_slot(&s, at("2026-10-16 18:30")).as_deref(),
+            Some("2026-10-16 18:30")
+        );
+        assert!(due_slot(&s, at("2026-10-16 18:34")).is_some());
+        // Missed while closed: skipped
+        assert_eq!(due_slot(&s, at("2026-10-16 18:35")), None);
+    }
+
+    #[test]
+    fn test_due_slot_weekdays_and_runs_once() {
+        // 2026-10-16 is a Friday
+        assert!(due_slot(&schedule("08:00", vec![1, 2]), at("2026-10-16 08:00")).is_none());
+        assert!(due_slot(&schedule("08:00", vec![5]), at("2026-10-16 08:00")).is_some());
+
+        let mut schedules = vec![schedule("08:00", vec![])];
+        assert_eq!(take_due(&mut schedules, at("2026-10-16 08:00")).len(), 1);
+        assert!(take_due(&mut schedules, at("2026-10-16 08:01")).is_empty());
+        assert_eq!(take_due(&mut schedules, at("2026-10-17 08:00")).len(), 1);
+    }
+
+    #[test]
+    fn test_validate() {
+        assert!(validate(&schedule("25:00", vec![])).is_err());
+        assert!(validate(&schedule("07:05", vec![0])).is_err());
+        let mut backup = schedule("07:05", vec![7]);
+        backup.action = "backup".to_string();
+        assert!(validate(&backup).is_ok());
+        backup.action = "delete".to_string();
+        assert!(validate(&backup).is_err());
+    }
+}
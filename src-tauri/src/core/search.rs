@@ -0,0 +1,245 @@
+//! Fuzzy search over launcher entities for the command palette (Ctrl+K).
+
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+use std::path::Path;
+use ts_rs::TS;
+
+use super::instance::Instance;
+
+pub const DEFAULT_LIMIT: usize = 50;
+
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export, export_to = "search.ts")]
+pub struct SearchResult {
+    pub kind: String, // "instance" | "version" | "mod" | "world" | "screenshot" | "setting"
+    pub id: String,   // instance id, version id, file name or setting key
+    pub title: String,
+    pub subtitle: Option<String>,
+    pub instance_id: Option<String>,
+    pub score: i64,
+}
+
+/// Case-insensitive subsequence match. Contiguous runs, word starts and
+/// prefix matches score higher; `None` if `query` isn't a subsequence.
+pub fn fuzzy_score(query: &str, text: &str) -> Option<i64> {
+    let query: Vec<char> = query
+        .to_lowercase()
+        .chars()
+        .filter(|c| !c.is_whitespace())
+        .collect();
+    if query.is_empty() {
+        return Some(0);
+    }
+    let text_lower = text.to_lowercase();
+    let chars: Vec<char> = text_lower.chars().collect();
+
+    let mut score = 0i64;
+    let mut qi = 0;
+    let mut prev_match: Option<usize> = None;
+    for (i, &c) in chars.iter().enumerate() {
+        if qi == query.len() {
+            break;
+        }
+        if c != query[qi] {
+            continue;
+        }
+        score += 1;
+        if prev_match.is_some_and(|p| p + 1 == i) {
+            score += 5;
+        }
+        if i == 0 || !chars[i - 1].is_alphanumeric() {
+            score += 8;
+        }
+        prev_match = Some(i);
+        qi += 1;
+    }
+    if qi < query.len() {
+        return None;
+    }
+
+    let needle: String = query.iter().collect();
+    if text_lower.starts_with(&needle) {
+        score += 20;
+    } else if text_lower.contains(&needle) {
+        score += 10;
+    }
+    // Prefer shorter names among otherwise equal matches
+    Some(score * 100 - chars.len() as i64)
+}
+
+fn push_match(
+    results: &mut Vec<SearchResult>,
+    query: &str,
+    kind: &str,
+    id: String,
+    subtitle: Option<String>,
+    instance_id: Option<&str>,
+) {
+    if let Some(score) = fuzzy_score(query, &id) {
+        results.push(SearchResult {
+            kind: kind.to_string(),
+            title: id.clone(),
+            id,
+            subtitle,
+            instance_id: instance_id.map(str::to_string),
+            score,
+        });
+    }
+}
+
+fn dir_names(dir: &Path, keep: impl Fn(&Path) -> bool) -> Vec<String> {
+    let Ok(entries) = std::fs::read_dir(dir) else {
+        return Vec::new();
+    };
+    entries
+        .flatten()
+        .filter(|e| keep(&e.path()))
+        .map(|e| e.file_name().to_string_lossy().to_string())
+        .collect()
+}
+
+fn has_extension(path: &Path, extensions: &[&str]) -> bool {
+    path.extension()
+        .and_then(|e| e.to_str())
+        .is_some_and(|e| extensions.iter().any(|x| e.eq_ignore_ascii_case(x)))
+}
+
+fn installed_versions(versions_dir: &Path) -> Vec<String> {
+    dir_names(versions_dir, |p| {
+        p.file_name()
+            .is_some_and(|name| p.join(format!("{}.json", name.to_string_lossy())).exists())
+    })
+}
+
+/// Flattened (dotted) keys of the serialized launcher config
+pub fn setting_keys(config: &serde_json::Value) -> Vec<String> {
+    fn walk(value: &serde_json::Value, prefix: &str, out: &mut Vec<String>) {
+        if let serde_json::Value::Object(map) = value {
+            for (key, child) in map {
+                let path = if prefix.is_empty() {
+                    key.clone()
+                } else {
+                    format!("{}.{}", prefix, key)
+                };
+                if child.is_object() {
+                    walk(child, &path, out);
+                } else {
+                    out.push(path);
+                }
+            }
+        }
+    }
+    let mut keys = Vec::new();
+    walk(config, "", &mut keys);
+    keys
+}
+
+/// Search instances and their versions, mods, worlds and screenshots, plus
+/// versions in `shared_versions_dir` and the given setting keys
+pub fn search(
+    query: &str,
+    instances: &[Instance],
+    shared_versions_dir: Option<&Path>,
+    settings: &[String],
+    limit: usize,
+) -> Vec<SearchResult> {
+    let mut results = Vec::new();
+
+    let mut seen_versions = HashSet::new();
+    if let Some(dir) = shared_versions_dir {
+        for version in installed_versions(dir) {
+            if seen_versions.insert(version.clone()) {
+                push_match(&mut results, query, "version", version, None, None);
+            }
+        }
+    }
+
+    for instance in instances {
+        let id = Some(instance.id.as_str());
+        if let Some(score) = fuzzy_score(query, &instance.name) {
+            results.push(SearchResult {
+                kind: "instance".to_string(),
+                id: instance.id.clone(),
+                title: instance.name.clone(),
+                subtitle: instance.version_id.clone(),
+                instance_id: Some(instance.id.clone()),
+                score,
+            });
+        }
+
+        let dir = &instance.game_dir;
+        let subtitle = Some(instance.name.clone());
+        for version in installed_versions(&dir.join("versions")) {
+            if seen_versions.insert(version.clone()) {
+                push_match(
+                    &mut results,
+                    query,
+                    "version",
+                    version,
+                    subtitle.clone(),
+                    id,
+                );
+            }
+        }
+        for name in dir_names(&dir.join("mods"), |p| {
+            has_extension(p, &["jar", "disabled"])
+        }) {
+            push_match(&mut results, query, "mod", name, subtitle.clone(), id);
+        }
+        for name in dir_names(&dir.join("saves"), |p| p.join("level.dat").exists()) {
+            push_match(&mut results, query, "world", name, subtitle.clone(), id);
+        }
+        for name in dir_names(&dir.join("screenshots"), |p| has_extension(p, &["png"])) {
+            push_match(
+                &mut results,
+                query,
+                "screenshot",
+                name,
+                subtitle.clone(),
+                id,
+            );
+        }
+    }
+
+    for key in settings {
+        push_match(&mut results, query, "setting", key.clone(), None, None);
+    }
+
+    results.sort_by(|a, b| b.score.cmp(&a.score).then_with(|| a.title.cmp(&b.title)));
+    results.truncate(limit);
+    results
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_fuzzy_score() {
+        assert!(fuzzy_score("sodium", "sodium-fabric-0.5.8.jar").is_some());
+        assert!(fuzzy_score("sdm", "sodium-fabric-0.5.8.jar").is_some());
+        assert!(fuzzy_score("xyz", "sodium-fabric-0.5.8.jar").is_none());
+        // Prefix beats a scattered match
+        assert!(
+            fuzzy_score("fab", "fabric-api.jar").unwrap()
+                > fuzzy_score("fab", "foo-and-bar.jar").unwrap()
+        );
+        assert_eq!(fuzzy_score("", "anything"), Some(0));
+    }
+
+    #[test]
+    fn test_setting_keys() {
+        let config = serde_json::json!({
+            "maxMemory": 2048,
+            "cloudSync": {"enabled": false, "provider": "webdav"}
+        });
+        let mut keys = setting_keys(&config);
+        keys.sort();
+        assert_eq!(
+            keys,
+            vec!["cloudSync.enabled", "cloudSync.provider", "maxMemory"]
+        );
+    }
+}
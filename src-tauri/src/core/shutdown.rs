@@ -0,0 +1,193 @@
+//! Clean shutdown tracking and the exit prompt.
+//!
+//! A marker file is written to the app data directory at startup and removed
+//! once the launcher has exited normally. Finding it at the next start means
+//! the launcher crashed or was killed, possibly in the middle of a download or
+//! install; [`ShutdownState::previous_clean`] tells recovery scans whether
+//! they need to run.
+//!
+//! Closing the window while downloads or games are running is held back and
+//! [`prompt_exit`] asks with a native dialog whether to quit anyway. The UI
+//! can also quit through `confirm_exit`.
+
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+use tauri::{AppHandle, Manager, Window};
+use tauri_plugin_dialog::{DialogExt, MessageDialogButtons, MessageDialogKind};
+use ts_rs::TS;
+
+use super::config::ConfigState;
+use super::download_manager::{DownloadManager, DownloadSessionInfo};
+use super::instance::InstanceState;
+use super::local_api::{self, RunningGame};
+
+const MARKER_FILE: &str = ".running";
+
+/// What would be interrupted by quitting now
+#[derive(Debug, Clone, Default, Serialize, Deserialize, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export, export_to = "shutdown.ts")]
+pub struct ActiveWork {
+    pub downloads: Vec<DownloadSessionInfo>,
+    pub running_games: Vec<RunningGame>,
+}
+
+impl ActiveWork {
+    pub fn is_empty(&self) -> bool {
+        self.downloads.is_empty() && self.running_games.is_empty()
+    }
+}
+
+pub struct ShutdownState {
+    pub previous_clean: bool, // the last run exited normally
+    marker: PathBuf,
+    exit_confirmed: AtomicBool,
+    prompting: AtomicBool, // the exit dialog is open
+}
+
+/// Whether the last run shut down cleanly; marks this run as started
+fn check_and_mark(marker: &Path) -> bool {
+    let clean = !marker.exists();
+    if let Some(parent) = marker.parent() {
+        let _ = std::fs::create_dir_all(parent);
+    }
+    let _ = std::fs::write(marker, std::process::id().to_string());
+    clean
+}
+
+impl ShutdownState {
+    pub fn new(app_handle: &AppHandle) -> Self {
+        let marker = app_handle.path().app_data_dir().unwrap().join(MARKER_FILE);
+        Self {
+            previous_clean: check_and_mark(&marker),
+            marker,
+            exit_confirmed: AtomicBool::new(false),
+            prompting: AtomicBool::new(false),
+        }
+    }
+
+    pub fn confirm_exit(&self) {
+        self.exit_confirmed.store(true, Ordering::Release);
+    }
+
+    pub fn exit_confirmed(&self) -> bool {
+        self.exit_confirmed.load(Ordering::Acquire)
+    }
+}
+
+pub fn active_work(app_handle: &AppHandle) -> ActiveWork {
+    ActiveWork {
+        downloads: app_handle
+            .try_state::<DownloadManager>()
+            .map(|manager| manager.active())
+            .unwrap_or_default(),
+        running_games: local_api::running_games(app_handle),
+    }
+}
+
+/// Work the user has to confirm interrupting before the window may close
+pub fn blocking_work(app_handle: &AppHandle) -> Option<ActiveWork> {
+    if app_handle
+        .try_state::<ShutdownState>()
+        .is_some_and(|state| state.exit_confirmed())
+    {
+        return None;
+    }
+    let work = active_work(app_handle);
+    (!work.is_empty()).then_some(work)
+}
+
+/// "2 downloads and 1 running game will be interrupted."
+fn describe(work: &ActiveWork) -> String {
+    let plural = |n: usize, what: &str| format!("{} {}{}", n, what, if n == 1 { "" } else { "s" });
+    let parts: Vec<String> = [
+        (work.downloads.len(), "download"),
+        (work.running_games.len(), "running game"),
+    ]
+    .into_iter()
+    .filter(|(n, _)| *n > 0)
+    .map(|(n, what)| plural(n, what))
+    .collect();
+    format!("{} will be interrupted.", parts.join(" and "))
+}
+
+/// Ask whether to quit despite `work` and quit if the user agrees
+pub fn prompt_exit(window: &Window, work: &ActiveWork) {
+    let app_handle = window.app_handle().clone();
+    let Some(state) = app_handle.try_state::<ShutdownState>() else {
+        return;
+    };
+    if state.prompting.swap(true, Ordering::AcqRel) {
+        return;
+    }
+    window
+        .dialog()
+        .message(describe(work))
+        .title("Quit DropOut?")
+        .kind(MessageDialogKind::Warning)
+        .buttons(MessageDialogButtons::OkCancelCustom(
+            "Quit".to_string(),
+            "Keep running".to_string(),
+        ))
+        .parent(window)
+        .show(move |quit| {
+            let state = app_handle.state::<ShutdownState>();
+            state.prompting.store(false, Ordering::Release);
+            if quit {
+                state.confirm_exit();
+                app_handle.exit(0);
+            }
+        });
+}
+
+/// Write out pending state and record the clean shutdown
+pub fn finish(app_handle: &AppHandle) {
+    super::log_batch::flush(app_handle);
+    if let Some(config_state) = app_handle.try_state::<ConfigState>()
+        && let Err(e) = config_state.save()
+    {
+        eprintln!("[Shutdown] Failed to save config: {}", e);
+    }
+    if let Some(instance_state) = app_handle.try_state::<InstanceState>()
+        && let Err(e) = instance_state.save()
+    {
+        eprintln!("[Shutdown] Failed to save instances: {}", e);
+    }
+    if let Some(state) = app_handle.try_state::<ShutdownState>() {
+        let _ = std::fs::remove_file(&state.marker);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_check_and_mark() {
+        let dir = std::env::temp_dir().join(format!("dropout-shutdown-{}", uuid::Uuid::new_v4()));
+        let marker = dir.join(MARKER_FILE);
+        assert!(check_and_mark(&marker));
+        // Not removed, as after a crash
+        assert!(!check_and_mark(&marker));
+        std::fs::remove_file(&marker).unwrap();
+        assert!(check_and_mark(&marker));
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn test_describe() {
+        let game = RunningGame {
+            launch_id: "l".to_string(),
+            instance_id: "i".to_string(),
+            version_id: "1.21".to_string(),
+            pid: None,
+            started_at: 0,
+        };
+        let work = ActiveWork {
+            downloads: Vec::new(),
+            running_games: vec![game.clone(), game],
+        };
+        assert_eq!(describe(&work), "2 running games will be interrupted.");
+    }
+}
@@ -0,0 +1,150 @@
+//! Reachability checks for the online services the launcher depends on.
+//!
+//! Each service is probed with a cheap GET request. Any HTTP response below
+//! 500 means the service answered (auth endpoints reply 4xx to an
+//! unauthenticated GET); slow answers and rate limiting are reported as
+//! degraded so the UI can explain failing logins or downloads.
+
+use futures::future::join_all;
+use serde::{Deserialize, Serialize};
+use std::time::{Duration, Instant};
+use ts_rs::TS;
+
+const PROBE_TIMEOUT: Duration = Duration::from_secs(8);
+const DEGRADED_LATENCY_MS: u64 = 2000;
+
+/// (id, display name, probe URL)
+const SERVICES: &[(&str, &str, &str)] = &[
+    (
+        "mojang-session",
+        "Mojang Session Server",
+        "https://sessionserver.mojang.com/session/minecraft/profile/069a79f444e94726a5befca90e38aaf5",
+    ),
+    (
+        "mojang-meta",
+        "Mojang Version Manifest",
+        "https://piston-meta.mojang.com/mc/game/version_manifest_v2.json",
+    ),
+    (
+        "microsoft-auth",
+        "Microsoft Login",
+        "https://login.microsoftonline.com/consumers/v2.0/.well-known/openid-configuration",
+    ),
+    (
+        "xbox-auth",
+        "Xbox Live Authentication",
+        "https://user.auth.xboxlive.com/user/authenticate",
+    ),
+    (
+        "minecraft-services",
+        "Minecraft Services",
+        "https://api.minecraftservices.com/minecraft/profile",
+    ),
+    (
+        "fabric-meta",
+        "Fabric Meta",
+        "https://meta.fabricmc.net/v2/versions/game",
+    ),
+    (
+        "adoptium",
+        "Adoptium API",
+        "https://api.adoptium.net/v3/info/available_releases",
+    ),
+];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, TS)]
+#[serde(rename_all = "lowercase")]
+#[ts(export, export_to = "status.ts")]
+pub enum ServiceHealth {
+    Up,
+    Degraded,
+    Down,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export, export_to = "status.ts")]
+pub struct ServiceStatus {
+    pub id: String,
+    pub name: String,
+    pub url: String,
+    pub status: ServiceHealth,
+    pub latency_ms: Option<u64>,
+    pub http_status: Option<u16>,
+    pub error: Option<String>,
+}
+
+/// Classify a probe result by HTTP status and latency
+pub fn classify(http_status: u16, latency_ms: u64) -> ServiceHealth {
+    if http_status >= 500 {
+        ServiceHealth::Down
+    } else if http_status == 429 || latency_ms >= DEGRADED_LATENCY_MS {
+        ServiceHealth::Degraded
+    } else {
+        ServiceHealth::Up
+    }
+}
+
+async fn probe(client: &reqwest::Client, id: &str, name: &str, url: &str) -> ServiceStatus {
+    let start = Instant::now();
+    let result = client.get(url).send().await;
+    let latency_ms = start.elapsed().as_millis() as u64;
+
+    match result {
+        Ok(response) => {
+            let http_status = response.status().as_u16();
+            ServiceStatus {
+                id: id.to_string(),
+                name: name.to_string(),
+                url: url.to_string(),
+                status: classify(http_status, latency_ms),
+                latency_ms: Some(latency_ms),
+                http_status: Some(http_status),
+                error: None,
+            }
+        }
+        Err(e) => ServiceStatus {
+            id: id.to_string(),
+            name: name.to_string(),
+            url: url.to_string(),
+            status: ServiceHealth::Down,
+            latency_ms: None,
+            http_status: None,
+            error: Some(if e.is_timeout() {
+                format!("Timed out after {}s", PROBE_TIMEOUT.as_secs())
+            } else {
+                e.to_string()
+            }),
+        },
+    }
+}
+
+/// Probe all services concurrently
+pub async fn get_service_status() -> Vec<ServiceStatus> {
+    let client = reqwest::Client::builder()
+        .timeout(PROBE_TIMEOUT)
+        .user_agent("DropOut/1.0")
+        .build()
+        .unwrap_or_default();
+
+    join_all(
+        SERVICES
+            .iter()
+            .map(|(id, name, url)| probe(&client, id, name, url)),
+    )
+    .await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_classify() {
+        assert_eq!(classify(200, 100), ServiceHealth::Up);
+        assert_eq!(classify(401, 100), ServiceHealth::Up);
+        assert_eq!(classify(200, 5000), ServiceHealth::Degraded);
+        assert_eq!(classify(429, 100), ServiceHealth::Degraded);
+        assert_eq!(classify(503, 100), ServiceHealth::Down);
+    }
+}
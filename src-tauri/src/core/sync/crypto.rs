@@ -0,0 +1,241 @@
+//! Passphrase-based encryption for data that leaves the machine.
+//!
+//! Layout: the `DOENC1` magic, a 16-byte salt, a 12-byte nonce and the
+//! AES-256-GCM ciphertext. The key is derived from the passphrase with
+//! Argon2id, so a wrong passphrase fails authentication instead of producing
+//! garbage.
+//!
+//! Files too large to hold in memory (cloud backups) use `DOENC2` instead:
+//! the magic, a 16-byte salt, a 7-byte nonce prefix and the data encrypted
+//! in 64 KiB chunks with the STREAM construction, which also detects chunks
+//! being reordered, dropped or cut off at the end.
+
+use aes_gcm::aead::generic_array::GenericArray;
+use aes_gcm::aead::rand_core::RngCore;
+use aes_gcm::aead::stream::{DecryptorBE32, EncryptorBE32};
+use aes_gcm::aead::{Aead, KeyInit, OsRng};
+use aes_gcm::{Aes256Gcm, Nonce};
+use argon2::Argon2;
+use std::fs::File;
+use std::io::{BufRead, BufReader, BufWriter, Read, Write};
+use std::path::Path;
+
+const MAGIC: &[u8] = b"DOENC1";
+const STREAM_MAGIC: &[u8] = b"DOENC2";
+const SALT_LEN: usize = 16;
+const NONCE_LEN: usize = 12;
+const STREAM_NONCE_LEN: usize = 7; // the rest of the nonce is the chunk counter
+const CHUNK_SIZE: usize = 64 * 1024;
+const TAG_LEN: usize = 16;
+
+fn derive_key(passphrase: &str, salt: &[u8]) -> Result<[u8; 32], String> {
+    let mut key = [0u8; 32];
+    Argon2::default()
+        .hash_password_into(passphrase.as_bytes(), salt, &mut key)
+        .map_err(|e| format!("Key derivation failed: {}", e))?;
+    Ok(key)
+}
+
+pub fn encrypt(data: &[u8], passphrase: &str) -> Result<Vec<u8>, String> {
+    if passphrase.is_empty() {
+        return Err("An encryption passphrase is required".to_string());
+    }
+
+    let mut salt = [0u8; SALT_LEN];
+    let mut nonce = [0u8; NONCE_LEN];
+    OsRng.fill_bytes(&mut salt);
+    OsRng.fill_bytes(&mut nonce);
+
+    let key = derive_key(passphrase, &salt)?;
+    let cipher = Aes256Gcm::new_from_slice(&key).map_err(|e| e.to_string())?;
+    let ciphertext = cipher
+        .encrypt(Nonce::from_slice(&nonce), data)
+        .map_err(|_| "Encryption failed".to_string())?;
+
+    let mut out = Vec::with_capacity(MAGIC.len() + SALT_LEN + NONCE_LEN + ciphertext.len());
+    out.extend_from_slice(MAGIC);
+    out.extend_from_slice(&salt);
+    out.extend_from_slice(&nonce);
+    out.extend_from_slice(&ciphertext);
+    Ok(out)
+}
+
+pub fn decrypt(data: &[u8], passphrase: &str) -> Result<Vec<u8>, String> {
+    let body = data
+        .strip_prefix(MAGIC)
+        .ok_or_else(|| "Data is not encrypted by DropOut".to_string())?;
+    if body.len() < SALT_LEN + NONCE_LEN {
+        return Err("Encrypted data is truncated".to_string());
+    }
+    let (salt, rest) = body.split_at(SALT_LEN);
+    let (nonce, ciphertext) = rest.split_at(NONCE_LEN);
+
+    let key = derive_key(passphrase, salt)?;
+    let cipher = Aes256Gcm::new_from_slice(&key).map_err(|e| e.to_string())?;
+    cipher
+        .decrypt(Nonce::from_slice(nonce), ciphertext)
+        .map_err(|_| "Wrong passphrase or corrupted data".to_string())
+}
+
+/// Read until `buf` is full or the reader is exhausted
+fn read_chunk(reader: &mut impl Read, buf: &mut [u8]) -> std::io::Result<usize> {
+    let mut filled = 0;
+    while filled < buf.len() {
+        match reader.read(&mut buf[filled..]) {
+            Ok(0) => break,
+            Ok(n) => filled += n,
+            Err(e) if e.kind() == std::io::ErrorKind::Interrupted => {}
+            Err(e) => return Err(e),
+        }
+    }
+    Ok(filled)
+}
+
+/// Encrypt the file at `src` into `dst` chunk by chunk
+pub fn encrypt_file(src: &Path, dst: &Path, passphrase: &str) -> Result<(), String> {
+    if passphrase.is_empty() {
+        return Err("An encryption passphrase is required".to_string());
+    }
+
+    let mut salt = [0u8; SALT_LEN];
+    let mut nonce = [0u8; STREAM_NONCE_LEN];
+    OsRng.fill_bytes(&mut salt);
+    OsRng.fill_bytes(&mut nonce);
+
+    let key = derive_key(passphrase, &salt)?;
+    let cipher = Aes256Gcm::new_from_slice(&key).map_err(|e| e.to_string())?;
+    let mut encryptor = EncryptorBE32::from_aead(cipher, GenericArray::from_slice(&nonce));
+
+    let mut reader = BufReader::new(File::open(src).map_err(|e| e.to_string())?);
+    let mut writer = BufWriter::new(File::create(dst).map_err(|e| e.to_string())?);
+    for part in [STREAM_MAGIC, &salt, &nonce] {
+        writer.write_all(part).map_err(|e| e.to_string())?;
+    }
+
+    let mut buf = vec![0u8; CHUNK_SIZE];
+    loop {
+        let len = read_chunk(&mut reader, &mut buf).map_err(|e| e.to_string())?;
+        let last = len < CHUNK_SIZE || reader.fill_buf().map_err(|e| e.to_string())?.is_empty();
+        if last {
+            let chunk = encryptor
+                .encrypt_last(&buf[..len])
+                .map_err(|_| "Encryption failed".to_string())?;
+            writer.write_all(&chunk).map_err(|e| e.to_string())?;
+            break;
+        }
+        let chunk = encryptor
+            .encrypt_next(&buf[..len])
+            .map_err(|_| "Encryption failed".to_string())?;
+        writer.write_all(&chunk).map_err(|e| e.to_string())?;
+    }
+    writer.flush().map_err(|e| e.to_string())
+}
+
+/// Decrypt the file at `src` into `dst`. Also accepts the single-shot
+/// `DOENC1` format that older backups were written in.
+pub fn decrypt_file(src: &Path, dst: &Path, passphrase: &str) -> Result<(), String> {
+    let mut reader = BufReader::new(File::open(src).map_err(|e| e.to_string())?);
+    let mut magic = [0u8; 6];
+    let len = read_chunk(&mut reader, &mut magic).map_err(|e| e.to_string())?;
+    if &magic[..len] == MAGIC {
+        let mut data = magic.to_vec();
+        reader.read_to_end(&mut data).map_err(|e| e.to_string())?;
+        let plain = decrypt(&data, passphrase)?;
+        return std::fs::write(dst, plain).map_err(|e| e.to_string());
+    }
+    if &magic[..len] != STREAM_MAGIC {
+        return Err("Data is not encrypted by DropOut".to_string());
+    }
+
+    let mut salt = [0u8; SALT_LEN];
+    let mut nonce = [0u8; STREAM_NONCE_LEN];
+    reader
+        .read_exact(&mut salt)
+        .and_then(|()| reader.read_exact(&mut nonce))
+        .map_err(|_| "Encrypted data is truncated".to_string())?;
+
+    let key = derive_key(passphrase, &salt)?;
+    let cipher = Aes256Gcm::new_from_slice(&key).map_err(|e| e.to_string())?;
+    let mut decryptor = DecryptorBE32::from_aead(cipher, GenericArray::from_slice(&nonce));
+    let mut writer = BufWriter::new(File::create(dst).map_err(|e| e.to_string())?);
+
+    let mut buf = vec![0u8; CHUNK_SIZE + TAG_LEN];
+    loop {
+        let len = read_chunk(&mut reader, &mut buf).map_err(|e| e.to_string())?;
+        let last = len < buf.len() || reader.fill_buf().map_err(|e| e.to_string())?.is_empty();
+        if last {
+            let chunk = decryptor
+                .decrypt_last(&buf[..len])
+                .map_err(|_| "Wrong passphrase or corrupted data".to_string())?;
+            writer.write_all(&chunk).map_err(|e| e.to_string())?;
+            break;
+        }
+        let chunk = decryptor
+            .decrypt_next(&buf[..len])
+            .map_err(|_| "Wrong passphrase or corrupted data".to_string())?;
+        writer.write_all(&chunk).map_err(|e| e.to_string())?;
+    }
+    writer.flush().map_err(|e| e.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_roundtrip() {
+        let encrypted = encrypt(b"level.dat contents", "hunter2").unwrap();
+        assert!(encrypted.starts_with(MAGIC));
+        assert_eq!(
+            decrypt(&encrypted, "hunter2").unwrap(),
+            b"level.dat contents"
+        );
+    }
+
+    #[test]
+    fn test_wrong_passphrase() {
+        let encrypted = encrypt(b"secret", "correct").unwrap();
+        assert!(decrypt(&encrypted, "wrong").is_err());
+    }
+
+    #[test]
+    fn test_rejects_plain_data() {
+        assert!(decrypt(b"PK\x03\x04", "pass").is_err());
+        assert!(encrypt(b"data", "").is_err());
+    }
+
+    #[test]
+    fn test_file_roundtrip() {
+        let dir = std::env::temp_dir().join(format!("dropout-crypto-{}", uuid::Uuid::new_v4()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let (plain, encrypted, decrypted) = (
+            dir.join("plain"),
+            dir.join("encrypted"),
+            dir.join("decrypted"),
+        );
+
+        // Empty, exactly one chunk and a partial last chunk
+        for len in [0, CHUNK_SIZE, CHUNK_SIZE * 2 + 100] {
+            let data: Vec<u8> = (0..len).map(|i| (i % 251) as u8).collect();
+            std::fs::write(&plain, &data).unwrap();
+            encrypt_file(&plain, &encrypted, "hunter2").unwrap();
+            assert!(std::fs::read(&encrypted).unwrap().starts_with(STREAM_MAGIC));
+            decrypt_file(&encrypted, &decrypted, "hunter2").unwrap();
+            assert_eq!(std::fs::read(&decrypted).unwrap(), data);
+            assert!(decrypt_file(&encrypted, &decrypted, "wrong").is_err());
+        }
+
+        // Cutting off the last chunk is detected
+        let full = std::fs::read(&encrypted).unwrap();
+        let header = STREAM_MAGIC.len() + SALT_LEN + STREAM_NONCE_LEN;
+        std::fs::write(&encrypted, &full[..header + CHUNK_SIZE + TAG_LEN]).unwrap();
+        assert!(decrypt_file(&encrypted, &decrypted, "hunter2").is_err());
+
+        // Older single-shot backups still decrypt
+        std::fs::write(&encrypted, encrypt(b"level.dat", "hunter2").unwrap()).unwrap();
+        decrypt_file(&encrypted, &decrypted, "hunter2").unwrap();
+        assert_eq!(std::fs::read(&decrypted).unwrap(), b"level.dat");
+
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+}
@@ -0,0 +1,625 @@
+//! Cloud backup of instance folders.
+//!
+//! Selected folders of an instance (saves, config, ...) are zipped,
+//! encrypted with the configured passphrase and uploaded to a WebDAV or
+//! S3-compatible backend under `<prefix>/<instance id>/<backup id>.dobak`.
+//! The backup history is kept next to them in `<prefix>/index.json`; it only
+//! holds names, sizes and timestamps, never file contents. It isn't
+//! encrypted either, so a restore goes by what the decrypted archive holds.
+
+pub mod crypto;
+mod s3;
+mod webdav;
+
+use super::config::CloudSyncConfig;
+use super::instance::Instance;
+use serde::{Deserialize, Serialize};
+use std::fs::File;
+use std::io::{Read, Seek};
+use std::path::{Path, PathBuf};
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use ts_rs::TS;
+
+const INDEX_FILE: &str = "index.json";
+const BACKUP_EXTENSION: &str = "dobak";
+
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export, export_to = "sync.ts")]
+pub struct BackupEntry {
+    pub id: String,
+    pub instance_id: String,
+    pub instance_name: String,
+    pub created_at: i64,
+    pub size: u64,
+    pub folders: Vec<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+#[serde(rename_all = "camelCase")]
+struct BackupIndex {
+    backups: Vec<BackupEntry>,
+}
+
+/// Storage backend selected by `CloudSyncConfig::provider`
+pub enum SyncBackend {
+    WebDav(webdav::WebDavClient),
+    S3(s3::S3Client),
+}
+
+impl SyncBackend {
+    pub fn from_config(config: &CloudSyncConfig) -> Result<Self, String> {
+        match config.provider.as_str() {
+            "webdav" => {
+                if config.webdav_url.is_empty() {
+                    return Err("WebDAV URL is not configured".to_string());
+                }
+                Ok(Self::WebDav(webdav::WebDavClient::new(
+                    &config.webdav_url,
+                    config.webdav_username.clone(),
+                    config.webdav_password.clone(),
+                )))
+            }
+            "s3" => {
+                let (Some(access_key), Some(secret_key)) =
+                    (&config.s3_access_key, &config.s3_secret_key)
+                else {
+                    return Err("S3 credentials are not configured".to_string());
+                };
+                if config.s3_bucket.is_empty() {
+                    return Err("S3 bucket is not configured".to_string());
+                }
+                Ok(Self::S3(s3::S3Client::new(
+                    &config.s3_endpoint,
+                    &config.s3_region,
+                    &config.s3_bucket,
+                    access_key,
+                    secret_key,
+                    config.s3_path_style,
+                )))
+            }
+            "none" | "" => Err("Cloud backup is not configured".to_string()),
+            other => Err(format!("Unknown cloud backup provider: {}", other)),
+        }
+    }
+
+    pub async fn put(&self, path: &str, data: Vec<u8>) -> Result<(), String> {
+        match self {
+            Self::WebDav(client) => client.put(path, data).await,
+            Self::S3(client) => client.put(path, data).await,
+        }
+    }
+
+    /// Upload the file at `file` without reading it into memory
+    pub async fn put_file(&self, path: &str, file: &Path) -> Result<(), String> {
+        match self {
+            Self::WebDav(client) => client.put_file(path, file).await,
+            Self::S3(client) => client.put_file(path, file).await,
+        }
+    }
+
+    /// Download `path` into `file`; false if it doesn't exist
+    pub async fn get_file(&self, path: &str, file: &Path) -> Result<bool, String> {
+        match self {
+            Self::WebDav(client) => client.get_file(path, file).await,
+            Self::S3(client) => client.get_file(path, file).await,
+        }
+    }
+
+    pub async fn get(&self, path: &str) -> Result<Option<Vec<u8>>, String> {
+        match self {
+            Self::WebDav(client) => client.get(path).await,
+            Self::S3(client) => client.get(path).await,
+        }
+    }
+
+    pub async fn delete(&self, path: &str) -> Result<(), String> {
+        match self {
+            Self::WebDav(client) => client.delete(path).await,
+            Self::S3(client) => client.delete(path).await,
+        }
+    }
+}
+
+/// A request body that streams `path` from disk, and its length
+async fn file_body(path: &Path) -> Result<(reqwest::Body, u64), String> {
+    let file = tokio::fs::File::open(path)
+        .await
+        .map_err(|e| e.to_string())?;
+    let len = file.metadata().await.map_err(|e| e.to_string())?.len();
+    let stream = futures::stream::try_unfold(file, |mut file| async move {
+        let mut buf = vec![0u8; 64 * 1024];
+        let n = file.read(&mut buf).await?;
+        if n == 0 {
+            return Ok(None);
+        }
+        buf.truncate(n);
+        Ok::<_, std::io::Error>(Some((buf, file)))
+    });
+    Ok((reqwest::Body::wrap_stream(stream), len))
+}
+
+/// Write a response body to `path` as it arrives
+async fn save_body(mut response: reqwest::Response, path: &Path) -> Result<(), String> {
+    let mut file = tokio::fs::File::create(path)
+        .await
+        .map_err(|e| e.to_string())?;
+    while let Some(chunk) = response.chunk().await.map_err(|e| e.to_string())? {
+        file.write_all(&chunk).await.map_err(|e| e.to_string())?;
+    }
+    file.flush().await.map_err(|e| e.to_string())
+}
+
+/// Temporary files of one backup or restore, removed when dropped
+struct TempFiles(Vec<PathBuf>);
+
+impl Drop for TempFiles {
+    fn drop(&mut self) {
+        for path in &self.0 {
+            let _ = std::fs::remove_file(path);
+        }
+    }
+}
+
+fn remote_path(config: &CloudSyncConfig, name: &str) -> String {
+    let prefix = config.remote_prefix.trim_matches('/');
+    if prefix.is_empty() {
+        name.to_string()
+    } else {
+        format!("{}/{}", prefix, name)
+    }
+}
+
+fn backup_path(config: &CloudSyncConfig, entry: &BackupEntry) -> String {
+    remote_path(
+        config,
+        &format!("{}/{}.{}", entry.instance_id, entry.id, BACKUP_EXTENSION),
+    )
+}
+
+fn passphrase(config: &CloudSyncConfig) -> Result<&str, String> {
+    config
+        .passphrase
+        .as_deref()
+        .filter(|p| !p.is_empty())
+        .ok_or_else(|| "Set a backup passphrase before using cloud backup".to_string())
+}
+
+async fn load_index(
+    backend: &SyncBackend,
+    config: &CloudSyncConfig,
+) -> Result<BackupIndex, String> {
+    match backend.get(&remote_path(config, INDEX_FILE)).await? {
+        Some(bytes) => {
+            serde_json::from_slice(&bytes).map_err(|e| format!("Invalid backup index: {}", e))
+        }
+        None => Ok(BackupIndex::default()),
+    }
+}
+
+async fn save_index(
+    backend: &SyncBackend,
+    config: &CloudSyncConfig,
+    index: &BackupIndex,
+) -> Result<(), String> {
+    let content = serde_json::to_vec_pretty(index).map_err(|e| e.to_string())?;
+    backend.put(&remote_path(config, INDEX_FILE), content).await
+}
+
+/// A folder entry must name a single top-level file or folder of the instance
+pub fn is_valid_folder(name: &str) -> bool {
+    !name.is_empty() && name != "." && name != ".." && !name.contains('/') && !name.contains('\\')
+}
+
+/// Zip the given top-level folders/files of `game_dir` into the file `dest`
+pub fn archive_folders(game_dir: &Path, folders: &[String], dest: &Path) -> Result<(), String> {
+    fn add_file(
+        writer: &mut zip::ZipWriter<File>,
+        path: &Path,
+        name: &str,
+        options: zip::write::SimpleFileOptions,
+    ) -> Result<(), String> {
+        writer
+            .start_file(name, options)
+            .map_err(|e| e.to_string())?;
+        let mut file = File::open(path).map_err(|e| e.to_string())?;
+        std::io::copy(&mut file, writer).map_err(|e| e.to_string())?;
+        Ok(())
+    }
+
+    fn add_dir(
+        writer: &mut zip::ZipWriter<File>,
+        dir: &Path,
+        base: &Path,
+        options: zip::write::SimpleFileOptions,
+    ) -> Result<(), String> {
+        for entry in std::fs::read_dir(dir).map_err(|e| e.to_string())?.flatten() {
+            let path = entry.path();
+            let name = path
+                .strip_prefix(base)
+                .map_err(|e| e.to_string())?
+                .to_string_lossy()
+                .replace('\\', "/");
+            if path.is_dir() {
+                writer
+                    .add_directory(format!("{}/", name), options)
+                    .map_err(|e| e.to_string())?;
+                add_dir(writer, &path, base, options)?;
+            } else {
+                add_file(writer, &path, &name, options)?;
+            }
+        }
+        Ok(())
+    }
+
+    let options = zip::write::SimpleFileOptions::default()
+        .compression_method(zip::CompressionMethod::Deflated)
+        .large_file(true);
+    let mut writer = zip::ZipWriter::new(File::create(dest).map_err(|e| e.to_string())?);
+    for folder in folders {
+        let path = game_dir.join(folder);
+        if path.is_dir() {
+            writer
+                .add_directory(format!("{}/", folder), options)
+                .map_err(|e| e.to_string())?;
+            add_dir(&mut writer, &path, game_dir, options)?;
+        } else if path.is_file() {
+            add_file(&mut writer, &path, folder, options)?;
+        }
+    }
+    writer.finish().map_err(|e| e.to_string())?;
+    Ok(())
+}
+
+/// The top-level folders/files an archive holds
+fn archive_roots<R: Read + Seek>(zip: &zip::ZipArchive<R>) -> Vec<String> {
+    let mut roots: Vec<String> = zip
+        .file_names()
+        .filter_map(|name| name.split('/').next())
+        .filter(|root| is_valid_folder(root))
+        .map(str::to_string)
+        .collect();
+    roots.sort();
+    roots.dedup();
+    roots
+}
+
+/// Replace the folders of `game_dir` that the archive at `archive` holds
+/// with its contents, returning their names. Which folders are replaced is
+/// decided by the (authenticated) archive alone, and one the archive doesn't
+/// have is left as it is. The archive is extracted next to them first, so a
+/// corrupt backup leaves the current folders as they were.
+pub fn restore_folders(game_dir: &Path, archive: &Path) -> Result<Vec<String>, String> {
+    let file = File::open(archive).map_err(|e| e.to_string())?;
+    let mut zip = zip::ZipArchive::new(file).map_err(|e| format!("Invalid backup: {}", e))?;
+    let folders = archive_roots(&zip);
+    let roots: Vec<&str> = folders.iter().map(String::as_str).collect();
+
+    let id = uuid::Uuid::new_v4().simple().to_string();
+    let staging = game_dir.join(format!(".restore-{}", id));
+    let result = extract_folders(&mut zip, &staging, &roots).and_then(|()| {
+        swap_folders(
+            game_dir,
+            &staging,
+            &game_dir.join(format!(".restore-old-{}", id)),
+            &roots,
+        )
+    });
+    let _ = std::fs::remove_dir_all(&staging);
+    result.map(|()| folders)
+}
+
+/// Extract the entries of `zip` under `folders` into `dir`
+fn extract_folders<R: Read + Seek>(
+    zip: &mut zip::ZipArchive<R>,
+    dir: &Path,
+    folders: &[&str],
+) -> Result<(), String> {
+    std::fs::create_dir_all(dir).map_err(|e| e.to_string())?;
+    for i in 0..zip.len() {
+        let mut file = zip.by_index(i).map_err(|e| e.to_string())?;
+        let Some(relative) = file.enclosed_name() else {
+            continue;
+        };
+        let top = relative
+            .components()
+            .next()
+            .map(|c| c.as_os_str().to_string_lossy().to_string())
+            .unwrap_or_default();
+        if !folders.contains(&top.as_str()) {
+            continue;
+        }
+        let target = dir.join(relative);
+        if file.is_dir() {
+            std::fs::create_dir_all(&target).map_err(|e| e.to_string())?;
+            continue;
+        }
+        if let Some(parent) = target.parent() {
+            std::fs::create_dir_all(parent).map_err(|e| e.to_string())?;
+        }
+        let mut out = File::create(&target).map_err(|e| e.to_string())?;
+        std::io::copy(&mut file, &mut out).map_err(|e| format!("Invalid backup: {}", e))?;
+    }
+    Ok(())
+}
+
+/// Move `folders` of `game_dir` into `previous` and the extracted ones from
+/// `staging` into their place, putting the old ones back if a move fails.
+/// A folder with nothing extracted for it stays where it is.
+fn swap_folders(
+    game_dir: &Path,
+    staging: &Path,
+    previous: &Path,
+    folders: &[&str],
+) -> Result<(), String> {
+    std::fs::create_dir_all(previous).map_err(|e| e.to_string())?;
+    let mut swapped = Vec::new();
+    let mut result = Ok(());
+    for folder in folders {
+        let (current, old, new) = (
+            game_dir.join(folder),
+            previous.join(folder),
+            staging.join(folder),
+        );
+        if !new.exists() {
+            continue;
+        }
+        if current.exists()
+            && let Err(e) = std::fs::rename(&current, &old)
+        {
+            result = Err(format!("Failed to move {} aside: {}", folder, e));
+            break;
+        }
+        swapped.push(*folder);
+        if let Err(e) = std::fs::rename(&new, &current) {
+            result = Err(format!("Failed to restore {}: {}", folder, e));
+            break;
+        }
+    }
+
+    if result.is_err() {
+        for folder in swapped.iter().rev() {
+            let (current, old) = (game_dir.join(folder), previous.join(folder));
+            if !old.exists() {
+                continue;
+            }
+            let _ = if current.is_dir() {
+                std::fs::remove_dir_all(&current)
+            } else {
+                std::fs::remove_file(&current)
+            };
+            if std::fs::rename(&old, &current).is_err() {
+                // Keep the old copy where it is rather than lose it
+                return result.map_err(|e| {
+                    format!("{}; the previous files are in {}", e, previous.display())
+                });
+            }
+        }
+    }
+    let _ = std::fs::remove_dir_all(previous);
+    result
+}
+
+/// Back up `folders` of an instance (the configured defaults if empty)
+pub async fn backup_instance(
+    config: &CloudSyncConfig,
+    instance: &Instance,
+    folders: Vec<String>,
+) -> Result<BackupEntry, String> {
+    let backend = SyncBackend::from_config(config)?;
+    let passphrase = passphrase(config)?.to_string();
+    let folders = if folders.is_empty() {
+        config.folders.clone()
+    } else {
+        folders
+    };
+    if let Some(bad) = folders.iter().find(|f| !is_valid_folder(f)) {
+        return Err(format!("Invalid backup folder: {}", bad));
+    }
+
+    // Zip and encrypt through files next to the instance rather than memory
+    let id = uuid::Uuid::new_v4().simple().to_string();
+    let archive = instance.game_dir.join(format!(".backup-{}.zip", id));
+    let encrypted = instance
+        .game_dir
+        .join(format!(".backup-{}.{}", id, BACKUP_EXTENSION));
+    let _temp = TempFiles(vec![archive.clone(), encrypted.clone()]);
+
+    let game_dir = instance.game_dir.clone();
+    let archive_folders_list = folders.clone();
+    let (archive_path, encrypted_path) = (archive.clone(), encrypted.clone());
+    tokio::task::spawn_blocking(move || {
+        archive_folders(&game_dir, &archive_folders_list, &archive_path)?;
+        crypto::encrypt_file(&archive_path, &encrypted_path, &passphrase)?;
+        let _ = std::fs::remove_file(&archive_path);
+        Ok::<_, String>(())
+    })
+    .await
+    .map_err(|e| e.to_string())??;
+    let size = std::fs::metadata(&encrypted)
+        .map_err(|e| e.to_string())?
+        .len();
+
+    let created_at = chrono::Utc::now().timestamp();
+    let entry = BackupEntry {
+        id: format!(
+            "{}-{}",
+            created_at,
+            &uuid::Uuid::new_v4().simple().to_string()[..8]
+        ),
+        instance_id: instance.id.clone(),
+        instance_name: instance.name.clone(),
+        created_at,
+        size,
+        folders,
+    };
+
+    backend
+        .put_file(&backup_path(config, &entry), &encrypted)
+        .await?;
+    let mut index = load_index(&backend, config).await?;
+    index.backups.push(entry.clone());
+    save_index(&backend, config, &index).await?;
+
+    println!("[Sync] Backed up {} ({} bytes)", instance.name, entry.size);
+    Ok(entry)
+}
+
+/// Backup history, newest first, optionally limited to one instance
+pub async fn list_backups(
+    config: &CloudSyncConfig,
+    instance_id: Option<&str>,
+) -> Result<Vec<BackupEntry>, String> {
+    let backend = SyncBackend::from_config(config)?;
+    let mut backups: Vec<BackupEntry> = load_index(&backend, config)
+        .await?
+        .backups
+        .into_iter()
+        .filter(|b| instance_id.is_none_or(|id| b.instance_id == id))
+        .collect();
+    backups.sort_by_key(|b| std::cmp::Reverse(b.created_at));
+    Ok(backups)
+}
+
+/// Download, decrypt and unpack a backup into `instance`
+pub async fn restore_backup(
+    config: &CloudSyncConfig,
+    backup_id: &str,
+    instance: &Instance,
+) -> Result<BackupEntry, String> {
+    let backend = SyncBackend::from_config(config)?;
+    let passphrase = passphrase(config)?.to_string();
+    let entry = load_index(&backend, config)
+        .await?
+        .backups
+        .into_iter()
+        .find(|b| b.id == backup_id)
+        .ok_or_else(|| format!("Backup {} not found", backup_id))?;
+
+    let id = uuid::Uuid::new_v4().simple().to_string();
+    let encrypted = instance
+        .game_dir
+        .join(format!(".restore-{}.{}", id, BACKUP_EXTENSION));
+    let archive = instance.game_dir.join(format!(".restore-{}.zip", id));
+    let _temp = TempFiles(vec![encrypted.clone(), archive.clone()]);
+
+    if !backend
+        .get_file(&backup_path(config, &entry), &encrypted)
+        .await?
+    {
+        return Err(format!("Backup {} is missing from the server", backup_id));
+    }
+
+    // The index isn't authenticated, so the folders to replace come from
+    // the decrypted archive rather than `entry.folders`
+    let game_dir = instance.game_dir.clone();
+    let folders = tokio::task::spawn_blocking(move || {
+        crypto::decrypt_file(&encrypted, &archive, &passphrase)?;
+        restore_folders(&game_dir, &archive)
+    })
+    .await
+    .map_err(|e| e.to_string())??;
+
+    println!("[Sync] Restored backup {} into {}", entry.id, instance.name);
+    Ok(BackupEntry { folders, ..entry })
+}
+
+pub async fn delete_backup(config: &CloudSyncConfig, backup_id: &str) -> Result<(), String> {
+    let backend = SyncBackend::from_config(config)?;
+    let mut index = load_index(&backend, config).await?;
+    let Some(pos) = index.backups.iter().position(|b| b.id == backup_id) else {
+        return Err(format!("Backup {} not found", backup_id));
+    };
+    let entry = index.backups.remove(pos);
+    backend.delete(&backup_path(config, &entry)).await?;
+    save_index(&backend, config, &index).await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_is_valid_folder() {
+        assert!(is_valid_folder("saves"));
+        assert!(is_valid_folder("options.txt"));
+        assert!(!is_valid_folder(".."));
+        assert!(!is_valid_folder("saves/../.."));
+        assert!(!is_valid_folder(""));
+    }
+
+    #[test]
+    fn test_archive_roundtrip() {
+        let src = std::env::temp_dir().join(format!("dropout-sync-src-{}", uuid::Uuid::new_v4()));
+        let dst = std::env::temp_dir().join(format!("dropout-sync-dst-{}", uuid::Uuid::new_v4()));
+        std::fs::create_dir_all(src.join("saves/world")).unwrap();
+        std::fs::write(src.join("saves/world/level.dat"), b"level").unwrap();
+        std::fs::write(src.join("options.txt"), b"fov:70").unwrap();
+        std::fs::create_dir_all(dst.join("saves/old")).unwrap();
+
+        let folders = vec!["saves".to_string(), "options.txt".to_string()];
+        let archive = src.join("backup.zip");
+        archive_folders(&src, &folders, &archive).unwrap();
+        assert_eq!(
+            restore_folders(&dst, &archive).unwrap(),
+            folders_sorted(&folders)
+        );
+
+        assert_eq!(
+            std::fs::read(dst.join("saves/world/level.dat")).unwrap(),
+            b"level"
+        );
+        assert_eq!(std::fs::read(dst.join("options.txt")).unwrap(), b"fov:70");
+        assert!(!dst.join("saves/old").exists());
+
+        assert_eq!(std::fs::read_dir(&dst).unwrap().count(), 2);
+
+        // A corrupt backup changes nothing
+        let mut corrupt = std::fs::read(&archive).unwrap();
+        let len = corrupt.len();
+        corrupt[len / 3..len / 2].fill(0);
+        std::fs::write(&archive, corrupt).unwrap();
+        std::fs::write(dst.join("options.txt"), b"fov:90").unwrap();
+        assert!(restore_folders(&dst, &archive).is_err());
+        assert_eq!(std::fs::read(dst.join("options.txt")).unwrap(), b"fov:90");
+        assert!(dst.join("saves/world/level.dat").exists());
+        assert_eq!(std::fs::read_dir(&dst).unwrap().count(), 2);
+
+        let _ = std::fs::remove_dir_all(&src);
+        let _ = std::fs::remove_dir_all(&dst);
+    }
+
+    fn folders_sorted(folders: &[String]) -> Vec<String> {
+        let mut folders = folders.to_vec();
+        folders.sort();
+        folders
+    }
+
+    #[test]
+    fn test_restore_keeps_folders_missing_from_archive() {
+        let src = std::env::temp_dir().join(format!("dropout-sync-src-{}", uuid::Uuid::new_v4()));
+        let dst = std::env::temp_dir().join(format!("dropout-sync-dst-{}", uuid::Uuid::new_v4()));
+        std::fs::create_dir_all(&src).unwrap();
+        std::fs::write(src.join("options.txt"), b"fov:70").unwrap();
+        std::fs::create_dir_all(dst.join("saves/world")).unwrap();
+        std::fs::write(dst.join("saves/world/level.dat"), b"level").unwrap();
+
+        // Backed up with "saves" listed, but the instance had none
+        let archive = src.join("backup.zip");
+        let folders = vec!["saves".to_string(), "options.txt".to_string()];
+        archive_folders(&src, &folders, &archive).unwrap();
+        assert_eq!(
+            restore_folders(&dst, &archive).unwrap(),
+            vec!["options.txt"]
+        );
+
+        assert_eq!(std::fs::read(dst.join("options.txt")).unwrap(), b"fov:70");
+        assert_eq!(
+            std::fs::read(dst.join("saves/world/level.dat")).unwrap(),
+            b"level"
+        );
+
+        let _ = std::fs::remove_dir_all(&src);
+        let _ = std::fs::remove_dir_all(&dst);
+    }
+}
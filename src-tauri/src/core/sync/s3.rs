@@ -0,0 +1,276 @@
+//! Minimal S3-compatible client (PUT/GET/DELETE object) signed with AWS
+//! Signature Version 4. Works with AWS S3, MinIO, Cloudflare R2, Backblaze B2
+//! and other providers exposing the S3 API.
+
+use chrono::{DateTime, Utc};
+use hmac::{Hmac, Mac};
+use reqwest::{Method, StatusCode};
+use sha2::{Digest, Sha256};
+use std::path::Path;
+
+type HmacSha256 = Hmac<Sha256>;
+
+pub struct S3Client {
+    client: reqwest::Client,
+    endpoint: String,
+    region: String,
+    bucket: String,
+    access_key: String,
+    secret_key: String,
+    path_style: bool,
+}
+
+fn hmac(key: &[u8], data: &str) -> Vec<u8> {
+    let mut mac = HmacSha256::new_from_slice(key).expect("HMAC accepts any key length");
+    mac.update(data.as_bytes());
+    mac.finalize().into_bytes().to_vec()
+}
+
+fn sha256_hex(data: &[u8]) -> String {
+    hex::encode(Sha256::digest(data))
+}
+
+/// Derive the SigV4 signing key for a date (`YYYYMMDD`), region and service
+pub fn signing_key(secret_key: &str, date: &str, region: &str, service: &str) -> Vec<u8> {
+    let k_date = hmac(format!("AWS4{}", secret_key).as_bytes(), date);
+    let k_region = hmac(&k_date, region);
+    let k_service = hmac(&k_region, service);
+    hmac(&k_service, "aws4_request")
+}
+
+/// Percent-encode an object key per SigV4 rules, keeping `/` separators
+pub fn encode_key(key: &str) -> String {
+    let mut out = String::new();
+    for byte in key.bytes() {
+        match byte {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'_' | b'.' | b'~' | b'/' => {
+                out.push(byte as char)
+            }
+            _ => out.push_str(&format!("%{:02X}", byte)),
+        }
+    }
+    out
+}
+
+impl S3Client {
+    pub fn new(
+        endpoint: &str,
+        region: &str,
+        bucket: &str,
+        access_key: &str,
+        secret_key: &str,
+        path_style: bool,
+    ) -> Self {
+        Self {
+            client: reqwest::Client::new(),
+            endpoint: endpoint.trim_end_matches('/').to_string(),
+            region: if region.is_empty() {
+                "us-east-1".to_string()
+            } else {
+                region.to_string()
+            },
+            bucket: bucket.to_string(),
+            access_key: access_key.to_string(),
+            secret_key: secret_key.to_string(),
+            path_style,
+        }
+    }
+
+    /// (host, canonical URI, full URL) for an object key
+    fn locate(&self, key: &str) -> Result<(String, String, String), String> {
+        let (scheme, host) = self
+            .endpoint
+            .split_once("://")
+            .ok_or_else(|| format!("Invalid S3 endpoint: {}", self.endpoint))?;
+        let key = encode_key(key.trim_start_matches('/'));
+        if self.path_style {
+            let uri = format!("/{}/{}", self.bucket, key);
+            Ok((
+                host.to_string(),
+                uri.clone(),
+                format!("{}://{}{}", scheme, host, uri),
+            ))
+        } else {
+            let host = format!("{}.{}", self.bucket, host);
+            let uri = format!("/{}", key);
+            Ok((
+                host.clone(),
+                uri.clone(),
+                format!("{}://{}{}", scheme, host, uri),
+            ))
+        }
+    }
+
+    /// A request signed for a body with the given SHA-256; the caller
+    /// attaches the body itself
+    fn signed_request(
+        &self,
+        method: Method,
+        key: &str,
+        payload_hash: &str,
+        now: DateTime<Utc>,
+    ) -> Result<reqwest::RequestBuilder, String> {
+        let (host, uri, url) = self.locate(key)?;
+        let amz_date = now.format("%Y%m%dT%H%M%SZ").to_string();
+        let date = now.format("%Y%m%d").to_string();
+
+        let canonical_headers = format!(
+            "host:{}\nx-amz-content-sha256:{}\nx-amz-date:{}\n",
+            host, payload_hash, amz_date
+        );
+        let signed_headers = "host;x-amz-content-sha256;x-amz-date";
+        let canonical_request = format!(
+            "{}\n{}\n\n{}\n{}\n{}",
+            method.as_str(),
+            uri,
+            canonical_headers,
+            signed_headers,
+            payload_hash
+        );
+
+        let scope = format!("{}/{}/s3/aws4_request", date, self.region);
+        let string_to_sign = format!(
+            "AWS4-HMAC-SHA256\n{}\n{}\n{}",
+            amz_date,
+            scope,
+            sha256_hex(canonical_request.as_bytes())
+        );
+        let signature = hex::encode(hmac(
+            &signing_key(&self.secret_key, &date, &self.region, "s3"),
+            &string_to_sign,
+        ));
+        let authorization = format!(
+            "AWS4-HMAC-SHA256 Credential={}/{}, SignedHeaders={}, Signature={}",
+            self.access_key, scope, signed_headers, signature
+        );
+
+        Ok(self
+            .client
+            .request(method, url)
+            .header("x-amz-content-sha256", payload_hash)
+            .header("x-amz-date", amz_date)
+            .header("Authorization", authorization))
+    }
+
+    pub async fn put(&self, key: &str, data: Vec<u8>) -> Result<(), String> {
+        let response = self
+            .signed_request(Method::PUT, key, &sha256_hex(&data), Utc::now())?
+            .body(data)
+            .send()
+            .await
+            .map_err(|e| format!("S3 request failed: {}", e))?;
+        if !response.status().is_success() {
+            return Err(format!("S3 PUT {} failed: {}", key, response.status()));
+        }
+        Ok(())
+    }
+
+    pub async fn put_file(&self, key: &str, file: &Path) -> Result<(), String> {
+        let path = file.to_path_buf();
+        let payload_hash = tokio::task::spawn_blocking(move || {
+            let mut hasher = Sha256::new();
+            let mut reader = std::fs::File::open(&path).map_err(|e| e.to_string())?;
+            std::io::copy(&mut reader, &mut hasher).map_err(|e| e.to_string())?;
+            Ok::<_, String>(hex::encode(hasher.finalize()))
+        })
+        .await
+        .map_err(|e| e.to_string())??;
+        let (body, len) = super::file_body(file).await?;
+        let response = self
+            .signed_request(Method::PUT, key, &payload_hash, Utc::now())?
+            .header(reqwest::header::CONTENT_LENGTH, len)
+            .body(body)
+            .send()
+            .await
+            .map_err(|e| format!("S3 request failed: {}", e))?;
+        if !response.status().is_success() {
+            return Err(format!("S3 PUT {} failed: {}", key, response.status()));
+        }
+        Ok(())
+    }
+
+    pub async fn get_file(&self, key: &str, file: &Path) -> Result<bool, String> {
+        let response = self
+            .signed_request(Method::GET, key, &sha256_hex(&[]), Utc::now())?
+            .send()
+            .await
+            .map_err(|e| format!("S3 request failed: {}", e))?;
+        if response.status() == StatusCode::NOT_FOUND {
+            return Ok(false);
+        }
+        if !response.status().is_success() {
+            return Err(format!("S3 GET {} failed: {}", key, response.status()));
+        }
+        super::save_body(response, file).await?;
+        Ok(true)
+    }
+
+    pub async fn get(&self, key: &str) -> Result<Option<Vec<u8>>, String> {
+        let response = self
+            .signed_request(Method::GET, key, &sha256_hex(&[]), Utc::now())?
+            .send()
+            .await
+            .map_err(|e| format!("S3 request failed: {}", e))?;
+        if response.status() == StatusCode::NOT_FOUND {
+            return Ok(None);
+        }
+        if !response.status().is_success() {
+            return Err(format!("S3 GET {} failed: {}", key, response.status()));
+        }
+        let bytes = response.bytes().await.map_err(|e| e.to_string())?;
+        Ok(Some(bytes.to_vec()))
+    }
+
+    pub async fn delete(&self, key: &str) -> Result<(), String> {
+        let response = self
+            .signed_request(Method::DELETE, key, &sha256_hex(&[]), Utc::now())?
+            .send()
+            .await
+            .map_err(|e| format!("S3 request failed: {}", e))?;
+        let status = response.status();
+        if !status.is_success() && status != StatusCode::NOT_FOUND {
+            return Err(format!("S3 DELETE {} failed: {}", key, status));
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_signing_key() {
+        // Example from the AWS Signature Version 4 documentation
+        let key = signing_key(
+            "wJalrXUtnFEMI/K7MDENG+bPxRfiCYEXAMPLEKEY",
+            "20120215",
+            "us-east-1",
+            "iam",
+        );
+        assert_eq!(
+            hex::encode(key),
+            "f4780e2d9f65fa895f9c67b32ce1baf0b0d8a43505a000a1a9e090d414db404d"
+        );
+    }
+
+    #[test]
+    fn test_encode_key() {
+        assert_eq!(encode_key("dropout/a b/c+d.bin"), "dropout/a%20b/c%2Bd.bin");
+    }
+
+    #[test]
+    fn test_locate() {
+        let path_style = S3Client::new("http://localhost:9000", "", "backups", "a", "s", true);
+        let (host, uri, url) = path_style.locate("dropout/x.bin").unwrap();
+        assert_eq!(host, "localhost:9000");
+        assert_eq!(uri, "/backups/dropout/x.bin");
+        assert_eq!(url, "http://localhost:9000/backups/dropout/x.bin");
+
+        let virtual_host =
+            S3Client::new("https://s3.amazonaws.com", "", "backups", "a", "s", false);
+        let (host, _, url) = virtual_host.locate("x.bin").unwrap();
+        assert_eq!(host, "backups.s3.amazonaws.com");
+        assert_eq!(url, "https://backups.s3.amazonaws.com/x.bin");
+    }
+}
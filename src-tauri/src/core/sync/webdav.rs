@@ -0,0 +1,130 @@
+//! Minimal WebDAV client (PUT/GET/DELETE plus MKCOL for parent folders).
+
+use reqwest::{Method, StatusCode};
+use std::path::Path;
+
+pub struct WebDavClient {
+    client: reqwest::Client,
+    base_url: String,
+    username: Option<String>,
+    password: Option<String>,
+}
+
+impl WebDavClient {
+    pub fn new(base_url: &str, username: Option<String>, password: Option<String>) -> Self {
+        Self {
+            client: reqwest::Client::new(),
+            base_url: base_url.trim_end_matches('/').to_string(),
+            username: username.filter(|u| !u.is_empty()),
+            password,
+        }
+    }
+
+    fn url(&self, path: &str) -> String {
+        format!("{}/{}", self.base_url, path.trim_start_matches('/'))
+    }
+
+    fn request(&self, method: Method, path: &str) -> reqwest::RequestBuilder {
+        let request = self.client.request(method, self.url(path));
+        match &self.username {
+            Some(username) => request.basic_auth(username, self.password.as_ref()),
+            None => request,
+        }
+    }
+
+    /// Create every parent collection of `path`; existing ones are fine
+    async fn ensure_parents(&self, path: &str) -> Result<(), String> {
+        let segments: Vec<&str> = path.split('/').filter(|s| !s.is_empty()).collect();
+        let mut current = String::new();
+        for segment in &segments[..segments.len().saturating_sub(1)] {
+            current.push_str(segment);
+            current.push('/');
+            let response = self
+                .request(Method::from_bytes(b"MKCOL").unwrap(), &current)
+                .send()
+                .await
+                .map_err(|e| format!("WebDAV request failed: {}", e))?;
+            // 405 means the collection already exists
+            let status = response.status();
+            if !status.is_success() && status != StatusCode::METHOD_NOT_ALLOWED {
+                return Err(format!("WebDAV MKCOL {} failed: {}", current, status));
+            }
+        }
+        Ok(())
+    }
+
+    pub async fn put(&self, path: &str, data: Vec<u8>) -> Result<(), String> {
+        self.ensure_parents(path).await?;
+        let response = self
+            .request(Method::PUT, path)
+            .body(data)
+            .send()
+            .await
+            .map_err(|e| format!("WebDAV request failed: {}", e))?;
+        if !response.status().is_success() {
+            return Err(format!("WebDAV PUT {} failed: {}", path, response.status()));
+        }
+        Ok(())
+    }
+
+    pub async fn put_file(&self, path: &str, file: &Path) -> Result<(), String> {
+        self.ensure_parents(path).await?;
+        let (body, len) = super::file_body(file).await?;
+        let response = self
+            .request(Method::PUT, path)
+            .header(reqwest::header::CONTENT_LENGTH, len)
+            .body(body)
+            .send()
+            .await
+            .map_err(|e| format!("WebDAV request failed: {}", e))?;
+        if !response.status().is_success() {
+            return Err(format!("WebDAV PUT {} failed: {}", path, response.status()));
+        }
+        Ok(())
+    }
+
+    pub async fn get_file(&self, path: &str, file: &Path) -> Result<bool, String> {
+        let response = self
+            .request(Method::GET, path)
+            .send()
+            .await
+            .map_err(|e| format!("WebDAV request failed: {}", e))?;
+        if response.status() == StatusCode::NOT_FOUND {
+            return Ok(false);
+        }
+        if !response.status().is_success() {
+            return Err(format!("WebDAV GET {} failed: {}", path, response.status()));
+        }
+        super::save_body(response, file).await?;
+        Ok(true)
+    }
+
+    pub async fn get(&self, path: &str) -> Result<Option<Vec<u8>>, String> {
+        let response = self
+            .request(Method::GET, path)
+            .send()
+            .await
+            .map_err(|e| format!("WebDAV request failed: {}", e))?;
+        if response.status() == StatusCode::NOT_FOUND {
+            return Ok(None);
+        }
+        if !response.status().is_success() {
+            return Err(format!("WebDAV GET {} failed: {}", path, response.status()));
+        }
+        let bytes = response.bytes().await.map_err(|e| e.to_string())?;
+        Ok(Some(bytes.to_vec()))
+    }
+
+    pub async fn delete(&self, path: &str) -> Result<(), String> {
+        let response = self
+            .request(Method::DELETE, path)
+            .send()
+            .await
+            .map_err(|e| format!("WebDAV request failed: {}", e))?;
+        let status = response.status();
+        if !status.is_success() && status != StatusCode::NOT_FOUND {
+            return Err(format!("WebDAV DELETE {} failed: {}", path, status));
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,214 @@
+//! Opt-in local usage statistics.
+//!
+//! When `usage_stats_enabled` is set, launch durations, download speeds and
+//! failure categories are appended to `<app_data>/usage_stats.jsonl`. Nothing
+//! is ever sent over the network; users can view the summary or attach it to
+//! bug reports.
+
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::io::{BufRead, Write};
+use std::path::PathBuf;
+use tauri::{AppHandle, Manager};
+use ts_rs::TS;
+
+use super::config::ConfigState;
+
+const RECENT_EVENTS: usize = 50;
+
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export, export_to = "usage_stats.ts")]
+pub struct UsageEvent {
+    pub timestamp: i64,
+    pub kind: String, // "launch" | "download" | "failure"
+    pub instance_id: Option<String>,
+    pub duration_ms: Option<u64>,
+    pub bytes: Option<u64>,
+    pub category: Option<String>, // failure category, see `classify_failure`
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export, export_to = "usage_stats.ts")]
+pub struct UsageStats {
+    pub enabled: bool,
+    pub launches: u64,
+    pub failures: u64,
+    pub average_launch_ms: Option<u64>,
+    pub average_download_bytes_per_sec: Option<u64>,
+    pub failures_by_category: BTreeMap<String, u64>,
+    pub recent: Vec<UsageEvent>, // newest first
+}
+
+impl UsageEvent {
+    fn new(kind: &str, instance_id: Option<&str>) -> Self {
+        Self {
+            timestamp: chrono::Utc::now().timestamp(),
+            kind: kind.to_string(),
+            instance_id: instance_id.map(str::to_string),
+            duration_ms: None,
+            bytes: None,
+            category: None,
+        }
+    }
+
+    pub fn launch(instance_id: &str, duration_ms: u64) -> Self {
+        Self {
+            duration_ms: Some(duration_ms),
+            ..Self::new("launch", Some(instance_id))
+        }
+    }
+
+    pub fn download(bytes: u64, duration_ms: u64) -> Self {
+        Self {
+            bytes: Some(bytes),
+            duration_ms: Some(duration_ms),
+            ..Self::new("download", None)
+        }
+    }
+
+    pub fn failure(instance_id: Option<&str>, error: &str) -> Self {
+        Self {
+            category: Some(classify_failure(error).to_string()),
+            ..Self::new("failure", instance_id)
+        }
+    }
+}
+
+/// Coarse failure category, so no paths or account names end up in the log
+pub fn classify_failure(error: &str) -> &'static str {
+    let error = error.to_lowercase();
+    if error.contains("account") || error.contains("login") || error.contains("token") {
+        "auth"
+    } else if error.contains("java") {
+        "java"
+    } else if error.contains("checksum") || error.contains("sha1") || error.contains("sha256") {
+        "integrity"
+    } else if error.contains("download") || error.contains("request") || error.contains("http") {
+        "network"
+    } else if error.contains("spawn") || error.contains("sandbox") || error.contains("process") {
+        "process"
+    } else if error.contains("version") || error.contains("not found") {
+        "missing"
+    } else {
+        "other"
+    }
+}
+
+fn stats_path(app_handle: &AppHandle) -> Result<PathBuf, String> {
+    Ok(app_handle
+        .path()
+        .app_data_dir()
+        .map_err(|e| e.to_string())?
+        .join("usage_stats.jsonl"))
+}
+
+fn is_enabled(app_handle: &AppHandle) -> bool {
+    app_handle
+        .try_state::<ConfigState>()
+        .is_some_and(|state| state.config.lock().unwrap().usage_stats_enabled)
+}
+
+/// Append an event if the user opted in; errors are only logged
+pub fn record(app_handle: &AppHandle, event: UsageEvent) {
+    if !is_enabled(app_handle) {
+        return;
+    }
+    let result = stats_path(app_handle).and_then(|path| {
+        let mut line = serde_json::to_string(&event).map_err(|e| e.to_string())?;
+        line.push('\n');
+        std::fs::OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&path)
+            .and_then(|mut file| file.write_all(line.as_bytes()))
+            .map_err(|e| e.to_string())
+    });
+    if let Err(e) = result {
+        eprintln!("[UsageStats] Failed to record event: {}", e);
+    }
+}
+
+/// Aggregate recorded events (oldest first)
+pub fn summarize(events: &[UsageEvent]) -> UsageStats {
+    let mut stats = UsageStats::default();
+    let mut launch_ms = Vec::new();
+    let (mut download_bytes, mut download_ms) = (0u64, 0u64);
+
+    for event in events {
+        match event.kind.as_str() {
+            "launch" => {
+                stats.launches += 1;
+                launch_ms.extend(event.duration_ms);
+            }
+            "download" => {
+                if let (Some(bytes), Some(ms)) = (event.bytes, event.duration_ms) {
+                    download_bytes += bytes;
+                    download_ms += ms;
+                }
+            }
+            "failure" => {
+                stats.failures += 1;
+                let category = event.category.clone().unwrap_or_else(|| "other".into());
+                *stats.failures_by_category.entry(category).or_default() += 1;
+            }
+            _ => {}
+        }
+    }
+
+    if !launch_ms.is_empty() {
+        stats.average_launch_ms = Some(launch_ms.iter().sum::<u64>() / launch_ms.len() as u64);
+    }
+    stats.average_download_bytes_per_sec = (download_bytes * 1000).checked_div(download_ms);
+    stats.recent = events.iter().rev().take(RECENT_EVENTS).cloned().collect();
+    stats
+}
+
+pub fn load_stats(app_handle: &AppHandle) -> Result<UsageStats, String> {
+    let path = stats_path(app_handle)?;
+    let events: Vec<UsageEvent> = match std::fs::File::open(&path) {
+        Ok(file) => std::io::BufReader::new(file)
+            .lines()
+            .map_while(Result::ok)
+            .filter_map(|line| serde_json::from_str(&line).ok())
+            .collect(),
+        Err(_) => Vec::new(),
+    };
+    Ok(UsageStats {
+        enabled: is_enabled(app_handle),
+        ..summarize(&events)
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_classify_failure() {
+        assert_eq!(classify_failure("No active account found"), "auth");
+        assert_eq!(classify_failure("Java 17 not found"), "java");
+        assert_eq!(classify_failure("Checksum mismatch for 17"), "integrity");
+        assert_eq!(classify_failure("Request error: timed out"), "network");
+        assert_eq!(classify_failure("something else"), "other");
+    }
+
+    #[test]
+    fn test_summarize() {
+        let events = vec![
+            UsageEvent::launch("a", 2000),
+            UsageEvent::launch("a", 4000),
+            UsageEvent::download(3_000_000, 1000),
+            UsageEvent::download(1_000_000, 1000),
+            UsageEvent::failure(Some("a"), "Request error: timed out"),
+        ];
+        let stats = summarize(&events);
+        assert_eq!(stats.launches, 2);
+        assert_eq!(stats.failures, 1);
+        assert_eq!(stats.average_launch_ms, Some(3000));
+        assert_eq!(stats.average_download_bytes_per_sec, Some(2_000_000));
+        assert_eq!(stats.failures_by_category.get("network"), Some(&1));
+        assert_eq!(stats.recent[0].kind, "failure");
+    }
+}
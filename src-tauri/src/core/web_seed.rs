@@ -0,0 +1,483 @@
+//! Web seed downloads for large modpack archives.
+//!
+//! Packs may publish their archive as a `.torrent` file instead of (or next
+//! to) a plain HTTP URL. The launcher does not bundle a BitTorrent engine and
+//! never talks to peers or trackers: the archive is fetched from the
+//! torrent's web seeds (BEP 19, the `url-list` key), which are plain HTTP
+//! mirrors of its content, or the pack's HTTP fallback. The download goes
+//! through [`download_files`](super::downloader::download_files) so it shows
+//! up in the regular progress events.
+//!
+//! Mirrors are not trusted: the downloaded file is checked piece by piece
+//! against the hashes in the torrent's info dictionary. The info hash is the
+//! SHA-1 of that dictionary exactly as it appears in the `.torrent`, so it
+//! can be compared with a hash the pack publishes elsewhere. Only
+//! single-file torrents are supported.
+
+use super::download_audit::DownloadAudit;
+use super::downloader::{DownloadConcurrency, DownloadTask, download_files};
+use serde::{Deserialize, Serialize};
+use sha1::{Digest, Sha1};
+use std::io::Read;
+use std::path::{Path, PathBuf};
+use tauri::Window;
+use ts_rs::TS;
+
+/// `.torrent` files larger than this are refused
+const MAX_TORRENT_SIZE: usize = 8 * 1024 * 1024;
+/// Bencode nesting deeper than this is refused
+const MAX_DEPTH: usize = 32;
+
+/// Where a modpack archive can be downloaded from
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+#[serde(tag = "type", rename_all = "camelCase")]
+#[ts(export, export_to = "web_seed.ts")]
+pub enum PackSource {
+    Http {
+        url: String,
+        sha1: Option<String>,
+    },
+    #[serde(rename_all = "camelCase")]
+    WebSeed {
+        /// URL of the `.torrent` file describing the archive
+        torrent_url: String,
+        /// Expected info hash (hex), if the pack publishes one
+        info_hash: Option<String>,
+        /// Plain HTTP URL used if no web seed works
+        fallback_url: Option<String>,
+    },
+}
+
+/// A bencoded value, borrowing from the input
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Bencode<'a> {
+    Int(i64),
+    Bytes(&'a [u8]),
+    List(Vec<Bencode<'a>>),
+    /// Entries with the raw bytes of each value, which the info hash needs
+    Dict(Vec<(&'a [u8], Bencode<'a>, &'a [u8])>),
+}
+
+impl<'a> Bencode<'a> {
+    fn get(&self, key: &str) -> Option<&Bencode<'a>> {
+        self.entry(key).map(|(value, _)| value)
+    }
+
+    fn entry(&self, key: &str) -> Option<(&Bencode<'a>, &'a [u8])> {
+        match self {
+            Self::Dict(entries) => entries
+                .iter()
+                .find(|(k, _, _)| *k == key.as_bytes())
+                .map(|(_, value, raw)| (value, *raw)),
+            _ => None,
+        }
+    }
+
+    fn as_int(&self) -> Option<i64> {
+        match self {
+            Self::Int(n) => Some(*n),
+            _ => None,
+        }
+    }
+
+    fn as_bytes(&self) -> Option<&'a [u8]> {
+        match self {
+            Self::Bytes(bytes) => Some(bytes),
+            _ => None,
+        }
+    }
+
+    fn as_str(&self) -> Option<&'a str> {
+        self.as_bytes().and_then(|b| std::str::from_utf8(b).ok())
+    }
+}
+
+/// Parse the value starting at `data[*pos]`, leaving `pos` after it
+fn parse_value<'a>(data: &'a [u8], pos: &mut usize, depth: usize) -> Result<Bencode<'a>, String> {
+    let invalid = || "Invalid torrent file".to_string();
+    if depth > MAX_DEPTH {
+        return Err(invalid());
+    }
+    match data.get(*pos).copied().ok_or_else(invalid)? {
+        b'i' => {
+            let end = *pos
+                + data[*pos..]
+                    .iter()
+                    .position(|&b| b == b'e')
+                    .ok_or_else(invalid)?;
+            let n = std::str::from_utf8(&data[*pos + 1..end])
+                .ok()
+                .and_then(|s| s.parse().ok())
+                .ok_or_else(invalid)?;
+            *pos = end + 1;
+            Ok(Bencode::Int(n))
+        }
+        b'l' => {
+            *pos += 1;
+            let mut items = Vec::new();
+            while data.get(*pos).copied().ok_or_else(invalid)? != b'e' {
+                items.push(parse_value(data, pos, depth + 1)?);
+            }
+            *pos += 1;
+            Ok(Bencode::List(items))
+        }
+        b'd' => {
+            *pos += 1;
+            let mut entries = Vec::new();
+            while data.get(*pos).copied().ok_or_else(invalid)? != b'e' {
+                let key = parse_value(data, pos, depth + 1)?
+                    .as_bytes()
+                    .ok_or_else(invalid)?;
+                let start = *pos;
+                let value = parse_value(data, pos, depth + 1)?;
+                entries.push((key, value, &data[start..*pos]));
+            }
+            *pos += 1;
+            Ok(Bencode::Dict(entries))
+        }
+        b'0'..=b'9' => {
+            let colon = *pos
+                + data[*pos..]
+                    .iter()
+                    .position(|&b| b == b':')
+                    .ok_or_else(invalid)?;
+            let len: usize = std::str::from_utf8(&data[*pos..colon])
+                .ok()
+                .and_then(|s| s.parse().ok())
+                .ok_or_else(invalid)?;
+            let end = (colon + 1).checked_add(len).ok_or_else(invalid)?;
+            let bytes = data.get(colon + 1..end).ok_or_else(invalid)?;
+            *pos = end;
+            Ok(Bencode::Bytes(bytes))
+        }
+        _ => Err(invalid()),
+    }
+}
+
+/// What a single-file `.torrent` says about its content
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Metainfo {
+    /// SHA-1 of the info dictionary as stored in the file, lowercase hex
+    pub info_hash: String,
+    pub name: String,
+    pub length: u64,
+    pub piece_length: u64,
+    pub pieces: Vec<[u8; 20]>,
+    pub web_seeds: Vec<String>,
+}
+
+pub fn parse_torrent(data: &[u8]) -> Result<Metainfo, String> {
+    let invalid = || "Invalid torrent file".to_string();
+    let mut pos = 0;
+    let root = parse_value(data, &mut pos, 0)?;
+    let (info, info_raw) = root.entry("info").ok_or_else(invalid)?;
+    if info.get("files").is_some() {
+        return Err("Multi-file torrents are not supported".to_string());
+    }
+
+    let name = info
+        .get("name")
+        .and_then(Bencode::as_str)
+        .and_then(sanitize_file_name)
+        .ok_or_else(|| "The torrent has no usable file name".to_string())?;
+    let length = info
+        .get("length")
+        .and_then(Bencode::as_int)
+        .and_then(|n| u64::try_from(n).ok())
+        .ok_or_else(invalid)?;
+    let piece_length = info
+        .get("piece length")
+        .and_then(Bencode::as_int)
+        .and_then(|n| u64::try_from(n).ok())
+        .filter(|n| *n > 0)
+        .ok_or_else(invalid)?;
+    let pieces_raw = info
+        .get("pieces")
+        .and_then(Bencode::as_bytes)
+        .filter(|p| p.len() % 20 == 0)
+        .ok_or_else(invalid)?;
+    let pieces: Vec<[u8; 20]> = pieces_raw
+        .chunks_exact(20)
+        .map(|c| c.try_into().unwrap())
+        .collect();
+    if pieces.len() as u64 != length.div_ceil(piece_length) {
+        return Err(invalid());
+    }
+
+    // `url-list` is a single URL or a list of them
+    let web_seeds = match root.get("url-list") {
+        Some(Bencode::List(items)) => items
+            .iter()
+            .filter_map(Bencode::as_str)
+            .map(str::to_string)
+            .collect(),
+        Some(value) => value.as_str().map(str::to_string).into_iter().collect(),
+        None => Vec::new(),
+    };
+
+    Ok(Metainfo {
+        info_hash: hex::encode(Sha1::digest(info_raw)),
+        name,
+        length,
+        piece_length,
+        pieces,
+        web_seeds,
+    })
+}
+
+/// `name` if it is a single plain file name, so it can't point outside the
+/// download folder
+fn sanitize_file_name(name: &str) -> Option<String> {
+    (super::safe_path::is_safe_relative(name) && Path::new(name).components().count() == 1)
+        .then(|| name.to_string())
+}
+
+/// HTTP URLs for the torrent content, following BEP 19: a web seed ending in
+/// `/` is a directory and the file name is appended
+pub fn web_seed_urls(meta: &Metainfo) -> Vec<String> {
+    meta.web_seeds
+        .iter()
+        .filter(|ws| ws.starts_with("http://") || ws.starts_with("https://"))
+        .map(|ws| {
+            if ws.ends_with('/') {
+                format!("{}{}", ws, meta.name)
+            } else {
+                ws.clone()
+            }
+        })
+        .collect()
+}
+
+/// Whether the file at `path` has the torrent's length and piece hashes
+pub fn verify_pieces(path: &Path, meta: &Metainfo) -> Result<bool, String> {
+    let mut file = std::fs::File::open(path).map_err(|e| e.to_string())?;
+    if file.metadata().map_err(|e| e.to_string())?.len() != meta.length {
+        return Ok(false);
+    }
+    let mut buf = vec![0u8; meta.piece_length.min(1 << 24) as usize];
+    for (index, expected) in meta.pieces.iter().enumerate() {
+        let piece_len = (meta.length - index as u64 * meta.piece_length).min(meta.piece_length);
+        let mut hasher = Sha1::new();
+        let mut remaining = piece_len;
+        while remaining > 0 {
+            let want = remaining.min(buf.len() as u64) as usize;
+            file.read_exact(&mut buf[..want])
+                .map_err(|e| e.to_string())?;
+            hasher.update(&buf[..want]);
+            remaining -= want as u64;
+        }
+        if hasher.finalize().as_slice() != expected {
+            return Ok(false);
+        }
+    }
+    Ok(true)
+}
+
+async fn fetch_torrent(url: &str) -> Result<Metainfo, String> {
+    let response = reqwest::Client::new()
+        .get(url)
+        .send()
+        .await
+        .and_then(|r| r.error_for_status())
+        .map_err(|e| format!("Failed to fetch torrent file: {}", e))?;
+    if response
+        .content_length()
+        .is_some_and(|len| len > MAX_TORRENT_SIZE as u64)
+    {
+        return Err("The torrent file is too large".to_string());
+    }
+    let data = response
+        .bytes()
+        .await
+        .map_err(|e| format!("Failed to fetch torrent file: {}", e))?;
+    if data.len() > MAX_TORRENT_SIZE {
+        return Err("The torrent file is too large".to_string());
+    }
+    parse_torrent(&data)
+}
+
+/// Download a pack archive into `dest_dir`, returning the file path
+pub async fn download_pack(
+    window: &Window,
+    source: &PackSource,
+    dest_dir: &Path,
+    concurrency: DownloadConcurrency,
+    audit: Option<&DownloadAudit>,
+) -> Result<PathBuf, String> {
+    let (candidates, file_name, sha1, meta) = match source {
+        PackSource::Http { url, sha1 } => {
+            let name = url
+                .split('?')
+                .next()
+                .and_then(|u| u.rsplit('/').next())
+                .and_then(sanitize_file_name)
+                .unwrap_or_else(|| "modpack.zip".to_string());
+            (vec![url.clone()], name, sha1.clone(), None)
+        }
+        PackSource::WebSeed {
+            torrent_url,
+            info_hash,
+            fallback_url,
+        } => {
+            let meta = fetch_torrent(torrent_url).await?;
+            if let Some(expected) = info_hash
+                && !expected.eq_ignore_ascii_case(&meta.info_hash)
+            {
+                return Err(format!(
+                    "The torrent file has info hash {}, expected {}",
+                    meta.info_hash, expected
+                ));
+            }
+            let mut urls = web_seed_urls(&meta);
+            urls.extend(fallback_url.iter().cloned());
+            if urls.is_empty() {
+                return Err(
+                    "This torrent has no web seeds or HTTP fallback, and peer-to-peer downloads are not supported"
+                        .to_string(),
+                );
+            }
+            (urls, meta.name.clone(), None, Some(meta))
+        }
+    };
+
+    let path = dest_dir.join(&file_name);
+    for url in &candidates {
+        println!("[WebSeed] Fetching {} from {}", file_name, url);
+        let task = DownloadTask {
+            url: url.clone(),
+            path: path.clone(),
+            sha1: sha1.clone(),
+            sha256: None,
+        };
+        if let Err(e) = download_files(window.clone(), vec![task], concurrency, audit).await {
+            println!("[WebSeed] {} failed: {}", url, e);
+            let _ = std::fs::remove_file(&path);
+            continue;
+        }
+        let Some(meta) = &meta else {
+            return Ok(path);
+        };
+        let (check_path, check_meta) = (path.clone(), meta.clone());
+        let verified = tokio::task::spawn_blocking(move || verify_pieces(&check_path, &check_meta))
+            .await
+            .map_err(|e| e.to_string())??;
+        if verified {
+            return Ok(path);
+        }
+        println!(
+            "[WebSeed] {} does not match the torrent {}",
+            url, meta.info_hash
+        );
+        let _ = std::fs::remove_file(&path);
+    }
+
+    Err(format!("Failed to download {} from any source", file_name))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn bytes(data: &[u8]) -> Vec<u8> {
+        let mut out = format!("{}:", data.len()).into_bytes();
+        out.extend_from_slice(data);
+        out
+    }
+
+    /// A single-file torrent of `data`; `extra` is spliced into the info
+    /// dictionary (keys must stay sorted)
+    fn torrent(data: &[u8], piece_length: usize, extra: &[u8]) -> (Vec<u8>, Vec<u8>) {
+        let mut pieces = Vec::new();
+        for chunk in data.chunks(piece_length) {
+            pieces.extend_from_slice(&Sha1::digest(chunk));
+        }
+        let mut info = format!("d6:lengthi{}e4:name", data.len()).into_bytes();
+        info.extend(bytes(b"pack.zip"));
+        info.extend(format!("12:piece lengthi{}e6:pieces", piece_length).into_bytes());
+        info.extend(bytes(&pieces));
+        info.extend_from_slice(extra);
+        info.push(b'e');
+
+        let mut file = b"d4:info".to_vec();
+        file.extend_from_slice(&info);
+        file.extend(b"8:url-listl");
+        file.extend(bytes(b"https://cdn.example/packs/"));
+        file.extend(b"ee");
+        (file, info)
+    }
+
+    #[test]
+    fn test_parse_torrent() {
+        let data: Vec<u8> = (0..40_000u32).map(|i| (i % 251) as u8).collect();
+        // A key a reconstruction from name, length and pieces would miss
+        let (file, info) = torrent(&data, 1 << 15, b"7:privatei1e");
+        let meta = parse_torrent(&file).unwrap();
+        assert_eq!(meta.info_hash, hex::encode(Sha1::digest(&info)));
+        assert_eq!(meta.name, "pack.zip");
+        assert_eq!(meta.length, 40_000);
+        assert_eq!(meta.pieces.len(), 2);
+        assert_eq!(
+            web_seed_urls(&meta),
+            vec!["https://cdn.example/packs/pack.zip"]
+        );
+    }
+
+    #[test]
+    fn test_verify_pieces() {
+        let dir = std::env::temp_dir().join(format!("dropout-webseed-{}", uuid::Uuid::new_v4()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("pack.zip");
+        let mut data: Vec<u8> = (0..40_000u32).map(|i| (i % 251) as u8).collect();
+        let meta = parse_torrent(&torrent(&data, 1 << 15, b"").0).unwrap();
+
+        std::fs::write(&path, &data).unwrap();
+        assert!(verify_pieces(&path, &meta).unwrap());
+
+        // A changed byte in the short last piece
+        data[39_000] ^= 1;
+        std::fs::write(&path, &data).unwrap();
+        assert!(!verify_pieces(&path, &meta).unwrap());
+
+        std::fs::write(&path, &data[..30_000]).unwrap();
+        assert!(!verify_pieces(&path, &meta).unwrap());
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn test_parse_torrent_rejects_invalid() {
+        assert!(parse_torrent(b"").is_err());
+        assert!(parse_torrent(b"d4:infod5:filesleee").is_err());
+        assert!(parse_torrent(b"i12").is_err());
+        assert!(parse_torrent(b"9999999999:x").is_err());
+
+        // Pieces that don't cover the length
+        let (mut file, _) = torrent(&[1, 2, 3], 2, b"");
+        let at = file.windows(3).position(|w| w == b"i3e").unwrap();
+        file[at + 1] = b'9';
+        assert!(parse_torrent(&file).is_err());
+    }
+
+    #[test]
+    fn test_sanitize_file_name() {
+        assert_eq!(sanitize_file_name("pack.zip").as_deref(), Some("pack.zip"));
+        assert_eq!(sanitize_file_name("../../.bashrc"), None);
+        assert_eq!(sanitize_file_name("dir/pack.zip"), None);
+        assert_eq!(sanitize_file_name(""), None);
+    }
+
+    #[test]
+    fn test_web_seed_file_url_used_as_is() {
+        let meta = Metainfo {
+            info_hash: "00".to_string(),
+            name: "pack.zip".to_string(),
+            length: 0,
+            piece_length: 1,
+            pieces: Vec::new(),
+            web_seeds: vec![
+                "https://cdn.example/pack.zip".to_string(),
+                "ftp://ignored".to_string(),
+            ],
+        };
+        assert_eq!(web_seed_urls(&meta), vec!["https://cdn.example/pack.zip"]);
+    }
+}
@@ -15,8 +15,7 @@ use std::os::windows::process::CommandExt;
 /// Helper macro to emit launcher log events
 macro_rules! emit_log {
     ($window:expr, $msg:expr) => {
-        let _ = $window.emit("launcher-log", $msg);
-        println!("[Launcher] {}", $msg);
+        core::log_batch::log($window.app_handle(), $msg);
     };
 }
 
@@ -42,27 +41,6 @@ impl MsRefreshTokenState {
     }
 }
 
-/// Check if a string contains unresolved placeholders in the form ${...}
-///
-/// After the replacement phase, if a string still contains ${...}, it means
-/// that placeholder variable was not found in the replacements map and is
-/// therefore unresolved. We should skip adding such arguments to avoid
-/// passing malformed arguments to the game launcher.
-fn has_unresolved_placeholder(s: &str) -> bool {
-    // Look for the opening sequence
-    if let Some(start_pos) = s.find("${") {
-        // Check if there's a closing brace after the opening sequence
-        if s[start_pos + 2..].find('}').is_some() {
-            // Found a complete ${...} pattern - this is an unresolved placeholder
-            return true;
-        }
-        // Found ${ but no closing } - also treat as unresolved/malformed
-        return true;
-    }
-    // No ${ found - the string is fully resolved
-    false
-}
-
 #[tauri::command]
 #[dropout_macros::api]
 async fn start_game(
@@ -73,6 +51,37 @@ async fn start_game(
     instance_state: State<'_, core::instance::InstanceState>,
     instance_id: String,
     version_id: String,
+) -> Result<String, String> {
+    let started = std::time::Instant::now();
+    let result = launch_game(
+        window.clone(),
+        auth_state,
+        config_state,
+        assistant_state,
+        instance_state,
+        instance_id.clone(),
+        version_id,
+    )
+    .await;
+    let event = match &result {
+        Ok(_) => core::usage_stats::UsageEvent::launch(
+            &instance_id,
+            started.elapsed().as_millis() as u64,
+        ),
+        Err(e) => core::usage_stats::UsageEvent::failure(Some(&instance_id), e),
+    };
+    core::usage_stats::record(window.app_handle(), event);
+    result
+}
+
+async fn launch_game(
+    window: Window,
+    auth_state: State<'_, core::auth::AccountState>,
+    config_state: State<'_, core::config::ConfigState>,
+    assistant_state: State<'_, core::assistant::AssistantState>,
+    instance_state: State<'_, core::instance::InstanceState>,
+    instance_id: String,
+    version_id: String,
 ) -> Result<String, String> {
     emit_log!(
         window,
@@ -81,14 +90,57 @@ async fn start_game(
             version_id, instance_id
         )
     );
+    let mut timer = core::launch_report::LaunchTimer::new();
 
-    // Check for active account
+    // Use the instance's default account if set, otherwise the active account
     emit_log!(window, "Checking for active account...".to_string());
-    let mut account = auth_state
-        .active_account
-        .lock()
-        .unwrap()
-        .clone()
+    let app_data_dir = window
+        .app_handle()
+        .path()
+        .app_data_dir()
+        .map_err(|e| e.to_string())?;
+
+    // Broken instances stay visible for inspection but must not launch. The
+    // launched version need not be the one selected in the instance.
+    let instance = instance_state
+        .get_instance(&instance_id)
+        .ok_or_else(|| format!("Instance {} not found", instance_id))?;
+    let status = core::instance::check_version_integrity(
+        &instance,
+        &version_id,
+        &app_data_dir.join("versions"),
+    );
+    if let Some(reason) = core::instance::launch_blocked_reason(&instance, &version_id, status) {
+        return Err(reason);
+    }
+    timer.finish(core::launch_report::LaunchPhase::Verification);
+
+    let account_storage = core::account_storage::AccountStorage::new(app_data_dir);
+    let active_account = auth_state.active_account.lock().unwrap().clone();
+    let instance_account = instance_state
+        .get_instance(&instance_id)
+        .and_then(|instance| instance.default_account_uuid)
+        .filter(|uuid| {
+            active_account
+                .as_ref()
+                .is_none_or(|active| active.uuid() != *uuid)
+        })
+        .and_then(|uuid| match account_storage.get_account(&uuid) {
+            Some((stored, _)) => Some(stored.to_account()),
+            None => {
+                emit_log!(
+                    window,
+                    format!(
+                        "Instance default account {} not found, using the active account",
+                        uuid
+                    )
+                );
+                None
+            }
+        });
+    let uses_active_account = instance_account.is_none();
+    let mut account = instance_account
+        .or(active_account)
         .ok_or("No active account found. Please login first.")?;
 
     // Check if Microsoft account token is expired and refresh if needed
@@ -103,9 +155,16 @@ async fn start_game(
             )
             .await
             {
-                Ok((refreshed_account, _new_ms_refresh)) => {
+                Ok((refreshed_account, new_ms_refresh)) => {
                     let refreshed_account = core::auth::Account::Microsoft(refreshed_account);
-                    *auth_state.active_account.lock().unwrap() = Some(refreshed_account.clone());
+                    if uses_active_account {
+                        *auth_state.active_account.lock().unwrap() =
+                            Some(refreshed_account.clone());
+                    } else if let Err(e) =
+                        account_storage.update_account(&refreshed_account, Some(new_ms_refresh))
+                    {
+                        emit_log!(window, format!("Failed to save refreshed account: {}", e));
+                    }
                     account = refreshed_account;
                     emit_log!(window, "Token refreshed successfully".to_string());
                 }
@@ -120,7 +179,36 @@ async fn start_game(
         }
     }
 
+    // Third-party servers have no expiry time; ask whether the token still works
+    if let core::auth::Account::Yggdrasil(ygg_account) = &account {
+        match core::auth::yggdrasil::validate(ygg_account).await {
+            Ok(true) => {}
+            Ok(false) => {
+                emit_log!(window, "Token expired, refreshing...".to_string());
+                let refreshed = core::auth::yggdrasil::refresh(ygg_account)
+                    .await
+                    .map_err(|e| {
+                        format!("Your login session has expired. Please login again: {}", e)
+                    })?;
+                let refreshed_account = core::auth::Account::Yggdrasil(refreshed);
+                if uses_active_account {
+                    *auth_state.active_account.lock().unwrap() = Some(refreshed_account.clone());
+                }
+                if let Err(e) = account_storage.update_account(&refreshed_account, None) {
+                    emit_log!(window, format!("Failed to save refreshed account: {}", e));
+                }
+                account = refreshed_account;
+                emit_log!(window, "Token refreshed successfully".to_string());
+            }
+            // Let the game try; the server may only be unreachable from here
+            Err(e) => {
+                emit_log!(window, format!("Warning: {}", e));
+            }
+        }
+    }
+
     emit_log!(window, "Account found".to_string());
+    timer.finish(core::launch_report::LaunchPhase::Auth);
 
     let config = config_state.config.lock().unwrap().clone();
     emit_log!(window, format!("Java path: {}", config.java_path));
@@ -141,6 +229,12 @@ async fn start_game(
 
     emit_log!(window, format!("Game directory: {:?}", game_dir));
 
+    if let core::auth::Account::Offline(offline) = &account
+        && let Err(e) = core::auth::install_offline_skin(&game_dir, offline)
+    {
+        emit_log!(window, format!("Warning: {}", e));
+    }
+
     // 1. Load version (supports both vanilla and modded versions with inheritance)
     emit_log!(
         window,
@@ -149,10 +243,18 @@ async fn start_game(
 
     // First, load the local version to get the original inheritsFrom value
     // (before merge clears it)
-    let original_inherits_from =
+    // Libraries the loader's own JSON adds, for the launch report
+    let (original_inherits_from, loader_libraries) =
         match core::manifest::load_local_version(&game_dir, &version_id).await {
-            Ok(local_version) => local_version.inherits_from.clone(),
-            Err(_) => None,
+            Ok(local_version) if local_version.inherits_from.is_some() => (
+                local_version.inherits_from.clone(),
+                local_version
+                    .libraries
+                    .iter()
+                    .map(|l| l.name.clone())
+                    .collect::<std::collections::HashSet<_>>(),
+            ),
+            _ => (None, Default::default()),
         };
 
     let version_details = core::manifest::load_version(&game_dir, &version_id)
@@ -166,11 +268,65 @@ async fn start_game(
             version_details.main_class
         )
     );
+    timer.finish(core::launch_report::LaunchPhase::Metadata);
 
     // Determine the actual minecraft version for client.jar
     // (for modded versions, this is the parent vanilla version)
+    let has_parent_version = original_inherits_from.is_some();
     let minecraft_version = original_inherits_from.unwrap_or_else(|| version_id.clone());
 
+    // Bring mods up to date within their major version before they're loaded
+    if let Some(instance) = instance_state.get_instance(&instance_id)
+        && instance.auto_update_mods
+        && let Some(loader) = instance.mod_loader.filter(|l| l != "vanilla")
+    {
+        emit_log!(window, "Checking for mod updates...".to_string());
+        match core::mod_updates::apply_safe_updates(&game_dir, &minecraft_version, &loader).await {
+            Ok(summary) => {
+                emit_log!(
+                    window,
+                    format!(
+                        "Mod updates: {} applied, {} skipped (major), {} failed",
+                        summary.updated.len(),
+                        summary.skipped.len(),
+                        summary.failed.len()
+                    )
+                );
+                let _ = window.emit("mods-auto-updated", &summary);
+            }
+            Err(e) => {
+                emit_log!(window, format!("Warning: mod update check failed: {}", e));
+            }
+        }
+        timer.finish(core::launch_report::LaunchPhase::ModUpdates);
+    }
+
+    let system_locale = core::locale::system_locale();
+    let instance_language = instance_state
+        .get_instance(&instance_id)
+        .and_then(|i| i.game_settings.language)
+        .filter(|l| core::locale::is_language_code(l));
+    if let Some(language) = instance_language.or_else(|| {
+        config
+            .auto_game_language
+            .then(|| {
+                system_locale
+                    .as_deref()
+                    .and_then(core::locale::game_language)
+            })
+            .flatten()
+    }) {
+        match core::locale::preset_game_language(&game_dir, &language, &minecraft_version) {
+            Ok(true) => {
+                emit_log!(window, format!("Set game language to {}", language));
+            }
+            Ok(false) => {}
+            Err(e) => {
+                emit_log!(window, format!("Warning: {}", e));
+            }
+        }
+    }
+
     // Get required Java version from version file's javaVersion field
     // The version file (after merging with parent) should contain the correct javaVersion
     let required_java_major = version_details
@@ -208,7 +364,7 @@ async fn start_game(
         .get_instance(&instance_id)
         .ok_or_else(|| format!("Instance {} not found", instance_id))?;
 
-    let java_installation = core::java::priority::resolve_java_for_launch(
+    let java_installation = core::java::resolve_java_for_launch(
         app_handle,
         instance.java_path_override.as_deref(),
         Some(&config.java_path),
@@ -246,7 +402,24 @@ async fn start_game(
             java_installation.version, java_installation.path
         )
     );
+    timer.finish(core::launch_report::LaunchPhase::Java);
+    if java_installation.emulated {
+        emit_log!(
+            window,
+            format!(
+                "Warning: this {} Java runs under emulation (e.g. Rosetta) on an ARM64 machine; install a native aarch64 Java for better performance",
+                java_installation.arch
+            )
+        );
+        let _ = window.emit("java-emulated-warning", &java_installation);
+    }
+
+    assistant_state.assistant.lock().unwrap().java_in_use = Some(format!(
+        "{} ({}, {})",
+        java_installation.version, java_installation.vendor, java_installation.arch
+    ));
 
+    let java_major = core::java::validation::parse_java_version(&java_installation.version);
     let java_path_to_use = java_installation.path;
 
     // 2. Prepare download tasks
@@ -287,7 +460,12 @@ async fn start_game(
     let mut native_libs_paths = Vec::new(); // Store paths to native jars for extraction
 
     for lib in &version_details.libraries {
-        if core::rules::is_library_allowed(&lib.rules, Some(&config.feature_flags)) {
+        if core::rules::is_library_included(
+            lib,
+            &version_details.libraries,
+            Some(&config.feature_flags),
+            config.native_classifier_override.as_deref(),
+        ) {
             // 1. Standard Library - check for explicit downloads first
             if let Some(downloads) = &lib.downloads {
                 if let Some(artifact) = &downloads.artifact {
@@ -310,38 +488,25 @@ async fn start_game(
                 // 2. Native Library (classifiers)
                 // e.g. "natives-linux": { ... }
                 if let Some(classifiers) = &downloads.classifiers {
-                    // Determine candidate keys based on OS and architecture
-                    let arch = std::env::consts::ARCH;
-                    let mut candidates: Vec<String> = Vec::new();
-                    if cfg!(target_os = "linux") {
-                        candidates.push("natives-linux".to_string());
-                        candidates.push(format!("natives-linux-{}", arch));
-                        if arch == "aarch64" {
-                            candidates.push("natives-linux-arm64".to_string());
-                        }
-                    } else if cfg!(target_os = "windows") {
-                        candidates.push("natives-windows".to_string());
-                        candidates.push(format!("natives-windows-{}", arch));
-                    } else if cfg!(target_os = "macos") {
-                        candidates.push("natives-osx".to_string());
-                        candidates.push("natives-macos".to_string());
-                        candidates.push(format!("natives-macos-{}", arch));
-                    }
-
-                    // Pick the first available classifier key
-                    let mut chosen: Option<core::game_version::DownloadArtifact> = None;
-                    for key in candidates {
-                        if let Some(native_artifact_value) = classifiers.get(&key) {
-                            if let Ok(artifact) =
-                                serde_json::from_value::<core::game_version::DownloadArtifact>(
-                                    native_artifact_value.clone(),
+                    let chosen = match core::natives::select_native(
+                        lib,
+                        classifiers,
+                        config.native_classifier_override.as_deref(),
+                    ) {
+                        Ok(selected) => selected.map(|(_, artifact)| artifact),
+                        Err(warning) => {
+                            emit_log!(
+                                window,
+                                format!(
+                                    "Warning: no native classifier matched for {} (tried {})",
+                                    warning.library,
+                                    warning.tried.join(", ")
                                 )
-                            {
-                                chosen = Some(artifact);
-                                break;
-                            }
+                            );
+                            let _ = window.emit("natives-classifier-missing", &warning);
+                            None
                         }
-                    }
+                    };
 
                     if let Some(native_artifact) = chosen {
                         let path_str = native_artifact.path.clone().unwrap(); // Natives usually have path
@@ -355,7 +520,7 @@ async fn start_game(
                             sha256: None,
                         });
 
-                        native_libs_paths.push(native_path);
+                        native_libs_paths.push((native_path, core::natives::extract_excludes(lib)));
                     }
                 }
             } else {
@@ -379,7 +544,6 @@ async fn start_game(
     }
 
     // --- Assets ---
-    println!("Fetching asset index...");
     // Use shared caches for assets if enabled
     let assets_dir = if config.use_shared_caches {
         app_handle.path().app_data_dir().unwrap().join("assets")
@@ -395,116 +559,89 @@ async fn start_game(
         .as_ref()
         .ok_or("Version has no asset index information")?;
 
-    // Download Asset Index JSON
-    let asset_index_path = indexes_dir.join(format!("{}.json", asset_index.id));
-
-    // Check if index exists or download it
-    // Note: We need the content of this file to parse it.
-    // If we just add it to download_tasks, we can't parse it *now*.
-    // So we must download it immediately (await) before processing objects.
-
-    let asset_index_content: String = if asset_index_path.exists() {
-        tokio::fs::read_to_string(&asset_index_path)
-            .await
-            .map_err(|e| e.to_string())?
-    } else {
-        println!("Downloading asset index from {}", asset_index.url);
-        let content = reqwest::get(&asset_index.url)
-            .await
-            .map_err(|e| e.to_string())?
-            .text()
-            .await
-            .map_err(|e| e.to_string())?;
-
-        // Save it for next time
-        tokio::fs::create_dir_all(&indexes_dir)
-            .await
-            .map_err(|e| e.to_string())?;
-        tokio::fs::write(&asset_index_path, &content)
-            .await
-            .map_err(|e| e.to_string())?;
-        content
-    };
-
-    #[derive(serde::Deserialize, Debug)]
-    struct AssetObject {
-        hash: String,
-        #[allow(dead_code)]
-        size: u64,
-    }
-
-    #[derive(serde::Deserialize, Debug)]
-    struct AssetIndexJson {
-        objects: std::collections::HashMap<String, AssetObject>,
-    }
-
-    let asset_index_parsed: AssetIndexJson =
-        serde_json::from_str(&asset_index_content).map_err(|e| e.to_string())?;
-
-    println!("Processing {} assets...", asset_index_parsed.objects.len());
-
-    for (_name, object) in asset_index_parsed.objects {
-        let hash = object.hash;
-        let prefix = &hash[0..2];
-        let path = objects_dir.join(prefix).join(&hash);
-        let url = format!(
-            "https://resources.download.minecraft.net/{}/{}",
-            prefix, hash
-        );
-
-        download_tasks.push(core::downloader::DownloadTask {
-            url,
-            path,
-            sha1: Some(hash),
-            sha256: None,
-        });
+    // Patched Log4j config for versions affected by Log4Shell
+    if let Some(task) =
+        core::log4j::config_download_task(&version_details, &minecraft_version, &assets_dir)
+    {
+        download_tasks.push(task);
     }
 
+    // A loader profile may list a library of the version it inherits from again
+    let download_tasks = core::downloader::dedup_tasks(download_tasks);
     emit_log!(
         window,
         format!(
-            "Total download tasks: {} (Client + Libraries + Assets)",
+            "Client and library download tasks: {}",
             download_tasks.len()
         )
     );
 
-    // 4. Start Download
+    // 4. Start Download. The client and libraries start right away; asset
+    // objects join the queue as the index is fetched and parsed.
+    let concurrency = core::downloader::concurrency_for_instance(&config, Some(&instance));
     emit_log!(
         window,
         format!(
             "Starting downloads with {} concurrent threads...",
-            config.download_threads
+            concurrency
         )
     );
-    core::downloader::download_files(
-        window.clone(),
-        download_tasks,
-        config.download_threads as usize,
-    )
-    .await
-    .map_err(|e| e.to_string())?;
+    let audit =
+        core::download_audit::DownloadAudit::open_or_warn(app_handle, &instance_id, "launch");
+    let (sender, receiver) = core::downloader::task_channel();
+    for task in download_tasks {
+        sender.send(task);
+    }
+    let (asset_index_parsed, downloaded) = tokio::join!(
+        queue_asset_downloads(&window, sender, asset_index, &indexes_dir, &objects_dir),
+        core::downloader::download_planned(window.clone(), receiver, concurrency, audit.as_ref()),
+    );
+    downloaded?;
+    let asset_index_parsed = asset_index_parsed?;
     emit_log!(window, "All downloads completed successfully".to_string());
+    timer.finish(core::launch_report::LaunchPhase::Download);
+
+    // Legacy versions (and some mods) read assets by name instead of hash
+    let virtual_assets_dir = core::asset_index::materialize_dir(
+        &asset_index_parsed,
+        &asset_index.id,
+        &config.asset_virtualization,
+        &assets_dir,
+        &game_dir,
+    );
+    if let Some(target) = virtual_assets_dir.clone() {
+        emit_log!(
+            window,
+            format!("Materializing assets into {}...", target.display())
+        );
+        let objects_dir = objects_dir.clone();
+        let written = tokio::task::spawn_blocking(move || {
+            core::asset_index::materialize(&asset_index_parsed, &objects_dir, &target)
+        })
+        .await
+        .map_err(|e| e.to_string())??;
+        emit_log!(window, format!("Materialized {} asset files", written));
+        timer.finish(core::launch_report::LaunchPhase::Assets);
+    }
 
     // 5. Extract Natives
     emit_log!(window, "Extracting native libraries...".to_string());
-    let natives_dir = game_dir.join("versions").join(&version_id).join("natives");
-
-    // Clean old natives if they exist to prevent conflicts
-    if natives_dir.exists() {
-        tokio::fs::remove_dir_all(&natives_dir)
-            .await
-            .map_err(|e| e.to_string())?;
-    }
-    tokio::fs::create_dir_all(&natives_dir)
-        .await
-        .map_err(|e| e.to_string())?;
+    // Each launch gets its own directory so a running copy of the same
+    // version keeps its natives; it is removed when the game exits
+    let natives_root = app_handle
+        .path()
+        .app_data_dir()
+        .map_err(|e| e.to_string())?
+        .join("natives");
+    let natives_dir = core::natives::create_launch_dir(&natives_root).map_err(|e| e.to_string())?;
 
-    for path in native_libs_paths {
+    for (path, excludes) in native_libs_paths {
         if path.exists() {
             println!("Extracting native: {:?}", path);
-            utils::zip::extract_zip(&path, &natives_dir)?;
+            core::natives::extract_natives(&path, &natives_dir, &excludes)?;
         }
     }
+    timer.finish(core::launch_report::LaunchPhase::Natives);
 
     // 6. Construct Classpath
     let cp_separator = if cfg!(target_os = "windows") {
@@ -512,50 +649,223 @@ async fn start_game(
     } else {
         ":"
     };
-    let mut classpath_entries = Vec::new();
+    let mut library_entries = Vec::new();
 
     // Add libraries
+    let is_mojang_version = matches!(
+        version_details.version_type.as_deref(),
+        Some("release" | "snapshot" | "old_beta" | "old_alpha")
+    );
     for lib in &version_details.libraries {
-        if core::rules::is_library_allowed(&lib.rules, Some(&config.feature_flags)) {
-            if let Some(downloads) = &lib.downloads {
+        if core::rules::is_library_included(
+            lib,
+            &version_details.libraries,
+            Some(&config.feature_flags),
+            config.native_classifier_override.as_deref(),
+        ) {
+            let lib_path = if let Some(downloads) = &lib.downloads {
                 // Standard library with explicit downloads
-                if let Some(artifact) = &downloads.artifact {
+                downloads.artifact.as_ref().map(|artifact| {
                     let path_str = artifact
                         .path
                         .clone()
                         .unwrap_or_else(|| format!("{}.jar", lib.name));
-                    let lib_path = libraries_dir.join(path_str);
-                    classpath_entries.push(lib_path.to_string_lossy().to_string());
-                }
+                    libraries_dir.join(path_str)
+                })
             } else {
                 // Library without explicit downloads (mod loader libraries)
                 // Use Maven coordinate resolution
-                if let Some(lib_path) = core::maven::get_library_path(&lib.name, &libraries_dir) {
-                    classpath_entries.push(lib_path.to_string_lossy().to_string());
-                }
+                core::maven::get_library_path(&lib.name, &libraries_dir)
+            };
+            if let Some(lib_path) = lib_path {
+                let origin = if loader_libraries.contains(&lib.name) {
+                    "loader"
+                } else if has_parent_version || is_mojang_version {
+                    "vanilla"
+                } else {
+                    "custom"
+                };
+                library_entries.push((
+                    lib.name.clone(),
+                    origin.to_string(),
+                    lib_path.to_string_lossy().to_string(),
+                ));
             }
         }
     }
+    // The loader's copy of a library shared with vanilla wins
+    let (resolved_libraries, substitutions) =
+        core::launch_report::resolve_libraries(library_entries);
+    for sub in &substitutions {
+        emit_log!(
+            window,
+            format!(
+                "Using {} {} ({}) instead of {} ({})",
+                sub.library,
+                sub.kept.version,
+                sub.kept.origin,
+                sub.dropped.version,
+                sub.dropped.origin
+            )
+        );
+    }
+    let mut classpath_entries: Vec<String> =
+        resolved_libraries.iter().map(|l| l.path.clone()).collect();
     // Add client jar
     classpath_entries.push(client_path.to_string_lossy().to_string());
 
+    // Saved now so a launch that fails to start still has one; saved again
+    // with the spawn timing once the process runs
+    let mut report = core::launch_report::LaunchReport {
+        instance_id: instance_id.clone(),
+        version_id: version_id.clone(),
+        main_class: version_details.main_class.clone(),
+        created_at: chrono::Utc::now().timestamp(),
+        libraries: resolved_libraries,
+        substitutions,
+        timings: timer.timings().to_vec(),
+        total_ms: timer.total_ms(),
+    };
+    let reports_dir = app_handle
+        .path()
+        .app_data_dir()
+        .map_err(|e| e.to_string())?
+        .join("launch_reports");
+    if let Err(e) = core::launch_report::save(&reports_dir, &report) {
+        emit_log!(
+            window,
+            format!("Warning: Failed to save launch report: {}", e)
+        );
+    }
+
     let classpath = classpath_entries.join(cp_separator);
 
     // 7. Prepare Arguments
     let mut args = Vec::new();
-    let natives_path = natives_dir.to_string_lossy().to_string();
+
+    // Placeholder values shared by JVM and game arguments
+    let user_type = account.user_type();
+    // Use version_type from version JSON if available, fallback to "release"
+    let version_type_str = version_details
+        .version_type
+        .clone()
+        .unwrap_or_else(|| "release".to_string());
+    let mut arg_context = core::args::ArgContext::new();
+    arg_context
+        .with_auth(
+            &account.username(),
+            &account.uuid(),
+            &account.access_token(),
+            user_type,
+        )
+        .with_version(&version_id, &version_type_str, &asset_index.id)
+        .with_paths(&game_dir, &assets_dir, &natives_dir, &libraries_dir)
+        .with_classpath(&classpath, cp_separator)
+        .with_launcher()
+        .with_resolution(
+            instance.game_settings.width.unwrap_or(config.width),
+            instance.game_settings.height.unwrap_or(config.height),
+        )
+        .with_quick_play(&config.feature_flags, &game_dir);
+    if let Some(dir) = &virtual_assets_dir {
+        arg_context.set(
+            core::args::Placeholder::GameAssets,
+            dir.to_string_lossy().to_string(),
+        );
+    }
 
     // 7a. JVM Arguments - Parse from version.json for full compatibility
-    // First add arguments from version.json if available
-    if let Some(args_obj) = &version_details.arguments {
-        if let Some(jvm_args) = &args_obj.jvm {
-            parse_jvm_arguments(
-                jvm_args,
-                &mut args,
-                &natives_path,
-                &classpath,
-                &config.feature_flags,
+    if let Some(jvm_args) = version_details
+        .arguments
+        .as_ref()
+        .and_then(|a| a.jvm.as_ref())
+    {
+        // Skip memory args as we set them explicitly
+        args.extend(
+            arg_context
+                .expand(
+                    jvm_args,
+                    core::args::ResolveMode::Lenient,
+                    Some(&config.feature_flags),
+                )
+                .into_iter()
+                .filter(|a| !a.starts_with("-Xmx") && !a.starts_with("-Xms")),
+        );
+    }
+
+    // Log4Shell mitigation (1.7 - 1.18.0)
+    let log4j_args = core::log4j::jvm_args(&version_details, &minecraft_version, &assets_dir);
+    if !log4j_args.is_empty() {
+        emit_log!(
+            window,
+            format!("Applying Log4Shell mitigation: {}", log4j_args.join(" "))
+        );
+        args.extend(log4j_args);
+    }
+
+    // Instance Java agents
+    let (agent_args, missing_agents) = core::agents::jvm_args(&instance.java_agents);
+    for path in &missing_agents {
+        emit_log!(
+            window,
+            format!("Warning: Java agent not found, skipping: {}", path)
+        );
+    }
+    for arg in &agent_args {
+        emit_log!(window, format!("Using Java agent: {}", arg));
+    }
+    args.extend(agent_args);
+
+    // authlib-injector points the game's session and skin lookups at the
+    // account's Yggdrasil server
+    if let core::auth::Account::Yggdrasil(ygg_account) = &account
+        && !core::agents::has_known_agent(&instance.java_agents, "authlib-injector")
+    {
+        let agents_dir = app_handle
+            .path()
+            .app_data_dir()
+            .map_err(|e| e.to_string())?
+            .join("agents");
+        let path = core::agents::ensure_known_agent("authlib-injector", &agents_dir)
+            .await
+            .map_err(|e| format!("Failed to get authlib-injector: {}", e))?;
+        let agent = core::agents::JavaAgent {
+            path: path.to_string_lossy().to_string(),
+            args: Some(ygg_account.server_url.clone()),
+            enabled: true,
+        };
+        emit_log!(window, format!("Using Java agent: {}", agent.to_jvm_arg()));
+        args.push(agent.to_jvm_arg());
+    }
+
+    // Per-instance JVM arguments replace the recommended ones
+    match instance
+        .jvm_args_override
+        .as_deref()
+        .map(str::trim)
+        .filter(|a| !a.is_empty())
+    {
+        Some(custom) => {
+            emit_log!(window, format!("Using instance JVM arguments: {}", custom));
+            args.extend(custom.split_whitespace().map(String::from));
+        }
+        None => {
+            let recommended = core::jvm_defaults::recommended_args(
+                &minecraft_version,
+                java_major,
+                instance.mod_loader.as_deref(),
+                &args,
             );
+            if !recommended.is_empty() {
+                emit_log!(
+                    window,
+                    format!(
+                        "Adding recommended JVM arguments: {}",
+                        recommended.join(" ")
+                    )
+                );
+                args.extend(recommended);
+            }
         }
     }
 
@@ -563,11 +873,21 @@ async fn start_game(
     args.push(format!("-Xmx{}M", config.max_memory));
     args.push(format!("-Xms{}M", config.min_memory));
 
-    // Ensure natives path is set if not already in jvm args
-    if !args.iter().any(|a| a.contains("-Djava.library.path")) {
-        args.push(format!("-Djava.library.path={}", natives_path));
+    // Keep game output readable on non-UTF-8 (e.g. CJK Windows) code pages
+    if core::locale::should_force_utf8(&config.utf8_jvm_args, system_locale.as_deref()) {
+        for arg in core::locale::UTF8_JVM_ARGS {
+            let key = arg.split('=').next().unwrap_or(arg);
+            if !args.iter().any(|a| a.starts_with(key)) {
+                args.push(arg.to_string());
+            }
+        }
     }
 
+    // Ensure natives paths are set if not already in jvm args (LWJGL 2 also
+    // reads org.lwjgl.librarypath)
+    let library_path_args = core::natives::library_path_args(&version_details, &natives_dir, &args);
+    args.extend(library_path_args);
+
     // Ensure classpath is set if not already
     if !args.iter().any(|a| a == "-cp" || a == "-classpath") {
         args.push("-cp".to_string());
@@ -578,100 +898,67 @@ async fn start_game(
     args.push(version_details.main_class.clone());
 
     // 7c. Game Arguments
-    // Replacements map
-    let mut replacements = std::collections::HashMap::new();
-    replacements.insert("${auth_player_name}", account.username());
-    replacements.insert("${version_name}", version_id.clone());
-    replacements.insert("${game_directory}", game_dir.to_string_lossy().to_string());
-    replacements.insert("${assets_root}", assets_dir.to_string_lossy().to_string());
-    replacements.insert("${assets_index_name}", asset_index.id.clone());
-    replacements.insert("${auth_uuid}", account.uuid());
-    replacements.insert("${auth_access_token}", account.access_token());
-    // Set user_type dynamically: "msa" for Microsoft accounts, "legacy" for offline
-    let user_type = match &account {
-        core::auth::Account::Microsoft(_) => "msa",
-        core::auth::Account::Offline(_) => "legacy",
-    };
-    replacements.insert("${user_type}", user_type.to_string());
-    // Use version_type from version JSON if available, fallback to "release"
-    let version_type_str = version_details
-        .version_type
-        .clone()
-        .unwrap_or_else(|| "release".to_string());
-    replacements.insert("${version_type}", version_type_str);
-    replacements.insert("${user_properties}", "{}".to_string()); // Correctly pass empty JSON object for user properties
-
     if let Some(minecraft_arguments) = &version_details.minecraft_arguments {
-        // Legacy string
-        for part in minecraft_arguments.split_whitespace() {
-            let mut arg = part.to_string();
-            for (key, val) in &replacements {
-                arg = arg.replace(key, val);
-            }
-            args.push(arg);
-        }
-    } else if let Some(args_obj) = &version_details.arguments {
-        if let Some(game_args) = &args_obj.game {
-            // Can be array of strings or objects
-            if let Some(list) = game_args.as_array() {
-                for item in list {
-                    if let Some(s) = item.as_str() {
-                        let mut arg = s.to_string();
-                        for (key, val) in &replacements {
-                            arg = arg.replace(key, val);
-                        }
-                        args.push(arg);
-                    } else if let Some(obj) = item.as_object() {
-                        // Check rules
-                        // Simplified: if it has "value", and rules pass.
-                        // For now, assuming rules pass if no "rules" field or simplistic check
-                        // Ideally we should implement a helper to check rules for args just like libs
-
-                        let allow = if let Some(rules_val) = obj.get("rules") {
-                            if let Ok(rules) = serde_json::from_value::<Vec<core::game_version::Rule>>(
-                                rules_val.clone(),
-                            ) {
-                                core::rules::is_library_allowed(
-                                    &Some(rules),
-                                    Some(&config.feature_flags),
-                                )
-                            } else {
-                                true // Parse error, assume allow? or disallow.
-                            }
-                        } else {
-                            true
-                        };
-
-                        if allow {
-                            if let Some(val) = obj.get("value") {
-                                if let Some(s) = val.as_str() {
-                                    let mut arg = s.to_string();
-                                    for (key, replacement) in &replacements {
-                                        arg = arg.replace(key, replacement);
-                                    }
-                                    // Skip arguments with unresolved placeholders
-                                    if !has_unresolved_placeholder(&arg) {
-                                        args.push(arg);
-                                    }
-                                } else if let Some(arr) = val.as_array() {
-                                    for sub in arr {
-                                        if let Some(s) = sub.as_str() {
-                                            let mut arg = s.to_string();
-                                            for (key, replacement) in &replacements {
-                                                arg = arg.replace(key, replacement);
-                                            }
-                                            // Skip arguments with unresolved placeholders
-                                            if !has_unresolved_placeholder(&arg) {
-                                                args.push(arg);
-                                            }
-                                        }
-                                    }
-                                }
-                            }
-                        }
-                    }
-                }
-            }
+        // Legacy string: flags and values are positional, keep them paired
+        args.extend(
+            arg_context.expand_legacy(minecraft_arguments, core::args::ResolveMode::Lenient),
+        );
+    } else if let Some(game_args) = version_details
+        .arguments
+        .as_ref()
+        .and_then(|a| a.game.as_ref())
+    {
+        // Skip arguments with unresolved placeholders
+        args.extend(arg_context.expand(
+            game_args,
+            core::args::ResolveMode::Strict,
+            Some(&config.feature_flags),
+        ));
+    }
+
+    let main_index = args
+        .iter()
+        .position(|a| a == &version_details.main_class)
+        .unwrap_or(0);
+    let window_args = core::args::window_args(
+        &args[main_index..],
+        instance.game_settings.width,
+        instance.game_settings.height,
+        instance.game_settings.fullscreen,
+    );
+    args.extend(window_args);
+
+    // Huge modpacks can exceed the Windows command line limit. The files go
+    // into this launch's own directory (removed with the natives when the
+    // game exits), so two launches of one version never share them.
+    if cfg!(target_os = "windows")
+        && core::args::command_line_length(&java_path_to_use, &args)
+            > core::args::WINDOWS_COMMAND_LINE_LIMIT
+    {
+        if java_major >= 9 {
+            // Move the JVM options into an @argfile; game arguments (with the
+            // access token) stay on the command line
+            let main_index = args
+                .iter()
+                .position(|a| a == &version_details.main_class)
+                .unwrap_or(args.len());
+            let argfile = natives_dir.join("launch-args.txt");
+            std::fs::write(&argfile, core::args::argfile_contents(&args[..main_index]))
+                .map_err(|e| format!("Failed to write argument file: {}", e))?;
+            args.splice(..main_index, [format!("@{}", argfile.to_string_lossy())]);
+            emit_log!(
+                window,
+                "Command line too long, using an argument file".to_string()
+            );
+        } else if let Some(cp_index) = args.iter().position(|a| a == "-cp" || a == "-classpath") {
+            // Java 8 has no @argfile: point -cp at a jar whose manifest holds the classpath
+            let pathing_jar = natives_dir.join(format!("{}-classpath.jar", version_id));
+            core::args::write_pathing_jar(&pathing_jar, &classpath_entries)?;
+            args[cp_index + 1] = pathing_jar.to_string_lossy().to_string();
+            emit_log!(
+                window,
+                "Command line too long, using a classpath jar".to_string()
+            );
         }
     }
 
@@ -739,7 +1026,47 @@ async fn start_game(
         window,
         format!("Starting Java process: {}", java_path_to_use)
     );
-    let mut command = Command::new(&java_path_to_use);
+    let (program, args) = if instance.sandbox.enabled {
+        // The game only gets write access to its own instance folder
+        let app_data_dir = app_handle
+            .path()
+            .app_data_dir()
+            .map_err(|e| e.to_string())?;
+        let mut read_only = vec![
+            libraries_dir.clone(),
+            assets_dir.clone(),
+            app_data_dir.join("natives"),
+            app_data_dir.join("agents"),
+        ];
+        if let Some(java_home) = std::path::Path::new(&java_path_to_use)
+            .parent()
+            .and_then(|bin| bin.parent())
+        {
+            read_only.push(java_home.to_path_buf());
+        }
+        let wrapped = core::sandbox::wrap_command(
+            &instance.sandbox,
+            &java_path_to_use,
+            &args,
+            &game_dir,
+            &read_only,
+        )?;
+        emit_log!(
+            window,
+            format!(
+                "Sandbox enabled for this instance (network: {})",
+                if instance.sandbox.allow_network {
+                    "allowed"
+                } else {
+                    "blocked"
+                }
+            )
+        );
+        wrapped
+    } else {
+        (java_path_to_use.clone(), args)
+    };
+    let mut command = Command::new(&program);
     command.args(&args);
     command.current_dir(&game_dir); // Run in game directory
     command.stdout(Stdio::piped());
@@ -749,7 +1076,7 @@ async fn start_game(
     #[cfg(target_os = "windows")]
     {
         const CREATE_NO_WINDOW: u32 = 0x08000000;
-        command.creation_flags(CREATE_NO_WINDOW);
+        command.creation_flags(CREATE_NO_WINDOW | core::sandbox::creation_flags(&instance.sandbox));
         emit_log!(
             window,
             "Applied CREATE_NO_WINDOW flag for Windows".to_string()
@@ -757,11 +1084,103 @@ async fn start_game(
     }
 
     // Spawn and handle output
+    let spawned_at = std::time::SystemTime::now();
     let mut child = command
         .spawn()
-        .map_err(|e| format!("Failed to launch Java at '{}': {}\nPlease check your Java installation and path configuration in Settings.", java_path_to_use, e))?;
+        .map_err(|e| {
+            core::natives::remove_launch_dir(&natives_dir);
+            format!("Failed to launch Java at '{}': {}\nPlease check your Java installation and path configuration in Settings.", java_path_to_use, e)
+        })?;
 
     emit_log!(window, "Java process started successfully".to_string());
+    if let Some(pid) = child.id() {
+        core::natives::mark_owner(&natives_dir, pid);
+    }
+    timer.finish(core::launch_report::LaunchPhase::Spawn);
+    emit_log!(
+        window,
+        format!("Launch took {} ms ({})", timer.total_ms(), timer.summary())
+    );
+    report.timings = timer.timings().to_vec();
+    report.total_ms = timer.total_ms();
+    if let Err(e) = core::launch_report::save(&reports_dir, &report) {
+        emit_log!(
+            window,
+            format!("Warning: Failed to save launch report: {}", e)
+        );
+    }
+
+    if let Err(e) = core::sandbox::apply_process_limits(&instance.sandbox, &child) {
+        // Don't let an untrusted modpack run unrestricted
+        let _ = child.kill().await;
+        return Err(e);
+    }
+
+    let launch_id = uuid::Uuid::new_v4().to_string();
+    let launch_started = std::time::Instant::now();
+    if let Err(e) = core::launch_history::record_launch(
+        window.app_handle(),
+        &instance_id,
+        core::launch_history::LaunchRecord {
+            id: launch_id.clone(),
+            timestamp: chrono::Utc::now().timestamp(),
+            version_id: version_id.clone(),
+            java_path: java_path_to_use.clone(),
+            java_major,
+            args: masked_args,
+            exit_code: None,
+            duration_ms: None,
+            memory_pressure: false,
+        },
+    ) {
+        emit_log!(window, format!("Failed to record launch history: {}", e));
+    }
+
+    core::local_api::game_started(
+        window.app_handle(),
+        core::local_api::RunningGame {
+            launch_id: launch_id.clone(),
+            instance_id: instance_id.clone(),
+            version_id: version_id.clone(),
+            pid: child.id(),
+            started_at: chrono::Utc::now().timestamp(),
+        },
+    );
+    let _ = window.emit(
+        "game-launched",
+        core::launch_report::GameLaunched {
+            instance_id: instance_id.clone(),
+            version_id: version_id.clone(),
+            launch_id: launch_id.clone(),
+            pid: child.id(),
+            timings: report.timings,
+            total_ms: report.total_ms,
+        },
+    );
+
+    // Warn when the system runs low on memory while the game is running
+    let game_running = std::sync::Arc::new(std::sync::atomic::AtomicBool::new(true));
+    {
+        let app_handle = window.app_handle().clone();
+        let instance_id = instance_id.clone();
+        let launch_id = launch_id.clone();
+        let running = game_running.clone();
+        let max_memory = config.max_memory as u64;
+        tokio::spawn(async move {
+            let history_app = app_handle.clone();
+            let history_instance = instance_id.clone();
+            core::memory_monitor::watch(app_handle, instance_id, max_memory, running, move || {
+                if let Err(e) = core::launch_history::record_memory_pressure(
+                    &history_app,
+                    &history_instance,
+                    &launch_id,
+                ) {
+                    eprintln!("[LaunchHistory] Failed to record memory pressure: {}", e);
+                }
+            })
+            .await;
+        });
+    }
 
     let stdout = child
         .stdout
@@ -782,12 +1201,13 @@ async fn start_game(
     let assistant_arc = assistant_state.assistant.clone();
     tokio::spawn(async move {
         let mut reader = BufReader::new(stdout).lines();
+        let mut parser = core::game_log::GameLogParser::new();
         while let Ok(Some(line)) = reader.next_line().await {
-            assistant_arc.lock().unwrap().add_log(line.clone());
-            let _ = window_rx.emit("game-stdout", line);
+            let _ = window_rx.emit("game-log", parser.parse(&line, false));
+            assistant_arc.lock().unwrap().add_log(line);
         }
         // Emit log when stdout stream ends (game closing)
-        let _ = window_rx.emit("launcher-log", "Game stdout stream ended");
+        core::log_batch::log(window_rx.app_handle(), "Game stdout stream ended");
     });
 
     let window_rx_err = window.clone();
@@ -795,26 +1215,58 @@ async fn start_game(
     let window_exit = window.clone();
     tokio::spawn(async move {
         let mut reader = BufReader::new(stderr).lines();
+        let mut parser = core::game_log::GameLogParser::new();
         while let Ok(Some(line)) = reader.next_line().await {
-            assistant_arc_err.lock().unwrap().add_log(line.clone());
-            let _ = window_rx_err.emit("game-stderr", line);
+            let _ = window_rx_err.emit("game-log", parser.parse(&line, true));
+            assistant_arc_err.lock().unwrap().add_log(line);
         }
         // Emit log when stderr stream ends
-        let _ = window_rx_err.emit("launcher-log", "Game stderr stream ended");
+        core::log_batch::log(window_rx_err.app_handle(), "Game stderr stream ended");
     });
 
     // Monitor game process exit
+    let history_instance_id = instance_id.clone();
+    let crash_game_dir = game_dir.clone();
     tokio::spawn(async move {
-        match child.wait().await {
+        let exit_code = match child.wait().await {
             Ok(status) => {
                 let msg = format!("Game process exited with status: {}", status);
-                let _ = window_exit.emit("launcher-log", &msg);
+                core::log_batch::log(window_exit.app_handle(), &msg);
                 let _ = window_exit.emit("game-exited", status.code().unwrap_or(-1));
+                status.code()
             }
             Err(e) => {
                 let msg = format!("Error waiting for game process: {}", e);
-                let _ = window_exit.emit("launcher-log", &msg);
+                core::log_batch::log(window_exit.app_handle(), &msg);
+                None
+            }
+        };
+        game_running.store(false, std::sync::atomic::Ordering::Relaxed);
+        if exit_code != Some(0) {
+            let dumps = core::crash::find_new_dumps(&crash_game_dir, spawned_at);
+            for dump in &dumps {
+                let msg = format!("JVM crash dump written: {}", dump.path);
+                core::log_batch::log(window_exit.app_handle(), &msg);
             }
+            let _ = window_exit.emit(
+                "crash-detected",
+                core::crash::CrashDetected {
+                    instance_id: history_instance_id.clone(),
+                    exit_code,
+                    dumps,
+                },
+            );
+        }
+        core::natives::remove_launch_dir(&natives_dir);
+        core::local_api::game_exited(window_exit.app_handle(), &launch_id);
+        if let Err(e) = core::launch_history::record_exit(
+            window_exit.app_handle(),
+            &history_instance_id,
+            &launch_id,
+            exit_code,
+            launch_started.elapsed().as_millis() as u64,
+        ) {
+            eprintln!("[LaunchHistory] Failed to record exit: {}", e);
         }
     });
 
@@ -827,83 +1279,20 @@ async fn start_game(
     Ok(format!("Launched Minecraft {} successfully!", version_id))
 }
 
-/// Parse JVM arguments from version.json
-fn parse_jvm_arguments(
-    jvm_args: &serde_json::Value,
-    args: &mut Vec<String>,
-    natives_path: &str,
-    classpath: &str,
-    feature_flags: &core::config::FeatureFlags,
-) {
-    let mut replacements = std::collections::HashMap::new();
-    replacements.insert("${natives_directory}", natives_path.to_string());
-    replacements.insert("${classpath}", classpath.to_string());
-    replacements.insert("${launcher_name}", "DropOut".to_string());
-    replacements.insert("${launcher_version}", env!("CARGO_PKG_VERSION").to_string());
-
-    if let Some(list) = jvm_args.as_array() {
-        for item in list {
-            if let Some(s) = item.as_str() {
-                // Simple string argument
-                let mut arg = s.to_string();
-                for (key, val) in &replacements {
-                    arg = arg.replace(key, val);
-                }
-                // Skip memory args as we set them explicitly
-                if !arg.starts_with("-Xmx") && !arg.starts_with("-Xms") {
-                    args.push(arg);
-                }
-            } else if let Some(obj) = item.as_object() {
-                // Conditional argument with rules
-                let allow = if let Some(rules_val) = obj.get("rules") {
-                    if let Ok(rules) =
-                        serde_json::from_value::<Vec<core::game_version::Rule>>(rules_val.clone())
-                    {
-                        core::rules::is_library_allowed(&Some(rules), Some(feature_flags))
-                    } else {
-                        false
-                    }
-                } else {
-                    true
-                };
-
-                if allow {
-                    if let Some(val) = obj.get("value") {
-                        if let Some(s) = val.as_str() {
-                            let mut arg = s.to_string();
-                            for (key, replacement) in &replacements {
-                                arg = arg.replace(key, replacement);
-                            }
-                            if !arg.starts_with("-Xmx") && !arg.starts_with("-Xms") {
-                                args.push(arg);
-                            }
-                        } else if let Some(arr) = val.as_array() {
-                            for sub in arr {
-                                if let Some(s) = sub.as_str() {
-                                    let mut arg = s.to_string();
-                                    for (key, replacement) in &replacements {
-                                        arg = arg.replace(key, replacement);
-                                    }
-                                    if !arg.starts_with("-Xmx") && !arg.starts_with("-Xms") {
-                                        args.push(arg);
-                                    }
-                                }
-                            }
-                        }
-                    }
-                }
-            }
-        }
-    }
-}
-
 #[tauri::command]
 #[dropout_macros::api]
-async fn get_versions() -> Result<Vec<core::manifest::Version>, String> {
-    core::manifest::fetch_version_manifest()
+async fn get_versions(
+    instance_state: State<'_, core::instance::InstanceState>,
+) -> Result<Vec<core::manifest::Version>, String> {
+    let mut versions = core::manifest::fetch_version_manifest()
         .await
         .map(|m| m.versions)
-        .map_err(|e| e.to_string())
+        .map_err(|e| e.to_string())?;
+    // Pins of the active instance float to the top
+    if let Some(instance) = instance_state.get_active_instance() {
+        core::manifest::mark_pinned(&mut versions, &instance.pinned_versions);
+    }
+    Ok(versions)
 }
 
 /// Get all available versions from Mojang's version manifest
@@ -914,9 +1303,10 @@ async fn get_versions_of_instance(
     instance_state: State<'_, core::instance::InstanceState>,
     instance_id: String,
 ) -> Result<Vec<core::manifest::Version>, String> {
-    let game_dir = instance_state
-        .get_instance_game_dir(&instance_id)
+    let instance = instance_state
+        .get_instance(&instance_id)
         .ok_or_else(|| format!("Instance {} not found", instance_id))?;
+    let game_dir = instance.game_dir;
 
     match core::manifest::fetch_version_manifest().await {
         Ok(manifest) => {
@@ -945,12 +1335,65 @@ async fn get_versions_of_instance(
                 }
             }
 
+            core::manifest::mark_pinned(&mut versions, &instance.pinned_versions);
             Ok(versions)
         }
         Err(e) => Err(e.to_string()),
     }
 }
 
+/// Pin a version so it floats to the top of the instance's version picker
+#[tauri::command]
+#[dropout_macros::api]
+async fn pin_version(
+    instance_state: State<'_, core::instance::InstanceState>,
+    instance_id: String,
+    version_id: String,
+) -> Result<Vec<String>, String> {
+    let mut instance = instance_state
+        .get_instance(&instance_id)
+        .ok_or_else(|| format!("Instance {} not found", instance_id))?;
+    if !instance.pinned_versions.contains(&version_id) {
+        instance.pinned_versions.push(version_id);
+    }
+    let pinned = instance.pinned_versions.clone();
+    instance_state.update_instance(instance)?;
+    Ok(pinned)
+}
+
+/// Remove a version from the instance's pinned versions
+#[tauri::command]
+#[dropout_macros::api]
+async fn unpin_version(
+    instance_state: State<'_, core::instance::InstanceState>,
+    instance_id: String,
+    version_id: String,
+) -> Result<Vec<String>, String> {
+    let mut instance = instance_state
+        .get_instance(&instance_id)
+        .ok_or_else(|| format!("Instance {} not found", instance_id))?;
+    instance.pinned_versions.retain(|v| v != &version_id);
+    let pinned = instance.pinned_versions.clone();
+    instance_state.update_instance(instance)?;
+    Ok(pinned)
+}
+
+/// Compare two vanilla versions: Java requirement, main class, asset index and libraries
+#[tauri::command]
+#[dropout_macros::api]
+async fn diff_versions(
+    from_version_id: String,
+    to_version_id: String,
+) -> Result<core::version_diff::VersionDiff, String> {
+    let (from, to) = tokio::join!(
+        core::manifest::fetch_vanilla_version(&from_version_id),
+        core::manifest::fetch_vanilla_version(&to_version_id)
+    );
+    let from = from.map_err(|e| e.to_string())?;
+    let to = to.map_err(|e| e.to_string())?;
+    Ok(core::version_diff::diff(&from, &to))
+}
+
 /// Check if a version is installed (has client.jar)
 #[tauri::command]
 #[dropout_macros::api]
@@ -979,6 +1422,20 @@ async fn check_version_installed(
             .next()
             .unwrap_or(&version_id)
             .to_string()
+    } else if version_id.contains("-neoforge-") {
+        // Format: 1.21.1-neoforge-21.1.77
+        version_id
+            .split("-neoforge-")
+            .next()
+            .unwrap_or(&version_id)
+            .to_string()
+    } else if version_id.contains("-OptiFine_") {
+        // Format: 1.20.1-OptiFine_HD_U_I6
+        version_id
+            .split("-OptiFine_")
+            .next()
+            .unwrap_or(&version_id)
+            .to_string()
     } else {
         version_id.clone()
     };
@@ -991,6 +1448,33 @@ async fn check_version_installed(
     Ok(client_jar.exists())
 }
 
+/// Fetch the asset index (verified against its sha1) and queue its objects
+/// on `sender`, which is dropped once everything is queued
+async fn queue_asset_downloads(
+    window: &Window,
+    sender: core::downloader::TaskSender,
+    asset_index: &core::game_version::AssetIndex,
+    indexes_dir: &std::path::Path,
+    objects_dir: &std::path::Path,
+) -> Result<core::asset_index::AssetIndexContents, String> {
+    emit_log!(window, format!("Loading asset index {}...", asset_index.id));
+    let content = core::asset_index::load_or_fetch(asset_index, indexes_dir).await?;
+    let contents = core::asset_index::parse(&content)?;
+    let (count, size) = core::asset_index::download_size(&contents);
+    emit_log!(
+        window,
+        format!(
+            "Queueing {} assets ({:.2} MB)...",
+            count,
+            size as f64 / (1024.0 * 1024.0)
+        )
+    );
+    for task in core::asset_index::download_tasks(&contents, objects_dir) {
+        sender.send(task);
+    }
+    Ok(contents)
+}
+
 /// Install a version (download client, libraries, assets) without launching
 #[tauri::command]
 #[dropout_macros::api]
@@ -1000,6 +1484,29 @@ async fn install_version(
     instance_state: State<'_, core::instance::InstanceState>,
     instance_id: String,
     version_id: String,
+) -> Result<(), String> {
+    let mut machine = core::install::InstallStateMachine::start(&window, &instance_id, &version_id);
+    let result = run_install(
+        &window,
+        &config_state,
+        &instance_state,
+        &instance_id,
+        &version_id,
+        &mut machine,
+    )
+    .await;
+    machine.finish(&result);
+    result
+}
+
+/// The phases of [`install_version`], reported through `machine`
+async fn run_install(
+    window: &Window,
+    config_state: &core::config::ConfigState,
+    instance_state: &core::instance::InstanceState,
+    instance_id: &str,
+    version_id: &str,
+    machine: &mut core::install::InstallStateMachine,
 ) -> Result<(), String> {
     emit_log!(
         window,
@@ -1013,7 +1520,7 @@ async fn install_version(
 
     // Get game directory from instance
     let game_dir = instance_state
-        .get_instance_game_dir(&instance_id)
+        .get_instance_game_dir(instance_id)
         .ok_or_else(|| format!("Instance {} not found", instance_id))?;
 
     // Ensure game directory exists
@@ -1030,7 +1537,7 @@ async fn install_version(
     );
 
     // First, try to fetch the vanilla version from Mojang and save it locally
-    let _version_details = match core::manifest::load_local_version(&game_dir, &version_id).await {
+    let _version_details = match core::manifest::load_local_version(&game_dir, version_id).await {
         Ok(v) => v,
         Err(_) => {
             // Not found locally, fetch from Mojang
@@ -1038,22 +1545,20 @@ async fn install_version(
                 window,
                 format!("Fetching version {} from Mojang...", version_id)
             );
-            let fetched = core::manifest::fetch_vanilla_version(&version_id)
+            let (fetched, data) = core::manifest::fetch_vanilla_version_json(version_id)
                 .await
                 .map_err(|e| e.to_string())?;
 
-            // Save the version JSON locally
+            // Save the verified version JSON locally, byte for byte
             emit_log!(window, format!("Saving version JSON..."));
-            core::manifest::save_local_version(&game_dir, &fetched)
-                .await
-                .map_err(|e| e.to_string())?;
+            core::manifest::save_version_json(&game_dir, version_id, &data)?;
 
             fetched
         }
     };
 
     // Now load the full version with inheritance resolved
-    let version_details = core::manifest::load_version(&game_dir, &version_id)
+    let version_details = core::manifest::load_version(&game_dir, version_id)
         .await
         .map_err(|e| e.to_string())?;
 
@@ -1069,11 +1574,13 @@ async fn install_version(
     let minecraft_version = version_details
         .inherits_from
         .clone()
-        .unwrap_or_else(|| version_id.clone());
+        .unwrap_or_else(|| version_id.to_string());
 
     // Prepare download tasks
+    machine.advance(core::install::InstallPhase::PlanningDownloads);
     emit_log!(window, "Preparing download tasks...".to_string());
     let mut download_tasks = Vec::new();
+    let mut native_jars = Vec::new();
 
     // --- Client Jar ---
     let downloads = version_details
@@ -1116,7 +1623,12 @@ async fn install_version(
     };
 
     for lib in &version_details.libraries {
-        if core::rules::is_library_allowed(&lib.rules, Some(&config.feature_flags)) {
+        if core::rules::is_library_included(
+            lib,
+            &version_details.libraries,
+            Some(&config.feature_flags),
+            config.native_classifier_override.as_deref(),
+        ) {
             if let Some(downloads) = &lib.downloads {
                 if let Some(artifact) = &downloads.artifact {
                     let path_str = artifact
@@ -1137,38 +1649,25 @@ async fn install_version(
 
                 // Native Library (classifiers)
                 if let Some(classifiers) = &downloads.classifiers {
-                    // Determine candidate keys based on OS and architecture
-                    let arch = std::env::consts::ARCH;
-                    let mut candidates: Vec<String> = Vec::new();
-                    if cfg!(target_os = "linux") {
-                        candidates.push("natives-linux".to_string());
-                        candidates.push(format!("natives-linux-{}", arch));
-                        if arch == "aarch64" {
-                            candidates.push("natives-linux-arm64".to_string());
-                        }
-                    } else if cfg!(target_os = "windows") {
-                        candidates.push("natives-windows".to_string());
-                        candidates.push(format!("natives-windows-{}", arch));
-                    } else if cfg!(target_os = "macos") {
-                        candidates.push("natives-osx".to_string());
-                        candidates.push("natives-macos".to_string());
-                        candidates.push(format!("natives-macos-{}", arch));
-                    }
-
-                    // Pick the first available classifier key
-                    let mut chosen: Option<core::game_version::DownloadArtifact> = None;
-                    for key in candidates {
-                        if let Some(native_artifact_value) = classifiers.get(&key) {
-                            if let Ok(artifact) =
-                                serde_json::from_value::<core::game_version::DownloadArtifact>(
-                                    native_artifact_value.clone(),
+                    let chosen = match core::natives::select_native(
+                        lib,
+                        classifiers,
+                        config.native_classifier_override.as_deref(),
+                    ) {
+                        Ok(selected) => selected.map(|(_, artifact)| artifact),
+                        Err(warning) => {
+                            emit_log!(
+                                window,
+                                format!(
+                                    "Warning: no native classifier matched for {} (tried {})",
+                                    warning.library,
+                                    warning.tried.join(", ")
                                 )
-                            {
-                                chosen = Some(artifact);
-                                break;
-                            }
+                            );
+                            let _ = window.emit("natives-classifier-missing", &warning);
+                            None
                         }
-                    }
+                    };
 
                     if let Some(native_artifact) = chosen {
                         let path_str = native_artifact.path.clone().unwrap();
@@ -1181,6 +1680,7 @@ async fn install_version(
                             sha1: native_artifact.sha1,
                             sha256: None,
                         });
+                        native_jars.push((native_path, core::natives::extract_excludes(lib)));
                     }
                 }
             } else {
@@ -1222,88 +1722,73 @@ async fn install_version(
         .as_ref()
         .ok_or("Version has no asset index information")?;
 
-    let asset_index_path = indexes_dir.join(format!("{}.json", asset_index.id));
-
-    let asset_index_content: String = if asset_index_path.exists() {
-        tokio::fs::read_to_string(&asset_index_path)
-            .await
-            .map_err(|e| e.to_string())?
-    } else {
-        emit_log!(window, format!("Downloading asset index..."));
-        let content = reqwest::get(&asset_index.url)
-            .await
-            .map_err(|e| e.to_string())?
-            .text()
-            .await
-            .map_err(|e| e.to_string())?;
-
-        tokio::fs::create_dir_all(&indexes_dir)
-            .await
-            .map_err(|e| e.to_string())?;
-        tokio::fs::write(&asset_index_path, &content)
-            .await
-            .map_err(|e| e.to_string())?;
-        content
-    };
-
-    #[derive(serde::Deserialize)]
-    struct AssetObject {
-        hash: String,
-    }
-
-    #[derive(serde::Deserialize)]
-    struct AssetIndexJson {
-        objects: std::collections::HashMap<String, AssetObject>,
-    }
-
-    let asset_index_parsed: AssetIndexJson =
-        serde_json::from_str(&asset_index_content).map_err(|e| e.to_string())?;
-
-    emit_log!(
-        window,
-        format!("Processing {} assets...", asset_index_parsed.objects.len())
-    );
-
-    for (_name, object) in asset_index_parsed.objects {
-        let hash = object.hash;
-        let prefix = &hash[0..2];
-        let path = objects_dir.join(prefix).join(&hash);
-        let url = format!(
-            "https://resources.download.minecraft.net/{}/{}",
-            prefix, hash
-        );
-
-        download_tasks.push(core::downloader::DownloadTask {
-            url,
-            path,
-            sha1: Some(hash),
-            sha256: None,
-        });
+    // Patched Log4j config for versions affected by Log4Shell
+    if let Some(task) =
+        core::log4j::config_download_task(&version_details, &minecraft_version, &assets_dir)
+    {
+        download_tasks.push(task);
     }
 
+    // A loader profile may list a library of the version it inherits from again
+    let download_tasks = core::downloader::dedup_tasks(download_tasks);
     emit_log!(
         window,
         format!(
-            "Total download tasks: {} (Client + Libraries + Assets)",
+            "Client and library download tasks: {}",
             download_tasks.len()
         )
     );
 
-    // Start Download
+    // Start Download; asset objects are queued while the first files download
+    machine.advance(core::install::InstallPhase::Downloading);
+    let concurrency = core::downloader::concurrency_for_instance(
+        &config,
+        instance_state.get_instance(instance_id).as_ref(),
+    );
     emit_log!(
         window,
         format!(
             "Starting downloads with {} concurrent threads...",
-            config.download_threads
+            concurrency
         )
     );
-    core::downloader::download_files(
-        window.clone(),
-        download_tasks,
-        config.download_threads as usize,
-    )
-    .await
-    .map_err(|e| e.to_string())?;
+    let audit = core::download_audit::DownloadAudit::open_or_warn(
+        window.app_handle(),
+        instance_id,
+        "install",
+    );
+    let (sender, receiver) = core::downloader::task_channel();
+    for task in download_tasks {
+        sender.send(task);
+    }
+    let (queued, downloaded) = tokio::join!(
+        queue_asset_downloads(window, sender, asset_index, &indexes_dir, &objects_dir),
+        core::downloader::download_planned(window.clone(), receiver, concurrency, audit.as_ref()),
+    );
+    downloaded?;
+    queued?;
+
+    // Natives are extracted per launch; extracting them once here catches
+    // corrupt jars before the first launch does
+    machine.advance(core::install::InstallPhase::ExtractingNatives);
+    if !native_jars.is_empty() {
+        let natives_root = window
+            .app_handle()
+            .path()
+            .app_data_dir()
+            .map_err(|e| e.to_string())?
+            .join("natives");
+        tokio::task::spawn_blocking(move || {
+            let dir = core::natives::create_launch_dir(&natives_root).map_err(|e| e.to_string())?;
+            let result = native_jars.iter().try_for_each(|(jar, excludes)| {
+                core::natives::extract_natives(jar, &dir, excludes)
+            });
+            core::natives::remove_launch_dir(&dir);
+            result
+        })
+        .await
+        .map_err(|e| e.to_string())??;
+    }
 
     emit_log!(
         window,
@@ -1311,7 +1796,7 @@ async fn install_version(
     );
 
     // Emit event to notify frontend that version installation is complete
-    let _ = window.emit("version-installed", &version_id);
+    let _ = window.emit("version-installed", version_id);
 
     Ok(())
 }
@@ -1321,45 +1806,277 @@ async fn install_version(
 async fn login_offline(
     window: Window,
     state: State<'_, core::auth::AccountState>,
+    config_state: State<'_, core::config::ConfigState>,
     username: String,
 ) -> Result<core::auth::Account, String> {
-    let uuid = core::auth::generate_offline_uuid(&username);
-    let account = core::auth::Account::Offline(core::auth::OfflineAccount { username, uuid });
+    core::parental::require_unlocked(window.app_handle(), "change accounts")?;
+    let username = username.trim().to_string();
+    core::auth::validate_offline_username(&username)?;
+    let namespace = config_state
+        .config
+        .lock()
+        .unwrap()
+        .offline_uuid_namespace
+        .clone();
+    let uuid = core::auth::generate_offline_uuid(&username, &namespace)?;
+
+    // Keep the skin if this account was used before
+    let app_dir = window
+        .app_handle()
+        .path()
+        .app_data_dir()
+        .map_err(|e| e.to_string())?;
+    let storage = core::account_storage::AccountStorage::new(app_dir.clone());
+    let skin_path = match storage.get_account(&uuid) {
+        Some((core::account_storage::StoredAccount::Offline(existing), _)) => existing.skin_path,
+        _ => None,
+    };
+    let account = core::auth::Account::Offline(core::auth::OfflineAccount {
+        username,
+        uuid,
+        skin_path,
+    });
 
     *state.active_account.lock().unwrap() = Some(account.clone());
 
     // Save to storage
-    let app_handle = window.app_handle();
-    let app_dir = app_handle
+    storage.add_or_update_account(&account, None)?;
+
+    Ok(account)
+}
+
+/// Rename an offline account. The UUID is regenerated from the new name
+/// unless `keep_uuid` is set (which keeps existing worlds' player data).
+#[tauri::command]
+#[dropout_macros::api]
+async fn rename_offline_account(
+    window: Window,
+    state: State<'_, core::auth::AccountState>,
+    config_state: State<'_, core::config::ConfigState>,
+    instance_state: State<'_, core::instance::InstanceState>,
+    uuid: String,
+    new_name: String,
+    keep_uuid: Option<bool>,
+) -> Result<core::auth::Account, String> {
+    core::parental::require_unlocked(window.app_handle(), "change accounts")?;
+    let new_name = new_name.trim().to_string();
+    core::auth::validate_offline_username(&new_name)?;
+
+    let app_dir = window
+        .app_handle()
         .path()
         .app_data_dir()
         .map_err(|e| e.to_string())?;
-    let storage = core::account_storage::AccountStorage::new(app_dir);
-    storage.add_or_update_account(&account, None)?;
+    let storage = core::account_storage::AccountStorage::new(app_dir.clone());
+    let Some((core::account_storage::StoredAccount::Offline(mut offline), _)) =
+        storage.get_account(&uuid)
+    else {
+        return Err("Offline account not found".to_string());
+    };
+
+    offline.username = new_name;
+    if !keep_uuid.unwrap_or(false) {
+        let namespace = config_state
+            .config
+            .lock()
+            .unwrap()
+            .offline_uuid_namespace
+            .clone();
+        offline.uuid = core::auth::generate_offline_uuid(&offline.username, &namespace)?;
+    }
+    let account = core::auth::Account::Offline(offline);
+
+    // Hold the active account lock so storage and memory change together
+    let mut active = state.active_account.lock().unwrap();
+    storage.replace_account(&uuid, &account)?;
+    if active.as_ref().is_some_and(|a| a.uuid() == uuid) {
+        *active = Some(account.clone());
+    }
+    drop(active);
+
+    // Point instances that launch with this account at the new UUID
+    if account.uuid() != uuid {
+        let mut changed = false;
+        {
+            let mut config = instance_state.instances.lock().unwrap();
+            for instance in config.instances.iter_mut() {
+                if instance.default_account_uuid.as_deref() == Some(uuid.as_str()) {
+                    instance.default_account_uuid = Some(account.uuid());
+                    changed = true;
+                }
+            }
+        }
+        if changed {
+            instance_state.save()?;
+        }
+    }
 
     Ok(account)
 }
 
+/// Assign a local skin PNG to an offline account, or clear it with `None`
 #[tauri::command]
 #[dropout_macros::api]
-async fn get_active_account(
+async fn set_offline_skin(
+    window: Window,
     state: State<'_, core::auth::AccountState>,
-) -> Result<Option<core::auth::Account>, String> {
-    Ok(state.active_account.lock().unwrap().clone())
+    uuid: String,
+    skin_path: Option<String>,
+) -> Result<core::auth::Account, String> {
+    let app_dir = window
+        .app_handle()
+        .path()
+        .app_data_dir()
+        .map_err(|e| e.to_string())?;
+    let storage = core::account_storage::AccountStorage::new(app_dir.clone());
+    let Some((core::account_storage::StoredAccount::Offline(mut offline), _)) =
+        storage.get_account(&uuid)
+    else {
+        return Err("Offline account not found".to_string());
+    };
+
+    // Copy the skin into the launcher's data so the original file can move
+    let skins_dir = app_dir.join("skins");
+    let stored_skin = skins_dir.join(format!("{}.png", uuid));
+    offline.skin_path = match skin_path {
+        Some(path) => {
+            let data = std::fs::read(&path).map_err(|e| format!("Failed to read skin: {}", e))?;
+            core::auth::validate_skin_png(&data)?;
+            std::fs::create_dir_all(&skins_dir).map_err(|e| e.to_string())?;
+            std::fs::write(&stored_skin, data).map_err(|e| e.to_string())?;
+            Some(stored_skin.to_string_lossy().to_string())
+        }
+        None => {
+            let _ = std::fs::remove_file(&stored_skin);
+            None
+        }
+    };
+
+    let account = core::auth::Account::Offline(offline);
+    storage.update_account(&account, None)?;
+    let mut active = state.active_account.lock().unwrap();
+    if active.as_ref().is_some_and(|a| a.uuid() == uuid) {
+        *active = Some(account.clone());
+    }
+    Ok(account)
+}
+
+/// Minecraft Services access token of the active Microsoft account
+fn active_microsoft_token(state: &core::auth::AccountState) -> Result<String, String> {
+    match state.active_account.lock().unwrap().as_ref() {
+        Some(core::auth::Account::Microsoft(account)) => {
+            if core::auth::is_token_expired(account.expires_at) {
+                return Err(
+                    "Your login session has expired. Please refresh the account".to_string()
+                );
+            }
+            Ok(account.access_token.clone())
+        }
+        Some(_) => Err("Skins can only be changed for Microsoft accounts".to_string()),
+        None => Err("No active account found. Please login first.".to_string()),
+    }
+}
+
+fn skin_cache_dir(window: &Window) -> Result<std::path::PathBuf, String> {
+    Ok(window
+        .app_handle()
+        .path()
+        .app_data_dir()
+        .map_err(|e| e.to_string())?
+        .join("cache")
+        .join("skins"))
 }
 
+/// Skin and cape of the active account, with the textures cached locally for
+/// the UI. Offline accounts report their local skin.
 #[tauri::command]
 #[dropout_macros::api]
-async fn logout(window: Window, state: State<'_, core::auth::AccountState>) -> Result<(), String> {
-    // Get current account UUID before clearing
-    let uuid = state
+async fn fetch_player_skin(
+    window: Window,
+    state: State<'_, core::auth::AccountState>,
+) -> Result<core::skin::PlayerSkin, String> {
+    let account = state
         .active_account
         .lock()
         .unwrap()
-        .as_ref()
-        .map(|a| a.uuid());
+        .clone()
+        .ok_or("No active account found. Please login first.")?;
+    match account {
+        core::auth::Account::Offline(offline) => Ok(core::skin::PlayerSkin {
+            profile: core::skin::SkinProfile {
+                id: offline.uuid,
+                name: offline.username,
+                skins: Vec::new(),
+                capes: Vec::new(),
+            },
+            skin_path: offline.skin_path,
+            cape_path: None,
+        }),
+        core::auth::Account::Yggdrasil(account) => Err(format!(
+            "Manage this skin on {}",
+            account.server_name.unwrap_or(account.server_url)
+        )),
+        core::auth::Account::Microsoft(_) => {
+            let profile = core::skin::fetch_profile(&active_microsoft_token(&state)?).await?;
+            Ok(core::skin::cache_active(profile, &skin_cache_dir(&window)?).await)
+        }
+    }
+}
+
+/// Upload a skin PNG (`variant` is "classic" or "slim") for the active
+/// Microsoft account
+#[tauri::command]
+#[dropout_macros::api]
+async fn upload_skin(
+    window: Window,
+    state: State<'_, core::auth::AccountState>,
+    path: String,
+    variant: String,
+) -> Result<core::skin::PlayerSkin, String> {
+    let token = active_microsoft_token(&state)?;
+    let variant = core::skin::SkinVariant::parse(&variant)?;
+    let png = tokio::fs::read(&path)
+        .await
+        .map_err(|e| format!("Failed to read skin: {}", e))?;
+    let profile = core::skin::upload_skin(&token, png, variant).await?;
+    Ok(core::skin::cache_active(profile, &skin_cache_dir(&window)?).await)
+}
+
+/// Show one of the active Microsoft account's capes, or hide it with `None`
+#[tauri::command]
+#[dropout_macros::api]
+async fn set_cape(
+    window: Window,
+    state: State<'_, core::auth::AccountState>,
+    cape_id: Option<String>,
+) -> Result<core::skin::PlayerSkin, String> {
+    let token = active_microsoft_token(&state)?;
+    let profile = core::skin::set_cape(&token, cape_id.as_deref()).await?;
+    Ok(core::skin::cache_active(profile, &skin_cache_dir(&window)?).await)
+}
+
+#[tauri::command]
+#[dropout_macros::api]
+async fn get_active_account(
+    state: State<'_, core::auth::AccountState>,
+) -> Result<Option<core::auth::Account>, String> {
+    Ok(state.active_account.lock().unwrap().clone())
+}
 
-    *state.active_account.lock().unwrap() = None;
+#[tauri::command]
+#[dropout_macros::api]
+async fn logout(window: Window, state: State<'_, core::auth::AccountState>) -> Result<(), String> {
+    core::parental::require_unlocked(window.app_handle(), "change accounts")?;
+    // Get current account before clearing
+    let account = state.active_account.lock().unwrap().take();
+    let uuid = account.as_ref().map(|a| a.uuid());
+
+    // Best effort: the account is removed locally either way
+    if let Some(core::auth::Account::Yggdrasil(ygg_account)) = &account
+        && let Err(e) = core::auth::yggdrasil::invalidate(ygg_account).await
+    {
+        println!("[Auth] Failed to invalidate Yggdrasil token: {}", e);
+    }
 
     // Remove from storage
     if let Some(uuid) = uuid {
@@ -1368,7 +2085,7 @@ async fn logout(window: Window, state: State<'_, core::auth::AccountState>) -> R
             .path()
             .app_data_dir()
             .map_err(|e| e.to_string())?;
-        let storage = core::account_storage::AccountStorage::new(app_dir);
+        let storage = core::account_storage::AccountStorage::new(app_dir.clone());
         storage.remove_account(&uuid)?;
     }
 
@@ -1380,16 +2097,26 @@ async fn logout(window: Window, state: State<'_, core::auth::AccountState>) -> R
 async fn get_settings(
     state: State<'_, core::config::ConfigState>,
 ) -> Result<core::config::LauncherConfig, String> {
-    Ok(state.config.lock().unwrap().clone())
+    let mut config = state.config.lock().unwrap().clone();
+    // The PIN hash stays in the backend; the UI uses get_parental_status
+    config.parental = Default::default();
+    Ok(config)
 }
 
 #[tauri::command]
 #[dropout_macros::api]
 async fn save_settings(
     state: State<'_, core::config::ConfigState>,
-    config: core::config::LauncherConfig,
+    mut config: core::config::LauncherConfig,
 ) -> Result<(), String> {
-    *state.config.lock().unwrap() = config;
+    let mut current = state.config.lock().unwrap();
+    // The PIN only changes through set_launcher_pin, schedules through their
+    // own commands and the local API token when the API is started
+    config.parental = current.parental.clone();
+    config.schedules = current.schedules.clone();
+    config.local_api_token = current.local_api_token.clone();
+    *current = config;
+    drop(current);
     state.save()?;
     Ok(())
 }
@@ -1403,9 +2130,15 @@ async fn get_config_path(state: State<'_, core::config::ConfigState>) -> Result<
 #[tauri::command]
 #[dropout_macros::api]
 async fn read_raw_config(state: State<'_, core::config::ConfigState>) -> Result<String, String> {
-    tokio::fs::read_to_string(&state.file_path)
+    let content = tokio::fs::read_to_string(&state.file_path)
         .await
-        .map_err(|e| e.to_string())
+        .map_err(|e| e.to_string())?;
+    // Leave the PIN hash out; save_raw_config puts the current one back
+    let mut value: serde_json::Value = serde_json::from_str(&content).map_err(|e| e.to_string())?;
+    if let Some(object) = value.as_object_mut() {
+        object.remove("parental");
+    }
+    serde_json::to_string_pretty(&value).map_err(|e| e.to_string())
 }
 
 #[tauri::command]
@@ -1413,10 +2146,28 @@ async fn read_raw_config(state: State<'_, core::config::ConfigState>) -> Result<
 async fn save_raw_config(
     state: State<'_, core::config::ConfigState>,
     content: String,
-) -> Result<(), String> {
+) -> Result<Vec<core::config::ConfigChange>, String> {
     // Validate JSON
-    let new_config: core::config::LauncherConfig =
+    let mut value: serde_json::Value =
         serde_json::from_str(&content).map_err(|e| format!("Invalid JSON: {}", e))?;
+    let mut new_config: core::config::LauncherConfig =
+        serde_json::from_value(value.clone()).map_err(|e| format!("Invalid JSON: {}", e))?;
+    let changes = {
+        let current = state.config.lock().unwrap();
+        // read_raw_config leaves the PIN hash out, so a missing key keeps it
+        if value.get("parental").is_some() && new_config.parental != current.parental {
+            return Err(
+                "The launcher PIN can only be changed from the parental settings".to_string(),
+            );
+        }
+        new_config.parental = current.parental.clone();
+        core::config::diff_configs(&current, &new_config)
+    };
+    if let Some(object) = value.as_object_mut() {
+        let parental = serde_json::to_value(&new_config.parental).map_err(|e| e.to_string())?;
+        object.insert("parental".to_string(), parental);
+    }
+    let content = serde_json::to_string_pretty(&value).map_err(|e| e.to_string())?;
 
     // Save to file
     tokio::fs::write(&state.file_path, &content)
@@ -1424,9 +2175,18 @@ async fn save_raw_config(
         .map_err(|e| e.to_string())?;
 
     // Update in-memory state
+    new_config.apply_download_settings();
     *state.config.lock().unwrap() = new_config;
 
-    Ok(())
+    for change in &changes {
+        println!(
+            "[Config] {}: {} -> {}",
+            change.key,
+            change.old_value.as_deref().unwrap_or("(unset)"),
+            change.new_value.as_deref().unwrap_or("(unset)")
+        );
+    }
+    Ok(changes)
 }
 
 #[tauri::command]
@@ -1443,6 +2203,7 @@ async fn complete_microsoft_login(
     ms_refresh_state: State<'_, MsRefreshTokenState>,
     device_code: String,
 ) -> Result<core::auth::Account, String> {
+    core::parental::require_unlocked(window.app_handle(), "change accounts")?;
     // Helper to emit auth progress
     let emit_progress = |step: &str| {
         let _ = window.emit("auth-progress", step);
@@ -1499,13 +2260,43 @@ async fn complete_microsoft_login(
         .path()
         .app_data_dir()
         .map_err(|e| e.to_string())?;
-    let storage = core::account_storage::AccountStorage::new(app_dir);
+    let storage = core::account_storage::AccountStorage::new(app_dir.clone());
     storage.add_or_update_account(&account, ms_refresh_token)?;
 
     Ok(account)
 }
 
-/// Refresh token for current Microsoft account
+/// Sign in to a third-party Yggdrasil server. `server_url` may be the
+/// server's website or its API root.
+#[tauri::command]
+#[dropout_macros::api]
+async fn login_yggdrasil(
+    window: Window,
+    state: State<'_, core::auth::AccountState>,
+    server_url: String,
+    username: String,
+    password: String,
+) -> Result<core::auth::Account, String> {
+    core::parental::require_unlocked(window.app_handle(), "change accounts")?;
+    let api_root = core::auth::yggdrasil::resolve_api_root(&server_url).await?;
+    let account = core::auth::Account::Yggdrasil(
+        core::auth::yggdrasil::authenticate(&api_root, username.trim(), &password).await?,
+    );
+
+    *state.active_account.lock().unwrap() = Some(account.clone());
+
+    let app_dir = window
+        .app_handle()
+        .path()
+        .app_data_dir()
+        .map_err(|e| e.to_string())?;
+    let storage = core::account_storage::AccountStorage::new(app_dir);
+    storage.add_or_update_account(&account, None)?;
+
+    Ok(account)
+}
+
+/// Refresh the token of the current Microsoft or Yggdrasil account
 #[tauri::command]
 #[dropout_macros::api]
 async fn refresh_account(
@@ -1521,10 +2312,18 @@ async fn refresh_account(
         .map_err(|e| e.to_string())?;
     let storage = core::account_storage::AccountStorage::new(app_dir.clone());
 
-    let (_stored_account, ms_refresh) = storage
+    let (stored_account, ms_refresh) = storage
         .get_active_account()
         .ok_or("No active account found")?;
 
+    if let core::account_storage::StoredAccount::Yggdrasil(ygg_account) = &stored_account {
+        let account =
+            core::auth::Account::Yggdrasil(core::auth::yggdrasil::refresh(ygg_account).await?);
+        *state.active_account.lock().unwrap() = Some(account.clone());
+        storage.add_or_update_account(&account, None)?;
+        return Ok(account);
+    }
+
     let ms_refresh_token = ms_refresh.ok_or("No refresh token available")?;
 
     // Perform full refresh
@@ -1572,6 +2371,7 @@ async fn get_recommended_java(
 #[tauri::command]
 #[dropout_macros::api]
 async fn fetch_adoptium_java(
+    app_handle: tauri::AppHandle,
     major_version: u32,
     image_type: String,
 ) -> Result<core::java::JavaDownloadInfo, String> {
@@ -1579,7 +2379,7 @@ async fn fetch_adoptium_java(
         "jdk" => core::java::ImageType::Jdk,
         _ => core::java::ImageType::Jre,
     };
-    core::java::fetch_java_release(major_version, img_type)
+    core::java::fetch_java_release(&app_handle, major_version, img_type)
         .await
         .map_err(|e| e.to_string())
 }
@@ -1612,13 +2412,16 @@ async fn fetch_available_java_versions() -> Result<Vec<u32>, String> {
         .map_err(|e| e.to_string())
 }
 
-/// Fetch Java catalog with platform availability (uses cache)
+/// Fetch Java catalog with platform availability (uses cache). With a
+/// filter, only the matching releases are returned at first; the full
+/// catalog follows as a `java-catalog-updated` event.
 #[tauri::command]
 #[dropout_macros::api]
 async fn fetch_java_catalog(
     app_handle: tauri::AppHandle,
+    filter: Option<core::java::CatalogFilter>,
 ) -> Result<core::java::JavaCatalog, String> {
-    core::java::fetch_java_catalog(&app_handle, false)
+    core::java::fetch_java_catalog(&app_handle, false, filter.unwrap_or_default())
         .await
         .map_err(|e| e.to_string())
 }
@@ -1629,11 +2432,175 @@ async fn fetch_java_catalog(
 async fn refresh_java_catalog(
     app_handle: tauri::AppHandle,
 ) -> Result<core::java::JavaCatalog, String> {
-    core::java::fetch_java_catalog(&app_handle, true)
+    core::java::fetch_java_catalog(&app_handle, true, Default::default())
         .await
         .map_err(|e| e.to_string())
 }
 
+/// List running download sessions (installs, launches, loader libraries)
+#[tauri::command]
+#[dropout_macros::api]
+async fn get_active_downloads(
+    manager: State<'_, core::download_manager::DownloadManager>,
+) -> Result<Vec<core::download_manager::DownloadSessionInfo>, String> {
+    Ok(manager.active())
+}
+
+/// Pause a download session; running files stop at their next chunk
+#[tauri::command]
+#[dropout_macros::api]
+async fn pause_download(
+    manager: State<'_, core::download_manager::DownloadManager>,
+    session_id: String,
+) -> Result<(), String> {
+    manager.pause(&session_id)
+}
+
+/// Resume a paused download session
+#[tauri::command]
+#[dropout_macros::api]
+async fn resume_download(
+    manager: State<'_, core::download_manager::DownloadManager>,
+    session_id: String,
+) -> Result<(), String> {
+    manager.resume(&session_id)
+}
+
+/// Cancel a download session; the install or launch waiting on it fails
+#[tauri::command]
+#[dropout_macros::api]
+async fn cancel_download(
+    manager: State<'_, core::download_manager::DownloadManager>,
+    session_id: String,
+) -> Result<(), String> {
+    manager.cancel(&session_id)
+}
+
+/// Downloads and games that quitting now would interrupt
+#[tauri::command]
+#[dropout_macros::api]
+async fn get_active_work(
+    app_handle: tauri::AppHandle,
+) -> Result<core::shutdown::ActiveWork, String> {
+    Ok(core::shutdown::active_work(&app_handle))
+}
+
+/// Quit even though downloads or games are running
+#[tauri::command]
+#[dropout_macros::api]
+async fn confirm_exit(
+    app_handle: tauri::AppHandle,
+    shutdown_state: State<'_, core::shutdown::ShutdownState>,
+) -> Result<(), String> {
+    shutdown_state.confirm_exit();
+    app_handle.exit(0);
+    Ok(())
+}
+
+/// Whether the previous run of the launcher exited normally
+#[tauri::command]
+#[dropout_macros::api]
+async fn was_last_shutdown_clean(
+    shutdown_state: State<'_, core::shutdown::ShutdownState>,
+) -> Result<bool, String> {
+    Ok(shutdown_state.previous_clean)
+}
+
+/// Clear saved accounts, the assistant's history, logs, caches or all of
+/// them. With `dry_run` nothing is deleted and the report lists what would be.
+#[tauri::command]
+#[dropout_macros::api]
+async fn reset_launcher(
+    app_handle: tauri::AppHandle,
+    auth_state: State<'_, core::auth::AccountState>,
+    ms_refresh_state: State<'_, MsRefreshTokenState>,
+    assistant_state: State<'_, core::assistant::AssistantState>,
+    scope: core::reset::ResetScope,
+    dry_run: bool,
+) -> Result<core::reset::ResetReport, String> {
+    use core::reset::ResetScope;
+    if !dry_run && scope.includes(ResetScope::Accounts) {
+        core::parental::require_unlocked(&app_handle, "change accounts")?;
+    }
+    let handle = app_handle.clone();
+    let report = tokio::task::spawn_blocking(move || core::reset::reset(&handle, scope, dry_run))
+        .await
+        .map_err(|e| e.to_string())??;
+    if dry_run {
+        return Ok(report);
+    }
+
+    if scope.includes(ResetScope::Accounts) {
+        *auth_state.active_account.lock().unwrap() = None;
+        *ms_refresh_state.token.lock().unwrap() = None;
+    }
+    if scope.includes(ResetScope::Assistant) {
+        assistant_state.assistant.lock().unwrap().clear_logs();
+    }
+    println!(
+        "[Reset] Cleared {:?}: {} entries, {} bytes, {} error(s)",
+        scope,
+        report.entries.len(),
+        report.total_bytes,
+        report.errors.len()
+    );
+    Ok(report)
+}
+
+/// Whether a launcher PIN is set and whether this session is unlocked
+#[tauri::command]
+#[dropout_macros::api]
+async fn get_parental_status(
+    app_handle: tauri::AppHandle,
+) -> Result<core::parental::ParentalStatus, String> {
+    Ok(core::parental::status(&app_handle))
+}
+
+/// Allow account, instance and mod changes until `lock_launcher` or restart
+#[tauri::command]
+#[dropout_macros::api]
+async fn unlock_launcher(app_handle: tauri::AppHandle, pin: String) -> Result<(), String> {
+    core::parental::unlock(&app_handle, &pin)
+}
+
+#[tauri::command]
+#[dropout_macros::api]
+async fn lock_launcher(app_handle: tauri::AppHandle) -> Result<(), String> {
+    core::parental::lock(&app_handle);
+    Ok(())
+}
+
+/// Set, change or (with no `new_pin`) remove the launcher PIN
+#[tauri::command]
+#[dropout_macros::api]
+async fn set_launcher_pin(
+    app_handle: tauri::AppHandle,
+    current_pin: Option<String>,
+    new_pin: Option<String>,
+) -> Result<(), String> {
+    core::parental::set_pin(&app_handle, current_pin.as_deref(), new_pin.as_deref())
+}
+
+/// What the interrupted previous session left behind, if anything (also
+/// sent as `recovery-report` at startup)
+#[tauri::command]
+#[dropout_macros::api]
+async fn get_recovery_report(
+    recovery_state: State<'_, core::recovery::RecoveryState>,
+) -> Result<Option<core::recovery::RecoveryReport>, String> {
+    Ok(recovery_state.report.lock().unwrap().clone())
+}
+
+/// Resume, clean up or ignore what the recovery scan found
+#[tauri::command]
+#[dropout_macros::api]
+async fn apply_recovery(
+    app_handle: tauri::AppHandle,
+    action: core::recovery::RecoveryAction,
+) -> Result<(), String> {
+    core::recovery::apply(&app_handle, action).await
+}
+
 /// Cancel current Java download
 #[tauri::command]
 #[dropout_macros::api]
@@ -1647,7 +2614,7 @@ async fn cancel_java_download() -> Result<(), String> {
 #[dropout_macros::api]
 async fn get_pending_java_downloads(
     app_handle: tauri::AppHandle,
-) -> Result<Vec<core::downloader::PendingJavaDownload>, String> {
+) -> Result<Vec<core::java::PendingJavaDownload>, String> {
     Ok(core::java::get_pending_downloads(&app_handle))
 }
 
@@ -1694,6 +2661,7 @@ async fn get_fabric_loaders_for_version(
 #[dropout_macros::api]
 async fn install_fabric(
     window: Window,
+    config_state: State<'_, core::config::ConfigState>,
     instance_state: State<'_, core::instance::InstanceState>,
     instance_id: String,
     game_version: String,
@@ -1715,6 +2683,45 @@ async fn install_fabric(
         .await
         .map_err(|e| e.to_string())?;
 
+    let config = config_state.config.lock().unwrap().clone();
+    if config.predownload_loader_libraries {
+        // Intermediary and the loader's dependencies aren't in the vanilla
+        // JSON; get them now rather than stalling the first launch
+        let libraries_dir = if config.use_shared_caches {
+            window
+                .app_handle()
+                .path()
+                .app_data_dir()
+                .map_err(|e| e.to_string())?
+                .join("libraries")
+        } else {
+            game_dir.join("libraries")
+        };
+        let profile: serde_json::Value = std::fs::read_to_string(&result.path)
+            .map_err(|e| e.to_string())
+            .and_then(|content| serde_json::from_str(&content).map_err(|e| e.to_string()))?;
+        let tasks = core::fabric::library_tasks(&profile, &libraries_dir);
+        emit_log!(
+            window,
+            format!("Downloading {} Fabric libraries...", tasks.len())
+        );
+        let audit = core::download_audit::DownloadAudit::open_or_warn(
+            window.app_handle(),
+            &instance_id,
+            "install",
+        );
+        core::downloader::download_files(
+            window.clone(),
+            tasks,
+            core::downloader::concurrency_for_instance(
+                &config,
+                instance_state.get_instance(&instance_id).as_ref(),
+            ),
+            audit.as_ref(),
+        )
+        .await?;
+    }
+
     emit_log!(
         window,
         format!("Fabric installed successfully: {}", result.id)
@@ -1771,6 +2778,45 @@ async fn get_version_java_version(
     }
 }
 
+/// Explain why each library and conditional argument of a version is used.
+/// `os`/`arch` (e.g. "windows", "aarch64") evaluate for another platform.
+#[tauri::command]
+#[dropout_macros::api]
+async fn evaluate_rules_report(
+    config_state: State<'_, core::config::ConfigState>,
+    instance_state: State<'_, core::instance::InstanceState>,
+    instance_id: String,
+    version_id: String,
+    os: Option<String>,
+    arch: Option<String>,
+) -> Result<core::rules::RulesReport, String> {
+    let game_dir = instance_state
+        .get_instance_game_dir(&instance_id)
+        .ok_or_else(|| format!("Instance {} not found", instance_id))?;
+    let version = core::manifest::load_version(&game_dir, &version_id)
+        .await
+        .map_err(|e| e.to_string())?;
+    let (feature_flags, native_override) = {
+        let config = config_state.config.lock().unwrap();
+        (
+            config.feature_flags.clone(),
+            config.native_classifier_override.clone(),
+        )
+    };
+
+    let current = core::rules::Platform::current();
+    let platform = core::rules::Platform {
+        os: os.unwrap_or(current.os),
+        arch: arch.unwrap_or(current.arch),
+    };
+    Ok(core::rules::evaluate_rules_report(
+        &version,
+        Some(&feature_flags),
+        native_override.as_deref(),
+        &platform,
+    ))
+}
+
 /// Version metadata for display in the UI
 #[derive(serde::Serialize, TS)]
 #[serde(rename_all = "camelCase")]
@@ -1819,6 +2865,8 @@ async fn delete_version(
         if (version_id.starts_with("fabric-loader-")
             && instance.mod_loader == Some("fabric".to_string()))
             || (version_id.contains("-forge-") && instance.mod_loader == Some("forge".to_string()))
+            || (version_id.contains("-neoforge-")
+                && instance.mod_loader == Some("neoforge".to_string()))
         {
             instance.mod_loader = None;
             instance.mod_loader_version = None;
@@ -1883,10 +2931,13 @@ async fn get_version_metadata(
             .join("versions")
             .join(&minecraft_version)
             .join(format!("{}.jar", minecraft_version))
-    } else if version_id.contains("-forge-") {
-        // Format: 1.20.4-forge-49.0.38
+    } else if let Some(separator) = ["-forge-", "-neoforge-", "-OptiFine_"]
+        .into_iter()
+        .find(|separator| version_id.contains(separator))
+    {
+        // Format: 1.20.4-forge-49.0.38, 1.21.1-neoforge-21.1.77 or 1.20.1-OptiFine_HD_U_I6
         let minecraft_version = version_id
-            .split("-forge-")
+            .split(separator)
             .next()
             .unwrap_or(&version_id)
             .to_string();
@@ -1911,7 +2962,11 @@ async fn get_version_metadata(
     } else if metadata.java_version.is_none() {
         // If not installed and we don't have Java version yet, try to fetch from remote
         // This is for vanilla versions that are not installed
-        if !version_id.starts_with("fabric-loader-") && !version_id.contains("-forge-") {
+        if !version_id.starts_with("fabric-loader-")
+            && !version_id.contains("-forge-")
+            && !version_id.contains("-neoforge-")
+            && !version_id.contains("-OptiFine_")
+        {
             if let Ok(game_version) = core::manifest::fetch_vanilla_version(&version_id).await {
                 if let Some(java_ver) = game_version.java_version {
                     metadata.java_version = Some(java_ver.major_version);
@@ -1930,7 +2985,7 @@ async fn get_version_metadata(
 struct InstalledVersion {
     id: String,
     #[serde(rename = "type")]
-    version_type: String, // "release", "snapshot", "fabric", "forge", "modpack"
+    version_type: String, // "release", "snapshot", "fabric", "forge", "neoforge", "optifine", "modpack"
 }
 
 /// List all installed versions from the data directory
@@ -1969,6 +3024,10 @@ async fn list_installed_versions(
         // Determine version type based on folder name or JSON content
         let version_type = if name.starts_with("fabric-loader-") {
             "fabric".to_string()
+        } else if name.contains("-neoforge-") {
+            "neoforge".to_string()
+        } else if name.contains("-OptiFine_") {
+            "optifine".to_string()
         } else if name.contains("-forge") || name.contains("forge-") {
             "forge".to_string()
         } else {
@@ -2002,12 +3061,12 @@ async fn list_installed_versions(
     // Sort: modded/modpack first, then by version id descending
     installed.sort_by(|a, b| {
         let a_priority = match a.version_type.as_str() {
-            "fabric" | "forge" => 0,
+            "fabric" | "forge" | "neoforge" | "optifine" => 0,
             "modpack" => 1,
             _ => 2,
         };
         let b_priority = match b.version_type.as_str() {
-            "fabric" | "forge" => 0,
+            "fabric" | "forge" | "neoforge" | "optifine" => 0,
             "modpack" => 1,
             _ => 2,
         };
@@ -2062,6 +3121,114 @@ async fn get_forge_versions_for_game(
         .map_err(|e| e.to_string())
 }
 
+/// Java to run a loader installer with: the configured one, else the first detected
+async fn installer_java_path(
+    window: &Window,
+    config: &core::config::LauncherConfig,
+) -> Result<std::path::PathBuf, String> {
+    let java_path_str = if !config.java_path.is_empty() && config.java_path != "java" {
+        config.java_path.clone()
+    } else {
+        // Try to find a suitable Java installation
+        let javas = core::java::detect_all_java_installations(window.app_handle()).await;
+        if let Some(java) = javas.first() {
+            java.path.clone()
+        } else {
+            return Err(
+                "No Java installation found. Please configure Java in settings.".to_string(),
+            );
+        }
+    };
+    utils::path::normalize_java_path(&java_path_str)
+}
+
+/// Install Forge or NeoForge by running the installer's processors directly
+/// and writing the version JSON as `version_id`, without launching the
+/// installer itself
+#[allow(clippy::too_many_arguments)]
+async fn install_loader_natively(
+    window: &Window,
+    config: &core::config::LauncherConfig,
+    game_dir: &std::path::Path,
+    game_version: &str,
+    version_id: &str,
+    java_path: &std::path::Path,
+    audit: Option<&core::download_audit::DownloadAudit>,
+    download_installer: impl AsyncFnOnce(&std::path::Path) -> Result<(), String>,
+) -> Result<(), String> {
+    let app_data_dir = window
+        .app_handle()
+        .path()
+        .app_data_dir()
+        .map_err(|e| e.to_string())?;
+    let (versions_dir, libraries_dir) = if config.use_shared_caches {
+        (
+            app_data_dir.join("versions"),
+            app_data_dir.join("libraries"),
+        )
+    } else {
+        (game_dir.join("versions"), game_dir.join("libraries"))
+    };
+    let minecraft_jar = versions_dir
+        .join(game_version)
+        .join(format!("{}.jar", game_version));
+    if !minecraft_jar.exists() {
+        return Err(format!("Minecraft {} is not installed yet", game_version));
+    }
+
+    let work_dir = std::env::temp_dir().join(format!("dropout-installer-{}", uuid::Uuid::new_v4()));
+    let ctx = core::forge::processors::ProcessorContext {
+        installer_path: work_dir.join("installer.jar"),
+        libraries_dir,
+        minecraft_jar,
+        game_dir: game_dir.to_path_buf(),
+        work_dir: work_dir.clone(),
+    };
+    let result = async {
+        emit_log!(
+            window,
+            format!("Downloading installer for {}...", version_id)
+        );
+        download_installer(&ctx.installer_path).await?;
+        let (profile, mut version_json) =
+            core::forge::processors::read_installer(&ctx.installer_path)?;
+
+        let tasks = core::forge::processors::prepare_libraries(
+            &ctx.installer_path,
+            &profile,
+            &ctx.libraries_dir,
+        )?;
+        emit_log!(
+            window,
+            format!("Downloading {} installer libraries...", tasks.len())
+        );
+        core::downloader::download_files(
+            window.clone(),
+            tasks,
+            core::downloader::concurrency_from_config(config),
+            audit,
+        )
+        .await?;
+
+        core::forge::processors::run_processors(&profile, &ctx, java_path, |line| {
+            emit_log!(window, &line);
+        })
+        .await?;
+
+        version_json["id"] = serde_json::Value::String(version_id.to_string());
+        let version_dir = game_dir.join("versions").join(version_id);
+        std::fs::create_dir_all(&version_dir).map_err(|e| e.to_string())?;
+        std::fs::write(
+            version_dir.join(format!("{}.json", version_id)),
+            serde_json::to_string_pretty(&version_json).map_err(|e| e.to_string())?,
+        )
+        .map_err(|e| e.to_string())
+    }
+    .await;
+    let _ = std::fs::remove_dir_all(&work_dir);
+    result
+}
+
 /// Install Forge for a specific Minecraft version
 #[tauri::command]
 #[dropout_macros::api]
@@ -2085,30 +3252,88 @@ async fn install_forge(
         .get_instance_game_dir(&instance_id)
         .ok_or_else(|| format!("Instance {} not found", instance_id))?;
 
-    // Get Java path from config or detect
     let config = config_state.config.lock().unwrap().clone();
-    let app_handle = window.app_handle();
-    let java_path_str = if !config.java_path.is_empty() && config.java_path != "java" {
-        config.java_path.clone()
-    } else {
-        // Try to find a suitable Java installation
-        let javas = core::java::detect_all_java_installations(app_handle).await;
-        if let Some(java) = javas.first() {
-            java.path.clone()
-        } else {
-            return Err(
-                "No Java installation found. Please configure Java in settings.".to_string(),
-            );
-        }
-    };
-    let java_path = utils::path::normalize_java_path(&java_path_str)?;
-
-    emit_log!(window, "Running Forge installer...".to_string());
+    let java_path = installer_java_path(&window, &config).await?;
+    let audit = core::download_audit::DownloadAudit::open_or_warn(
+        window.app_handle(),
+        &instance_id,
+        "install",
+    );
 
-    // Run the Forge installer to properly patch the client
-    core::forge::run_forge_installer(&game_dir, &game_version, &forge_version, &java_path)
-        .await
-        .map_err(|e| format!("Forge installer failed: {}", e))?;
+    // Run the installer's processors ourselves when possible; legacy installers
+    // (and anything the native pipeline can't handle) go through the official one
+    if let Err(e) = install_loader_natively(
+        &window,
+        &config,
+        &game_dir,
+        &game_version,
+        &core::forge::generate_version_id(&game_version, &forge_version),
+        &java_path,
+        audit.as_ref(),
+        async |dest| {
+            core::forge::download_installer(&game_version, &forge_version, dest)
+                .await
+                .map_err(|e| e.to_string())
+        },
+    )
+    .await
+    {
+        emit_log!(
+            window,
+            format!(
+                "Native Forge install not possible ({}), running Forge installer...",
+                e
+            )
+        );
+        // Run the Forge installer to properly patch the client. Its libraries
+        // are fetched first through our downloader (progress, mirrors), so it
+        // can run offline when the vanilla jar is in place too.
+        let installer_path = game_dir.join("forge-installer.jar");
+        let result = async {
+            core::forge::download_installer(&game_version, &forge_version, &installer_path)
+                .await
+                .map_err(|e| e.to_string())?;
+            let offline = match core::forge::processors::installer_library_tasks(
+                &installer_path,
+                &game_dir.join("libraries"),
+            ) {
+                Ok(tasks) => {
+                    emit_log!(
+                        window,
+                        format!("Downloading {} Forge installer libraries...", tasks.len())
+                    );
+                    core::downloader::download_files(
+                        window.clone(),
+                        tasks,
+                        core::downloader::concurrency_for_instance(
+                            &config,
+                            instance_state.get_instance(&instance_id).as_ref(),
+                        ),
+                        audit.as_ref(),
+                    )
+                    .await?;
+                    game_dir
+                        .join("versions")
+                        .join(&game_version)
+                        .join(format!("{}.jar", game_version))
+                        .exists()
+                }
+                Err(e) => {
+                    emit_log!(
+                        window,
+                        format!("Installer will download its own libraries ({})", e)
+                    );
+                    false
+                }
+            };
+            core::forge::run_installer_jar(&installer_path, &game_dir, &java_path, offline)
+                .await
+                .map_err(|e| format!("Forge installer failed: {}", e))
+        }
+        .await;
+        let _ = std::fs::remove_file(&installer_path);
+        result?;
+    }
 
     emit_log!(
         window,
@@ -2160,59 +3385,491 @@ async fn install_forge(
     Ok(result)
 }
 
-#[derive(serde::Serialize, TS)]
-#[serde(rename_all = "camelCase")]
-#[ts(export, export_to = "core.ts")]
-struct GithubRelease {
-    tag_name: String,
-    name: String,
-    published_at: String,
-    body: String,
-    html_url: String,
+/// Get Minecraft versions supported by NeoForge
+#[tauri::command]
+#[dropout_macros::api]
+async fn get_neoforge_game_versions() -> Result<Vec<String>, String> {
+    core::neoforge::fetch_supported_game_versions()
+        .await
+        .map_err(|e| e.to_string())
 }
 
+/// Get available NeoForge versions for a specific Minecraft version
 #[tauri::command]
 #[dropout_macros::api]
-async fn get_github_releases() -> Result<Vec<GithubRelease>, String> {
-    let client = reqwest::Client::new();
-    let res = client
-        .get("https://api.github.com/repos/HydroRoll-Team/DropOut/releases")
-        .header("User-Agent", "DropOut-Launcher")
-        .send()
+async fn get_neoforge_versions_for_game(
+    game_version: String,
+) -> Result<Vec<core::neoforge::NeoForgeVersion>, String> {
+    core::neoforge::fetch_neoforge_versions(&game_version)
         .await
-        .map_err(|e| e.to_string())?;
+        .map_err(|e| e.to_string())
+}
 
-    if !res.status().is_success() {
-        return Err(format!("GitHub API returned status: {}", res.status()));
-    }
+/// Install NeoForge for a specific Minecraft version
+#[tauri::command]
+#[dropout_macros::api]
+async fn install_neoforge(
+    window: Window,
+    config_state: State<'_, core::config::ConfigState>,
+    instance_state: State<'_, core::instance::InstanceState>,
+    instance_id: String,
+    game_version: String,
+    neoforge_version: String,
+) -> Result<core::neoforge::InstalledNeoForgeVersion, String> {
+    emit_log!(
+        window,
+        format!(
+            "Installing NeoForge {} for Minecraft {} in instance {}...",
+            neoforge_version, game_version, instance_id
+        )
+    );
 
-    let releases: Vec<serde_json::Value> = res.json().await.map_err(|e| e.to_string())?;
+    let game_dir = instance_state
+        .get_instance_game_dir(&instance_id)
+        .ok_or_else(|| format!("Instance {} not found", instance_id))?;
+    let config = config_state.config.lock().unwrap().clone();
+    let java_path = installer_java_path(&window, &config).await?;
 
-    let mut result = Vec::new();
-    for r in releases {
-        if let (Some(tag), Some(name), Some(date), Some(body), Some(url)) = (
-            r["tag_name"].as_str(),
-            r["name"].as_str(),
-            r["published_at"].as_str(),
-            r["body"].as_str(),
-            r["html_url"].as_str(),
-        ) {
-            result.push(GithubRelease {
-                tag_name: tag.to_string(),
-                name: name.to_string(),
-                published_at: date.to_string(),
-                body: body.to_string(),
-                html_url: url.to_string(),
-            });
+    let version_id = core::neoforge::generate_version_id(&game_version, &neoforge_version);
+    let audit = core::download_audit::DownloadAudit::open_or_warn(
+        window.app_handle(),
+        &instance_id,
+        "install",
+    );
+    let result = match install_loader_natively(
+        &window,
+        &config,
+        &game_dir,
+        &game_version,
+        &version_id,
+        &java_path,
+        audit.as_ref(),
+        async |dest| {
+            core::neoforge::download_installer(&game_version, &neoforge_version, dest)
+                .await
+                .map_err(|e| e.to_string())
+        },
+    )
+    .await
+    {
+        Ok(()) => core::neoforge::InstalledNeoForgeVersion {
+            path: game_dir
+                .join("versions")
+                .join(&version_id)
+                .join(format!("{}.json", version_id)),
+            id: version_id,
+            minecraft_version: game_version.clone(),
+            neoforge_version: neoforge_version.clone(),
+        },
+        Err(e) => {
+            emit_log!(
+                window,
+                format!(
+                    "Native NeoForge install not possible ({}), running NeoForge installer...",
+                    e
+                )
+            );
+            core::neoforge::run_neoforge_installer(
+                &game_dir,
+                &game_version,
+                &neoforge_version,
+                &java_path,
+            )
+            .await
+            .map_err(|e| format!("NeoForge installer failed: {}", e))?;
+            // The installer names the version after itself; write ours
+            core::neoforge::install_neoforge(&game_dir, &game_version, &neoforge_version)
+                .await
+                .map_err(|e| e.to_string())?
         }
+    };
+
+    emit_log!(
+        window,
+        format!("NeoForge installed successfully: {}", result.id)
+    );
+
+    if let Some(mut instance) = instance_state.get_instance(&instance_id) {
+        instance.mod_loader = Some("neoforge".to_string());
+        instance.mod_loader_version = Some(neoforge_version.clone());
+        instance.version_id = Some(result.id.clone());
+        instance_state.update_instance(instance)?;
     }
-    Ok(result)
-}
 
-#[derive(Serialize, TS)]
-#[ts(export, export_to = "core.ts")]
-struct PastebinResponse {
-    url: String,
+    let _ = window.emit("neoforge-installed", &result.id);
+
+    Ok(result)
+}
+
+/// Get OptiFine releases for a specific Minecraft version
+#[tauri::command]
+#[dropout_macros::api]
+async fn get_optifine_versions_for_game(
+    game_version: String,
+) -> Result<Vec<core::optifine::OptiFineVersion>, String> {
+    core::optifine::fetch_versions_for_game(&game_version)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+/// Install OptiFine. On a Forge instance it is added to `mods/`; otherwise it
+/// becomes a version inheriting from the (installed) vanilla version.
+#[tauri::command]
+#[dropout_macros::api]
+async fn install_optifine(
+    window: Window,
+    config_state: State<'_, core::config::ConfigState>,
+    instance_state: State<'_, core::instance::InstanceState>,
+    instance_id: String,
+    game_version: String,
+    edition: String,
+) -> Result<core::optifine::InstalledOptiFineVersion, String> {
+    emit_log!(
+        window,
+        format!(
+            "Installing OptiFine {} for Minecraft {} in instance {}...",
+            edition, game_version, instance_id
+        )
+    );
+
+    let instance = instance_state
+        .get_instance(&instance_id)
+        .ok_or_else(|| format!("Instance {} not found", instance_id))?;
+    let game_dir = instance_state
+        .get_instance_game_dir(&instance_id)
+        .ok_or_else(|| format!("Instance {} not found", instance_id))?;
+    let file_name = core::optifine::release_file_name(&game_version, &edition);
+
+    if instance.mod_loader.as_deref() == Some("forge") {
+        let path = game_dir.join("mods").join(&file_name);
+        core::optifine::download(&file_name, &path)
+            .await
+            .map_err(|e| e.to_string())?;
+        emit_log!(window, format!("OptiFine added as a mod: {}", file_name));
+        return Ok(core::optifine::InstalledOptiFineVersion {
+            id: file_name,
+            minecraft_version: game_version,
+            edition,
+            path,
+        });
+    }
+
+    let config = config_state.config.lock().unwrap().clone();
+    let app_data_dir = window
+        .app_handle()
+        .path()
+        .app_data_dir()
+        .map_err(|e| e.to_string())?;
+    let (versions_dir, libraries_dir) = if config.use_shared_caches {
+        (
+            app_data_dir.join("versions"),
+            app_data_dir.join("libraries"),
+        )
+    } else {
+        (game_dir.join("versions"), game_dir.join("libraries"))
+    };
+    let minecraft_jar = versions_dir
+        .join(&game_version)
+        .join(format!("{}.jar", game_version));
+    if !minecraft_jar.exists() {
+        return Err(format!("Minecraft {} is not installed yet", game_version));
+    }
+    let java_path = installer_java_path(&window, &config).await?;
+
+    let work_dir = std::env::temp_dir().join(format!("dropout-optifine-{}", uuid::Uuid::new_v4()));
+    let installer_path = work_dir.join(&file_name);
+    let result = async {
+        emit_log!(window, format!("Downloading {}...", file_name));
+        core::optifine::download(&file_name, &installer_path)
+            .await
+            .map_err(|e| e.to_string())?;
+        emit_log!(
+            window,
+            "Patching the Minecraft client for OptiFine...".to_string()
+        );
+        core::optifine::install_optifine(
+            &game_dir,
+            &libraries_dir,
+            &minecraft_jar,
+            &installer_path,
+            &java_path,
+        )
+        .await
+        .map_err(|e| e.to_string())
+    }
+    .await;
+    let _ = std::fs::remove_dir_all(&work_dir);
+    let result = result?;
+
+    emit_log!(
+        window,
+        format!("OptiFine installed successfully: {}", result.id)
+    );
+
+    if let Some(mut instance) = instance_state.get_instance(&instance_id) {
+        instance.version_id = Some(result.id.clone());
+        instance_state.update_instance(instance)?;
+    }
+
+    let _ = window.emit("optifine-installed", &result.id);
+
+    Ok(result)
+}
+
+/// Download a modpack archive from an HTTP URL or a torrent's web seeds
+#[tauri::command]
+#[dropout_macros::api]
+async fn download_modpack_archive(
+    window: Window,
+    config_state: State<'_, core::config::ConfigState>,
+    source: core::web_seed::PackSource,
+) -> Result<String, String> {
+    let concurrency = {
+        let config = config_state.config.lock().unwrap();
+        core::downloader::concurrency_from_config(&config)
+    };
+    let dest_dir = window
+        .app_handle()
+        .path()
+        .app_data_dir()
+        .map_err(|e| e.to_string())?
+        .join("cache")
+        .join("modpacks");
+    std::fs::create_dir_all(&dest_dir).map_err(|e| e.to_string())?;
+
+    let audit = core::download_audit::DownloadAudit::open_or_warn(
+        window.app_handle(),
+        core::download_audit::PACK_ARCHIVES,
+        "install",
+    );
+    let path =
+        core::web_seed::download_pack(&window, &source, &dest_dir, concurrency, audit.as_ref())
+            .await?;
+    Ok(path.to_string_lossy().to_string())
+}
+
+#[tauri::command]
+#[dropout_macros::api]
+async fn get_github_releases(
+    app_handle: tauri::AppHandle,
+    config_state: State<'_, core::config::ConfigState>,
+    force: Option<bool>,
+) -> Result<core::releases::GithubReleasesResponse, String> {
+    let (token, interval_hours) = {
+        let config = config_state.config.lock().unwrap();
+        (
+            config.github_token.clone(),
+            config.release_check_interval_hours,
+        )
+    };
+
+    core::releases::get_releases(
+        &app_handle,
+        token.as_deref(),
+        interval_hours as u64 * 60 * 60,
+        force.unwrap_or(false),
+    )
+    .await
+}
+
+/// Probe Mojang, Microsoft/Xbox auth, Fabric Meta and Adoptium
+#[tauri::command]
+#[dropout_macros::api]
+async fn get_service_status() -> Result<Vec<core::status::ServiceStatus>, String> {
+    Ok(core::status::get_service_status().await)
+}
+
+/// System locale and the launch defaults derived from it
+#[tauri::command]
+#[dropout_macros::api]
+async fn get_locale_info(
+    config_state: State<'_, core::config::ConfigState>,
+) -> Result<core::locale::LocaleInfo, String> {
+    let mode = config_state.config.lock().unwrap().utf8_jvm_args.clone();
+    Ok(core::locale::locale_info(&mode))
+}
+
+/// Start announcing this launcher on the LAN and accepting synced instances
+#[tauri::command]
+#[dropout_macros::api]
+async fn start_lan_sync(
+    app_handle: tauri::AppHandle,
+) -> Result<core::lan_sync::LanSyncInfo, String> {
+    core::lan_sync::start(&app_handle).await
+}
+
+#[tauri::command]
+#[dropout_macros::api]
+async fn stop_lan_sync(app_handle: tauri::AppHandle) -> Result<(), String> {
+    core::lan_sync::stop(&app_handle);
+    Ok(())
+}
+
+/// Pairing code and listening port of this launcher
+#[tauri::command]
+#[dropout_macros::api]
+async fn get_lan_sync_info(
+    lan_state: State<'_, core::lan_sync::LanSyncState>,
+) -> Result<core::lan_sync::LanSyncInfo, String> {
+    Ok(lan_state.info())
+}
+
+/// Other DropOut launchers discovered on the LAN
+#[tauri::command]
+#[dropout_macros::api]
+async fn list_lan_peers(
+    lan_state: State<'_, core::lan_sync::LanSyncState>,
+) -> Result<Vec<core::lan_sync::LanPeer>, String> {
+    Ok(lan_state.list_peers())
+}
+
+/// Send an instance's mods and config to a peer, which imports it as a new instance
+#[tauri::command]
+#[dropout_macros::api]
+async fn sync_instance_to_peer(
+    app_handle: tauri::AppHandle,
+    instance_state: State<'_, core::instance::InstanceState>,
+    lan_state: State<'_, core::lan_sync::LanSyncState>,
+    instance_id: String,
+    peer_id: String,
+    pairing_code: String,
+) -> Result<(), String> {
+    let instance = instance_state
+        .get_instance(&instance_id)
+        .ok_or_else(|| format!("Instance {} not found", instance_id))?;
+    let peer = lan_state
+        .peers
+        .lock()
+        .unwrap()
+        .get(&peer_id)
+        .cloned()
+        .ok_or_else(|| "Peer is no longer available".to_string())?;
+    core::lan_sync::send_instance(&app_handle, &instance, &peer, &pairing_code).await
+}
+
+/// Back up instance folders to the configured cloud storage (encrypted)
+#[tauri::command]
+#[dropout_macros::api]
+async fn backup_instance_to_cloud(
+    config_state: State<'_, core::config::ConfigState>,
+    instance_state: State<'_, core::instance::InstanceState>,
+    instance_id: String,
+    folders: Option<Vec<String>>,
+) -> Result<core::sync::BackupEntry, String> {
+    let config = config_state.config.lock().unwrap().cloud_sync.clone();
+    let instance = instance_state
+        .get_instance(&instance_id)
+        .ok_or_else(|| format!("Instance {} not found", instance_id))?;
+    core::sync::backup_instance(&config, &instance, folders.unwrap_or_default()).await
+}
+
+/// List cloud backups, newest first
+#[tauri::command]
+#[dropout_macros::api]
+async fn list_cloud_backups(
+    config_state: State<'_, core::config::ConfigState>,
+    instance_id: Option<String>,
+) -> Result<Vec<core::sync::BackupEntry>, String> {
+    let config = config_state.config.lock().unwrap().cloud_sync.clone();
+    core::sync::list_backups(&config, instance_id.as_deref()).await
+}
+
+/// Restore a cloud backup into its original instance or `instance_id`
+#[tauri::command]
+#[dropout_macros::api]
+async fn restore_cloud_backup(
+    config_state: State<'_, core::config::ConfigState>,
+    instance_state: State<'_, core::instance::InstanceState>,
+    backup_id: String,
+    instance_id: Option<String>,
+) -> Result<core::sync::BackupEntry, String> {
+    let config = config_state.config.lock().unwrap().cloud_sync.clone();
+    let target_id = match instance_id {
+        Some(id) => id,
+        None => core::sync::list_backups(&config, None)
+            .await?
+            .into_iter()
+            .find(|b| b.id == backup_id)
+            .map(|b| b.instance_id)
+            .ok_or_else(|| format!("Backup {} not found", backup_id))?,
+    };
+    let instance = instance_state
+        .get_instance(&target_id)
+        .ok_or_else(|| format!("Instance {} not found", target_id))?;
+    core::sync::restore_backup(&config, &backup_id, &instance).await
+}
+
+#[tauri::command]
+#[dropout_macros::api]
+async fn delete_cloud_backup(
+    config_state: State<'_, core::config::ConfigState>,
+    backup_id: String,
+) -> Result<(), String> {
+    let config = config_state.config.lock().unwrap().cloud_sync.clone();
+    core::sync::delete_backup(&config, &backup_id).await
+}
+
+/// Export settings, instance metadata and optionally accounts to an encrypted file
+#[tauri::command]
+#[dropout_macros::api]
+async fn export_profile(
+    app_handle: tauri::AppHandle,
+    path: String,
+    passphrase: String,
+    include_accounts: bool,
+) -> Result<(), String> {
+    tokio::task::spawn_blocking(move || {
+        core::profile::export_profile(
+            &app_handle,
+            std::path::Path::new(&path),
+            &passphrase,
+            include_accounts,
+        )
+    })
+    .await
+    .map_err(|e| e.to_string())?
+}
+
+/// Import a profile exported with `export_profile`
+#[tauri::command]
+#[dropout_macros::api]
+async fn import_profile(
+    app_handle: tauri::AppHandle,
+    auth_state: State<'_, core::auth::AccountState>,
+    ms_refresh_state: State<'_, MsRefreshTokenState>,
+    path: String,
+    passphrase: String,
+) -> Result<core::profile::ProfileImportSummary, String> {
+    core::parental::require_unlocked(&app_handle, "change accounts")?;
+    // Argon2 and the file/folder work would stall the async runtime
+    let handle = app_handle.clone();
+    let summary = tokio::task::spawn_blocking(move || {
+        core::profile::import_profile(&handle, std::path::Path::new(&path), &passphrase)
+    })
+    .await
+    .map_err(|e| e.to_string())??;
+
+    // Sign in with the imported active account if none is active yet
+    if auth_state.active_account.lock().unwrap().is_none() {
+        let app_dir = app_handle
+            .path()
+            .app_data_dir()
+            .map_err(|e| e.to_string())?;
+        let storage = core::account_storage::AccountStorage::new(app_dir.clone());
+        if let Some((stored_account, ms_refresh)) = storage.get_active_account() {
+            *auth_state.active_account.lock().unwrap() = Some(stored_account.to_account());
+            if let Some(token) = ms_refresh {
+                *ms_refresh_state.token.lock().unwrap() = Some(token);
+            }
+        }
+    }
+    Ok(summary)
+}
+
+#[derive(Serialize, TS)]
+#[ts(export, export_to = "core.ts")]
+struct PastebinResponse {
+    url: String,
 }
 
 #[tauri::command]
@@ -2296,16 +3953,31 @@ async fn assistant_check_health(
     Ok(assistant.check_health(&config.assistant).await)
 }
 
+#[tauri::command]
+#[dropout_macros::api]
+async fn assistant_check_health_detailed(
+    assistant_state: State<'_, core::assistant::AssistantState>,
+    config_state: State<'_, core::config::ConfigState>,
+) -> Result<core::assistant::AssistantHealth, String> {
+    let assistant = assistant_state.assistant.lock().unwrap().clone();
+    let config = config_state.config.lock().unwrap().clone();
+    Ok(assistant.check_health_detailed(&config.assistant).await)
+}
+
 #[tauri::command]
 #[dropout_macros::api]
 async fn assistant_chat(
+    app_handle: tauri::AppHandle,
     assistant_state: State<'_, core::assistant::AssistantState>,
     config_state: State<'_, core::config::ConfigState>,
     messages: Vec<core::assistant::Message>,
 ) -> Result<core::assistant::Message, String> {
+    let launcher_context = core::assistant::context::build_assistant_context(&app_handle);
     let assistant = assistant_state.assistant.lock().unwrap().clone();
     let config = config_state.config.lock().unwrap().clone();
-    assistant.chat(messages, &config.assistant).await
+    assistant
+        .chat(messages, &config.assistant, &launcher_context)
+        .await
 }
 
 #[tauri::command]
@@ -2318,6 +3990,28 @@ async fn list_ollama_models(
     assistant.list_ollama_models(&endpoint).await
 }
 
+/// Pull a model into the configured Ollama server, streaming progress events
+#[tauri::command]
+#[dropout_macros::api]
+async fn pull_ollama_model(
+    window: Window,
+    assistant_state: State<'_, core::assistant::AssistantState>,
+    config_state: State<'_, core::config::ConfigState>,
+    model: String,
+) -> Result<(), String> {
+    let assistant = assistant_state.assistant.lock().unwrap().clone();
+    let endpoint = config_state
+        .config
+        .lock()
+        .unwrap()
+        .assistant
+        .ollama_endpoint
+        .clone();
+    assistant
+        .pull_ollama_model(&endpoint, &model, &window)
+        .await
+}
+
 #[tauri::command]
 #[dropout_macros::api]
 async fn list_openai_models(
@@ -2329,6 +4023,15 @@ async fn list_openai_models(
     assistant.list_openai_models(&config.assistant).await
 }
 
+/// Search the built-in knowledge base of common Minecraft errors
+#[tauri::command]
+#[dropout_macros::api]
+async fn search_assistant_knowledge(
+    query: String,
+) -> Result<Vec<core::assistant::knowledge::KnowledgeEntry>, String> {
+    Ok(core::assistant::knowledge::search(&query))
+}
+
 // ==================== Instance Management Commands ====================
 
 /// Create a new instance
@@ -2339,18 +4042,70 @@ async fn create_instance(
     state: State<'_, core::instance::InstanceState>,
     name: String,
 ) -> Result<core::instance::Instance, String> {
-    let app_handle = window.app_handle();
-    state.create_instance(name, app_handle)
+    core::parental::require_unlocked(window.app_handle(), "add instances")?;
+    state.create_instance(name)
+}
+
+/// Re-check whether an instance's files are intact (e.g. after restoring its
+/// directory)
+#[tauri::command]
+#[dropout_macros::api]
+async fn check_instance_integrity(
+    window: Window,
+    state: State<'_, core::instance::InstanceState>,
+    instance_id: String,
+) -> Result<core::instance::InstanceStatus, String> {
+    let shared_versions_dir = window
+        .app_handle()
+        .path()
+        .app_data_dir()
+        .map_err(|e| e.to_string())?
+        .join("versions");
+    state.refresh_status(&instance_id, &shared_versions_dir)
+}
+
+/// What deleting an instance would remove, so the UI can confirm its worlds
+#[tauri::command]
+#[dropout_macros::api]
+async fn get_instance_delete_preview(
+    state: State<'_, core::instance::InstanceState>,
+    instance_id: String,
+) -> Result<core::instance::DeletePreview, String> {
+    let instance = state
+        .get_instance(&instance_id)
+        .ok_or_else(|| format!("Instance {} not found", instance_id))?;
+    tokio::task::spawn_blocking(move || core::instance::delete_preview(&instance))
+        .await
+        .map_err(|e| e.to_string())
 }
 
-/// Delete an instance
+/// Delete an instance. With `keep_saves`, its saves and screenshots are moved
+/// to `export_path`, or to the launcher's `orphaned` folder, first.
 #[tauri::command]
 #[dropout_macros::api]
 async fn delete_instance(
+    window: Window,
     state: State<'_, core::instance::InstanceState>,
     instance_id: String,
-) -> Result<(), String> {
-    state.delete_instance(&instance_id)
+    keep_saves: Option<bool>,
+    export_path: Option<String>,
+) -> Result<core::instance::DeletedInstance, String> {
+    let keep_dir = if keep_saves.unwrap_or(false) {
+        match export_path.filter(|p| !p.trim().is_empty()) {
+            Some(path) => Some(std::path::PathBuf::from(path)),
+            None => Some(
+                window
+                    .app_handle()
+                    .path()
+                    .app_data_dir()
+                    .map_err(|e| e.to_string())?
+                    .join("orphaned"),
+            ),
+        }
+    } else {
+        None
+    };
+    state.delete_instance(&instance_id, keep_dir.as_deref())
 }
 
 /// Update an instance
@@ -2412,8 +4167,8 @@ async fn duplicate_instance(
     instance_id: String,
     new_name: String,
 ) -> Result<core::instance::Instance, String> {
-    let app_handle = window.app_handle();
-    state.duplicate_instance(&instance_id, new_name, app_handle)
+    core::parental::require_unlocked(window.app_handle(), "add instances")?;
+    state.duplicate_instance(&instance_id, new_name)
 }
 
 #[tauri::command]
@@ -2424,10 +4179,11 @@ async fn assistant_chat_stream(
     config_state: State<'_, core::config::ConfigState>,
     messages: Vec<core::assistant::Message>,
 ) -> Result<String, String> {
+    let launcher_context = core::assistant::context::build_assistant_context(window.app_handle());
     let assistant = assistant_state.assistant.lock().unwrap().clone();
     let config = config_state.config.lock().unwrap().clone();
     assistant
-        .chat_stream(messages, &config.assistant, &window)
+        .chat_stream(messages, &config.assistant, &launcher_context, &window)
         .await
 }
 
@@ -2452,9 +4208,8 @@ async fn migrate_shared_caches(
 ) -> Result<MigrationResult, String> {
     emit_log!(window, "Starting migration to shared caches...".to_string());
 
-    let app_handle = window.app_handle();
     let (moved, hardlinks, copies, saved_bytes) =
-        core::instance::migrate_to_shared_caches(app_handle, &instance_state)?;
+        core::instance::migrate_to_shared_caches(&instance_state)?;
 
     let saved_mb = saved_bytes as f64 / (1024.0 * 1024.0);
 
@@ -2547,16 +4302,42 @@ async fn list_instance_directory(
     Ok(files)
 }
 
-/// Delete a file in an instance directory
+/// Files downloaded for an instance, newest first
 #[tauri::command]
 #[dropout_macros::api]
-async fn delete_instance_file(path: String) -> Result<(), String> {
-    let path_buf = std::path::PathBuf::from(&path);
-    if path_buf.is_dir() {
-        tokio::fs::remove_dir_all(&path_buf)
-            .await
-            .map_err(|e| e.to_string())?;
-    } else {
+async fn get_download_audit(
+    app_handle: tauri::AppHandle,
+    instance_id: String,
+    limit: Option<usize>,
+) -> Result<Vec<core::download_audit::DownloadAuditEntry>, String> {
+    core::download_audit::read_audit(&app_handle, &instance_id, limit)
+}
+
+/// Scan an instance's mod jars for known malware and tampering
+#[tauri::command]
+#[dropout_macros::api]
+async fn scan_instance_mods(
+    instance_state: State<'_, core::instance::InstanceState>,
+    instance_id: String,
+) -> Result<core::mod_scan::ModScanReport, String> {
+    let game_dir = instance_state
+        .get_instance_game_dir(&instance_id)
+        .ok_or_else(|| format!("Instance {} not found", instance_id))?;
+    tokio::task::spawn_blocking(move || core::mod_scan::scan_instance_mods(&instance_id, &game_dir))
+        .await
+        .map_err(|e| e.to_string())?
+}
+
+/// Delete a file in an instance directory
+#[tauri::command]
+#[dropout_macros::api]
+async fn delete_instance_file(path: String) -> Result<(), String> {
+    let path_buf = std::path::PathBuf::from(&path);
+    if path_buf.is_dir() {
+        tokio::fs::remove_dir_all(&path_buf)
+            .await
+            .map_err(|e| e.to_string())?;
+    } else {
         tokio::fs::remove_file(&path_buf)
             .await
             .map_err(|e| e.to_string())?;
@@ -2564,51 +4345,1940 @@ async fn delete_instance_file(path: String) -> Result<(), String> {
     Ok(())
 }
 
-/// Open instance directory in system file explorer
+/// Whether a path points at a network share (UNC path or URL)
+fn is_network_path(path: &str) -> bool {
+    path.starts_with("\\\\") || path.starts_with("//") || path.contains("://")
+}
+
+/// Directories the launcher manages: its data folder and every instance folder
+fn managed_roots(
+    app_handle: &tauri::AppHandle,
+    instance_state: &core::instance::InstanceState,
+) -> Vec<std::path::PathBuf> {
+    let mut roots: Vec<std::path::PathBuf> =
+        app_handle.path().app_data_dir().ok().into_iter().collect();
+    roots.extend(
+        instance_state
+            .instances
+            .lock()
+            .unwrap()
+            .instances
+            .iter()
+            .map(|i| i.game_dir.clone()),
+    );
+    roots
+        .into_iter()
+        .filter_map(|root| root.canonicalize().ok())
+        .collect()
+}
+
+fn reveal_in_explorer(path: &std::path::Path) -> Result<(), String> {
+    #[cfg(target_os = "windows")]
+    {
+        std::process::Command::new("explorer")
+            .arg(path)
+            .spawn()
+            .map_err(|e| e.to_string())?;
+    }
+    #[cfg(target_os = "macos")]
+    {
+        std::process::Command::new("open")
+            .arg(path)
+            .spawn()
+            .map_err(|e| e.to_string())?;
+    }
+    #[cfg(target_os = "linux")]
+    {
+        std::process::Command::new("xdg-open")
+            .arg(path)
+            .spawn()
+            .map_err(|e| e.to_string())?;
+    }
+    Ok(())
+}
+
+/// Show a file selected in its folder; the file itself is never opened, so
+/// nothing gets executed
+fn select_in_explorer(file: &std::path::Path) -> Result<(), String> {
+    #[cfg(target_os = "windows")]
+    {
+        // explorer wants `/select,"path"` unquoted as a whole
+        use std::os::windows::process::CommandExt;
+        std::process::Command::new("explorer")
+            .raw_arg(format!("/select,\"{}\"", file.display()))
+            .spawn()
+            .map_err(|e| e.to_string())?;
+    }
+    #[cfg(target_os = "macos")]
+    {
+        std::process::Command::new("open")
+            .arg("-R")
+            .arg(file)
+            .spawn()
+            .map_err(|e| e.to_string())?;
+    }
+    #[cfg(target_os = "linux")]
+    {
+        // xdg-open has no way to select a file; open its folder
+        let parent = file.parent().ok_or("The file has no parent folder")?;
+        reveal_in_explorer(parent)?;
+    }
+    Ok(())
+}
+
+/// Open a launcher-managed directory in the system file explorer, or show a
+/// launcher-managed file in its folder
+#[tauri::command]
+#[dropout_macros::api]
+async fn open_file_explorer(
+    app_handle: tauri::AppHandle,
+    instance_state: State<'_, core::instance::InstanceState>,
+    path: String,
+) -> Result<(), String> {
+    if is_network_path(&path) {
+        return Err("Network locations cannot be opened".to_string());
+    }
+    let target = std::path::Path::new(&path)
+        .canonicalize()
+        .map_err(|e| format!("Cannot open {}: {}", path, e))?;
+    if !managed_roots(&app_handle, &instance_state)
+        .iter()
+        .any(|root| target.starts_with(root))
+    {
+        return Err("Only launcher and instance folders can be opened".to_string());
+    }
+    // Explorer rejects the \\?\ form canonicalize returns on Windows
+    let target = dunce::simplified(&target);
+    if target.is_dir() {
+        reveal_in_explorer(target)
+    } else {
+        select_in_explorer(target)
+    }
+}
+
+/// Open a well-known folder of an instance in the system file explorer
+#[tauri::command]
+#[dropout_macros::api]
+async fn open_path_in_explorer(
+    instance_state: State<'_, core::instance::InstanceState>,
+    kind: String, // "instance" | "mods" | "saves" | "logs" | "config" | "resourcepacks" | "shaderpacks" | "screenshots" | "crash-reports"
+    instance_id: String,
+) -> Result<(), String> {
+    let game_dir = instance_state
+        .get_instance_game_dir(&instance_id)
+        .ok_or_else(|| format!("Instance {} not found", instance_id))?;
+    let target = match kind.as_str() {
+        "instance" => game_dir,
+        "mods" | "saves" | "logs" | "config" | "resourcepacks" | "shaderpacks" | "screenshots"
+        | "crash-reports" => game_dir.join(&kind),
+        _ => return Err(format!("Unknown folder: {}", kind)),
+    };
+    std::fs::create_dir_all(&target).map_err(|e| e.to_string())?;
+    reveal_in_explorer(&target)
+}
+
+/// Download a well-known Java agent and add it to an instance
+#[tauri::command]
+#[dropout_macros::api]
+async fn install_java_agent(
+    app_handle: tauri::AppHandle,
+    instance_state: State<'_, core::instance::InstanceState>,
+    instance_id: String,
+    agent: String, // "authlib-injector"
+    args: Option<String>,
+) -> Result<core::agents::JavaAgent, String> {
+    let mut instance = instance_state
+        .get_instance(&instance_id)
+        .ok_or_else(|| format!("Instance {} not found", instance_id))?;
+    let agents_dir = app_handle
+        .path()
+        .app_data_dir()
+        .map_err(|e| e.to_string())?
+        .join("agents");
+    let path = core::agents::download_known_agent(&agent, &agents_dir).await?;
+
+    let java_agent = core::agents::JavaAgent {
+        path: path.to_string_lossy().to_string(),
+        args,
+        enabled: true,
+    };
+    // Replace an older copy of the same agent instead of stacking them
+    let prefix = format!("{}-", agent);
+    instance.java_agents.retain(|a| {
+        !std::path::Path::new(&a.path)
+            .file_name()
+            .is_some_and(|name| name.to_string_lossy().starts_with(&prefix))
+    });
+    instance.java_agents.push(java_agent.clone());
+    instance_state.update_instance(instance)?;
+    Ok(java_agent)
+}
+
+/// Past launches of an instance (newest first), with credentials masked
+#[tauri::command]
+#[dropout_macros::api]
+async fn get_launch_history(
+    app_handle: tauri::AppHandle,
+    instance_id: String,
+) -> Result<Vec<core::launch_history::LaunchRecord>, String> {
+    core::launch_history::get_history(&app_handle, &instance_id)
+}
+
+/// Generate `server.properties` for a server instance and optionally accept
+/// the EULA; an existing `server.properties` is left untouched
+#[tauri::command]
+#[dropout_macros::api]
+async fn init_server_files(
+    instance_state: State<'_, core::instance::InstanceState>,
+    instance_id: String,
+    template: core::server::ServerTemplate,
+    accept_eula: bool,
+) -> Result<(), String> {
+    let instance = instance_state
+        .get_instance(&instance_id)
+        .ok_or_else(|| format!("Instance {} not found", instance_id))?;
+    core::server::init_server_files(&instance.game_dir, &template, accept_eula)
+}
+
+/// Set one `server.properties` value, validated against its type; returns the
+/// stored (normalized) value
+#[tauri::command]
+#[dropout_macros::api]
+async fn set_server_property(
+    instance_state: State<'_, core::instance::InstanceState>,
+    instance_id: String,
+    key: String,
+    value: String,
+) -> Result<String, String> {
+    let instance = instance_state
+        .get_instance(&instance_id)
+        .ok_or_else(|| format!("Instance {} not found", instance_id))?;
+    core::server::update_property(&instance.game_dir, &key, &value)
+}
+
+/// Whitelist and operators of a server instance
+#[tauri::command]
+#[dropout_macros::api]
+async fn get_server_players(
+    instance_state: State<'_, core::instance::InstanceState>,
+    instance_id: String,
+) -> Result<core::server::players::ServerPlayers, String> {
+    let instance = instance_state
+        .get_instance(&instance_id)
+        .ok_or_else(|| format!("Instance {} not found", instance_id))?;
+    core::server::players::read_players(&instance.game_dir)
+}
+
+/// Add a player to `whitelist.json` or `ops.json` (`list` is "whitelist" or
+/// "ops"), resolving their UUID the way the server would
+#[tauri::command]
+#[dropout_macros::api]
+async fn add_server_player(
+    instance_state: State<'_, core::instance::InstanceState>,
+    instance_id: String,
+    list: String,
+    name: String,
+    level: Option<u8>,
+) -> Result<core::server::players::ServerPlayers, String> {
+    use core::server::players;
+    let instance = instance_state
+        .get_instance(&instance_id)
+        .ok_or_else(|| format!("Instance {} not found", instance_id))?;
+    let dir = &instance.game_dir;
+    let entry = players::resolve_player(&name, players::is_online_mode(dir)).await?;
+    let mut current = players::read_players(dir)?;
+    match list.as_str() {
+        "whitelist" => players::whitelist_add(&mut current, entry),
+        "ops" => players::op_add(&mut current, entry, level.unwrap_or(4))?,
+        other => return Err(format!("Unknown player list '{}'", other)),
+    }
+    players::write_players(dir, &current)?;
+    Ok(current)
+}
+
+/// Remove a player (name or UUID) from `whitelist.json` or `ops.json`
+#[tauri::command]
+#[dropout_macros::api]
+async fn remove_server_player(
+    instance_state: State<'_, core::instance::InstanceState>,
+    instance_id: String,
+    list: String,
+    player: String,
+) -> Result<core::server::players::ServerPlayers, String> {
+    use core::server::players;
+    let instance = instance_state
+        .get_instance(&instance_id)
+        .ok_or_else(|| format!("Instance {} not found", instance_id))?;
+    let mut current = players::read_players(&instance.game_dir)?;
+    if players::remove(&mut current, &list, &player)? {
+        players::write_players(&instance.game_dir, &current)?;
+    }
+    Ok(current)
+}
+
+/// Run a command on a locally running server instance over RCON, using the
+/// port and password from its `server.properties`
+#[tauri::command]
+#[dropout_macros::api]
+async fn send_rcon_command(
+    instance_state: State<'_, core::instance::InstanceState>,
+    instance_id: String,
+    command: String,
+) -> Result<String, String> {
+    let instance = instance_state
+        .get_instance(&instance_id)
+        .ok_or_else(|| format!("Instance {} not found", instance_id))?;
+    let properties = std::fs::read_to_string(instance.game_dir.join(core::server::PROPERTIES_FILE))
+        .map_err(|e| format!("Failed to read server.properties: {}", e))?;
+    let (port, password) = core::server::rcon_settings(&properties)?;
+    core::protocol::execute("127.0.0.1", port, &password, &command).await
+}
+
+/// Add a scheduled server launch or backup, or replace the one with the
+/// same id
+#[tauri::command]
+#[dropout_macros::api]
+async fn add_schedule(
+    config_state: State<'_, core::config::ConfigState>,
+    instance_state: State<'_, core::instance::InstanceState>,
+    mut schedule: core::scheduler::Schedule,
+) -> Result<core::scheduler::Schedule, String> {
+    core::scheduler::validate(&schedule)?;
+    let instance = instance_state
+        .get_instance(&schedule.instance_id)
+        .ok_or_else(|| format!("Instance {} not found", schedule.instance_id))?;
+    if schedule.action == "launch" && !core::server::is_server_dir(&instance.game_dir) {
+        return Err(format!(
+            "Only server instances can be launched on a schedule; {} has no {}",
+            instance.name,
+            core::server::PROPERTIES_FILE
+        ));
+    }
+    if schedule.id.is_empty() {
+        schedule.id = uuid::Uuid::new_v4().to_string();
+    }
+    {
+        let mut config = config_state.config.lock().unwrap();
+        config.schedules.retain(|s| s.id != schedule.id);
+        config.schedules.push(schedule.clone());
+    }
+    config_state.save()?;
+    Ok(schedule)
+}
+
+#[tauri::command]
+#[dropout_macros::api]
+async fn list_schedules(
+    config_state: State<'_, core::config::ConfigState>,
+) -> Result<Vec<core::scheduler::Schedule>, String> {
+    Ok(config_state.config.lock().unwrap().schedules.clone())
+}
+
+#[tauri::command]
+#[dropout_macros::api]
+async fn remove_schedule(
+    config_state: State<'_, core::config::ConfigState>,
+    schedule_id: String,
+) -> Result<(), String> {
+    config_state
+        .config
+        .lock()
+        .unwrap()
+        .schedules
+        .retain(|s| s.id != schedule_id);
+    config_state.save()
+}
+
+/// Launch an instance's current version in the main window, for callers
+/// without a window of their own (scheduler, local API)
+async fn launch_instance(
+    app_handle: tauri::AppHandle,
+    instance_id: String,
+) -> Result<String, String> {
+    let instance_state: State<core::instance::InstanceState> = app_handle.state();
+    let instance = instance_state
+        .get_instance(&instance_id)
+        .ok_or_else(|| format!("Instance {} not found", instance_id))?;
+    let version_id = instance
+        .version_id
+        .clone()
+        .ok_or_else(|| format!("Instance {} has no version to launch", instance.name))?;
+    let window = app_handle
+        .get_webview_window("main")
+        .ok_or_else(|| "Main window is not available".to_string())?
+        .as_ref()
+        .window();
+    start_game(
+        window,
+        app_handle.state(),
+        app_handle.state(),
+        app_handle.state(),
+        instance_state,
+        instance_id,
+        version_id,
+    )
+    .await
+}
+
+/// Start the dedicated server of a server instance in the background,
+/// downloading the vanilla server jar of its version first if it has none
+async fn launch_server(
+    app_handle: tauri::AppHandle,
+    instance: &core::instance::Instance,
+) -> Result<String, String> {
+    let game_dir = &instance.game_dir;
+    if !core::server::is_server_dir(game_dir) {
+        return Err(format!(
+            "{} is not a server instance: it has no {}",
+            instance.name,
+            core::server::PROPERTIES_FILE
+        ));
+    }
+    let version_id = instance
+        .version_id
+        .clone()
+        .ok_or_else(|| format!("Instance {} has no version to launch", instance.name))?;
+    let config = {
+        let config_state: State<core::config::ConfigState> = app_handle.state();
+        config_state.config.lock().unwrap().clone()
+    };
+    let version = core::manifest::load_version(game_dir, &version_id)
+        .await
+        .map_err(|e| e.to_string())?;
+
+    let jar = game_dir.join(core::server::SERVER_JAR);
+    if !jar.exists() {
+        if let Some(loader) = instance.mod_loader.as_deref().filter(|l| *l != "vanilla") {
+            return Err(format!(
+                "Put the {} server jar of {} in {} as {}",
+                loader,
+                instance.name,
+                game_dir.display(),
+                core::server::SERVER_JAR
+            ));
+        }
+        let server = version
+            .downloads
+            .as_ref()
+            .and_then(|downloads| downloads.server.clone())
+            .ok_or_else(|| format!("Minecraft {} has no dedicated server", version_id))?;
+        let window = app_handle
+            .get_webview_window("main")
+            .ok_or_else(|| "Main window is not available".to_string())?
+            .as_ref()
+            .window();
+        let audit =
+            core::download_audit::DownloadAudit::open_or_warn(&app_handle, &instance.id, "launch");
+        core::downloader::download_files(
+            window,
+            vec![core::downloader::DownloadTask {
+                url: server.url,
+                path: jar,
+                sha1: server.sha1,
+                sha256: None,
+            }],
+            core::downloader::concurrency_for_instance(&config, Some(instance)),
+            audit.as_ref(),
+        )
+        .await?;
+    }
+
+    let required_java_major = version.java_version.as_ref().map(|jv| jv.major_version);
+    let java = core::java::resolve_java_for_launch(
+        &app_handle,
+        instance.java_path_override.as_deref(),
+        Some(&config.java_path),
+        required_java_major,
+        required_java_major.filter(|major| *major <= 8).map(|_| 8),
+    )
+    .await
+    .ok_or_else(|| format!("No compatible Java found for Minecraft {}", version_id))?;
+
+    let mut command = Command::new(&java.path);
+    command.args(core::server::launch_args(
+        config.min_memory,
+        config.max_memory,
+    ));
+    command.current_dir(game_dir);
+    command.stdin(Stdio::null());
+    command.stdout(Stdio::null());
+    command.stderr(Stdio::null());
+    #[cfg(target_os = "windows")]
+    {
+        const CREATE_NO_WINDOW: u32 = 0x08000000;
+        command.creation_flags(CREATE_NO_WINDOW);
+    }
+    let mut child = command
+        .spawn()
+        .map_err(|e| format!("Failed to start the server: {}", e))?;
+    let pid = child.id().unwrap_or_default();
+    // Reap the process when the server stops
+    tokio::spawn(async move {
+        let _ = child.wait().await;
+    });
+    Ok(format!(
+        "Started the server of {} (pid {})",
+        instance.name, pid
+    ))
+}
+
+/// Run one due schedule and report the outcome as a `schedule-run` event
+async fn run_schedule(app_handle: tauri::AppHandle, schedule: core::scheduler::Schedule) {
+    let result = async {
+        let instance_state: State<core::instance::InstanceState> = app_handle.state();
+        let instance = instance_state
+            .get_instance(&schedule.instance_id)
+            .ok_or_else(|| format!("Instance {} not found", schedule.instance_id))?;
+        match schedule.action.as_str() {
+            "launch" => launch_server(app_handle.clone(), &instance).await,
+            "backup" => {
+                let config: State<core::config::ConfigState> = app_handle.state();
+                let cloud_sync = config.config.lock().unwrap().cloud_sync.clone();
+                core::sync::backup_instance(&cloud_sync, &instance, Vec::new())
+                    .await
+                    .map(|backup| format!("Backed up {} as {}", instance.name, backup.id))
+            }
+            other => Err(format!("Unknown schedule action '{}'", other)),
+        }
+    }
+    .await;
+    if let Err(e) = &result {
+        eprintln!("[Scheduler] Schedule {} failed: {}", schedule.id, e);
+    }
+    let _ = app_handle.emit(
+        "schedule-run",
+        serde_json::json!({
+            "id": schedule.id,
+            "action": schedule.action,
+            "instanceId": schedule.instance_id,
+            "result": result,
+        }),
+    );
+}
+
+/// Start the localhost API (see `core::local_api`), creating its token on
+/// first use
+#[tauri::command]
+#[dropout_macros::api]
+async fn start_local_api(
+    app_handle: tauri::AppHandle,
+    config_state: State<'_, core::config::ConfigState>,
+) -> Result<core::local_api::LocalApiInfo, String> {
+    let (port, token) = {
+        let mut config = config_state.config.lock().unwrap();
+        let token = config
+            .local_api_token
+            .get_or_insert_with(|| uuid::Uuid::new_v4().simple().to_string())
+            .clone();
+        (config.local_api_port, token)
+    };
+    config_state.save()?;
+    let launcher: core::local_api::Launcher =
+        std::sync::Arc::new(|app, instance_id| Box::pin(launch_instance(app, instance_id)));
+    core::local_api::start(&app_handle, port, token, launcher).await
+}
+
+#[tauri::command]
+#[dropout_macros::api]
+async fn stop_local_api(app_handle: tauri::AppHandle) -> Result<(), String> {
+    core::local_api::stop(&app_handle);
+    Ok(())
+}
+
+#[tauri::command]
+#[dropout_macros::api]
+async fn get_local_api_info(
+    local_api_state: State<'_, core::local_api::LocalApiState>,
+) -> Result<core::local_api::LocalApiInfo, String> {
+    Ok(local_api_state.info())
+}
+
+/// Install the modpack of a confirmed `dropout://install-modpack` link as a
+/// new instance, in the main window
+async fn run_deep_link(
+    app_handle: tauri::AppHandle,
+    link: core::deep_link::DeepLink,
+) -> Result<String, String> {
+    let core::deep_link::DeepLink::InstallModpack {
+        source,
+        id,
+        version,
+    } = link
+    else {
+        return Err("Only modpack links are installed".to_string());
+    };
+    core::parental::require_unlocked(&app_handle, "add instances")?;
+    let window = app_handle
+        .get_webview_window("main")
+        .ok_or_else(|| "Main window is not available".to_string())?
+        .as_ref()
+        .window();
+    let config_state: State<core::config::ConfigState> = app_handle.state();
+    let instance_state: State<core::instance::InstanceState> = app_handle.state();
+
+    emit_log!(
+        window,
+        format!("Looking up modpack {} on {}...", id, source)
+    );
+    let release = core::modpack::find_release(&source, &id, version.as_deref()).await?;
+    let concurrency = {
+        let config = config_state.config.lock().unwrap();
+        core::downloader::concurrency_from_config(&config)
+    };
+    let dest_dir = app_handle
+        .path()
+        .app_data_dir()
+        .map_err(|e| e.to_string())?
+        .join("cache")
+        .join("modpacks");
+    std::fs::create_dir_all(&dest_dir).map_err(|e| e.to_string())?;
+    let pack_source = core::web_seed::PackSource::Http {
+        url: release.url,
+        sha1: release.sha1,
+    };
+    let audit = core::download_audit::DownloadAudit::open_or_warn(
+        window.app_handle(),
+        core::download_audit::PACK_ARCHIVES,
+        "deep link",
+    );
+    let path = core::web_seed::download_pack(
+        &window,
+        &pack_source,
+        &dest_dir,
+        concurrency,
+        audit.as_ref(),
+    )
+    .await?;
+    let instance =
+        import_zip_as_instance(&window, &config_state, &instance_state, &path, None).await?;
+    Ok(format!(
+        "Installed {} {} as {}",
+        id, release.version_name, instance.name
+    ))
+}
+
+/// Confirmed `dropout://` join links the UI hasn't handled yet
+#[tauri::command]
+#[dropout_macros::api]
+async fn take_pending_deep_links(
+    deep_link_state: State<'_, core::deep_link::DeepLinkState>,
+) -> Result<Vec<core::deep_link::DeepLink>, String> {
+    Ok(std::mem::take(
+        &mut *deep_link_state.pending.lock().unwrap(),
+    ))
+}
+
+/// Make this launcher the handler of `dropout://` links for the current user
+#[tauri::command]
+#[dropout_macros::api]
+async fn register_url_scheme() -> Result<(), String> {
+    let exe = std::env::current_exe().map_err(|e| e.to_string())?;
+    core::deep_link::register_scheme(&exe)
+}
+
+/// Install the newest compatible version of every project in a shared mod
+/// list (see `core::mod_list` for the accepted formats)
+#[tauri::command]
+#[dropout_macros::api]
+async fn import_mod_list(
+    window: Window,
+    config_state: State<'_, core::config::ConfigState>,
+    instance_state: State<'_, core::instance::InstanceState>,
+    instance_id: String,
+    manifest: String,
+) -> Result<core::mod_list::ModListImport, String> {
+    core::parental::require_unlocked(window.app_handle(), "add mods")?;
+    let instance = instance_state
+        .get_instance(&instance_id)
+        .ok_or_else(|| format!("Instance {} not found", instance_id))?;
+    let version_id = instance
+        .version_id
+        .clone()
+        .ok_or("Instance has no version selected")?;
+    let loader = instance
+        .mod_loader
+        .clone()
+        .filter(|loader| loader != "vanilla")
+        .ok_or("Instance has no mod loader")?;
+    let entries = core::mod_list::parse_manifest(&manifest)?;
+
+    // Mods are published for the vanilla version a loader profile inherits from
+    let game_version = core::manifest::load_local_version(&instance.game_dir, &version_id)
+        .await
+        .ok()
+        .and_then(|version| version.inherits_from)
+        .unwrap_or(version_id);
+
+    let mut report = core::mod_list::ModListImport::default();
+    let mut tasks = Vec::new();
+    for entry in &entries {
+        match core::mod_list::resolve(entry, &game_version, &loader).await {
+            Ok(file) => {
+                let path = instance.game_dir.join(&file.path);
+                if let Some(name) = path.file_name() {
+                    report.installed.push(name.to_string_lossy().to_string());
+                }
+                tasks.push(core::downloader::DownloadTask {
+                    url: file.url,
+                    path,
+                    sha1: file.sha1,
+                    sha256: None,
+                });
+            }
+            Err(error) => {
+                let line = format!("Skipping {}: {}", entry, error);
+                emit_log!(window, &line);
+                report.failed.push(core::mod_list::ModListFailure {
+                    entry: entry.to_string(),
+                    error,
+                });
+            }
+        }
+    }
+
+    let concurrency = {
+        let config = config_state.config.lock().unwrap();
+        core::downloader::concurrency_for_instance(&config, Some(&instance))
+    };
+    let audit = core::download_audit::DownloadAudit::open_or_warn(
+        window.app_handle(),
+        &instance_id,
+        "install",
+    );
+    core::downloader::download_files(window.clone(), tasks, concurrency, audit.as_ref())
+        .await
+        .map_err(|e| e.to_string())?;
+    Ok(report)
+}
+
+/// The Minecraft version mods are published for (the vanilla version a loader
+/// profile inherits from) and the mod loader of an instance
+async fn instance_mod_target(
+    instance: &core::instance::Instance,
+) -> (Option<String>, Option<String>) {
+    let game_version = match &instance.version_id {
+        Some(version_id) => Some(
+            core::manifest::load_local_version(&instance.game_dir, version_id)
+                .await
+                .ok()
+                .and_then(|version| version.inherits_from)
+                .unwrap_or_else(|| version_id.clone()),
+        ),
+        None => None,
+    };
+    let loader = instance
+        .mod_loader
+        .clone()
+        .filter(|loader| loader != "vanilla");
+    (game_version, loader)
+}
+
+/// Search CurseForge mods (or the projects of `class_id`), narrowed to the
+/// Minecraft version and loader of `instance_id` when given
+#[tauri::command]
+#[dropout_macros::api]
+async fn search_curseforge(
+    instance_state: State<'_, core::instance::InstanceState>,
+    query: String,
+    class_id: Option<u64>,
+    instance_id: Option<String>,
+    page: Option<u32>,
+) -> Result<core::curseforge::CurseForgeSearch, String> {
+    let (game_version, loader) = match instance_id.and_then(|id| instance_state.get_instance(&id)) {
+        Some(instance) => instance_mod_target(&instance).await,
+        None => (None, None),
+    };
+    core::curseforge::search(
+        &query,
+        class_id,
+        game_version.as_deref(),
+        loader.as_deref(),
+        page.unwrap_or(0),
+    )
+    .await
+}
+
+/// Files of a CurseForge project, newest first, that work with `instance_id`
+/// when given
+#[tauri::command]
+#[dropout_macros::api]
+async fn get_curseforge_files(
+    instance_state: State<'_, core::instance::InstanceState>,
+    mod_id: u64,
+    instance_id: Option<String>,
+) -> Result<Vec<core::curseforge::CurseForgeFile>, String> {
+    let (game_version, loader) = match instance_id.and_then(|id| instance_state.get_instance(&id)) {
+        Some(instance) => instance_mod_target(&instance).await,
+        None => (None, None),
+    };
+    core::curseforge::files(mod_id, game_version.as_deref(), loader.as_deref()).await
+}
+
+/// Install a file of a CurseForge project into an instance: `file_id`, or the
+/// newest file for the instance's version and loader. Returns the path of the
+/// installed file relative to the game directory.
+#[tauri::command]
+#[dropout_macros::api]
+async fn install_curseforge_mod(
+    window: Window,
+    config_state: State<'_, core::config::ConfigState>,
+    instance_state: State<'_, core::instance::InstanceState>,
+    instance_id: String,
+    mod_id: u64,
+    file_id: Option<u64>,
+) -> Result<String, String> {
+    core::parental::require_unlocked(window.app_handle(), "add mods")?;
+    let instance = instance_state
+        .get_instance(&instance_id)
+        .ok_or_else(|| format!("Instance {} not found", instance_id))?;
+    let project = core::curseforge::project(mod_id).await?;
+    let file = match file_id {
+        Some(file_id) => core::curseforge::file(mod_id, file_id).await?,
+        None => {
+            let (game_version, loader) = instance_mod_target(&instance).await;
+            core::curseforge::files(mod_id, game_version.as_deref(), loader.as_deref())
+                .await?
+                .into_iter()
+                .next()
+                .ok_or_else(|| {
+                    format!(
+                        "No file of {} for {} {}",
+                        project.name,
+                        loader.unwrap_or_default(),
+                        game_version.unwrap_or_default()
+                    )
+                })?
+        }
+    };
+    let download = core::curseforge::to_download(&file, project.class_id);
+    if file.file_name.contains(['/', '\\']) || file.file_name.starts_with('.') {
+        return Err(format!("Invalid file name: {}", file.file_name));
+    }
+    emit_log!(
+        window,
+        format!(
+            "Installing {} ({}) into {}...",
+            project.name, file.display_name, instance.name
+        )
+    );
+
+    let concurrency = {
+        let config = config_state.config.lock().unwrap();
+        core::downloader::concurrency_for_instance(&config, Some(&instance))
+    };
+    let task = core::downloader::DownloadTask {
+        url: download.url,
+        path: instance.game_dir.join(&download.path),
+        sha1: download.sha1,
+        sha256: None,
+    };
+    let audit = core::download_audit::DownloadAudit::open_or_warn(
+        window.app_handle(),
+        &instance_id,
+        "install",
+    );
+    core::downloader::download_files(window.clone(), vec![task], concurrency, audit.as_ref())
+        .await
+        .map_err(|e| e.to_string())?;
+    Ok(download.path)
+}
+
+/// Shader loader of an instance ("iris" or "optifine"), from its version
+/// and enabled mods
+async fn instance_shader_loader(instance: &core::instance::Instance) -> Option<&'static str> {
+    let game_dir = instance.game_dir.clone();
+    let mods = tokio::task::spawn_blocking(move || core::mods::scan_mods(&game_dir))
+        .await
+        .map_err(|e| e.to_string())
+        .and_then(|scanned| scanned)
+        .unwrap_or_default();
+    core::modrinth::detect_shader_loader(instance.version_id.as_deref(), &mods)
+}
+
+/// Minecraft version and Modrinth loader tags that projects of
+/// `project_type` need to work in `instance`
+async fn modrinth_target(
+    instance: &core::instance::Instance,
+    project_type: &str,
+) -> (Option<String>, Vec<String>) {
+    let (game_version, loader) = instance_mod_target(instance).await;
+    let shader_loader = if project_type == "shader" {
+        instance_shader_loader(instance).await
+    } else {
+        None
+    };
+    let loaders = core::modrinth::project_loaders(project_type, loader.as_deref(), shader_loader);
+    (game_version, loaders)
+}
+
+/// Shader loader of an instance: "iris", "optifine" or None when shader
+/// packs can't be used
+#[tauri::command]
+#[dropout_macros::api]
+async fn get_shader_loader(
+    instance_state: State<'_, core::instance::InstanceState>,
+    instance_id: String,
+) -> Result<Option<String>, String> {
+    let instance = instance_state
+        .get_instance(&instance_id)
+        .ok_or_else(|| format!("Instance {} not found", instance_id))?;
+    Ok(instance_shader_loader(&instance).await.map(String::from))
+}
+
+/// Search Modrinth mods, resource packs or shader packs (`project_type`
+/// "mod", "resourcepack" or "shader"), narrowed to what works with
+/// `instance_id` when given
+#[tauri::command]
+#[dropout_macros::api]
+async fn search_modrinth(
+    instance_state: State<'_, core::instance::InstanceState>,
+    query: String,
+    project_type: String,
+    instance_id: Option<String>,
+    page: Option<u32>,
+) -> Result<core::modrinth::ModrinthSearch, String> {
+    let (game_version, loaders) = match instance_id.and_then(|id| instance_state.get_instance(&id))
+    {
+        Some(instance) => modrinth_target(&instance, &project_type).await,
+        None => (None, Vec::new()),
+    };
+    core::modrinth::search(
+        &query,
+        &project_type,
+        game_version.as_deref(),
+        &loaders,
+        page.unwrap_or(0),
+    )
+    .await
+}
+
+/// Versions of a Modrinth project, newest first, that work with
+/// `instance_id` when given
+#[tauri::command]
+#[dropout_macros::api]
+async fn get_modrinth_versions(
+    instance_state: State<'_, core::instance::InstanceState>,
+    project_id: String,
+    instance_id: Option<String>,
+) -> Result<Vec<core::modrinth::ModrinthVersion>, String> {
+    let (game_version, loaders) = match instance_id.and_then(|id| instance_state.get_instance(&id))
+    {
+        Some(instance) => {
+            let project = core::modrinth::project(&project_id).await?;
+            modrinth_target(&instance, &project.project_type).await
+        }
+        None => (None, Vec::new()),
+    };
+    core::modrinth::versions(&project_id, game_version.as_deref(), &loaders).await
+}
+
+/// Install a version of a Modrinth mod, resource pack or shader pack into an
+/// instance: `version_id`, or the newest version that works with the
+/// instance. Returns the path of the installed file relative to the game
+/// directory.
+#[tauri::command]
+#[dropout_macros::api]
+async fn install_modrinth_project(
+    window: Window,
+    config_state: State<'_, core::config::ConfigState>,
+    instance_state: State<'_, core::instance::InstanceState>,
+    instance_id: String,
+    project_id: String,
+    version_id: Option<String>,
+) -> Result<String, String> {
+    core::parental::require_unlocked(window.app_handle(), "add mods")?;
+    let instance = instance_state
+        .get_instance(&instance_id)
+        .ok_or_else(|| format!("Instance {} not found", instance_id))?;
+    let project = core::modrinth::project(&project_id).await?;
+    if !core::modrinth::PROJECT_TYPES.contains(&project.project_type.as_str()) {
+        return Err(format!(
+            "{} is a {}, which can't be installed into an instance",
+            project.title, project.project_type
+        ));
+    }
+    if project.project_type == "shader" && instance_shader_loader(&instance).await.is_none() {
+        emit_log!(
+            window,
+            format!(
+                "Warning: {} has no shader loader; install Iris or OptiFine to use {}",
+                instance.name, project.title
+            )
+        );
+    }
+    let version = match version_id {
+        Some(version_id) => core::modrinth::version(&version_id).await?,
+        None => {
+            let (game_version, loaders) = modrinth_target(&instance, &project.project_type).await;
+            core::modrinth::versions(&project.id, game_version.as_deref(), &loaders)
+                .await?
+                .into_iter()
+                .next()
+                .ok_or_else(|| {
+                    format!(
+                        "No version of {} for {} {}",
+                        project.title,
+                        loaders.join("/"),
+                        game_version.unwrap_or_default()
+                    )
+                })?
+        }
+    };
+    if version.project_id != project.id {
+        return Err(format!(
+            "Version {} is not a version of {}",
+            version.id, project.title
+        ));
+    }
+    let download = core::modrinth::to_download(&version, &project.project_type)?;
+    emit_log!(
+        window,
+        format!(
+            "Installing {} ({}) into {}...",
+            project.title, version.version_number, instance.name
+        )
+    );
+
+    let concurrency = {
+        let config = config_state.config.lock().unwrap();
+        core::downloader::concurrency_for_instance(&config, Some(&instance))
+    };
+    let task = core::downloader::DownloadTask {
+        url: download.url,
+        path: instance.game_dir.join(&download.path),
+        sha1: download.sha1,
+        sha256: None,
+    };
+    let audit = core::download_audit::DownloadAudit::open_or_warn(
+        window.app_handle(),
+        &instance_id,
+        "install",
+    );
+    core::downloader::download_files(window.clone(), vec![task], concurrency, audit.as_ref())
+        .await
+        .map_err(|e| e.to_string())?;
+    Ok(download.path)
+}
+
+/// Undo the last mod update of an instance (see `core::mod_rollback`)
+#[tauri::command]
+#[dropout_macros::api]
+async fn rollback_last_update(
+    instance_state: State<'_, core::instance::InstanceState>,
+    instance_id: String,
+) -> Result<core::mod_rollback::RollbackResult, String> {
+    let game_dir = instance_state
+        .get_instance_game_dir(&instance_id)
+        .ok_or_else(|| format!("Instance {} not found", instance_id))?;
+    tokio::task::spawn_blocking(move || core::mod_rollback::restore(&game_dir))
+        .await
+        .map_err(|e| e.to_string())?
+}
+
+/// Mods of an instance (disabled ones included) with the id, version,
+/// loader, dependencies and icon path read from their jars
+#[tauri::command]
+#[dropout_macros::api]
+async fn list_installed_mods(
+    instance_state: State<'_, core::instance::InstanceState>,
+    instance_id: String,
+) -> Result<Vec<core::mods::InstalledMod>, String> {
+    let game_dir = instance_state
+        .get_instance_game_dir(&instance_id)
+        .ok_or_else(|| format!("Instance {} not found", instance_id))?;
+    tokio::task::spawn_blocking(move || core::mods::scan_mods(&game_dir))
+        .await
+        .map_err(|e| e.to_string())?
+}
+
+/// Icon of a mod jar in the instance's mods folder as a `data:` URL
+#[tauri::command]
+#[dropout_macros::api]
+async fn get_mod_icon(
+    instance_state: State<'_, core::instance::InstanceState>,
+    instance_id: String,
+    file_name: String,
+) -> Result<Option<String>, String> {
+    use base64::Engine;
+    if file_name.contains(['/', '\\']) || file_name.starts_with('.') {
+        return Err(format!("Invalid file name: {}", file_name));
+    }
+    let jar = instance_state
+        .get_instance_game_dir(&instance_id)
+        .ok_or_else(|| format!("Instance {} not found", instance_id))?
+        .join("mods")
+        .join(&file_name);
+    let icon = tokio::task::spawn_blocking(move || core::mods::read_icon(&jar))
+        .await
+        .map_err(|e| e.to_string())??;
+    Ok(icon.map(|data| {
+        format!(
+            "data:image/png;base64,{}",
+            base64::engine::general_purpose::STANDARD.encode(data)
+        )
+    }))
+}
+
+/// Enable or disable a mod of an instance by renaming its jar to and from
+/// `.jar.disabled`. Returns the jar's new file name.
+#[tauri::command]
+#[dropout_macros::api]
+async fn toggle_mod(
+    window: Window,
+    instance_state: State<'_, core::instance::InstanceState>,
+    instance_id: String,
+    file_name: String,
+    enabled: bool,
+) -> Result<String, String> {
+    if enabled {
+        core::parental::require_unlocked(window.app_handle(), "enable mods")?;
+    }
+    let game_dir = instance_state
+        .get_instance_game_dir(&instance_id)
+        .ok_or_else(|| format!("Instance {} not found", instance_id))?;
+    let new_name = tokio::task::spawn_blocking(move || {
+        core::mods::set_enabled(&game_dir, &file_name, enabled)
+    })
+    .await
+    .map_err(|e| e.to_string())??;
+    println!(
+        "[Mods] {} {} in instance {}",
+        if enabled { "Enabled" } else { "Disabled" },
+        new_name,
+        instance_id
+    );
+    Ok(new_name)
+}
+
+/// Shareable list of an instance's enabled mods: `format` is "markdown",
+/// "json" or "html"
+#[tauri::command]
+#[dropout_macros::api]
+async fn export_mod_list(
+    instance_state: State<'_, core::instance::InstanceState>,
+    instance_id: String,
+    format: String,
+) -> Result<String, String> {
+    let instance = instance_state
+        .get_instance(&instance_id)
+        .ok_or_else(|| format!("Instance {} not found", instance_id))?;
+    let game_dir = instance.game_dir.clone();
+    let mods = tokio::task::spawn_blocking(move || core::mods::scan_mods(&game_dir))
+        .await
+        .map_err(|e| e.to_string())??;
+    core::mods::export_list(&instance.name, &mods, &format)
+}
+
+/// Markdown notes and image attachments of an instance
+#[tauri::command]
+#[dropout_macros::api]
+async fn get_instance_notes(
+    instance_state: State<'_, core::instance::InstanceState>,
+    instance_id: String,
+) -> Result<core::notes::InstanceNotes, String> {
+    let instance = instance_state
+        .get_instance(&instance_id)
+        .ok_or_else(|| format!("Instance {} not found", instance_id))?;
+    core::notes::read(&instance.game_dir, instance.notes.as_deref())
+}
+
+/// Write an instance's NOTES.md, replacing the old single-string notes
+#[tauri::command]
+#[dropout_macros::api]
+async fn save_instance_notes(
+    instance_state: State<'_, core::instance::InstanceState>,
+    instance_id: String,
+    content: String,
+) -> Result<(), String> {
+    let mut instance = instance_state
+        .get_instance(&instance_id)
+        .ok_or_else(|| format!("Instance {} not found", instance_id))?;
+    core::notes::write(&instance.game_dir, &content)?;
+    if instance.notes.take().is_some() {
+        instance_state.update_instance(instance)?;
+    }
+    Ok(())
+}
+
+/// Copy an image into an instance's note attachments; returns the name to
+/// reference as `notes/<name>`
+#[tauri::command]
+#[dropout_macros::api]
+async fn add_note_attachment(
+    instance_state: State<'_, core::instance::InstanceState>,
+    instance_id: String,
+    source_path: String,
+) -> Result<String, String> {
+    let game_dir = instance_state
+        .get_instance_game_dir(&instance_id)
+        .ok_or_else(|| format!("Instance {} not found", instance_id))?;
+    core::notes::add_attachment(&game_dir, std::path::Path::new(&source_path))
+}
+
+#[tauri::command]
+#[dropout_macros::api]
+async fn remove_note_attachment(
+    instance_state: State<'_, core::instance::InstanceState>,
+    instance_id: String,
+    name: String,
+) -> Result<(), String> {
+    let game_dir = instance_state
+        .get_instance_game_dir(&instance_id)
+        .ok_or_else(|| format!("Instance {} not found", instance_id))?;
+    core::notes::remove_attachment(&game_dir, &name)
+}
+
+/// Zip an instance's latest log, newest crash report and JVM crash dumps
+/// with a summary; returns the bundle's path
+#[tauri::command]
+#[dropout_macros::api]
+async fn export_diagnostics(
+    app_handle: tauri::AppHandle,
+    instance_state: State<'_, core::instance::InstanceState>,
+    instance_id: String,
+) -> Result<String, String> {
+    let instance = instance_state
+        .get_instance(&instance_id)
+        .ok_or_else(|| format!("Instance {} not found", instance_id))?;
+    let dest = app_handle
+        .path()
+        .app_data_dir()
+        .map_err(|e| e.to_string())?
+        .join("diagnostics")
+        .join(format!(
+            "{}-{}.zip",
+            instance_id,
+            chrono::Local::now().format("%Y%m%d-%H%M%S")
+        ));
+    let bundle = dest.clone();
+    tokio::task::spawn_blocking(move || {
+        core::crash::write_bundle(&instance.name, &instance.game_dir, &bundle)
+    })
+    .await
+    .map_err(|e| e.to_string())??;
+    Ok(dest.to_string_lossy().to_string())
+}
+
+/// Package an instance to share it: `format` is "zip" (the whole game
+/// directory) or "mrpack" (a Modrinth pack whose mods are downloaded from
+/// Modrinth on import). Written to `dest`, or to the exports folder.
+#[tauri::command]
+#[dropout_macros::api]
+async fn export_instance(
+    app_handle: tauri::AppHandle,
+    instance_state: State<'_, core::instance::InstanceState>,
+    instance_id: String,
+    format: String,
+    dest: Option<String>,
+) -> Result<core::modpack::ExportSummary, String> {
+    let instance = instance_state
+        .get_instance(&instance_id)
+        .ok_or_else(|| format!("Instance {} not found", instance_id))?;
+    let extension = match format.as_str() {
+        "zip" => "zip",
+        "mrpack" => "mrpack",
+        other => return Err(format!("Unknown export format: {}", other)),
+    };
+    let dest = match dest.filter(|d| !d.trim().is_empty()) {
+        Some(dest) => std::path::PathBuf::from(dest),
+        None => app_handle
+            .path()
+            .app_data_dir()
+            .map_err(|e| e.to_string())?
+            .join("exports")
+            .join(format!(
+                "{}-{}.{}",
+                instance
+                    .name
+                    .replace(|c: char| !c.is_alphanumeric() && !" -_".contains(c), "_"),
+                chrono::Local::now().format("%Y%m%d-%H%M%S"),
+                extension
+            )),
+    };
+    let game_dir = instance.game_dir.clone();
+
+    if extension == "zip" {
+        return tokio::task::spawn_blocking(move || core::modpack::export_zip(&game_dir, &dest))
+            .await
+            .map_err(|e| e.to_string())?;
+    }
+
+    // The pack names the vanilla version, not the loader's version id
+    let version_id = instance
+        .version_id
+        .clone()
+        .ok_or("The instance has no version selected")?;
+    let minecraft_version = match core::manifest::load_local_version(&game_dir, &version_id).await {
+        Ok(version) => version.inherits_from.unwrap_or(version_id),
+        Err(_) => version_id,
+    };
+    let info = core::modpack::ModpackInfo {
+        name: instance.name.clone(),
+        minecraft_version: Some(minecraft_version),
+        mod_loader: instance.mod_loader.clone(),
+        mod_loader_version: instance.mod_loader_version.clone(),
+        modpack_type: "modrinth".to_string(),
+        instance_id: Some(instance.id.clone()),
+    };
+    let referenced = core::modpack::resolve_mrpack_files(&game_dir, &dest).await?;
+    tokio::task::spawn_blocking(move || {
+        core::modpack::write_mrpack(&game_dir, &dest, &info, "1.0.0", &referenced)
+    })
+    .await
+    .map_err(|e| e.to_string())?
+}
+
+/// Graphics driver and OpenGL version, with warnings for preflight
+#[tauri::command]
+#[dropout_macros::api]
+async fn get_gpu_info() -> Result<core::gpu::GpuInfo, String> {
+    Ok(core::gpu::probe().await)
+}
+
+/// Create an instance from an official-launcher `.minecraft` directory (the
+/// default location when `path` is empty), used in place or copied
+#[tauri::command]
+#[dropout_macros::api]
+async fn import_dot_minecraft(
+    window: Window,
+    instance_state: State<'_, core::instance::InstanceState>,
+    path: Option<String>,
+    name: Option<String>,
+    copy: bool,
+) -> Result<core::dot_minecraft::DotMinecraftImport, String> {
+    core::parental::require_unlocked(window.app_handle(), "add instances")?;
+    let source = match path.filter(|p| !p.trim().is_empty()) {
+        Some(path) => std::path::PathBuf::from(path),
+        None => core::dot_minecraft::default_dir()
+            .ok_or("Could not determine the default .minecraft location")?,
+    };
+    let name = name
+        .filter(|n| !n.trim().is_empty())
+        .unwrap_or_else(|| "Imported .minecraft".to_string());
+    instance_state
+        .import_dot_minecraft(&source, name, copy)
+        .await
+}
+
+/// Scan the jars a pack carries in its overrides before any are extracted
+fn check_pack_overrides(pack: &std::path::Path) -> Result<(), String> {
+    let name = pack
+        .file_name()
+        .map(|n| n.to_string_lossy().to_string())
+        .unwrap_or_default();
+    core::mod_scan::check_before_install(pack, &name)
+}
+
+/// Put a parsed pack into `instance`: extract its overrides, download the
+/// files it lists and install its Minecraft version and mod loader
+async fn install_pack(
+    window: &Window,
+    config_state: &State<'_, core::config::ConfigState>,
+    instance_state: &State<'_, core::instance::InstanceState>,
+    instance: &core::instance::Instance,
+    zip_path: &std::path::Path,
+    pack: &core::modpack::ParsedModpack,
+    minecraft_version: &str,
+) -> Result<(), String> {
+    let instance_id = instance.id.clone();
+    let game_dir = instance.game_dir.clone();
+    let prefixes = pack.override_prefixes.clone();
+    let source = zip_path.to_path_buf();
+    tokio::task::spawn_blocking(move || {
+        check_pack_overrides(&source)?;
+        core::modpack::extract_overrides(&source, &game_dir, &prefixes, |_, _, _| {})
+    })
+    .await
+    .map_err(|e| e.to_string())??;
+
+    // A few unavailable mods shouldn't lose the whole import
+    if !pack.files.is_empty() {
+        let tasks = pack
+            .files
+            .iter()
+            .filter_map(|file| {
+                Some(core::downloader::DownloadTask {
+                    url: file.url.clone(),
+                    path: core::safe_path::join(&instance.game_dir, &file.path)?,
+                    sha1: file.sha1.clone(),
+                    sha256: None,
+                })
+            })
+            .collect();
+        let concurrency = {
+            let config = config_state.config.lock().unwrap();
+            core::downloader::concurrency_for_instance(&config, Some(instance))
+        };
+        let audit = core::download_audit::DownloadAudit::open_or_warn(
+            window.app_handle(),
+            &instance_id,
+            "install",
+        );
+        if let Err(e) =
+            core::downloader::download_files(window.clone(), tasks, concurrency, audit.as_ref())
+                .await
+        {
+            emit_log!(window, format!("Warning: some pack files failed: {}", e));
+        }
+    }
+
+    install_pack_version(
+        window,
+        config_state,
+        instance_state,
+        &instance_id,
+        pack,
+        minecraft_version,
+    )
+    .await?;
+
+    // Remember what the pack put into the instance so it can be updated
+    let origin = core::modpack::identify_origin(zip_path, &pack.info.modpack_type).await;
+    let source = zip_path.to_path_buf();
+    let prefixes = pack.override_prefixes.clone();
+    let overrides =
+        tokio::task::spawn_blocking(move || core::modpack::override_hashes(&source, &prefixes))
+            .await
+            .map_err(|e| e.to_string())??;
+    let mut instance = instance_state
+        .get_instance(&instance_id)
+        .ok_or_else(|| format!("Instance {} not found", instance_id))?;
+    instance.pack = Some(core::modpack::InstalledPack {
+        name: pack.info.name.clone(),
+        modpack_type: pack.info.modpack_type.clone(),
+        origin,
+        files: pack.files.clone(),
+        overrides,
+    });
+    instance_state.update_instance(instance)
+}
+
+/// Install the Minecraft version and mod loader a pack asks for
+async fn install_pack_version(
+    window: &Window,
+    config_state: &State<'_, core::config::ConfigState>,
+    instance_state: &State<'_, core::instance::InstanceState>,
+    instance_id: &str,
+    pack: &core::modpack::ParsedModpack,
+    minecraft_version: &str,
+) -> Result<(), String> {
+    let instance_id = instance_id.to_string();
+    install_version(
+        window.clone(),
+        config_state.clone(),
+        instance_state.clone(),
+        instance_id.clone(),
+        minecraft_version.to_string(),
+    )
+    .await?;
+    let loader_version = pack.info.mod_loader_version.clone();
+    match (pack.info.mod_loader.as_deref(), loader_version) {
+        (Some("fabric"), Some(version)) => {
+            install_fabric(
+                window.clone(),
+                config_state.clone(),
+                instance_state.clone(),
+                instance_id.clone(),
+                minecraft_version.to_string(),
+                version,
+            )
+            .await?;
+        }
+        (Some("forge"), Some(version)) => {
+            install_forge(
+                window.clone(),
+                config_state.clone(),
+                instance_state.clone(),
+                instance_id.clone(),
+                minecraft_version.to_string(),
+                version,
+            )
+            .await?;
+        }
+        (Some("neoforge"), Some(version)) => {
+            install_neoforge(
+                window.clone(),
+                config_state.clone(),
+                instance_state.clone(),
+                instance_id.clone(),
+                minecraft_version.to_string(),
+                version,
+            )
+            .await?;
+        }
+        (loader, _) => {
+            if let Some(loader) = loader {
+                emit_log!(
+                    window,
+                    format!(
+                        "Warning: {} can't be installed automatically, the instance uses vanilla Minecraft",
+                        loader
+                    )
+                );
+            }
+            let mut instance = instance_state
+                .get_instance(&instance_id)
+                .ok_or_else(|| format!("Instance {} not found", instance_id))?;
+            instance.version_id = Some(minecraft_version.to_string());
+            instance.mod_loader = None;
+            instance.mod_loader_version = None;
+            instance_state.update_instance(instance)?;
+        }
+    }
+    Ok(())
+}
+
+/// Create a ready-to-launch instance from a modpack or exported instance zip
+/// (MultiMC / Prism Launcher, Modrinth or CurseForge): extracts its files,
+/// downloads the mods it lists and installs its Minecraft version and loader
 #[tauri::command]
 #[dropout_macros::api]
-async fn open_file_explorer(path: String) -> Result<(), String> {
-    #[cfg(target_os = "windows")]
-    {
-        std::process::Command::new("explorer")
-            .arg(&path)
-            .spawn()
-            .map_err(|e| e.to_string())?;
+async fn import_instance_from_zip(
+    window: Window,
+    config_state: State<'_, core::config::ConfigState>,
+    instance_state: State<'_, core::instance::InstanceState>,
+    path: String,
+    name: Option<String>,
+) -> Result<core::instance::Instance, String> {
+    core::parental::require_unlocked(window.app_handle(), "add instances")?;
+    import_zip_as_instance(
+        &window,
+        &config_state,
+        &instance_state,
+        std::path::Path::new(&path),
+        name,
+    )
+    .await
+}
+
+/// Create an instance from the modpack or instance zip at `zip_path`
+async fn import_zip_as_instance(
+    window: &Window,
+    config_state: &State<'_, core::config::ConfigState>,
+    instance_state: &State<'_, core::instance::InstanceState>,
+    zip_path: &std::path::Path,
+    name: Option<String>,
+) -> Result<core::instance::Instance, String> {
+    let pack = core::modpack::import(zip_path).await?;
+    let minecraft_version = pack
+        .info
+        .minecraft_version
+        .clone()
+        .ok_or("The archive does not say which Minecraft version it uses")?;
+    let name = name
+        .filter(|n| !n.trim().is_empty())
+        .unwrap_or_else(|| pack.info.name.clone());
+    emit_log!(
+        window,
+        format!(
+            "Importing {} ({}, Minecraft {})...",
+            name, pack.info.modpack_type, minecraft_version
+        )
+    );
+
+    let instance = instance_state.create_instance(name)?;
+    let instance_id = instance.id.clone();
+    let result = install_pack(
+        window,
+        config_state,
+        instance_state,
+        &instance,
+        zip_path,
+        &pack,
+        &minecraft_version,
+    )
+    .await;
+
+    // Don't leave a half-imported instance behind
+    if let Err(e) = result {
+        let _ = instance_state.delete_instance(&instance_id, None);
+        return Err(e);
     }
-    #[cfg(target_os = "macos")]
-    {
-        std::process::Command::new("open")
-            .arg(&path)
-            .spawn()
-            .map_err(|e| e.to_string())?;
+    emit_log!(window, format!("Imported instance {}", instance.name));
+    instance_state
+        .get_instance(&instance_id)
+        .ok_or_else(|| format!("Instance {} not found", instance_id))
+}
+
+/// Install a modpack (Modrinth, CurseForge or MultiMC zip) into an existing
+/// instance: downloads the pack's files, extracts its overrides and switches
+/// the instance to the pack's Minecraft version and loader
+#[tauri::command]
+#[dropout_macros::api]
+async fn install_modpack(
+    window: Window,
+    config_state: State<'_, core::config::ConfigState>,
+    instance_state: State<'_, core::instance::InstanceState>,
+    instance_id: String,
+    path: String,
+) -> Result<core::instance::Instance, String> {
+    core::parental::require_unlocked(window.app_handle(), "add mods")?;
+    let instance = instance_state
+        .get_instance(&instance_id)
+        .ok_or_else(|| format!("Instance {} not found", instance_id))?;
+    let zip_path = std::path::PathBuf::from(&path);
+    let pack = core::modpack::import(&zip_path).await?;
+    let minecraft_version = pack
+        .info
+        .minecraft_version
+        .clone()
+        .ok_or("The modpack does not say which Minecraft version it uses")?;
+    emit_log!(
+        window,
+        format!(
+            "Installing {} ({}, Minecraft {}, {} files) into {}...",
+            pack.info.name,
+            pack.info.modpack_type,
+            minecraft_version,
+            pack.files.len(),
+            instance.name
+        )
+    );
+    install_pack(
+        &window,
+        &config_state,
+        &instance_state,
+        &instance,
+        &zip_path,
+        &pack,
+        &minecraft_version,
+    )
+    .await?;
+    emit_log!(
+        window,
+        format!("Installed {} into {}", pack.info.name, instance.name)
+    );
+    instance_state
+        .get_instance(&instance_id)
+        .ok_or_else(|| format!("Instance {} not found", instance_id))
+}
+
+/// The newest published version of the pack installed in an instance, or
+/// `None` when it is up to date
+#[tauri::command]
+#[dropout_macros::api]
+async fn check_modpack_update(
+    instance_state: State<'_, core::instance::InstanceState>,
+    instance_id: String,
+) -> Result<Option<core::modpack::PackRelease>, String> {
+    let instance = instance_state
+        .get_instance(&instance_id)
+        .ok_or_else(|| format!("Instance {} not found", instance_id))?;
+    let origin = instance
+        .pack
+        .and_then(|pack| pack.origin)
+        .ok_or("The instance's modpack isn't published on Modrinth or CurseForge")?;
+    core::modpack::latest_release(&origin).await
+}
+
+/// Update the pack installed in an instance to its newest version, or to the
+/// archive at `path`. Only files that differ from the installed version are
+/// downloaded or removed, and config files the user edited are kept.
+#[tauri::command]
+#[dropout_macros::api]
+async fn update_modpack(
+    window: Window,
+    config_state: State<'_, core::config::ConfigState>,
+    instance_state: State<'_, core::instance::InstanceState>,
+    instance_id: String,
+    path: Option<String>,
+) -> Result<core::modpack::PackUpdateSummary, String> {
+    core::parental::require_unlocked(window.app_handle(), "add mods")?;
+    let instance = instance_state
+        .get_instance(&instance_id)
+        .ok_or_else(|| format!("Instance {} not found", instance_id))?;
+    let installed = instance
+        .pack
+        .clone()
+        .ok_or("The instance wasn't installed from a modpack")?;
+    let concurrency = {
+        let config = config_state.config.lock().unwrap();
+        core::downloader::concurrency_for_instance(&config, Some(&instance))
+    };
+
+    let (zip_path, release) = match path.filter(|p| !p.trim().is_empty()) {
+        Some(path) => (std::path::PathBuf::from(path), None),
+        None => {
+            let origin = installed.origin.as_ref().ok_or(
+                "The modpack isn't published on Modrinth or CurseForge, choose the new version's file",
+            )?;
+            let release = core::modpack::latest_release(origin)
+                .await?
+                .ok_or("The modpack is already up to date")?;
+            emit_log!(
+                window,
+                format!("Downloading {} {}...", installed.name, release.version_name)
+            );
+            let dest_dir = window
+                .app_handle()
+                .path()
+                .app_data_dir()
+                .map_err(|e| e.to_string())?
+                .join("cache")
+                .join("modpacks");
+            std::fs::create_dir_all(&dest_dir).map_err(|e| e.to_string())?;
+            let source = core::web_seed::PackSource::Http {
+                url: release.url.clone(),
+                sha1: release.sha1.clone(),
+            };
+            let audit = core::download_audit::DownloadAudit::open_or_warn(
+                window.app_handle(),
+                &instance.id,
+                "update",
+            );
+            let path = core::web_seed::download_pack(
+                &window,
+                &source,
+                &dest_dir,
+                concurrency,
+                audit.as_ref(),
+            )
+            .await?;
+            (path, Some(release))
+        }
+    };
+
+    let pack = core::modpack::import(&zip_path).await?;
+    let minecraft_version = pack
+        .info
+        .minecraft_version
+        .clone()
+        .ok_or("The modpack does not say which Minecraft version it uses")?;
+    emit_log!(
+        window,
+        format!("Updating {} in {}...", pack.info.name, instance.name)
+    );
+
+    // Apply only the delta to the installed version. Jars it replaces or
+    // removes are saved first so the update can be rolled back.
+    let game_dir = instance.game_dir.clone();
+    let old_files = installed.files.clone();
+    let new_files = pack.files.clone();
+    let prefixes = pack.override_prefixes.clone();
+    let source = zip_path.clone();
+    let (diff, tasks) = tokio::task::spawn_blocking(move || {
+        let (diff, tasks) = core::modpack::plan_file_sync(&game_dir, &old_files, &new_files);
+        // Override jars may be written over existing ones too
+        let override_paths = core::modpack::override_hashes(&source, &prefixes)?;
+        let override_mods =
+            core::modpack::mod_file_names(override_paths.keys().map(String::as_str));
+        let replaced: Vec<&str> = diff
+            .replaced_mods()
+            .into_iter()
+            .chain(override_mods.iter().copied())
+            .collect();
+        let written: Vec<&str> = diff
+            .written_mods()
+            .into_iter()
+            .chain(override_mods.iter().copied())
+            .collect();
+        core::mod_rollback::create_point(&game_dir, "modpack update", &replaced, &written)?;
+        Ok::<_, String>((diff, tasks))
+    })
+    .await
+    .map_err(|e| e.to_string())??;
+    if !tasks.is_empty() {
+        let audit = core::download_audit::DownloadAudit::open_or_warn(
+            window.app_handle(),
+            &instance.id,
+            "update",
+        );
+        core::downloader::download_files(window.clone(), tasks, concurrency, audit.as_ref())
+            .await
+            .map_err(|e| {
+                format!(
+                    "Failed to download the new pack files, {} was not updated: {}",
+                    pack.info.name, e
+                )
+            })?;
     }
-    #[cfg(target_os = "linux")]
-    {
-        std::process::Command::new("xdg-open")
-            .arg(&path)
-            .spawn()
-            .map_err(|e| e.to_string())?;
+
+    // Only drop old files once the new ones are in place
+    let game_dir = instance.game_dir.clone();
+    let old_files = installed.files.clone();
+    let old_overrides = installed.overrides.clone();
+    let prefixes = pack.override_prefixes.clone();
+    let source = zip_path.clone();
+    let (summary, overrides) = tokio::task::spawn_blocking(move || {
+        check_pack_overrides(&source)?;
+        core::modpack::remove_dropped_files(&game_dir, &diff.removed)?;
+        let (overrides, kept) =
+            core::modpack::update_overrides(&source, &game_dir, &prefixes, &old_overrides)?;
+        let summary = core::modpack::update_summary(&old_files, &diff, kept);
+        Ok::<_, String>((summary, overrides))
+    })
+    .await
+    .map_err(|e| e.to_string())??;
+
+    let version_changed = instance.version_id.as_deref() != Some(minecraft_version.as_str())
+        || instance.mod_loader != pack.info.mod_loader
+        || instance.mod_loader_version != pack.info.mod_loader_version;
+    if version_changed {
+        install_pack_version(
+            &window,
+            &config_state,
+            &instance_state,
+            &instance_id,
+            &pack,
+            &minecraft_version,
+        )
+        .await?;
     }
-    Ok(())
+
+    let origin = match (release, installed.origin) {
+        (Some(release), Some(origin)) => Some(core::modpack::PackOrigin {
+            version_id: release.version_id,
+            version_name: release.version_name,
+            ..origin
+        }),
+        _ => core::modpack::identify_origin(&zip_path, &pack.info.modpack_type).await,
+    };
+    let mut instance = instance_state
+        .get_instance(&instance_id)
+        .ok_or_else(|| format!("Instance {} not found", instance_id))?;
+    instance.pack = Some(core::modpack::InstalledPack {
+        name: pack.info.name.clone(),
+        modpack_type: pack.info.modpack_type.clone(),
+        origin,
+        files: pack.files.clone(),
+        overrides,
+    });
+    instance_state.update_instance(instance)?;
+    emit_log!(
+        window,
+        format!(
+            "Updated {}: {} added, {} changed, {} removed, {} edited file(s) kept",
+            pack.info.name,
+            summary.added.len(),
+            summary.changed.len(),
+            summary.removed.len(),
+            summary.kept.len()
+        )
+    );
+    Ok(summary)
+}
+
+/// Libraries, their origin and the substitutions of the instance's last launch
+#[tauri::command]
+#[dropout_macros::api]
+async fn get_last_launch_report(
+    window: Window,
+    instance_id: String,
+) -> Result<Option<core::launch_report::LaunchReport>, String> {
+    let dir = window
+        .app_handle()
+        .path()
+        .app_data_dir()
+        .map_err(|e| e.to_string())?
+        .join("launch_reports");
+    core::launch_report::load(&dir, &instance_id)
+}
+
+/// Summary of the locally recorded usage statistics (see `usage_stats_enabled`)
+#[tauri::command]
+#[dropout_macros::api]
+async fn get_usage_stats(
+    app_handle: tauri::AppHandle,
+) -> Result<core::usage_stats::UsageStats, String> {
+    core::usage_stats::load_stats(&app_handle)
+}
+
+/// Fuzzy search across instances, versions, mods, worlds, screenshots and settings
+#[tauri::command]
+#[dropout_macros::api]
+async fn search_everything(
+    app_handle: tauri::AppHandle,
+    config_state: State<'_, core::config::ConfigState>,
+    instance_state: State<'_, core::instance::InstanceState>,
+    query: String,
+    limit: Option<usize>,
+) -> Result<Vec<core::search::SearchResult>, String> {
+    let config = config_state.config.lock().unwrap().clone();
+    let shared_versions_dir = if config.use_shared_caches {
+        Some(
+            app_handle
+                .path()
+                .app_data_dir()
+                .map_err(|e| e.to_string())?
+                .join("versions"),
+        )
+    } else {
+        None
+    };
+    let settings =
+        core::search::setting_keys(&serde_json::to_value(&config).map_err(|e| e.to_string())?);
+    let instances = instance_state.list_instances();
+    let limit = limit.unwrap_or(core::search::DEFAULT_LIMIT);
+
+    tokio::task::spawn_blocking(move || {
+        core::search::search(
+            &query,
+            &instances,
+            shared_versions_dir.as_deref(),
+            &settings,
+            limit,
+        )
+    })
+    .await
+    .map_err(|e| e.to_string())
 }
 
 fn main() {
-    tauri::Builder::default()
+    let context = tauri::generate_context!();
+
+    // A `dropout://` link opened while the launcher is running: hand it over
+    // to that process instead of starting a second launcher
+    let startup_link = core::deep_link::url_from_args(std::env::args());
+    if let Some(url) = &startup_link
+        && let Some(app_data_dir) = core::deep_link::app_data_dir(&context.config().identifier)
+        && core::deep_link::forward_to_running(&app_data_dir, url)
+    {
+        return;
+    }
+
+    let app = tauri::Builder::default()
         .plugin(tauri_plugin_fs::init())
         .plugin(tauri_plugin_dialog::init())
         .plugin(tauri_plugin_shell::init())
         .manage(core::auth::AccountState::new())
         .manage(MsRefreshTokenState::new())
         .manage(core::assistant::AssistantState::new())
-        .setup(|app| {
+        .manage(core::lan_sync::LanSyncState::new())
+        .manage(core::local_api::LocalApiState::new())
+        .manage(core::deep_link::DeepLinkState::new(std::sync::Arc::new(
+            |app, link| Box::pin(run_deep_link(app, link)),
+        )))
+        .manage(core::download_manager::DownloadManager::default())
+        .manage(core::recovery::RecoveryState::default())
+        .manage(core::parental::ParentalState::default())
+        .setup(move |app| {
+            // Receive `dropout://` links from later launcher processes
+            let link_handle = app.handle().clone();
+            tauri::async_runtime::spawn(async move {
+                if let Err(e) = core::deep_link::listen(link_handle).await {
+                    eprintln!("[DeepLink] Failed to listen for links: {}", e);
+                }
+            });
+            if let Some(url) = &startup_link {
+                core::deep_link::handle_url(app.handle(), url);
+            }
+
+            // Note whether the last run exited normally, then mark this one
+            let shutdown_state = core::shutdown::ShutdownState::new(app.handle());
+            if !shutdown_state.previous_clean {
+                println!("[Startup] The launcher did not shut down cleanly last time");
+            }
+            app.manage(shutdown_state);
+
             let config_state = core::config::ConfigState::new(app.handle());
             app.manage(config_state);
 
             // Initialize instance state
-            let instance_state = core::instance::InstanceState::new(app.handle());
+            let instance_state =
+                core::instance::InstanceState::new(&app.path().app_data_dir().unwrap());
 
             // Migrate legacy data if needed
-            if let Err(e) = core::instance::migrate_legacy_data(app.handle(), &instance_state) {
+            if let Err(e) = core::instance::migrate_legacy_data(&instance_state) {
                 eprintln!("[Startup] Warning: Failed to migrate legacy data: {}", e);
             }
 
@@ -2616,7 +6286,7 @@ fn main() {
 
             // Load saved account on startup
             let app_dir = app.path().app_data_dir().unwrap();
-            let storage = core::account_storage::AccountStorage::new(app_dir);
+            let storage = core::account_storage::AccountStorage::new(app_dir.clone());
 
             if let Some((stored_account, ms_refresh)) = storage.get_active_account() {
                 let account = stored_account.to_account();
@@ -2632,6 +6302,40 @@ fn main() {
                 println!("[Startup] Loaded saved account");
             }
 
+            // Remove natives left behind by launches that never cleaned up
+            let swept = core::natives::sweep_stale(&app_dir.join("natives"));
+            if swept > 0 {
+                println!("[Startup] Removed {} stale natives director(ies)", swept);
+            }
+
+            // Look for what an interrupted session left behind
+            let previous_clean = app.state::<core::shutdown::ShutdownState>().previous_clean;
+            if !previous_clean {
+                let recovery_handle = app.handle().clone();
+                tauri::async_runtime::spawn(async move {
+                    let scan_handle = recovery_handle.clone();
+                    let Ok(report) = tokio::task::spawn_blocking(move || {
+                        core::recovery::scan(&scan_handle, swept)
+                    })
+                    .await
+                    else {
+                        return;
+                    };
+                    if report.is_empty() {
+                        return;
+                    }
+                    println!(
+                        "[Startup] Recovery: {} orphaned file(s), {} interrupted install(s)",
+                        report.orphaned_files.len(),
+                        report.interrupted_installs.len()
+                    );
+                    let recovery_state: State<core::recovery::RecoveryState> =
+                        recovery_handle.state();
+                    *recovery_state.report.lock().unwrap() = Some(report.clone());
+                    let _ = recovery_handle.emit("recovery-report", report);
+                });
+            }
+
             // Check for pending Java downloads and notify frontend
             let pending = core::java::get_pending_downloads(app.app_handle());
             if !pending.is_empty() {
@@ -2639,19 +6343,128 @@ fn main() {
                 let _ = app.emit("pending-java-downloads", pending.len());
             }
 
+            // Periodically refresh the GitHub release cache in the background
+            let release_handle = app.handle().clone();
+            tauri::async_runtime::spawn(async move {
+                loop {
+                    let (token, interval_hours) = {
+                        let config_state: State<core::config::ConfigState> = release_handle.state();
+                        let config = config_state.config.lock().unwrap();
+                        (
+                            config.github_token.clone(),
+                            config.release_check_interval_hours,
+                        )
+                    };
+                    let interval_secs = (interval_hours as u64).max(1) * 60 * 60;
+
+                    match core::releases::get_releases(
+                        &release_handle,
+                        token.as_deref(),
+                        interval_secs,
+                        false,
+                    )
+                    .await
+                    {
+                        Ok(response) if !response.from_cache => {
+                            let _ = release_handle.emit("github-releases-updated", &response);
+                        }
+                        Ok(_) => {}
+                        Err(e) => eprintln!("[Releases] Background refresh failed: {}", e),
+                    }
+
+                    tokio::time::sleep(std::time::Duration::from_secs(interval_secs)).await;
+                }
+            });
+
+            // Run scheduled launches and backups
+            let scheduler_handle = app.handle().clone();
+            tauri::async_runtime::spawn(async move {
+                loop {
+                    tokio::time::sleep(core::scheduler::TICK).await;
+                    let config_state: State<core::config::ConfigState> = scheduler_handle.state();
+                    let due = {
+                        let mut config = config_state.config.lock().unwrap();
+                        core::scheduler::take_due(
+                            &mut config.schedules,
+                            chrono::Local::now().naive_local(),
+                        )
+                    };
+                    if due.is_empty() {
+                        continue;
+                    }
+                    if let Err(e) = config_state.save() {
+                        eprintln!("[Scheduler] Failed to save schedules: {}", e);
+                    }
+                    for schedule in due {
+                        tauri::async_runtime::spawn(run_schedule(
+                            scheduler_handle.clone(),
+                            schedule,
+                        ));
+                    }
+                }
+            });
+
+            // Serve the localhost API if the user opted in
+            core::local_api::track_events(app.handle());
+            let local_api_enabled = {
+                let config_state: State<core::config::ConfigState> = app.state();
+                config_state.config.lock().unwrap().local_api_enabled
+            };
+            if local_api_enabled {
+                let api_handle = app.handle().clone();
+                tauri::async_runtime::spawn(async move {
+                    let config_state: State<core::config::ConfigState> = api_handle.state();
+                    if let Err(e) = start_local_api(api_handle.clone(), config_state).await {
+                        eprintln!("[LocalApi] Failed to start: {}", e);
+                    }
+                });
+            }
+
+            // Announce on the LAN if instance sync is enabled
+            let lan_sync_enabled = {
+                let config_state: State<core::config::ConfigState> = app.state();
+                config_state.config.lock().unwrap().lan_sync_enabled
+            };
+            if lan_sync_enabled {
+                let lan_handle = app.handle().clone();
+                tauri::async_runtime::spawn(async move {
+                    if let Err(e) = core::lan_sync::start(&lan_handle).await {
+                        eprintln!("[LanSync] Failed to start: {}", e);
+                    }
+                });
+            }
+
             Ok(())
         })
+        .on_window_event(|window, event| {
+            // Ask before interrupting downloads or running games
+            if let tauri::WindowEvent::CloseRequested { api, .. } = event
+                && let Some(work) = core::shutdown::blocking_work(window.app_handle())
+            {
+                api.prevent_close();
+                core::shutdown::prompt_exit(window, &work);
+            }
+        })
         .invoke_handler(tauri::generate_handler![
             start_game,
             get_versions,
             get_versions_of_instance,
+            pin_version,
+            unpin_version,
+            diff_versions,
             check_version_installed,
             install_version,
             list_installed_versions,
             get_version_java_version,
+            evaluate_rules_report,
             get_version_metadata,
             delete_version,
             login_offline,
+            set_offline_skin,
+            fetch_player_skin,
+            upload_skin,
+            set_cape,
+            rename_offline_account,
             get_active_account,
             logout,
             get_settings,
@@ -2661,6 +6474,7 @@ fn main() {
             save_raw_config,
             start_microsoft_login,
             complete_microsoft_login,
+            login_yggdrasil,
             refresh_account,
             // Java commands
             detect_java,
@@ -2673,6 +6487,11 @@ fn main() {
             cancel_java_download,
             get_pending_java_downloads,
             resume_java_downloads,
+            // Download sessions
+            get_active_downloads,
+            pause_download,
+            resume_download,
+            cancel_download,
             // Fabric commands
             get_fabric_game_versions,
             get_fabric_loader_versions,
@@ -2684,16 +6503,40 @@ fn main() {
             get_forge_game_versions,
             get_forge_versions_for_game,
             install_forge,
+            get_neoforge_game_versions,
+            get_neoforge_versions_for_game,
+            install_neoforge,
+            get_optifine_versions_for_game,
+            install_optifine,
+            download_modpack_archive,
             get_github_releases,
+            get_service_status,
+            get_locale_info,
+            start_lan_sync,
+            stop_lan_sync,
+            get_lan_sync_info,
+            list_lan_peers,
+            sync_instance_to_peer,
+            backup_instance_to_cloud,
+            list_cloud_backups,
+            restore_cloud_backup,
+            delete_cloud_backup,
+            export_profile,
+            import_profile,
             upload_to_pastebin,
             assistant_check_health,
+            assistant_check_health_detailed,
             assistant_chat,
             assistant_chat_stream,
             list_ollama_models,
+            pull_ollama_model,
             list_openai_models,
+            search_assistant_knowledge,
             // Instance management commands
             create_instance,
             delete_instance,
+            get_instance_delete_preview,
+            check_instance_integrity,
             update_instance,
             list_instances,
             get_instance,
@@ -2702,9 +6545,80 @@ fn main() {
             duplicate_instance,
             migrate_shared_caches,
             list_instance_directory,
+            scan_instance_mods,
+            get_download_audit,
             delete_instance_file,
-            open_file_explorer
+            open_file_explorer,
+            open_path_in_explorer,
+            install_java_agent,
+            get_usage_stats,
+            get_launch_history,
+            search_everything,
+            init_server_files,
+            set_server_property,
+            get_server_players,
+            add_server_player,
+            remove_server_player,
+            send_rcon_command,
+            add_schedule,
+            list_schedules,
+            remove_schedule,
+            start_local_api,
+            stop_local_api,
+            get_local_api_info,
+            take_pending_deep_links,
+            register_url_scheme,
+            import_mod_list,
+            rollback_last_update,
+            export_mod_list,
+            get_instance_notes,
+            save_instance_notes,
+            add_note_attachment,
+            remove_note_attachment,
+            export_diagnostics,
+            get_gpu_info,
+            import_dot_minecraft,
+            import_instance_from_zip,
+            get_last_launch_report,
+            get_active_work,
+            confirm_exit,
+            was_last_shutdown_clean,
+            export_instance,
+            install_modpack,
+            get_recovery_report,
+            apply_recovery,
+            get_parental_status,
+            unlock_launcher,
+            lock_launcher,
+            set_launcher_pin,
+            check_modpack_update,
+            update_modpack,
+            reset_launcher,
+            search_curseforge,
+            get_curseforge_files,
+            install_curseforge_mod,
+            list_installed_mods,
+            get_mod_icon,
+            toggle_mod,
+            get_shader_loader,
+            search_modrinth,
+            get_modrinth_versions,
+            install_modrinth_project
         ])
-        .run(tauri::generate_context!())
+        .build(context)
         .expect("error while running tauri application");
+
+    app.run(|_app_handle, _event| {
+        if let tauri::RunEvent::Exit = &_event {
+            core::shutdown::finish(_app_handle);
+        }
+
+        // macOS delivers `dropout://` links to the running app
+        #[cfg(target_os = "macos")]
+        if let tauri::RunEvent::Opened { urls } = &_event {
+            for url in urls {
+                core::deep_link::handle_url(_app_handle, url.as_str());
+            }
+        }
+    });
 }
@@ -1,7 +1,6 @@
 #[cfg(test)]
 pub mod api;
 pub mod path;
-pub mod zip;
 
 // File system related utility functions
 #[allow(dead_code)]